@@ -0,0 +1,92 @@
+// Criterion-style benchmarks for the individual simulation items.
+//
+// Each benchmark builds a `GroupOpsSimulation` holding a single item and times `simulate()`
+// repeatedly, with the harness in `harness`.
+//
+// Run via `cargo bench`, optionally with a substring filter: `cargo bench -- multi_pairings`.
+
+mod harness;
+
+use blstrs_pvss_simulation::group_ops_simulation::GroupOpsSimulation;
+use harness::BenchmarkGroup;
+use rand::thread_rng;
+use std::hint::black_box;
+
+fn main() {
+    // cargo passes `--bench`; anything else is treated as a benchmark id filter
+    let filter_arg = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let filter = filter_arg.as_deref();
+    let mut rng = thread_rng();
+
+    let group = BenchmarkGroup::new("g1_exps", 100, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.g1_exps(1);
+    group.bench_function(1, || black_box(&sim).simulate());
+
+    let group = BenchmarkGroup::new("g2_exps", 100, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.g2_exps(1);
+    group.bench_function(1, || black_box(&sim).simulate());
+
+    let group = BenchmarkGroup::new("gt_exps", 100, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.gt_exps(1);
+    group.bench_function(1, || black_box(&sim).simulate());
+
+    let group = BenchmarkGroup::new("g1_multi_exps", 50, filter);
+    for size in [2, 16, 256, 1024] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.g1_multi_exps(1, size);
+        group.bench_function(size, || black_box(&sim).simulate());
+    }
+
+    let group = BenchmarkGroup::new("g2_multi_exps", 50, filter);
+    for size in [2, 16, 256, 1024] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.g2_multi_exps(1, size);
+        group.bench_function(size, || black_box(&sim).simulate());
+    }
+
+    let group = BenchmarkGroup::new("gt_multi_exps", 20, filter);
+    for size in [2, 16] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.gt_multi_exps(1, size);
+        group.bench_function(size, || black_box(&sim).simulate());
+    }
+
+    let group = BenchmarkGroup::new("pairings", 50, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.pairings(1);
+    group.bench_function(1, || black_box(&sim).simulate());
+
+    let group = BenchmarkGroup::new("multi_pairings", 50, filter);
+    for size in [2, 3, 16] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.multi_pairings(1, size);
+        group.bench_function(size, || black_box(&sim).simulate());
+    }
+
+    let group = BenchmarkGroup::new("miller_loops", 50, filter);
+    for size in [1, 3, 16] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.miller_loops(1, size);
+        group.bench_function(size, || black_box(&sim).simulate());
+    }
+    let group = BenchmarkGroup::new("final_exponentiations", 50, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.final_exponentiations(1);
+    group.bench_function(1, || black_box(&sim).simulate());
+
+    let group = BenchmarkGroup::new("scalar_ops", 100, filter);
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.scalar_muls(1);
+    group.bench_function("mul", || black_box(&sim).simulate());
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.scalar_inversions(1);
+    group.bench_function("inversion", || black_box(&sim).simulate());
+    for size in [16, 1024] {
+        let mut sim = GroupOpsSimulation::new(&mut rng);
+        sim.scalar_batch_inversions(1, size);
+        group.bench_function(format!("batch_inversion/{}", size), || black_box(&sim).simulate());
+    }
+}
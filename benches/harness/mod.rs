@@ -0,0 +1,122 @@
+// The criterion-style harness the benchmarks share: each benchmark warms up, then takes a fixed
+// number of samples, summarized as a bootstrapped confidence interval for the mean time per
+// iteration plus Tukey-fence outlier classification.
+
+use rand::{thread_rng, Rng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const WARM_UP_TIME: Duration = Duration::from_secs(1);
+const MEASUREMENT_TIME: Duration = Duration::from_secs(3);
+const BOOTSTRAP_RESAMPLES: usize = 1000;
+const CONFIDENCE_LEVEL: f64 = 0.95;
+
+pub struct BenchmarkGroup<'a> {
+    name: &'a str,
+    sample_size: usize,
+    filter: Option<&'a str>,
+}
+
+impl<'a> BenchmarkGroup<'a> {
+    pub fn new(name: &'a str, sample_size: usize, filter: Option<&'a str>) -> Self {
+        Self { name, sample_size, filter }
+    }
+
+    // each sample runs `f` a fixed number of times, chosen during warm-up so that all samples
+    // together take roughly MEASUREMENT_TIME
+    pub fn bench_function<O>(&self, id: impl std::fmt::Display, mut f: impl FnMut() -> O) {
+        let full_id = format!("{}/{}", self.name, id);
+        if let Some(filter) = self.filter {
+            if !full_id.contains(filter) {
+                return;
+            }
+        }
+
+        let warm_up_start = Instant::now();
+        let mut warm_up_iters = 0u64;
+        while warm_up_start.elapsed() < WARM_UP_TIME {
+            black_box(f());
+            warm_up_iters += 1;
+        }
+        let estimated_ns = warm_up_start.elapsed().as_nanos() as f64 / warm_up_iters as f64;
+        let target_ns = MEASUREMENT_TIME.as_nanos() as f64 / self.sample_size as f64;
+        let iters_per_sample = ((target_ns / estimated_ns) as u64).max(1);
+
+        let samples : Vec<f64> = (0..self.sample_size)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iters_per_sample {
+                    black_box(f());
+                }
+                start.elapsed().as_nanos() as f64 / iters_per_sample as f64
+            })
+            .collect();
+
+        let (lower, estimate, upper) = bootstrap_mean_ci(&samples);
+        println!("{:<31} time:   [{} {} {}]", full_id, format_ns(lower), format_ns(estimate), format_ns(upper));
+        report_outliers(&samples);
+    }
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+fn bootstrap_mean_ci(samples: &[f64]) -> (f64, f64, f64) {
+    let mut rng = thread_rng();
+    let mut means : Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            let resample : Vec<f64> = (0..samples.len())
+                .map(|_| samples[rng.gen_range(0..samples.len())])
+                .collect();
+            mean(&resample)
+        })
+        .collect();
+    means.sort_by(f64::total_cmp);
+    let alpha = (1.0 - CONFIDENCE_LEVEL) / 2.0;
+    (percentile(&means, alpha), mean(samples), percentile(&means, 1.0 - alpha))
+}
+
+fn report_outliers(samples: &[f64]) {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (q1, q3) = (percentile(&sorted, 0.25), percentile(&sorted, 0.75));
+    let iqr = q3 - q1;
+    let (low_severe, low_mild) = (q1 - 3.0 * iqr, q1 - 1.5 * iqr);
+    let (high_mild, high_severe) = (q3 + 1.5 * iqr, q3 + 3.0 * iqr);
+
+    let count = |pred: &dyn Fn(f64) -> bool| samples.iter().filter(|&&x| pred(x)).count();
+    let classes = [
+        ("low severe", count(&|x| x < low_severe)),
+        ("low mild", count(&|x| x >= low_severe && x < low_mild)),
+        ("high mild", count(&|x| x > high_mild && x <= high_severe)),
+        ("high severe", count(&|x| x > high_severe)),
+    ];
+    let total : usize = classes.iter().map(|(_, c)| c).sum();
+    if total == 0 {
+        return;
+    }
+    let pct = |c: usize| 100.0 * c as f64 / samples.len() as f64;
+    println!("Found {} outliers among {} measurements ({:.2}%)", total, samples.len(), pct(total));
+    for (name, c) in classes.iter().filter(|(_, c)| *c > 0) {
+        println!("  {} ({:.2}%) {}", c, pct(*c), name);
+    }
+}
+
+fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{:.4} ns", ns)
+    } else if ns < 1e6 {
+        format!("{:.4} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.4} ms", ns / 1e6)
+    } else {
+        format!("{:.4} s", ns / 1e9)
+    }
+}
@@ -0,0 +1,40 @@
+// One benchmark per protocol preset and standard parameter set (`presets::STANDARD`), so every
+// preset is covered without a benchmark written for it. Each times `simulate()` of the preset's
+// whole workload, built with the fast setup.
+//
+// Run via `cargo bench --bench presets`, optionally with a substring filter of the benchmark
+// IDs, which are the preset and its parameters: `cargo bench --bench presets -- groth/n=64`.
+
+mod harness;
+
+use blstrs_pvss_simulation::group_ops_simulation::{GroupOpsSimulation, Setup};
+use blstrs_pvss_simulation::presets;
+use harness::BenchmarkGroup;
+use rand::thread_rng;
+use std::hint::black_box;
+
+// whole workloads take milliseconds to seconds per run
+const SAMPLE_SIZE: usize = 10;
+
+fn main() {
+    // cargo passes `--bench`; anything else is treated as a benchmark id filter
+    let filter_arg = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let filter = filter_arg.as_deref();
+    let mut rng = thread_rng();
+
+    for &(name, _) in presets::STANDARD {
+        let protocol = presets::by_name(name).unwrap_or_else(|| panic!("`{}` is not a preset", name));
+        let group = BenchmarkGroup::new(name, SAMPLE_SIZE, filter);
+        for params in presets::standard_params(name) {
+            let id : Vec<String> = params.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            let id = id.join(",");
+            if filter.is_some_and(|filter| !format!("{}/{}", name, id).contains(filter)) {
+                continue;
+            }
+            let workload = protocol.evaluate(&params).unwrap_or_else(|e| panic!("{} {}: {}", name, id, e));
+            let mut sim = GroupOpsSimulation::new(&mut rng);
+            sim.setup(Setup::Fast).workload(&workload);
+            group.bench_function(id, || black_box(&sim).simulate());
+        }
+    }
+}
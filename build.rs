@@ -0,0 +1,26 @@
+// Exposes the locked blstrs and blst versions as BLSTRS_VERSION and BLST_VERSION, for machine
+// profiles (see `machine`), and with feature `gpu` generates the OpenCL multi-exp kernels of
+// `backend::Gpu`.
+
+use std::fs;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    for (name, var) in [("blstrs", "BLSTRS_VERSION"), ("blst", "BLST_VERSION")] {
+        println!("cargo:rustc-env={}={}", var, locked_version(&lock, name).unwrap_or("unknown"));
+    }
+    #[cfg(feature = "gpu")]
+    {
+        use blstrs::{Fp, Fp2, G1Affine, G2Affine};
+        let source = ec_gpu_gen::SourceBuilder::new().add_multiexp::<G1Affine, Fp>().add_multiexp::<G2Affine, Fp2>();
+        ec_gpu_gen::generate(&source);
+    }
+}
+
+// the `version` line following `name = "<name>"` in a `[[package]]` entry
+fn locked_version<'a>(lock: &'a str, name: &str) -> Option<&'a str> {
+    let mut lines = lock.lines();
+    lines.find(|line| line.trim() == format!("name = \"{}\"", name))?;
+    lines.next()?.trim().strip_prefix("version = \"")?.strip_suffix('"')
+}
@@ -0,0 +1,261 @@
+//! Sums of many points (the accumulation loops of verifiers, aggregating keys or signatures) in
+//! projective coordinates, one mixed addition per point, or in affine coordinates: pairwise in a
+//! tree, each level's additions sharing one field inversion (Montgomery's trick), so that an
+//! addition costs about six field multiplications instead of a mixed addition's eleven, plus
+//! one inversion per level. Which representation a verifier should keep its points in follows
+//! from the two times.
+
+use crate::curve_ops::BatchToAffine;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blst::{
+    blst_fp, blst_fp2, blst_fp2_inverse, blst_fp2_mul, blst_fp2_sqr, blst_fp2_sub, blst_fp_inverse, blst_fp_mul,
+    blst_fp_sqr, blst_fp_sub, blst_p1_affine, blst_p2_affine,
+};
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective};
+use group::prime::PrimeCurveAffine;
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Coordinates {
+    /// a projective accumulator, adding the affine points one by one
+    Projective,
+    /// affine additions in a tree, batch-inverting the denominators of each level
+    Affine,
+}
+
+impl Coordinates {
+    pub const ALL: [Coordinates; 2] = [Coordinates::Projective, Coordinates::Affine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Coordinates::Projective => "projective",
+            Coordinates::Affine => "affine",
+        }
+    }
+}
+
+/// The base fields of G1 and G2, as blst's `blst_fp` and `blst_fp2` (blstrs keeps its own
+/// field types private).
+pub trait BaseField : Copy + Default + PartialEq {
+    fn sub(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    fn square(&self) -> Self;
+    fn inverse(&self) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+macro_rules! impl_base_field {
+    ($field:ty, $sub:ident, $mul:ident, $sqr:ident, $inverse:ident) => {
+        impl BaseField for $field {
+            fn sub(&self, other: &Self) -> Self {
+                let mut out = Self::default();
+                // SAFETY: blst reads and writes one field element through each pointer
+                unsafe { $sub(&mut out, self, other) };
+                out
+            }
+
+            fn mul(&self, other: &Self) -> Self {
+                let mut out = Self::default();
+                // SAFETY: as above
+                unsafe { $mul(&mut out, self, other) };
+                out
+            }
+
+            fn square(&self) -> Self {
+                let mut out = Self::default();
+                // SAFETY: as above
+                unsafe { $sqr(&mut out, self) };
+                out
+            }
+
+            fn inverse(&self) -> Self {
+                let mut out = Self::default();
+                // SAFETY: as above
+                unsafe { $inverse(&mut out, self) };
+                out
+            }
+        }
+    };
+}
+
+impl_base_field!(blst_fp, blst_fp_sub, blst_fp_mul, blst_fp_sqr, blst_fp_inverse);
+impl_base_field!(blst_fp2, blst_fp2_sub, blst_fp2_mul, blst_fp2_sqr, blst_fp2_inverse);
+
+/// Affine points by their coordinates.
+pub trait AffinePoint : PrimeCurveAffine {
+    type Base : BaseField;
+    fn coordinates(&self) -> (Self::Base, Self::Base);
+    /// not checked to be on the curve
+    fn from_coordinates(x: Self::Base, y: Self::Base) -> Self;
+}
+
+macro_rules! impl_affine_point {
+    ($affine:ty, $blst_affine:ident, $field:ty) => {
+        impl AffinePoint for $affine {
+            type Base = $field;
+
+            fn coordinates(&self) -> ($field, $field) {
+                // SAFETY: blstrs' affine types are repr(transparent) wrappers of the blst types
+                let point = unsafe { &*(self as *const Self as *const $blst_affine) };
+                (point.x, point.y)
+            }
+
+            fn from_coordinates(x: $field, y: $field) -> Self {
+                // SAFETY: as above
+                unsafe { std::mem::transmute::<$blst_affine, Self>($blst_affine { x, y }) }
+            }
+        }
+    };
+}
+
+impl_affine_point!(G1Affine, blst_p1_affine, blst_fp);
+impl_affine_point!(G2Affine, blst_p2_affine, blst_fp2);
+
+// Montgomery's trick: one inversion for all of `values`, leaving zeros as they are
+fn batch_invert<F>(values: &mut [F])
+where F : BaseField {
+    // the product of the non-zero values before each one
+    let mut products = Vec::with_capacity(values.len());
+    let mut acc : Option<F> = None;
+    for value in values.iter() {
+        products.push(acc);
+        if !value.is_zero() {
+            acc = Some(acc.map_or(*value, |acc| acc.mul(value)));
+        }
+    }
+    let Some(acc) = acc else { return };
+    let mut inverse = acc.inverse();
+    for (value, product) in values.iter_mut().zip(products).rev() {
+        if value.is_zero() {
+            continue;
+        }
+        let value_inverse = product.map_or(inverse, |product| inverse.mul(&product));
+        inverse = inverse.mul(value);
+        *value = value_inverse;
+    }
+}
+
+/// `sum_i points[i]` with affine additions, a level of a tree at a time. Pairs that share an
+/// x-coordinate (equal or opposite points), whose affine sum would divide by zero, are added
+/// projectively instead.
+pub fn sum_affine<A>(points: &[A]) -> A::Curve
+where A : AffinePoint {
+    let mut level : Vec<A> = points.iter().filter(|p| !bool::from(p.is_identity())).copied().collect();
+    let mut rest = A::Curve::identity();
+    let mut denominators = Vec::new();
+    while level.len() > 1 {
+        let pairs = level.len() / 2;
+        denominators.clear();
+        denominators.extend((0..pairs).map(|i| level[2 * i + 1].coordinates().0.sub(&level[2 * i].coordinates().0)));
+        batch_invert(&mut denominators);
+        let mut next = Vec::with_capacity(pairs + 1);
+        for (i, inverse) in denominators.iter().enumerate() {
+            let (p, q) = (level[2 * i], level[2 * i + 1]);
+            if inverse.is_zero() {
+                rest += p.to_curve() + q;
+                continue;
+            }
+            let ((x1, y1), (x2, y2)) = (p.coordinates(), q.coordinates());
+            let lambda = y2.sub(&y1).mul(inverse);
+            let x3 = lambda.square().sub(&x1).sub(&x2);
+            let y3 = lambda.mul(&x1.sub(&x3)).sub(&y1);
+            next.push(A::from_coordinates(x3, y3));
+        }
+        if level.len() % 2 == 1 {
+            next.push(level[level.len() - 1]);
+        }
+        level = next;
+    }
+    match level.first() {
+        Some(last) => rest + last,
+        None => rest,
+    }
+}
+
+/// `sum_i points[i]` with a projective accumulator.
+pub fn sum_projective<A>(points: &[A]) -> A::Curve
+where A : PrimeCurveAffine {
+    points.iter().fold(A::Curve::identity(), |acc, p| acc + p)
+}
+
+struct Accumulations<A> {
+    coordinates: Coordinates,
+    num: usize,
+    points: Vec<A>,
+}
+
+impl<A> Accumulations<A>
+where A : AffinePoint {
+    fn sum(&self) -> A::Curve {
+        match self.coordinates {
+            Coordinates::Projective => sum_projective(&self.points),
+            Coordinates::Affine => sum_affine(&self.points),
+        }
+    }
+}
+
+impl<A> GroupOpsSimulationItem for Accumulations<A>
+where A : AffinePoint + Send + Sync,
+      A::Curve : NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} accumulations ({}): {} of size {}", A::Curve::NAME, self.coordinates.name(), self.num, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.points.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<A::Curve>>((0..self.num).map(|_| self.sum()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.sum()));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.sum());
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.sum() == sum_projective(&self.points))
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn add_accumulations<T>(&mut self, num: usize, size: usize) -> &mut Self
+    where T : BatchToAffine,
+          T::AffineRepr : AffinePoint<Curve = T> + Send + Sync + 'static,
+          T : NamedGroup + Send + Sync {
+        let points = T::batch_to_affine(&self.rng().points::<T>(size));
+        self.group(|sim| {
+            for coordinates in Coordinates::ALL {
+                sim.push_item(Accumulations { coordinates, num, points: points.clone() });
+            }
+        })
+    }
+
+    /// The same `num` sums of `size` affine G1 points in every `Coordinates`, as one item each.
+    pub fn g1_accumulations(&mut self, num: usize, size: usize) -> &mut Self {
+        self.add_accumulations::<G1Projective>(num, size)
+    }
+    /// Same as `g1_accumulations`, in G2.
+    pub fn g2_accumulations(&mut self, num: usize, size: usize) -> &mut Self {
+        self.add_accumulations::<G2Projective>(num, size)
+    }
+}
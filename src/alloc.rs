@@ -0,0 +1,96 @@
+//! Heap accounting for reports, with the `alloc-tracking` feature: a global allocator that
+//! counts the allocations and the bytes allocated and tracks current and peak heap usage. Without the feature,
+//! `stats` returns `None` and nothing is counted.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocStats {
+    /// bytes allocated since the start of the process
+    pub allocated: usize,
+    /// calls to the allocator since the start of the process, reallocations included
+    pub allocations: usize,
+    /// bytes currently allocated
+    pub current: usize,
+    /// most bytes allocated at once since the last `reset_peak`
+    pub peak: usize,
+}
+
+#[cfg(feature = "alloc-tracking")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub(super) static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    pub(super) static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    pub(super) static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    pub(super) static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct CountingAllocator;
+
+    fn allocated(size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    // SAFETY: all allocation is delegated to the system allocator; only counters are added
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+                allocated(new_size);
+            }
+            new
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+}
+
+#[cfg(feature = "alloc-tracking")]
+pub fn stats() -> Option<AllocStats> {
+    use std::sync::atomic::Ordering;
+    Some(AllocStats {
+        allocated: counting::ALLOCATED.load(Ordering::Relaxed),
+        allocations: counting::ALLOCATIONS.load(Ordering::Relaxed),
+        current: counting::CURRENT.load(Ordering::Relaxed),
+        peak: counting::PEAK.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(not(feature = "alloc-tracking"))]
+pub fn stats() -> Option<AllocStats> {
+    None
+}
+
+/// Restarts peak tracking from the current usage.
+pub fn reset_peak() {
+    #[cfg(feature = "alloc-tracking")]
+    {
+        use std::sync::atomic::Ordering;
+        counting::PEAK.store(counting::CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
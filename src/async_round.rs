@@ -0,0 +1,174 @@
+//! A DKG-style round run asynchronously: every party deals a transcript, sends it to all others
+//! over links with their own delays, and verifies the transcripts it receives in the order they
+//! arrive, while later ones are still on their way. `party::PartySimulation` instead starts a
+//! step only once the previous one is done everywhere, which misses that verifying the first
+//! transcripts overlaps with waiting for the last.
+//!
+//! Parties run in virtual time: dealing and verifying one transcript are simulated once each,
+//! and every party's timeline is computed from those times and the link delays. So the result
+//! doesn't depend on how many cores the simulating machine has to run the parties on.
+
+use crate::network::parse_duration;
+
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::time::Duration;
+
+
+/// Every message takes `base` plus a delay drawn uniformly from `0..=jitter`, independently per
+/// link.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Delays {
+    pub base: Duration,
+    pub jitter: Duration,
+}
+
+impl Delays {
+    /// Parses `BASE[,JITTER]`, e.g. `50ms` or `50ms,20ms`, in us, ms or s.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (base, jitter) = spec.split_once(',').unwrap_or((spec, "0s"));
+        let parse = |s: &str| parse_duration(s.trim()).ok_or_else(|| format!("invalid delay `{}`", s.trim()));
+        Ok(Self { base: parse(base)?, jitter: parse(jitter)? })
+    }
+
+    fn sample(&self, rng: &mut impl RngCore) -> Duration {
+        self.base + self.jitter.mul_f64(rng.gen_range(0.0..=1.0))
+    }
+}
+
+impl fmt::Display for Delays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.base)?;
+        if !self.jitter.is_zero() {
+            write!(f, " + up to {:?}", self.jitter)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AsyncRound {
+    pub parties: usize,
+    /// one party's dealing
+    pub deal: Duration,
+    /// one transcript's verification
+    pub verify: Duration,
+    pub delays: Delays,
+}
+
+/// A transcript received by a party.
+#[derive(Clone, Copy, Debug)]
+pub struct Arrival {
+    pub from: usize,
+    pub arrived: Duration,
+    /// when its verification finished
+    pub verified: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct PartyTimeline {
+    pub party: usize,
+    /// when its own transcript was dealt and sent
+    pub dealt: Duration,
+    /// in the order they were verified, which is the order they arrived
+    pub arrivals: Vec<Arrival>,
+}
+
+impl PartyTimeline {
+    /// When the party had verified every transcript.
+    pub fn done(&self) -> Duration {
+        self.arrivals.last().map_or(self.dealt, |arrival| arrival.verified)
+    }
+
+    pub fn last_arrival(&self) -> Duration {
+        self.arrivals.iter().map(|arrival| arrival.arrived).max().unwrap_or(self.dealt)
+    }
+
+    /// When the party would be done if it only started verifying once every transcript had
+    /// arrived, as in a round of sequential steps.
+    pub fn done_sequentially(&self, verify: Duration) -> Duration {
+        self.last_arrival().max(self.dealt) + verify * self.arrivals.len() as u32
+    }
+}
+
+impl AsyncRound {
+    /// Every party's timeline, with the link delays drawn from `rng`.
+    pub fn simulate(&self, mut rng: impl RngCore) -> AsyncRoundReport {
+        // delays[from][to]
+        let delays : Vec<Vec<Duration>> = (0..self.parties)
+            .map(|_| (0..self.parties).map(|_| self.delays.sample(&mut rng)).collect())
+            .collect();
+        let parties = (0..self.parties)
+            .map(|party| {
+                let mut arrivals : Vec<(usize, Duration)> = (0..self.parties)
+                    .filter(|&from| from != party)
+                    .map(|from| (from, self.deal + delays[from][party]))
+                    .collect();
+                arrivals.sort_by_key(|&(from, arrived)| (arrived, from));
+                // one transcript at a time, each as soon as it's there and the last is verified
+                let mut free = self.deal;
+                let arrivals = arrivals.into_iter()
+                    .map(|(from, arrived)| {
+                        free = free.max(arrived) + self.verify;
+                        Arrival { from, arrived, verified: free }
+                    })
+                    .collect();
+                PartyTimeline { party, dealt: self.deal, arrivals }
+            })
+            .collect();
+        AsyncRoundReport { round: *self, parties }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AsyncRoundReport {
+    pub round: AsyncRound,
+    pub parties: Vec<PartyTimeline>,
+}
+
+impl AsyncRoundReport {
+    /// When the last party was done.
+    pub fn total(&self) -> Duration {
+        self.parties.iter().map(PartyTimeline::done).max().unwrap_or_default()
+    }
+
+    /// The same round with every party waiting for all transcripts before verifying any.
+    pub fn sequential(&self) -> Duration {
+        self.parties.iter().map(|party| party.done_sequentially(self.round.verify)).max().unwrap_or_default()
+    }
+
+    /// The party that was done last.
+    pub fn slowest(&self) -> Option<&PartyTimeline> {
+        self.parties.iter().max_by_key(|party| party.done())
+    }
+}
+
+// beyond this many parties, only the slowest is shown
+const MAX_LISTED_PARTIES : usize = 16;
+
+impl fmt::Display for AsyncRoundReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "asynchronous round, {} parties, delays of {}:", self.round.parties, self.round.delays)?;
+        writeln!(f, "  dealing: {:.3?}, verifying one transcript: {:.3?}", self.round.deal, self.round.verify)?;
+        let timeline = |f: &mut fmt::Formatter<'_>, name: &str, party: &PartyTimeline| writeln!(f, "  {} {}: dealt at {:.3?}, last transcript arrived at {:.3?}, done at {:.3?}",
+            name, party.party + 1, party.dealt, party.last_arrival(), party.done());
+        if self.parties.len() <= MAX_LISTED_PARTIES {
+            for party in &self.parties {
+                timeline(f, "party", party)?;
+            }
+        }
+        if let Some(slowest) = self.slowest() {
+            if self.parties.len() <= MAX_LISTED_PARTIES {
+                writeln!(f, "  party {}'s transcripts:", slowest.party + 1)?;
+                for arrival in &slowest.arrivals {
+                    writeln!(f, "    from party {}: arrived at {:.3?}, verified at {:.3?}", arrival.from + 1, arrival.arrived, arrival.verified)?;
+                }
+            } else {
+                timeline(f, "slowest party", slowest)?;
+            }
+        }
+        let (total, sequential) = (self.total(), self.sequential());
+        writeln!(f, "  round: {:.3?}", total)?;
+        writeln!(f, "  verifying only after the last arrival: {:.3?} ({:.2}x the round)", sequential, sequential.as_secs_f64() / total.as_secs_f64().max(f64::MIN_POSITIVE))
+    }
+}
@@ -0,0 +1,763 @@
+//! Curve backends: the core group operations (exps, multi-exps, pairings, multi-pairings) behind
+//! one trait, so the same workload can be timed on different implementations of the curve and
+//! the results compared op by op.
+//!
+//! `Blstrs` and `Blst` (blst's C functions without the blstrs layer) are built in. The others
+//! implement `Backend` on top of their crate, an optional dependency behind a feature, and
+//! `simulate_on_all` runs them when it is on: `Arkworks` (`ark-bls12-381`) with `arkworks`,
+//! `Zkcrypto` (`bls12_381`) with `zkcrypto`, `ArkBn254` with `bn254` and `Gpu` with `gpu`.
+//! `gap_table` sums their times by kind of op, for the constant factor between two
+//! implementations of each.
+//!
+//! Backends need not implement BLS12-381: `Backend::CURVE` says which curve they do, and reports
+//! name it next to the backend. `ArkBn254` (`ark-bn254`, with `bn254`) runs the same presets on
+//! BN254, the curve of the EVM's pairing precompiles; its elements are smaller
+//! (`Curve::element_size`), which changes transcript sizes, and its security level lower.
+//!
+//! `Gpu` (with `gpu`) runs blstrs' multi-exps on OpenCL GPUs, on the CPU where there is none
+//! (`gpu_devices`), and everything else as `Blstrs`. Its multi-exps are timed end to end, with
+//! the transfers to and from the device, as a verifier offloading them would pay them.
+
+use crate::clock::Instant;
+use crate::communication::{Communication, Element};
+use crate::pairing_utils;
+use crate::render::{Cell, Table, Totals};
+use crate::report::{ItemReport, Metadata, OpCount, SimulationReport};
+use crate::workload::{OpKind, Workload};
+
+use blst::{
+    blst_final_exp, blst_fp12, blst_fp12_mul, blst_fp12_one, blst_fp12_sqr, blst_hash_to_g1, blst_hash_to_g2,
+    blst_miller_loop_n, blst_p1, blst_p1_affine, blst_p1_mult, blst_p1s_mult_pippenger,
+    blst_p1s_mult_pippenger_scratch_sizeof, blst_p1s_to_affine, blst_p2, blst_p2_affine, blst_p2_mult,
+    blst_p2s_mult_pippenger, blst_p2s_mult_pippenger_scratch_sizeof, blst_p2s_to_affine, blst_scalar,
+    blst_scalar_from_be_bytes, limb_t,
+};
+use blstrs::{G1Affine, G1Projective, G2Projective, Gt, Scalar};
+use group::{ff::Field as _, Group as _};
+use rand::RngCore;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::fmt;
+use std::hint::black_box;
+use std::ptr;
+use std::time::Duration;
+
+
+/// The pairing-friendly curves backends implement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Curve {
+    Bls12_381,
+    Bn254,
+}
+
+impl Curve {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Curve::Bls12_381 => "BLS12-381",
+            Curve::Bn254 => "BN254",
+        }
+    }
+
+    /// Compressed encoding size in bytes (`Element::size` is BLS12-381's).
+    pub fn element_size(&self, element: Element) -> usize {
+        match (self, element) {
+            (Curve::Bls12_381, element) => element.size(),
+            (Curve::Bn254, Element::G1) => 32,
+            (Curve::Bn254, Element::G2) => 64,
+            (Curve::Bn254, Element::Gt) => 384,
+            (Curve::Bn254, Element::Scalar) => 32,
+        }
+    }
+
+    /// Total bytes of `communication` with this curve's encodings.
+    pub fn bytes(&self, communication: &Communication) -> usize {
+        communication.messages.iter().map(|message| message.num * self.element_size(message.element)).sum()
+    }
+}
+
+/// One implementation of a pairing-friendly curve.
+pub trait Backend {
+    /// as reports name the backend
+    const NAME: &'static str;
+    const CURVE: Curve;
+    type Scalar: 'static;
+    type G1: 'static;
+    type G2: 'static;
+    type Gt: 'static;
+
+    fn random_scalar(rng: &mut dyn RngCore) -> Self::Scalar;
+    fn random_g1(rng: &mut dyn RngCore) -> Self::G1;
+    fn random_g2(rng: &mut dyn RngCore) -> Self::G2;
+    fn random_gt(rng: &mut dyn RngCore) -> Self::Gt;
+
+    fn g1_exp(base: &Self::G1, scalar: &Self::Scalar) -> Self::G1;
+    fn g2_exp(base: &Self::G2, scalar: &Self::Scalar) -> Self::G2;
+    fn gt_exp(base: &Self::Gt, scalar: &Self::Scalar) -> Self::Gt;
+    fn g1_multi_exp(bases: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1;
+    fn g2_multi_exp(bases: &[Self::G2], scalars: &[Self::Scalar]) -> Self::G2;
+    fn pairing(a: &Self::G1, b: &Self::G2) -> Self::Gt;
+    /// the product of the pairings of `a[i]` and `b[i]`, with one final exponentiation
+    fn multi_pairing(a: &[Self::G1], b: &[Self::G2]) -> Self::Gt;
+}
+
+/// BLS12-381 via blstrs (over blst), as `GroupOpsSimulation` uses it.
+pub struct Blstrs;
+
+impl Backend for Blstrs {
+    const NAME: &'static str = "blstrs";
+    const CURVE: Curve = Curve::Bls12_381;
+    type Scalar = Scalar;
+    type G1 = G1Projective;
+    type G2 = G2Projective;
+    type Gt = Gt;
+
+    fn random_scalar(rng: &mut dyn RngCore) -> Scalar {
+        Scalar::random(rng)
+    }
+    fn random_g1(rng: &mut dyn RngCore) -> G1Projective {
+        G1Projective::random(rng)
+    }
+    fn random_g2(rng: &mut dyn RngCore) -> G2Projective {
+        G2Projective::random(rng)
+    }
+    fn random_gt(rng: &mut dyn RngCore) -> Gt {
+        Gt::random(rng)
+    }
+
+    fn g1_exp(base: &G1Projective, scalar: &Scalar) -> G1Projective {
+        base * scalar
+    }
+    fn g2_exp(base: &G2Projective, scalar: &Scalar) -> G2Projective {
+        base * scalar
+    }
+    fn gt_exp(base: &Gt, scalar: &Scalar) -> Gt {
+        base * scalar
+    }
+    fn g1_multi_exp(bases: &[G1Projective], scalars: &[Scalar]) -> G1Projective {
+        G1Projective::multi_exp(bases, scalars)
+    }
+    fn g2_multi_exp(bases: &[G2Projective], scalars: &[Scalar]) -> G2Projective {
+        G2Projective::multi_exp(bases, scalars)
+    }
+    fn pairing(a: &G1Projective, b: &G2Projective) -> Gt {
+        blstrs::pairing(&G1Affine::from(a), &b.into())
+    }
+    fn multi_pairing(a: &[G1Projective], b: &[G2Projective]) -> Gt {
+        pairing_utils::multi_pairing(a, b)
+    }
+}
+
+/// BLS12-381 via blst's C functions directly, bypassing blstrs' wrappers: compared with `Blstrs`,
+/// it shows what the safe wrapper layer adds to the simulated costs. Exps in Gt, which blst
+/// doesn't provide, are square-and-multiply over `blst_fp12_sqr`/`blst_fp12_mul`, as blstrs
+/// does them. Multi-exps run single-threaded, whereas blstrs' use blst's thread pool on
+/// machines with several cores, so compare the two on one core.
+pub struct Blst;
+
+const BLST_DST : &[u8] = b"blstrs-pvss-simulation random points";
+
+impl Backend for Blst {
+    const NAME: &'static str = "blst";
+    const CURVE: Curve = Curve::Bls12_381;
+    type Scalar = blst_scalar;
+    type G1 = blst_p1;
+    type G2 = blst_p2;
+    type Gt = blst_fp12;
+
+    fn random_scalar(rng: &mut dyn RngCore) -> blst_scalar {
+        let mut wide = [0u8; 64];
+        rng.fill_bytes(&mut wide);
+        let mut scalar = blst_scalar::default();
+        // SAFETY: blst reads 64 bytes from `wide` and reduces them into `scalar`
+        unsafe { blst_scalar_from_be_bytes(&mut scalar, wide.as_ptr(), wide.len()) };
+        scalar
+    }
+    fn random_g1(rng: &mut dyn RngCore) -> blst_p1 {
+        let mut msg = [0u8; 64];
+        rng.fill_bytes(&mut msg);
+        let mut out = blst_p1::default();
+        // SAFETY: all pointers come with their lengths, and `out` is one point
+        unsafe { blst_hash_to_g1(&mut out, msg.as_ptr(), msg.len(), BLST_DST.as_ptr(), BLST_DST.len(), std::ptr::null(), 0) };
+        out
+    }
+    fn random_g2(rng: &mut dyn RngCore) -> blst_p2 {
+        let mut msg = [0u8; 64];
+        rng.fill_bytes(&mut msg);
+        let mut out = blst_p2::default();
+        // SAFETY: as for `random_g1`
+        unsafe { blst_hash_to_g2(&mut out, msg.as_ptr(), msg.len(), BLST_DST.as_ptr(), BLST_DST.len(), std::ptr::null(), 0) };
+        out
+    }
+    fn random_gt(rng: &mut dyn RngCore) -> blst_fp12 {
+        let (a, b) = (Self::random_g1(rng), Self::random_g2(rng));
+        Self::pairing(&a, &b)
+    }
+
+    fn g1_exp(base: &blst_p1, scalar: &blst_scalar) -> blst_p1 {
+        let mut out = blst_p1::default();
+        // SAFETY: `scalar.b` holds the 255 bits blst reads
+        unsafe { blst_p1_mult(&mut out, base, scalar.b.as_ptr(), 255) };
+        out
+    }
+    fn g2_exp(base: &blst_p2, scalar: &blst_scalar) -> blst_p2 {
+        let mut out = blst_p2::default();
+        // SAFETY: as for `g1_exp`
+        unsafe { blst_p2_mult(&mut out, base, scalar.b.as_ptr(), 255) };
+        out
+    }
+    fn gt_exp(base: &blst_fp12, scalar: &blst_scalar) -> blst_fp12 {
+        // SAFETY: blst_fp12_one points to a static constant
+        let mut acc = unsafe { *blst_fp12_one() };
+        for i in (0..255).rev() {
+            // SAFETY: the fp12 functions allow their output to alias their inputs
+            unsafe {
+                blst_fp12_sqr(&mut acc, &acc);
+                if (scalar.b[i / 8] >> (i % 8)) & 1 == 1 {
+                    blst_fp12_mul(&mut acc, &acc, base);
+                }
+            }
+        }
+        acc
+    }
+    fn g1_multi_exp(bases: &[blst_p1], scalars: &[blst_scalar]) -> blst_p1 {
+        let n = bases.len().min(scalars.len());
+        let mut affine = vec![blst_p1_affine::default(); n];
+        let mut out = blst_p1::default();
+        // blst takes arrays of pointers; a null after the first makes it read contiguously
+        let points = [bases.as_ptr(), ptr::null()];
+        let affine_ptrs = [affine.as_ptr(), ptr::null()];
+        let scalar_ptrs = [scalars.as_ptr() as *const u8, ptr::null()];
+        // SAFETY: `scratch` has the size blst asks for; blst reads `n` points and contiguous
+        // 32-byte scalars (`blst_scalar` is exactly its bytes), and writes `n` affine points
+        unsafe {
+            blst_p1s_to_affine(affine.as_mut_ptr(), points.as_ptr(), n);
+            let mut scratch = vec![0 as limb_t; blst_p1s_mult_pippenger_scratch_sizeof(n) / std::mem::size_of::<limb_t>()];
+            blst_p1s_mult_pippenger(&mut out, affine_ptrs.as_ptr(), n, scalar_ptrs.as_ptr(), 255, scratch.as_mut_ptr());
+        }
+        out
+    }
+    fn g2_multi_exp(bases: &[blst_p2], scalars: &[blst_scalar]) -> blst_p2 {
+        let n = bases.len().min(scalars.len());
+        let mut affine = vec![blst_p2_affine::default(); n];
+        let mut out = blst_p2::default();
+        // blst takes arrays of pointers; a null after the first makes it read contiguously
+        let points = [bases.as_ptr(), ptr::null()];
+        let affine_ptrs = [affine.as_ptr(), ptr::null()];
+        let scalar_ptrs = [scalars.as_ptr() as *const u8, ptr::null()];
+        // SAFETY: as for `g1_multi_exp`
+        unsafe {
+            blst_p2s_to_affine(affine.as_mut_ptr(), points.as_ptr(), n);
+            let mut scratch = vec![0 as limb_t; blst_p2s_mult_pippenger_scratch_sizeof(n) / std::mem::size_of::<limb_t>()];
+            blst_p2s_mult_pippenger(&mut out, affine_ptrs.as_ptr(), n, scalar_ptrs.as_ptr(), 255, scratch.as_mut_ptr());
+        }
+        out
+    }
+    fn pairing(a: &blst_p1, b: &blst_p2) -> blst_fp12 {
+        Self::multi_pairing(std::slice::from_ref(a), std::slice::from_ref(b))
+    }
+    fn multi_pairing(a: &[blst_p1], b: &[blst_p2]) -> blst_fp12 {
+        let n = a.len().min(b.len());
+        let mut a_affine = vec![blst_p1_affine::default(); n];
+        let mut b_affine = vec![blst_p2_affine::default(); n];
+        let mut out = blst_fp12::default();
+        // SAFETY: every array holds `n` entries, passed as contiguous as in `g1_multi_exp`
+        unsafe {
+            blst_p1s_to_affine(a_affine.as_mut_ptr(), [a.as_ptr(), ptr::null()].as_ptr(), n);
+            blst_p2s_to_affine(b_affine.as_mut_ptr(), [b.as_ptr(), ptr::null()].as_ptr(), n);
+            let (a_ptrs, b_ptrs) = ([a_affine.as_ptr(), ptr::null()], [b_affine.as_ptr(), ptr::null()]);
+            blst_miller_loop_n(&mut out, b_ptrs.as_ptr(), a_ptrs.as_ptr(), n);
+            blst_final_exp(&mut out, &out);
+        }
+        out
+    }
+}
+
+#[cfg(any(feature = "arkworks", feature = "bn254"))]
+pub use ark::{Ark, ArkCurve};
+#[cfg(feature = "arkworks")]
+pub use ark::Arkworks;
+#[cfg(feature = "bn254")]
+pub use ark::ArkBn254;
+
+#[cfg(any(feature = "arkworks", feature = "bn254"))]
+mod ark {
+    use super::{Backend, Curve};
+
+    use ark_ec::pairing::{Pairing, PairingOutput};
+    use ark_ec::{CurveGroup, VariableBaseMSM as _};
+    use ark_ff::UniformRand as _;
+    use rand::RngCore;
+    use std::marker::PhantomData;
+
+    /// A curve of arkworks, as `Ark` runs it.
+    pub trait ArkCurve: Pairing {
+        const NAME: &'static str;
+        const CURVE: Curve;
+    }
+
+    #[cfg(feature = "arkworks")]
+    impl ArkCurve for ark_bls12_381::Bls12_381 {
+        const NAME: &'static str = "arkworks";
+        const CURVE: Curve = Curve::Bls12_381;
+    }
+
+    #[cfg(feature = "bn254")]
+    impl ArkCurve for ark_bn254::Bn254 {
+        const NAME: &'static str = "arkworks";
+        const CURVE: Curve = Curve::Bn254;
+    }
+
+    /// A pairing-friendly curve via arkworks' generic curve code (`ark-ec`), with the field
+    /// arithmetic of the curve's crate. Multi-exps convert their bases to affine first, as
+    /// `Blst`'s do, and run single-threaded (the crates' `parallel` feature is off), so compare
+    /// it with `Blstrs` on one core.
+    pub struct Ark<E>(PhantomData<E>);
+
+    /// BLS12-381 via `ark-bls12-381`.
+    #[cfg(feature = "arkworks")]
+    pub type Arkworks = Ark<ark_bls12_381::Bls12_381>;
+
+    /// BN254 via `ark-bn254`.
+    #[cfg(feature = "bn254")]
+    pub type ArkBn254 = Ark<ark_bn254::Bn254>;
+
+    impl<E: ArkCurve> Backend for Ark<E> {
+        const NAME: &'static str = E::NAME;
+        const CURVE: Curve = E::CURVE;
+        type Scalar = E::ScalarField;
+        type G1 = E::G1;
+        type G2 = E::G2;
+        type Gt = PairingOutput<E>;
+
+        fn random_scalar(rng: &mut dyn RngCore) -> E::ScalarField {
+            E::ScalarField::rand(rng)
+        }
+        fn random_g1(rng: &mut dyn RngCore) -> E::G1 {
+            E::G1::rand(rng)
+        }
+        fn random_g2(rng: &mut dyn RngCore) -> E::G2 {
+            E::G2::rand(rng)
+        }
+        fn random_gt(rng: &mut dyn RngCore) -> PairingOutput<E> {
+            PairingOutput::rand(rng)
+        }
+
+        fn g1_exp(base: &E::G1, scalar: &E::ScalarField) -> E::G1 {
+            *base * scalar
+        }
+        fn g2_exp(base: &E::G2, scalar: &E::ScalarField) -> E::G2 {
+            *base * scalar
+        }
+        fn gt_exp(base: &PairingOutput<E>, scalar: &E::ScalarField) -> PairingOutput<E> {
+            *base * scalar
+        }
+        fn g1_multi_exp(bases: &[E::G1], scalars: &[E::ScalarField]) -> E::G1 {
+            let n = bases.len().min(scalars.len());
+            E::G1::msm_unchecked(&E::G1::normalize_batch(&bases[..n]), &scalars[..n])
+        }
+        fn g2_multi_exp(bases: &[E::G2], scalars: &[E::ScalarField]) -> E::G2 {
+            let n = bases.len().min(scalars.len());
+            E::G2::msm_unchecked(&E::G2::normalize_batch(&bases[..n]), &scalars[..n])
+        }
+        fn pairing(a: &E::G1, b: &E::G2) -> PairingOutput<E> {
+            E::pairing(a, b)
+        }
+        fn multi_pairing(a: &[E::G1], b: &[E::G2]) -> PairingOutput<E> {
+            E::multi_pairing(a, b)
+        }
+    }
+}
+
+
+#[cfg(feature = "zkcrypto")]
+pub use zkcrypto::Zkcrypto;
+
+#[cfg(feature = "zkcrypto")]
+mod zkcrypto {
+    use super::{Backend, Curve};
+    use crate::pippenger::{self, PippengerConfig};
+
+    use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
+    use group::{ff::Field as _, Curve as _, Group as _};
+    use rand::RngCore;
+
+    /// BLS12-381 via zkcrypto's pure-Rust `bls12_381`, to compare a portable implementation
+    /// with blst's assembly. The crate has no multi-exp, so multi-exps run
+    /// `pippenger::multi_exp_projective` (with `PippengerConfig::default_for` the size) rather than
+    /// summing individual exps, which would widen the gap to blst with the size for want of an
+    /// algorithm rather than of a fast curve. They run single-threaded: compare on one core.
+    pub struct Zkcrypto;
+
+    fn multi_exp<G>(bases: &[G], scalars: &[Scalar]) -> G
+    where G : group::Group + for<'a> std::ops::AddAssign<&'a G> + for<'a> std::ops::SubAssign<&'a G> {
+        let n = bases.len().min(scalars.len());
+        let scalars : Vec<[u8; 32]> = scalars[..n].iter().map(Scalar::to_bytes).collect();
+        pippenger::multi_exp_projective(&bases[..n], &scalars, &PippengerConfig::default_for(n))
+    }
+
+    impl Backend for Zkcrypto {
+        const NAME: &'static str = "zkcrypto";
+        const CURVE: Curve = Curve::Bls12_381;
+        type Scalar = Scalar;
+        type G1 = G1Projective;
+        type G2 = G2Projective;
+        type Gt = Gt;
+
+        fn random_scalar(rng: &mut dyn RngCore) -> Scalar {
+            Scalar::random(rng)
+        }
+        fn random_g1(rng: &mut dyn RngCore) -> G1Projective {
+            G1Projective::random(rng)
+        }
+        fn random_g2(rng: &mut dyn RngCore) -> G2Projective {
+            G2Projective::random(rng)
+        }
+        fn random_gt(rng: &mut dyn RngCore) -> Gt {
+            Gt::random(rng)
+        }
+
+        fn g1_exp(base: &G1Projective, scalar: &Scalar) -> G1Projective {
+            base * scalar
+        }
+        fn g2_exp(base: &G2Projective, scalar: &Scalar) -> G2Projective {
+            base * scalar
+        }
+        fn gt_exp(base: &Gt, scalar: &Scalar) -> Gt {
+            base * scalar
+        }
+        fn g1_multi_exp(bases: &[G1Projective], scalars: &[Scalar]) -> G1Projective {
+            multi_exp(bases, scalars)
+        }
+        fn g2_multi_exp(bases: &[G2Projective], scalars: &[Scalar]) -> G2Projective {
+            multi_exp(bases, scalars)
+        }
+        fn pairing(a: &G1Projective, b: &G2Projective) -> Gt {
+            bls12_381::pairing(&a.to_affine(), &b.to_affine())
+        }
+        fn multi_pairing(a: &[G1Projective], b: &[G2Projective]) -> Gt {
+            let n = a.len().min(b.len());
+            let mut a_affine = vec![G1Affine::identity(); n];
+            let mut b_affine = vec![G2Affine::identity(); n];
+            G1Projective::batch_normalize(&a[..n], &mut a_affine);
+            G2Projective::batch_normalize(&b[..n], &mut b_affine);
+            let prepared : Vec<G2Prepared> = b_affine.into_iter().map(G2Prepared::from).collect();
+            let terms : Vec<(&G1Affine, &G2Prepared)> = a_affine.iter().zip(&prepared).collect();
+            multi_miller_loop(&terms).final_exponentiation()
+        }
+    }
+}
+
+
+#[cfg(feature = "gpu")]
+pub use gpu::{gpu_devices, Gpu};
+
+#[cfg(feature = "gpu")]
+mod gpu {
+    use super::{Backend, Blstrs, Curve};
+
+    use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective, Gt, Scalar};
+    use ec_gpu::GpuName;
+    use ec_gpu_gen::multiexp::MultiexpKernel;
+    use ec_gpu_gen::rust_gpu_tools::{opencl, Device, GPUError, Program};
+    use ec_gpu_gen::threadpool::Worker;
+    use ec_gpu_gen::EcError;
+    use ff::PrimeField as _;
+    use group::prime::PrimeCurveAffine;
+    use group::Curve as _;
+    use rand::RngCore;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    /// BLS12-381 via blstrs, with the G1 and G2 multi-exps on every OpenCL GPU, by the kernels
+    /// of `ec-gpu-gen` that the build script generates. Where there is no device, or the kernels
+    /// fail to build for it, the multi-exps run on the CPU as `Blstrs` runs them: `gpu_devices`
+    /// tells which. Multi-exps are timed end to end, with the conversion of the bases to affine,
+    /// their upload with the scalars and the download of the results, as a verifier would pay
+    /// them. The kernel time isn't reported on its own, since `ec-gpu-gen` runs the transfers
+    /// and the kernel in one call.
+    pub struct Gpu;
+
+    struct Kernels {
+        g1: MultiexpKernel<'static, G1Affine>,
+        g2: MultiexpKernel<'static, G2Affine>,
+        devices: usize,
+        pool: Worker,
+    }
+
+    // the kernels on every device, built on first use; `None` without a device
+    fn kernels() -> Option<&'static Mutex<Kernels>> {
+        static KERNELS : OnceLock<Option<Mutex<Kernels>>> = OnceLock::new();
+        KERNELS.get_or_init(|| {
+            let devices = Device::all();
+            let programs = || devices.iter().map(|device| program(device)).collect::<Result<Vec<_>, _>>().ok();
+            let g1 = MultiexpKernel::create(programs()?, &devices).ok()?;
+            let g2 = MultiexpKernel::create(programs()?, &devices).ok()?;
+            Some(Mutex::new(Kernels { devices: g1.num_kernels(), g1, g2, pool: Worker::new() }))
+        }).as_ref()
+    }
+
+    fn program(device: &Device) -> Result<Program, EcError> {
+        let source = include_str!(env!("_EC_GPU_OPENCL_KERNEL_SOURCE"));
+        let device = device.opencl_device().ok_or(GPUError::DeviceNotFound)?;
+        Ok(Program::Opencl(opencl::Program::from_opencl(device, source)?))
+    }
+
+    /// The number of GPUs `Gpu` runs multi-exps on, 0 if it runs them on the CPU.
+    pub fn gpu_devices() -> usize {
+        kernels().map_or(0, |kernels| kernels.lock().unwrap().devices)
+    }
+
+    fn multi_exp<G: PrimeCurveAffine<Scalar = Scalar> + GpuName>(kernel: &mut MultiexpKernel<'static, G>, pool: &Worker, bases: &[G::Curve], scalars: &[Scalar]) -> Option<G::Curve> {
+        let n = bases.len().min(scalars.len());
+        let mut affine = vec![G::identity(); n];
+        G::Curve::batch_normalize(&bases[..n], &mut affine);
+        let scalars = scalars[..n].iter().map(Scalar::to_repr).collect();
+        kernel.multiexp(pool, Arc::new(affine), Arc::new(scalars), 0).ok()
+    }
+
+    impl Backend for Gpu {
+        const NAME: &'static str = "blstrs-gpu";
+        const CURVE: Curve = Curve::Bls12_381;
+        type Scalar = Scalar;
+        type G1 = G1Projective;
+        type G2 = G2Projective;
+        type Gt = Gt;
+
+        fn random_scalar(rng: &mut dyn RngCore) -> Scalar {
+            Blstrs::random_scalar(rng)
+        }
+        fn random_g1(rng: &mut dyn RngCore) -> G1Projective {
+            Blstrs::random_g1(rng)
+        }
+        fn random_g2(rng: &mut dyn RngCore) -> G2Projective {
+            Blstrs::random_g2(rng)
+        }
+        fn random_gt(rng: &mut dyn RngCore) -> Gt {
+            Blstrs::random_gt(rng)
+        }
+
+        fn g1_exp(base: &G1Projective, scalar: &Scalar) -> G1Projective {
+            Blstrs::g1_exp(base, scalar)
+        }
+        fn g2_exp(base: &G2Projective, scalar: &Scalar) -> G2Projective {
+            Blstrs::g2_exp(base, scalar)
+        }
+        fn gt_exp(base: &Gt, scalar: &Scalar) -> Gt {
+            Blstrs::gt_exp(base, scalar)
+        }
+        // on the CPU, too, if the device fails
+        fn g1_multi_exp(bases: &[G1Projective], scalars: &[Scalar]) -> G1Projective {
+            kernels()
+                .and_then(|kernels| {
+                    let kernels = &mut *kernels.lock().unwrap();
+                    multi_exp(&mut kernels.g1, &kernels.pool, bases, scalars)
+                })
+                .unwrap_or_else(|| Blstrs::g1_multi_exp(bases, scalars))
+        }
+        fn g2_multi_exp(bases: &[G2Projective], scalars: &[Scalar]) -> G2Projective {
+            kernels()
+                .and_then(|kernels| {
+                    let kernels = &mut *kernels.lock().unwrap();
+                    multi_exp(&mut kernels.g2, &kernels.pool, bases, scalars)
+                })
+                .unwrap_or_else(|| Blstrs::g2_multi_exp(bases, scalars))
+        }
+        fn pairing(a: &G1Projective, b: &G2Projective) -> Gt {
+            Blstrs::pairing(a, b)
+        }
+        fn multi_pairing(a: &[G1Projective], b: &[G2Projective]) -> Gt {
+            Blstrs::multi_pairing(a, b)
+        }
+    }
+}
+
+
+/// A workload op that a backend has no counterpart for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedOp {
+    pub backend: &'static str,
+    pub kind: OpKind,
+}
+
+impl fmt::Display for UnsupportedOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the {} backend doesn't run `{}` (only exps, multi-exps, pairings and multi-pairings)", self.backend, self.kind.name())
+    }
+}
+
+impl std::error::Error for UnsupportedOp {}
+
+/// Whether every backend runs ops of this kind.
+pub fn is_core(kind: OpKind) -> bool {
+    matches!(kind, OpKind::G1Exps | OpKind::G2Exps | OpKind::GtExps | OpKind::G1MultiExps | OpKind::G2MultiExps | OpKind::Pairings | OpKind::MultiPairings)
+}
+
+/// Runs every op of `workload` once on backend `B`, timing each, with inputs drawn from
+/// `rng`. Supports the ops of `Backend`: G1/G2/Gt exps, G1/G2 multi-exps, pairings and
+/// multi-pairings.
+pub fn simulate_on<B: Backend>(workload: &Workload, mut rng: impl RngCore) -> Result<SimulationReport, UnsupportedOp> {
+    let rng : &mut dyn RngCore = &mut rng;
+    let mut runs : Vec<(Box<dyn Fn()>, Duration)> = Vec::new();
+    for op in &workload.ops {
+        let (num, size) = (op.num, op.size);
+        let setup_start = Instant::now();
+        let run : Box<dyn Fn()> = match op.kind {
+            OpKind::G1Exps => {
+                let args : Vec<_> = (0..num).map(|_| (B::random_g1(rng), B::random_scalar(rng))).collect();
+                Box::new(move || args.iter().for_each(|(base, scalar)| { black_box(B::g1_exp(base, scalar)); }))
+            }
+            OpKind::G2Exps => {
+                let args : Vec<_> = (0..num).map(|_| (B::random_g2(rng), B::random_scalar(rng))).collect();
+                Box::new(move || args.iter().for_each(|(base, scalar)| { black_box(B::g2_exp(base, scalar)); }))
+            }
+            OpKind::GtExps => {
+                let args : Vec<_> = (0..num).map(|_| (B::random_gt(rng), B::random_scalar(rng))).collect();
+                Box::new(move || args.iter().for_each(|(base, scalar)| { black_box(B::gt_exp(base, scalar)); }))
+            }
+            // ops of varying sizes use the first of the inputs of the largest
+            OpKind::G1MultiExps => {
+                let sizes = op.draw_sizes(rng);
+                let bases : Vec<_> = (0..size).map(|_| B::random_g1(rng)).collect();
+                let scalars : Vec<_> = (0..size).map(|_| B::random_scalar(rng)).collect();
+                Box::new(move || for &size in &sizes { black_box(B::g1_multi_exp(&bases[..size], &scalars[..size])); })
+            }
+            OpKind::G2MultiExps => {
+                let sizes = op.draw_sizes(rng);
+                let bases : Vec<_> = (0..size).map(|_| B::random_g2(rng)).collect();
+                let scalars : Vec<_> = (0..size).map(|_| B::random_scalar(rng)).collect();
+                Box::new(move || for &size in &sizes { black_box(B::g2_multi_exp(&bases[..size], &scalars[..size])); })
+            }
+            OpKind::Pairings => {
+                let args : Vec<_> = (0..num).map(|_| (B::random_g1(rng), B::random_g2(rng))).collect();
+                Box::new(move || args.iter().for_each(|(a, b)| { black_box(B::pairing(a, b)); }))
+            }
+            OpKind::MultiPairings => {
+                let sizes = op.draw_sizes(rng);
+                let a : Vec<_> = (0..size).map(|_| B::random_g1(rng)).collect();
+                let b : Vec<_> = (0..size).map(|_| B::random_g2(rng)).collect();
+                Box::new(move || for &size in &sizes { black_box(B::multi_pairing(&a[..size], &b[..size])); })
+            }
+            kind => return Err(UnsupportedOp { backend: B::NAME, kind }),
+        };
+        runs.push((run, setup_start.elapsed()));
+    }
+
+    let start_time = Instant::now();
+    let items = workload.ops.iter().zip(&runs)
+        .map(|(op, (run, setup))| {
+            let item_start = Instant::now();
+            run();
+            ItemReport {
+                description: op.to_string(),
+                label: op.label.clone(),
+                phase: op.phase.clone(),
+                phase_path: op.phase.iter().cloned().collect(),
+                op_count: OpCount::multi(op.num, op.size),
+                setup: *setup,
+                cold: None,
+                duration: item_start.elapsed(),
+                memory: None,
+                energy: None,
+                notes: None,
+            }
+        })
+        .collect();
+    Ok(SimulationReport { metadata: Metadata::default(), items, total: start_time.elapsed(), cpu: None })
+}
+
+/// A backend's name and curve, e.g. `blstrs (BLS12-381)`.
+pub fn describe<B: Backend>() -> String {
+    format!("{} ({})", B::NAME, B::CURVE.name())
+}
+
+/// Runs `workload` on every built-in backend, each with inputs derived from `seed`. Returns each
+/// backend's `describe` and curve with its report.
+pub fn simulate_on_all(workload: &Workload, seed: u64) -> Vec<(String, Curve, Result<SimulationReport, UnsupportedOp>)> {
+    fn run<B: Backend>(workload: &Workload, seed: u64) -> (String, Curve, Result<SimulationReport, UnsupportedOp>) {
+        (describe::<B>(), B::CURVE, simulate_on::<B>(workload, ChaCha20Rng::seed_from_u64(seed)))
+    }
+    #[allow(unused_mut)]
+    let mut all = vec![run::<Blstrs>(workload, seed), run::<Blst>(workload, seed)];
+    #[cfg(feature = "arkworks")]
+    all.push(run::<Arkworks>(workload, seed));
+    #[cfg(feature = "zkcrypto")]
+    all.push(run::<Zkcrypto>(workload, seed));
+    #[cfg(feature = "bn254")]
+    all.push(run::<ArkBn254>(workload, seed));
+    #[cfg(feature = "gpu")]
+    all.push(run::<Gpu>(workload, seed));
+    all
+}
+
+// the first column's `name` and one per backend, each after the first followed by its ratio to
+// the first
+fn comparison_header(name: &str, reports: &[(String, SimulationReport)]) -> Vec<String> {
+    let mut header = vec![name.to_string()];
+    for (i, (name, _)) in reports.iter().enumerate() {
+        header.push(name.clone());
+        if i > 0 {
+            // without the curves, which are in the time columns' headers, unless they differ
+            let short = |name: &str| name.split(" (").next().unwrap_or(name).to_string();
+            let curve = |name: &str| name.split_once(" (").map(|(_, curve)| curve.to_string());
+            let numerator = match curve(name) == curve(&reports[0].0) {
+                true => short(name),
+                false => name.clone(),
+            };
+            header.push(format!("{} / {}", numerator, short(&reports[0].0)));
+        }
+    }
+    header
+}
+
+// `name` and the time of each backend, each after the first also as a ratio to the first
+fn comparison_row(name: String, times: &[Duration]) -> Vec<Cell> {
+    let mut row = vec![Cell::Text(name)];
+    for (j, &time) in times.iter().enumerate() {
+        row.push(Cell::Time(time));
+        if j > 0 {
+            row.push(Cell::Text(format!("{:.2}", time.as_secs_f64() / times[0].as_secs_f64())));
+        }
+    }
+    row
+}
+
+/// A row per op and columns per backend, each after the first also as a ratio to the first.
+pub fn comparison_table(reports: &[(String, SimulationReport)]) -> Table {
+    let header = comparison_header("op", reports);
+    let Some((_, first)) = reports.first() else { return Table { header, rows: Vec::new(), totals: Totals::None } };
+    let mut rows : Vec<Vec<Cell>> = (0..first.items.len())
+        .map(|i| {
+            let item = &first.items[i];
+            let name = match &item.phase {
+                Some(phase) => format!("{} / {}", phase, item.name()),
+                None => item.name(),
+            };
+            let times : Vec<Duration> = reports.iter().map(|(_, report)| report.items[i].duration).collect();
+            comparison_row(name, &times)
+        })
+        .collect();
+    let totals : Vec<Duration> = reports.iter().map(|(_, report)| report.total).collect();
+    rows.push(comparison_row("total".to_string(), &totals));
+    let totals = Totals::Row(rows.len() - 1);
+    Table { header, rows, totals }
+}
+
+/// A row per kind of op (G1 exps, multi-pairings, ...) with the time of all of `workload`'s ops
+/// of the kind on each backend, as `comparison_table` lays it out: the constant factor between
+/// two implementations for each class of op, which the rows per op spread over the phases.
+pub fn gap_table(workload: &Workload, reports: &[(String, SimulationReport)]) -> Table {
+    let mut kinds : Vec<OpKind> = Vec::new();
+    for op in &workload.ops {
+        if !kinds.contains(&op.kind) {
+            kinds.push(op.kind);
+        }
+    }
+    let rows = kinds.into_iter()
+        .map(|kind| {
+            let times : Vec<Duration> = reports.iter()
+                .map(|(_, report)| workload.ops.iter().zip(&report.items).filter(|(op, _)| op.kind == kind).map(|(_, item)| item.duration).sum())
+                .collect();
+            comparison_row(kind.name().to_string(), &times)
+        })
+        .collect();
+    Table { header: comparison_header("op class", reports), rows, totals: Totals::None }
+}
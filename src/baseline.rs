@@ -0,0 +1,277 @@
+//! Saved timings to compare later runs against, to catch regressions (say, after a blstrs
+//! upgrade) in the numbers a paper relies on.
+//!
+//! A baseline is a list of named timings, one per item and one per simulation total, stored as
+//! TOML under `baselines/NAME.toml`:
+//!
+//! ```toml
+//! profile_id = "3f2a9c0e51d7b846"
+//!
+//! [[timing]]
+//! name = "Dealer / G1 exps: 1024 (share encryptions)"
+//! ns = 1432117.0
+//! ```
+
+use crate::report::{RepeatedRuns, SimulationReport};
+use crate::stats::Summary;
+use crate::toml::{self, Value};
+
+use std::fmt::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BaselineError::Io(e) => write!(f, "{}", e),
+            BaselineError::Invalid(message) => write!(f, "invalid baseline: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+impl From<std::io::Error> for BaselineError {
+    fn from(e: std::io::Error) -> Self {
+        BaselineError::Io(e)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Baseline {
+    /// unique names, in the order recorded
+    pub timings: Vec<(String, Duration)>,
+    /// the machine profile of the first report recorded (see `Metadata::profile_id`)
+    pub profile_id: Option<String>,
+    /// set when a recorded run was interrupted, so that timings of items that never ran are
+    /// missing rather than removed
+    pub partial: bool,
+    /// the statistics behind the timings recorded from runs, under the same names, for the
+    /// exports of module `export`; not saved, so empty in a loaded baseline
+    pub summaries: Vec<(String, Summary)>,
+    /// the time of every phase of the recorded runs, and their totals, named as the timings (the
+    /// means of repeated runs), for the LaTeX macros of module `latex`; not saved either
+    pub phases: Vec<(String, Duration)>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Where `--save-baseline NAME` and `--baseline NAME` keep the baseline.
+    pub fn path(name: &str) -> PathBuf {
+        Path::new("baselines").join(format!("{}.toml", name))
+    }
+
+    /// Adds a timing, numbering repeated names (`name #2`, ...) so every entry stays unique.
+    pub fn add(&mut self, name: String, duration: Duration) {
+        let mut unique = name.clone();
+        let mut i = 1;
+        while self.get(&unique).is_some() {
+            i += 1;
+            unique = format!("{} #{}", name, i);
+        }
+        self.timings.push((unique, duration));
+    }
+
+    // adds the mean as the timing
+    fn add_summary(&mut self, name: String, summary: Summary) {
+        self.add(name, summary.mean);
+        let (unique, _) = self.timings.last().unwrap();
+        self.summaries.push((unique.clone(), summary));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Duration> {
+        self.timings.iter().find(|(n, _)| n == name).map(|(_, d)| *d)
+    }
+
+    /// Adds every item of `report` and its total, their names prefixed with `prefix` (e.g. the
+    /// parameters of a sweep point) to tell several simulations of one run apart.
+    pub fn record(&mut self, prefix: &str, report: &SimulationReport) {
+        if self.profile_id.is_none() {
+            self.profile_id = report.metadata.profile_id.clone();
+        }
+        self.partial |= report.metadata.partial;
+        for item in &report.items {
+            self.add_summary(timing_name(prefix, item.phase.as_deref(), &item.name()), Summary::from_samples(&[item.duration]));
+        }
+        self.add_summary(timing_name(prefix, None, "total"), Summary::from_samples(&[report.total]));
+        self.add_phases(prefix, report.phase_totals(), report.total);
+    }
+
+    /// Like `record`, with the mean of every item.
+    pub fn record_runs(&mut self, prefix: &str, runs: &RepeatedRuns) {
+        if self.profile_id.is_none() {
+            self.profile_id = runs.profile_id.clone();
+        }
+        self.partial |= runs.partial;
+        for item in &runs.items {
+            self.add_summary(timing_name(prefix, item.phase.as_deref(), &item.name()), item.summary);
+        }
+        self.add_summary(timing_name(prefix, None, "total"), runs.total);
+        self.add_phases(prefix, runs.phase_totals(), runs.total.mean);
+    }
+
+    fn add_phases(&mut self, prefix: &str, phases: Vec<(String, Duration)>, total: Duration) {
+        let phases = phases.into_iter().chain([("total".to_string(), total)]);
+        self.phases.extend(phases.map(|(phase, time)| (timing_name(prefix, None, &phase), time)));
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BaselineError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, BaselineError> {
+        let invalid = |message: &str| BaselineError::Invalid(message.to_string());
+        let root = toml::parse(text).map_err(|e| BaselineError::Invalid(e.to_string()))?;
+        let profile_id = match root.get("profile_id") {
+            None => None,
+            Some(Value::String(id)) => Some(id.clone()),
+            Some(_) => return Err(invalid("`profile_id` must be a string")),
+        };
+        let partial = match root.get("partial") {
+            None => false,
+            Some(&Value::Boolean(partial)) => partial,
+            Some(_) => return Err(invalid("`partial` must be a boolean")),
+        };
+        let timings = match root.get("timing") {
+            None => return Ok(Self { profile_id, partial, ..Self::new() }),
+            Some(Value::Array(timings)) => timings,
+            Some(_) => return Err(invalid("`timing` must be an array of tables ([[timing]])")),
+        };
+        let mut baseline = Self { profile_id, partial, ..Self::new() };
+        for timing in timings {
+            let timing = match timing {
+                Value::Table(timing) => timing,
+                _ => return Err(invalid("a timing is not a table")),
+            };
+            let name = match timing.get("name") {
+                Some(Value::String(name)) => name.clone(),
+                _ => return Err(invalid("every timing needs a string `name`")),
+            };
+            let ns = match timing.get("ns") {
+                Some(&Value::Float(ns)) if ns >= 0.0 => ns,
+                Some(&Value::Integer(ns)) if ns >= 0 => ns as f64,
+                _ => return Err(BaselineError::Invalid(format!("`{}` needs a non-negative `ns`", name))),
+            };
+            baseline.timings.push((name, Duration::from_secs_f64(ns / 1e9)));
+        }
+        Ok(baseline)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# timings in nanoseconds, saved by `pvss-sim --save-baseline`\n");
+        if let Some(id) = &self.profile_id {
+            writeln!(out, "profile_id = \"{}\"", id).unwrap();
+        }
+        if self.partial {
+            writeln!(out, "partial = true").unwrap();
+        }
+        for (name, duration) in &self.timings {
+            let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(out, "\n[[timing]]\nname = \"{}\"\nns = {:.1}", name, duration.as_secs_f64() * 1e9).unwrap();
+        }
+        out
+    }
+
+    /// Creates the file's directory if needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_toml())
+    }
+
+    /// Compares `current` against this baseline; timings that changed by more than
+    /// `threshold` (relative, e.g. 0.1 for 10%) are flagged.
+    pub fn compare(&self, current: &Baseline, threshold: f64) -> BaselineComparison {
+        BaselineComparison {
+            threshold,
+            profile_ids: (self.profile_id.clone(), current.profile_id.clone()),
+            timings: current.timings.iter()
+                .map(|(name, duration)| TimingChange { name: name.clone(), baseline: self.get(name), current: *duration })
+                .collect(),
+            missing: self.timings.iter()
+                .filter(|(name, _)| current.get(name).is_none())
+                .map(|(name, _)| name.clone())
+                .collect(),
+        }
+    }
+}
+
+fn timing_name(prefix: &str, phase: Option<&str>, name: &str) -> String {
+    let name = match phase {
+        Some(phase) => format!("{} / {}", phase, name),
+        None => name.to_string(),
+    };
+    if prefix.is_empty() { name } else { format!("{}: {}", prefix, name) }
+}
+
+#[derive(Clone, Debug)]
+pub struct TimingChange {
+    pub name: String,
+    /// `None` if the baseline has no timing of this name
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+}
+
+impl TimingChange {
+    /// Relative change, e.g. 0.25 for 25% slower than the baseline.
+    pub fn change(&self) -> Option<f64> {
+        self.baseline.map(|baseline| self.current.as_secs_f64() / baseline.as_secs_f64() - 1.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BaselineComparison {
+    pub threshold: f64,
+    /// of the baseline and of the current run
+    pub profile_ids: (Option<String>, Option<String>),
+    pub timings: Vec<TimingChange>,
+    /// timings of the baseline the current run doesn't have
+    pub missing: Vec<String>,
+}
+
+impl BaselineComparison {
+    /// Timings slower than the baseline by more than the threshold.
+    pub fn regressions(&self) -> impl Iterator<Item = &TimingChange> {
+        self.timings.iter().filter(|timing| timing.change().is_some_and(|change| change > self.threshold))
+    }
+}
+
+impl fmt::Display for BaselineComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "compared with the baseline (threshold {:.0}%):", self.threshold * 100.0)?;
+        if let (Some(baseline), Some(current)) = &self.profile_ids {
+            if baseline != current {
+                writeln!(f, "  warning: the baseline comes from machine profile {}, this run from {}", baseline, current)?;
+            }
+        }
+        for timing in &self.timings {
+            let (Some(baseline), Some(change)) = (timing.baseline, timing.change()) else {
+                writeln!(f, "  {}: {:?} (not in the baseline)", timing.name, timing.current)?;
+                continue;
+            };
+            let flag = if change > self.threshold {
+                "  REGRESSION"
+            } else if change < -self.threshold {
+                "  improvement"
+            } else {
+                ""
+            };
+            writeln!(f, "  {}: {:?} -> {:?} ({:+.1}%){}", timing.name, baseline, timing.current, change * 100.0, flag)?;
+        }
+        for name in &self.missing {
+            writeln!(f, "  {}: missing from this run", name)?;
+        }
+        writeln!(f, "{} regression(s)", self.regressions().count())
+    }
+}
@@ -0,0 +1,225 @@
+//! What precomputation buys a verifier under a memory budget: fixed-base tables for the bases
+//! of its exps, of any window size, and prepared G2 points for the G2 inputs of its pairings.
+//!
+//! Every base of the workload's G1 and G2 exps and every G2 input of its pairings is taken to
+//! be the same from one run to the next (keys, generators), so the best time found is a lower
+//! bound: inputs that change with every transcript can't be precomputed. The workload is timed
+//! as is, then plain exps, fixed-base exps with tables of every window size and the preparation
+//! of a G2 point on their own. For every choice of a window size per group (or no tables), the
+//! budget goes to tables and prepared points in the order of the time they save per byte, and
+//! the choice that saves the most wins.
+
+use crate::clock::Instant;
+use crate::communication::format_bytes;
+use crate::curve_ops::BatchToAffine as _;
+use crate::fixed_base::FixedBaseTable;
+use crate::group_ops_simulation::{GroupOpsSimulation, WarmUp};
+use crate::stats::Summary;
+use crate::workload::{OpKind, Workload};
+
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::Group as _;
+use rand::RngCore;
+use std::fmt;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::Duration;
+
+
+/// The window sizes tried for the tables.
+pub const WINDOW_BITS : std::ops::RangeInclusive<usize> = 2..=12;
+// blst's G2Prepared holds one line (an Fp6 element, 288 bytes) per step of the Miller loop: 63
+// doublings and 5 additions for BLS12-381
+const G2_PREPARED_BYTES : usize = 68 * 288;
+// ops timed per sample of the per-op times
+const SAMPLE_OPS : usize = 16;
+
+/// Bytes of one base's table with `window_bits`-bit windows, of affine points of `point_bytes`.
+pub fn table_bytes(window_bits: usize, point_bytes: usize) -> usize {
+    (Scalar::NUM_BITS as usize).div_ceil(window_bits) * (point_bytes << window_bits)
+}
+
+/// The inputs of a workload that precomputation could serve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Precomputable {
+    pub g1_exps: usize,
+    pub g2_exps: usize,
+    /// the G2 inputs of all pairings
+    pub g2_inputs: usize,
+}
+
+impl Precomputable {
+    pub fn of(workload: &Workload) -> Self {
+        let mut inputs = Self::default();
+        for op in &workload.ops {
+            match op.kind {
+                OpKind::G1Exps => inputs.g1_exps += op.num,
+                OpKind::G2Exps => inputs.g2_exps += op.num,
+                OpKind::Pairings | OpKind::PairingsConverting { .. } | OpKind::MultiPairings | OpKind::MultiPairingsConverting { .. } =>
+                    inputs.g2_inputs += op.num * op.size,
+                OpKind::PairingEqualityChecks { rhs_size } => inputs.g2_inputs += op.num * (op.size + rhs_size),
+                _ => {}
+            }
+        }
+        inputs
+    }
+}
+
+/// Median times of single operations.
+#[derive(Clone, Debug)]
+pub struct Timings {
+    pub g1_exp: Duration,
+    pub g2_exp: Duration,
+    pub g2_preparation: Duration,
+    /// (window bits, G1 fixed-base exp, G2 fixed-base exp), for every size of `WINDOW_BITS`
+    pub fixed_base_exps: Vec<(usize, Duration, Duration)>,
+}
+
+/// How much of the budget goes to what.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Strategy {
+    /// (window bits, number of bases with a table)
+    pub g1_tables: Option<(usize, usize)>,
+    pub g2_tables: Option<(usize, usize)>,
+    pub prepared_g2: usize,
+    pub bytes: usize,
+    pub saved: Duration,
+}
+
+impl Strategy {
+    /// The best strategy with at most `budget` bytes: for every pair of window sizes, the
+    /// precomputations that save time, cheapest per nanosecond saved first, as many of each as
+    /// fit.
+    pub fn best(inputs: &Precomputable, timings: &Timings, budget: usize) -> Self {
+        let g1_windows = std::iter::once(None).chain(timings.fixed_base_exps.iter().map(|&(bits, g1, _)| Some((bits, g1))));
+        let mut best = Strategy::default();
+        for g1_window in g1_windows {
+            let g2_windows = std::iter::once(None).chain(timings.fixed_base_exps.iter().map(|&(bits, _, g2)| Some((bits, g2))));
+            for g2_window in g2_windows {
+                let strategy = Self::fill(inputs, timings, budget, g1_window, g2_window);
+                if strategy.saved > best.saved {
+                    best = strategy;
+                }
+            }
+        }
+        best
+    }
+
+    // the budget spent on tables of the given windows (bits, time of a fixed-base exp) and on
+    // prepared points
+    fn fill(inputs: &Precomputable, timings: &Timings, budget: usize, g1_window: Option<(usize, Duration)>, g2_window: Option<(usize, Duration)>) -> Self {
+        // (which, bytes per unit, time saved per unit, units)
+        let mut options = Vec::new();
+        if let Some((bits, time)) = g1_window.filter(|&(_, time)| time < timings.g1_exp) {
+            options.push((0, table_bytes(bits, size_of::<G1Affine>()), timings.g1_exp - time, inputs.g1_exps));
+        }
+        if let Some((bits, time)) = g2_window.filter(|&(_, time)| time < timings.g2_exp) {
+            options.push((1, table_bytes(bits, size_of::<G2Affine>()), timings.g2_exp - time, inputs.g2_exps));
+        }
+        options.push((2, G2_PREPARED_BYTES, timings.g2_preparation, inputs.g2_inputs));
+        options.sort_by(|a, b| (b.2.as_secs_f64() / b.1 as f64).total_cmp(&(a.2.as_secs_f64() / a.1 as f64)));
+
+        let mut strategy = Strategy::default();
+        for (which, bytes, saved, units) in options {
+            let units = units.min((budget - strategy.bytes) / bytes);
+            strategy.bytes += units * bytes;
+            strategy.saved += saved * units as u32;
+            match which {
+                0 => strategy.g1_tables = g1_window.map(|(bits, _)| (bits, units)).filter(|_| units > 0),
+                1 => strategy.g2_tables = g2_window.map(|(bits, _)| (bits, units)).filter(|_| units > 0),
+                _ => strategy.prepared_g2 = units,
+            }
+        }
+        strategy
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (group, tables) in [("G1", self.g1_tables), ("G2", self.g2_tables)] {
+            if let Some((bits, bases)) = tables {
+                parts.push(format!("{} tables with {}-bit windows for {} bases", group, bits, bases));
+            }
+        }
+        if self.prepared_g2 > 0 {
+            parts.push(format!("{} prepared G2 points", self.prepared_g2));
+        }
+        match parts.is_empty() {
+            true => write!(f, "no precomputation"),
+            false => write!(f, "{}, {}", parts.join(", "), format_bytes(self.bytes)),
+        }
+    }
+}
+
+/// The result of `explore`.
+#[derive(Clone, Debug)]
+pub struct BudgetExploration {
+    pub budget: usize,
+    pub inputs: Precomputable,
+    pub timings: Timings,
+    /// the workload's median time without precomputation
+    pub baseline: Duration,
+    pub best: Strategy,
+}
+
+/// Times `workload` and the operations precomputation replaces, taking the median of
+/// `repetitions` runs each, and finds the best `Strategy` within `budget` bytes.
+pub fn explore(workload: &Workload, budget: usize, repetitions: usize, mut rng: impl RngCore) -> BudgetExploration {
+    assert!(repetitions > 0, "need at least one repetition");
+    let median = |f: &dyn Fn()| {
+        let samples : Vec<Duration> = (0..repetitions)
+            .map(|_| {
+                let start_time = Instant::now();
+                f();
+                start_time.elapsed()
+            })
+            .collect();
+        Summary::from_samples(&samples).median / SAMPLE_OPS as u32
+    };
+
+    let mut sim = GroupOpsSimulation::new(&mut rng);
+    sim.workload(workload).warm_up(WarmUp::Once);
+    let baseline = sim.simulate_n(repetitions).total.median;
+
+    let g1_base = G1Projective::random(&mut rng);
+    let g2_base = G2Projective::random(&mut rng);
+    let scalars : Vec<Scalar> = (0..SAMPLE_OPS).map(|_| Scalar::random(&mut rng)).collect();
+    let g1_exp = median(&|| { black_box::<Vec<_>>(scalars.iter().map(|s| g1_base * s).collect()); });
+    let g2_exp = median(&|| { black_box::<Vec<_>>(scalars.iter().map(|s| g2_base * s).collect()); });
+    let g2_points = G2Projective::batch_to_affine(&(0..SAMPLE_OPS).map(|_| G2Projective::random(&mut rng)).collect::<Vec<_>>());
+    let g2_preparation = median(&|| { black_box::<Vec<_>>(g2_points.iter().map(|&p| G2Prepared::from(p)).collect()); });
+    let fixed_base_exps = WINDOW_BITS
+        .map(|bits| {
+            let (g1_table, g2_table) = (FixedBaseTable::new(g1_base, bits), FixedBaseTable::new(g2_base, bits));
+            let g1 = median(&|| { black_box::<Vec<_>>(scalars.iter().map(|s| g1_table.mul(s)).collect()); });
+            let g2 = median(&|| { black_box::<Vec<_>>(scalars.iter().map(|s| g2_table.mul(s)).collect()); });
+            (bits, g1, g2)
+        })
+        .collect();
+
+    let inputs = Precomputable::of(workload);
+    let timings = Timings { g1_exp, g2_exp, g2_preparation, fixed_base_exps };
+    let best = Strategy::best(&inputs, &timings, budget);
+    BudgetExploration { budget, inputs, timings, baseline, best }
+}
+
+impl fmt::Display for BudgetExploration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs = &self.inputs;
+        writeln!(f, "precomputable: {} G1 exps, {} G2 exps, {} G2 pairing inputs", inputs.g1_exps, inputs.g2_exps, inputs.g2_inputs)?;
+        writeln!(f, "{:>6}  {:>10}  {:>12}  {:>10}  {:>12}", "window", "G1 table", "G1 exp", "G2 table", "G2 exp")?;
+        for &(bits, g1, g2) in &self.timings.fixed_base_exps {
+            writeln!(f, "{:>6}  {:>10}  {:>12}  {:>10}  {:>12}", bits,
+                format_bytes(table_bytes(bits, size_of::<G1Affine>())), format!("{:.3?}", g1),
+                format_bytes(table_bytes(bits, size_of::<G2Affine>())), format!("{:.3?}", g2))?;
+        }
+        writeln!(f, "{:>6}  {:>10}  {:>12}  {:>10}  {:>12}", "none", "", format!("{:.3?}", self.timings.g1_exp), "", format!("{:.3?}", self.timings.g2_exp))?;
+        writeln!(f, "G2 preparation: {:.3?}, {} per point", self.timings.g2_preparation, format_bytes(G2_PREPARED_BYTES))?;
+        writeln!(f, "without precomputation: {:.3?}", self.baseline)?;
+        let best = self.baseline.saturating_sub(self.best.saved);
+        let saved = 100.0 * self.best.saved.as_secs_f64() / self.baseline.as_secs_f64();
+        writeln!(f, "best within {}: {:.3?} ({:.1}% less), {}", format_bytes(self.budget), best, saved, self.best)
+    }
+}
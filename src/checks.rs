@@ -0,0 +1,72 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+use crate::serialization::PointEncoding;
+
+use blstrs::{G1Affine, G2Affine};
+use rand::RngCore;
+use std::hint::black_box;
+
+
+// points that arrived through an unchecked deserialization path still need to be validated
+trait SubgroupCheck : PointEncoding {
+    fn is_valid(&self) -> bool;
+}
+
+impl SubgroupCheck for G1Affine {
+    fn is_valid(&self) -> bool {
+        bool::from(self.is_on_curve() & self.is_torsion_free())
+    }
+}
+
+impl SubgroupCheck for G2Affine {
+    fn is_valid(&self) -> bool {
+        bool::from(self.is_on_curve() & self.is_torsion_free())
+    }
+}
+
+struct SubgroupChecks<T> {
+    points: Vec<T>,
+}
+
+impl<T> SubgroupChecks<T>
+where T : SubgroupCheck {
+    fn new(rng: &mut impl RngCore, num: usize) -> Self {
+        Self { points: (0..num).map(|_| T::random_element(rng)).collect() }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for SubgroupChecks<T>
+where T : SubgroupCheck + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} subgroup checks: {}", T::NAME, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.points.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.points.iter().map(T::is_valid).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.points.len(), |i| self.points[i].is_valid()));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// On-curve plus subgroup-membership checks of affine G1 points.
+    pub fn g1_subgroup_checks(&mut self, num: usize) -> &mut Self {
+        let item = SubgroupChecks::<G1Affine>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// On-curve plus subgroup-membership checks of affine G2 points.
+    pub fn g2_subgroup_checks(&mut self, num: usize) -> &mut Self {
+        let item = SubgroupChecks::<G2Affine>::new(self.rng(), num);
+        self.push_item(item)
+    }
+}
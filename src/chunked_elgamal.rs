@@ -0,0 +1,220 @@
+//! Chunked exponential ElGamal in G1, as in Groth21-style DKGs: every share is split into `l`
+//! chunks of `b` bits, and chunk `j` of receiver `i`'s share is encrypted in the exponent,
+//! `C_ij = pk_i^{r_j} g^{s_ij}`, under randomness `R_j = g^{r_j}` shared by all receivers.
+//!
+//! Decrypting a chunk gives `g^{s_ij}` only, so the receiver recovers `s_ij` with a baby-step
+//! giant-step discrete logarithm over the `2^b` possible values: a table of `2^{b/2}` baby steps
+//! built once per key, and up to `2^{b/2}` giant steps per chunk. Small chunks make decryption
+//! cheap but mean more chunks, so more ciphertexts to compute, send and check; the dealer's
+//! proof that all chunks are small is verified with multi-exps over all `n * l` chunks.
+
+use crate::curve_ops::BatchToAffine;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::Group as _;
+use rand::RngCore;
+use std::collections::HashMap;
+use std::hint::black_box;
+
+
+/// Chunk sizes above this need baby-step tables too large to build.
+pub const MAX_CHUNK_BITS : usize = 40;
+
+// giant steps are normalized to affine this many at a time, so looking them up costs one
+// inversion per batch
+const GIANT_STEP_BATCH : usize = 64;
+
+/// Baby steps `g^0, ..., g^{m-1}` for discrete logarithms of up to `bits` bits.
+pub struct BabySteps {
+    bits: usize,
+    table: HashMap<[u8; 48], u64>,
+}
+
+impl BabySteps {
+    pub fn new(bits: usize) -> Self {
+        assert!((1..=MAX_CHUNK_BITS).contains(&bits), "chunks must have between 1 and {} bits", MAX_CHUNK_BITS);
+        let steps : Vec<G1Projective> = std::iter::successors(Some(G1Projective::identity()), |p| Some(p + G1Projective::generator()))
+            .take(1 << bits.div_ceil(2))
+            .collect();
+        let table = G1Projective::batch_to_affine(&steps).iter().zip(0..).map(|(p, j)| (p.to_compressed(), j)).collect();
+        Self { bits, table }
+    }
+
+    /// Number of baby steps, `2^{⌈bits/2⌉}`.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// `x < 2^bits` with `h = g^x`, if there is one.
+    pub fn log(&self, h: &G1Projective) -> Option<u64> {
+        let m = self.table.len() as u64;
+        let giant_step = -(G1Projective::generator() * Scalar::from(m));
+        let giant_steps = (1u64 << self.bits).div_ceil(m);
+        let mut current = *h;
+        let mut batch = Vec::with_capacity(GIANT_STEP_BATCH);
+        for start in (0..giant_steps).step_by(GIANT_STEP_BATCH) {
+            batch.clear();
+            for _ in start..(start + GIANT_STEP_BATCH as u64).min(giant_steps) {
+                batch.push(current);
+                current += giant_step;
+            }
+            let found = G1Projective::batch_to_affine(&batch).iter().zip(start..)
+                .find_map(|(p, k)| self.table.get(&p.to_compressed()).map(|j| k * m + j));
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+fn chunk_randomness(r: &[Scalar]) -> Vec<G1Projective> {
+    r.iter().map(|r| G1Projective::generator() * r).collect()
+}
+
+struct Encryptions {
+    num: usize,
+    keys: Vec<G1Projective>,
+    // the chunks of every receiver's share, receiver by receiver
+    chunks: Vec<Scalar>,
+    r: Vec<Scalar>,
+}
+
+impl Encryptions {
+    fn encrypt(&self) -> (Vec<G1Projective>, Vec<G1Projective>) {
+        let l = self.r.len();
+        let ciphertexts = self.chunks.iter().enumerate()
+            .map(|(k, chunk)| G1Projective::multi_exp(&[self.keys[k / l], G1Projective::generator()], &[self.r[k % l], *chunk]))
+            .collect();
+        (chunk_randomness(&self.r), ciphertexts)
+    }
+}
+
+impl GroupOpsSimulationItem for Encryptions {
+    fn description(&self) -> String {
+        format!("chunked ElGamal encryptions: {} to {} receivers of {} chunks", self.num, self.keys.len(), self.r.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.chunks.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.encrypt());
+        }
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.encrypt());
+    }
+}
+
+struct TableBuilds {
+    bits: usize,
+}
+
+impl GroupOpsSimulationItem for TableBuilds {
+    fn description(&self) -> String {
+        format!("baby-step tables ({}-bit chunks): 1 of {} points", self.bits, 1u64 << self.bits.div_ceil(2))
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(1, 1 << self.bits.div_ceil(2))
+    }
+
+    fn simulate(&self) {
+        black_box(BabySteps::new(self.bits));
+    }
+}
+
+// one receiver decrypting its chunks of `num` dealings
+struct Decryptions {
+    num: usize,
+    table: BabySteps,
+    sk: Scalar,
+    randomness: Vec<G1Projective>,
+    ciphertexts: Vec<G1Projective>,
+}
+
+impl Decryptions {
+    fn decrypt(&self) -> Vec<u64> {
+        self.randomness.iter().zip(&self.ciphertexts)
+            .map(|(randomness, ciphertext)| self.table.log(&(ciphertext - randomness * self.sk)).expect("chunks are small"))
+            .collect()
+    }
+}
+
+impl GroupOpsSimulationItem for Decryptions {
+    fn description(&self) -> String {
+        format!("chunked ElGamal decryptions ({}-bit chunks, baby-step giant-step): {} of {} chunks", self.table.bits, self.num, self.ciphertexts.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.ciphertexts.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.decrypt());
+        }
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.decrypt());
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` dealings of chunked ElGamal to `receivers` keys, with `chunks` chunks of
+    /// `chunk_bits` bits per share (`⌈255 / chunk_bits⌉` cover a whole scalar), as four items:
+    /// the dealer's encryptions, the verification of the proof that every chunk is small (one
+    /// multi-exp over all chunks per repetition, `proof_repetitions` in all), one receiver's
+    /// baby-step table, and that receiver decrypting its chunks of every dealing. Chunk values
+    /// are uniform, so decryption takes half the giant steps on average.
+    pub fn chunked_elgamal(&mut self, num: usize, receivers: usize, chunk_bits: usize, chunks: usize, proof_repetitions: usize) -> &mut Self {
+        assert!((1..=MAX_CHUNK_BITS).contains(&chunk_bits), "chunks must have between 1 and {} bits", MAX_CHUNK_BITS);
+        let chunk = |rng: &mut dyn RngCore| rng.next_u64() & ((1u64 << chunk_bits) - 1);
+        let keys = self.rng().points(receivers);
+        let shares : Vec<Scalar> = (0..receivers * chunks).map(|_| Scalar::from(chunk(self.rng()))).collect();
+        let r : Vec<Scalar> = (0..chunks).map(|_| Scalar::random(&mut *self.rng())).collect();
+        let encryptions = Encryptions { num, keys, chunks: shares, r: r.clone() };
+
+        let sk = Scalar::random(&mut *self.rng());
+        let pk = G1Projective::generator() * sk;
+        let randomness = chunk_randomness(&r);
+        let ciphertexts = r.iter()
+            .map(|r| pk * r + G1Projective::generator() * Scalar::from(chunk(self.rng())))
+            .collect();
+        let decryptions = Decryptions { num, table: BabySteps::new(chunk_bits), sk, randomness, ciphertexts };
+        self.group(|sim| {
+            sim.push_item(encryptions)
+                .g1_multi_exps(num * proof_repetitions, receivers * chunks + 1)
+                .push_item(TableBuilds { bits: chunk_bits })
+                .push_item(decryptions);
+        })
+    }
+}
+
+/// Chunks of `chunk_bits` bits that cover a scalar.
+pub fn chunks_per_share(chunk_bits: usize) -> usize {
+    (Scalar::NUM_BITS as usize).div_ceil(chunk_bits)
+}
@@ -0,0 +1,714 @@
+use blstrs_pvss_simulation::async_round::Delays;
+use blstrs_pvss_simulation::config;
+use blstrs_pvss_simulation::cost_model::CostProfile;
+use blstrs_pvss_simulation::group_ops_simulation::{ExecutionOrder, Setup, WarmUp};
+use blstrs_pvss_simulation::history::{self, Filter, History};
+use blstrs_pvss_simulation::network::NetworkModel;
+use blstrs_pvss_simulation::parallel::available_threads;
+use blstrs_pvss_simulation::progress::Reporter;
+use blstrs_pvss_simulation::render::{Format, Style, Unit};
+use blstrs_pvss_simulation::schedule::Schedule;
+use blstrs_pvss_simulation::sigma::Sigma;
+use blstrs_pvss_simulation::test_vectors::TestVectors;
+use blstrs_pvss_simulation::weighted::Weights;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "\
+usage: pvss-sim [options]              simulate Groth's PVSS with the default parameters
+       pvss-sim run FILE [options]     simulate the workload described in a TOML file (or JSON,
+                                       ending in .json), with the options it sets unless given
+       pvss-sim list                   list the presets with their parameters
+       pvss-sim sweep PRESET NAME=VALUES... [options]
+                                       simulate a preset for every combination of parameter
+                                       values, e.g. `sweep groth n=128..=8192:*2 k=16 t=2*n/3 l=16`
+                                       (VALUES: comma-separated expressions or ranges LO..=HI,
+                                       LO..=HI:+STEP, LO..=HI:*FACTOR)
+       pvss-sim pvss N T [options]     run the actual PVSS for N parties and threshold T and
+                                       compare its measured time with the simulated op counts
+       pvss-sim dkg SCHEME NAME=VALUE... [options]
+                                       simulate one party's work in a DKG where all n parties
+                                       deal a transcript of the PVSS SCHEME (pvss, groth,
+                                       groth21, scrape, schoenmakers), e.g. `dkg pvss n=64 t=42`,
+                                       and the network-wide total; values as for sweep; with
+                                       f=VALUES, also the complaints of f faulty dealers
+       pvss-sim compare PRESET NAME=VALUE... vs PRESET NAME=VALUE... [options]
+                                       simulate two configurations and print their per-phase
+                                       times side by side with the speedup of the second, e.g.
+                                       `compare groth n=1024 k=16 t=682 l=16 vs scrape n=1024 t=682`
+       pvss-sim weighted PRESET WEIGHTS NAME=VALUE... [options]
+                                       simulate a preset with stake-weighted thresholds, party i
+                                       holding w_i shares (WEIGHTS: W1,W2,..., equal:N:W or
+                                       zipf:N:W), with n set to the number of shares, e.g.
+                                       `weighted pvss 3,1,1,2 t=4`, and report the heaviest
+                                       party's and the total cost
+       pvss-sim async PRESET DELAY[,JITTER] NAME=VALUE... [options]
+                                       simulate a preset's dealing and verification, then every
+                                       party dealing and verifying the others' transcripts as
+                                       they arrive, each message delayed by DELAY plus up to
+                                       JITTER, e.g. `async scrape 50ms,100ms n=64 t=42`, and
+                                       print the round's timeline; values as for sweep
+       pvss-sim reconstruct WEIGHTS T SAMPLES [options]
+                                       deal an actual PVSS to the shares of WEIGHTS (as for
+                                       weighted) with threshold T, and time its reconstruction
+                                       from SAMPLES random sets of parties holding T + 1 shares
+       pvss-sim backends PRESET NAME=VALUE... [options]
+                                       run a preset's exps, multi-exps and pairings on every built-in
+                                       curve backend and compare their times op by op
+       pvss-sim msm-threads SIZE [MAX_THREADS] [--reps N]
+                                       time a G1 multi-exp of SIZE bases on 1, 2, 4, ... up to
+                                       MAX_THREADS threads (default: all cores), reporting the
+                                       median of N runs each, the speedup and the efficiency
+       pvss-sim tune-msm SIZE... [--reps N]
+                                       time a G1 Pippenger multi-exp of each SIZE at window sizes
+                                       around the rule of thumb, with signed and unsigned buckets,
+                                       and report the fastest
+       pvss-sim pipeline PROTOCOL SIZE [INVALID_PERCENT] [--reps N]
+                                       verify SIZE schnorr or dleq proofs, INVALID_PERCENT
+                                       percent of them invalid (default: 1), as they arrive in
+                                       batches of 1, 2, 4, ... up to all SIZE at the end, and
+                                       report the time to the first reject and the total time
+                                       (median of N runs) per batch size
+       pvss-sim budget PRESET MIB NAME=VALUE... [--phase PHASE] [--reps N]
+                                       find the fastest a preset's verifier phase (or PHASE) gets
+                                       with MIB mebibytes of precomputation: fixed-base tables
+                                       of any window size for the bases of its exps and prepared
+                                       G2 points for its pairings, all taken as fixed inputs;
+                                       values as for sweep
+       pvss-sim calibrate [--output FILE]
+                                       measure per-operation costs on this machine and save them
+                                       as a profile (default: profile.toml)
+       pvss-sim profile [--output FILE]
+                                       same as calibrate, also recording the CPU model, core
+                                       count, frequency governor and blstrs/blst versions, as
+                                       JSON (default: profile.json); reports are stamped with
+                                       the machine's profile ID
+       pvss-sim predict PROFILE PRESET NAME=VALUES...
+                                       estimate a preset's runtime from a profile, without running
+                                       it; takes the same parameter values as sweep
+       pvss-sim project PROFILE PRESET NAME=VALUE... --network BW,RTT[,p2p]
+                                       project the wall-clock time of one run of a preset for a
+                                       committee of n parties (n one of the values): every
+                                       phase's compute estimated from the profile, then sending
+                                       its messages to the other parties, e.g. `project
+                                       profile.toml pvss n=1024 t=683 --network 100Mbps,50ms`
+       pvss-sim merge PROFILE PROFILE... [--unit g1-exps]
+                                       merge the profiles of several machines into one table of
+                                       per-op costs, each machine's relative to the first's, or
+                                       with --unit g1-exps in G1 exps of its own machine
+       pvss-sim ops PRESET NAME=VALUES... [--output FILE]
+                                       print a preset's op counts per phase as JSON, the ops of
+                                       each kind summed up with their sizes, for every
+                                       combination of parameter values as for sweep
+       pvss-sim query [PRESET] [NAME=VALUE...] [--profile ID] [--since DATE] [--until DATE]
+                                       list the points that sweep, compare, weighted and async
+                                       recorded in the history, filtered by preset, parameter
+                                       values, profile ID (or a prefix of it) and date
+                                       (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS, UTC, both included)
+
+options:
+  --reps N             run every simulation N times and report mean/stddev/median/min/max per item
+  --warm-up            run every item once, untimed, before measuring
+  --warm-up-time MS    cycle through the items, untimed, for at least MS milliseconds before measuring
+  --seed SEED          derive all bases and scalars from SEED (default: random, printed at startup)
+  --fast-setup         derive bases from small multiples of the generator instead of hashing
+                       each to the curve, which makes building large simulations cheap
+  --cold-start         also time the first operation of every kind on its own, before any
+                       warm-up, as the latency of a freshly started process
+  --order ORDER        run the operations of the items grouped (default: item by item),
+                       interleaved (one operation of every item in turn) or shuffled
+  --record-schedule FILE
+                       save the order the items and operations ran in, and the seed, to FILE
+                       (with the default command and run)
+  --replay FILE        run the schedule saved in FILE, with its seed, instead of --order's
+  --validate           before timing, check every multi-exp and multi-pairing against a naive
+                       computation on the same inputs, and fail on any mismatch
+  --test-vectors FILE  save the results every exp, multi-exp and pairing computes, and the seed,
+                       to FILE, untimed (with the default command and run)
+  --check-vectors FILE compute the results again with the seed saved in FILE (e.g. on another
+                       blstrs version) and fail unless they all match it byte for byte
+  --instructions       instead of timing the items, count the instructions and cache misses of
+                       one run of each under Callgrind (with the default command and run; needs
+                       valgrind and a build with the instruction-counts feature)
+  --energy             also measure the energy of every item from the processor's counters (RAPL
+                       on Linux, powermetrics on macOS) and report joules per item and phase
+                       (needs a build with the energy-measurement feature, and usually root)
+  --dry-run            print the operations (counts, sizes, estimated input bytes) and messages
+                       the command would simulate, without running anything
+  --estimate PROFILE   also estimate the time of every simulated workload from the per-op costs
+                       in PROFILE (from calibrate or profile, or written by hand), and print
+                       the estimate after the measurement; with --dry-run, instead of it
+  --throughput SECS    instead of timing one run, run every item for SECS seconds and report
+                       the operations completed per second (with the default command, run
+                       and dkg; not with --reps or baselines)
+  --threads N          spread the operations of each item over N threads, reporting CPU time too
+  --parallel           same as --threads with the number of available cores
+  --network BW,RTT[,p2p]
+                       with pvss and project, estimate the round time on a network of this upload
+                       bandwidth and round-trip time, e.g. 100Mbps,50ms (broadcast by default)
+  --format FORMAT      print the results of run, sweep, compare, predict and query as a table of
+                       phase times in FORMAT: text, markdown or latex (a tabular)
+  --unit UNIT          unit of the times in such tables: ns, us, ms, s, auto (default) for
+                       each time in its own, or g1-exps for multiples of one G1 exp's time
+                       (measured first; with predict, or --estimate and --dry-run, from the profile),
+                       cycles@FREQUENCY for clock cycles at e.g. 3.5GHz, or cycles at the
+                       frequency of the time-stamp counter (counted first, on x86-64)
+  --digits N           significant digits of the times in such tables (default: 3)
+  --no-totals          leave the total row (or column, in sweeps) out of such tables
+  --percent            follow every time column of such tables with each time's percentage of
+                       its total
+  --plot FILE          with sweep or predict, also draw the results as an SVG: time against the
+                       first parameter that varies, and stacked bars of the phases
+  --crossover A,B      with sweep or predict, also print the first parameter combination in
+                       which phase B takes less time than phase A
+  --phase PHASE        the phase budget explores (default: Verifier)
+  --fit NAME           with sweep or predict, also fit every phase's time against parameter
+                       NAME to a·n + b, a·n log n + b and a·n² + b, and print the best fit
+  --static-g2          with presets, prepare the G2 inputs of pairings that they mark as the same
+                       in every run (committee keys, generators) once, in a phase `Static G2`,
+                       and reuse them in every multi-pairing; sweep and predict also report
+                       what preparing them in every multi-pairing would add per run
+  --history DIR        record the points of presets that runs measure in DIR, and query it
+                       (default: history)
+  --no-history         don't record the points measured
+  --save-baseline NAME store the timings of every item in baselines/NAME.toml
+  --baseline NAME      compare the timings with those saved under NAME, flagging changes beyond
+                       the threshold, and exit with status 1 on any regression
+  --threshold PCT      relative change that --baseline flags, in percent (default: 10)
+  --export-criterion DIR
+                       also write every timing as a Criterion benchmark under DIR (e.g.
+                       target/criterion, for critcmp), as baseline NAME of --save-baseline or
+                       `new`
+  --export-bencher FILE
+                       also write every timing to FILE as a line of `cargo bench` output
+  --export-latex FILE  also write the time of every phase and total, or with calibrate and
+                       profile every per-op cost, to FILE as a LaTeX macro, e.g.
+                       \\newcommand{\\GOneExpTime}{142~$\\mu$s}, for \\input in a paper (times as
+                       --unit and --digits have them)
+  --pin CORES          run on these cores only, e.g. 2 or 0,2-3 (Linux), each thread of --threads
+                       pinned to one of them in turn
+  --strict             refuse to measure, rather than warn, when the frequency governor of the
+                       cores isn't `performance` or turbo boost is on
+  -q, --quiet          don't report progress (the running item, elapsed time, ETA) on stderr
+  --metrics FILE       keep FILE up to date with the progress and the times of the items run so
+                       far, in the Prometheus text format (e.g. for a node exporter's textfile
+                       collector), rewritten as every item starts
+  -h, --help           print this message";
+
+pub enum Command {
+    Groth,
+    Run(PathBuf),
+    Sweep { preset: String, axes: Vec<String> },
+    Pvss { n: usize, t: usize },
+    Dkg { scheme: String, axes: Vec<String> },
+    Weighted { preset: String, weights: Weights, axes: Vec<String> },
+    Async { preset: String, delays: Delays, axes: Vec<String> },
+    Reconstruct { weights: Weights, t: usize, samples: usize },
+    /// presets and their parameters, before and after `vs`
+    Compare { a: (String, Vec<String>), b: (String, Vec<String>) },
+    Backends { preset: String, axes: Vec<String> },
+    MsmThreads { size: usize, max_threads: usize },
+    TuneMsm { sizes: Vec<usize> },
+    Pipeline { protocol: Sigma, size: usize, invalid_percent: usize },
+    /// the budget in bytes
+    Budget { preset: String, budget: usize, axes: Vec<String> },
+    Calibrate,
+    Profile,
+    Predict { profile: PathBuf, preset: String, axes: Vec<String> },
+    Project { profile: PathBuf, preset: String, axes: Vec<String> },
+    Ops { preset: String, axes: Vec<String> },
+    Query(Filter),
+    List,
+    Merge(Vec<PathBuf>),
+}
+
+pub struct Options {
+    pub command: Command,
+    pub reps: usize,
+    pub warm_up: WarmUp,
+    pub seed: Option<u64>,
+    pub setup: Setup,
+    pub order: ExecutionOrder,
+    pub record_schedule: Option<PathBuf>,
+    pub replay: Option<Schedule>,
+    pub test_vectors: Option<PathBuf>,
+    /// the vectors of --check-vectors
+    pub check_vectors: Option<TestVectors>,
+    pub cold_start: bool,
+    pub instructions: bool,
+    pub energy: bool,
+    pub dry_run: bool,
+    pub validate: bool,
+    /// --throughput: how long every item runs
+    pub throughput: Option<Duration>,
+    /// the cost table of --estimate
+    pub estimate: Option<CostProfile>,
+    pub threads: usize,
+    pub output: Option<PathBuf>,
+    pub network: Option<NetworkModel>,
+    pub quiet: bool,
+    /// --metrics, writing its file
+    pub metrics: Option<Reporter>,
+    /// where runs record their points, `None` with --no-history
+    pub history: Option<PathBuf>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub export_criterion: Option<PathBuf>,
+    pub export_bencher: Option<PathBuf>,
+    pub export_latex: Option<PathBuf>,
+    /// relative, e.g. 0.1 for 10%
+    pub threshold: f64,
+    /// set by any of --format, --unit, --digits, --no-totals and --percent
+    pub table: Option<Style>,
+    /// --unit g1-exps
+    pub g1_exp_units: bool,
+    /// --unit cycles, at the frequency of the time-stamp counter
+    pub cycle_units: bool,
+    pub plot: Option<PathBuf>,
+    /// phases whose times --crossover compares
+    pub crossover: Option<(String, String)>,
+    /// --phase, the phase budget explores
+    pub phase: String,
+    /// the parameter --fit fits the times against
+    pub fit: Option<String>,
+    pub static_g2: bool,
+    /// --pin, empty for any core
+    pub pin: Vec<usize>,
+    pub strict: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::Groth,
+            reps: 1,
+            warm_up: WarmUp::None,
+            seed: None,
+            setup: Setup::Random,
+            order: ExecutionOrder::Grouped,
+            record_schedule: None,
+            replay: None,
+            test_vectors: None,
+            check_vectors: None,
+            cold_start: false,
+            instructions: false,
+            energy: false,
+            dry_run: false,
+            validate: false,
+            throughput: None,
+            estimate: None,
+            threads: 1,
+            output: None,
+            network: None,
+            quiet: false,
+            metrics: None,
+            history: Some(History::DEFAULT_DIR.into()),
+            save_baseline: None,
+            baseline: None,
+            export_criterion: None,
+            export_bencher: None,
+            export_latex: None,
+            threshold: 0.1,
+            table: None,
+            g1_exp_units: false,
+            cycle_units: false,
+            plot: None,
+            crossover: None,
+            phase: "Verifier".to_string(),
+            fit: None,
+            static_g2: false,
+            pin: Vec::new(),
+            strict: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(msg) => {
+                eprintln!("error: {}\n\n{}", msg, USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.peekable();
+        let mut positional = |name: &str| args.next_if(|arg| !arg.starts_with('-')).ok_or(format!("missing {}", name));
+        match positional("command").ok().as_deref() {
+            None => {}
+            Some("run") => options.command = Command::Run(positional("workload file")?.into()),
+            Some("sweep") => {
+                let preset = positional("preset name")?;
+                options.command = Command::Sweep { preset, axes: Vec::new() };
+            }
+            Some("pvss") => {
+                let n = parse_value("pvss N", positional("N").ok())?;
+                let t = parse_value("pvss T", positional("T").ok())?;
+                if t >= n {
+                    return Err("pvss threshold T must be below N".to_string());
+                }
+                options.command = Command::Pvss { n, t };
+            }
+            Some("dkg") => {
+                let scheme = positional("scheme")?;
+                options.command = Command::Dkg { scheme, axes: Vec::new() };
+            }
+            Some("weighted") => {
+                let preset = positional("preset name")?;
+                let weights = Weights::parse(&positional("weights")?)?;
+                options.command = Command::Weighted { preset, weights, axes: Vec::new() };
+            }
+            Some("async") => {
+                let preset = positional("preset name")?;
+                let delays = Delays::parse(&positional("delay")?)?;
+                options.command = Command::Async { preset, delays, axes: Vec::new() };
+            }
+            Some("reconstruct") => {
+                let weights = Weights::parse(&positional("weights")?)?;
+                let t = parse_value("reconstruct T", positional("T").ok())?;
+                let samples = parse_value("reconstruct SAMPLES", positional("SAMPLES").ok())?;
+                if t >= weights.total() {
+                    return Err("reconstruct threshold T must be below the number of shares".to_string());
+                }
+                if samples == 0 {
+                    return Err("reconstruct SAMPLES must be at least 1".to_string());
+                }
+                options.command = Command::Reconstruct { weights, t, samples };
+            }
+            Some("compare") => {
+                let preset = positional("preset name")?;
+                let mut a = (preset, Vec::new());
+                while let Some(axis) = args.next_if(|arg| !arg.starts_with('-') && arg != "vs") {
+                    a.1.push(axis);
+                }
+                if args.next_if(|arg| arg == "vs").is_none() {
+                    return Err("compare needs two configurations separated by `vs`".to_string());
+                }
+                let preset = args.next_if(|arg| !arg.starts_with('-')).ok_or("missing preset name after `vs`")?;
+                let mut b = (preset, Vec::new());
+                while let Some(axis) = args.next_if(|arg| !arg.starts_with('-')) {
+                    b.1.push(axis);
+                }
+                options.command = Command::Compare { a, b };
+            }
+            Some("backends") => {
+                let preset = positional("preset name")?;
+                options.command = Command::Backends { preset, axes: Vec::new() };
+            }
+            Some("msm-threads") => {
+                let size = parse_value("msm-threads SIZE", positional("SIZE").ok())?;
+                let max_threads = match positional("MAX_THREADS") {
+                    Ok(max) => parse_value("msm-threads MAX_THREADS", Some(max))?,
+                    Err(_) => available_threads(),
+                };
+                if size == 0 || max_threads == 0 {
+                    return Err("msm-threads SIZE and MAX_THREADS must be at least 1".to_string());
+                }
+                options.command = Command::MsmThreads { size, max_threads };
+            }
+            Some("tune-msm") => {
+                let mut sizes = vec![parse_value("tune-msm SIZE", positional("SIZE").ok())?];
+                while let Ok(size) = positional("SIZE") {
+                    sizes.push(parse_value("tune-msm SIZE", Some(size))?);
+                }
+                if sizes.contains(&0) {
+                    return Err("tune-msm sizes must be at least 1".to_string());
+                }
+                options.command = Command::TuneMsm { sizes };
+            }
+            Some("pipeline") => {
+                let name = positional("protocol (schnorr or dleq)")?;
+                let protocol = Sigma::parse(&name).ok_or_else(|| format!("unknown protocol '{}' for pipeline (schnorr or dleq)", name))?;
+                let size = parse_value("pipeline SIZE", positional("SIZE").ok())?;
+                let invalid_percent = match positional("INVALID_PERCENT") {
+                    Ok(percent) => parse_value("pipeline INVALID_PERCENT", Some(percent))?,
+                    Err(_) => 1,
+                };
+                if size == 0 || invalid_percent > 100 {
+                    return Err("pipeline SIZE must be at least 1 and INVALID_PERCENT at most 100".to_string());
+                }
+                options.command = Command::Pipeline { protocol, size, invalid_percent };
+            }
+            Some("budget") => {
+                let preset = positional("preset name")?;
+                let mib : f64 = parse_value("budget MIB", positional("MIB").ok())?;
+                if mib.is_nan() || mib < 0.0 {
+                    return Err("budget MIB must not be negative".to_string());
+                }
+                options.command = Command::Budget { preset, budget: (mib * 1024.0 * 1024.0) as usize, axes: Vec::new() };
+            }
+            Some("calibrate") => options.command = Command::Calibrate,
+            Some("profile") => options.command = Command::Profile,
+            Some("predict") => {
+                let profile = positional("profile")?.into();
+                let preset = positional("preset name")?;
+                options.command = Command::Predict { profile, preset, axes: Vec::new() };
+            }
+            Some("project") => {
+                let profile = positional("profile")?.into();
+                let preset = positional("preset name")?;
+                options.command = Command::Project { profile, preset, axes: Vec::new() };
+            }
+            Some("ops") => {
+                let preset = positional("preset name")?;
+                options.command = Command::Ops { preset, axes: Vec::new() };
+            }
+            Some("query") => {
+                let mut filter = Filter::default();
+                while let Ok(arg) = positional("preset or NAME=VALUE") {
+                    match arg.split_once('=') {
+                        Some((name, value)) => filter.params.push((name.to_string(), parse_value(&format!("query {}", name), Some(value.to_string()))?)),
+                        None if filter.preset.is_none() && filter.params.is_empty() => filter.preset = Some(arg),
+                        None => return Err(format!("query takes a preset, then parameters NAME=VALUE, not '{}'", arg)),
+                    }
+                }
+                options.command = Command::Query(filter);
+            }
+            Some("list") => options.command = Command::List,
+            Some("merge") => {
+                let mut profiles = Vec::new();
+                while let Ok(profile) = positional("profile") {
+                    profiles.push(profile.into());
+                }
+                if profiles.len() < 2 {
+                    return Err("merge takes the profiles of at least two machines".to_string());
+                }
+                options.command = Command::Merge(profiles);
+            }
+            Some(command) => return Err(format!("unknown command '{}'", command)),
+        }
+        if let Command::Sweep { axes, .. } | Command::Dkg { axes, .. } | Command::Weighted { axes, .. } | Command::Async { axes, .. } | Command::Backends { axes, .. } | Command::Predict { axes, .. }
+            | Command::Project { axes, .. } | Command::Ops { axes, .. } | Command::Budget { axes, .. } = &mut options.command {
+            while let Some(axis) = args.next_if(|arg| !arg.starts_with('-')) {
+                axes.push(axis);
+            }
+        }
+        let mut given = Vec::new();
+        while let Some(arg) = args.next() {
+            given.push(arg.clone());
+            match arg.as_str() {
+                "--reps" => {
+                    options.reps = parse_value(&arg, args.next())?;
+                    if options.reps == 0 {
+                        return Err("--reps must be at least 1".to_string());
+                    }
+                }
+                "--warm-up" => options.warm_up = WarmUp::Once,
+                "--warm-up-time" => {
+                    let millis = parse_value(&arg, args.next())?;
+                    options.warm_up = WarmUp::For(Duration::from_millis(millis));
+                }
+                "--seed" => options.seed = Some(parse_value(&arg, args.next())?),
+                "--fast-setup" => options.setup = Setup::Fast,
+                "--cold-start" => options.cold_start = true,
+                "--dry-run" => options.dry_run = true,
+                "--validate" => options.validate = true,
+                "--throughput" => {
+                    let secs : f64 = parse_value(&arg, args.next())?;
+                    if secs.is_nan() || secs <= 0.0 {
+                        return Err("--throughput must be positive".to_string());
+                    }
+                    options.throughput = Some(Duration::from_secs_f64(secs));
+                }
+                "--estimate" => {
+                    let path : String = parse_value(&arg, args.next())?;
+                    options.estimate = Some(CostProfile::load(&path).map_err(|e| format!("{}: {}", path, e))?);
+                }
+                "--order" => {
+                    let name : String = parse_value(&arg, args.next())?;
+                    options.order = ExecutionOrder::parse(&name).ok_or_else(|| format!("unknown order '{}' (grouped, interleaved, shuffled)", name))?;
+                }
+                "--record-schedule" => options.record_schedule = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--replay" => {
+                    let path : String = parse_value(&arg, args.next())?;
+                    options.replay = Some(Schedule::load(&path).map_err(|e| format!("{}: {}", path, e))?);
+                }
+                "--test-vectors" => options.test_vectors = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--check-vectors" => {
+                    let path : String = parse_value(&arg, args.next())?;
+                    options.check_vectors = Some(TestVectors::load(&path).map_err(|e| format!("{}: {}", path, e))?);
+                }
+                "--threads" => {
+                    options.threads = parse_value(&arg, args.next())?;
+                    if options.threads == 0 {
+                        return Err("--threads must be at least 1".to_string());
+                    }
+                }
+                "--parallel" => options.threads = available_threads(),
+                "--network" => {
+                    let spec = args.next().ok_or_else(|| format!("{} requires a value", arg))?;
+                    options.network = Some(NetworkModel::parse(&spec)?);
+                }
+                "--format" => {
+                    let name : String = parse_value(&arg, args.next())?;
+                    let format = Format::parse(&name).ok_or_else(|| format!("unknown format '{}' (text, markdown, latex)", name))?;
+                    options.table.get_or_insert_with(Style::default).format = format;
+                }
+                "--unit" => {
+                    let name : String = parse_value(&arg, args.next())?;
+                    let style = options.table.get_or_insert_with(Style::default);
+                    // the time of a G1 exp is only known once it has been measured or looked up, and
+                    // the frequency of the time-stamp counter once it has been counted
+                    options.g1_exp_units = name == "g1-exps";
+                    options.cycle_units = name == "cycles";
+                    if !options.g1_exp_units && !options.cycle_units {
+                        style.unit = Unit::parse(&name)
+                            .ok_or_else(|| format!("unknown unit '{}' (ns, us, ms, s, cycles, cycles@FREQUENCY, g1-exps, auto)", name))?;
+                    }
+                }
+                "--no-totals" => options.table.get_or_insert_with(Style::default).totals = false,
+                "--percent" => options.table.get_or_insert_with(Style::default).percent = true,
+                "--digits" => {
+                    let digits = parse_value(&arg, args.next())?;
+                    if digits == 0 {
+                        return Err("--digits must be at least 1".to_string());
+                    }
+                    options.table.get_or_insert_with(Style::default).digits = digits;
+                }
+                "--crossover" => {
+                    let phases : String = parse_value(&arg, args.next())?;
+                    let (a, b) = phases.split_once(',').ok_or("--crossover takes two phases separated by a comma")?;
+                    options.crossover = Some((a.to_string(), b.to_string()));
+                }
+                "--phase" => options.phase = parse_value(&arg, args.next())?,
+                "--fit" => options.fit = Some(parse_value(&arg, args.next())?),
+                "--plot" => options.plot = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--history" => options.history = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--no-history" => options.history = None,
+                "--profile" | "--since" | "--until" => {
+                    let value : String = parse_value(&arg, args.next())?;
+                    let Command::Query(filter) = &mut options.command else {
+                        return Err(format!("{} only works with query", arg));
+                    };
+                    if arg == "--profile" {
+                        filter.profile_id = Some(value);
+                        continue;
+                    }
+                    let dates = history::parse_date(&value).ok_or_else(|| format!("invalid date '{}' for {} (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)", value, arg))?;
+                    match arg.as_str() {
+                        "--since" => filter.since = Some(dates.start),
+                        _ => filter.until = Some(dates.end),
+                    }
+                }
+                "--save-baseline" => options.save_baseline = Some(parse_value(&arg, args.next())?),
+                "--baseline" => options.baseline = Some(parse_value(&arg, args.next())?),
+                "--export-criterion" => options.export_criterion = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--export-bencher" => options.export_bencher = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--export-latex" => options.export_latex = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "--threshold" => {
+                    let percent : f64 = parse_value(&arg, args.next())?;
+                    if percent.is_nan() || percent < 0.0 {
+                        return Err("--threshold must not be negative".to_string());
+                    }
+                    options.threshold = percent / 100.0;
+                }
+                "--pin" => {
+                    let cores : String = parse_value(&arg, args.next())?;
+                    options.pin = parse_cores(&cores).ok_or_else(|| format!("invalid cores '{}' for --pin (e.g. 2 or 0,2-3)", cores))?;
+                }
+                "--static-g2" => options.static_g2 = true,
+                "--strict" => options.strict = true,
+                "--instructions" => options.instructions = true,
+                "--energy" => options.energy = true,
+                "-q" | "--quiet" => options.quiet = true,
+                "--metrics" => {
+                    let path : String = parse_value(&arg, args.next())?;
+                    options.metrics = Some(Reporter::metrics(&path).map_err(|e| format!("{}: {}", path, e))?);
+                }
+                "--output" => options.output = Some(parse_value::<String>(&arg, args.next())?.into()),
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ => return Err(format!("unrecognized argument '{}'", arg)),
+            }
+        }
+        // a workload file's [options], unless overridden by flags
+        if let Command::Run(path) = &options.command {
+            let file = config::load_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let given = |flags: &[&str]| given.iter().any(|arg| flags.contains(&arg.as_str()));
+            let run = file.options;
+            if let Some(reps) = run.reps.filter(|_| !given(&["--reps"])) {
+                options.reps = reps;
+            }
+            if let Some(warm_up) = run.warm_up.filter(|_| !given(&["--warm-up", "--warm-up-time"])) {
+                options.warm_up = warm_up;
+            }
+            if let Some(order) = run.order.filter(|_| !given(&["--order"])) {
+                options.order = order;
+            }
+            if let Some(threads) = run.threads.filter(|_| !given(&["--threads", "--parallel"])) {
+                options.threads = threads;
+            }
+            if let Some(seed) = run.seed.filter(|_| !given(&["--seed"])) {
+                options.seed = Some(seed);
+            }
+        }
+        let exports = options.export_criterion.is_some() || options.export_bencher.is_some() || options.export_latex.is_some();
+        if options.throughput.is_some() && (options.reps > 1 || options.save_baseline.is_some() || options.baseline.is_some() || exports) {
+            return Err("--throughput can't be combined with --reps, --save-baseline, --baseline or the exports".to_string());
+        }
+        if (options.record_schedule.is_some() || options.replay.is_some()) && !matches!(options.command, Command::Groth | Command::Run(_)) {
+            return Err("--record-schedule and --replay only work with the default command and run".to_string());
+        }
+        if (options.record_schedule.is_some() || options.replay.is_some()) && options.throughput.is_some() {
+            return Err("--throughput has no schedule to record or replay".to_string());
+        }
+        if (options.test_vectors.is_some() || options.check_vectors.is_some()) && !matches!(options.command, Command::Groth | Command::Run(_)) {
+            return Err("--test-vectors and --check-vectors only work with the default command and run".to_string());
+        }
+        if options.instructions && !matches!(options.command, Command::Groth | Command::Run(_)) {
+            return Err("--instructions only works with the default command and run".to_string());
+        }
+        if options.instructions && (options.throughput.is_some() || options.save_baseline.is_some() || options.baseline.is_some() || exports) {
+            return Err("--instructions has no times for --throughput, the baselines or the exports".to_string());
+        }
+        if options.energy && (options.order != ExecutionOrder::Grouped || options.replay.is_some() || options.throughput.is_some()) {
+            return Err("--energy measures items run one after the other, not with --order, --replay or --throughput".to_string());
+        }
+        if let (Some(schedule), Some(vectors)) = (&options.replay, &options.check_vectors) {
+            if schedule.seed.is_some() && vectors.seed.is_some() && schedule.seed != vectors.seed {
+                return Err("the replayed schedule and the test vectors have different seeds".to_string());
+            }
+        }
+        if matches!(options.command, Command::Project { .. }) && options.network.is_none() {
+            return Err("project needs --network".to_string());
+        }
+        if let Some(schedule) = &options.replay {
+            if options.reps > schedule.repetitions.len() {
+                return Err(format!("--reps {} but the schedule has {} repetitions", options.reps, schedule.repetitions.len()));
+            }
+        }
+        Ok(options)
+    }
+}
+
+// a list of cores and ranges of them, as in `taskset -c`: 0,2-3
+fn parse_cores(list: &str) -> Option<Vec<usize>> {
+    let mut cores = Vec::new();
+    for part in list.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) : (usize, usize) = (first.trim().parse().ok()?, last.trim().parse().ok()?);
+                if first > last {
+                    return None;
+                }
+                cores.extend(first..=last);
+            }
+            None => cores.push(part.trim().parse().ok()?),
+        }
+    }
+    cores.sort();
+    cores.dedup();
+    Some(cores)
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value.parse().map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
@@ -0,0 +1,54 @@
+//! The clocks all measurements and timestamps read. Natively they are `std::time::Instant` and
+//! `SystemTime::now`. `wasm32-unknown-unknown` has no clock in std, so there the embedder provides
+//! one as the import `now` of module `pvss_sim`, in milliseconds since the Unix epoch, e.g.
+//! `{ pvss_sim: { now: () => performance.timeOrigin + performance.now() } }` in a browser.
+
+use std::time::SystemTime;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use wasm::Instant;
+
+/// The wall-clock time, for the dates of reports, profiles and records.
+pub fn system_time() -> SystemTime {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return SystemTime::now();
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return std::time::UNIX_EPOCH + wasm::since_epoch();
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod wasm {
+    use std::time::Duration;
+
+    #[link(wasm_import_module = "pvss_sim")]
+    extern "C" {
+        fn now() -> f64;
+    }
+
+    /// Milliseconds since the Unix epoch.
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Self {
+            // SAFETY: the import takes no arguments and returns a number
+            Self(unsafe { now() })
+        }
+
+        /// Zero if the embedder's clock went backwards.
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1e3)
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Self::now().duration_since(*self)
+        }
+    }
+
+    pub(super) fn since_epoch() -> Duration {
+        Instant::now().duration_since(Instant(0.0))
+    }
+}
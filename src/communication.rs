@@ -0,0 +1,130 @@
+//! Communication costs: the byte sizes of the messages a protocol sends, next to its compute.
+
+use crate::report::{item_name, write_by_phase};
+
+use std::fmt;
+
+
+/// What messages are made of, with blstrs' compressed encodings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Element {
+    G1,
+    G2,
+    Gt,
+    Scalar,
+}
+
+impl Element {
+    /// Encoded size in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            Element::G1 => 48,
+            Element::G2 => 96,
+            Element::Gt => 288,
+            Element::Scalar => 32,
+        }
+    }
+
+    /// By the names workload files use: `g1`, `g2`, `gt` or `scalar`.
+    pub fn parse(key: &str) -> Option<Self> {
+        [Element::G1, Element::G2, Element::Gt, Element::Scalar].into_iter().find(|element| element.key() == key)
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Element::G1 => "g1",
+            Element::G2 => "g2",
+            Element::Gt => "gt",
+            Element::Scalar => "scalar",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Element::G1 => "G1",
+            Element::G2 => "G2",
+            Element::Gt => "Gt",
+            Element::Scalar => "scalars",
+        }
+    }
+}
+
+/// `num` elements sent as part of a phase, to everyone (PVSS transcripts are public).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub element: Element,
+    pub num: usize,
+    pub label: Option<String>,
+    /// the phase whose role sends the message
+    pub phase: Option<String>,
+}
+
+impl Message {
+    pub fn bytes(&self) -> usize {
+        self.num * self.element.size()
+    }
+}
+
+/// All messages of a protocol run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Communication {
+    pub messages: Vec<Message>,
+    /// the number of parties receiving the messages, if known
+    pub parties: Option<usize>,
+}
+
+impl Communication {
+    /// Total bytes of all messages.
+    pub fn total(&self) -> usize {
+        self.messages.iter().map(Message::bytes).sum()
+    }
+
+    /// Bytes sent per phase, in order of first appearance.
+    pub fn phase_totals(&self) -> Vec<(String, usize)> {
+        let mut totals : Vec<(String, usize)> = Vec::new();
+        for message in &self.messages {
+            let Some(phase) = &message.phase else { continue };
+            match totals.iter_mut().find(|(name, _)| name == phase) {
+                Some((_, bytes)) => *bytes += message.bytes(),
+                None => totals.push((phase.clone(), message.bytes())),
+            }
+        }
+        totals
+    }
+}
+
+/// Bytes with a binary unit, e.g. "1.50 KiB".
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+impl fmt::Display for Communication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "communication:")?;
+        write_by_phase(f, &self.messages,
+            |message| message.phase.as_deref(),
+            |f, indent, message| {
+                let description = format!("{} {}", message.num, message.element.name());
+                writeln!(f, "{}{}: {}", indent, item_name(&description, message.label.as_deref()), format_bytes(message.bytes()))
+            },
+            |f, group| writeln!(f, "    phase total: {}", format_bytes(group.iter().map(Message::bytes).sum()))
+        )?;
+        writeln!(f, "  transcript: {}", format_bytes(self.total()))?;
+        if let Some(parties) = self.parties {
+            // every party receives everything; sending it point-to-point multiplies by the parties
+            writeln!(f, "  per-party bandwidth: {} received ({} over all {} parties)",
+                format_bytes(self.total()), format_bytes(self.total() * parties), parties)?;
+        }
+        Ok(())
+    }
+}
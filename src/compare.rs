@@ -0,0 +1,110 @@
+//! Side-by-side comparison of two simulated configurations: per-phase times, their difference
+//! and the speedup of the second over the first.
+
+use crate::communication::format_bytes;
+use crate::render::{Cell, Table, Totals};
+
+use std::fmt;
+use std::time::Duration;
+
+
+/// One configuration's results.
+#[derive(Clone, Debug)]
+pub struct Side {
+    /// e.g. `groth n=1024 k=16 t=682 l=16`
+    pub name: String,
+    pub phases: Vec<(String, Duration)>,
+    pub total: Duration,
+    pub transcript: Option<usize>,
+}
+
+/// Prints as a table with a row per phase (in the order they first appear on either side), the
+/// total and the transcript size. `speedup` is `a / b`, so above 1 when `b` is faster.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub a: Side,
+    pub b: Side,
+}
+
+impl Comparison {
+    pub fn new(a: Side, b: Side) -> Self {
+        Self { a, b }
+    }
+
+    /// Speedup of `b` over `a` in the phase, if both have it.
+    pub fn speedup(&self, phase: &str) -> Option<f64> {
+        let time = |side: &Side| side.phases.iter().find(|(name, _)| name == phase).map(|(_, d)| *d);
+        time(&self.a).zip(time(&self.b)).map(|(a, b)| ratio(a.as_secs_f64(), b.as_secs_f64()))
+    }
+
+    pub fn total_speedup(&self) -> f64 {
+        ratio(self.a.total.as_secs_f64(), self.b.total.as_secs_f64())
+    }
+
+    // in the order they first appear on either side
+    fn phase_names(&self) -> Vec<&str> {
+        let mut phase_names : Vec<&str> = Vec::new();
+        for (name, _) in self.a.phases.iter().chain(&self.b.phases) {
+            if !phase_names.contains(&name.as_str()) {
+                phase_names.push(name);
+            }
+        }
+        phase_names
+    }
+
+    /// Times of A and B and the speedup per phase, for rendering as Markdown or LaTeX; the
+    /// header names the two configurations.
+    pub fn to_table(&self) -> Table {
+        let time = |side: &Side, phase: &str| side.phases.iter().find(|(name, _)| name == phase).map_or(Cell::Empty, |(_, d)| Cell::Time(*d));
+        let speedup = |speedup: Option<f64>| speedup.map_or(Cell::Empty, |s| Cell::Text(format!("{:.2}", s)));
+        let mut rows : Vec<Vec<Cell>> = self.phase_names().into_iter()
+            .map(|name| vec![Cell::Text(name.to_string()), time(&self.a, name), time(&self.b, name), speedup(self.speedup(name))])
+            .collect();
+        let totals = Totals::Row(rows.len());
+        rows.push(vec![Cell::Text("total".to_string()), Cell::Time(self.a.total), Cell::Time(self.b.total), speedup(Some(self.total_speedup()))]);
+        if let (Some(a), Some(b)) = (self.a.transcript, self.b.transcript) {
+            rows.push(vec![Cell::Text("transcript".to_string()), Cell::Bytes(a), Cell::Bytes(b), speedup(Some(ratio(a as f64, b as f64)))]);
+        }
+        Table { header: vec![String::new(), self.a.name.clone(), self.b.name.clone(), "speedup".to_string()], rows, totals }
+    }
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+    if b == 0.0 { f64::INFINITY } else { a / b }
+}
+
+fn difference(a: Duration, b: Duration) -> String {
+    if b >= a { format!("+{:.3?}", b - a) } else { format!("-{:.3?}", a - b) }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "A: {}", self.a.name)?;
+        writeln!(f, "B: {}", self.b.name)?;
+        let phase_names = self.phase_names();
+        let time = |side: &Side, phase: &str| side.phases.iter().find(|(name, _)| name == phase).map(|(_, d)| *d);
+        let row = |name: &str, a: Option<Duration>, b: Option<Duration>| -> Vec<String> {
+            let cell = |d: Option<Duration>| d.map_or_else(|| "-".to_string(), |d| format!("{:.3?}", d));
+            let (diff, speedup) = match (a, b) {
+                (Some(a), Some(b)) => (difference(a, b), format!("{:.2}x", ratio(a.as_secs_f64(), b.as_secs_f64()))),
+                _ => ("-".to_string(), "-".to_string()),
+            };
+            vec![name.to_string(), cell(a), cell(b), diff, speedup]
+        };
+        let mut rows = vec![["", "A", "B", "B - A", "speedup"].map(str::to_string).to_vec()];
+        rows.extend(phase_names.iter().map(|&name| row(name, time(&self.a, name), time(&self.b, name))));
+        rows.push(row("total", Some(self.a.total), Some(self.b.total)));
+        if let (Some(a), Some(b)) = (self.a.transcript, self.b.transcript) {
+            let diff = if b >= a { format!("+{}", format_bytes(b - a)) } else { format!("-{}", format_bytes(a - b)) };
+            rows.push(vec!["transcript".to_string(), format_bytes(a), format_bytes(b), diff, format!("{:.2}x", ratio(a as f64, b as f64))]);
+        }
+        let widths : Vec<usize> = (0..5).map(|i| rows.iter().map(|row| row[i].len()).max().unwrap()).collect();
+        for row in &rows {
+            let line : Vec<String> = row.iter().zip(&widths).enumerate()
+                .map(|(i, (cell, &w))| if i == 0 { format!("{:<w$}", cell, w = w) } else { format!("{:>w$}", cell, w = w) })
+                .collect();
+            writeln!(f, "{}", line.join("  "))?;
+        }
+        Ok(())
+    }
+}
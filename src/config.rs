@@ -0,0 +1,569 @@
+//! Workload files: a TOML list of `[[item]]` tables, each either one op or a protocol preset.
+//!
+//! ```toml
+//! [[item]]
+//! op = "g1_multi_exps"
+//! num = 2
+//! size = 1024
+//! label = "share commitments"
+//! phase = "Dealer"
+//!
+//! [[item]]
+//! preset = "groth"
+//! n = 1024
+//! k = 16
+//! t = 660
+//! l = 16
+//! ```
+//!
+//! `num` defaults to 1. `size` is required for sized ops (multi-exps, multi-pairings, ...;
+//! for Feldman commitments and share checks, the degree of the polynomials, and for Pedersen
+//! commitments and openings and scalar batch ops, the length of the vectors, and for hashes to
+//! curve, the length of the messages in bytes).
+//! Serialization ops take `compressed` (default `true`), `pairings_converting` and
+//! `multi_pairings_converting` `to_affine` (default `true`, timing the conversion of their
+//! projective inputs to affine as its own item), fixed-base exps and Feldman
+//! commitments `window_bits`, Pippenger multi-exps `window_bits` and `buckets` (`signed`, the default, or `unsigned`),
+//! streaming multi-exps `chunk_size` (at most `size`),
+//! `poly_evals` `num_points`, `pairing_equality_checks` `rhs_size` (`size` being the number
+//! of pairings on the left-hand side), `multi_pairings_static` `static_inputs` (at most `size`), `elgamal_encryptions` `shared_randomness` (default
+//! `false`, `size` being the number of receivers), `chunked_elgamal` `chunk_bits`,
+//! `proof_repetitions` and `chunks` (default enough chunks to cover a scalar, `size` being the
+//! number of receivers), `reed_solomon_encodings` and `reed_solomon_decodings` `data_shares`
+//! (at most `size`, the number of symbols of a codeword), `merkle_trees` and
+//! `merkle_proof_verifications` `hash` (`sha256`, the default, or `blake2s`), `arity` (default 2)
+//! and `leaf` (`g1`, the default, `g2`, `gt` or `scalar`, `size` being the number of leaves),
+//! `sigma_verifications` `protocol` (`schnorr` or `dleq`),
+//! `batched` (default `false`, `size` being the number of proofs per verification) and
+//! `invalid_percent` (default 0),
+//! `kzg_multi_openings` `points` (`size` being the degree), `kzg_batch_verifications`
+//! `degree` (`size` being the number of openings) and `srs_verifications` `batched` (default
+//! `true`, `size` being the degree, as for `srs_generations`).
+//!
+//! Multi-exps (`g1_multi_exps`, `g2_multi_exps`, `gt_multi_exps`) and `multi_pairings` and
+//! `multi_pairings_converting` can take `sizes` instead of `size`, for ops whose sizes vary:
+//! `"uniform:MIN:MAX"`, `"zipf:MAX:S"` (size `k` with probability in proportion to `1/k^S`) or
+//! a list `"S1,S2,..."` to draw from (see `sizes::SizeDistribution`), with sizes up to
+//! `sizes::MAX_SIZE` (2^20).
+//!
+//! Any count can also be given as an expression over the integers in a top-level `[params]`
+//! table, e.g. `num = "n*k + l + 1"` (see `protocol::Expr` for the syntax).
+//!
+//! An `[options]` table sets how the workload runs: `reps`, `warm_up` (`true` for one untimed
+//! run, or milliseconds), `order` (`grouped`, `interleaved` or `shuffled`), `threads` and
+//! `seed` (an integer, or a string for seeds beyond an i64). A top-level `version` names the
+//! schema the file was written for, `SCHEMA_VERSION` by default; a file for a newer schema is
+//! rejected rather than half understood.
+//!
+//! The same schema can be written as JSON (files ending in `.json`), where `version` is
+//! required:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "options": { "reps": 5 },
+//!   "item": [
+//!     { "op": "g1_multi_exps", "num": 2, "size": 1024, "phase": "Dealer" }
+//!   ]
+//! }
+//! ```
+
+use crate::chunked_elgamal;
+use crate::communication::Element;
+use crate::group_ops_simulation::{ExecutionOrder, WarmUp};
+use crate::json;
+use crate::merkle::MerkleHash;
+use crate::pippenger::{self, Buckets, PippengerConfig};
+use crate::presets;
+use crate::protocol::{Expr, Params};
+use crate::sigma::Sigma;
+use crate::sizes::SizeDistribution;
+use crate::toml::{self, Table, Value};
+use crate::workload::{Op, OpKind, Workload};
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    /// a well-formed file that doesn't describe a valid workload; names the offending item
+    Invalid(String),
+    /// the file's schema version, newer than `SCHEMA_VERSION`
+    UnsupportedVersion(i64),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ConfigError::Invalid(message) => write!(f, "{}", message),
+            ConfigError::UnsupportedVersion(version) => write!(f, "the file is for workload schema version {}, but this build reads up to version {}", version, SCHEMA_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::ParseError> for ConfigError {
+    fn from(e: toml::ParseError) -> Self {
+        ConfigError::Parse { line: e.line, message: e.message }
+    }
+}
+
+impl From<json::ParseError> for ConfigError {
+    fn from(e: json::ParseError) -> Self {
+        ConfigError::Parse { line: e.line, message: e.message }
+    }
+}
+
+/// The newest version of the workload schema this build reads.
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// How a workload file asks to be run; every option is unset unless the file sets it.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    pub reps: Option<usize>,
+    pub warm_up: Option<WarmUp>,
+    pub order: Option<ExecutionOrder>,
+    pub threads: Option<usize>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WorkloadFile {
+    pub version: i64,
+    pub workload: Workload,
+    pub options: RunOptions,
+}
+
+/// Reads JSON from files ending in `.json`, TOML from any other.
+pub fn load_file(path: impl AsRef<Path>) -> Result<WorkloadFile, ConfigError> {
+    let text = std::fs::read_to_string(&path)?;
+    match path.as_ref().extension() {
+        Some(extension) if extension == "json" => parse_json(&text),
+        _ => parse_file(&text),
+    }
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Workload, ConfigError> {
+    load_file(path).map(|file| file.workload)
+}
+
+pub fn parse(text: &str) -> Result<Workload, ConfigError> {
+    parse_file(text).map(|file| file.workload)
+}
+
+pub fn parse_file(text: &str) -> Result<WorkloadFile, ConfigError> {
+    from_table(&toml::parse(text)?)
+}
+
+pub fn parse_json(text: &str) -> Result<WorkloadFile, ConfigError> {
+    let root = match toml::from_json(json::parse(text)?).map_err(ConfigError::Invalid)? {
+        Value::Table(root) => root,
+        _ => return Err(ConfigError::Invalid("the file must be a JSON object".to_string())),
+    };
+    if root.get("version").is_none() {
+        return Err(ConfigError::Invalid("missing `version`".to_string()));
+    }
+    from_table(&root)
+}
+
+fn from_table(root: &Table) -> Result<WorkloadFile, ConfigError> {
+    let invalid = |message: String| Err(ConfigError::Invalid(message));
+    if let Some(key) = root.keys().find(|&key| !["version", "params", "options", "item"].contains(&key)) {
+        return invalid(format!("unknown top-level key `{}`", key));
+    }
+    let version = match root.get("version") {
+        None => SCHEMA_VERSION,
+        Some(&Value::Integer(version)) if version >= 1 => version,
+        Some(_) => return invalid("`version` must be a positive integer".to_string()),
+    };
+    if version > SCHEMA_VERSION {
+        return Err(ConfigError::UnsupportedVersion(version));
+    }
+    let options = match root.get("options") {
+        None => RunOptions::default(),
+        Some(Value::Table(table)) => run_options(table)?,
+        Some(_) => return invalid("`options` must be a table".to_string()),
+    };
+    Ok(WorkloadFile { version, workload: workload(root)?, options })
+}
+
+fn run_options(table: &Table) -> Result<RunOptions, ConfigError> {
+    fn invalid<T>(message: String) -> Result<T, ConfigError> {
+        Err(ConfigError::Invalid(format!("options: {}", message)))
+    }
+    if let Some(key) = table.keys().find(|&key| !["reps", "warm_up", "order", "threads", "seed"].contains(&key)) {
+        return invalid(format!("unknown key `{}`", key));
+    }
+    let at_least_one = |key: &str| match table.get(key) {
+        None => Ok(None),
+        Some(&Value::Integer(i)) if i >= 1 => Ok(Some(i as usize)),
+        Some(_) => invalid(format!("`{}` must be a positive integer", key)),
+    };
+    let warm_up = match table.get("warm_up") {
+        None => None,
+        Some(&Value::Boolean(once)) => Some(if once { WarmUp::Once } else { WarmUp::None }),
+        Some(&Value::Integer(millis)) if millis >= 0 => Some(WarmUp::For(Duration::from_millis(millis as u64))),
+        Some(_) => return invalid("`warm_up` must be a boolean or a number of milliseconds".to_string()),
+    };
+    let order = match table.get("order") {
+        None => None,
+        Some(Value::String(name)) => match ExecutionOrder::parse(name) {
+            Some(order) => Some(order),
+            None => return invalid(format!("unknown `order` `{}` (grouped, interleaved, shuffled)", name)),
+        },
+        Some(_) => return invalid("`order` must be a string".to_string()),
+    };
+    let seed = match table.get("seed") {
+        None => None,
+        Some(&Value::Integer(seed)) if seed >= 0 => Some(seed as u64),
+        Some(Value::String(seed)) if seed.parse::<u64>().is_ok() => seed.parse().ok(),
+        Some(_) => return invalid("`seed` must be an unsigned integer, or one as a string".to_string()),
+    };
+    Ok(RunOptions { reps: at_least_one("reps")?, warm_up, order, threads: at_least_one("threads")?, seed })
+}
+
+fn workload(root: &Table) -> Result<Workload, ConfigError> {
+    let mut params = Params::new();
+    match root.get("params") {
+        None => {}
+        Some(Value::Table(table)) => for (name, value) in &table.0 {
+            match value {
+                &Value::Integer(i) => params.insert(name, i),
+                v => return Err(ConfigError::Invalid(format!("parameter `{}` must be an integer, not {}", name, v.type_name()))),
+            }
+        },
+        Some(_) => return Err(ConfigError::Invalid("`params` must be a table".to_string())),
+    }
+    let items = match root.get("item") {
+        None => return Ok(Workload::new()),
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err(ConfigError::Invalid("`item` must be an array of tables ([[item]])".to_string())),
+    };
+    let mut workload = Workload::new();
+    for (i, item) in items.iter().enumerate() {
+        let item = match item {
+            Value::Table(item) => item,
+            _ => return Err(ConfigError::Invalid(format!("item {}: not a table", i + 1))),
+        };
+        let entry = Item { table: item, index: i + 1, params: &params };
+        workload = workload.extend(entry.workload()?);
+    }
+    Ok(workload)
+}
+
+struct Item<'a> {
+    table: &'a Table,
+    index: usize,
+    params: &'a Params,
+}
+
+impl Item<'_> {
+    fn error<T>(&self, message: impl fmt::Display) -> Result<T, ConfigError> {
+        Err(ConfigError::Invalid(format!("item {}: {}", self.index, message)))
+    }
+
+    fn check_keys(&self, allowed: &[&str]) -> Result<(), ConfigError> {
+        match self.table.keys().find(|key| !allowed.contains(key)) {
+            Some(key) => self.error(format!("unknown key `{}`", key)),
+            None => Ok(()),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<Option<&str>, ConfigError> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(v) => self.error(format!("`{}` must be a string, not {}", key, v.type_name())),
+        }
+    }
+
+    // an integer, or a string holding an expression over the file's parameters
+    fn integer(&self, key: &str) -> Result<Option<usize>, ConfigError> {
+        match self.table.get(key) {
+            None => Ok(None),
+            Some(&Value::Integer(i)) if i >= 0 => Ok(Some(i as usize)),
+            Some(Value::Integer(_)) => self.error(format!("`{}` must not be negative", key)),
+            Some(Value::String(expr)) => match Expr::parse(expr).and_then(|expr| expr.eval_count(self.params)) {
+                Ok(value) => Ok(Some(value)),
+                Err(e) => self.error(format!("`{}`: {}", key, e)),
+            },
+            Some(v) => self.error(format!("`{}` must be an integer or an expression, not {}", key, v.type_name())),
+        }
+    }
+
+    fn required_integer(&self, key: &str) -> Result<usize, ConfigError> {
+        self.integer(key)?.map_or_else(|| self.error(format!("missing `{}`", key)), Ok)
+    }
+
+    fn bool_or(&self, key: &str, default: bool) -> Result<bool, ConfigError> {
+        match self.table.get(key) {
+            None => Ok(default),
+            Some(&Value::Boolean(b)) => Ok(b),
+            Some(v) => self.error(format!("`{}` must be a boolean, not {}", key, v.type_name())),
+        }
+    }
+
+    fn workload(&self) -> Result<Workload, ConfigError> {
+        let workload = match (self.string("op")?, self.string("preset")?) {
+            (Some(op), None) => Workload { ops: vec![self.op(op)?] },
+            (None, Some(preset)) => self.preset(preset)?,
+            (Some(_), Some(_)) => return self.error("has both `op` and `preset`"),
+            (None, None) => return self.error("needs either `op` or `preset`"),
+        };
+        Ok(match self.string("phase")? {
+            Some(phase) => workload.in_phase(phase),
+            None => workload,
+        })
+    }
+
+    fn op(&self, name: &str) -> Result<Op, ConfigError> {
+        let mut keys = vec!["op", "num", "size", "label", "phase"];
+        let compressed = || { self.bool_or("compressed", true) };
+        let kind = match name {
+            "poly_evals" => {
+                keys.push("num_points");
+                OpKind::PolyEvals { num_points: self.required_integer("num_points")? }
+            }
+            "pairing_equality_checks" => {
+                keys.push("rhs_size");
+                OpKind::PairingEqualityChecks { rhs_size: self.required_integer("rhs_size")? }
+            }
+            "serialize_g1" | "serialize_g2" | "deserialize_g1" | "deserialize_g2" => {
+                keys.push("compressed");
+                let compressed = compressed()?;
+                match name {
+                    "serialize_g1" => OpKind::SerializeG1 { compressed },
+                    "serialize_g2" => OpKind::SerializeG2 { compressed },
+                    "deserialize_g1" => OpKind::DeserializeG1 { compressed },
+                    _ => OpKind::DeserializeG2 { compressed },
+                }
+            }
+            "pairings_converting" | "multi_pairings_converting" => {
+                keys.push("to_affine");
+                let to_affine = self.bool_or("to_affine", true)?;
+                match name {
+                    "pairings_converting" => OpKind::PairingsConverting { to_affine },
+                    _ => OpKind::MultiPairingsConverting { to_affine },
+                }
+            }
+            "multi_pairings_static" => {
+                keys.push("static_inputs");
+                OpKind::MultiPairingsStatic { static_inputs: self.required_integer("static_inputs")? }
+            }
+            "kzg_multi_openings" => {
+                keys.push("points");
+                OpKind::KzgMultiOpenings { points: self.required_integer("points")? }
+            }
+            "kzg_batch_verifications" => {
+                keys.push("degree");
+                OpKind::KzgBatchVerifications { degree: self.required_integer("degree")? }
+            }
+            "srs_verifications" => {
+                keys.push("batched");
+                OpKind::SrsVerifications { batched: self.bool_or("batched", true)? }
+            }
+            "elgamal_encryptions" => {
+                keys.push("shared_randomness");
+                OpKind::ElGamalEncryptions { shared_randomness: self.bool_or("shared_randomness", false)? }
+            }
+            "chunked_elgamal" => {
+                keys.extend(["chunk_bits", "chunks", "proof_repetitions"]);
+                let chunk_bits = self.required_integer("chunk_bits")?;
+                if !(1..=chunked_elgamal::MAX_CHUNK_BITS).contains(&chunk_bits) {
+                    return self.error(format!("`chunk_bits` must be between 1 and {}", chunked_elgamal::MAX_CHUNK_BITS));
+                }
+                let chunks = self.integer("chunks")?.unwrap_or(chunked_elgamal::chunks_per_share(chunk_bits));
+                OpKind::ChunkedElGamal { chunk_bits, chunks, proof_repetitions: self.required_integer("proof_repetitions")? }
+            }
+            "sigma_verifications" => {
+                keys.extend(["protocol", "batched", "invalid_percent"]);
+                let protocol = match self.string("protocol")? {
+                    Some(name) => match Sigma::parse(name) {
+                        Some(protocol) => protocol,
+                        None => return self.error(format!("unknown `protocol` `{}` (schnorr, dleq)", name)),
+                    },
+                    None => return self.error("missing `protocol`"),
+                };
+                let invalid_percent = self.integer("invalid_percent")?.unwrap_or(0);
+                if invalid_percent > 100 {
+                    return self.error("`invalid_percent` must be at most 100");
+                }
+                OpKind::SigmaVerifications { protocol, batched: self.bool_or("batched", false)?, invalid_percent }
+            }
+            "fixed_base_exps" | "g2_fixed_base_exps" | "feldman_commitments" | "g2_feldman_commitments" => {
+                keys.push("window_bits");
+                let window_bits = self.required_integer("window_bits")?;
+                if !(1..=16).contains(&window_bits) {
+                    return self.error("`window_bits` must be between 1 and 16");
+                }
+                match name {
+                    "fixed_base_exps" => OpKind::FixedBaseExps { window_bits },
+                    "g2_fixed_base_exps" => OpKind::G2FixedBaseExps { window_bits },
+                    "feldman_commitments" => OpKind::FeldmanCommitments { window_bits },
+                    _ => OpKind::G2FeldmanCommitments { window_bits },
+                }
+            }
+            "g1_short_exps" | "g2_short_exps" | "g1_short_multi_exps" | "g2_short_multi_exps" => {
+                keys.push("bits");
+                let bits = self.required_integer("bits")?;
+                if !(1..=254).contains(&bits) {
+                    return self.error("`bits` must be between 1 and 254");
+                }
+                match name {
+                    "g1_short_exps" => OpKind::G1ShortExps { bits },
+                    "g2_short_exps" => OpKind::G2ShortExps { bits },
+                    "g1_short_multi_exps" => OpKind::G1ShortMultiExps { bits },
+                    _ => OpKind::G2ShortMultiExps { bits },
+                }
+            }
+            "g1_sparse_multi_exps" | "g2_sparse_multi_exps" => {
+                keys.push("binary_percent");
+                let binary_percent = self.required_integer("binary_percent")?;
+                if binary_percent > 100 {
+                    return self.error("`binary_percent` must be at most 100");
+                }
+                if name == "g1_sparse_multi_exps" {
+                    OpKind::G1SparseMultiExps { binary_percent }
+                } else {
+                    OpKind::G2SparseMultiExps { binary_percent }
+                }
+            }
+            "merkle_trees" | "merkle_proof_verifications" => {
+                keys.extend(["hash", "arity", "leaf"]);
+                let hash_name = self.string("hash")?.unwrap_or("sha256");
+                let hash = match MerkleHash::parse(hash_name) {
+                    Some(hash) => hash,
+                    None => return self.error(format!("unknown `hash` `{}` (sha256, blake2s)", hash_name)),
+                };
+                let arity = self.integer("arity")?.unwrap_or(2);
+                if arity < 2 {
+                    return self.error("`arity` must be at least 2");
+                }
+                let leaf_name = self.string("leaf")?.unwrap_or("g1");
+                let leaf = match Element::parse(leaf_name) {
+                    Some(leaf) => leaf,
+                    None => return self.error(format!("unknown `leaf` `{}` (g1, g2, gt, scalar)", leaf_name)),
+                };
+                if name == "merkle_trees" {
+                    OpKind::MerkleTrees { hash, arity, leaf }
+                } else {
+                    OpKind::MerkleProofVerifications { hash, arity, leaf }
+                }
+            }
+            "reed_solomon_encodings" | "reed_solomon_decodings" => {
+                keys.push("data_shares");
+                let data_shares = self.required_integer("data_shares")?;
+                if data_shares == 0 {
+                    return self.error("`data_shares` must be at least 1");
+                }
+                if name == "reed_solomon_encodings" {
+                    OpKind::ReedSolomonEncodings { data_shares }
+                } else {
+                    OpKind::ReedSolomonDecodings { data_shares }
+                }
+            }
+            "g1_streaming_multi_exps" | "g2_streaming_multi_exps" => {
+                keys.push("chunk_size");
+                let chunk_size = self.required_integer("chunk_size")?;
+                if chunk_size == 0 {
+                    return self.error("`chunk_size` must be at least 1");
+                }
+                if name == "g1_streaming_multi_exps" {
+                    OpKind::G1StreamingMultiExps { chunk_size }
+                } else {
+                    OpKind::G2StreamingMultiExps { chunk_size }
+                }
+            }
+            "g1_pippenger_multi_exps" | "g2_pippenger_multi_exps" => {
+                keys.extend(["window_bits", "buckets"]);
+                let window_bits = self.required_integer("window_bits")?;
+                if !(1..=pippenger::MAX_WINDOW_BITS).contains(&window_bits) {
+                    return self.error(format!("`window_bits` must be between 1 and {}", pippenger::MAX_WINDOW_BITS));
+                }
+                let buckets = match self.string("buckets")? {
+                    None => Buckets::Signed,
+                    Some(name) => match Buckets::parse(name) {
+                        Some(buckets) => buckets,
+                        None => return self.error(format!("unknown `buckets` `{}` (signed, unsigned)", name)),
+                    },
+                };
+                let config = PippengerConfig::new(window_bits, buckets);
+                if name == "g1_pippenger_multi_exps" {
+                    OpKind::G1PippengerMultiExps(config)
+                } else {
+                    OpKind::G2PippengerMultiExps(config)
+                }
+            }
+            _ => match OpKind::from_name(name) {
+                Some(kind) => kind,
+                None => return self.error(format!("unknown op `{}`", name)),
+            },
+        };
+        if kind.takes_size_distribution() {
+            keys.push("sizes");
+        }
+        self.check_keys(&keys)?;
+
+        let num = self.integer("num")?.unwrap_or(1);
+        if let Some(sizes) = self.string("sizes")? {
+            if self.table.get("size").is_some() {
+                return self.error("has both `size` and `sizes`");
+            }
+            let sizes = SizeDistribution::parse(sizes).or_else(|e| self.error(e))?;
+            let mut op = Op::varying(kind, num, sizes);
+            op.label = self.string("label")?.map(str::to_string);
+            return Ok(op);
+        }
+        let size = if kind.is_sized() {
+            self.required_integer("size")?
+        } else if self.table.get("size").is_some() {
+            return self.error(format!("`{}` takes no `size`", name));
+        } else {
+            1
+        };
+        if kind == OpKind::Fft && !size.is_power_of_two() {
+            return self.error("FFT `size` must be a power of two");
+        }
+        if let OpKind::KzgMultiOpenings { points } = kind {
+            if !(1..=size).contains(&points) {
+                return self.error("`points` must be between 1 and the degree `size`");
+            }
+        }
+        let mut op = Op::new(kind, num, size);
+        op.label = self.string("label")?.map(str::to_string);
+        Ok(op)
+    }
+
+    // every key other than `preset` and `phase` is a parameter of the preset
+    fn preset(&self, name: &str) -> Result<Workload, ConfigError> {
+        let protocol = match presets::by_name(name) {
+            Some(protocol) => protocol,
+            None => return self.error(format!("unknown preset `{}`", name)),
+        };
+        let needed = protocol.params();
+        let mut params = Params::new();
+        for key in self.table.keys().filter(|&key| key != "preset" && key != "phase") {
+            if !needed.contains(&key) {
+                return self.error(format!("preset `{}` has no parameter `{}` (it takes {})", name, key, needed.join(", ")));
+            }
+            params.insert(key, self.required_integer(key)? as i64);
+        }
+        if let Some(missing) = needed.iter().find(|&&key| params.get(key).is_none()) {
+            return self.error(format!("missing `{}`", missing));
+        }
+        protocol.evaluate(&params).or_else(|e| self.error(e))
+    }
+}
@@ -0,0 +1,429 @@
+//! Analytic cost model: per-op costs measured once on the current machine (`calibrate`), used to
+//! predict the runtime of workloads far too large to execute (`CostProfile::estimate`).
+//!
+//! Unsized ops have a fixed cost each. For sized ops (multi-exps, multi-pairings, ...) the cost
+//! is measured at several sizes and interpolated linearly in log-log space. Beyond the largest
+//! measured size, multi-exps are extrapolated as `size / log2(size)` (Pippenger's bound) and
+//! everything else linearly, as are multi-exps measured at size 1 alone. Hand-written tables can instead give a multi-exp cost as
+//! `g1_multi_exps = { a = 1500.0, b = 20000.0 }`, i.e. `a * size / log2(size) + b` ns.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, WarmUp};
+use crate::machine::{Machine, MachineProfile};
+use crate::report::{ItemReport, Metadata, OpCount, SimulationReport};
+use crate::sigma::{self, Sigma};
+use crate::toml::{self, Table, Value};
+use crate::workload::{Op, OpKind, Workload};
+
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::time::Duration;
+
+
+/// Cost of one operation of a kind, in nanoseconds.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cost {
+    Fixed(f64),
+    /// (size, ns per operation) pairs, in increasing order of size (see `Cost::by_size`)
+    BySize(Vec<(usize, f64)>),
+    /// `a * size / log2(size) + b`, the cost model of Pippenger's multi-exps
+    Msm { a: f64, b: f64 },
+}
+
+impl Cost {
+    /// A cost measured at several sizes, which must be increasing, with positive costs (the
+    /// interpolation is in log-log space).
+    pub fn by_size(kind: OpKind, points: Vec<(usize, f64)>) -> Result<Self, ProfileError> {
+        let invalid = |message: &str| Err(ProfileError::Invalid(format!("`{}` {}", kind.name(), message)));
+        if points.is_empty() {
+            return invalid("has no sizes");
+        }
+        if points.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return invalid("must list its sizes in increasing order, each once");
+        }
+        if points.iter().any(|&(size, ns)| size == 0 || !(ns > 0.0 && ns.is_finite())) {
+            return invalid("must have sizes of at least 1 and positive costs");
+        }
+        Ok(Cost::BySize(points))
+    }
+
+    pub(crate) fn at(&self, kind: OpKind, size: usize) -> f64 {
+        let points = match self {
+            Cost::Fixed(ns) => return *ns,
+            Cost::BySize(points) => points,
+            &Cost::Msm { a, b } => {
+                let s = size.max(2) as f64;
+                return a * s / s.log2() + b;
+            }
+        };
+        let s = size.max(1) as f64;
+        let (&(first_size, first_ns), &(last_size, last_ns)) = (points.first().unwrap(), points.last().unwrap());
+        if size <= first_size {
+            return first_ns * s / first_size as f64;
+        }
+        if size >= last_size {
+            let scale = match kind {
+                // with no size above 1 to scale from, linearly
+                OpKind::G1MultiExps | OpKind::G2MultiExps | OpKind::GtMultiExps if last_size > 1 =>
+                    (s / s.log2()) / (last_size as f64 / (last_size as f64).log2()),
+                _ => s / last_size as f64,
+            };
+            return last_ns * scale;
+        }
+        let i = points.iter().position(|&(sz, _)| sz >= size).unwrap();
+        let ((s0, c0), (s1, c1)) = (points[i - 1], points[i]);
+        let t = (s.ln() - (s0 as f64).ln()) / ((s1 as f64).ln() - (s0 as f64).ln());
+        (c0.ln() + t * (c1.ln() - c0.ln())).exp()
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(std::io::Error),
+    Invalid(String),
+    /// the workload contains an op kind the profile has no cost for
+    MissingCost(OpKind),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "{}", e),
+            ProfileError::Invalid(message) => write!(f, "invalid profile: {}", message),
+            ProfileError::MissingCost(kind) => write!(f, "the profile has no cost for `{}`", kind.name()),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<std::io::Error> for ProfileError {
+    fn from(e: std::io::Error) -> Self {
+        ProfileError::Io(e)
+    }
+}
+
+/// Measured per-op costs of one machine.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CostProfile {
+    pub costs: Vec<(OpKind, Cost)>,
+    /// the `machine::Machine::id` of the machine the costs were measured on, stamped on the
+    /// reports they predict
+    pub machine_id: Option<String>,
+}
+
+impl CostProfile {
+    pub fn cost(&self, kind: OpKind) -> Option<&Cost> {
+        self.costs.iter().find(|(k, _)| *k == kind).map(|(_, cost)| cost)
+    }
+
+    /// Predicted time of one op.
+    pub fn op_time(&self, op: &Op) -> Result<Duration, ProfileError> {
+        // ops of varying sizes take the expected time over their sizes
+        if let Some(sizes) = &op.sizes {
+            return sizes.probabilities().into_iter()
+                .map(|(size, p)| Ok(self.op_time(&Op::new(op.kind, op.num, size))?.mul_f64(p)))
+                .sum();
+        }
+        // exps by short exponents take their share of the full exps' doublings and additions
+        let full = match op.kind {
+            OpKind::G1ShortExps { bits } => Some((OpKind::G1Exps, bits)),
+            OpKind::G2ShortExps { bits } => Some((OpKind::G2Exps, bits)),
+            OpKind::G1ShortMultiExps { bits } => Some((OpKind::G1MultiExps, bits)),
+            OpKind::G2ShortMultiExps { bits } => Some((OpKind::G2MultiExps, bits)),
+            _ => None,
+        };
+        if let Some((kind, bits)) = full {
+            let time = self.op_time(&Op::new(kind, op.num, op.size))?;
+            return Ok(time.mul_f64(bits as f64 / 255.0));
+        }
+        // the streamed multi-exps, one per chunk and one for the rest; decoding uncompressed
+        // bases without checks is small next to them
+        let streamed = match op.kind {
+            OpKind::G1StreamingMultiExps { chunk_size } => Some((OpKind::G1MultiExps, chunk_size)),
+            OpKind::G2StreamingMultiExps { chunk_size } => Some((OpKind::G2MultiExps, chunk_size)),
+            _ => None,
+        };
+        if let Some((kind, chunk_size)) = streamed {
+            let chunks = self.op_time(&Op::new(kind, op.num * (op.size / chunk_size), chunk_size))?;
+            return match op.size % chunk_size {
+                0 => Ok(chunks),
+                rest => Ok(chunks + self.op_time(&Op::new(kind, op.num, rest))?),
+            };
+        }
+        // encoding multiplies by the generator matrix; decoding derives the weights of the
+        // symbols that arrived once, then combines them into the `k (n - k) / n` payload
+        // symbols missing on average
+        if let OpKind::ReedSolomonEncodings { data_shares: k } | OpKind::ReedSolomonDecodings { data_shares: k } = op.kind {
+            let products = |num: usize| -> Result<Duration, ProfileError> {
+                Ok(self.op_time(&Op::new(OpKind::ScalarMuls, num, 1))? + self.op_time(&Op::new(OpKind::ScalarAdds, num, 1))?)
+            };
+            if let OpKind::ReedSolomonEncodings { .. } = op.kind {
+                return products(op.num * k * op.size.saturating_sub(k));
+            }
+            let missing = k * op.size.saturating_sub(k) / op.size.max(1);
+            return Ok(products(k * k + 2 * missing * k + op.num * missing * k)? + self.op_time(&Op::new(OpKind::ScalarBatchInversions, 1, k + missing * k))?);
+        }
+        // a failed batch is bisected, every batch checked costing its multi-exps
+        if let OpKind::SigmaVerifications { protocol, batched: true, invalid_percent: invalid_percent @ 1.. } = op.kind {
+            let bases = if protocol == Sigma::Dleq { 2 } else { 1 };
+            return sigma::bisection_batches(op.size, sigma::invalid_proofs(op.size, invalid_percent)).into_iter()
+                .map(|(size, num)| self.op_time(&Op::new(OpKind::G1MultiExps, op.num * num * bases, 2 * size + 1)))
+                .sum();
+        }
+        // a multi-pairing without the preparation of its static G2 points
+        if let OpKind::MultiPairingsStatic { static_inputs } = op.kind {
+            let preparations = self.op_time(&Op::new(OpKind::G2Preparations, op.num * static_inputs, 1))?;
+            return Ok(self.op_time(&Op::new(OpKind::MultiPairings, op.num, op.size))?.saturating_sub(preparations));
+        }
+        // an equality check is one multi-pairing over both sides; negating G1 points is negligible,
+        // as are the additions of ElGamal, which is costed as its G1 exps
+        let (kind, size, per_op) = match op.kind {
+            OpKind::PairingEqualityChecks { rhs_size } => (OpKind::MultiPairings, op.size + rhs_size, 1),
+            // the calibrated pairings have affine inputs, the multi-pairings convert theirs
+            OpKind::PairingsConverting { to_affine: false } => (OpKind::Pairings, 1, 1),
+            OpKind::MultiPairingsConverting { to_affine: true } => (OpKind::MultiPairings, op.size, 1),
+            OpKind::ElGamalEncryptions { shared_randomness: false } => (OpKind::G1Exps, 1, 2 * op.size),
+            OpKind::ElGamalEncryptions { shared_randomness: true } => (OpKind::G1Exps, 1, op.size + 1),
+            OpKind::ElGamalDecryptions => (OpKind::G1Exps, 1, 1),
+            // both verification items, taking a multi-exp of size 2 for two exps
+            OpKind::DleqProofs => (OpKind::G1Exps, 1, 2),
+            OpKind::DleqVerifications => (OpKind::G1Exps, 1, 8),
+            OpKind::DleqBatchVerifications => (OpKind::G1MultiExps, 2 * op.size + 1, 2),
+            // the hashes are small next to the exps
+            OpKind::G1SchnorrProofs => (OpKind::G1Exps, 1, 1),
+            OpKind::G2SchnorrProofs => (OpKind::G2Exps, 1, 1),
+            OpKind::G1SchnorrVerifications => (OpKind::G1MultiExps, 2, 1),
+            OpKind::G2SchnorrVerifications => (OpKind::G2MultiExps, 2, 1),
+            OpKind::G1SchnorrBatchVerifications => (OpKind::G1MultiExps, 2 * op.size + 1, 1),
+            OpKind::G2SchnorrBatchVerifications => (OpKind::G2MultiExps, 2 * op.size + 1, 1),
+            OpKind::FeldmanCommitments { window_bits } => (OpKind::FixedBaseExps { window_bits }, 1, op.size + 1),
+            OpKind::G2FeldmanCommitments { window_bits } => (OpKind::G2FixedBaseExps { window_bits }, 1, op.size + 1),
+            OpKind::FeldmanShareChecks => (OpKind::G1MultiExps, op.size + 1, 1),
+            OpKind::G2FeldmanShareChecks => (OpKind::G2MultiExps, op.size + 1, 1),
+            OpKind::PedersenCommitments | OpKind::PedersenOpenings => (OpKind::G1MultiExps, op.size + 1, 1),
+            // the quotient's multi-exp, next to which the division is small for few points
+            OpKind::KzgOpenings => (OpKind::G1MultiExps, op.size, 1),
+            OpKind::KzgMultiOpenings { points } => (OpKind::G1MultiExps, op.size + 1 - points, 1),
+            // fixed-base, so cheaper than the exps; the batched checks' multi-exps, next to which
+            // their pairing is small
+            OpKind::SrsGenerations => (OpKind::G1Exps, 1, op.size + 1),
+            OpKind::SrsVerifications { batched: true } => (OpKind::G1MultiExps, op.size, 2),
+            OpKind::SrsVerifications { batched: false } => (OpKind::MultiPairings, 2, op.size),
+            // one multi-exp per base of the proofs; one by one, the verifier stops at the first
+            // invalid proof, on average the `(n + 1) / (k + 1)`th of `n` with `k` invalid
+            OpKind::SigmaVerifications { protocol, batched, invalid_percent } => {
+                let bases = if protocol == Sigma::Dleq { 2 } else { 1 };
+                let checked = match sigma::invalid_proofs(op.size, invalid_percent) {
+                    0 => op.size,
+                    invalid => (op.size + 1) / (invalid + 1),
+                };
+                if batched { (OpKind::G1MultiExps, 2 * op.size + 1, bases) } else { (OpKind::G1MultiExps, 2, bases * checked) }
+            }
+            kind => (kind, op.size, 1),
+        };
+        let cost = self.cost(kind).ok_or(ProfileError::MissingCost(kind))?;
+        Ok(Duration::from_secs_f64((op.num * per_op) as f64 * cost.at(kind, size) / 1e9))
+    }
+
+    /// Predicts the time of every op of `workload`, as a report in which each op is one item.
+    pub fn estimate(&self, workload: &Workload) -> Result<SimulationReport, ProfileError> {
+        let items = workload.ops.iter()
+            .map(|op| Ok(ItemReport {
+                description: op.to_string(),
+                label: op.label.clone(),
+                phase: op.phase.clone(),
+                phase_path: op.phase.iter().cloned().collect(),
+                op_count: OpCount::multi(op.num, op.sizes.as_ref().map_or(op.size, |sizes| sizes.mean().round() as usize)),
+                setup: Duration::ZERO,
+                cold: None,
+                duration: self.op_time(op)?,
+                memory: None,
+                energy: None,
+                notes: None,
+            }))
+            .collect::<Result<Vec<_>, ProfileError>>()?;
+        Ok(SimulationReport {
+            metadata: Metadata { profile_id: self.machine_id.clone(), ..Metadata::default() },
+            total: items.iter().map(|item| item.duration).sum(),
+            items,
+            cpu: None,
+        })
+    }
+
+    /// Reads the TOML of `calibrate`, or the JSON of `profile` if the file name ends in `.json`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        if path.as_ref().extension().is_some_and(|extension| extension == "json") {
+            return Ok(MachineProfile::load(path)?.costs);
+        }
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ProfileError> {
+        let invalid = |message: String| ProfileError::Invalid(message);
+        let root = toml::parse(text).map_err(|e| invalid(e.to_string()))?;
+        let machine_id = match root.get("machine_id") {
+            None => None,
+            Some(Value::String(id)) => Some(id.clone()),
+            Some(_) => return Err(invalid("`machine_id` must be a string".to_string())),
+        };
+        let costs = match root.get("costs") {
+            Some(Value::Table(costs)) => costs,
+            _ => return Err(invalid("missing [costs] table".to_string())),
+        };
+        Ok(CostProfile { machine_id, costs: parse_costs(costs)? })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# per-operation costs in nanoseconds, measured by `pvss-sim calibrate`\n");
+        if let Some(id) = &self.machine_id {
+            writeln!(out, "machine_id = \"{}\"", id).unwrap();
+        }
+        out.push_str("[costs]\n");
+        for (kind, cost) in &self.costs {
+            match cost {
+                Cost::Fixed(ns) => writeln!(out, "{} = {:.1}", kind.name(), ns).unwrap(),
+                Cost::BySize(points) => {
+                    let points : Vec<String> = points.iter().map(|(size, ns)| format!("[{}, {:.1}]", size, ns)).collect();
+                    writeln!(out, "{} = [{}]", kind.name(), points.join(", ")).unwrap();
+                }
+                Cost::Msm { a, b } => writeln!(out, "{} = {{ a = {:.1}, b = {:.1} }}", kind.name(), a, b).unwrap(),
+            }
+        }
+        out
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_toml())
+    }
+}
+
+
+/// The costs of a `[costs]` table, or of a JSON profile's `costs` object read as one
+/// (`toml::from_json`): a number of nanoseconds per op, a list of `[size, ns]` pairs
+/// (`Cost::by_size`) or `{ a, b }` (`Cost::Msm`) for each op kind.
+pub(crate) fn parse_costs(costs: &Table) -> Result<Vec<(OpKind, Cost)>, ProfileError> {
+    let invalid = |message: String| ProfileError::Invalid(message);
+    let number = |value: &Value| match *value {
+        Value::Integer(i) => Some(i as f64),
+        Value::Float(x) => Some(x),
+        _ => None,
+    };
+    costs.0.iter()
+        .map(|(name, value)| {
+            let kind = OpKind::from_name(name).ok_or_else(|| invalid(format!("unknown op `{}`", name)))?;
+            let cost = match value {
+                Value::Array(points) => {
+                    let points = points.iter()
+                        .map(|point| match point {
+                            Value::Array(pair) if pair.len() == 2 => match (&pair[0], number(&pair[1])) {
+                                (&Value::Integer(size), Some(ns)) if size > 0 => Some((size as usize, ns)),
+                                _ => None,
+                            },
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| invalid(format!("`{}` must be a list of [size, ns] pairs", name)))?;
+                    Cost::by_size(kind, points)?
+                }
+                Value::Table(model) => {
+                    let coefficient = |key: &str| model.get(key).and_then(number)
+                        .ok_or_else(|| invalid(format!("`{}` must have numbers `a` and `b`", name)));
+                    Cost::Msm { a: coefficient("a")?, b: coefficient("b")? }
+                }
+                value => Cost::Fixed(number(value)
+                    .ok_or_else(|| invalid(format!("`{}` must be a number, a list of [size, ns] pairs or {{ a, b }}", name)))?),
+            };
+            Ok((kind, cost))
+        })
+        .collect()
+}
+
+/// What `calibrate` measures: the kinds and, for sized kinds, the sizes.
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    pub kinds: Vec<(OpKind, Vec<usize>)>,
+    /// each measurement runs enough ops to take about this long
+    pub target_time: Duration,
+    pub repetitions: usize,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            kinds: vec![
+                (OpKind::G1Exps, vec![]),
+                (OpKind::G2Exps, vec![]),
+                (OpKind::GtExps, vec![]),
+                (OpKind::Pairings, vec![]),
+                (OpKind::G1MultiExps, vec![2, 4, 16, 64, 256, 1024, 4096]),
+                (OpKind::G2MultiExps, vec![2, 4, 16, 64, 256, 1024, 4096]),
+                (OpKind::GtMultiExps, vec![2, 4, 16]),
+                (OpKind::MultiPairings, vec![2, 4, 8, 16]),
+                (OpKind::MillerLoops, vec![1, 2, 4, 8, 16]),
+                (OpKind::FinalExponentiations, vec![]),
+                (OpKind::G2Preparations, vec![]),
+                (OpKind::ScalarMuls, vec![]),
+                (OpKind::ScalarAdds, vec![]),
+                (OpKind::ScalarInversions, vec![]),
+                (OpKind::ScalarBatchInversions, vec![16, 256, 4096]),
+                (OpKind::G1SubgroupChecks, vec![]),
+                (OpKind::G2SubgroupChecks, vec![]),
+                (OpKind::G1Decompressions, vec![]),
+                (OpKind::G2Decompressions, vec![]),
+                (OpKind::G1EqualityChecks, vec![]),
+                (OpKind::G2EqualityChecks, vec![]),
+                (OpKind::G1AffineEqualityChecks, vec![16, 256, 4096]),
+                (OpKind::G2AffineEqualityChecks, vec![16, 256, 4096]),
+                (OpKind::G1IdentityChecks, vec![]),
+                (OpKind::G2IdentityChecks, vec![]),
+                (OpKind::G1AffineIdentityChecks, vec![16, 256, 4096]),
+                (OpKind::G2AffineIdentityChecks, vec![16, 256, 4096]),
+                (OpKind::GtMuls, vec![]),
+                (OpKind::GtEqualityChecks, vec![]),
+                (OpKind::GtMembershipChecks, vec![]),
+                (OpKind::G1HashToCurve, vec![32, 1024, 8192]),
+                (OpKind::G2HashToCurve, vec![32, 1024, 8192]),
+            ],
+            target_time: Duration::from_millis(100),
+            repetitions: 3,
+        }
+    }
+}
+
+impl Calibration {
+    /// Measures every configured kind, calling `progress` after each measurement.
+    /// The profile is stamped with the current machine's ID.
+    pub fn run(&self, seed: u64, mut progress: impl FnMut(OpKind, usize, f64)) -> CostProfile {
+        let mut profile = CostProfile { machine_id: Some(Machine::current().id()), ..CostProfile::default() };
+        for (kind, sizes) in &self.kinds {
+            let cost = if kind.is_sized() {
+                Cost::BySize(sizes.iter()
+                    .map(|&size| {
+                        let ns = self.measure(seed, *kind, size);
+                        progress(*kind, size, ns);
+                        (size, ns)
+                    })
+                    .collect())
+            } else {
+                let ns = self.measure(seed, *kind, 1);
+                progress(*kind, 1, ns);
+                Cost::Fixed(ns)
+            };
+            profile.costs.push((*kind, cost));
+        }
+        profile
+    }
+
+    // median time per op over `repetitions` runs of enough ops to take about `target_time`
+    fn measure(&self, seed: u64, kind: OpKind, size: usize) -> f64 {
+        let time_per_op = |num: usize| {
+            let mut sim = GroupOpsSimulation::with_seed(seed);
+            sim.workload(&Workload::new().op(kind, num, size)).warm_up(WarmUp::Once);
+            let runs = sim.simulate_n(self.repetitions);
+            runs.total.median.as_secs_f64() * 1e9 / num as f64
+        };
+        let probe = time_per_op(1);
+        let num = (self.target_time.as_secs_f64() * 1e9 / probe).clamp(1.0, 100_000.0) as usize;
+        if num == 1 { probe } else { time_per_op(num) }
+    }
+}
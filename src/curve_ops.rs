@@ -0,0 +1,475 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup, Sampler};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blst::{blst_p1, blst_p1_affine, blst_p1s_to_affine, blst_p2, blst_p2_affine, blst_p2s_to_affine};
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective};
+use group::{prime::PrimeCurveAffine, Curve, Group as _};
+use rand::RngCore;
+use std::hint::black_box;
+use std::ptr;
+
+
+/// Projective to affine conversion of a whole slice, sharing a single field inversion across all
+/// points (Montgomery's trick).
+///
+/// blstrs only provides group's default `Curve::batch_normalize`, which calls `to_affine()` per
+/// point, so this goes straight to blst's batch conversion.
+pub trait BatchToAffine : Curve {
+    fn batch_to_affine(points: &[Self]) -> Vec<Self::AffineRepr>;
+}
+
+// blst's batch conversion multiplies all z-coordinates together, so a single point at infinity
+// would corrupt every output; points at infinity are skipped and left as the affine identity
+// (the `Default` of blstrs' affine types)
+macro_rules! impl_batch_to_affine {
+    ($projective:ty, $affine:ty, $blst_point:ty, $blst_affine:ty, $blst_batch_fn:ident) => {
+        impl BatchToAffine for $projective {
+            fn batch_to_affine(points: &[Self]) -> Vec<$affine> {
+                let convert = |points: &[Self]| {
+                    let mut out = vec![<$affine>::default(); points.len()];
+                    if !points.is_empty() {
+                        // blst convention: a null second pointer means the first one points to a
+                        // contiguous array
+                        let inputs : [*const $blst_point; 2] = [points.as_ptr() as *const $blst_point, ptr::null()];
+                        // SAFETY: blstrs' projective and affine types are repr(transparent)
+                        // wrappers of the blst types, and `out` holds `points.len()` elements
+                        unsafe { $blst_batch_fn(out.as_mut_ptr() as *mut $blst_affine, inputs.as_ptr(), points.len()) };
+                    }
+                    out
+                };
+
+                if !points.iter().any(|p| bool::from(p.is_identity())) {
+                    return convert(points);
+                }
+                let finite : Vec<usize> = (0..points.len()).filter(|&i| !bool::from(points[i].is_identity())).collect();
+                let converted = convert(&finite.iter().map(|&i| points[i]).collect::<Vec<_>>());
+                let mut out = vec![<$affine>::default(); points.len()];
+                for (i, p) in finite.into_iter().zip(converted) {
+                    out[i] = p;
+                }
+                out
+            }
+        }
+    };
+}
+
+impl_batch_to_affine!(G1Projective, G1Affine, blst_p1, blst_p1_affine, blst_p1s_to_affine);
+impl_batch_to_affine!(G2Projective, G2Affine, blst_p2, blst_p2_affine, blst_p2s_to_affine);
+
+// projective + projective
+struct Additions<T> {
+    lhs: Vec<T>,
+    rhs: Vec<T>,
+}
+
+impl<T> Additions<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        Self { lhs: rng.points(num), rhs: rng.points(num) }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Additions<T>
+where T : Curve + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} additions: {}", T::NAME, self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| *a + b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] + self.rhs[i]));
+    }
+}
+
+// projective + affine, cheaper since the affine z-coordinate is implicitly 1
+struct MixedAdditions<T>
+where T : Curve {
+    lhs: Vec<T>,
+    rhs: Vec<T::AffineRepr>,
+}
+
+impl<T> MixedAdditions<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        let rhs = rng.points::<T>(num).iter().map(T::to_affine).collect();
+        Self { lhs: rng.points(num), rhs }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for MixedAdditions<T>
+where T : Curve + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} mixed additions: {}", T::NAME, self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| *a + b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] + &self.rhs[i]));
+    }
+}
+
+struct Doublings<T> {
+    points: Vec<T>,
+}
+
+impl<T> Doublings<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        Self { points: rng.points(num) }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Doublings<T>
+where T : Curve + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} doublings: {}", T::NAME, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.points.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.points.iter().map(T::double).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.points.len(), |i| self.points[i].double()));
+    }
+}
+
+// one to_affine() (and so one field inversion) per point
+struct AffineConversions<T> {
+    points: Vec<T>,
+}
+
+impl<T> AffineConversions<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        Self { points: rng.points(num) }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for AffineConversions<T>
+where T : Curve + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} affine conversions: {}", T::NAME, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.points.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T::AffineRepr>>(self.points.iter().map(T::to_affine).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.points.len(), |i| self.points[i].to_affine()));
+    }
+}
+
+struct BatchNormalizations<T> {
+    num: usize,
+    points: Vec<T>,
+}
+
+impl<T> BatchNormalizations<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize, size: usize) -> Self {
+        Self { num, points: rng.points(size) }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for BatchNormalizations<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} batch affine conversions: {} of size {}", T::NAME, self.num, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.points.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(T::batch_to_affine(&self.points));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| T::batch_to_affine(&self.points)));
+    }
+}
+
+
+// projective equality: comparing cross-multiplied coordinates, without normalizing
+struct EqualityChecks<T> {
+    lhs: Vec<T>,
+    rhs: Vec<T>,
+}
+
+impl<T> EqualityChecks<T>
+where T : Curve {
+    // equal pairs in different coordinates, as a verifier's checks pass
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        let lhs : Vec<T> = rng.points(num);
+        let rhs = lhs.iter().map(|p| p.double() - p).collect();
+        Self { lhs, rhs }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for EqualityChecks<T>
+where T : Curve + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} equality checks: {}", T::NAME, self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| a == b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] == self.rhs[i]));
+    }
+}
+
+// both sides of `size` checks normalized in one batch, then compared in affine coordinates
+struct AffineEqualityChecks<T> {
+    num: usize,
+    // the left-hand sides, then the right-hand sides
+    points: Vec<T>,
+}
+
+impl<T> AffineEqualityChecks<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize, size: usize) -> Self {
+        let EqualityChecks { mut lhs, rhs } = EqualityChecks::new(rng, size);
+        lhs.extend(rhs);
+        Self { num, points: lhs }
+    }
+
+    fn size(&self) -> usize {
+        self.points.len() / 2
+    }
+}
+
+impl<T> AffineEqualityChecks<T>
+where T : BatchToAffine,
+      T::AffineRepr : PartialEq {
+    fn check(&self) -> Vec<bool> {
+        let affine = T::batch_to_affine(&self.points);
+        let (lhs, rhs) = affine.split_at(self.size());
+        lhs.iter().zip(rhs).map(|(a, b)| a == b).collect()
+    }
+}
+
+impl<T> GroupOpsSimulationItem for AffineEqualityChecks<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : PartialEq + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} affine equality checks: {} of size {}", T::NAME, self.num, self.size())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.size())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.check());
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.check()));
+    }
+}
+
+// projective `is_identity`, a test of the z-coordinate
+struct IdentityChecks<T> {
+    points: Vec<T>,
+}
+
+impl<T> IdentityChecks<T>
+where T : Curve {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        Self { points: rng.points(num) }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for IdentityChecks<T>
+where T : Curve + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} identity checks: {}", T::NAME, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.points.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.points.iter().map(|p| p.is_identity().into()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.points.len(), |i| bool::from(self.points[i].is_identity())));
+    }
+}
+
+// `size` points normalized in one batch, then checked in affine coordinates
+struct AffineIdentityChecks<T> {
+    num: usize,
+    points: Vec<T>,
+}
+
+impl<T> AffineIdentityChecks<T>
+where T : BatchToAffine,
+      T::AffineRepr : PrimeCurveAffine {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize, size: usize) -> Self {
+        Self { num, points: rng.points(size) }
+    }
+
+    fn check(&self) -> Vec<bool> {
+        T::batch_to_affine(&self.points).iter().map(|p| p.is_identity().into()).collect()
+    }
+}
+
+impl<T> GroupOpsSimulationItem for AffineIdentityChecks<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : PrimeCurveAffine + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} affine identity checks: {} of size {}", T::NAME, self.num, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.points.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.check());
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.check()));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    pub fn g1_additions(&mut self, num: usize) -> &mut Self {
+        let item = Additions::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    pub fn g2_additions(&mut self, num: usize) -> &mut Self {
+        let item = Additions::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// Projective plus affine additions.
+    pub fn g1_mixed_additions(&mut self, num: usize) -> &mut Self {
+        let item = MixedAdditions::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// Projective plus affine additions.
+    pub fn g2_mixed_additions(&mut self, num: usize) -> &mut Self {
+        let item = MixedAdditions::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    pub fn g1_doublings(&mut self, num: usize) -> &mut Self {
+        let item = Doublings::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    pub fn g2_doublings(&mut self, num: usize) -> &mut Self {
+        let item = Doublings::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// Individual projective to affine conversions.
+    pub fn g1_affine_conversions(&mut self, num: usize) -> &mut Self {
+        let item = AffineConversions::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// Individual projective to affine conversions.
+    pub fn g2_affine_conversions(&mut self, num: usize) -> &mut Self {
+        let item = AffineConversions::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// `num` batch conversions of `size` projective points to affine.
+    pub fn g1_batch_normalizations(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = BatchNormalizations::<G1Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// `num` batch conversions of `size` projective points to affine.
+    pub fn g2_batch_normalizations(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = BatchNormalizations::<G2Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// Equality checks of projective points.
+    pub fn g1_equality_checks(&mut self, num: usize) -> &mut Self {
+        let item = EqualityChecks::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// Equality checks of projective points.
+    pub fn g2_equality_checks(&mut self, num: usize) -> &mut Self {
+        let item = EqualityChecks::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// `num` batches of `size` equality checks of projective points in affine coordinates,
+    /// normalizing both sides of a batch together.
+    pub fn g1_affine_equality_checks(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = AffineEqualityChecks::<G1Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// `num` batches of `size` equality checks of projective points in affine coordinates,
+    /// normalizing both sides of a batch together.
+    pub fn g2_affine_equality_checks(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = AffineEqualityChecks::<G2Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// `is_identity` on projective points.
+    pub fn g1_identity_checks(&mut self, num: usize) -> &mut Self {
+        let item = IdentityChecks::<G1Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// `is_identity` on projective points.
+    pub fn g2_identity_checks(&mut self, num: usize) -> &mut Self {
+        let item = IdentityChecks::<G2Projective>::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// `num` batches of `size` `is_identity` checks of projective points in affine coordinates,
+    /// normalizing each batch together.
+    pub fn g1_affine_identity_checks(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = AffineIdentityChecks::<G1Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// `num` batches of `size` `is_identity` checks of projective points in affine coordinates,
+    /// normalizing each batch together.
+    pub fn g2_affine_identity_checks(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = AffineIdentityChecks::<G2Projective>::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+}
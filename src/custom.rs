@@ -0,0 +1,46 @@
+//! Items built from user closures, for timing protocol-specific steps (a bespoke share
+//! encoding, a hash over the transcript, ...) in the same report as the group operations.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::report::OpCount;
+
+use rand::RngCore;
+use std::hint::black_box;
+use std::marker::PhantomData;
+
+
+struct Custom<S, F, T> {
+    name: String,
+    state: S,
+    run: F,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<S, F, T> GroupOpsSimulationItem for Custom<S, F, T>
+where S : Send + Sync, F : Fn(&S) -> T + Send + Sync {
+    fn description(&self) -> String {
+        self.name.clone()
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(1)
+    }
+
+    fn simulate(&self) {
+        black_box((self.run)(black_box(&self.state)));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// An item that times `run` on the state built by `setup`, which draws its inputs from the
+    /// simulation's rng (so they follow the seed) and isn't timed:
+    /// `sim.custom("share encoding", |rng| random_shares(rng, n), |shares| encode(shares))`.
+    /// What `run` returns goes through `black_box`, so it isn't optimized away.
+    pub fn custom<S, F, T>(&mut self, name: &str, setup: impl FnOnce(&mut dyn RngCore) -> S, run: F) -> &mut Self
+    where S : Send + Sync + 'static, F : Fn(&S) -> T + Send + Sync + 'static, T : 'static {
+        let state = setup(self.rng());
+        self.push_item(Custom { name: name.to_string(), state, run, output: PhantomData })
+    }
+}
@@ -0,0 +1,265 @@
+//! Chaum–Pedersen proofs of discrete-log equality, made non-interactive with Fiat–Shamir:
+//! given `g, h, a = g^x, b = h^x`, prove that `log_g a = log_h b` without revealing `x`.
+//!
+//! Proofs come in two forms: the usual challenge and response, and the commitments with the
+//! response, which is larger but lets a verifier check many proofs over the same `g` and `h` at
+//! once, with one multi-exp per base and a random linear combination of the proofs.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, MultiExp};
+use crate::hash::hash_to_scalar;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use group::{Group, GroupEncoding};
+use rand::{RngCore, SeedableRng as _};
+use rand_chacha::ChaCha20Rng;
+use std::hint::black_box;
+
+
+const DOMAIN: &[u8] = b"blstrs-pvss-simulation DLEQ v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DleqProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+fn challenge<T>(g: &T, h: &T, a: &T, b: &T, commit_g: &T, commit_h: &T) -> Scalar
+where T : GroupEncoding {
+    let encodings = [g, h, a, b, commit_g, commit_h].map(|p| p.to_bytes());
+    let inputs : Vec<&[u8]> = encodings.iter().map(|e| e.as_ref()).collect();
+    hash_to_scalar(DOMAIN, &inputs)
+}
+
+impl DleqProof {
+    /// Proves `log_g a = log_h b`, given `x` with `a = g^x` and `b = h^x`. Costs two
+    /// exponentiations.
+    pub fn prove<T>(g: &T, h: &T, a: &T, b: &T, x: &Scalar, rng: &mut impl RngCore) -> Self
+    where T : Group<Scalar = Scalar> + GroupEncoding {
+        let w = Scalar::random(rng);
+        let (commit_g, commit_h) = (*g * w, *h * w);
+        let challenge = challenge(g, h, a, b, &commit_g, &commit_h);
+        Self { challenge, response: w + challenge * x }
+    }
+
+    /// Checks a proof that `log_g a = log_h b`. Costs four exponentiations.
+    pub fn verify<T>(&self, g: &T, h: &T, a: &T, b: &T) -> bool
+    where T : Group<Scalar = Scalar> + GroupEncoding {
+        let commit_g = *g * self.response - *a * self.challenge;
+        let commit_h = *h * self.response - *b * self.challenge;
+        challenge(g, h, a, b, &commit_g, &commit_h) == self.challenge
+    }
+
+    /// Same as `verify`, with `g^z a^-c` and `h^z b^-c` as multi-exps of size 2.
+    pub fn verify_multi_exp<T>(&self, g: &T, h: &T, a: &T, b: &T) -> bool
+    where T : Group<Scalar = Scalar> + GroupEncoding + MultiExp {
+        let exponents = [self.response, -self.challenge];
+        let commit_g = T::multi_exp(&[*g, *a], &exponents);
+        let commit_h = T::multi_exp(&[*h, *b], &exponents);
+        challenge(g, h, a, b, &commit_g, &commit_h) == self.challenge
+    }
+}
+
+/// A proof that carries its commitments instead of its challenge, so proofs can be verified in
+/// batches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchableDleqProof<T> {
+    pub commit_g: T,
+    pub commit_h: T,
+    pub response: Scalar,
+}
+
+impl<T> BatchableDleqProof<T>
+where T : Group<Scalar = Scalar> + GroupEncoding + MultiExp
+{
+    /// Same as `DleqProof::prove`.
+    pub fn prove(g: &T, h: &T, a: &T, b: &T, x: &Scalar, rng: &mut impl RngCore) -> Self {
+        let w = Scalar::random(rng);
+        let (commit_g, commit_h) = (*g * w, *h * w);
+        let challenge = challenge(g, h, a, b, &commit_g, &commit_h);
+        Self { commit_g, commit_h, response: w + challenge * x }
+    }
+
+    /// Checks proofs that `log_g a_i = log_h b_i` for all statements `(a_i, b_i)`, as
+    /// `g^(sum r_i z_i) = prod (A_i a_i^c_i)^r_i` and the same for `h`, with random `r_i`: two
+    /// multi-exps of size `2 * n + 1` for `n` proofs.
+    pub fn verify_batch(g: &T, h: &T, statements: &[(T, T)], proofs: &[Self], rng: &mut impl RngCore) -> bool {
+        assert_eq!(statements.len(), proofs.len(), "need one proof per statement");
+        let n = proofs.len();
+        let (mut bases_g, mut bases_h) = (Vec::with_capacity(2 * n + 1), Vec::with_capacity(2 * n + 1));
+        let mut exponents = Vec::with_capacity(2 * n + 1);
+        let mut combined_response = Scalar::ZERO;
+        for ((a, b), proof) in statements.iter().zip(proofs) {
+            let challenge = challenge(g, h, a, b, &proof.commit_g, &proof.commit_h);
+            let r = Scalar::random(&mut *rng);
+            combined_response += r * proof.response;
+            bases_g.extend([proof.commit_g, *a]);
+            bases_h.extend([proof.commit_h, *b]);
+            exponents.extend([-r, -(r * challenge)]);
+        }
+        bases_g.push(*g);
+        bases_h.push(*h);
+        exponents.push(combined_response);
+        bool::from(T::multi_exp(&bases_g, &exponents).is_identity()) && bool::from(T::multi_exp(&bases_h, &exponents).is_identity())
+    }
+}
+
+// random statements `(g^x, h^x)` over G1, with their witnesses
+fn statements<R>(sim: &mut GroupOpsSimulation<R>, num: usize) -> (G1Projective, G1Projective, Vec<(G1Projective, G1Projective)>, Vec<Scalar>)
+where R : RngCore {
+    let (g, h) = (G1Projective::generator(), sim.rng().points::<G1Projective>(1)[0]);
+    let witnesses : Vec<Scalar> = (0..num).map(|_| Scalar::random(&mut *sim.rng())).collect();
+    let statements = witnesses.iter().map(|x| (g * x, h * x)).collect();
+    (g, h, statements, witnesses)
+}
+
+struct Proofs {
+    g: G1Projective,
+    h: G1Projective,
+    statements: Vec<(G1Projective, G1Projective)>,
+    witnesses: Vec<Scalar>,
+}
+
+impl Proofs {
+    fn prove(&self, i: usize) -> DleqProof {
+        let (a, b) = &self.statements[i];
+        // a fixed nonce seed per proof keeps `simulate` free of shared state
+        DleqProof::prove(&self.g, &self.h, a, b, &self.witnesses[i], &mut ChaCha20Rng::seed_from_u64(i as u64))
+    }
+}
+
+impl GroupOpsSimulationItem for Proofs {
+    fn description(&self) -> String {
+        format!("DLEQ proofs: {}", self.statements.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.statements.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<DleqProof>>((0..self.statements.len()).map(|i| self.prove(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.statements.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.prove(i));
+    }
+}
+
+struct Verifications {
+    multi_exps: bool,
+    g: G1Projective,
+    h: G1Projective,
+    statements: Vec<(G1Projective, G1Projective)>,
+    proofs: Vec<DleqProof>,
+}
+
+impl Verifications {
+    fn verify(&self, i: usize) -> bool {
+        let ((a, b), proof) = (&self.statements[i], &self.proofs[i]);
+        if self.multi_exps {
+            proof.verify_multi_exp(&self.g, &self.h, a, b)
+        } else {
+            proof.verify(&self.g, &self.h, a, b)
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for Verifications {
+    fn description(&self) -> String {
+        let method = if self.multi_exps { "2 multi-exps of size 2" } else { "4 exps" };
+        format!("DLEQ verifications ({}): {}", method, self.proofs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.proofs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.proofs.len()).map(|i| self.verify(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.proofs.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+}
+
+struct BatchVerifications {
+    num: usize,
+    g: G1Projective,
+    h: G1Projective,
+    statements: Vec<(G1Projective, G1Projective)>,
+    proofs: Vec<BatchableDleqProof<G1Projective>>,
+}
+
+impl BatchVerifications {
+    fn verify(&self, i: usize) -> bool {
+        BatchableDleqProof::verify_batch(&self.g, &self.h, &self.statements, &self.proofs, &mut ChaCha20Rng::seed_from_u64(i as u64))
+    }
+}
+
+impl GroupOpsSimulationItem for BatchVerifications {
+    fn description(&self) -> String {
+        format!("DLEQ batch verifications: {} of {} proofs", self.num, self.proofs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.proofs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.num).map(|i| self.verify(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` DLEQ proofs over G1, two exps each.
+    pub fn dleq_prove(&mut self, num: usize) -> &mut Self {
+        let (g, h, statements, witnesses) = statements(self, num);
+        self.push_item(Proofs { g, h, statements, witnesses })
+    }
+
+    /// The same `num` DLEQ verifications over G1 with four exps and with two multi-exps of
+    /// size 2, as one item each.
+    pub fn dleq_verify(&mut self, num: usize) -> &mut Self {
+        let (g, h, statements, witnesses) = statements(self, num);
+        let proofs : Vec<DleqProof> = statements.iter().zip(&witnesses)
+            .map(|((a, b), x)| DleqProof::prove(&g, &h, a, b, x, &mut *self.rng()))
+            .collect();
+        self.group(|sim| {
+            for multi_exps in [false, true] {
+                sim.push_item(Verifications { multi_exps, g, h, statements: statements.clone(), proofs: proofs.clone() });
+            }
+        })
+    }
+
+    /// `num` batch verifications of `size` DLEQ proofs over the same `g` and `h` in G1, two
+    /// multi-exps of size `2 * size + 1` each.
+    pub fn dleq_batch_verify(&mut self, num: usize, size: usize) -> &mut Self {
+        let (g, h, statements, witnesses) = statements(self, size);
+        let proofs = statements.iter().zip(&witnesses)
+            .map(|((a, b), x)| BatchableDleqProof::prove(&g, &h, a, b, x, &mut *self.rng()))
+            .collect();
+        self.push_item(BatchVerifications { num, g, h, statements, proofs })
+    }
+}
@@ -0,0 +1,132 @@
+//! ElGamal encryption in G1 of messages that are themselves group elements, as PVSS dealers
+//! encrypt shares to the receivers' public keys `pk_i = g^sk_i`.
+//!
+//! With independent randomness, the ciphertext for receiver `i` is `(g^r_i, M_i * pk_i^r_i)`:
+//! two exponentiations per receiver. Multi-receiver ElGamal shares one `r` across all
+//! receivers, `(g^r, M_1 * pk_1^r, ..., M_n * pk_n^r)`, for one exponentiation per receiver and
+//! one overall. Decryption is `c_i / c_0^sk_i`, one exponentiation.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// The ciphertexts of one encryption to several receivers; `randomness` has one element per
+/// receiver or, with shared randomness, a single one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertexts {
+    pub randomness: Vec<G1Projective>,
+    pub messages: Vec<G1Projective>,
+}
+
+/// Encrypts `messages[i]` to `keys[i]` with the randomness `r[i]`, or `r[0]` for every
+/// receiver if `r` has a single element.
+pub fn encrypt(keys: &[G1Projective], messages: &[G1Projective], r: &[Scalar]) -> Ciphertexts {
+    assert_eq!(keys.len(), messages.len(), "need one message per key");
+    assert!(r.len() == 1 || r.len() == keys.len(), "need one random scalar, or one per key");
+    let g = G1Projective::generator();
+    Ciphertexts {
+        randomness: r.iter().map(|r| g * r).collect(),
+        messages: keys.iter().zip(messages).enumerate().map(|(i, (key, message))| message + key * r[i % r.len()]).collect(),
+    }
+}
+
+/// The message encrypted to receiver `i`, whose secret key is `sk`.
+pub fn decrypt(ciphertexts: &Ciphertexts, i: usize, sk: &Scalar) -> G1Projective {
+    let randomness = &ciphertexts.randomness[i % ciphertexts.randomness.len()];
+    ciphertexts.messages[i] - randomness * sk
+}
+
+struct Encryptions {
+    num: usize,
+    keys: Vec<G1Projective>,
+    messages: Vec<G1Projective>,
+    r: Vec<Scalar>,
+}
+
+impl GroupOpsSimulationItem for Encryptions {
+    fn description(&self) -> String {
+        let randomness = if self.r.len() == 1 && self.keys.len() > 1 { "shared" } else { "independent" };
+        format!("ElGamal encryptions ({} randomness): {} to {} receivers", randomness, self.num, self.keys.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.keys.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(encrypt(&self.keys, &self.messages, &self.r));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| encrypt(&self.keys, &self.messages, &self.r)));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(encrypt(&self.keys, &self.messages, &self.r));
+    }
+}
+
+struct Decryptions {
+    ciphertexts: Ciphertexts,
+    keys: Vec<Scalar>,
+}
+
+impl GroupOpsSimulationItem for Decryptions {
+    fn description(&self) -> String {
+        format!("ElGamal decryptions: {}", self.keys.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.keys.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<G1Projective>>(self.keys.iter().enumerate().map(|(i, sk)| decrypt(&self.ciphertexts, i, sk)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.keys.len(), |i| decrypt(&self.ciphertexts, i, &self.keys[i])));
+    }
+
+    fn ops(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(decrypt(&self.ciphertexts, i, &self.keys[i]));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` encryptions of one message each to `receivers` public keys, as a PVSS dealer
+    /// encrypts the shares; with `shared_randomness`, as multi-receiver ElGamal.
+    pub fn elgamal_encryptions(&mut self, num: usize, receivers: usize, shared_randomness: bool) -> &mut Self {
+        let keys = self.rng().points(receivers);
+        let messages = self.rng().points(receivers);
+        let r = (0..if shared_randomness { 1 } else { receivers }).map(|_| Scalar::random(&mut *self.rng())).collect();
+        self.push_item(Encryptions { num, keys, messages, r })
+    }
+
+    /// `num` receivers decrypting their message, one exponentiation each.
+    pub fn elgamal_decryptions(&mut self, num: usize) -> &mut Self {
+        let keys : Vec<Scalar> = (0..num).map(|_| Scalar::random(&mut *self.rng())).collect();
+        let ciphertexts = Ciphertexts { randomness: self.rng().points(num), messages: self.rng().points(num) };
+        self.push_item(Decryptions { ciphertexts, keys })
+    }
+}
@@ -0,0 +1,319 @@
+//! Energy per item, with the `energy-measurement` feature: a `Meter` reads the processor's energy
+//! counters before and after every item (`GroupOpsSimulation::measure_energy`), so reports show
+//! the joules of each item and phase next to its time.
+//!
+//! On Linux the counters are RAPL's, through the powercap interface
+//! (`/sys/class/powercap/intel-rapl:N/energy_uj`, one per package, also on AMD), which since
+//! Linux 5.10 only root can read. On macOS, `powermetrics` (also root) samples the CPU power every
+//! `POWERMETRICS_INTERVAL_MS` in a child process, and an item is charged the power of the samples
+//! it overlaps. Either way the figures are estimates: they count the whole package, including
+//! other processes and idle power, and items much shorter than the counters' resolution (about
+//! a millisecond for RAPL, the sampling interval for powermetrics) come out noisy, which
+//! repetitions average out. Without the feature, `Meter::open` fails.
+
+use crate::clock::Instant;
+
+use std::fmt;
+
+
+/// How often powermetrics samples.
+pub const POWERMETRICS_INTERVAL_MS : u64 = 20;
+
+#[derive(Debug)]
+pub enum EnergyError {
+    Io(std::io::Error),
+    /// built without the `energy-measurement` feature
+    NotBuilt,
+    /// no RAPL counters (Linux) or no powermetrics (macOS)
+    NoCounters,
+    /// the counters exist but can't be read
+    PermissionDenied(String),
+    Invalid(String),
+}
+
+impl fmt::Display for EnergyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnergyError::Io(e) => write!(f, "{}", e),
+            EnergyError::NotBuilt => write!(f, "measuring energy needs a build with the energy-measurement feature (cargo build --features energy-measurement)"),
+            EnergyError::NoCounters => write!(f, "no energy counters found (RAPL under /sys/class/powercap on Linux, powermetrics on macOS)"),
+            EnergyError::PermissionDenied(what) => write!(f, "can't read {} (usually needs root)", what),
+            EnergyError::Invalid(message) => write!(f, "invalid energy reading: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EnergyError {}
+
+impl From<std::io::Error> for EnergyError {
+    fn from(e: std::io::Error) -> Self {
+        EnergyError::Io(e)
+    }
+}
+
+/// The counters at one point in time, for `Meter::joules`.
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "energy-measurement"), allow(dead_code))]
+pub struct Reading {
+    at: Instant,
+    /// microjoules per RAPL domain; empty for powermetrics
+    counters: Vec<u64>,
+}
+
+/// A source of energy readings.
+pub struct Meter {
+    source: Source,
+}
+
+impl Meter {
+    /// RAPL's counters on Linux, powermetrics on macOS.
+    pub fn open() -> Result<Self, EnergyError> {
+        Source::open().map(|source| Self { source })
+    }
+
+    /// `RAPL` or `powermetrics`.
+    pub fn name(&self) -> &'static str {
+        self.source.name()
+    }
+
+    pub fn read(&self) -> Reading {
+        Reading { at: Instant::now(), counters: self.source.counters() }
+    }
+
+    /// The energy used between two readings, in joules.
+    pub fn joules(&self, from: &Reading, to: &Reading) -> f64 {
+        self.source.joules(from, to)
+    }
+}
+
+/// `3.456 J (Dealer 1.234 J, Verifier 2.222 J)`: a run's joules and those of its phases.
+pub fn summary(total: f64, phases: &[(String, f64)]) -> String {
+    let phases : Vec<String> = phases.iter().map(|(name, joules)| format!("{} {:.3} J", name, joules)).collect();
+    match phases.is_empty() {
+        true => format!("{:.3} J", total),
+        false => format!("{:.3} J ({})", total, phases.join(", ")),
+    }
+}
+
+#[cfg(not(feature = "energy-measurement"))]
+enum Source {}
+
+#[cfg(not(feature = "energy-measurement"))]
+impl Source {
+    fn open() -> Result<Self, EnergyError> {
+        Err(EnergyError::NotBuilt)
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {}
+    }
+
+    fn counters(&self) -> Vec<u64> {
+        match *self {}
+    }
+
+    fn joules(&self, _from: &Reading, _to: &Reading) -> f64 {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "energy-measurement")]
+enum Source {
+    Rapl(Vec<rapl::Domain>),
+    Powermetrics(powermetrics::Sampler),
+}
+
+#[cfg(feature = "energy-measurement")]
+impl Source {
+    fn open() -> Result<Self, EnergyError> {
+        if cfg!(target_os = "macos") {
+            powermetrics::Sampler::spawn().map(Source::Powermetrics)
+        } else {
+            rapl::domains().map(Source::Rapl)
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Source::Rapl(_) => "RAPL",
+            Source::Powermetrics(_) => "powermetrics",
+        }
+    }
+
+    fn counters(&self) -> Vec<u64> {
+        match self {
+            // a counter that fails to read mid-run counts as unchanged
+            Source::Rapl(domains) => domains.iter().map(|domain| domain.read().unwrap_or(0)).collect(),
+            Source::Powermetrics(_) => Vec::new(),
+        }
+    }
+
+    fn joules(&self, from: &Reading, to: &Reading) -> f64 {
+        match self {
+            Source::Rapl(domains) => domains.iter().zip(from.counters.iter().zip(&to.counters))
+                .map(|(domain, (&before, &after))| domain.microjoules(before, after) as f64 / 1e6)
+                .sum(),
+            Source::Powermetrics(sampler) => sampler.joules(from.at, to.at),
+        }
+    }
+}
+
+#[cfg(feature = "energy-measurement")]
+mod rapl {
+    use super::EnergyError;
+
+    use std::path::{Path, PathBuf};
+
+    const POWERCAP : &str = "/sys/class/powercap";
+
+    /// A package's counter, which wraps around at `range`.
+    pub(super) struct Domain {
+        energy: PathBuf,
+        range: u64,
+    }
+
+    impl Domain {
+        pub(super) fn read(&self) -> Result<u64, EnergyError> {
+            read_number(&self.energy)
+        }
+
+        pub(super) fn microjoules(&self, before: u64, after: u64) -> u64 {
+            match after >= before {
+                true => after - before,
+                false => after + (self.range - before),
+            }
+        }
+    }
+
+    fn read_number(path: &Path) -> Result<u64, EnergyError> {
+        let text = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => EnergyError::PermissionDenied(path.display().to_string()),
+            _ => EnergyError::Io(e),
+        })?;
+        text.trim().parse().map_err(|_| EnergyError::Invalid(format!("`{}` in {}", text.trim(), path.display())))
+    }
+
+    /// The package domains (`intel-rapl:N` named `package-N`); their subdomains (cores, uncore,
+    /// DRAM) and the platform domain overlap them.
+    pub(super) fn domains() -> Result<Vec<Domain>, EnergyError> {
+        let entries = match std::fs::read_dir(POWERCAP) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(EnergyError::NoCounters),
+            Err(e) => return Err(e.into()),
+        };
+        let mut dirs : Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("intel-rapl:") && name.matches(':').count() == 1))
+            .filter(|path| std::fs::read_to_string(path.join("name")).is_ok_and(|name| name.starts_with("package")))
+            .collect();
+        dirs.sort();
+        if dirs.is_empty() {
+            return Err(EnergyError::NoCounters);
+        }
+        dirs.into_iter()
+            .map(|dir| {
+                let domain = Domain { energy: dir.join("energy_uj"), range: read_number(&dir.join("max_energy_range_uj"))? };
+                domain.read()?;
+                Ok(domain)
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "energy-measurement")]
+mod powermetrics {
+    use super::{EnergyError, POWERMETRICS_INTERVAL_MS};
+    use crate::clock::Instant;
+
+    use std::io::{BufRead as _, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// A running `powermetrics` and the power of every sample it printed so far, each covering
+    /// the interval before it arrived.
+    pub(super) struct Sampler {
+        child: Child,
+        samples: Arc<Mutex<Vec<(Instant, f64)>>>,
+    }
+
+    // the CPU power in watts, from `CPU Power: 1234 mW` (Apple silicon) or
+    // `Intel energy model derived package power (CPUs+GT+SA): 1.23W`
+    fn watts(line: &str) -> Option<f64> {
+        let value = match line.strip_prefix("CPU Power:") {
+            Some(value) => value,
+            None if line.contains("package power") => line.rsplit(':').next()?,
+            None => return None,
+        };
+        let value = value.trim();
+        match value.strip_suffix("mW") {
+            Some(milliwatts) => milliwatts.trim().parse::<f64>().ok().map(|mw| mw / 1e3),
+            None => value.strip_suffix('W')?.trim().parse().ok(),
+        }
+    }
+
+    impl Sampler {
+        pub(super) fn spawn() -> Result<Self, EnergyError> {
+            let mut child = Command::new("powermetrics")
+                .args(["--samplers", "cpu_power", "-i", &POWERMETRICS_INTERVAL_MS.to_string()])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => EnergyError::NoCounters,
+                    _ => EnergyError::Io(e),
+                })?;
+            let stdout = child.stdout.take().unwrap();
+            let samples = Arc::new(Mutex::new(Vec::new()));
+            let received = samples.clone();
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if let Some(watts) = watts(&line) {
+                        received.lock().unwrap().push((Instant::now(), watts));
+                    }
+                }
+            });
+            let sampler = Self { child, samples };
+            // the first sample, or powermetrics refusing to run without root
+            if !sampler.wait_for(Instant::now(), Duration::from_secs(2)) {
+                return Err(EnergyError::PermissionDenied("powermetrics samples".to_string()));
+            }
+            Ok(sampler)
+        }
+
+        // waits until a sample after `at` has arrived, for `limit` at most
+        fn wait_for(&self, at: Instant, limit: Duration) -> bool {
+            let interval = Duration::from_millis(POWERMETRICS_INTERVAL_MS);
+            let deadline = Instant::now() + limit;
+            while Instant::now() < deadline {
+                if self.samples.lock().unwrap().last().is_some_and(|&(time, _)| time >= at) {
+                    return true;
+                }
+                std::thread::sleep(interval / 4);
+            }
+            false
+        }
+
+        pub(super) fn joules(&self, from: Instant, to: Instant) -> f64 {
+            let interval = Duration::from_millis(POWERMETRICS_INTERVAL_MS);
+            self.wait_for(to, 10 * interval);
+            let mut samples = self.samples.lock().unwrap();
+            // later readings start after this one, so the samples that ended before it are done
+            samples.retain(|&(end, _)| end > from);
+            samples.iter()
+                .map(|&(end, watts)| {
+                    let start = end - interval;
+                    let overlap = end.min(to).saturating_duration_since(start.max(from));
+                    watts * overlap.as_secs_f64()
+                })
+                .sum()
+        }
+    }
+
+    impl Drop for Sampler {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
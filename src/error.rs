@@ -0,0 +1,33 @@
+//! Errors of the fallible builder API (`GroupOpsSimulation::try_op` and `try_workload`), which
+//! checks ops before building their inputs instead of panicking halfway, or silently timing
+//! nonsense such as multi-exps of no bases.
+
+use crate::communication::format_bytes;
+
+use std::fmt;
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    /// an op whose size, or one of whose parameters, doesn't fit its kind: multi-exps of size
+    /// 0, a size for an unsized kind, an FFT of a size that isn't a power of two, ...
+    InvalidSize { op: String, message: String },
+    /// a derived count, e.g. the `k*n*l + l + 1` bases of a preset, or the bytes of an op's
+    /// inputs, that doesn't fit its type
+    Overflow(String),
+    /// the estimated bytes of the inputs exceed the memory limit
+    OutOfMemoryEstimate { estimate: usize, limit: usize },
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::InvalidSize { op, message } => write!(f, "{}: {}", op, message),
+            SimulationError::Overflow(message) => write!(f, "overflow: {}", message),
+            SimulationError::OutOfMemoryEstimate { estimate, limit } =>
+                write!(f, "the inputs would take an estimated {}, more than the limit of {}", format_bytes(*estimate), format_bytes(*limit)),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
@@ -0,0 +1,104 @@
+//! Single exponentiations computed with different algorithms, to pin down which one the per-op
+//! cost of "one exponentiation" stands for: plain double-and-add, wNAF with a window table built
+//! per exponentiation, and blstrs' own `Mul` (blst's constant-time ladder).
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::{Group, Wnaf, WnafGroup};
+use rand::RngCore;
+use std::hint::black_box;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExpMethod {
+    /// one doubling per scalar bit and one addition per set bit
+    DoubleAndAdd,
+    /// group's wNAF, with the window size it recommends for a single scalar
+    Wnaf,
+    /// blstrs' `base * scalar`
+    Default,
+}
+
+impl ExpMethod {
+    pub const ALL: [ExpMethod; 3] = [ExpMethod::DoubleAndAdd, ExpMethod::Wnaf, ExpMethod::Default];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExpMethod::DoubleAndAdd => "double-and-add",
+            ExpMethod::Wnaf => "wNAF",
+            ExpMethod::Default => "blstrs default",
+        }
+    }
+}
+
+pub fn exp<T>(base: &T, scalar: &Scalar, method: ExpMethod) -> T
+where T : Group<Scalar = Scalar> + WnafGroup {
+    match method {
+        ExpMethod::DoubleAndAdd => {
+            let bytes = scalar.to_bytes_le();
+            let mut acc = T::identity();
+            for bit in (0..Scalar::NUM_BITS as usize).rev() {
+                acc = acc.double();
+                if (bytes[bit / 8] >> (bit % 8)) & 1 == 1 {
+                    acc += base;
+                }
+            }
+            acc
+        }
+        ExpMethod::Wnaf => Wnaf::new().scalar(scalar).base(*base),
+        ExpMethod::Default => *base * scalar,
+    }
+}
+
+struct MethodExps<T> {
+    method: ExpMethod,
+    bases: Vec<T>,
+    scalars: Vec<Scalar>,
+}
+
+impl<T> GroupOpsSimulationItem for MethodExps<T>
+where T : Group<Scalar = Scalar> + WnafGroup + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} exps ({}): {}", T::NAME, self.method.name(), self.bases.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.bases.iter().zip(&self.scalars).map(|(base, scalar)| exp(base, scalar, self.method)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.bases.len(), |i| exp(&self.bases[i], &self.scalars[i], self.method)));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn add_exp_methods<T>(&mut self, num: usize) -> &mut Self
+    where T : Group<Scalar = Scalar> + WnafGroup + NamedGroup + Send + Sync + 'static {
+        let bases : Vec<T> = self.rng().points(num);
+        let scalars : Vec<Scalar> = (0..num).map(|_| Scalar::random(&mut *self.rng())).collect();
+        self.group(|sim| {
+            for method in ExpMethod::ALL {
+                sim.push_item(MethodExps { method, bases: bases.clone(), scalars: scalars.clone() });
+            }
+        })
+    }
+
+    /// The same `num` G1 exponentiations once with every `ExpMethod`, as one item each.
+    pub fn g1_exp_methods(&mut self, num: usize) -> &mut Self {
+        self.add_exp_methods::<G1Projective>(num)
+    }
+    /// Same as `g1_exp_methods`, in G2.
+    pub fn g2_exp_methods(&mut self, num: usize) -> &mut Self {
+        self.add_exp_methods::<G2Projective>(num)
+    }
+}
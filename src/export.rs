@@ -0,0 +1,118 @@
+//! The timings of a run in the formats of other benchmark tools, so their tooling can read them
+//! as it is: Criterion's directory of `estimates.json` files, which `critcmp` compares, and the
+//! `cargo bench` (libtest) text that CI benchmark dashboards parse.
+//!
+//! Criterion bootstraps a confidence interval for every estimate. Only the mean gets one here, a
+//! normal approximation from the standard error; the median, median absolute deviation and
+//! standard deviation are exact for the samples, with an interval of just the point.
+
+use crate::baseline::Baseline;
+use crate::json::Value;
+use crate::stats::Summary;
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+
+/// Writes every timing of `results` as a Criterion benchmark under `dir` (e.g.
+/// `target/criterion`): `dir/BENCHMARK/BASELINE/benchmark.json` and `estimates.json`, with
+/// Criterion's file-name-safe directory names. Returns how many it wrote.
+pub fn write_criterion(results: &Baseline, dir: impl AsRef<Path>, baseline: &str) -> std::io::Result<usize> {
+    let mut directories = HashSet::new();
+    for (name, summary) in &results.summaries {
+        let directory = unique_directory(name, &mut directories);
+        let path = dir.as_ref().join(&directory).join(baseline);
+        std::fs::create_dir_all(&path)?;
+        std::fs::write(path.join("benchmark.json"), benchmark_json(name, &directory))?;
+        std::fs::write(path.join("estimates.json"), estimates_json(summary))?;
+    }
+    Ok(results.summaries.len())
+}
+
+// as Criterion names them: unsafe characters replaced, at most 64 bytes, and numbered if taken
+fn unique_directory(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut safe = name.replace(['?', '"', '/', '\\', '*', '<', '>', ':', '|', '^'], "_");
+    let mut end = safe.len().min(64);
+    while !safe.is_char_boundary(end) {
+        end -= 1;
+    }
+    safe.truncate(end);
+    let safe = safe.trim().to_string();
+    let mut unique = safe.clone();
+    let mut i = 1;
+    while !taken.insert(unique.clone()) {
+        i += 1;
+        unique = format!("{}_{}", safe, i);
+    }
+    unique
+}
+
+fn benchmark_json(name: &str, directory: &str) -> String {
+    let string = |s: &str| Value::String(s.to_string());
+    Value::Object(vec![
+        ("group_id".to_string(), string(name)),
+        ("function_id".to_string(), Value::Null),
+        ("value_str".to_string(), Value::Null),
+        ("throughput".to_string(), Value::Null),
+        ("full_id".to_string(), string(name)),
+        ("directory_name".to_string(), string(directory)),
+        ("title".to_string(), string(name)),
+    ]).to_pretty_string()
+}
+
+fn nanos(duration: Duration) -> f64 {
+    duration.as_nanos() as f64
+}
+
+// in nanoseconds, as Criterion's
+fn estimate(point: f64, standard_error: f64) -> Value {
+    let margin = 1.96 * standard_error;
+    Value::Object(vec![
+        ("confidence_interval".to_string(), Value::Object(vec![
+            ("confidence_level".to_string(), Value::Number(0.95)),
+            ("lower_bound".to_string(), Value::Number((point - margin).max(0.0))),
+            ("upper_bound".to_string(), Value::Number(point + margin)),
+        ])),
+        ("point_estimate".to_string(), Value::Number(point)),
+        ("standard_error".to_string(), Value::Number(standard_error)),
+    ])
+}
+
+fn estimates_json(summary: &Summary) -> String {
+    let standard_error = nanos(summary.stddev) / (summary.count as f64).sqrt();
+    Value::Object(vec![
+        ("mean".to_string(), estimate(nanos(summary.mean), standard_error)),
+        ("median".to_string(), estimate(nanos(summary.median), 0.0)),
+        ("median_abs_dev".to_string(), estimate(nanos(summary.median_abs_dev), 0.0)),
+        ("slope".to_string(), Value::Null),
+        ("std_dev".to_string(), estimate(nanos(summary.stddev), 0.0)),
+    ]).to_pretty_string()
+}
+
+/// Every timing of `results` as a line of `cargo bench` output, as libtest reports it: the
+/// median in nanoseconds and, after `+/-`, the range from the fastest to the slowest run.
+/// Whitespace in the names becomes `_`, since parsers of the format split on it.
+pub fn bencher(results: &Baseline) -> String {
+    let mut out = String::new();
+    for (name, summary) in &results.summaries {
+        let name : String = name.chars().map(|c| if c.is_whitespace() { '_' } else { c }).collect();
+        let range = summary.max - summary.min;
+        writeln!(out, "test {} ... bench: {:>14} ns/iter (+/- {})", name, thousands(summary.median.as_nanos()), thousands(range.as_nanos())).unwrap();
+    }
+    out
+}
+
+// with commas between groups of three digits, as libtest prints them
+fn thousands(n: u128) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
@@ -0,0 +1,150 @@
+//! Feldman commitments `C_j = g^{a_j}` to the coefficients of a degree-`t` polynomial `p`, the
+//! building block of every verifiable secret sharing here. Committing costs `t + 1` fixed-base
+//! exps of `g`; checking a share against the commitments is one multi-exp of size `t + 1`,
+//! `prod_j C_j^{i^j}`, compared to the share in the exponent, `g^{p(i)}`, as PVSS verifiers see
+//! it. A verifier holding the share itself would add one fixed-base exp.
+
+use crate::curve_ops::BatchToAffine;
+use crate::fixed_base::FixedBaseTable;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, MultiExp, NamedGroup};
+use crate::polynomial::horner;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field as _;
+use group::Curve;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// Commitments to `coeffs` under the base of `table`.
+pub fn commit<T>(table: &FixedBaseTable<T>, coeffs: &[Scalar]) -> Vec<T>
+where T : BatchToAffine {
+    coeffs.iter().map(|a| table.mul(a)).collect()
+}
+
+/// `1, i, i^2, ..., i^t` for `t + 1` commitments.
+pub fn powers(i: u64, num: usize) -> Vec<Scalar> {
+    let i = Scalar::from(i);
+    std::iter::successors(Some(Scalar::ONE), |power| Some(power * i)).take(num).collect()
+}
+
+/// Whether `g^{p(i)}` is consistent with the commitments to the coefficients of `p`.
+pub fn check_share<T>(commitments: &[T], i: u64, share: &T) -> bool
+where T : Curve + MultiExp {
+    T::multi_exp(commitments, &powers(i, commitments.len())) == *share
+}
+
+struct Commitments<T>
+where T : Curve {
+    table: FixedBaseTable<T>,
+    polys: Vec<Vec<Scalar>>,
+}
+
+impl<T> GroupOpsSimulationItem for Commitments<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} Feldman commitments ({}-bit windows): {} of degree {}", T::NAME, self.table.window_bits(), self.polys.len(), self.polys[0].len() - 1)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.polys.len(), self.polys[0].len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Vec<T>>>(self.polys.iter().map(|coeffs| commit(&self.table, coeffs)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.polys.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(commit(&self.table, &self.polys[i]));
+    }
+}
+
+struct ShareChecks<T> {
+    commitments: Vec<T>,
+    // `(i, g^{p(i)})` for parties `i = 1..=num`
+    shares: Vec<(u64, T)>,
+}
+
+impl<T> GroupOpsSimulationItem for ShareChecks<T>
+where T : Curve + MultiExp + NamedGroup + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} Feldman share checks: {} of degree {}", T::NAME, self.shares.len(), self.commitments.len() - 1)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.shares.len(), self.commitments.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.shares.iter().map(|(i, share)| check_share(&self.commitments, *i, share)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.shares.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        let (index, share) = &self.shares[i];
+        black_box(check_share(&self.commitments, *index, share));
+    }
+}
+
+fn random_polys<R>(sim: &mut GroupOpsSimulation<R>, num: usize, t: usize) -> Vec<Vec<Scalar>>
+where R : RngCore {
+    (0..num).map(|_| (0..=t).map(|_| Scalar::random(&mut *sim.rng())).collect()).collect()
+}
+
+fn commitments<T, R>(sim: &mut GroupOpsSimulation<R>, num: usize, t: usize, window_bits: usize) -> &mut GroupOpsSimulation<R>
+where T : BatchToAffine + NamedGroup + Send + Sync + 'static,
+      T::AffineRepr : Send + Sync,
+      R : RngCore {
+    let polys = random_polys(sim, num, t);
+    sim.group(|sim| {
+        sim.fixed_base_table_build(T::generator(), window_bits)
+            .push_item(Commitments { table: FixedBaseTable::new(T::generator(), window_bits), polys });
+    })
+}
+
+fn share_checks<T, R>(sim: &mut GroupOpsSimulation<R>, num: usize, t: usize) -> &mut GroupOpsSimulation<R>
+where T : BatchToAffine + MultiExp + NamedGroup + Send + Sync + 'static,
+      R : RngCore {
+    let coeffs = random_polys(sim, 1, t).remove(0);
+    // setup only; the window size doesn't matter
+    let table = FixedBaseTable::new(T::generator(), 8);
+    let shares = (1..=num as u64).map(|i| (i, table.mul(&horner(&coeffs, &Scalar::from(i))))).collect();
+    sim.push_item(ShareChecks { commitments: commit(&table, &coeffs), shares })
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` commitments to degree-`t` polynomials in G1, with fixed-base exps on a
+    /// `window_bits` table of the generator. As for `fixed_base_exps`, building the table is an
+    /// item of its own.
+    pub fn feldman_commitments(&mut self, num: usize, t: usize, window_bits: usize) -> &mut Self {
+        commitments::<G1Projective, R>(self, num, t, window_bits)
+    }
+
+    /// Same as `feldman_commitments`, in G2.
+    pub fn g2_feldman_commitments(&mut self, num: usize, t: usize, window_bits: usize) -> &mut Self {
+        commitments::<G2Projective, R>(self, num, t, window_bits)
+    }
+
+    /// Checks of the shares of parties `1..=num` against commitments to one degree-`t`
+    /// polynomial in G1.
+    pub fn feldman_share_checks(&mut self, num: usize, t: usize) -> &mut Self {
+        share_checks::<G1Projective, R>(self, num, t)
+    }
+
+    /// Same as `feldman_share_checks`, in G2.
+    pub fn g2_feldman_share_checks(&mut self, num: usize, t: usize) -> &mut Self {
+        share_checks::<G2Projective, R>(self, num, t)
+    }
+}
@@ -0,0 +1,282 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::Scalar;
+use ff::{BatchInvert as _, Field as _};
+use rand::RngCore;
+use std::hint::black_box;
+
+
+fn random_scalars(mut rng: &mut impl RngCore, num: usize) -> Vec<Scalar> {
+    (0..num).map(|_| Scalar::random(&mut rng)).collect()
+}
+
+struct ScalarMuls {
+    lhs: Vec<Scalar>,
+    rhs: Vec<Scalar>,
+}
+
+impl ScalarMuls {
+    fn new(rng: &mut impl RngCore, num: usize) -> Self {
+        Self {
+            lhs: random_scalars(rng, num),
+            rhs: random_scalars(rng, num),
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for ScalarMuls {
+    fn description(&self) -> String {
+        format!("scalar muls: {}", self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Scalar>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| a * b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] * self.rhs[i]));
+    }
+}
+
+struct ScalarAdds {
+    lhs: Vec<Scalar>,
+    rhs: Vec<Scalar>,
+}
+
+impl ScalarAdds {
+    fn new(rng: &mut impl RngCore, num: usize) -> Self {
+        Self {
+            lhs: random_scalars(rng, num),
+            rhs: random_scalars(rng, num),
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for ScalarAdds {
+    fn description(&self) -> String {
+        format!("scalar adds: {}", self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Scalar>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| a + b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] + self.rhs[i]));
+    }
+}
+
+struct ScalarInversions {
+    args: Vec<Scalar>,
+}
+
+impl ScalarInversions {
+    fn new(rng: &mut impl RngCore, num: usize) -> Self {
+        Self { args: random_scalars(rng, num) }
+    }
+}
+
+impl GroupOpsSimulationItem for ScalarInversions {
+    fn description(&self) -> String {
+        format!("scalar inversions: {}", self.args.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.args.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Scalar>>(self.args.iter().map(|x| x.invert().unwrap()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.args.len(), |i| self.args[i].invert().unwrap()));
+    }
+}
+
+// Montgomery's trick: one inversion plus about 3 multiplications per element
+struct BatchInversions {
+    num: usize,
+    args: Vec<Scalar>,
+}
+
+impl BatchInversions {
+    fn new(rng: &mut impl RngCore, num: usize, size: usize) -> Self {
+        Self { num, args: random_scalars(rng, size) }
+    }
+
+    fn batch_invert(&self) -> Vec<Scalar> {
+        // inversion is in place, so work on a copy; copying is negligible next to the field ops
+        let mut args = self.args.clone();
+        args.iter_mut().batch_invert();
+        args
+    }
+}
+
+impl GroupOpsSimulationItem for BatchInversions {
+    fn description(&self) -> String {
+        format!("scalar batch inversions: {} of size {}", self.num, self.args.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.args.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.batch_invert());
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.batch_invert()));
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BatchOp {
+    /// the Lagrange coefficients at 0 of all points 1..=size
+    LagrangeCoefficients,
+    /// x^0, ..., x^(size - 1)
+    Powers,
+    InnerProduct,
+}
+
+// each batch op computed with per-element loops as written from the formulas, or the way an
+// optimized verifier does it
+struct ScalarBatchOps {
+    op: BatchOp,
+    batched: bool,
+    num: usize,
+    x: Scalar,
+    lhs: Vec<Scalar>,
+    rhs: Vec<Scalar>,
+}
+
+impl ScalarBatchOps {
+    fn new(rng: &mut impl RngCore, op: BatchOp, batched: bool, num: usize, size: usize) -> Self {
+        Self { op, batched, num, x: Scalar::random(&mut *rng), lhs: random_scalars(rng, size), rhs: random_scalars(rng, size) }
+    }
+
+    fn size(&self) -> usize {
+        self.lhs.len()
+    }
+
+    fn compute(&self) -> Vec<Scalar> {
+        match (self.op, self.batched) {
+            (BatchOp::LagrangeCoefficients, false) => lagrange_coefficients_one_by_one(self.size()),
+            (BatchOp::LagrangeCoefficients, true) => lagrange_coefficients_of_range(self.size()),
+            (BatchOp::Powers, false) => (0..self.size() as u64).map(|i| self.x.pow_vartime([i])).collect(),
+            (BatchOp::Powers, true) => std::iter::successors(Some(Scalar::ONE), |power| Some(power * self.x)).take(self.size()).collect(),
+            (BatchOp::InnerProduct, false) => {
+                let mut sum = Scalar::ZERO;
+                for i in 0..self.size() {
+                    sum += self.lhs[i] * self.rhs[i];
+                }
+                vec![sum]
+            }
+            (BatchOp::InnerProduct, true) => vec![self.lhs.iter().zip(&self.rhs).map(|(a, b)| a * b).sum()],
+        }
+    }
+}
+
+// λ_i = ∏_{j≠i} j / (j - i) for the points 1..=n, with one inversion per coefficient
+fn lagrange_coefficients_one_by_one(n: usize) -> Vec<Scalar> {
+    (1..=n as u64)
+        .map(|i| {
+            let (numerator, denominator) = (1..=n as u64)
+                .filter(|&j| j != i)
+                .fold((Scalar::ONE, Scalar::ONE), |(num, den), j| (num * Scalar::from(j), den * (Scalar::from(j) - Scalar::from(i))));
+            numerator * denominator.invert().unwrap()
+        })
+        .collect()
+}
+
+/// The Lagrange coefficients at 0 of the points 1..=n in O(n): for consecutive points they are
+/// `λ_i = (-1)^(i+1) C(n, i)`, built up as `C(n, i) = C(n, i - 1) (n - i + 1) / i` with the
+/// inverses of 1..=n from one batch inversion.
+fn lagrange_coefficients_of_range(n: usize) -> Vec<Scalar> {
+    let mut inverses : Vec<Scalar> = (1..=n as u64).map(Scalar::from).collect();
+    inverses.iter_mut().batch_invert();
+    let mut binomial = Scalar::ONE;
+    (1..=n)
+        .map(|i| {
+            binomial *= Scalar::from((n - i + 1) as u64) * inverses[i - 1];
+            if i % 2 == 1 { binomial } else { -binomial }
+        })
+        .collect()
+}
+
+impl GroupOpsSimulationItem for ScalarBatchOps {
+    fn description(&self) -> String {
+        let op = match self.op {
+            BatchOp::LagrangeCoefficients => "Lagrange coefficients of 1..=n",
+            BatchOp::Powers => "powers",
+            BatchOp::InnerProduct => "inner products",
+        };
+        let method = if self.batched { "batched" } else { "one by one" };
+        format!("scalar {} ({}): {} of size {}", op, method, self.num, self.size())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.size())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.compute());
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.compute()));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    pub fn scalar_muls(&mut self, num: usize) -> &mut Self {
+        let item = ScalarMuls::new(self.rng(), num);
+        self.push_item(item)
+    }
+    pub fn scalar_adds(&mut self, num: usize) -> &mut Self {
+        let item = ScalarAdds::new(self.rng(), num);
+        self.push_item(item)
+    }
+    pub fn scalar_inversions(&mut self, num: usize) -> &mut Self {
+        let item = ScalarInversions::new(self.rng(), num);
+        self.push_item(item)
+    }
+    /// `num` batch inversions of `size` scalars each.
+    pub fn scalar_batch_inversions(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = BatchInversions::new(self.rng(), num, size);
+        self.push_item(item)
+    }
+    /// `num` times each of all Lagrange coefficients of 1..=size, the first `size` powers of a
+    /// scalar, and an inner product of two vectors of `size` scalars, computed one element
+    /// at a time as written from the formulas and batched as an optimized verifier does it (the
+    /// coefficients from binomials, the powers by repeated multiplication, the inner product
+    /// over zipped iterators, without bounds checks), as one item each.
+    pub fn scalar_batch_ops(&mut self, num: usize, size: usize) -> &mut Self {
+        self.group(|sim| {
+            for op in [BatchOp::LagrangeCoefficients, BatchOp::Powers, BatchOp::InnerProduct] {
+                for batched in [false, true] {
+                    let item = ScalarBatchOps::new(sim.rng(), op, batched, num, size);
+                    sim.push_item(item);
+                }
+            }
+        })
+    }
+}
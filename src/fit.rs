@@ -0,0 +1,91 @@
+//! Least-squares fits of measured times to the complexity models papers state, `a·n + b`,
+//! `a·n log n + b` and `a·n² + b`, so scaling claims such as "verification is quasi-linear in
+//! n" come out of a sweep rather than a look at its plot.
+
+use std::fmt;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    Linear,
+    NLogN,
+    Quadratic,
+}
+
+impl Model {
+    pub const ALL: [Model; 3] = [Model::Linear, Model::NLogN, Model::Quadratic];
+
+    /// The term `a` multiplies, at `n`.
+    pub fn term(&self, n: f64) -> f64 {
+        match self {
+            Model::Linear => n,
+            Model::NLogN => n * n.max(1.0).log2(),
+            Model::Quadratic => n * n,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Model::Linear => "a·n + b",
+            Model::NLogN => "a·n log n + b",
+            Model::Quadratic => "a·n² + b",
+        }
+    }
+}
+
+/// `y ≈ a · model.term(n) + b`, with times in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fit {
+    pub model: Model,
+    pub a: f64,
+    pub b: f64,
+    /// the coefficient of determination, 1 for a perfect fit
+    pub r_squared: f64,
+}
+
+impl Fit {
+    /// The least-squares fit of `model` to the `(n, y)` points, if they have at least two
+    /// distinct `n`.
+    pub fn new(model: Model, points: &[(f64, f64)]) -> Option<Self> {
+        let len = points.len() as f64;
+        let xs : Vec<f64> = points.iter().map(|&(n, _)| model.term(n)).collect();
+        let mean_x = xs.iter().sum::<f64>() / len;
+        let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / len;
+        let var_x : f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+        if points.len() < 2 || var_x == 0.0 {
+            return None;
+        }
+        let cov : f64 = xs.iter().zip(points).map(|(x, &(_, y))| (x - mean_x) * (y - mean_y)).sum();
+        let a = cov / var_x;
+        let b = mean_y - a * mean_x;
+        let residuals : f64 = xs.iter().zip(points).map(|(x, &(_, y))| (y - a * x - b).powi(2)).sum();
+        let total : f64 = points.iter().map(|&(_, y)| (y - mean_y).powi(2)).sum();
+        let r_squared = if total == 0.0 { 1.0 } else { 1.0 - residuals / total };
+        Some(Self { model, a, b, r_squared })
+    }
+
+    pub fn predict(&self, n: f64) -> f64 {
+        self.a * self.model.term(n) + self.b
+    }
+}
+
+/// The fits of all models, the best (highest R²) first.
+pub fn fit_all(points: &[(f64, f64)]) -> Vec<Fit> {
+    let mut fits : Vec<Fit> = Model::ALL.iter().filter_map(|&model| Fit::new(model, points)).collect();
+    fits.sort_by(|x, y| y.r_squared.total_cmp(&x.r_squared));
+    fits
+}
+
+// seconds with a unit that keeps a few digits before the point, keeping the sign
+fn seconds(x: f64) -> String {
+    let (scale, unit) = [(1.0, "s"), (1e-3, "ms"), (1e-6, "µs")].into_iter()
+        .find(|&(scale, _)| x.abs() >= scale)
+        .unwrap_or((1e-9, "ns"));
+    format!("{:.3}{}", x / scale, unit)
+}
+
+impl fmt::Display for Fit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} with a = {}, b = {} (R² = {:.4})", self.model.name(), seconds(self.a), seconds(self.b), self.r_squared)
+    }
+}
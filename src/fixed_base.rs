@@ -0,0 +1,151 @@
+use crate::curve_ops::BatchToAffine;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::Curve;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// Precomputed multiples of a single base for fixed-base exponentiation with `window_bits`-bit
+/// windows: for every window `i`, the table holds `j * 2^(window_bits * i) * base` for all
+/// `0 <= j < 2^window_bits`. An exponentiation then costs one mixed addition per window and no
+/// doublings.
+pub struct FixedBaseTable<T>
+where T : Curve {
+    window_bits: usize,
+    windows: Vec<Vec<T::AffineRepr>>,
+}
+
+impl<T> FixedBaseTable<T>
+where T : BatchToAffine {
+    pub fn new(base: T, window_bits: usize) -> Self {
+        assert!((1..=16).contains(&window_bits), "window size must be between 1 and 16 bits");
+        let num_windows = (Scalar::NUM_BITS as usize).div_ceil(window_bits);
+        let mut window_base = base;
+        let windows = (0..num_windows)
+            .map(|_| {
+                let mut row = Vec::with_capacity(1 << window_bits);
+                let mut acc = T::identity();
+                for _ in 0..(1 << window_bits) {
+                    row.push(acc);
+                    acc += window_base;
+                }
+                // acc is now 2^window_bits * window_base, the base of the next window
+                window_base = acc;
+                T::batch_to_affine(&row)
+            })
+            .collect();
+        Self { window_bits, windows }
+    }
+
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// Number of precomputed points held by the table.
+    pub fn size(&self) -> usize {
+        self.windows.len() << self.window_bits
+    }
+
+    pub fn mul(&self, scalar: &Scalar) -> T {
+        let bytes = scalar.to_bytes_le();
+        let bit = |i: usize| i < 256 && (bytes[i / 8] >> (i % 8)) & 1 == 1;
+        let mut acc = T::identity();
+        for (i, row) in self.windows.iter().enumerate() {
+            let digit = (0..self.window_bits)
+                .filter(|j| bit(i * self.window_bits + j))
+                .fold(0, |digit, j| digit | (1 << j));
+            if digit != 0 {
+                acc += &row[digit];
+            }
+        }
+        acc
+    }
+}
+
+struct FixedBaseTableBuilds<T> {
+    base: T,
+    window_bits: usize,
+}
+
+impl<T> GroupOpsSimulationItem for FixedBaseTableBuilds<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} fixed-base table builds ({}-bit windows): 1", T::NAME, self.window_bits)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(1)
+    }
+
+    fn simulate(&self) {
+        black_box(FixedBaseTable::new(self.base, self.window_bits));
+    }
+}
+
+struct FixedBaseExps<T>
+where T : Curve {
+    table: FixedBaseTable<T>,
+    scalars: Vec<Scalar>,
+}
+
+impl<T> FixedBaseExps<T>
+where T : BatchToAffine {
+    fn new(mut rng: &mut impl RngCore, base: T, num: usize, window_bits: usize) -> Self {
+        Self {
+            table: FixedBaseTable::new(base, window_bits),
+            scalars: (0..num).map(|_| Scalar::random(&mut rng)).collect(),
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for FixedBaseExps<T>
+where T : BatchToAffine + NamedGroup + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} fixed-base exps ({}-bit windows): {}", T::NAME, self.table.window_bits, self.scalars.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.scalars.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.scalars.iter().map(|s| self.table.mul(s)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.scalars.len(), |i| self.table.mul(&self.scalars[i])));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` exponentiations of one G1 base using a precomputed window table. Building the table
+    /// is added as its own item, so its one-off cost is reported separately.
+    pub fn fixed_base_exps(&mut self, num: usize, window_bits: usize) -> &mut Self {
+        let base = self.rng().points::<G1Projective>(1)[0];
+        let item = FixedBaseExps::new(self.rng(), base, num, window_bits);
+        self.group(|sim| { sim.push_item(FixedBaseTableBuilds { base, window_bits }).push_item(item); })
+    }
+    /// Same as `fixed_base_exps`, in G2.
+    pub fn g2_fixed_base_exps(&mut self, num: usize, window_bits: usize) -> &mut Self {
+        let base = self.rng().points::<G2Projective>(1)[0];
+        let item = FixedBaseExps::new(self.rng(), base, num, window_bits);
+        self.group(|sim| { sim.push_item(FixedBaseTableBuilds { base, window_bits }).push_item(item); })
+    }
+
+    /// One build of the table of `base`, for items that use fixed-base exps internally.
+    pub(crate) fn fixed_base_table_build<T>(&mut self, base: T, window_bits: usize) -> &mut Self
+    where T : BatchToAffine + NamedGroup + Send + Sync + 'static,
+          T::AffineRepr : Send + Sync {
+        self.push_item(FixedBaseTableBuilds { base, window_bits })
+    }
+}
@@ -0,0 +1,216 @@
+//! G1 exponentiation with the GLV method, using BLS12-381's endomorphism
+//! `phi(x, y) = (beta * x, y)`, which acts on G1 as multiplication by `lambda = z^2 - 1` (`z` the
+//! curve parameter). Since `lambda^2 + lambda + 1` is exactly the group order, any scalar splits
+//! into `k = k1 + k2 * lambda` with both halves below `lambda < 2^128`, and `k * P` becomes
+//! `k1 * P + k2 * phi(P)`: a two-base exponentiation with half the doublings.
+//!
+//! Both the GLV exps and the plain exps they are compared against use 4-bit fixed windows, so
+//! the two differ only in the endomorphism; blstrs' own `*` is timed next to them.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blst::{blst_fp, blst_fp_cneg, blst_fp_from_uint64, blst_fp_inverse, blst_fp_mul, blst_fp_sqrt, blst_fp_sub, blst_p1};
+use blstrs::{G1Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+const WINDOW_BITS : usize = 4;
+// |z| for BLS12-381
+const Z : u128 = 0xd201_0000_0001_0000;
+const LAMBDA : u128 = Z * Z - 1;
+
+/// The endomorphism, with the cube root of unity `beta` matching `lambda`.
+#[derive(Clone, Copy, Debug)]
+pub struct Endomorphism {
+    beta: blst_fp,
+}
+
+impl Default for Endomorphism {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Endomorphism {
+    pub fn new() -> Self {
+        // the two non-trivial cube roots of unity are (-1 +- sqrt(-3)) / 2; phi uses the one
+        // that gives lambda * P rather than lambda^2 * P
+        let from_u64 = |n: u64| {
+            let mut out = blst_fp::default();
+            // SAFETY: blst reads six limbs
+            unsafe { blst_fp_from_uint64(&mut out, [n, 0, 0, 0, 0, 0].as_ptr()) };
+            out
+        };
+        let (one, two, three) = (from_u64(1), from_u64(2), from_u64(3));
+        let mut root = blst_fp::default();
+        let mut half = blst_fp::default();
+        // SAFETY: plain field arithmetic on initialized values; blst allows aliasing outputs
+        let candidates = unsafe {
+            blst_fp_cneg(&mut root, &three, true);
+            assert!(blst_fp_sqrt(&mut root, &root), "-3 is a square modulo p");
+            blst_fp_inverse(&mut half, &two);
+            let mut betas = [blst_fp::default(); 2];
+            for (beta, negate) in betas.iter_mut().zip([false, true]) {
+                let mut signed_root = blst_fp::default();
+                blst_fp_cneg(&mut signed_root, &root, negate);
+                blst_fp_sub(beta, &signed_root, &one);
+                blst_fp_mul(beta, beta, &half);
+            }
+            betas
+        };
+        let g = G1Projective::generator();
+        let lambda_g = g * Scalar::from_u128(LAMBDA);
+        candidates.into_iter()
+            .map(|beta| Self { beta })
+            .find(|endo| endo.apply(&g) == lambda_g)
+            .expect("one cube root of unity corresponds to lambda")
+    }
+
+    /// `lambda * p`, for `p` in G1.
+    pub fn apply(&self, p: &G1Projective) -> G1Projective {
+        // blst's Jacobian coordinates: x = X / Z^2, so scaling X scales x
+        let mut point = *as_blst(p);
+        // SAFETY: blst allows the output to alias an input
+        unsafe { blst_fp_mul(&mut point.x, &point.x, &self.beta) };
+        // SAFETY: blstrs' G1Projective is a repr(transparent) wrapper of blst_p1
+        unsafe { std::mem::transmute::<blst_p1, G1Projective>(point) }
+    }
+
+    /// `k1 * p + k2 * phi(p)` with `k = k1 + k2 * lambda`, sharing the doublings.
+    pub fn mul(&self, p: &G1Projective, scalar: &Scalar) -> G1Projective {
+        let (k1, k2) = decompose(scalar);
+        let table = window_table(p);
+        let phi_table = table.map(|q| self.apply(&q));
+        windowed(&[(&table, k1), (&phi_table, k2)], 128)
+    }
+}
+
+fn as_blst(p: &G1Projective) -> &blst_p1 {
+    // SAFETY: blstrs' G1Projective is a repr(transparent) wrapper of blst_p1
+    unsafe { &*(p as *const G1Projective as *const blst_p1) }
+}
+
+/// `(k1, k2)` with `k = k1 + k2 * lambda` and `k1 < lambda`, by long division of `k` by lambda.
+pub fn decompose(scalar: &Scalar) -> (u128, u128) {
+    let bytes = scalar.to_bytes_le();
+    let (mut remainder, mut quotient) = (0u128, 0u128);
+    for bit in (0..256).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((bytes[bit / 8] >> (bit % 8)) & 1) as u128;
+        quotient <<= 1;
+        // with the carry, the remainder is below 2 * lambda, so one subtraction suffices
+        if carry == 1 || remainder >= LAMBDA {
+            remainder = remainder.wrapping_sub(LAMBDA);
+            quotient |= 1;
+        }
+    }
+    (remainder, quotient)
+}
+
+// j * p for every digit j of a window
+fn window_table(p: &G1Projective) -> [G1Projective; 1 << WINDOW_BITS] {
+    let mut table = [G1Projective::identity(); 1 << WINDOW_BITS];
+    for j in 1..table.len() {
+        table[j] = table[j - 1] + p;
+    }
+    table
+}
+
+// sum of digit-wise table lookups over `bits`-bit scalars, most significant window first
+fn windowed<const N: usize>(terms: &[(&[G1Projective; N], u128)], bits: usize) -> G1Projective {
+    let mut acc = G1Projective::identity();
+    for window in (0..bits.div_ceil(WINDOW_BITS)).rev() {
+        for _ in 0..WINDOW_BITS {
+            acc = acc.double();
+        }
+        for (table, scalar) in terms {
+            let digit = (scalar >> (window * WINDOW_BITS)) as usize & (N - 1);
+            if digit != 0 {
+                acc += table[digit];
+            }
+        }
+    }
+    acc
+}
+
+/// `scalar * p` with the same 4-bit windows as `Endomorphism::mul` but no endomorphism.
+pub fn windowed_mul(p: &G1Projective, scalar: &Scalar) -> G1Projective {
+    let bytes = scalar.to_bytes_le();
+    let (low, high) = (u128::from_le_bytes(bytes[..16].try_into().unwrap()), u128::from_le_bytes(bytes[16..].try_into().unwrap()));
+    let table = window_table(p);
+    let high = windowed(&[(&table, high)], Scalar::NUM_BITS as usize - 128);
+    let mut acc = high;
+    for _ in 0..128 {
+        acc = acc.double();
+    }
+    acc + windowed(&[(&table, low)], 128)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Windowed,
+    Glv,
+    Default,
+}
+
+struct GlvExps {
+    method: Method,
+    endomorphism: Endomorphism,
+    bases: Vec<G1Projective>,
+    scalars: Vec<Scalar>,
+}
+
+impl GlvExps {
+    fn exp(&self, i: usize) -> G1Projective {
+        match self.method {
+            Method::Windowed => windowed_mul(&self.bases[i], &self.scalars[i]),
+            Method::Glv => self.endomorphism.mul(&self.bases[i], &self.scalars[i]),
+            Method::Default => self.bases[i] * self.scalars[i],
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for GlvExps {
+    fn description(&self) -> String {
+        let method = match self.method {
+            Method::Windowed => "4-bit windows",
+            Method::Glv => "4-bit windows, GLV",
+            Method::Default => "blstrs default",
+        };
+        format!("G1 exps ({}): {}", method, self.bases.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<G1Projective>>((0..self.bases.len()).map(|i| self.exp(i)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.bases.len(), |i| self.exp(i)));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// The same `num` G1 exponentiations with plain 4-bit windows, with the same windows and the
+    /// GLV endomorphism, and with blstrs' `*`, as one item each.
+    pub fn g1_glv_exps(&mut self, num: usize) -> &mut Self {
+        let bases : Vec<G1Projective> = self.rng().points(num);
+        let scalars : Vec<Scalar> = (0..num).map(|_| Scalar::random(&mut *self.rng())).collect();
+        let endomorphism = Endomorphism::new();
+        self.group(|sim| {
+            for method in [Method::Windowed, Method::Glv, Method::Default] {
+                sim.push_item(GlvExps { method, endomorphism, bases: bases.clone(), scalars: scalars.clone() });
+            }
+        })
+    }
+}
@@ -1,24 +1,37 @@
 
-use blstrs::{pairing, Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
-use group::{ff::Field as _, Curve as _, Group};
-use pairing::{MultiMillerLoop, MillerLoopResult};
-use rand::{thread_rng, RngCore};
-use std::{ops::Mul, time::{Duration, Instant}};
+use blstrs::{pairing, Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult, Scalar};
+use pairing::{MillerLoopResult as _, MultiMillerLoop as _};
+use group::{ff::{Field as _, PrimeField as _}, Curve as _, Group};
+use rand::RngCore;
+use rand::seq::SliceRandom as _;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use crate::alloc;
+use crate::clock::Instant;
+use crate::error::SimulationError;
+use crate::curve_ops::BatchToAffine as _;
+use crate::energy::Meter;
+use crate::interrupt;
+use crate::machine::Machine;
+use crate::pairing_utils::{multi_miller_loop_affine, multi_pairing};
+use crate::parallel::{available_threads, parallel_map, process_cpu_time};
+use crate::progress::{Progress, Reporter};
+use crate::schedule::{Schedule, ScheduleError, Step};
+use crate::sizes::{self, SizeDistribution};
+use crate::report::{item_name, ConcurrentReport, ItemReport, ItemSummary, ItemThroughput, MemoryUsage, Metadata, OpCount, PhaseRun, SimulationReport, ThroughputReport};
+pub use crate::report::RepeatedRuns;
+use crate::stats::Summary;
+use crate::test_vectors::{concat_outputs, OutputBytes, TestVectors};
+use std::ops::Mul;
 use std::hint::black_box;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 
-fn multi_pairing<'a>(lhs: impl Iterator<Item = &'a G1Projective>, rhs: impl Iterator<Item = &'a G2Projective>) -> Gt {
-    <Bls12 as MultiMillerLoop>::multi_miller_loop(
-        &lhs.zip(rhs)
-           .map(|(a,b)| (a.to_affine(), G2Prepared::from(b.to_affine())))
-           .collect::<Vec<(G1Affine, G2Prepared)>>()
-           .iter()
-           .map(|(g1, g2)| (g1, g2))
-           .collect::<Vec<(&G1Affine, &G2Prepared)>>()
-           ).final_exponentiation()
-}
-
-trait MultiExp : Sized {
+/// `prod_i bases[i]^scalars[i]`, with blstrs' Pippenger in G1 and G2.
+pub trait MultiExp : Sized {
     fn multi_exp(bases: &[Self], scalars: &[Scalar]) -> Self;
 }
 
@@ -34,180 +47,1521 @@ impl MultiExp for G2Projective {
     }
 }
 
-trait GroupOpsSimulationItem {
+// blstrs has no multi-exp in Gt, so use interleaved square-and-multiply: one squaring per scalar
+// bit shared across all bases, plus one multiplication per set bit
+impl MultiExp for Gt {
+    fn multi_exp(bases: &[Self], scalars: &[Scalar]) -> Gt {
+        let scalar_bytes : Vec<[u8; 32]> = scalars.iter().map(Scalar::to_bytes_le).collect();
+        let mut acc = Gt::identity();
+        for bit in (0..Scalar::NUM_BITS as usize).rev() {
+            acc = acc.double();
+            for (base, bytes) in bases.iter().zip(&scalar_bytes) {
+                if (bytes[bit / 8] >> (bit % 8)) & 1 == 1 {
+                    acc += base;
+                }
+            }
+        }
+        acc
+    }
+}
+
+/// The name items use for their group in reports.
+pub trait NamedGroup {
+    const NAME: &'static str;
+}
+
+impl NamedGroup for G1Projective {
+    const NAME: &'static str = "G1";
+}
+
+impl NamedGroup for G2Projective {
+    const NAME: &'static str = "G2";
+}
+
+impl NamedGroup for Gt {
+    const NAME: &'static str = "Gt";
+}
+
+/// How items draw their random group elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Setup {
+    /// independent points, each hashed to the curve
+    #[default]
+    Random,
+    /// a random multiple of the generator followed by running sums of small multiples of it:
+    /// one addition per point, with discrete logarithms that are far from random
+    Fast,
+}
+
+/// The simulation's rng, which also draws the group elements of items according to its `Setup`.
+pub struct Sampler<R> {
+    rng: R,
+    setup: Setup,
+}
+
+impl<R> Sampler<R>
+where R : RngCore {
+    /// `num` points to use as inputs; draw scalars from the sampler as from any rng.
+    pub fn points<T: Group>(&mut self, num: usize) -> Vec<T> {
+        match self.setup {
+            Setup::Random => (0..num).map(|_| T::random(&mut self.rng)).collect(),
+            Setup::Fast => {
+                // the random start keeps the points of different calls apart, so e.g. additions
+                // don't hit the doubling case
+                let multiples : Vec<T> = std::iter::successors(Some(T::generator()), |p| Some(*p + T::generator())).take(16).collect();
+                let mut acc = T::generator() * T::Scalar::random(&mut self.rng);
+                (0..num)
+                    .map(|_| {
+                        acc += multiples[self.rng.next_u32() as usize % multiples.len()];
+                        acc
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl<R> RngCore for Sampler<R>
+where R : RngCore {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// One timed step of a simulation, added with `GroupOpsSimulation::item`.
+///
+/// Items draw all their inputs when they are built, usually in a `new` that takes the
+/// simulation's `Sampler` (`GroupOpsSimulation::rng`): that time is reported as setup, not
+/// measured. `simulate` does the measured work and nothing else, passing its results to
+/// `std::hint::black_box` so they aren't optimized away. It may run many times (repetitions,
+/// warm-up), so it must not consume the inputs. The simulation times every call and reports it
+/// as an `ItemReport` with the item's `description` and `op_count`. Items are `Send + Sync` so
+/// that `GroupOpsSimulation::simulate_concurrent` can run them on worker threads.
+pub trait GroupOpsSimulationItem : Send + Sync {
+    /// Kind and size of the operations, e.g. `G1 exps: 100`, which reports print.
+    fn description(&self) -> String;
+    /// How many operations of which size `simulate` runs, for per-op times.
+    fn op_count(&self) -> OpCount;
     fn simulate(&self);
+    /// Items that consist of independent operations spread them over `threads` threads.
+    fn simulate_parallel(&self, _threads: usize) {
+        self.simulate()
+    }
+    /// Items that can run their operations one at a time override both `ops` and
+    /// `simulate_op`, so that execution orders other than `Grouped` can interleave them with
+    /// other items' operations; the rest run as a whole.
+    fn ops(&self) -> usize {
+        1
+    }
+    fn simulate_op(&self, _i: usize) {
+        self.simulate()
+    }
+    /// What the item's operations consist of, where that explains their time (e.g. the group
+    /// operations of a multi-exp), which reports print under the item.
+    fn notes(&self) -> Option<String> {
+        None
+    }
+    /// Items built on an optimized primitive (multi-exps, multi-pairings) compute its result on
+    /// their inputs the naive way too and tell whether both agree; the rest have nothing to
+    /// check.
+    fn validate(&self) -> Option<bool> {
+        None
+    }
+    /// The results of the item's operations as bytes (see `test_vectors`), computed untimed;
+    /// `None` for items with no results to compare, such as conversions and serializations.
+    fn output(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
+/// `prod_i bases[i]^scalars[i]` one exp at a time, to validate multi-exps against.
+pub(crate) fn naive_multi_exp<T>(bases: &[T], scalars: &[Scalar]) -> T
+where T : Group + Mul<Scalar, Output = T> {
+    bases.iter().zip(scalars).fold(T::identity(), |acc, (base, scalar)| acc + *base * *scalar)
+}
+
+// `prod_i e(a_i, b_i)` one pairing at a time
+fn naive_multi_pairing(args_g1: &[G1Affine], args_g2: &[G2Affine]) -> Gt {
+    args_g1.iter().zip(args_g2).map(|(a, b)| pairing(a, b)).sum()
+}
 
-struct Exps<T>
-where T : Mul<Scalar> + Group {
+
+/// Exponentiations of random bases by random scalars, as `GroupOpsSimulation::g1_exps` adds them.
+pub struct Exps<T>
+where T : Mul<Scalar> + Group + NamedGroup {
     bases : Vec<T>,
     scalars : Vec<Scalar>
 }
 
 
 impl<T> Exps<T>
-where T : Mul<Scalar> + Group {
-    fn new(mut rng: &mut impl RngCore, num : usize) -> Self {
+where T : Mul<Scalar> + Group + NamedGroup {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize) -> Self {
         Self {
-            bases: (0..num).map(|_| T::random(&mut rng)).collect(),
-            scalars: (0..num).map(|_| Scalar::random(&mut rng)).collect(),
+            bases: rng.points(num),
+            scalars: (0..num).map(|_| Scalar::random(&mut *rng)).collect(),
         }
     }
 }
 
 impl<T> GroupOpsSimulationItem for Exps<T>
-where T : Mul<Scalar> + Group {
+where T : Mul<Scalar> + Group + NamedGroup + Send + Sync,
+      <T as Mul<Scalar>>::Output : OutputBytes + Send {
+    fn description(&self) -> String {
+        format!("{} exps: {}", T::NAME, self.bases.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.bases.len())
+    }
+
     fn simulate(&self) {
         // use black_box here so the rust compiler doesn't optimize away the "dead" code
         // https://doc.rust-lang.org/stable/std/hint/fn.black_box.html
-        let result : Vec<<T as Mul<Scalar>>::Output> = black_box(self.bases
+        black_box::<Vec<<T as Mul<Scalar>>::Output>>(self.bases
             .iter()
             .zip(&self.scalars)
             .map(|(base, scalar)| *base * *scalar)
             .collect());
     }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.bases.len(), |i| self.bases[i] * self.scalars[i]));
+    }
+
+    fn ops(&self) -> usize {
+        self.bases.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.bases[i] * self.scalars[i]);
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<_> = self.bases.iter().zip(&self.scalars).map(|(base, scalar)| *base * *scalar).collect();
+        Some(concat_outputs(&results))
+    }
 }
 
-type G1Exps = Exps<G1Projective>;
-type G2Exps = Exps<G2Projective>;
+pub type G1Exps = Exps<G1Projective>;
+pub type G2Exps = Exps<G2Projective>;
+pub type GtExps = Exps<Gt>;
 
-struct MultiExps<T>
-where T : Mul<Scalar> + Group {
-    num: usize,
-    bases : Vec<T>,
-    scalars : Vec<Scalar>
+/// `num` multi-exps of `size` random bases, each over the same inputs, or multi-exps of
+/// varying sizes (`with_sizes`), each over the first of the same inputs.
+pub struct MultiExps<T>
+where T : Mul<Scalar> + Group + NamedGroup {
+    /// the size of each multi-exp, at most `scalars.len()`
+    sizes: Vec<usize>,
+    /// the first `scalars.len()` are used
+    bases : Arc<Vec<T>>,
+    scalars : Vec<Scalar>,
+    shared : bool,
 }
 
 impl<T> MultiExps<T>
-where T : MultiExp + Group + Mul<Scalar> {
-    fn new(mut rng: &mut impl RngCore, num : usize, size: usize) -> Self {
+where T : MultiExp + Group + Mul<Scalar> + NamedGroup {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize, size: usize) -> Self {
+        Self::drawn(rng, vec![size; num], size)
+    }
+
+    /// One multi-exp of each of `sizes`, over the first bases and scalars of the largest.
+    pub fn with_sizes(rng: &mut Sampler<impl RngCore>, sizes: Vec<usize>) -> Self {
+        let size = sizes.iter().copied().max().unwrap_or(0);
+        Self::drawn(rng, sizes, size)
+    }
+
+    fn drawn(rng: &mut Sampler<impl RngCore>, sizes: Vec<usize>, size: usize) -> Self {
         Self {
-            num,
-            bases: (0..size).map(|_| T::random(&mut rng)).collect(),
-            scalars: (0..size).map(|_| Scalar::random(&mut rng)).collect(),
+            sizes,
+            bases: Arc::new(rng.points(size)),
+            scalars: (0..size).map(|_| Scalar::random(&mut *rng)).collect(),
+            shared: false,
         }
     }
+
+    pub fn with_bases(rng: &mut Sampler<impl RngCore>, num : usize, size: usize, bases: &SharedBases<T>) -> Self {
+        assert!(size <= bases.len(), "multi-exps of size {} on {} shared bases", size, bases.len());
+        Self {
+            sizes: vec![size; num],
+            bases: bases.0.clone(),
+            scalars: (0..size).map(|_| Scalar::random(&mut *rng)).collect(),
+            shared: true,
+        }
+    }
+
+    fn multi_exp(&self, i: usize) -> T {
+        let size = self.sizes[i];
+        T::multi_exp(&self.bases[..size], &self.scalars[..size])
+    }
+
+    // the multi-exps of distinct sizes, one of each
+    fn distinct(&self) -> Vec<usize> {
+        (0..self.sizes.len()).filter(|&i| !self.sizes[..i].contains(&self.sizes[i])).collect()
+    }
 }
 
 impl<T> GroupOpsSimulationItem for MultiExps<T>
-where T : MultiExp + Group + Mul<Scalar> {
+where T : MultiExp + Group + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync {
+    fn description(&self) -> String {
+        let shared = if self.shared { " (shared bases)" } else { "" };
+        format!("{} multi-exps: {} {}{}", T::NAME, self.sizes.len(), sizes::describe(&self.sizes), shared)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.sizes.len(), sizes::mean(&self.sizes))
+    }
+
     fn simulate(&self) {
         // use black_box here so the rust compiler doesn't optimize away the "dead" code
         // https://doc.rust-lang.org/stable/std/hint/fn.black_box.html
-        let result : Vec<T> = black_box(
-            (0..self.num).map(|_| T::multi_exp(&self.bases, &self.scalars)).collect()
+        black_box::<Vec<T>>(
+            (0..self.sizes.len()).map(|i| self.multi_exp(i)).collect()
             );
     }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.sizes.len(), |i| self.multi_exp(i)));
+    }
+
+    fn ops(&self) -> usize {
+        self.sizes.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.multi_exp(i));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.distinct().into_iter().all(|i| self.multi_exp(i) == naive_multi_exp(&self.bases[..self.sizes[i]], &self.scalars[..self.sizes[i]])))
+    }
+
+    // multi-exps of the same size have the same inputs, so the same result
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<T> = self.distinct().into_iter().map(|i| self.multi_exp(i)).collect();
+        Some(concat_outputs(&results))
+    }
 }
 
-type G1MultiExps = MultiExps<G1Projective>;
-type G2MultiExps = MultiExps<G2Projective>;
+/// Bases that several multi-exp items use, like the commitment key a verifier reuses across
+/// all its multi-exps; see `GroupOpsSimulation::shared_bases_g1`.
+#[derive(Clone, Debug)]
+pub struct SharedBases<T>(Arc<Vec<T>>);
 
-struct Pairings {
-    args_G1: Vec<G1Affine>,
-    args_G2: Vec<G2Affine>,
+impl<T> SharedBases<T> {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub type G1MultiExps = MultiExps<G1Projective>;
+pub type G2MultiExps = MultiExps<G2Projective>;
+pub type GtMultiExps = MultiExps<Gt>;
+
+/// Single pairings of random affine points.
+pub struct Pairings {
+    args_g1: Vec<G1Affine>,
+    args_g2: Vec<G2Affine>,
 }
 
 
 impl Pairings {
-    fn new(mut rng: &mut impl RngCore, num : usize) -> Self {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize) -> Self {
         Self {
-            args_G1: (0..num).map(|_| G1Projective::random(&mut rng).to_affine()).collect(),
-            args_G2: (0..num).map(|_| G2Projective::random(&mut rng).to_affine()).collect(),
+            args_g1: rng.points(num).iter().map(G1Projective::to_affine).collect(),
+            args_g2: rng.points(num).iter().map(G2Projective::to_affine).collect(),
         }
     }
 }
 
 impl GroupOpsSimulationItem for Pairings {
+    fn description(&self) -> String {
+        format!("pairings: {}", self.args_g1.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.args_g1.len())
+    }
+
     fn simulate(&self) {
         // use black_box here so the rust compiler doesn't optimize away the "dead" code
         // https://doc.rust-lang.org/stable/std/hint/fn.black_box.html
-        let result : Vec<Gt> = black_box(
-            self.args_G1.iter()
-            .zip(&self.args_G2)
+        black_box::<Vec<Gt>>(
+            self.args_g1.iter()
+            .zip(&self.args_g2)
             .map(|(a, b)| pairing(a,b))
             .collect()
             );
     }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.args_g1.len(), |i| pairing(&self.args_g1[i], &self.args_g2[i])));
+    }
+
+    fn ops(&self) -> usize {
+        self.args_g1.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(pairing(&self.args_g1[i], &self.args_g2[i]));
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<Gt> = self.args_g1.iter().zip(&self.args_g2).map(|(a, b)| pairing(a, b)).collect();
+        Some(concat_outputs(&results))
+    }
+}
+
+/// Multi-Miller loops of affine points without the final exponentiation, with the G2 points
+/// prepared in `new` (`prepared`) or as part of every loop. Loops of varying sizes
+/// (`with_sizes`) pair the first of the same points.
+pub struct MillerLoops {
+    /// the size of each loop, at most `args_g1.len()`
+    sizes: Vec<usize>,
+    args_g1: Vec<G1Affine>,
+    args_g2: Vec<G2Affine>,
+    // the first of `args_g2`, prepared in advance; the others are prepared in every loop
+    prepared: Vec<G2Prepared>,
+}
+
+impl MillerLoops {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize, size: usize, prepared: bool) -> Self {
+        Self::partly_prepared(rng, num, size, if prepared { size } else { 0 })
+    }
+
+    /// Same as `new`, with only the first `prepared` of the `size` G2 points prepared in
+    /// advance, as for pairings that pair a static key with a fresh point.
+    pub fn partly_prepared(rng: &mut Sampler<impl RngCore>, num : usize, size: usize, prepared: usize) -> Self {
+        assert!(prepared <= size, "at most all G2 points can be prepared");
+        Self::drawn(rng, vec![size; num], size, prepared)
+    }
+
+    /// One loop of each of `sizes`, over the first points of the largest.
+    pub fn with_sizes(rng: &mut Sampler<impl RngCore>, sizes: Vec<usize>, prepared: bool) -> Self {
+        let size = sizes.iter().copied().max().unwrap_or(0);
+        Self::drawn(rng, sizes, size, if prepared { size } else { 0 })
+    }
+
+    fn drawn(rng: &mut Sampler<impl RngCore>, sizes: Vec<usize>, size: usize, prepared: usize) -> Self {
+        let args_g1 = G1Projective::batch_to_affine(&rng.points(size));
+        let args_g2 = G2Projective::batch_to_affine(&rng.points(size));
+        let prepared = args_g2[..prepared].iter().map(|&p| G2Prepared::from(p)).collect();
+        Self { sizes, args_g1, args_g2, prepared }
+    }
+
+    fn miller_loop(&self, i: usize) -> MillerLoopResult {
+        let size = self.sizes[i];
+        if self.prepared.len() >= size {
+            return multi_miller_loop_affine(&self.args_g1[..size], &self.prepared[..size]);
+        }
+        let fresh : Vec<G2Prepared> = self.args_g2[self.prepared.len()..size].iter().map(|&p| G2Prepared::from(p)).collect();
+        let terms : Vec<(&G1Affine, &G2Prepared)> = self.args_g1[..size].iter().zip(self.prepared.iter().chain(&fresh)).collect();
+        Bls12::multi_miller_loop(&terms)
+    }
+
+    // the loops of distinct sizes, one of each
+    fn distinct(&self) -> Vec<usize> {
+        (0..self.sizes.len()).filter(|&i| !self.sizes[..i].contains(&self.sizes[i])).collect()
+    }
+}
+
+impl GroupOpsSimulationItem for MillerLoops {
+    fn description(&self) -> String {
+        let prepared = match self.prepared.len() {
+            0 => String::new(),
+            prepared if prepared == self.args_g2.len() => " (G2 prepared in advance)".to_string(),
+            prepared => format!(" ({} of the G2 points prepared in advance)", prepared),
+        };
+        format!("Miller loops{}: {} {}", prepared, self.sizes.len(), sizes::describe(&self.sizes))
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.sizes.len(), sizes::mean(&self.sizes))
+    }
+
+    fn simulate(&self) {
+        // use black_box here so the rust compiler doesn't optimize away the "dead" code
+        // https://doc.rust-lang.org/stable/std/hint/fn.black_box.html
+        for i in 0..self.sizes.len() {
+            black_box(self.miller_loop(i));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.sizes.len(), |i| self.miller_loop(i)));
+    }
+
+    fn ops(&self) -> usize {
+        self.sizes.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.miller_loop(i));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.distinct().into_iter().all(|i| {
+            let size = self.sizes[i];
+            self.miller_loop(i).final_exponentiation() == naive_multi_pairing(&self.args_g1[..size], &self.args_g2[..size])
+        }))
+    }
+
+    // a Miller loop's result is only defined up to the final exponentiation
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<Gt> = self.distinct().into_iter().map(|i| self.miller_loop(i).final_exponentiation()).collect();
+        Some(concat_outputs(&results))
+    }
+}
+
+/// The conversions of pairing inputs from projective to affine: `num` batch normalizations of
+/// `size` G1 points and, unless the G2 side is prepared in advance, of `size` G2 points, or
+/// conversions of varying sizes (`with_sizes`) of the first of the same points.
+pub struct PairingInputConversions {
+    /// the number of G1 points of each conversion, at most `args_g1.len()`
+    sizes: Vec<usize>,
+    args_g1: Vec<G1Projective>,
+    // empty if the G2 side is prepared in advance
+    args_g2: Vec<G2Projective>,
+}
+
+impl PairingInputConversions {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize, size: usize, g2: bool) -> Self {
+        Self::with_g2(rng, num, size, if g2 { size } else { 0 })
+    }
+
+    /// Same as `new`, converting `g2_size` G2 points, those not prepared in advance.
+    pub fn with_g2(rng: &mut Sampler<impl RngCore>, num : usize, size: usize, g2_size: usize) -> Self {
+        let args_g1 = rng.points(size);
+        let args_g2 = rng.points(g2_size);
+        Self { sizes: vec![size; num], args_g1, args_g2 }
+    }
+
+    /// One conversion of each of `sizes` G1 points and, with `g2`, as many G2 points.
+    pub fn with_sizes(rng: &mut Sampler<impl RngCore>, sizes: Vec<usize>, g2: bool) -> Self {
+        let size = sizes.iter().copied().max().unwrap_or(0);
+        let args_g1 = rng.points(size);
+        let args_g2 = rng.points(if g2 { size } else { 0 });
+        Self { sizes, args_g1, args_g2 }
+    }
+
+    fn convert(&self, i: usize) -> (Vec<G1Affine>, Vec<G2Affine>) {
+        let size = self.sizes[i];
+        (G1Projective::batch_to_affine(&self.args_g1[..size]), G2Projective::batch_to_affine(&self.args_g2[..size.min(self.args_g2.len())]))
+    }
+}
+
+impl GroupOpsSimulationItem for PairingInputConversions {
+    fn description(&self) -> String {
+        let groups = match self.args_g2.len() {
+            0 => "G1".to_string(),
+            g2 if g2 == self.args_g1.len() => "G1 and G2".to_string(),
+            g2 => format!("G1 and {} G2", g2),
+        };
+        format!("affine conversions of pairing inputs ({}): {} {}", groups, self.sizes.len(), sizes::describe(&self.sizes))
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.sizes.len(), sizes::mean(&self.sizes))
+    }
+
+    fn simulate(&self) {
+        for i in 0..self.sizes.len() {
+            black_box(self.convert(i));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.sizes.len(), |i| self.convert(i)));
+    }
+
+    fn ops(&self) -> usize {
+        self.sizes.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.convert(i));
+    }
+}
+
+/// Final exponentiations of Miller loop results.
+pub struct FinalExponentiations {
+    inputs: Vec<MillerLoopResult>,
+}
+
+impl FinalExponentiations {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num: usize) -> Self {
+        let g2 = [G2Prepared::from(G2Projective::generator().to_affine())];
+        Self {
+            inputs: rng.points(num).iter().map(|p: &G1Projective| multi_miller_loop_affine(&[p.to_affine()], &g2)).collect(),
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for FinalExponentiations {
+    fn description(&self) -> String {
+        format!("final exponentiations: {}", self.inputs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.inputs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Gt>>(self.inputs.iter().map(|f| f.final_exponentiation()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.inputs.len(), |i| self.inputs[i].final_exponentiation()));
+    }
+
+    fn ops(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.inputs[i].final_exponentiation());
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<Gt> = self.inputs.iter().map(|f| f.final_exponentiation()).collect();
+        Some(concat_outputs(&results))
+    }
 }
 
-struct MultiPairings {
+
+/// `e(a_1, b_1) ... e(a_k, b_k) == e(c_1, d_1) ... e(c_m, d_m)`, checked as one multi-pairing
+/// with the right-hand G1 points negated, whose result is compared with the identity.
+pub struct PairingEqualityChecks {
     num: usize,
-    args_G1: Vec<G1Projective>,
-    args_G2: Vec<G2Projective>,
+    lhs_g1: Vec<G1Projective>,
+    rhs_g1: Vec<G1Projective>,
+    // the G2 points of both sides, left-hand side first
+    args_g2: Vec<G2Projective>,
 }
 
-impl MultiPairings {
-    fn new(mut rng: &mut impl RngCore, num : usize, size: usize) -> Self {
+impl PairingEqualityChecks {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num: usize, lhs_size: usize, rhs_size: usize) -> Self {
         Self {
             num,
-            args_G1: (0..size).map(|_| G1Projective::random(&mut rng)).collect(),
-            args_G2: (0..size).map(|_| G2Projective::random(&mut rng)).collect(),
+            lhs_g1: rng.points(lhs_size),
+            rhs_g1: rng.points(rhs_size),
+            args_g2: rng.points(lhs_size + rhs_size),
         }
     }
+
+    fn args_g1(&self) -> Vec<G1Projective> {
+        self.lhs_g1.iter().copied().chain(self.rhs_g1.iter().map(|p| -p)).collect()
+    }
+
+    fn check(&self) -> bool {
+        bool::from(multi_pairing(&self.args_g1(), &self.args_g2).is_identity())
+    }
 }
 
-impl GroupOpsSimulationItem for MultiPairings {
+impl GroupOpsSimulationItem for PairingEqualityChecks {
+    fn description(&self) -> String {
+        format!("pairing equality checks: {} of {} = {} pairings", self.num, self.lhs_g1.len(), self.rhs_g1.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.args_g2.len())
+    }
+
     fn simulate(&self) {
-        // use black_box here so the rust compiler doesn't optimize away the "dead" code
-        // https://doc.rust-lang.org/stable/std/hint/fn.black_box.html
-        for i in 0..self.num {
-            let result : Gt = black_box(
-                multi_pairing(self.args_G1.iter(), self.args_G2.iter())
-                );
+        for _ in 0..self.num {
+            black_box(self.check());
         }
     }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.check()));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.check());
+    }
+
+    fn validate(&self) -> Option<bool> {
+        let args_g1 = G1Projective::batch_to_affine(&self.args_g1());
+        let args_g2 = G2Projective::batch_to_affine(&self.args_g2);
+        Some(multi_pairing(&self.args_g1(), &self.args_g2) == naive_multi_pairing(&args_g1, &args_g2))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(multi_pairing(&self.args_g1(), &self.args_g2).output_bytes())
+    }
 }
 
 
-pub struct GroupOpsSimulation<'a, R>
-where R : RngCore {
-    items: Vec<Box<dyn GroupOpsSimulationItem>>,
-    rng: &'a mut R 
+/// G2Prepared precomputation on its own, e.g. for public keys that are reused across many
+/// multi-pairings.
+pub struct G2Preparations {
+    args: Vec<G2Affine>,
+}
 
+impl G2Preparations {
+    pub fn new(rng: &mut Sampler<impl RngCore>, num : usize) -> Self {
+        Self {
+            args: rng.points(num).iter().map(G2Projective::to_affine).collect(),
+        }
+    }
 }
 
+impl GroupOpsSimulationItem for G2Preparations {
+    fn description(&self) -> String {
+        format!("G2 preparations: {}", self.args.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.args.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<G2Prepared>>(self.args.iter().map(|&p| G2Prepared::from(p)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.args.len(), |i| G2Prepared::from(self.args[i])));
+    }
+
+    fn ops(&self) -> usize {
+        self.args.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(G2Prepared::from(self.args[i]));
+    }
+}
+
+/// Untimed executions before measurement starts, to get page faults, lazy initialization and
+/// CPU frequency ramp-up out of the way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarmUp {
+    #[default]
+    None,
+    /// run every item once
+    Once,
+    /// cycle through the items until at least this much time has passed (every item runs at
+    /// least once)
+    For(Duration),
+}
+
+/// The order in which the operations of the items run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExecutionOrder {
+    /// item by item, all operations of an item back to back (cache-friendly)
+    #[default]
+    Grouped,
+    /// one operation of every item in turn, like a verifier alternating between op types
+    Interleaved,
+    /// all operations of all items in a random order, a different one in every repetition
+    Shuffled,
+}
 
-impl<'a, R> GroupOpsSimulation<'a, R>
+impl ExecutionOrder {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "grouped" => Some(ExecutionOrder::Grouped),
+            "interleaved" => Some(ExecutionOrder::Interleaved),
+            "shuffled" => Some(ExecutionOrder::Shuffled),
+            _ => None,
+        }
+    }
+}
+
+
+fn run_item(item: &dyn GroupOpsSimulationItem, threads: usize) {
+    if threads > 1 {
+        item.simulate_parallel(threads);
+    } else {
+        item.simulate();
+    }
+}
+
+struct Entry {
+    item: Box<dyn GroupOpsSimulationItem>,
+    label: Option<String>,
+    // `phase_path` joined by " / "
+    phase: Option<String>,
+    phase_path: Vec<String>,
+    // heap bytes taken by the item's inputs, with alloc tracking
+    inputs: Option<usize>,
+    // time spent drawing the item's inputs
+    setup: Duration,
+}
+
+pub struct GroupOpsSimulation<R>
+where R : RngCore {
+    items: Vec<Entry>,
+    // items added by the most recent builder call, which `label` applies to
+    last_added: Range<usize>,
+    in_group: bool,
+    // the phases `phase` is inside, outermost first
+    phase_path: Vec<String>,
+    rng: Sampler<R>,
+    warm_up: WarmUp,
+    order: ExecutionOrder,
+    cold_start: bool,
+    // drawn from `rng` when the order is set to `Shuffled`
+    shuffle_seed: u64,
+    // run instead of the order's schedule
+    replay: Option<Schedule>,
+    threads: usize,
+    seed: Option<u64>,
+    // heap usage after the last item was added, to attribute new allocations to the next one
+    heap_mark: Option<usize>,
+    // when the last item was added, to attribute the time since to the next one's setup
+    setup_mark: Instant,
+    progress: Option<Reporter>,
+    // what `try_op` and `try_workload` let the inputs take
+    memory_limit: Option<usize>,
+    energy: Option<Arc<Meter>>,
+}
+
+
+impl GroupOpsSimulation<ChaCha20Rng> {
+    /// Bases and scalars are derived deterministically from `seed`, so two simulations with the
+    /// same seed and items operate on identical inputs, on any machine.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut sim = Self::new(ChaCha20Rng::seed_from_u64(seed));
+        sim.seed = Some(seed);
+        sim
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
 where R : RngCore
 {
-    pub fn new(rng: &'a mut R) -> Self {
+    /// Also accepts `&mut rng`, to share one generator across several simulations.
+    pub fn new(rng: R) -> Self {
         Self {
             items: Vec::new(),
-            rng
+            last_added: 0..0,
+            in_group: false,
+            phase_path: Vec::new(),
+            rng: Sampler { rng, setup: Setup::Random },
+            warm_up: WarmUp::None,
+            order: ExecutionOrder::Grouped,
+            cold_start: false,
+            shuffle_seed: 0,
+            replay: None,
+            threads: 1,
+            seed: None,
+            heap_mark: alloc::stats().map(|stats| stats.current),
+            setup_mark: Instant::now(),
+            progress: None,
+            memory_limit: None,
+            energy: None,
+        }
+    }
+
+    /// Spreads the independent operations within each item (individual exps, repeated mexps,
+    /// pairings, multi-pairings) over all available cores.
+    pub fn parallel(&mut self, parallel: bool) -> &mut Self {
+        self.threads = if parallel { available_threads() } else { 1 };
+        self
+    }
+
+    /// Like `parallel(true)`, with an explicit thread count. Note that blst's multi-exp is
+    /// already multithreaded internally, regardless of this setting.
+    pub fn threads(&mut self, threads: usize) -> &mut Self {
+        assert!(threads > 0, "need at least one thread");
+        self.threads = threads;
+        self
+    }
+
+    // used by the item modules to register their builder methods
+    pub(crate) fn push_item(&mut self, item: impl GroupOpsSimulationItem + 'static) -> &mut Self {
+        if !self.in_group {
+            self.last_added = self.items.len()..self.items.len();
         }
+        let item = Box::new(item);
+        let current = alloc::stats().map(|stats| stats.current);
+        let inputs = current.zip(self.heap_mark).map(|(current, mark)| current.saturating_sub(mark));
+        self.items.push(Entry {
+            item,
+            label: None,
+            phase: (!self.phase_path.is_empty()).then(|| self.phase_path.join(" / ")),
+            phase_path: self.phase_path.clone(),
+            inputs,
+            setup: self.setup_mark.elapsed(),
+        });
+        self.heap_mark = alloc::stats().map(|stats| stats.current);
+        self.setup_mark = Instant::now();
+        self.last_added.end = self.items.len();
+        self
+    }
+
+    // for builder methods that add several items (e.g. a setup step and the operations using
+    // it): makes `label` apply to all of them
+    pub(crate) fn group(&mut self, f: impl FnOnce(&mut Self)) -> &mut Self {
+        let (start, in_group) = (self.items.len(), self.in_group);
+        self.in_group = true;
+        f(self);
+        self.in_group = in_group;
+        self.last_added = start..self.items.len();
+        self
     }
 
-    pub fn simulate(&self) {
-        for item in &self.items {
-            item.simulate();
+    /// Names the item(s) added by the preceding builder call, e.g.
+    /// `sim.g1_multi_exps(n, t).label("share commitments")`.
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        for entry in &mut self.items[self.last_added.clone()] {
+            entry.label = Some(label.to_string());
+        }
+        self
+    }
+
+    /// Adds the items built by `f` under a named protocol phase, so reports group them:
+    /// `sim.phase("Dealer", |p| { p.g1_exps(n).label("encryptions"); })`. Phases can be nested.
+    pub fn phase(&mut self, name: &str, f: impl FnOnce(&mut Self)) -> &mut Self {
+        self.phase_path.push(name.to_string());
+        f(self);
+        self.phase_path.pop();
+        self
+    }
+
+    /// How items added from now on draw their group elements; `Setup::Fast` makes building
+    /// large simulations cheap, since timing an operation rarely depends on its inputs.
+    pub fn setup(&mut self, setup: Setup) -> &mut Self {
+        self.rng.setup = setup;
+        self
+    }
+
+    /// Where items draw their inputs from, following the seed and `Setup`.
+    pub fn rng(&mut self) -> &mut Sampler<R> {
+        &mut self.rng
+    }
+
+    /// Makes `try_op` and `try_workload` refuse ops whose estimated inputs (`Op::input_bytes`)
+    /// take more than `bytes`, e.g. `machine::available_memory()`.
+    pub fn memory_limit(&mut self, bytes: usize) -> &mut Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    pub(crate) fn check_memory(&self, estimate: usize) -> Result<(), SimulationError> {
+        match self.memory_limit {
+            Some(limit) if estimate > limit => Err(SimulationError::OutOfMemoryEstimate { estimate, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds an item of any kind, e.g. one implemented in another crate.
+    pub fn item(&mut self, item: impl GroupOpsSimulationItem + 'static) -> &mut Self {
+        self.push_item(item)
+    }
+
+    fn run_item(&self, item: &dyn GroupOpsSimulationItem) {
+        run_item(item, self.threads);
+    }
+
+    pub fn warm_up(&mut self, warm_up: WarmUp) -> &mut Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// With an order other than `Grouped`, operations run one at a time on a single thread, each
+    /// timed and its time added to its item's, and memory is not tracked. Items that can't split
+    /// into operations (everything but exps, multi-exps, pairings, Miller loops, final
+    /// exponentiations, pairing equality checks and G2 preparations) count as one operation.
+    pub fn order(&mut self, order: ExecutionOrder) -> &mut Self {
+        if order == ExecutionOrder::Shuffled {
+            self.shuffle_seed = self.rng.next_u64();
+        }
+        self.order = order;
+        self
+    }
+
+    // (item, operation) pairs in the order they run, for orders other than `Grouped`
+    fn op_order(&self, repetition: usize) -> Vec<(usize, usize)> {
+        let ops : Vec<usize> = self.items.iter().map(|entry| entry.item.ops()).collect();
+        let mut schedule : Vec<(usize, usize)> = match self.order {
+            ExecutionOrder::Interleaved => (0..ops.iter().copied().max().unwrap_or(0))
+                .flat_map(|op| ops.iter().enumerate().filter(move |&(_, &n)| op < n).map(move |(i, _)| (i, op)))
+                .collect(),
+            ExecutionOrder::Grouped | ExecutionOrder::Shuffled => ops.iter().enumerate()
+                .flat_map(|(i, &n)| (0..n).map(move |op| (i, op)))
+                .collect(),
+        };
+        if self.order == ExecutionOrder::Shuffled {
+            schedule.shuffle(&mut ChaCha20Rng::seed_from_u64(self.shuffle_seed.wrapping_add(repetition as u64)));
+        }
+        schedule
+    }
+
+    // the steps of a repetition, replayed or following the order
+    fn steps(&self, repetition: usize) -> Vec<Step> {
+        match (&self.replay, self.order) {
+            (Some(schedule), _) => schedule.repetitions[repetition % schedule.repetitions.len()].clone(),
+            (None, ExecutionOrder::Grouped) => (0..self.items.len()).map(Step::Item).collect(),
+            (None, _) => self.op_order(repetition).into_iter().map(|(i, op)| Step::Op(i, op)).collect(),
+        }
+    }
+
+    /// What a run of `repetitions` repetitions executes, to save and `replay` later.
+    pub fn schedule(&self, repetitions: usize) -> Schedule {
+        Schedule {
+            seed: self.seed,
+            items: self.items.iter().map(|entry| entry.item.description()).collect(),
+            repetitions: (0..repetitions).map(|repetition| self.steps(repetition)).collect(),
+        }
+    }
+
+    /// Runs `schedule` instead of the order's, after checking it was recorded for the same
+    /// items, which must all have been added. Repetitions beyond the recorded ones start over
+    /// from the first. The inputs are the same only with the seed the schedule was recorded with.
+    pub fn replay(&mut self, schedule: Schedule) -> Result<&mut Self, ScheduleError> {
+        let descriptions : Vec<String> = self.items.iter().map(|entry| entry.item.description()).collect();
+        let ops : Vec<usize> = self.items.iter().map(|entry| entry.item.ops()).collect();
+        schedule.check(&descriptions, &ops)?;
+        self.replay = Some(schedule);
+        Ok(self)
+    }
+
+    // runs `steps(repetition)`, returning the time spent in every item; progress is reported
+    // whenever the running item changes
+    fn run_scheduled(&self, repetition: usize, repetitions: usize, start_time: Instant) -> Vec<Duration> {
+        let mut durations = vec![Duration::ZERO; self.items.len()];
+        let mut previous = None;
+        for step in self.steps(repetition) {
+            let i = step.item();
+            if previous != Some(i) {
+                self.report_progress(i, repetition, repetitions, start_time);
+                previous = Some(i);
+            }
+            let step_start = Instant::now();
+            match step {
+                Step::Item(_) => self.run_item(self.items[i].item.as_ref()),
+                Step::Op(_, op) => self.items[i].item.simulate_op(op),
+            }
+            durations[i] += step_start.elapsed();
+        }
+        durations
+    }
+
+    /// Tells `reporter` about every item as it starts, e.g. `Reporter::stderr()`.
+    pub fn progress(&mut self, reporter: Reporter) -> &mut Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Reads `meter` before and after every item that runs on its own (not with another
+    /// `order`, nor `simulate_for` or `simulate_concurrent`), so reports give the energy of each
+    /// item and phase (see `energy`). With alloc tracking, the reads' own allocations count as
+    /// the item's.
+    pub fn measure_energy(&mut self, meter: Arc<Meter>) -> &mut Self {
+        self.energy = Some(meter);
+        self
+    }
+
+    // runs the item, timing it, with the energy it took if there is a meter; reading the meter
+    // isn't timed
+    fn run_item_metered(&self, item: &dyn GroupOpsSimulationItem) -> (Duration, Option<f64>) {
+        let before = self.energy.as_ref().map(|meter| meter.read());
+        let start_time = Instant::now();
+        self.run_item(item);
+        let duration = start_time.elapsed();
+        let after = self.energy.as_ref().map(|meter| meter.read());
+        let energy = self.energy.as_ref().zip(before.zip(after)).map(|(meter, (before, after))| meter.joules(&before, &after));
+        (duration, energy)
+    }
+
+    // `item == self.items.len()` once the last repetition is done
+    fn report_progress(&self, item: usize, repetition: usize, repetitions: usize, start_time: Instant) {
+        let Some(reporter) = &self.progress else { return };
+        let entry = self.items.get(item);
+        let description = entry.map(|entry| entry.item.description()).unwrap_or_default();
+        reporter.report(&Progress {
+            item,
+            items: self.items.len(),
+            repetition,
+            repetitions,
+            description: &description,
+            label: entry.and_then(|entry| entry.label.as_deref()),
+            phase: entry.and_then(|entry| entry.phase.as_deref()),
+            elapsed: start_time.elapsed(),
+        });
+    }
+
+    /// Before anything else (including the warm-up), times the first operation of the first
+    /// item of every class of operations (G1 exps, G2 multi-exps, ...) on its own, as the
+    /// worst-case latency of a freshly started process: blst's lazy initialization, code and
+    /// data not yet paged in, cold caches and branch predictors. Drawing the inputs may already
+    /// have run some of the same code, so a freshly started process can be slower still.
+    pub fn cold_start(&mut self, cold_start: bool) -> &mut Self {
+        self.cold_start = cold_start;
+        self
+    }
+
+    // the cold-start time of every item, `None` for items whose class ran before
+    fn run_cold_start(&self) -> Vec<Option<Duration>> {
+        let mut seen : Vec<String> = Vec::new();
+        self.items.iter()
+            .map(|entry| {
+                // descriptions are "<class>: <counts>"
+                let description = entry.item.description();
+                let class = description.split(':').next().unwrap_or_default();
+                if !self.cold_start || entry.item.ops() == 0 || seen.iter().any(|seen| seen == class) {
+                    return None;
+                }
+                seen.push(class.to_string());
+                let start_time = Instant::now();
+                entry.item.simulate_op(0);
+                Some(start_time.elapsed())
+            })
+            .collect()
+    }
+
+    fn run_warm_up(&self) {
+        let start_time = Instant::now();
+        match self.warm_up {
+            WarmUp::None => {}
+            WarmUp::Once => self.items.iter().for_each(|entry| self.run_item(entry.item.as_ref())),
+            WarmUp::For(duration) => loop {
+                self.items.iter().for_each(|entry| self.run_item(entry.item.as_ref()));
+                if self.items.is_empty() || start_time.elapsed() >= duration {
+                    break;
+                }
+            },
+        }
+    }
+
+    /// Checks the items built on optimized primitives against naive computations on the same
+    /// inputs, untimed, returning how many were checked or the description of the first that
+    /// disagrees.
+    pub fn validate(&self) -> Result<usize, String> {
+        let mut checked = 0;
+        for entry in &self.items {
+            match entry.item.validate() {
+                None => {}
+                Some(true) => checked += 1,
+                Some(false) => return Err(entry.item.description()),
+            }
+        }
+        Ok(checked)
+    }
+
+    /// The results of every item that has any (`GroupOpsSimulationItem::output`), computed
+    /// untimed, with the simulation's seed.
+    pub fn test_vectors(&self) -> TestVectors {
+        let items = self.items.iter()
+            .filter_map(|entry| Some((item_name(&entry.item.description(), entry.label.as_deref()), entry.item.output()?)))
+            .collect();
+        TestVectors { seed: self.seed, items }
+    }
+
+    /// Runs every item once, after the warm-up, inside `instructions::measured`, for Callgrind to
+    /// count (see `instructions`), and returns the name and op count of every item in order.
+    #[cfg(feature = "instruction-counts")]
+    pub fn count_instructions(&self) -> Vec<(String, OpCount)> {
+        self.run_warm_up();
+        self.items.iter()
+            .map(|entry| {
+                crate::instructions::measured(&|| self.run_item(entry.item.as_ref()));
+                (item_name(&entry.item.description(), entry.label.as_deref()), entry.item.op_count())
+            })
+            .collect()
+    }
+
+    /// Runs every item once, timing each individually. With several threads, `cpu` exceeds
+    /// `total`; their ratio is the effective parallelism.
+    pub fn simulate(&self) -> SimulationReport {
+        let metadata = Metadata {
+            threads: self.threads,
+            seed: self.seed,
+            ..Metadata::default()
+        };
+        let cold = self.run_cold_start();
+        self.run_warm_up();
+        let start_cpu = process_cpu_time();
+        let start_time = Instant::now();
+        let scheduled = (self.order != ExecutionOrder::Grouped || self.replay.is_some()).then(|| self.run_scheduled(0, 1, start_time));
+        // a schedule interleaves the items, so only runs item by item can stop between them
+        let items : Vec<ItemReport> = self.items.iter().enumerate()
+            .take_while(|_| scheduled.is_some() || !interrupt::requested())
+            .map(|(i, entry)| {
+                let (duration, memory, energy) = match &scheduled {
+                    Some(durations) => (durations[i], None, None),
+                    None => {
+                        self.report_progress(i, 0, 1, start_time);
+                        let before = alloc::stats();
+                        alloc::reset_peak();
+                        let (duration, energy) = self.run_item_metered(entry.item.as_ref());
+                        let memory = before.zip(alloc::stats()).map(|(before, after)| MemoryUsage {
+                            inputs: entry.inputs.unwrap_or(0),
+                            allocated: after.allocated - before.allocated,
+                            allocations: after.allocations - before.allocations,
+                            peak: after.peak.saturating_sub(before.current),
+                        });
+                        (duration, memory, energy)
+                    }
+                };
+                ItemReport {
+                    description: entry.item.description(),
+                    label: entry.label.clone(),
+                    phase: entry.phase.clone(),
+                    phase_path: entry.phase_path.clone(),
+                    op_count: entry.item.op_count(),
+                    setup: entry.setup,
+                    cold: cold[i],
+                    duration,
+                    memory,
+                    energy,
+                    notes: entry.item.notes(),
+                }
+            })
+            .collect();
+        self.report_progress(self.items.len(), 0, 1, start_time);
+        SimulationReport {
+            metadata: Metadata { partial: items.len() < self.items.len(), ..metadata },
+            items,
+            total: start_time.elapsed(),
+            cpu: process_cpu_time().zip(start_cpu).map(|(end, start)| end - start),
+        }
+    }
+
+    /// Runs every item for `budget` instead of a fixed number of times, and reports how many
+    /// operations it completed per second: robust both for operations so cheap that one run is
+    /// too short to time and for ones so expensive that a few runs take long. Items that split
+    /// into operations stop after the first operation past the budget, the others after the
+    /// first run; every item runs at least once.
+    pub fn simulate_for(&self, budget: Duration) -> ThroughputReport {
+        self.run_warm_up();
+        let start_time = Instant::now();
+        let items : Vec<ItemThroughput> = self.items.iter().enumerate()
+            .take_while(|_| !interrupt::requested())
+            .map(|(i, entry)| {
+                self.report_progress(i, 0, 1, start_time);
+                let (item, op_count) = (entry.item.as_ref(), entry.item.op_count());
+                let item_start = Instant::now();
+                let (mut ops, mut runs) = (0, 0);
+                while runs == 0 || item_start.elapsed() < budget {
+                    runs += 1;
+                    if item.ops() > 1 && self.threads == 1 {
+                        item.simulate_op(ops % item.ops());
+                        ops += 1;
+                    } else {
+                        self.run_item(item);
+                        ops += op_count.num;
+                    }
+                }
+                ItemThroughput {
+                    description: item.description(),
+                    label: entry.label.clone(),
+                    phase: entry.phase.clone(),
+                    op_count,
+                    ops,
+                    elapsed: item_start.elapsed(),
+                }
+            })
+            .collect();
+        self.report_progress(self.items.len(), 0, 1, start_time);
+        ThroughputReport {
+            metadata: Metadata { threads: self.threads, seed: self.seed, partial: items.len() < self.items.len(), ..Metadata::default() },
+            budget,
+            items,
+        }
+    }
+
+    /// Runs the phases concurrently on `workers` threads, as parties working at the same time:
+    /// each worker takes the next phase not yet started and runs its items one after the
+    /// other. Items outside any phase count as one more phase. The report has every phase's
+    /// start and duration and the makespan, the wall-clock time until all are done. Contention
+    /// for cores and memory bandwidth shows up in the times, as it would between parties
+    /// sharing a machine; there is no progress reporting.
+    pub fn simulate_concurrent(&self, workers: usize) -> ConcurrentReport {
+        assert!(workers > 0, "need at least one worker");
+        let mut phases : Vec<(Option<String>, Vec<usize>)> = Vec::new();
+        for (i, entry) in self.items.iter().enumerate() {
+            match phases.iter_mut().find(|(phase, _)| *phase == entry.phase) {
+                Some((_, items)) => items.push(i),
+                None => phases.push((entry.phase.clone(), vec![i])),
+            }
+        }
+        self.run_warm_up();
+        let next = AtomicUsize::new(0);
+        // the rng and the progress reporter stay on this thread
+        let (entries, threads) = (&self.items, self.threads);
+        let start_time = Instant::now();
+        let mut runs : Vec<(usize, PhaseRun)> = std::thread::scope(|scope| {
+            let workers : Vec<_> = (0..workers.min(phases.len()))
+                .map(|worker| {
+                    let (phases, next) = (&phases, &next);
+                    scope.spawn(move || {
+                        let mut runs = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some((phase, items)) = phases.get(index) else { return runs };
+                            let start = start_time.elapsed();
+                            items.iter().for_each(|&i| run_item(entries[i].item.as_ref(), threads));
+                            runs.push((index, PhaseRun { phase: phase.clone(), worker, start, duration: start_time.elapsed() - start }));
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+        });
+        let makespan = start_time.elapsed();
+        runs.sort_by_key(|(index, _)| *index);
+        ConcurrentReport {
+            metadata: Metadata { threads: self.threads, seed: self.seed, ..Metadata::default() },
+            workers,
+            phases: runs.into_iter().map(|(_, run)| run).collect(),
+            makespan,
+        }
+    }
+
+    /// Runs the whole item list `repetitions` times, timing every item individually. An
+    /// interruption stops the runs after the running item; the summaries cover the repetitions
+    /// that completed, or the items that did if the first didn't.
+    pub fn simulate_n(&self, repetitions: usize) -> RepeatedRuns {
+        assert!(repetitions > 0, "need at least one repetition");
+        let mut item_samples = vec![Vec::with_capacity(repetitions); self.items.len()];
+        // joules per run, with a meter
+        let mut item_energy : Vec<Vec<f64>> = vec![Vec::new(); self.items.len()];
+        let mut total_samples = Vec::with_capacity(repetitions);
+        let mut cpu_samples = Vec::with_capacity(repetitions);
+        let cold = self.run_cold_start();
+        self.run_warm_up();
+        let start_time = Instant::now();
+        // the items all repetitions so far completed
+        let mut completed = self.items.len();
+        for repetition in 0..repetitions {
+            if interrupt::requested() {
+                break;
+            }
+            let mut total = Duration::ZERO;
+            let start_cpu = process_cpu_time();
+            let durations : Vec<Duration> = match self.order {
+                ExecutionOrder::Grouped if self.replay.is_none() => self.items.iter().enumerate()
+                    .take_while(|_| !interrupt::requested())
+                    .map(|(i, entry)| {
+                        self.report_progress(i, repetition, repetitions, start_time);
+                        let (duration, energy) = self.run_item_metered(entry.item.as_ref());
+                        item_energy[i].extend(energy);
+                        duration
+                    })
+                    .collect(),
+                _ => self.run_scheduled(repetition, repetitions, start_time),
+            };
+            if durations.len() < completed {
+                if repetition > 0 {
+                    break;
+                }
+                completed = durations.len();
+            }
+            for (samples, duration) in item_samples.iter_mut().zip(durations) {
+                samples.push(duration);
+                total += duration;
+            }
+            total_samples.push(total);
+            if let Some(cpu) = process_cpu_time().zip(start_cpu).map(|(end, start)| end - start) {
+                cpu_samples.push(cpu);
+            }
+        }
+        self.report_progress(self.items.len(), repetitions - 1, repetitions, start_time);
+        let partial = total_samples.len() < repetitions || completed < self.items.len();
+        if total_samples.is_empty() {
+            // interrupted before the first item
+            total_samples.push(Duration::ZERO);
+        }
+
+        RepeatedRuns {
+            repetitions: total_samples.len(),
+            threads: self.threads,
+            items: self.items.iter()
+                .zip(&item_samples)
+                .zip(cold)
+                .zip(&item_energy)
+                .take(completed)
+                .map(|(((entry, samples), cold), energy)| ItemSummary {
+                    description: entry.item.description(),
+                    label: entry.label.clone(),
+                    phase: entry.phase.clone(),
+                    phase_path: entry.phase_path.clone(),
+                    op_count: entry.item.op_count(),
+                    setup: entry.setup,
+                    cold,
+                    summary: Summary::from_samples(samples),
+                    energy: (!energy.is_empty()).then(|| energy.iter().sum::<f64>() / energy.len() as f64),
+                    notes: entry.item.notes(),
+                })
+                .collect(),
+            total: Summary::from_samples(&total_samples),
+            total_cpu: (!cpu_samples.is_empty() && cpu_samples.len() == total_samples.len()).then(|| Summary::from_samples(&cpu_samples)),
+            profile_id: Some(Machine::current().id()),
+            partial,
         }
     }
 
     // convenience methods
     pub fn g1_exps(&mut self, num: usize) -> &mut Self {
-        self.items.push(Box::new(G1Exps::new(self.rng, num)));
-        self
+        let item = G1Exps::new(&mut self.rng, num);
+        self.push_item(item)
     }
     pub fn g2_exps(&mut self, num: usize) -> &mut Self {
-        self.items.push(Box::new(G2Exps::new(self.rng, num)));
-        self
+        let item = G2Exps::new(&mut self.rng, num);
+        self.push_item(item)
+    }
+    pub fn gt_exps(&mut self, num: usize) -> &mut Self {
+        let item = GtExps::new(&mut self.rng, num);
+        self.push_item(item)
     }
     pub fn g1_multi_exps(&mut self, num: usize, size: usize) -> &mut Self {
-        self.items.push(Box::new(G1MultiExps::new(self.rng, num, size)));
-        self
+        let item = G1MultiExps::new(&mut self.rng, num, size);
+        self.push_item(item)
     }
     pub fn g2_multi_exps(&mut self, num: usize, size: usize) -> &mut Self {
-        self.items.push(Box::new(G2MultiExps::new(self.rng, num, size)));
-        self
+        let item = G2MultiExps::new(&mut self.rng, num, size);
+        self.push_item(item)
+    }
+    pub fn gt_multi_exps(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = GtMultiExps::new(&mut self.rng, num, size);
+        self.push_item(item)
+    }
+    /// `num` multi-exps whose sizes are drawn from `sizes`, as a verifier's vary with the
+    /// messages it checks: `sim.g1_multi_exps_varying(100, &SizeDistribution::parse("zipf:1024:1.1")?)`.
+    pub fn g1_multi_exps_varying(&mut self, num: usize, sizes: &SizeDistribution) -> &mut Self {
+        let sizes = sizes.sample(num, &mut self.rng);
+        let item = G1MultiExps::with_sizes(&mut self.rng, sizes);
+        self.push_item(item)
+    }
+    pub fn g2_multi_exps_varying(&mut self, num: usize, sizes: &SizeDistribution) -> &mut Self {
+        let sizes = sizes.sample(num, &mut self.rng);
+        let item = G2MultiExps::with_sizes(&mut self.rng, sizes);
+        self.push_item(item)
+    }
+    pub fn gt_multi_exps_varying(&mut self, num: usize, sizes: &SizeDistribution) -> &mut Self {
+        let sizes = sizes.sample(num, &mut self.rng);
+        let item = GtMultiExps::with_sizes(&mut self.rng, sizes);
+        self.push_item(item)
+    }
+    /// `size` G1 bases for `g1_multi_exps_on`, so several items multi-exponentiate the same
+    /// points (sharing their cache lines) instead of fresh ones each:
+    /// `let key = sim.shared_bases_g1(n); sim.g1_multi_exps_on(&key, 2, n).g1_multi_exps_on(&key, 1, t);`
+    pub fn shared_bases_g1(&mut self, size: usize) -> SharedBases<G1Projective> {
+        SharedBases(Arc::new(self.rng.points(size)))
+    }
+    pub fn shared_bases_g2(&mut self, size: usize) -> SharedBases<G2Projective> {
+        SharedBases(Arc::new(self.rng.points(size)))
+    }
+    /// `num` multi-exps of size `size` over the first `size` of `bases`, with fresh scalars.
+    pub fn g1_multi_exps_on(&mut self, bases: &SharedBases<G1Projective>, num: usize, size: usize) -> &mut Self {
+        let item = G1MultiExps::with_bases(&mut self.rng, num, size, bases);
+        self.push_item(item)
+    }
+    pub fn g2_multi_exps_on(&mut self, bases: &SharedBases<G2Projective>, num: usize, size: usize) -> &mut Self {
+        let item = G2MultiExps::with_bases(&mut self.rng, num, size, bases);
+        self.push_item(item)
     }
     pub fn pairings(&mut self, num: usize) -> &mut Self {
-        self.items.push(Box::new(Pairings::new(self.rng, num)));
-        self
+        self.pairings_converting(num, false)
     }
+    /// Pairings whose inputs start out projective: with `to_affine`, converting each pair to
+    /// affine is timed as its own item next to the pairings, otherwise it is done in advance.
+    pub fn pairings_converting(&mut self, num: usize, to_affine: bool) -> &mut Self {
+        let item = Pairings::new(&mut self.rng, num);
+        if !to_affine {
+            return self.push_item(item);
+        }
+        let conversions = PairingInputConversions::new(&mut self.rng, num, 1, true);
+        self.group(|sim| { sim.push_item(conversions).push_item(item); })
+    }
+    /// The affine conversion of the inputs, the Miller loops (including the preparation of the
+    /// G2 points) and the final exponentiations of `num` multi-pairings are added as three items,
+    /// so they are reported separately.
     pub fn multi_pairings(&mut self, num: usize, size: usize) -> &mut Self {
-        self.items.push(Box::new(MultiPairings::new(self.rng, num, size)));
-        self
+        self.multi_pairings_converting(num, size, true)
+    }
+    /// Same as `multi_pairings`, leaving out the affine conversion unless `to_affine`, for
+    /// inputs that are affine already (e.g. deserialized points).
+    pub fn multi_pairings_converting(&mut self, num: usize, size: usize, to_affine: bool) -> &mut Self {
+        let conversions = to_affine.then(|| PairingInputConversions::new(&mut self.rng, num, size, true));
+        let miller_loops = MillerLoops::new(&mut self.rng, num, size, false);
+        let final_exps = FinalExponentiations::new(&mut self.rng, num);
+        self.group(|sim| {
+            if let Some(conversions) = conversions {
+                sim.push_item(conversions);
+            }
+            sim.push_item(miller_loops).push_item(final_exps);
+        })
+    }
+    /// Same as `multi_pairings_converting`, for `num` multi-pairings whose sizes are drawn from
+    /// `sizes`; the Miller loops and conversions of each multi-pairing have its size.
+    pub fn multi_pairings_varying(&mut self, num: usize, sizes: &SizeDistribution, to_affine: bool) -> &mut Self {
+        let sizes = sizes.sample(num, &mut self.rng);
+        let conversions = to_affine.then(|| PairingInputConversions::with_sizes(&mut self.rng, sizes.clone(), true));
+        let miller_loops = MillerLoops::with_sizes(&mut self.rng, sizes, false);
+        let final_exps = FinalExponentiations::new(&mut self.rng, num);
+        self.group(|sim| {
+            if let Some(conversions) = conversions {
+                sim.push_item(conversions);
+            }
+            sim.push_item(miller_loops).push_item(final_exps);
+        })
+    }
+    /// Multi-pairings whose G2 side is reused across calls: the one-off G2Prepared precomputation,
+    /// the affine conversion of the G1 points, the Miller loops and the final exponentiations
+    /// are added as four items.
+    pub fn multi_pairings_prepared(&mut self, num: usize, size: usize) -> &mut Self {
+        let preparations = G2Preparations::new(&mut self.rng, size);
+        let conversions = PairingInputConversions::new(&mut self.rng, num, size, false);
+        let miller_loops = MillerLoops::new(&mut self.rng, num, size, true);
+        let final_exps = FinalExponentiations::new(&mut self.rng, num);
+        self.group(|sim| { sim.push_item(preparations).push_item(conversions).push_item(miller_loops).push_item(final_exps); })
+    }
+    /// Multi-pairings whose first `static_inputs` G2 points are prepared in advance, e.g.
+    /// committee public keys prepared once for a whole run, and whose others are fresh: the
+    /// affine conversion of the G1 points and the fresh G2 points, the Miller loops (including
+    /// the preparation of the fresh points) and the final exponentiations are added as three
+    /// items. The preparation of the static points isn't included, see `g2_preparations`.
+    pub fn multi_pairings_static(&mut self, num: usize, size: usize, static_inputs: usize) -> &mut Self {
+        assert!(static_inputs <= size, "at most all G2 inputs can be static");
+        let conversions = PairingInputConversions::with_g2(&mut self.rng, num, size, size - static_inputs);
+        let miller_loops = MillerLoops::partly_prepared(&mut self.rng, num, size, static_inputs);
+        let final_exps = FinalExponentiations::new(&mut self.rng, num);
+        self.group(|sim| { sim.push_item(conversions).push_item(miller_loops).push_item(final_exps); })
+    }
+    /// `num` G2Prepared precomputations on their own.
+    pub fn g2_preparations(&mut self, num: usize) -> &mut Self {
+        let item = G2Preparations::new(&mut self.rng, num);
+        self.push_item(item)
+    }
+    /// `num` multi-Miller loops of `size` affine pairs with G2 prepared in advance, without the
+    /// final exponentiation.
+    pub fn miller_loops(&mut self, num: usize, size: usize) -> &mut Self {
+        let item = MillerLoops::new(&mut self.rng, num, size, true);
+        self.push_item(item)
+    }
+    /// `num` checks that a product of `lhs_size` pairings equals one of `rhs_size` pairings, each
+    /// one multi-pairing over both sides after negating the right-hand G1 points.
+    pub fn pairing_equality_checks(&mut self, num: usize, lhs_size: usize, rhs_size: usize) -> &mut Self {
+        let item = PairingEqualityChecks::new(&mut self.rng, num, lhs_size, rhs_size);
+        self.push_item(item)
+    }
+    pub fn final_exponentiations(&mut self, num: usize) -> &mut Self {
+        let item = FinalExponentiations::new(&mut self.rng, num);
+        self.push_item(item)
     }
 }
@@ -0,0 +1,193 @@
+//! Arithmetic on Gt elements as a verifier does it on elements received over the network, e.g.
+//! combining the Gt parts of aggregated transcripts: multiplications (the group law, which
+//! blstrs writes additively), inversions, equality checks and membership checks, which an
+//! element decoded from untrusted bytes needs before the others mean anything.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+use crate::test_vectors::{concat_outputs, OutputBytes};
+
+use blst::{blst_fp12, blst_fp12_in_group, blst_fp12_inverse};
+use blstrs::Gt;
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GtInversion {
+    /// the conjugate in Fp12, which is the inverse in the cyclotomic subgroup (blstrs' negation)
+    Conjugation,
+    /// a full Fp12 inversion, the inverse of any non-zero element, members of Gt or not
+    Field,
+}
+
+impl GtInversion {
+    pub const ALL: [GtInversion; 2] = [GtInversion::Conjugation, GtInversion::Field];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GtInversion::Conjugation => "conjugation",
+            GtInversion::Field => "Fp12 inversion",
+        }
+    }
+
+    pub fn invert(&self, x: &Gt) -> Gt {
+        match self {
+            GtInversion::Conjugation => -x,
+            GtInversion::Field => {
+                let mut out = blst_fp12::default();
+                // SAFETY: Gt is a repr(transparent) wrapper of blstrs' Fp12, itself one of
+                // blst_fp12, and blst reads and writes one element through each pointer
+                unsafe {
+                    blst_fp12_inverse(&mut out, x as *const Gt as *const blst_fp12);
+                    std::mem::transmute::<blst_fp12, Gt>(out)
+                }
+            }
+        }
+    }
+}
+
+/// Whether `x` is in Gt, the order-r subgroup of Fp12 (blstrs keeps its check private).
+pub fn is_in_gt(x: &Gt) -> bool {
+    // SAFETY: as in `GtInversion::invert`
+    unsafe { blst_fp12_in_group(x as *const Gt as *const blst_fp12) }
+}
+
+struct GtMuls {
+    lhs: Vec<Gt>,
+    rhs: Vec<Gt>,
+}
+
+impl GroupOpsSimulationItem for GtMuls {
+    fn description(&self) -> String {
+        format!("Gt multiplications: {}", self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Gt>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| a + b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] + self.rhs[i]));
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<Gt> = self.lhs.iter().zip(&self.rhs).map(|(a, b)| a + b).collect();
+        Some(concat_outputs(&results))
+    }
+}
+
+struct GtInversions {
+    method: GtInversion,
+    args: Vec<Gt>,
+}
+
+impl GroupOpsSimulationItem for GtInversions {
+    fn description(&self) -> String {
+        format!("Gt inversions ({}): {}", self.method.name(), self.args.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.args.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Gt>>(self.args.iter().map(|x| self.method.invert(x)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.args.len(), |i| self.method.invert(&self.args[i])));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.args.iter().all(|x| self.method.invert(x) + x == Gt::identity()))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(self.args.iter().flat_map(|x| self.method.invert(x).output_bytes()).collect())
+    }
+}
+
+// equal pairs, as a verifier's checks pass, so that every limb is compared
+struct GtEqualityChecks {
+    lhs: Vec<Gt>,
+    rhs: Vec<Gt>,
+}
+
+impl GroupOpsSimulationItem for GtEqualityChecks {
+    fn description(&self) -> String {
+        format!("Gt equality checks: {}", self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.lhs.iter().zip(&self.rhs).map(|(a, b)| a == b).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.lhs[i] == self.rhs[i]));
+    }
+}
+
+struct GtMembershipChecks {
+    args: Vec<Gt>,
+}
+
+impl GroupOpsSimulationItem for GtMembershipChecks {
+    fn description(&self) -> String {
+        format!("Gt membership checks: {}", self.args.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.args.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.args.iter().map(is_in_gt).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.args.len(), |i| is_in_gt(&self.args[i])));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.args.iter().all(is_in_gt))
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    pub fn gt_muls(&mut self, num: usize) -> &mut Self {
+        let item = GtMuls { lhs: self.rng().points(num), rhs: self.rng().points(num) };
+        self.push_item(item)
+    }
+    /// The same `num` inversions with every `GtInversion`, as one item each.
+    pub fn gt_inversions(&mut self, num: usize) -> &mut Self {
+        let args : Vec<Gt> = self.rng().points(num);
+        self.group(|sim| {
+            for method in GtInversion::ALL {
+                sim.push_item(GtInversions { method, args: args.clone() });
+            }
+        })
+    }
+    pub fn gt_equality_checks(&mut self, num: usize) -> &mut Self {
+        let lhs : Vec<Gt> = self.rng().points(num);
+        let item = GtEqualityChecks { rhs: lhs.clone(), lhs };
+        self.push_item(item)
+    }
+    /// `num` checks that elements (of Gt, as a verifier's checks pass) are in Gt.
+    pub fn gt_membership_checks(&mut self, num: usize) -> &mut Self {
+        let item = GtMembershipChecks { args: self.rng().points(num) };
+        self.push_item(item)
+    }
+}
@@ -0,0 +1,105 @@
+use blst::{blst_fr, blst_fr_from_scalar, blst_scalar, blst_scalar_from_be_bytes, blst_sha256};
+use blstrs::Scalar;
+
+
+pub(crate) fn sha256(msg: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    // SAFETY: blst writes exactly 32 bytes to `out` and reads `msg.len()` bytes from `msg`
+    unsafe { blst_sha256(out.as_mut_ptr(), msg.as_ptr(), msg.len()) };
+    out
+}
+
+/// Fiat–Shamir challenge: hashes `domain` and the length-prefixed `inputs` to 64 bytes and
+/// reduces them mod r, so the result is statistically close to uniform.
+pub(crate) fn hash_to_scalar(domain: &[u8], inputs: &[&[u8]]) -> Scalar {
+    let mut msg = Vec::with_capacity(domain.len() + inputs.iter().map(|i| i.len() + 8).sum::<usize>() + 1);
+    msg.extend_from_slice(domain);
+    for input in inputs {
+        msg.extend_from_slice(&(input.len() as u64).to_be_bytes());
+        msg.extend_from_slice(input);
+    }
+    let mut wide = [0u8; 64];
+    for (counter, half) in wide.chunks_exact_mut(32).enumerate() {
+        msg.push(counter as u8);
+        half.copy_from_slice(&sha256(&msg));
+        msg.pop();
+    }
+    let mut scalar = blst_scalar::default();
+    let mut fr = blst_fr::default();
+    // SAFETY: blst reads `wide.len()` bytes and writes one value to each output
+    unsafe {
+        blst_scalar_from_be_bytes(&mut scalar, wide.as_ptr(), wide.len());
+        blst_fr_from_scalar(&mut fr, &scalar);
+    }
+    Scalar::from(fr)
+}
+
+// BLAKE2s-256 without a key (RFC 7693)
+const BLAKE2S_IV : [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+const BLAKE2S_SIGMA : [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+fn blake2s_compress(h: &mut [u32; 8], block: &[u8; 64], bytes: u64, last: bool) {
+    let m : Vec<u32> = block.chunks_exact(4).map(|word| u32::from_le_bytes(word.try_into().unwrap())).collect();
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&BLAKE2S_IV);
+    v[12] ^= bytes as u32;
+    v[13] ^= (bytes >> 32) as u32;
+    if last {
+        v[14] = !v[14];
+    }
+    let mut g = |a: usize, b: usize, c: usize, d: usize, x: u32, y: u32| {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(12);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(8);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(7);
+    };
+    for s in &BLAKE2S_SIGMA {
+        g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+    for (i, word) in h.iter_mut().enumerate() {
+        *word ^= v[i] ^ v[i + 8];
+    }
+}
+
+pub(crate) fn blake2s(msg: &[u8]) -> [u8; 32] {
+    let mut h = BLAKE2S_IV;
+    // no key, 32-byte digest
+    h[0] ^= 0x0101_0020;
+    // the last block, even if full or empty, is compressed with the final flag
+    let full_blocks = msg.len().saturating_sub(1) / 64;
+    for (i, block) in msg.chunks_exact(64).take(full_blocks).enumerate() {
+        blake2s_compress(&mut h, block.try_into().unwrap(), 64 * (i as u64 + 1), false);
+    }
+    let mut last = [0u8; 64];
+    let rest = &msg[64 * full_blocks..];
+    last[..rest.len()].copy_from_slice(rest);
+    blake2s_compress(&mut h, &last, msg.len() as u64, true);
+    let mut out = [0u8; 32];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
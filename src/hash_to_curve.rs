@@ -0,0 +1,389 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blst::{
+    blst_expand_message_xmd, blst_fp, blst_fp2, blst_fp2_eucl_inverse, blst_fp2_inverse, blst_fp2_is_square, blst_fp2_mul, blst_fp2_sqr,
+    blst_fp2_sqrt, blst_fp_eucl_inverse, blst_fp_from_bendian, blst_fp_inverse, blst_fp_is_square, blst_fp_mul, blst_fp_sqr, blst_fp_sqrt,
+    blst_map_to_g1, blst_map_to_g2,
+};
+use blstrs::{G1Projective, G2Projective};
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+use std::ptr;
+
+
+// the ciphersuites of the IETF BLS signature draft, with signatures in G1 or G2
+trait HashToCurve : NamedGroup + Sized {
+    const DST: &'static [u8];
+    /// the bytes expand_message_xmd produces for the two field elements hashed to
+    const EXPANDED_LEN: usize;
+
+    /// the base field, `Fp` or `Fp2`
+    const FIELD: &'static str;
+
+    type Field : Copy + PartialEq + Send + Sync;
+
+    fn random_field_element(rng: &mut impl RngCore) -> Self::Field;
+
+    /// SSWU-map `u` and `v`, add them and clear the cofactor: the random oracle variant of the
+    /// hash after `hash_to_field`.
+    fn map(u: &Self::Field, v: &Self::Field) -> Self;
+
+    /// SSWU-map `u` alone and clear the cofactor: the nonuniform encoding (`encode_to_curve`).
+    fn map_one(u: &Self::Field) -> Self;
+
+    fn field_op(op: FieldOp, a: &Self::Field, b: &Self::Field) -> FieldResult<Self::Field>;
+}
+
+/// Operations in the base fields that hashing to the curves is made of: the square root is
+/// most of an SSWU map, and an inversion makes its result affine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FieldOp {
+    Mul,
+    Square,
+    /// a square root, or none if there is none (constant-time)
+    Sqrt,
+    /// the Legendre symbol, as the map tells which of its two candidates is a square
+    IsSquare,
+    /// constant-time, by Fermat's little theorem
+    Inversion,
+    /// variable-time, by the extended Euclidean algorithm
+    EuclideanInversion,
+}
+
+impl FieldOp {
+    const ALL: [FieldOp; 6] = [FieldOp::Mul, FieldOp::Square, FieldOp::Sqrt, FieldOp::IsSquare, FieldOp::Inversion, FieldOp::EuclideanInversion];
+
+    fn name(&self) -> &'static str {
+        match self {
+            FieldOp::Mul => "multiplications",
+            FieldOp::Square => "squarings",
+            FieldOp::Sqrt => "square roots",
+            FieldOp::IsSquare => "quadratic residuosity checks",
+            FieldOp::Inversion => "inversions (constant-time)",
+            FieldOp::EuclideanInversion => "inversions (Euclidean)",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldResult<F> {
+    Element(F),
+    /// the square root of a non-square
+    NoRoot,
+    IsSquare(bool),
+}
+
+macro_rules! field_op {
+    ($op:expr, $a:expr, $b:expr, $field:ty, $mul:ident, $sqr:ident, $sqrt:ident, $is_square:ident, $inverse:ident, $eucl_inverse:ident) => {{
+        let mut out = <$field>::default();
+        // SAFETY: blst reads and writes one field element through each pointer
+        unsafe {
+            match $op {
+                FieldOp::Mul => $mul(&mut out, $a, $b),
+                FieldOp::Square => $sqr(&mut out, $a),
+                FieldOp::Sqrt if !$sqrt(&mut out, $a) => return FieldResult::NoRoot,
+                FieldOp::Sqrt => {}
+                FieldOp::IsSquare => return FieldResult::IsSquare($is_square($a)),
+                FieldOp::Inversion => $inverse(&mut out, $a),
+                FieldOp::EuclideanInversion => $eucl_inverse(&mut out, $a),
+            }
+        }
+        FieldResult::Element(out)
+    }};
+}
+
+impl HashToCurve for G1Projective {
+    const DST: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+    const EXPANDED_LEN: usize = 2 * 64;
+
+    const FIELD: &'static str = "Fp";
+
+    type Field = blst_fp;
+
+    fn random_field_element(rng: &mut impl RngCore) -> blst_fp {
+        random_fp(rng)
+    }
+
+    fn map(u: &blst_fp, v: &blst_fp) -> Self {
+        let mut out = G1Projective::identity();
+        // SAFETY: blst reads one field element from each input and writes one point to `out`
+        unsafe { blst_map_to_g1(out.as_mut(), u, v) };
+        out
+    }
+
+    fn map_one(u: &blst_fp) -> Self {
+        let mut out = G1Projective::identity();
+        // SAFETY: blst reads one field element from `u`, maps it alone when `v` is null, and
+        // writes one point to `out`
+        unsafe { blst_map_to_g1(out.as_mut(), u, ptr::null()) };
+        out
+    }
+
+    fn field_op(op: FieldOp, a: &blst_fp, b: &blst_fp) -> FieldResult<blst_fp> {
+        field_op!(op, a, b, blst_fp, blst_fp_mul, blst_fp_sqr, blst_fp_sqrt, blst_fp_is_square, blst_fp_inverse, blst_fp_eucl_inverse)
+    }
+}
+
+impl HashToCurve for G2Projective {
+    const DST: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+    const EXPANDED_LEN: usize = 2 * 2 * 64;
+
+    const FIELD: &'static str = "Fp2";
+
+    type Field = blst_fp2;
+
+    fn random_field_element(rng: &mut impl RngCore) -> blst_fp2 {
+        blst_fp2 { fp: [random_fp(rng), random_fp(rng)] }
+    }
+
+    fn map(u: &blst_fp2, v: &blst_fp2) -> Self {
+        let mut out = G2Projective::identity();
+        // SAFETY: blst reads one field element from each input and writes one point to `out`
+        unsafe { blst_map_to_g2(out.as_mut(), u, v) };
+        out
+    }
+
+    fn map_one(u: &blst_fp2) -> Self {
+        let mut out = G2Projective::identity();
+        // SAFETY: as for G1
+        unsafe { blst_map_to_g2(out.as_mut(), u, ptr::null()) };
+        out
+    }
+
+    fn field_op(op: FieldOp, a: &blst_fp2, b: &blst_fp2) -> FieldResult<blst_fp2> {
+        field_op!(op, a, b, blst_fp2, blst_fp2_mul, blst_fp2_sqr, blst_fp2_sqrt, blst_fp2_is_square, blst_fp2_inverse, blst_fp2_eucl_inverse)
+    }
+}
+
+fn random_fp(rng: &mut impl RngCore) -> blst_fp {
+    let mut bytes = [0u8; 48];
+    rng.fill_bytes(&mut bytes);
+    // below p, whose top byte is 0x1a
+    bytes[0] &= 0x0f;
+    let mut fp = blst_fp::default();
+    // SAFETY: blst reads 48 bytes and writes one field element
+    unsafe { blst_fp_from_bendian(&mut fp, bytes.as_ptr()) };
+    fp
+}
+
+// expand_message_xmd of random messages to the bytes of two field elements
+struct MessageExpansions<T> {
+    messages: Vec<Vec<u8>>,
+    dst: Vec<u8>,
+    _group: std::marker::PhantomData<T>,
+}
+
+impl<T> MessageExpansions<T>
+where T : HashToCurve {
+    fn new(rng: &mut impl RngCore, num: usize, msg_len: usize, dst: &[u8]) -> Self {
+        let messages = (0..num)
+            .map(|_| {
+                let mut msg = vec![0; msg_len];
+                rng.fill_bytes(&mut msg);
+                msg
+            })
+            .collect();
+        Self { messages, dst: dst.to_vec(), _group: std::marker::PhantomData }
+    }
+
+    fn expand(&self, msg: &[u8]) -> Vec<u8> {
+        let mut out = vec![0; T::EXPANDED_LEN];
+        // SAFETY: blst writes `out.len()` bytes to `out` and reads `msg.len()` and `dst.len()`
+        // bytes from the inputs
+        unsafe { blst_expand_message_xmd(out.as_mut_ptr(), out.len(), msg.as_ptr(), msg.len(), self.dst.as_ptr(), self.dst.len()) };
+        out
+    }
+}
+
+impl<T> GroupOpsSimulationItem for MessageExpansions<T>
+where T : HashToCurve + Send + Sync {
+    fn description(&self) -> String {
+        let msg_len = self.messages.first().map_or(0, Vec::len);
+        format!("expand_message_xmd for {} hashes: {} of {}-byte messages, {}-byte DST", T::NAME, self.messages.len(), msg_len, self.dst.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.messages.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Vec<u8>>>(self.messages.iter().map(|msg| self.expand(msg)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.messages.len(), |i| self.expand(&self.messages[i])));
+    }
+}
+
+// the rest of the hashes: mapping two field elements to the curve each, or one for the
+// nonuniform encoding
+struct MapsToCurve<T : HashToCurve> {
+    inputs: Vec<(T::Field, T::Field)>,
+    one: bool,
+}
+
+impl<T> MapsToCurve<T>
+where T : HashToCurve {
+    fn new(rng: &mut impl RngCore, num: usize, one: bool) -> Self {
+        Self { inputs: (0..num).map(|_| (T::random_field_element(rng), T::random_field_element(rng))).collect(), one }
+    }
+
+    fn map(&self, i: usize) -> T {
+        let (u, v) = &self.inputs[i];
+        match self.one {
+            true => T::map_one(u),
+            false => T::map(u, v),
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for MapsToCurve<T>
+where T : HashToCurve + Send + Sync {
+    fn description(&self) -> String {
+        match self.one {
+            true => format!("{} maps to curve of one field element: {}", T::NAME, self.inputs.len()),
+            false => format!("{} maps to curve: {}", T::NAME, self.inputs.len()),
+        }
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.inputs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>((0..self.inputs.len()).map(|i| self.map(i)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.inputs.len(), |i| self.map(i)));
+    }
+}
+
+struct FieldOps<T : HashToCurve> {
+    op: FieldOp,
+    lhs: Vec<T::Field>,
+    rhs: Vec<T::Field>,
+}
+
+impl<T> FieldOps<T>
+where T : HashToCurve {
+    fn new(rng: &mut impl RngCore, op: FieldOp, num: usize) -> Self {
+        let lhs = (0..num).map(|_| T::random_field_element(rng)).collect();
+        let rhs = (0..num).map(|_| T::random_field_element(rng)).collect();
+        Self { op, lhs, rhs }
+    }
+
+    fn apply(&self, i: usize) -> FieldResult<T::Field> {
+        T::field_op(self.op, &self.lhs[i], &self.rhs[i])
+    }
+}
+
+impl<T> GroupOpsSimulationItem for FieldOps<T>
+where T : HashToCurve + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} {}: {}", T::FIELD, self.op.name(), self.lhs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.lhs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<_>>((0..self.lhs.len()).map(|i| self.apply(i)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.lhs.len(), |i| self.apply(i)));
+    }
+
+    fn notes(&self) -> Option<String> {
+        let squares = (0..self.lhs.len()).filter(|&i| T::field_op(FieldOp::IsSquare, &self.lhs[i], &self.rhs[i]) == FieldResult::IsSquare(true)).count();
+        matches!(self.op, FieldOp::Sqrt | FieldOp::IsSquare).then(|| format!("{} of the {} inputs are squares", squares, self.lhs.len()))
+    }
+
+    // square roots square back, the two inversions agree and undo themselves, and the Legendre
+    // symbol says whether there is a root
+    fn validate(&self) -> Option<bool> {
+        let op = |op, a: &T::Field, b: &T::Field| T::field_op(op, a, b);
+        Some((0..self.lhs.len()).all(|i| {
+            let (a, b) = (&self.lhs[i], &self.rhs[i]);
+            match self.op {
+                FieldOp::Mul => op(FieldOp::Mul, a, b) == op(FieldOp::Mul, b, a),
+                FieldOp::Square => op(FieldOp::Square, a, b) == op(FieldOp::Mul, a, a),
+                FieldOp::Sqrt | FieldOp::IsSquare => match op(FieldOp::Sqrt, a, b) {
+                    FieldResult::Element(root) => op(FieldOp::Square, &root, b) == FieldResult::Element(*a) && op(FieldOp::IsSquare, a, b) == FieldResult::IsSquare(true),
+                    _ => op(FieldOp::IsSquare, a, b) == FieldResult::IsSquare(false),
+                },
+                FieldOp::Inversion | FieldOp::EuclideanInversion => match (op(FieldOp::Inversion, a, b), op(FieldOp::EuclideanInversion, a, b)) {
+                    (FieldResult::Element(inverse), euclidean) => euclidean == FieldResult::Element(inverse) && op(self.op, &inverse, b) == FieldResult::Element(*a),
+                    _ => false,
+                },
+            }
+        }))
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// Hashes of `msg_len`-byte messages to G1 (SSWU, random oracle variant), as in signing
+    /// with signatures in G1, with the ciphersuite's DST.
+    pub fn g1_hash_to_curve(&mut self, num: usize, msg_len: usize) -> &mut Self {
+        self.g1_hash_to_curve_with_dst(num, msg_len, G1Projective::DST)
+    }
+
+    /// Hashes to G1 as two items, expand_message_xmd of the messages with `dst`, which grows
+    /// with the message length, and the mapping to the curve, which doesn't. Reducing the
+    /// expanded bytes to field elements is left out, at a fraction of a percent of the hash.
+    pub fn g1_hash_to_curve_with_dst(&mut self, num: usize, msg_len: usize, dst: &[u8]) -> &mut Self {
+        let expansions = MessageExpansions::<G1Projective>::new(self.rng(), num, msg_len, dst);
+        let maps = MapsToCurve::<G1Projective>::new(self.rng(), num, false);
+        self.group(|sim| { sim.push_item(expansions).push_item(maps); })
+    }
+
+    /// Hashes of `msg_len`-byte messages to G2 (SSWU, random oracle variant), as in signing
+    /// with signatures in G2, with the ciphersuite's DST.
+    pub fn g2_hash_to_curve(&mut self, num: usize, msg_len: usize) -> &mut Self {
+        self.g2_hash_to_curve_with_dst(num, msg_len, G2Projective::DST)
+    }
+
+    /// Same as `g1_hash_to_curve_with_dst`, in G2.
+    pub fn g2_hash_to_curve_with_dst(&mut self, num: usize, msg_len: usize, dst: &[u8]) -> &mut Self {
+        let expansions = MessageExpansions::<G2Projective>::new(self.rng(), num, msg_len, dst);
+        let maps = MapsToCurve::<G2Projective>::new(self.rng(), num, false);
+        self.group(|sim| { sim.push_item(expansions).push_item(maps); })
+    }
+
+    fn hash_to_curve_steps<T>(&mut self, num: usize) -> &mut Self
+    where T : HashToCurve + Send + Sync + 'static {
+        self.group(|sim| {
+            for op in FieldOp::ALL {
+                let item = FieldOps::<T>::new(sim.rng(), op, num);
+                sim.push_item(item);
+            }
+            for one in [true, false] {
+                let item = MapsToCurve::<T>::new(sim.rng(), num, one);
+                sim.push_item(item);
+            }
+        })
+    }
+
+    /// The steps of hashing to G1 after `hash_to_field`, `num` of each: multiplications,
+    /// squarings, square roots, Legendre symbols and inversions (constant-time and Euclidean) in
+    /// Fp, and the maps of one and of two field elements to the curve. blst doesn't expose the
+    /// SSWU map on its own, but it is the difference of the two maps (with an addition on the
+    /// isogenous curve), and the isogeny and the cofactor clearing are what is left of the map
+    /// of one element.
+    pub fn g1_hash_to_curve_steps(&mut self, num: usize) -> &mut Self {
+        self.hash_to_curve_steps::<G1Projective>(num)
+    }
+
+    /// Same as `g1_hash_to_curve_steps`, in Fp2 and G2.
+    pub fn g2_hash_to_curve_steps(&mut self, num: usize) -> &mut Self {
+        self.hash_to_curve_steps::<G2Projective>(num)
+    }
+}
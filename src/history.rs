@@ -0,0 +1,341 @@
+//! Every measured point of a preset, kept in a history that runs append to, so that months of
+//! experiments stay searchable instead of scattered over terminal scrollback.
+//!
+//! The history is a directory (`history` by default) of two files. `runs.jsonl` holds one record
+//! per line:
+//!
+//! ```json
+//! {"date": 1760529600, "command": "sweep", "preset": "scrape", "params": {"n": 64, "t": 42}, "profile_id": "3f2a9c0e51d7b846", "seed": 42, "reps": 1, "phases": {"Dealer": 19229000, "Verifier": 61406000}, "total": 80635000}
+//! ```
+//!
+//! with the date in seconds since the Unix epoch and the times in nanoseconds (means, with
+//! `reps` above 1). `index.tsv` has one line per record, with its byte offset and length in
+//! `runs.jsonl`, its date, preset and profile ID, so that queries by those only read the records
+//! that match. Records past the end of the index (e.g. after the index was deleted) are read
+//! anyway.
+
+use crate::clock;
+use crate::json::{self, Value};
+use crate::protocol::Params;
+use crate::render::{Cell, Table, Totals};
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Io(e) => write!(f, "{}", e),
+            HistoryError::Invalid(message) => write!(f, "invalid history: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl From<std::io::Error> for HistoryError {
+    fn from(e: std::io::Error) -> Self {
+        HistoryError::Io(e)
+    }
+}
+
+/// The times of one point of a preset, as measured by one run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// seconds since the Unix epoch
+    pub date: u64,
+    /// the command that measured it, e.g. `sweep`
+    pub command: String,
+    pub preset: String,
+    pub params: Params,
+    pub profile_id: Option<String>,
+    pub seed: Option<u64>,
+    pub reps: usize,
+    pub phases: Vec<(String, Duration)>,
+    pub total: Duration,
+}
+
+impl Record {
+    /// A record of now.
+    pub fn new(command: &str, preset: &str, params: &Params, phases: &[(String, Duration)], total: Duration) -> Self {
+        Self {
+            date: clock::system_time().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs()),
+            command: command.to_string(),
+            preset: preset.to_string(),
+            params: params.clone(),
+            profile_id: None,
+            seed: None,
+            reps: 1,
+            phases: phases.to_vec(),
+            total,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let number = |x: f64| Value::Number(x);
+        let nanos = |duration: &Duration| number(duration.as_nanos() as f64);
+        Value::Object(vec![
+            ("date".to_string(), number(self.date as f64)),
+            ("command".to_string(), Value::String(self.command.clone())),
+            ("preset".to_string(), Value::String(self.preset.clone())),
+            ("params".to_string(), Value::Object(self.params.iter().map(|(name, value)| (name.to_string(), number(value as f64))).collect())),
+            ("profile_id".to_string(), self.profile_id.clone().map_or(Value::Null, Value::String)),
+            // as a string, since JSON numbers lose the low bits of large seeds
+            ("seed".to_string(), self.seed.map_or(Value::Null, |seed| Value::String(seed.to_string()))),
+            ("reps".to_string(), number(self.reps as f64)),
+            ("phases".to_string(), Value::Object(self.phases.iter().map(|(name, time)| (name.clone(), nanos(time))).collect())),
+            ("total".to_string(), nanos(&self.total)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let number = |key: &str| value.get(key).and_then(Value::as_f64).ok_or(format!("`{}` must be a number", key));
+        let string = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string).ok_or(format!("`{}` must be a string", key));
+        let optional = |key: &str| match value.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(format!("`{}` must be a string", key)),
+        };
+        let pairs = |key: &str| match value.get(key) {
+            Some(Value::Object(pairs)) => pairs.iter()
+                .map(|(name, value)| value.as_f64().map(|x| (name.clone(), x)).ok_or(format!("`{}` must map names to numbers", key)))
+                .collect::<Result<Vec<_>, _>>(),
+            _ => Err(format!("`{}` must be an object", key)),
+        };
+        let mut params = Params::new();
+        for (name, value) in pairs("params")? {
+            params.insert(&name, value as i64);
+        }
+        let seed = optional("seed")?.map(|seed| seed.parse().map_err(|_| "`seed` must be an integer".to_string())).transpose()?;
+        Ok(Self {
+            date: number("date")? as u64,
+            command: string("command")?,
+            preset: string("preset")?,
+            params,
+            profile_id: optional("profile_id")?,
+            seed,
+            reps: number("reps")? as usize,
+            phases: pairs("phases")?.into_iter().map(|(name, ns)| (name, Duration::from_nanos(ns as u64))).collect(),
+            total: Duration::from_nanos(number("total")? as u64),
+        })
+    }
+}
+
+/// Which records a query returns: those matching every criterion set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    pub preset: Option<String>,
+    /// parameters the record must have, with these values
+    pub params: Vec<(String, i64)>,
+    /// a prefix of the profile ID
+    pub profile_id: Option<String>,
+    /// dates in seconds since the Unix epoch, `until` excluded
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl Filter {
+    // the criteria that the index has
+    fn admits(&self, date: u64, preset: &str, profile_id: Option<&str>) -> bool {
+        self.preset.as_ref().is_none_or(|name| name == preset)
+            && self.profile_id.as_ref().is_none_or(|prefix| profile_id.is_some_and(|id| id.starts_with(prefix.as_str())))
+            && self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date < until)
+    }
+
+    pub fn matches(&self, record: &Record) -> bool {
+        self.admits(record.date, &record.preset, record.profile_id.as_deref())
+            && self.params.iter().all(|(name, value)| record.params.get(name) == Some(*value))
+    }
+}
+
+/// A history directory.
+pub struct History {
+    dir: PathBuf,
+}
+
+// one line of the index
+struct IndexEntry {
+    offset: u64,
+    length: u64,
+    date: u64,
+    preset: String,
+    profile_id: Option<String>,
+}
+
+impl History {
+    /// Where runs record their points unless told otherwise.
+    pub const DEFAULT_DIR : &'static str = "history";
+
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    fn runs_path(&self) -> PathBuf {
+        self.dir.join("runs.jsonl")
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.tsv")
+    }
+
+    /// Appends a record to the history, and its entry to the index, creating both as needed.
+    pub fn append(&self, record: &Record) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut line = record.to_json().to_compact_string();
+        line.push('\n');
+        let mut runs = OpenOptions::new().create(true).append(true).open(self.runs_path())?;
+        let offset = runs.seek(SeekFrom::End(0))?;
+        runs.write_all(line.as_bytes())?;
+        let profile_id = record.profile_id.as_deref().unwrap_or("-");
+        let entry = format!("{}\t{}\t{}\t{}\t{}\n", offset, line.len(), record.date, record.preset, profile_id);
+        OpenOptions::new().create(true).append(true).open(self.index_path())?.write_all(entry.as_bytes())
+    }
+
+    /// The records that match `filter`, oldest first; an empty list if there is no history yet.
+    pub fn query(&self, filter: &Filter) -> Result<Vec<Record>, HistoryError> {
+        let runs = match fs::read(self.runs_path()) {
+            Ok(runs) => runs,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let index = self.index()?;
+        let mut records = Vec::new();
+        let mut parse = |bytes: &[u8], line: usize| -> Result<(), HistoryError> {
+            let invalid = |message: String| HistoryError::Invalid(format!("runs.jsonl, record {}: {}", line, message));
+            let text = std::str::from_utf8(bytes).map_err(|_| invalid("not UTF-8".to_string()))?;
+            let record = Record::from_json(&json::parse(text).map_err(|e| invalid(e.to_string()))?).map_err(invalid)?;
+            if filter.matches(&record) {
+                records.push(record);
+            }
+            Ok(())
+        };
+        let mut indexed = 0;
+        for (i, entry) in index.iter().enumerate() {
+            let end = entry.offset + entry.length;
+            if end > runs.len() as u64 {
+                return Err(HistoryError::Invalid(format!("index.tsv, line {}: past the end of runs.jsonl", i + 1)));
+            }
+            if filter.admits(entry.date, &entry.preset, entry.profile_id.as_deref()) {
+                parse(&runs[entry.offset as usize..end as usize], i + 1)?;
+            }
+            indexed = indexed.max(end);
+        }
+        let rest = &runs[indexed as usize..];
+        for (i, line) in rest.split(|&b| b == b'\n').filter(|line| !line.is_empty()).enumerate() {
+            parse(line, index.len() + i + 1)?;
+        }
+        Ok(records)
+    }
+
+    fn index(&self) -> Result<Vec<IndexEntry>, HistoryError> {
+        let mut text = String::new();
+        match fs::File::open(self.index_path()) {
+            Ok(mut file) => file.read_to_string(&mut text)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        text.lines().enumerate()
+            .map(|(i, line)| {
+                let invalid = || HistoryError::Invalid(format!("index.tsv, line {}: expected offset, length, date, preset and profile ID", i + 1));
+                let fields : Vec<&str> = line.split('\t').collect();
+                let [offset, length, date, preset, profile_id] = fields[..] else { return Err(invalid()) };
+                Ok(IndexEntry {
+                    offset: offset.parse().map_err(|_| invalid())?,
+                    length: length.parse().map_err(|_| invalid())?,
+                    date: date.parse().map_err(|_| invalid())?,
+                    preset: preset.to_string(),
+                    profile_id: (profile_id != "-").then(|| profile_id.to_string()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The seconds a date covers, from `YYYY-MM-DD` (the whole day) or `YYYY-MM-DDTHH:MM:SS` (one
+/// second), in UTC, as seconds since the Unix epoch.
+pub fn parse_date(text: &str) -> Option<std::ops::Range<u64>> {
+    let (day, time) = match text.split_once('T') {
+        Some((day, time)) => (day, Some(time)),
+        None => (text, None),
+    };
+    let numbers = |text: &str, separator: char| -> Option<Vec<i64>> { text.split(separator).map(|part| part.parse().ok()).collect() };
+    let [year, month, day] = numbers(day, '-')?[..] else { return None };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let start = u64::try_from(days_from_civil(year, month, day) * 86400).ok()?;
+    let Some(time) = time else { return Some(start..start + 86400) };
+    let [hours, minutes, seconds] = numbers(time, ':')?[..] else { return None };
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) || !(0..60).contains(&seconds) {
+        return None;
+    }
+    let start = start + (hours * 3600 + minutes * 60 + seconds) as u64;
+    Some(start..start + 1)
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in UTC.
+pub fn format_date(date: u64) -> String {
+    let (year, month, day) = civil_from_days((date / 86400) as i64);
+    let seconds = date % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+// days since 1970-01-01 of a date of the proleptic Gregorian calendar (Hinnant's algorithm)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
+/// One row per record: its date, preset, parameters and profile, the times of the phases of all
+/// records, in the order they first appear, and the total.
+pub fn table(records: &[Record]) -> Table {
+    let mut phases : Vec<&str> = Vec::new();
+    for (name, _) in records.iter().flat_map(|record| &record.phases) {
+        if !phases.contains(&name.as_str()) {
+            phases.push(name);
+        }
+    }
+    let header = ["date", "command", "preset", "parameters", "profile"].into_iter().map(str::to_string)
+        .chain(phases.iter().map(|name| name.to_string()))
+        .chain(["total".to_string()])
+        .collect();
+    let rows = records.iter()
+        .map(|record| {
+            let params : Vec<String> = record.params.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            let text = [format_date(record.date), record.command.clone(), record.preset.clone(), params.join(" "), record.profile_id.clone().unwrap_or_default()];
+            text.into_iter().map(Cell::Text)
+                .chain(phases.iter().map(|name| record.phases.iter().find(|(n, _)| n == name).map_or(Cell::Empty, |&(_, time)| Cell::Time(time))))
+                .chain([Cell::Time(record.total)])
+                .collect()
+        })
+        .collect();
+    Table { header, rows, totals: Totals::Column(5 + phases.len()) }
+}
@@ -0,0 +1,204 @@
+//! Instruction counts and cache misses per item, with the `instruction-counts` feature: the
+//! numbers iai-callgrind reports, measured by Callgrind (valgrind), deterministic and comparable
+//! across machines where times aren't.
+//!
+//! `run_under_callgrind` runs the simulation again in a child process under `valgrind
+//! --tool=callgrind`, collecting only inside `measured`, which the child calls once per item
+//! (`GroupOpsSimulation::count_instructions`), and dumping the counts after every call. The
+//! dumps are read back in order. Cache misses come from Callgrind's cache simulation, and the
+//! estimated cycles weigh them as iai-callgrind does: 1 per L1 hit, 5 per last-level hit and 35
+//! per access to memory.
+
+use crate::report::OpCount;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+
+/// Set in the environment of the child to the directory of the dumps.
+pub const CHILD_ENV : &str = "PVSS_SIM_CALLGRIND_DIR";
+
+// the function to collect in, as Callgrind names it
+const MEASURED : &str = "*instructions::measured*";
+
+/// Runs `f`, the only code Callgrind counts in a child.
+#[inline(never)]
+pub fn measured(f: &dyn Fn()) {
+    f()
+}
+
+/// The dump directory when running as the child of `run_under_callgrind`.
+pub fn child_dir() -> Option<PathBuf> {
+    std::env::var_os(CHILD_ENV).map(PathBuf::from)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InstructionCounts {
+    pub instructions: u64,
+    pub data_reads: u64,
+    pub data_writes: u64,
+    /// instruction and data misses of the first-level caches
+    pub l1_misses: u64,
+    /// instruction and data misses of the last-level cache
+    pub ll_misses: u64,
+}
+
+impl InstructionCounts {
+    pub fn estimated_cycles(&self) -> u64 {
+        let accesses = self.instructions + self.data_reads + self.data_writes;
+        let l1_hits = accesses.saturating_sub(self.l1_misses);
+        let ll_hits = self.l1_misses.saturating_sub(self.ll_misses);
+        l1_hits + 5 * ll_hits + 35 * self.ll_misses
+    }
+
+    /// The totals of a Callgrind output file (its `events:` and `summary:` or `totals:` lines).
+    /// Events the file doesn't have count as 0.
+    pub fn parse(text: &str) -> Result<Self, InstructionError> {
+        let line = |key: &str| text.lines().find_map(|line| line.strip_prefix(key));
+        let events : Vec<&str> = line("events:")
+            .ok_or_else(|| InstructionError::Invalid("no `events:` line".to_string()))?
+            .split_whitespace().collect();
+        let totals = line("summary:").or_else(|| line("totals:"))
+            .ok_or_else(|| InstructionError::Invalid("no `summary:` or `totals:` line".to_string()))?
+            .split_whitespace()
+            .map(|total| total.parse::<u64>().map_err(|_| InstructionError::Invalid(format!("`{}` is not a count", total))))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sum = |names: &[&str]| -> u64 {
+            events.iter().zip(&totals).filter(|(event, _)| names.contains(event)).map(|(_, total)| total).sum()
+        };
+        Ok(Self {
+            instructions: sum(&["Ir"]),
+            data_reads: sum(&["Dr"]),
+            data_writes: sum(&["Dw"]),
+            l1_misses: sum(&["I1mr", "D1mr", "D1mw"]),
+            ll_misses: sum(&["ILmr", "DLmr", "DLmw"]),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum InstructionError {
+    Io(std::io::Error),
+    /// valgrind isn't installed
+    NoValgrind,
+    /// the child failed, or left out an item's dump
+    Failed(String),
+    Invalid(String),
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstructionError::Io(e) => write!(f, "{}", e),
+            InstructionError::NoValgrind => write!(f, "valgrind not found (counting instructions needs valgrind's Callgrind)"),
+            InstructionError::Failed(message) => write!(f, "the run under Callgrind failed: {}", message),
+            InstructionError::Invalid(message) => write!(f, "invalid Callgrind output: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for InstructionError {}
+
+impl From<std::io::Error> for InstructionError {
+    fn from(e: std::io::Error) -> Self {
+        InstructionError::Io(e)
+    }
+}
+
+/// Records the items the child measured, in order, for the parent to name the dumps by.
+pub fn write_items(dir: &Path, items: &[(String, OpCount)]) -> std::io::Result<()> {
+    let lines : String = items.iter().map(|(name, op_count)| format!("{}\t{}\t{}\n", op_count.num, op_count.size, name)).collect();
+    std::fs::write(dir.join("items"), lines)
+}
+
+fn read_items(dir: &Path) -> Result<Vec<(String, OpCount)>, InstructionError> {
+    std::fs::read_to_string(dir.join("items"))?.lines()
+        .map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let mut count = || fields.next().and_then(|field| field.parse().ok());
+            let (num, size) = (count(), count());
+            match (num, size, fields.next()) {
+                (Some(num), Some(size), Some(name)) => Ok((name.to_string(), OpCount { num, size })),
+                _ => Err(InstructionError::Invalid(format!("bad item line `{}`", line))),
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct InstructionReport {
+    pub items: Vec<(String, OpCount, InstructionCounts)>,
+}
+
+impl InstructionReport {
+    pub fn total(&self) -> InstructionCounts {
+        self.items.iter().fold(InstructionCounts::default(), |total, (_, _, counts)| InstructionCounts {
+            instructions: total.instructions + counts.instructions,
+            data_reads: total.data_reads + counts.data_reads,
+            data_writes: total.data_writes + counts.data_writes,
+            l1_misses: total.l1_misses + counts.l1_misses,
+            ll_misses: total.ll_misses + counts.ll_misses,
+        })
+    }
+}
+
+impl fmt::Display for InstructionCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} instructions, {} L1 misses, {} LL misses, ~{} cycles", self.instructions, self.l1_misses, self.ll_misses, self.estimated_cycles())
+    }
+}
+
+impl fmt::Display for InstructionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "instruction counts (Callgrind):")?;
+        for (name, op_count, counts) in &self.items {
+            match op_count.num {
+                0 => writeln!(f, "  {}: {}", name, counts)?,
+                num => writeln!(f, "  {}: {} ({} instructions/op)", name, counts, counts.instructions / num as u64)?,
+            }
+        }
+        writeln!(f, "  total: {}", self.total())
+    }
+}
+
+/// Runs this executable again with `args` under Callgrind, as the child that measures every
+/// item once, and reads back the counts of every item. The child's output is discarded.
+pub fn run_under_callgrind(args: &[String]) -> Result<InstructionReport, InstructionError> {
+    let dir = std::env::temp_dir().join(format!("pvss-sim-callgrind-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let result = run_child(&dir, args);
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn run_child(dir: &Path, args: &[String]) -> Result<InstructionReport, InstructionError> {
+    let out = dir.join("callgrind.out");
+    let status = Command::new("valgrind")
+        .args(["--tool=callgrind", "-q", "--cache-sim=yes", "--collect-atstart=no"])
+        .arg(format!("--toggle-collect={}", MEASURED))
+        .arg(format!("--dump-after={}", MEASURED))
+        .arg(format!("--callgrind-out-file={}", out.display()))
+        .arg(std::env::current_exe()?)
+        .args(args)
+        .env(CHILD_ENV, dir)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => InstructionError::NoValgrind,
+            _ => InstructionError::Io(e),
+        })?;
+    if !status.success() {
+        return Err(InstructionError::Failed(status.to_string()));
+    }
+    let items = read_items(dir)?;
+    // Callgrind numbers the dumps from 1
+    let items = items.into_iter().enumerate()
+        .map(|(i, (name, op_count))| {
+            let dump = std::fs::read_to_string(format!("{}.{}", out.display(), i + 1))
+                .map_err(|e| InstructionError::Failed(format!("no dump for `{}`: {}", name, e)))?;
+            Ok((name, op_count, InstructionCounts::parse(&dump)?))
+        })
+        .collect::<Result<_, InstructionError>>()?;
+    Ok(InstructionReport { items })
+}
@@ -0,0 +1,34 @@
+//! Graceful interruption of long runs: once `install`ed, Ctrl-C (or SIGTERM) only sets a flag,
+//! which simulations check between items and repetitions, and sweeps between parameter points,
+//! so that the item running when the signal arrives is the last, and the report of everything
+//! that completed is still printed and saved, marked partial. A second signal exits at once.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+
+static REQUESTED : AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn handle(_signal: libc::c_int) {
+    if REQUESTED.swap(true, Ordering::SeqCst) {
+        // SAFETY: `_exit` is async-signal-safe, unlike the exit handlers of `process::exit`
+        unsafe { libc::_exit(130) };
+    }
+    let message = b"\ninterrupted: finishing the running item (interrupt again to quit at once)\n";
+    // SAFETY: `write` is async-signal-safe; the buffer outlives the call
+    unsafe { libc::write(libc::STDERR_FILENO, message.as_ptr().cast(), message.len()) };
+}
+
+/// Traps SIGINT and SIGTERM; without it (or off Unix), they terminate the process as usual.
+pub fn install() {
+    #[cfg(unix)]
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the handler only touches an atomic and calls async-signal-safe functions
+        unsafe { libc::signal(signal, handle as extern "C" fn(libc::c_int) as libc::sighandler_t) };
+    }
+}
+
+/// Whether an interruption was requested, i.e. a run should stop at the next item or point.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,327 @@
+// A JSON reader and writer for machine profiles, op counts and the run history: objects keep their keys in file order, and
+// numbers are read as f64. `\u` escapes outside the basic multilingual plane are read as UTF-16 surrogate pairs.
+
+use std::fmt::{self, Write as _};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Two-space indented, one array element or object member per line.
+    pub(crate) fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    /// On a single line, e.g. for one record per line of a log.
+    pub(crate) fn to_compact_string(&self) -> String {
+        let mut out = String::new();
+        self.write_compact(&mut out);
+        out
+    }
+
+    fn write_compact(&self, out: &mut String) {
+        match self {
+            Value::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    value.write_compact(out);
+                }
+                out.push(']');
+            }
+            Value::Object(pairs) => {
+                out.push('{');
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_compact(out);
+                }
+                out.push('}');
+            }
+            scalar => scalar.write(out, 0),
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        let pad = |out: &mut String, indent: usize| out.extend(std::iter::repeat_n(' ', 2 * indent));
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Boolean(b) => write!(out, "{}", b).unwrap(),
+            // integral values print without a fraction so counts stay readable
+            Value::Number(x) if x.fract() == 0.0 && x.abs() < 1e15 => write!(out, "{}", *x as i64).unwrap(),
+            Value::Number(x) => write!(out, "{}", x).unwrap(),
+            Value::String(s) => write_string(out, s),
+            Value::Array(values) if values.is_empty() => out.push_str("[]"),
+            // arrays of numbers (e.g. [size, ns] pairs) stay on one line
+            Value::Array(values) if values.iter().all(|v| matches!(v, Value::Number(_))) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    value.write(out, indent);
+                }
+                out.push(']');
+            }
+            Value::Array(values) => {
+                out.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    pad(out, indent + 1);
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push(']');
+            }
+            Value::Object(pairs) if pairs.is_empty() => out.push_str("{}"),
+            Value::Object(pairs) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    pad(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < pairs.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ParseError {
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0, line: 1 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(value),
+        Some(c) => parser.error(format!("unexpected `{}` after the value", c)),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError { line: self.line, message: message.into() })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", c))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c @ (' ' | '\t' | '\r' | '\n')) = self.peek() {
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => self.string().map(Value::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                if self.eat(']') {
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    if !self.eat(',') {
+                        self.expect(']')?;
+                        return Ok(Value::Array(values));
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut pairs : Vec<(String, Value)> = Vec::new();
+                if self.eat('}') {
+                    return Ok(Value::Object(pairs));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some('"') {
+                        return self.error("expected a key");
+                    }
+                    let key = self.string()?;
+                    if pairs.iter().any(|(k, _)| *k == key) {
+                        return self.error(format!("`{}` is defined twice", key));
+                    }
+                    self.expect(':')?;
+                    pairs.push((key, self.value()?));
+                    if !self.eat(',') {
+                        self.expect('}')?;
+                        return Ok(Value::Object(pairs));
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || "+-.".contains(c)) {
+                    self.pos += 1;
+                }
+                let word : String = self.chars[start..self.pos].iter().collect();
+                match word.as_str() {
+                    "" => self.error("expected a value"),
+                    "null" => Ok(Value::Null),
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    _ => match word.parse() {
+                        Ok(x) => Ok(Value::Number(x)),
+                        Err(_) => self.error(format!("invalid value `{}`", word)),
+                    },
+                }
+            }
+        }
+    }
+
+    // the four hex digits starting at `pos`
+    fn hex_escape(&self, pos: usize) -> Option<u32> {
+        let hex : String = self.chars.get(pos..pos + 4)?.iter().collect();
+        u32::from_str_radix(&hex, 16).ok().filter(|_| hex.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return self.error("unterminated string"),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let c = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        Some('u') => {
+                            let code = match self.hex_escape(self.pos + 1) {
+                                // a high surrogate, to be followed by `\u` and the low one
+                                Some(high @ 0xd800..=0xdbff) => match self.hex_escape(self.pos + 7) {
+                                    Some(low @ 0xdc00..=0xdfff) if self.chars[self.pos + 5..self.pos + 7] == ['\\', 'u'] => {
+                                        self.pos += 6;
+                                        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                                    }
+                                    _ => return self.error("unpaired surrogate in \\u escape"),
+                                },
+                                Some(code) => code,
+                                None => return self.error("invalid \\u escape"),
+                            };
+                            match char::from_u32(code) {
+                                Some(c) => {
+                                    self.pos += 4;
+                                    c
+                                }
+                                None => return self.error("unpaired surrogate in \\u escape"),
+                            }
+                        }
+                        _ => return self.error("unsupported escape sequence"),
+                    };
+                    s.push(c);
+                    self.pos += 1;
+                }
+                Some(c) => {
+                    s.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
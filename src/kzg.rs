@@ -0,0 +1,378 @@
+//! KZG commitments `C = g1^{p(τ)}` to polynomials of degree `d`, for the parts of the scheme
+//! beyond committing: an opening proof `π = g1^{q(τ)}` at one point `z`, with
+//! `q = (p - p(z)) / (X - z)`; a multi-point opening at `m` points with a single `π`, `q` being
+//! `p` divided by the vanishing polynomial `Z = ∏ (X - z_i)` of the points; and the verification
+//! of `m` openings of different polynomials at different points with one multi-pairing of
+//! size 2, after a random linear combination:
+//! `e(Σ r_i (C_i - y_i g1 + z_i π_i), g2) = e(Σ r_i π_i, g2^τ)`.
+//!
+//! The setup draws `τ` at random, which is fine for timing and for checking the proofs, but not
+//! as a trusted setup. Its cost is measured too, the one-time cost of a powers-of-tau setup: the
+//! generation of the `d + 1` powers `g1^{τ^i}` with fixed-base exps, and the check that they
+//! are consecutive powers of the same `τ` as `g2^τ`, `e(g1^{τ^{i+1}}, g2) = e(g1^{τ^i}, g2^τ)`
+//! for every `i`, one multi-pairing each or all at once after a random linear combination.
+
+use crate::fixed_base::FixedBaseTable;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::pairing_utils::multi_pairing;
+use crate::polynomial::horner;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, G2Projective, Gt, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+// window of the fixed-base tables the setup computes the powers of τ with
+const SETUP_WINDOW_BITS : usize = 8;
+
+/// `g1^{τ^i}` up to the degree of the polynomials, and `g2^{τ^i}` up to the number of points
+/// of multi-point openings.
+pub struct Srs {
+    pub g1: Vec<G1Projective>,
+    pub g2: Vec<G2Projective>,
+}
+
+impl Srs {
+    pub fn new(tau: &Scalar, degree: usize, max_points: usize) -> Self {
+        let powers = |num: usize| std::iter::successors(Some(Scalar::ONE), |power| Some(power * tau)).take(num).collect::<Vec<_>>();
+        let g1 = FixedBaseTable::new(G1Projective::generator(), SETUP_WINDOW_BITS);
+        let g2 = FixedBaseTable::new(G2Projective::generator(), SETUP_WINDOW_BITS);
+        Self {
+            g1: powers(degree + 1).iter().map(|power| g1.mul(power)).collect(),
+            g2: powers(max_points.max(1) + 1).iter().map(|power| g2.mul(power)).collect(),
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.g1.len() - 1
+    }
+
+    pub fn commit(&self, coeffs: &[Scalar]) -> G1Projective {
+        assert!(coeffs.len() <= self.g1.len(), "the polynomial's degree exceeds the setup's");
+        G1Projective::multi_exp(&self.g1[..coeffs.len()], coeffs)
+    }
+
+    fn commit_g2(&self, coeffs: &[Scalar]) -> G2Projective {
+        assert!(coeffs.len() <= self.g2.len(), "more points than the setup supports");
+        G2Projective::multi_exp(&self.g2[..coeffs.len()], coeffs)
+    }
+}
+
+/// Whether the powers of `srs` are consecutive powers of one `τ`, the one of `g2^τ`, starting
+/// from the generator: one multi-pairing of size 2 per power.
+pub fn verify_srs(srs: &Srs) -> bool {
+    let g2 = [G2Projective::generator(), srs.g2[1]];
+    srs.g1[0] == G1Projective::generator()
+        && srs.g1.windows(2).all(|pair| multi_pairing(&[pair[1], -pair[0]], &g2) == Gt::identity())
+}
+
+/// `verify_srs` with the random `weights` (one per power but the first): two multi-exps of size
+/// `d` and one multi-pairing of size 2.
+pub fn batch_verify_srs(srs: &Srs, weights: &[Scalar]) -> bool {
+    assert_eq!(weights.len(), srs.degree(), "need one weight per power but the first");
+    let lhs = G1Projective::multi_exp(&srs.g1[1..], weights);
+    let rhs = G1Projective::multi_exp(&srs.g1[..srs.degree()], weights);
+    srs.g1[0] == G1Projective::generator()
+        && multi_pairing(&[lhs, -rhs], &[G2Projective::generator(), srs.g2[1]]) == Gt::identity()
+}
+
+/// `p / divisor` and the remainder, by long division; `divisor` must be monic.
+fn divide(coeffs: &[Scalar], divisor: &[Scalar]) -> (Vec<Scalar>, Vec<Scalar>) {
+    let m = divisor.len() - 1;
+    if coeffs.len() <= m {
+        return (Vec::new(), coeffs.to_vec());
+    }
+    let mut remainder = coeffs.to_vec();
+    let mut quotient = vec![Scalar::ZERO; coeffs.len() - m];
+    for i in (0..quotient.len()).rev() {
+        let q = remainder[i + m];
+        quotient[i] = q;
+        for (j, d) in divisor.iter().enumerate() {
+            remainder[i + j] -= q * d;
+        }
+    }
+    remainder.truncate(m);
+    (quotient, remainder)
+}
+
+/// `∏ (X - z_i)`, constant term first.
+fn vanishing_polynomial(points: &[Scalar]) -> Vec<Scalar> {
+    points.iter().fold(vec![Scalar::ONE], |acc, z| {
+        let mut next = vec![Scalar::ZERO; acc.len() + 1];
+        for (i, c) in acc.iter().enumerate() {
+            next[i + 1] += c;
+            next[i] -= c * z;
+        }
+        next
+    })
+}
+
+/// The opening at `z`: `p(z)` and the proof.
+pub fn open(srs: &Srs, coeffs: &[Scalar], z: &Scalar) -> (Scalar, G1Projective) {
+    let (quotient, remainder) = divide(coeffs, &[-z, Scalar::ONE]);
+    (remainder.first().copied().unwrap_or(Scalar::ZERO), srs.commit(&quotient))
+}
+
+/// The opening at all of `points`: the values there and one proof, the commitment to the
+/// quotient by their vanishing polynomial.
+pub fn open_multi(srs: &Srs, coeffs: &[Scalar], points: &[Scalar]) -> (Vec<Scalar>, G1Projective) {
+    let values = points.iter().map(|z| horner(coeffs, z)).collect();
+    let (quotient, _) = divide(coeffs, &vanishing_polynomial(points));
+    (values, srs.commit(&quotient))
+}
+
+/// Checks a multi-point opening: `e(C - g1^{I(τ)}, g2) = e(π, g2^{Z(τ)})`, `I` interpolating the
+/// values at the points.
+pub fn verify_multi(srs: &Srs, commitment: &G1Projective, points: &[Scalar], values: &[Scalar], proof: &G1Projective) -> bool {
+    let vanishing = vanishing_polynomial(points);
+    // I = Σ y_i Z / ((X - z_i) Z'(z_i)), with Z / (X - z_i) evaluated at z_i for Z'(z_i)
+    let mut interpolation = vec![Scalar::ZERO; points.len()];
+    for (z, y) in points.iter().zip(values) {
+        let (basis, _) = divide(&vanishing, &[-z, Scalar::ONE]);
+        let scale = y * horner(&basis, z).invert().unwrap();
+        for (c, b) in interpolation.iter_mut().zip(&basis) {
+            *c += scale * b;
+        }
+    }
+    let lhs = commitment - srs.commit(&interpolation);
+    multi_pairing(&[lhs, -proof], &[G2Projective::generator(), srs.commit_g2(&vanishing)]) == Gt::identity()
+}
+
+/// A claim `p(z) = y` about a committed polynomial.
+#[derive(Clone, Copy, Debug)]
+pub struct Opening {
+    pub commitment: G1Projective,
+    pub point: Scalar,
+    pub value: Scalar,
+    pub proof: G1Projective,
+}
+
+/// Checks all `openings` at once with the random `weights` (one per opening): one multi-exp of
+/// size `2m + 1`, one of size `m` and a multi-pairing of size 2.
+pub fn batch_verify(srs: &Srs, openings: &[Opening], weights: &[Scalar]) -> bool {
+    assert_eq!(openings.len(), weights.len(), "need one weight per opening");
+    let mut bases : Vec<G1Projective> = Vec::with_capacity(2 * openings.len() + 1);
+    let mut scalars : Vec<Scalar> = Vec::with_capacity(2 * openings.len() + 1);
+    let mut value_sum = Scalar::ZERO;
+    for (opening, r) in openings.iter().zip(weights) {
+        bases.extend([opening.commitment, opening.proof]);
+        scalars.extend([*r, r * opening.point]);
+        value_sum += r * opening.value;
+    }
+    bases.push(G1Projective::generator());
+    scalars.push(-value_sum);
+    let lhs = G1Projective::multi_exp(&bases, &scalars);
+    let proofs : Vec<G1Projective> = openings.iter().map(|opening| opening.proof).collect();
+    let rhs = G1Projective::multi_exp(&proofs, weights);
+    multi_pairing(&[lhs, -rhs], &[G2Projective::generator(), srs.g2[1]]) == Gt::identity()
+}
+
+struct SrsGenerations {
+    taus: Vec<Scalar>,
+    degree: usize,
+}
+
+impl GroupOpsSimulationItem for SrsGenerations {
+    fn description(&self) -> String {
+        format!("SRS generations: {} of degree {}", self.taus.len(), self.degree)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.taus.len(), self.degree)
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Srs>>(self.taus.iter().map(|tau| Srs::new(tau, self.degree, 1)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.taus.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(Srs::new(&self.taus[i], self.degree, 1));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.taus.iter().all(|tau| verify_srs(&Srs::new(tau, self.degree, 1))))
+    }
+}
+
+struct SrsVerifications {
+    setups: Vec<(Srs, Vec<Scalar>)>,
+    batched: bool,
+}
+
+impl SrsVerifications {
+    fn run(&self, i: usize) -> bool {
+        let (srs, weights) = &self.setups[i];
+        if self.batched { batch_verify_srs(srs, weights) } else { verify_srs(srs) }
+    }
+}
+
+impl GroupOpsSimulationItem for SrsVerifications {
+    fn description(&self) -> String {
+        let batched = if self.batched { " batched" } else { "" };
+        format!("SRS verifications{}: {} of degree {}", batched, self.setups.len(), self.setups[0].0.degree())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.setups.len(), self.setups[0].0.degree())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.setups.len()).map(|i| self.run(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.setups.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.run(i));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some((0..self.setups.len()).all(|i| self.run(i)))
+    }
+}
+
+struct Openings {
+    srs: Srs,
+    polys: Vec<Vec<Scalar>>,
+    // one per polynomial, or `m` per polynomial for multi-point openings
+    points: Vec<Vec<Scalar>>,
+}
+
+impl Openings {
+    fn run(&self, i: usize) -> G1Projective {
+        match self.points[i].as_slice() {
+            [z] => open(&self.srs, &self.polys[i], z).1,
+            points => open_multi(&self.srs, &self.polys[i], points).1,
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for Openings {
+    fn description(&self) -> String {
+        match self.points[0].len() {
+            1 => format!("KZG openings: {} of degree {}", self.polys.len(), self.srs.degree()),
+            m => format!("KZG multi-point openings: {} of degree {} at {} points", self.polys.len(), self.srs.degree(), m),
+        }
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.polys.len(), self.srs.degree())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<G1Projective>>((0..self.polys.len()).map(|i| self.run(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.polys.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.run(i));
+    }
+}
+
+struct BatchVerifications {
+    srs: Srs,
+    batches: Vec<(Vec<Opening>, Vec<Scalar>)>,
+}
+
+impl GroupOpsSimulationItem for BatchVerifications {
+    fn description(&self) -> String {
+        format!("KZG batch verifications: {} of {} openings", self.batches.len(), self.batches[0].0.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.batches.len(), self.batches[0].0.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>(self.batches.iter().map(|(openings, weights)| batch_verify(&self.srs, openings, weights)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.batches.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        let (openings, weights) = &self.batches[i];
+        black_box(batch_verify(&self.srs, openings, weights));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn kzg_polys(&mut self, num: usize, degree: usize) -> Vec<Vec<Scalar>> {
+        (0..num).map(|_| (0..=degree).map(|_| Scalar::random(&mut *self.rng())).collect()).collect()
+    }
+
+    /// `num` powers-of-tau setups of degree `degree`: the powers of `τ`, a fixed-base table of
+    /// the generator and `degree + 1` exps with it, and `g2^τ`.
+    pub fn srs_generations(&mut self, num: usize, degree: usize) -> &mut Self {
+        let taus = (0..num).map(|_| Scalar::random(&mut *self.rng())).collect();
+        self.push_item(SrsGenerations { taus, degree })
+    }
+
+    /// `num` checks of setups of degree `degree` (see `verify_srs`), `batched` or not.
+    pub fn srs_verifications(&mut self, num: usize, degree: usize, batched: bool) -> &mut Self {
+        assert!(degree >= 1, "a setup has at least two powers");
+        let setups = (0..num)
+            .map(|_| {
+                let srs = Srs::new(&Scalar::random(&mut *self.rng()), degree, 1);
+                let weights = (0..degree).map(|_| Scalar::random(&mut *self.rng())).collect();
+                (srs, weights)
+            })
+            .collect();
+        self.push_item(SrsVerifications { setups, batched })
+    }
+
+    fn kzg_openings_item(&mut self, num: usize, degree: usize, m: usize) -> &mut Self {
+        let srs = Srs::new(&Scalar::random(&mut *self.rng()), degree, m);
+        let polys = self.kzg_polys(num, degree);
+        let points = (0..num).map(|_| (0..m).map(|_| Scalar::random(&mut *self.rng())).collect()).collect();
+        self.push_item(Openings { srs, polys, points })
+    }
+
+    /// `num` opening proofs of polynomials of degree `degree` at one point each: an evaluation,
+    /// a division by `X - z` and a multi-exp of size `degree`.
+    pub fn kzg_openings(&mut self, num: usize, degree: usize) -> &mut Self {
+        self.kzg_openings_item(num, degree, 1)
+    }
+
+    /// `num` openings of polynomials of degree `degree` at `m` points each, with one proof.
+    pub fn kzg_multi_openings(&mut self, num: usize, degree: usize, m: usize) -> &mut Self {
+        assert!(m >= 1 && m <= degree, "need between 1 and the degree many points");
+        self.kzg_openings_item(num, degree, m)
+    }
+
+    /// `num` verifications of `m` openings each, of different polynomials of degree `degree` at
+    /// different points, batched into one multi-pairing. Only the setup depends on the degree.
+    pub fn kzg_batch_verifications(&mut self, num: usize, degree: usize, m: usize) -> &mut Self {
+        let srs = Srs::new(&Scalar::random(&mut *self.rng()), degree, 1);
+        let batches = (0..num)
+            .map(|_| {
+                let openings = self.kzg_polys(m, degree).iter()
+                    .map(|coeffs| {
+                        let point = Scalar::random(&mut *self.rng());
+                        let (value, proof) = open(&srs, coeffs, &point);
+                        Opening { commitment: srs.commit(coeffs), point, value, proof }
+                    })
+                    .collect();
+                let weights = (0..m).map(|_| Scalar::random(&mut *self.rng())).collect();
+                (openings, weights)
+            })
+            .collect();
+        self.push_item(BatchVerifications { srs, batches })
+    }
+}
@@ -0,0 +1,116 @@
+//! Measured times as LaTeX macros, `\newcommand{\GOneExpTime}{142~$\mu$s}`, so that the numbers
+//! in a paper's prose follow the simulation whenever it is re-run: the per-op costs of a profile,
+//! and the phase times and totals of a run.
+//!
+//! LaTeX control words are letters only, so a macro is named after its timing in camel case with
+//! every digit spelled out: phase `Verifier` of sweep point `n=64 t=42` becomes
+//! `\NSixFourTFourTwoVerifierTime`, and a G1 multi-exp of size 1024
+//! `\GOneMultiExpOneZeroTwoFourTime`. Names that come out the same are numbered, as timings of
+//! baselines are: `\DealerTime`, `\DealerTimeTwo`, ...
+
+use crate::baseline::Baseline;
+use crate::cost_model::{Calibration, Cost, CostProfile};
+use crate::render::{Format, Style};
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+
+const DIGITS : [&str; 10] = ["Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine"];
+
+/// `\newcommand` definitions, in the order added.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Macros {
+    /// names without the backslash, and values
+    pub definitions: Vec<(String, String)>,
+}
+
+impl Macros {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a macro named after `words` (see `macro_name`), numbering it if the name is taken.
+    pub fn add(&mut self, words: &str, value: String) {
+        let name = macro_name(words);
+        let mut unique = name.clone();
+        let mut i = 1;
+        while self.definitions.iter().any(|(n, _)| *n == unique) {
+            i += 1;
+            unique = format!("{}{}", name, macro_name(&i.to_string()));
+        }
+        self.definitions.push((unique, value));
+    }
+
+    /// The definitions, after `comment` (one `%` line per line of it).
+    pub fn to_tex(&self, comment: &str) -> String {
+        let mut out = String::new();
+        for line in comment.lines() {
+            writeln!(out, "% {}", line).unwrap();
+        }
+        for (name, value) in &self.definitions {
+            writeln!(out, "\\newcommand{{\\{}}}{{{}}}", name, value).unwrap();
+        }
+        out
+    }
+}
+
+/// `words` in camel case, with digits spelled out and everything but ASCII letters and digits
+/// dropped: `G1 exps` becomes `GOneExps`.
+pub fn macro_name(words: &str) -> String {
+    let mut name = String::new();
+    let mut word_start = true;
+    for c in words.chars() {
+        if c.is_ascii_digit() {
+            name.push_str(DIGITS[c as usize - '0' as usize]);
+            word_start = true;
+        } else if c.is_ascii_alphabetic() {
+            name.push(if word_start { c.to_ascii_uppercase() } else { c });
+            word_start = false;
+        } else {
+            word_start = true;
+        }
+    }
+    name
+}
+
+/// `duration` as `style` writes it in LaTeX tables, with a non-breaking space before the unit
+/// (which a fixed `--unit` leaves to the prose).
+pub fn time(duration: Duration, style: &Style) -> String {
+    Style { format: Format::Latex, ..*style }.duration(duration).replace(' ', "~")
+}
+
+/// Per-op costs of a profile: `\GOneExpTime` for a kind of op of a fixed cost, one macro per
+/// measured size for sized kinds (`\GOneMultiExpSixFourTime`), and for a multi-exp cost model the
+/// cost at the sizes that calibration measures.
+pub fn cost_macros(profile: &CostProfile, style: &Style) -> Macros {
+    let calibration = Calibration::default();
+    let mut macros = Macros::new();
+    for (kind, cost) in &profile.costs {
+        // one op of the kind: `g1_exps` is `\GOneExp...`
+        let op = kind.name().strip_suffix('s').unwrap_or(kind.name());
+        let nanos = |ns: f64| time(Duration::from_secs_f64(ns.max(0.0) / 1e9), style);
+        let sizes = match cost {
+            Cost::Fixed(ns) => {
+                macros.add(&format!("{} time", op), nanos(*ns));
+                continue;
+            }
+            Cost::BySize(points) => points.iter().map(|&(size, _)| size).collect(),
+            Cost::Msm { .. } => calibration.kinds.iter().find(|(k, _)| k == kind).map_or(Vec::new(), |(_, sizes)| sizes.clone()),
+        };
+        for size in sizes {
+            macros.add(&format!("{} {} time", op, size), nanos(cost.at(*kind, size)));
+        }
+    }
+    macros
+}
+
+/// Every phase time and total that `results` recorded, `\NSixFourDealerTime` for phase `Dealer`
+/// of point `n=64`.
+pub fn phase_macros(results: &Baseline, style: &Style) -> Macros {
+    let mut macros = Macros::new();
+    for (name, duration) in &results.phases {
+        macros.add(&format!("{} time", name), time(*duration, style));
+    }
+    macros
+}
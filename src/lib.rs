@@ -0,0 +1,75 @@
+pub mod accumulation;
+pub mod alloc;
+pub mod async_round;
+pub mod backend;
+pub mod baseline;
+pub mod budget;
+pub mod chunked_elgamal;
+mod checks;
+pub mod clock;
+pub mod communication;
+pub mod compare;
+pub mod config;
+pub mod cost_model;
+pub mod curve_ops;
+pub mod custom;
+pub mod dleq;
+pub mod elgamal;
+pub mod energy;
+pub mod error;
+pub mod exp_methods;
+pub mod export;
+pub mod feldman;
+pub mod fit;
+mod field_ops;
+pub mod fixed_base;
+pub mod glv;
+pub mod history;
+pub mod group_ops_simulation;
+pub mod gt_ops;
+mod hash;
+mod hash_to_curve;
+#[cfg(feature = "instruction-counts")]
+pub mod instructions;
+pub mod interrupt;
+mod json;
+pub mod kzg;
+pub mod latex;
+pub mod machine;
+pub mod merkle;
+pub mod merge;
+pub mod network;
+pub mod op_counts;
+pub mod pairing_products;
+pub mod pairing_utils;
+pub mod parallel;
+pub mod party;
+pub mod pedersen;
+pub mod pipeline;
+pub mod pippenger;
+pub mod plot;
+pub mod polynomial;
+pub mod presets;
+pub mod progress;
+pub mod protocol;
+pub mod reed_solomon;
+pub mod render;
+pub mod pvss;
+pub mod report;
+pub mod scaling;
+pub mod schedule;
+pub mod schnorr;
+pub mod serialization;
+pub mod short_exps;
+pub mod sizes;
+pub mod sigma;
+pub mod sparse_multi_exps;
+pub mod stats;
+pub mod streaming;
+pub mod sweep;
+pub mod test_vectors;
+mod toml;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub mod wasm;
+pub mod weighted;
+pub mod workload;
@@ -0,0 +1,248 @@
+//! The machine simulations run on, and profiles that store its calibrated per-op costs with a
+//! description of its hardware, as JSON:
+//!
+//! ```json
+//! {
+//!   "id": "3f2a9c0e51d7b846",
+//!   "created": 1760400000,
+//!   "machine": { "cpu_model": "Intel(R) Xeon(R) Processor", "cores": 8, "governor": "performance", "blstrs": "0.7.1", "blst": "0.3.13" },
+//!   "costs": { "g1_exps": 120000.0, "g1_multi_exps": [[2, 150000.0], [4, 190000.0]], "g2_multi_exps": { "a": 90000.0, "b": 40000.0 } }
+//! }
+//! ```
+//!
+//! The ID is derived from the hardware description and the library versions, so every report
+//! can be stamped with the ID of the machine it was measured on (or, for predictions, the
+//! profile it was predicted from), and results from different machines are never mixed up.
+
+use crate::clock;
+use crate::cost_model::{self, Cost, CostProfile, ProfileError};
+use crate::hash::sha256;
+use crate::json::{self, Value};
+use crate::parallel::available_threads;
+use crate::toml;
+
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Machine {
+    /// from /proc/cpuinfo, where there is one
+    pub cpu_model: Option<String>,
+    pub cores: usize,
+    /// the CPU frequency governor of Linux' cpufreq, e.g. `performance` or `powersave`
+    pub governor: Option<String>,
+    pub blstrs_version: String,
+    pub blst_version: String,
+}
+
+impl Machine {
+    pub fn detect() -> Self {
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let cpu_model = cpuinfo.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim() == "model name")
+            .map(|(_, value)| value.trim().to_string());
+        let governor = std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor").ok()
+            .map(|governor| governor.trim().to_string());
+        Self {
+            cpu_model,
+            cores: available_threads(),
+            governor,
+            blstrs_version: env!("BLSTRS_VERSION").to_string(),
+            blst_version: env!("BLST_VERSION").to_string(),
+        }
+    }
+
+    /// This machine, detected once per process.
+    pub fn current() -> &'static Machine {
+        static CURRENT : OnceLock<Machine> = OnceLock::new();
+        CURRENT.get_or_init(Machine::detect)
+    }
+
+    /// 16 hex digits of a hash of all fields.
+    pub fn id(&self) -> String {
+        let description = format!("{:?}\n{}\n{:?}\n{}\n{}", self.cpu_model, self.cores, self.governor, self.blstrs_version, self.blst_version);
+        sha256(description.as_bytes())[..8].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn to_json(&self) -> Value {
+        let optional = |s: &Option<String>| s.clone().map_or(Value::Null, Value::String);
+        Value::Object(vec![
+            ("cpu_model".to_string(), optional(&self.cpu_model)),
+            ("cores".to_string(), Value::Number(self.cores as f64)),
+            ("governor".to_string(), optional(&self.governor)),
+            ("blstrs".to_string(), Value::String(self.blstrs_version.clone())),
+            ("blst".to_string(), Value::String(self.blst_version.clone())),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Result<Self, ProfileError> {
+        let invalid = |key: &str| ProfileError::Invalid(format!("`machine.{}` is missing or has the wrong type", key));
+        let optional = |key: &str| match value.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(invalid(key)),
+        };
+        let string = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string).ok_or_else(|| invalid(key));
+        Ok(Self {
+            cpu_model: optional("cpu_model")?,
+            cores: value.get("cores").and_then(Value::as_f64).filter(|&n| n >= 1.0).ok_or_else(|| invalid("cores"))? as usize,
+            governor: optional("governor")?,
+            blstrs_version: string("blstrs")?,
+            blst_version: string("blst")?,
+        })
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {} cores", self.cpu_model.as_deref().unwrap_or("unknown CPU"), self.cores)?;
+        if let Some(governor) = &self.governor {
+            write!(f, ", {} governor", governor)?;
+        }
+        write!(f, ", blstrs {}, blst {}", self.blstrs_version, self.blst_version)
+    }
+}
+
+/// The memory Linux estimates is available for new processes without swapping
+/// (`MemAvailable` of /proc/meminfo), in bytes; `None` elsewhere.
+pub fn available_memory() -> Option<usize> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kib : usize = meminfo.lines().find_map(|line| line.strip_prefix("MemAvailable:"))?.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    kib.checked_mul(1024)
+}
+
+/// The frequency of the time-stamp counter in Hz, counted against the clock for 100 ms; `None`
+/// on other architectures than x86-64. The counter ticks at the CPU's nominal frequency
+/// whatever the cores run at, so cycles counted with it are the cores' own only with frequency
+/// scaling and boost off.
+pub fn tsc_frequency() -> Option<u64> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::_rdtsc;
+        let start_time = crate::clock::Instant::now();
+        // SAFETY: every x86-64 CPU has the time-stamp counter
+        let start = unsafe { _rdtsc() };
+        while start_time.elapsed() < std::time::Duration::from_millis(100) {}
+        // SAFETY: as above
+        let ticks = unsafe { _rdtsc() } - start;
+        Some((ticks as f64 / start_time.elapsed().as_secs_f64()) as u64)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    None
+}
+
+/// What about the CPU frequency of `cores` (all of them if empty) makes timings vary from run
+/// to run, as warnings: a cpufreq governor other than `performance`, which scales the clock
+/// with the load, and turbo boost, which raises it for as long as the chip stays cool. Empty
+/// where Linux' cpufreq isn't available (as in most virtual machines), since nothing is known.
+pub fn frequency_warnings(cores: &[usize]) -> Vec<String> {
+    const CPU : &str = "/sys/devices/system/cpu";
+    let read = |path: String| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let mut cores = cores.to_vec();
+    if cores.is_empty() {
+        cores = std::fs::read_dir(CPU).into_iter().flatten().flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("cpu")?.parse().ok())
+            .collect();
+        cores.sort();
+    }
+    // the cores of every governor but `performance`
+    let mut governors : Vec<(String, Vec<String>)> = Vec::new();
+    for core in cores {
+        let Some(governor) = read(format!("{}/cpu{}/cpufreq/scaling_governor", CPU, core)).filter(|g| g != "performance") else { continue };
+        match governors.iter_mut().find(|(g, _)| *g == governor) {
+            Some((_, cores)) => cores.push(core.to_string()),
+            None => governors.push((governor, vec![core.to_string()])),
+        }
+    }
+    let mut warnings : Vec<String> = governors.into_iter()
+        .map(|(governor, cores)| {
+            let plural = if cores.len() == 1 { "" } else { "s" };
+            format!("the `{}` frequency governor on core{} {} (set `performance`, e.g. with `cpupower frequency-set -g performance`)", governor, plural, cores.join(", "))
+        })
+        .collect();
+    if read(format!("{}/intel_pstate/no_turbo", CPU)).as_deref() == Some("0") {
+        warnings.push(format!("turbo boost is on (turn it off with `echo 1 > {}/intel_pstate/no_turbo`)", CPU));
+    }
+    if read(format!("{}/cpufreq/boost", CPU)).as_deref() == Some("1") {
+        warnings.push(format!("frequency boost is on (turn it off with `echo 0 > {}/cpufreq/boost`)", CPU));
+    }
+    warnings
+}
+
+/// Calibrated per-op costs together with the machine they were measured on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MachineProfile {
+    pub machine: Machine,
+    pub created: SystemTime,
+    /// with `machine_id` set to the machine's ID
+    pub costs: CostProfile,
+}
+
+impl MachineProfile {
+    /// Stamps `costs` with `machine`'s ID.
+    pub fn new(machine: Machine, mut costs: CostProfile) -> Self {
+        costs.machine_id = Some(machine.id());
+        Self { machine, created: clock::system_time(), costs }
+    }
+
+    pub fn id(&self) -> String {
+        self.machine.id()
+    }
+
+    pub fn to_json(&self) -> String {
+        // to a tenth of a nanosecond, like `CostProfile::to_toml`
+        let ns = |ns: f64| Value::Number((ns * 10.0).round() / 10.0);
+        let costs = self.costs.costs.iter()
+            .map(|(kind, cost)| {
+                let cost = match cost {
+                    Cost::Fixed(cost) => ns(*cost),
+                    Cost::BySize(points) => Value::Array(points.iter()
+                        .map(|&(size, cost)| Value::Array(vec![Value::Number(size as f64), ns(cost)]))
+                        .collect()),
+                    &Cost::Msm { a, b } => Value::Object(vec![("a".to_string(), ns(a)), ("b".to_string(), ns(b))]),
+                };
+                (kind.name().to_string(), cost)
+            })
+            .collect();
+        let created = self.created.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+        Value::Object(vec![
+            ("id".to_string(), Value::String(self.id())),
+            ("created".to_string(), Value::Number(created as f64)),
+            ("machine".to_string(), self.machine.to_json()),
+            ("costs".to_string(), Value::Object(costs)),
+        ]).to_pretty_string()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ProfileError> {
+        let invalid = |message: String| ProfileError::Invalid(message);
+        let root = json::parse(text).map_err(|e| invalid(e.to_string()))?;
+        let machine = Machine::from_json(root.get("machine").ok_or_else(|| invalid("missing `machine`".to_string()))?)?;
+        // an edited machine description no longer matches the ID reports were stamped with
+        match root.get("id").and_then(Value::as_str) {
+            Some(id) if id == machine.id() => {}
+            Some(id) => return Err(invalid(format!("the ID `{}` doesn't match the machine, whose ID is `{}`", id, machine.id()))),
+            None => return Err(invalid("missing `id`".to_string())),
+        }
+        let created = root.get("created").and_then(Value::as_f64).filter(|&secs| secs >= 0.0)
+            .ok_or_else(|| invalid("missing `created`".to_string()))?;
+        let costs = match root.get("costs").cloned().map(toml::from_json) {
+            Some(Ok(toml::Value::Table(costs))) => costs,
+            Some(Err(message)) => return Err(invalid(message)),
+            _ => return Err(invalid("missing `costs` object".to_string())),
+        };
+        let costs = CostProfile { costs: cost_model::parse_costs(&costs)?, machine_id: Some(machine.id()) };
+        Ok(Self { machine, created: UNIX_EPOCH + std::time::Duration::from_secs(created as u64), costs })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
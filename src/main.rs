@@ -1,152 +1,901 @@
 
-mod group_ops_simulation;
+mod cli;
 
-use group_ops_simulation::*;
+use blstrs_pvss_simulation::async_round::{AsyncRound, Delays};
+use blstrs_pvss_simulation::backend;
+use blstrs_pvss_simulation::baseline::Baseline;
+use blstrs_pvss_simulation::budget;
+use blstrs_pvss_simulation::communication::{format_bytes, Communication};
+use blstrs_pvss_simulation::compare::{Comparison, Side};
+use blstrs_pvss_simulation::energy::{self, Meter};
+use blstrs_pvss_simulation::config;
+use blstrs_pvss_simulation::cost_model::{Calibration, CostProfile};
+use blstrs_pvss_simulation::export;
+use blstrs_pvss_simulation::group_ops_simulation::*;
+use blstrs_pvss_simulation::history::{self, History, Record};
+#[cfg(feature = "instruction-counts")]
+use blstrs_pvss_simulation::instructions;
+use blstrs_pvss_simulation::interrupt;
+use blstrs_pvss_simulation::latex::{self, Macros};
+use blstrs_pvss_simulation::machine::{self, Machine, MachineProfile};
+use blstrs_pvss_simulation::merge::MachineComparison;
+use blstrs_pvss_simulation::op_counts::{self, OpCounts};
+use blstrs_pvss_simulation::parallel;
+use blstrs_pvss_simulation::party::PartySimulation;
+use blstrs_pvss_simulation::pipeline;
+use blstrs_pvss_simulation::pippenger;
+use blstrs_pvss_simulation::plot;
+use blstrs_pvss_simulation::presets::{self, GrothParams};
+use blstrs_pvss_simulation::progress::Reporter;
+use blstrs_pvss_simulation::protocol::{self, Params, Protocol, STATIC_G2_PHASE};
+use blstrs_pvss_simulation::pvss;
+use blstrs_pvss_simulation::scaling;
+use blstrs_pvss_simulation::schedule::Schedule;
+use blstrs_pvss_simulation::sweep::{Sweep, SweepRow, SweepTable};
+use blstrs_pvss_simulation::test_vectors::TestVectors;
+use blstrs_pvss_simulation::render::Unit;
+use blstrs_pvss_simulation::weighted::{self, WeightedReport, Weights};
+use blstrs_pvss_simulation::workload::{Op, OpKind, Workload};
+use rand::RngCore;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
 
-use blstrs::{pairing, Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
-use group::{ff::Field as _, Group as _, Curve as _};
-use pairing::{MultiMillerLoop, MillerLoopResult};
+use blstrs::{G1Projective, Scalar};
+use group::{ff::Field as _, Group as _};
 use rand::thread_rng;
-use std::time::{Instant, Duration};
+use std::process;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 
-fn multi_pairing<'a>(lhs: impl Iterator<Item = &'a G1Projective>, rhs: impl Iterator<Item = &'a G2Projective>) -> Gt {
-    <Bls12 as MultiMillerLoop>::multi_miller_loop(
-        &lhs.zip(rhs)
-           .map(|(a,b)| (a.to_affine(), G2Prepared::from(b.to_affine())))
-           .collect::<Vec<(G1Affine, G2Prepared)>>()
-           .iter()
-           .map(|(g1, g2)| (g1, g2))
-           .collect::<Vec<(&G1Affine, &G2Prepared)>>()
-           ).final_exponentiation()
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
 }
 
-fn simulate_group_ops(
-    num_exps_in_G1: usize,
-    mexps_in_G1: &[(usize, usize)], 
-    num_exps_in_G2: usize,
-    mexps_in_G2: &[(usize, usize)],
-    num_pairings: usize,
-    multi_pairings: &[(usize, usize)]) 
-{
-    let mut rng = thread_rng();
+// with --energy, the meter every simulation reads, opened once
+fn energy_meter(options: &cli::Options) -> Option<Arc<Meter>> {
+    static METER : OnceLock<Arc<Meter>> = OnceLock::new();
+    options.energy.then(|| METER.get_or_init(|| Arc::new(Meter::open().unwrap_or_else(|e| fail(format!("--energy: {}", e))))).clone())
+}
 
+// progress goes to stderr unless --quiet, and to the --metrics file
+fn progress(options: &cli::Options) -> Option<Reporter> {
+    match ((!options.quiet).then(Reporter::stderr), options.metrics.clone()) {
+        (Some(stderr), Some(metrics)) => Some(stderr.and(metrics)),
+        (stderr, metrics) => stderr.or(metrics),
+    }
+}
 
-    let bases_G1 : Vec<G1Projective> = (0..num_exps_in_G1).map(|_| G1Projective::random(&mut rng)).collect();
-    let scalars_G1 : Vec<Scalar> = (0..num_exps_in_G1).map(|_| Scalar::random(&mut rng)).collect();
-    let exp_G1_args = bases_G1.iter().zip(scalars_G1.iter());
+// the recorded points that match `filter`, as a table
+fn query(filter: &history::Filter, options: &cli::Options) {
+    let dir = options.history.clone().unwrap_or_else(|| History::DEFAULT_DIR.into());
+    let records = History::new(&dir).query(filter).unwrap_or_else(|e| fail(format!("{}: {}", dir.display(), e)));
+    if records.is_empty() {
+        return eprintln!("no recorded runs match");
+    }
+    print!("{}", history::table(&records).render(&options.table.unwrap_or_default()));
+}
 
-    let mut mexp_G1_args = Vec::new();
-    for (num_mexps, mexp_size) in mexps_in_G1 {
-        let bases : Vec<G1Projective> = (0..*mexp_size).map(|_| G1Projective::random(&mut rng)).collect();
-        let scalars : Vec<Scalar> = (0..*mexp_size).map(|_| Scalar::random(&mut rng)).collect();
-        mexp_G1_args.push((*num_mexps, bases, scalars));
+// the preset, with its static G2 inputs prepared once with --static-g2
+fn preset_protocol(preset: &str, options: &cli::Options) -> Protocol {
+    let protocol = presets::by_name(preset).unwrap_or_else(|| fail(format!("unknown preset `{}`", preset)));
+    match options.static_g2 {
+        true => protocol.with_static_g2(),
+        false => protocol,
     }
+}
 
-    let bases_G2 : Vec<G2Projective> = (0..num_exps_in_G2).map(|_| G2Projective::random(&mut rng)).collect();
-    let scalars_G2 : Vec<Scalar> = (0..num_exps_in_G2).map(|_| Scalar::random(&mut rng)).collect();
-    let exp_G2_args = bases_G2.iter().zip(scalars_G2.iter());
+// the parameter combinations of a sweep, each evaluated into a workload of the preset
+fn sweep_workloads(preset: &str, axes: &[String], options: &cli::Options) -> (Vec<String>, Vec<(Params, Workload, Communication)>) {
+    let protocol = preset_protocol(preset, options);
+    let mut sweep = Sweep::new();
+    for axis in axes {
+        sweep.parse_axis(axis).unwrap_or_else(|e| fail(e));
+    }
+    let points = sweep.points().unwrap_or_else(|e| fail(e));
+    // evaluate everything up front so a missing parameter doesn't abort a half-finished sweep
+    let workloads = points.into_iter()
+        .map(|params| {
+            let message = |e| format!("{} (parameters: {})", e, protocol.params().join(", "));
+            let workload = protocol.evaluate(&params).unwrap_or_else(|e| fail(message(e)));
+            let communication = protocol.communication(&params).unwrap_or_else(|e| fail(message(e)));
+            if let Some(e) = workload.ops.iter().find_map(|op| op.check().err()) {
+                fail(format!("{} (parameters: {})", e, params.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(" ")));
+            }
+            (params, workload, communication)
+        })
+        .collect();
+    (sweep.names().into_iter().map(str::to_string).collect(), workloads)
+}
 
-    let mut mexp_G2_args = Vec::new();
-    for (num_mexps, mexp_size) in mexps_in_G2 {
-        let bases : Vec<G2Projective> = (0..*mexp_size).map(|_| G2Projective::random(&mut rng)).collect();
-        let scalars : Vec<Scalar> = (0..*mexp_size).map(|_| Scalar::random(&mut rng)).collect();
-        mexp_G2_args.push((*num_mexps, bases, scalars));
+// checked, so that a workload that can't work fails before any of its inputs are drawn
+fn add_workload<R: RngCore>(sim: &mut GroupOpsSimulation<R>, workload: &Workload) {
+    if let Some(bytes) = machine::available_memory() {
+        sim.memory_limit(bytes);
     }
+    sim.try_workload(workload).unwrap_or_else(|e| fail(e));
+}
 
-    let pairing_args_G1 : Vec<G1Affine> = (0..num_pairings).map(|_| G1Projective::random(&mut rng).to_affine()).collect();
-    let pairing_args_G2 : Vec<G2Affine> = (0..num_pairings).map(|_| G2Projective::random(&mut rng).to_affine()).collect();
-    let pairing_args = pairing_args_G1.iter().zip(pairing_args_G2);
+// per-phase and total times of one simulation (the means with --reps) of a point of a preset,
+// also recorded in `results` and, unless interrupted, in the history as measured by `command`
+fn phase_times(workload: &Workload, rng: impl RngCore, options: &cli::Options, results: &mut Baseline, prefix: &str, (command, preset, params): (&str, &str, &Params)) -> (Vec<(String, Duration)>, Duration) {
+    let mut sim = GroupOpsSimulation::new(rng);
+    add_workload(sim.setup(options.setup), workload);
+    sim.warm_up(options.warm_up).cold_start(options.cold_start).order(options.order).threads(options.threads);
+    if let Some(reporter) = progress(options) {
+        sim.progress(reporter);
+    }
+    if let Some(meter) = energy_meter(options) {
+        sim.measure_energy(meter);
+    }
+    let (phases, total, profile_id, energy) = if options.reps == 1 {
+        let report = sim.simulate();
+        results.record(prefix, &report);
+        (report.phase_totals(), report.total, report.metadata.profile_id.clone(), report.energy().map(|total| (total, report.phase_energy())))
+    } else {
+        let runs = sim.simulate_n(options.reps);
+        results.record_runs(prefix, &runs);
+        (runs.phase_totals(), runs.total.mean, runs.profile_id.clone(), runs.energy().map(|total| (total, runs.phase_energy())))
+    };
+    if let Some((total, phases)) = energy {
+        println!("{}: energy {}", prefix, energy::summary(total, &phases));
+    }
+    if let Some(dir) = options.history.as_ref().filter(|_| !interrupt::requested()) {
+        let record = Record { profile_id, seed: options.seed, reps: options.reps, ..Record::new(command, preset, params, &phases, total) };
+        History::new(dir).append(&record).unwrap_or_else(|e| fail(format!("{}: {}", dir.display(), e)));
+    }
+    (phases, total)
+}
 
-    let mut multi_pairing_args = Vec::new();
-    for (num_multi_pairings, multi_pairing_size) in multi_pairings {
-        let multi_pairing_args_G1 : Vec<G1Projective> = (0..*multi_pairing_size).map(|_| G1Projective::random(&mut rng)).collect();
-        let multi_pairing_args_G2 : Vec<G2Projective> = (0..*multi_pairing_size).map(|_| G2Projective::random(&mut rng)).collect();
-        multi_pairing_args.push((*num_multi_pairings, multi_pairing_args_G1, multi_pairing_args_G2));
+fn sweep(preset: &str, axes: &[String], rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    let (param_names, workloads) = sweep_workloads(preset, axes, options);
+    let protocol = preset_protocol(preset, options);
+    let mut table = SweepTable { param_names, rows: Vec::new(), unit: protocol.throughput.as_ref().map(|(unit, _)| unit.clone()) };
+    let points = workloads.len();
+    let mut savings = Vec::new();
+    for (params, workload, communication) in workloads {
+        let point : Vec<String> = table.param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        let (phases, total) = phase_times(&workload, &mut *rng, options, results, &point.join(" "), ("sweep", preset, &params));
+        // the point may not have completed, so it's left out of the table
+        if interrupt::requested() {
+            eprintln!("partial: interrupted after {} of {} parameter points", table.rows.len(), points);
+            break;
+        }
+        if !options.quiet {
+            eprintln!("{}: {:?}", point.join(" "), total);
+        }
+        let units = protocol.units(&params).unwrap_or_else(|e| fail(e));
+        savings.extend(static_g2_savings(&point.join(" "), &workload, &phases));
+        table.rows.push(SweepRow { params, phases, total, transcript: Some(communication.total()), units });
+    }
+    match &options.table {
+        Some(style) => print!("{}", table.to_table().render(style)),
+        None => print!("{}", table),
     }
+    for line in savings {
+        println!("{}", line);
+    }
+    print_crossover(&table, options);
+    print_fits(&table, options);
+    save_plot(&table, options);
+}
 
-    let start_time = Instant::now();
-    let exp_G1_result : Vec<G1Projective> = exp_G1_args.map(|(base, scalar)| base * scalar).collect();
-    mexp_G1_args.iter().for_each( |(num_mexps, bases, scalars)| for i in 0..*num_mexps { G1Projective::multi_exp(bases, scalars); });
-    let exp_G2_result : Vec<G2Projective> = exp_G2_args.map(|(base, scalar)| base * scalar).collect();
-    mexp_G2_args.iter().for_each( |(num_mexps, bases, scalars)| for i in 0..*num_mexps { G2Projective::multi_exp(bases, scalars); });
-    let pairings_result : Vec<Gt> = pairing_args.map(|(a,b)| pairing(&a,&b)).collect();
-    multi_pairing_args.iter().for_each( |(num_multi_pairings, args_G1, args_G2)| for i in 0..*num_multi_pairings { multi_pairing(args_G1.iter(), args_G2.iter()); });
-    let duration = start_time.elapsed();
-    println!("{:?}", duration);
-}
-
-fn simulate_groth(n: usize, k: usize, t: usize, l: usize) {
-    println!("Groth16, n={}, k={}, t={}, l={}", n, k, t, l);
-    println!("Prover:");
-    simulate_group_ops(
-        n + 2*k + l + 2, // G1 exps
-        &[(2, n),
-          (n*k + n + k + l + 1, 2)], // G1 mexps
-        t + 1, // G2 exps
-        &[], // no G2 mexps
-        0,     // no pairings,
-        &[] // no multi-pairings
-        );
-    println!("Verifier:");
-    simulate_group_ops(
-        n + 2, // G1 exps
-        &[
-            (1, 2),
-            (2, n+1),
-            (n, k+1),
-            (1, l+1),
-            (1, k*n*l + l + 1),
-            (1, n+2),
-            (2, k)
-        ], // G1 mexps
-        1, // G2 exps
-        &[
-            (1, t+1),
-            (1, k)
-        ], // G2 mexps
-        0,     // no pairings,
-        &[(1,3)] // multi-pairings
-        );
-    // we need to do one inversion to convert the three pairings into multi-pairings, which I'm not
-    // simulating.
-    //
-    let mut rng = thread_rng();
-    let mut sim = GroupOpsSimulation::new(&mut rng);
-    sim
-        .g1_exps(n + 2*k + l + 2)
-        .g1_multi_exps(2, n)
-        .g1_multi_exps(n*k + n + k + l + 1, 2)
-        .g2_exps(t+1);
+// with --static-g2, the static G2 points a point prepares once, and what preparing them in every
+// multi-pairing instead would add per run at the time per preparation of its `Static G2` phase
+fn static_g2_savings(point: &str, workload: &Workload, phases: &[(String, Duration)]) -> Option<String> {
+    let (prepared, skipped) = protocol::static_g2_preparations(workload);
+    let (_, setup) = phases.iter().find(|(phase, _)| phase == STATIC_G2_PHASE)?;
+    if prepared == 0 {
+        return None;
+    }
+    let added = setup.mul_f64(skipped as f64 / prepared as f64);
+    Some(format!("{}: preparing the static G2 points once takes {:.3?} ({} preparations); preparing them in every multi-pairing would add {:.3?} ({} preparations) per run",
+        point, setup, prepared, added, skipped))
+}
 
-    let start_time = Instant::now();
-    sim.simulate();
-    let duration = start_time.elapsed();
-    println!("{:?}", duration);
-
-    let mut rng = thread_rng();
-    let mut sim = GroupOpsSimulation::new(&mut rng);
-    sim
-        .g1_exps(n + 2)
-        .g1_multi_exps(1,2)
-        .g1_multi_exps(2,n+1)
-        .g1_multi_exps(n,k+1)
-        .g1_multi_exps(1,l+1)
-        .g1_multi_exps(1,k*n*l+l+1)
-        .g1_multi_exps(1,n+2)
-        .g1_multi_exps(2,k)
-        .g2_exps(1)
-        .g2_multi_exps(1,t+1)
-        .g2_multi_exps(1,k)
-        .multi_pairings(1, 3);
+fn print_crossover(table: &SweepTable, options: &cli::Options) {
+    let Some((a, b)) = &options.crossover else { return };
+    match table.crossover(a, b) {
+        Some(row) => {
+            let point : Vec<String> = table.param_names.iter().map(|name| format!("{}={}", name, row.params.get(name).unwrap())).collect();
+            println!("{} is faster than {} from {}", b, a, point.join(" "));
+        }
+        None => println!("{} is never faster than {}", b, a),
+    }
+}
+
+fn print_fits(table: &SweepTable, options: &cli::Options) {
+    let Some(param) = &options.fit else { return };
+    if !table.param_names.contains(param) {
+        fail(format!("--fit: no parameter `{}` in the sweep", param));
+    }
+    println!("fits against {}:", param);
+    for (phase, fits) in table.fits(param) {
+        let Some((best, others)) = fits.split_first() else {
+            println!("  {}: too few distinct values of {} to fit", phase, param);
+            continue;
+        };
+        let others : Vec<String> = others.iter().map(|fit| format!("{}: R² = {:.4}", fit.model.name(), fit.r_squared)).collect();
+        println!("  {}: {}; {}", phase, best, others.join(", "));
+    }
+}
+
+fn save_plot(table: &SweepTable, options: &cli::Options) {
+    if let Some(path) = &options.plot {
+        plot::save_sweep_svg(table, path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        eprintln!("saved plot to {}", path.display());
+    }
+}
+
+fn pvss(n: usize, t: usize, rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    println!("PVSS, n={}, t={}", n, t);
+    let keys : Vec<pvss::Keypair> = (0..n).map(|_| pvss::Keypair::generate(&mut *rng)).collect();
+    let public_keys : Vec<G1Projective> = keys.iter().map(|key| key.public).collect();
+    let secret = Scalar::random(&mut *rng);
 
+    println!("measured:");
     let start_time = Instant::now();
-    sim.simulate();
-    let duration = start_time.elapsed();
-    println!("{:?}", duration);
+    let dealing = pvss::deal(&secret, t, &public_keys, &mut *rng);
+    let dealer_time = start_time.elapsed();
+    println!("  dealer: {:?}", dealer_time);
+    let start_time = Instant::now();
+    let result = pvss::check_share_commitments(&dealing, &mut *rng);
+    println!("    share commitment check: {:?}", start_time.elapsed());
+    let checks_time = Instant::now();
+    let result = result.and_then(|()| pvss::check_encryptions(&dealing, &public_keys));
+    println!("    encryption checks: {:?}", checks_time.elapsed());
+    let verifier_time = start_time.elapsed();
+    println!("  verifier: {:?}", verifier_time);
+    if let Err(e) = result {
+        fail(format!("dealing failed to verify: {}", e));
+    }
+    // parties decrypt concurrently, so one party's time is what counts towards the latency
+    let start_time = Instant::now();
+    let shares : Vec<pvss::DecryptedShare> = keys.iter().take(t + 1).enumerate()
+        .map(|(i, key)| pvss::decrypt_share(&dealing, i, key, &mut *rng))
+        .collect();
+    let party_time = start_time.elapsed() / (t as u32 + 1);
+    println!("  party (decryption): {:?}", party_time);
+    let start_time = Instant::now();
+    let reconstructed = pvss::reconstruct(&dealing, &public_keys, &shares).unwrap_or_else(|e| fail(format!("reconstruction failed: {}", e)));
+    let reconstructor_time = start_time.elapsed();
+    println!("  reconstructor: {:?}", reconstructor_time);
+    if reconstructed != G1Projective::generator() * secret {
+        fail("reconstructed the wrong secret");
+    }
+    println!("  end-to-end: {:?}", dealer_time + verifier_time + party_time + reconstructor_time);
+
+    println!("simulated:");
+    let params = Params::new().set("n", n as i64).set("t", t as i64);
+    // everyone verifies the dealing and decrypts their share
+    let mut round = PartySimulation::from_phases(&presets::pvss_protocol().evaluate(&params).unwrap(), |role| match role {
+        "Verifier" | "Party" => n,
+        _ => 1,
+    });
+    round.setup(options.setup).warm_up(options.warm_up).cold_start(options.cold_start).order(options.order).threads(options.threads);
+    if let Some(reporter) = progress(options) {
+        round.progress(reporter);
+    }
+    let round = round.simulate(&mut *rng);
+    print!("{}", round);
+    for role in round.steps.iter().flatten() {
+        results.record(&role.name, &role.report);
+    }
+    let communication = presets::pvss_protocol().communication(&params).unwrap();
+    print!("{}", communication);
+    if let Some(network) = &options.network {
+        print!("{}", network.round_time(&round, &communication, n));
+    }
+}
+
+// the DKG preset over `scheme`, with the complaints of f faulty dealers if the axes set f
+fn dkg_preset(scheme: &str, axes: &[String]) -> String {
+    match axes.iter().any(|axis| axis.starts_with("f=")) {
+        true => format!("dkg-{}-complaints", scheme),
+        false => format!("dkg-{}", scheme),
+    }
+}
+
+fn simulate_dkg(scheme: &str, axes: &[String], rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    let (param_names, workloads) = sweep_workloads(&dkg_preset(scheme, axes), axes, options);
+    for (params, workload, mut communication) in workloads {
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        println!("DKG over {}, {}", scheme, point.join(", "));
+        let mut sim = GroupOpsSimulation::new(&mut *rng);
+        add_workload(sim.setup(options.setup), &workload);
+        let per_party = run(&mut sim, options, results, &point.join(" "));
+        if interrupt::requested() {
+            break;
+        }
+        print_estimate(&workload, Some(per_party), options);
+        let n = params.get("n").unwrap() as u32;
+        println!("network-wide ({} parties): {:?}", n, per_party * n);
+        // every party receives all n transcripts, so the per-party line would undercount
+        communication.parties = None;
+        print!("{}", communication);
+        println!("  received per party: {}", format_bytes(communication.total() * n as usize));
+    }
+}
+
+// the preset's dealing and one verification, simulated, with each of the n parties dealing and
+// verifying everyone else's transcript as it arrives
+fn simulate_async(preset: &str, delays: &Delays, axes: &[String], rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    let (param_names, workloads) = sweep_workloads(preset, axes, options);
+    for (params, workload, _) in workloads {
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        println!("{}, {}", preset, point.join(", "));
+        let parties = params.get("n").unwrap_or_else(|| fail(format!("async needs a preset with parties `n`, which `{}` doesn't have", preset))) as usize;
+        let workload = Workload { ops: workload.ops.into_iter().filter(|op| matches!(op.phase.as_deref(), Some("Dealer" | "Prover" | "Verifier"))).collect() };
+        let (phases, _) = phase_times(&workload, &mut *rng, options, results, &point.join(" "), ("async", preset, &params));
+        if interrupt::requested() {
+            break;
+        }
+        let time = |names: &[&str]| phases.iter().filter(|(phase, _)| names.contains(&phase.as_str())).map(|&(_, time)| time).sum::<Duration>();
+        let (deal, verify) = (time(&["Dealer", "Prover"]), time(&["Verifier"]));
+        if deal.is_zero() || verify.is_zero() {
+            fail(format!("async needs a preset with a Dealer (or Prover) and a Verifier phase, which `{}` doesn't have", preset));
+        }
+        print!("{}", AsyncRound { parties, deal, verify, delays: *delays }.simulate(&mut *rng));
+    }
+}
+
+// the preset's axes with `n` set to the number of shares
+fn weighted_axes(weights: &Weights, axes: &[String]) -> Vec<String> {
+    if axes.iter().any(|axis| axis.starts_with("n=")) {
+        fail("weighted sets `n` to the number of shares itself");
+    }
+    std::iter::once(format!("n={}", weights.total())).chain(axes.iter().cloned()).collect()
+}
+
+fn simulate_weighted(preset: &str, weights: &Weights, axes: &[String], rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    let (param_names, workloads) = sweep_workloads(preset, &weighted_axes(weights, axes), options);
+    for (params, workload, _) in workloads {
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        println!("weighted {}, {}", preset, point.join(", "));
+        let (phases, _) = phase_times(&workload, &mut *rng, options, results, &point.join(" "), ("weighted", preset, &params));
+        if interrupt::requested() {
+            break;
+        }
+        print!("{}", WeightedReport::new(weights.clone(), &phases));
+    }
+}
+
+fn compare(a: &(String, Vec<String>), b: &(String, Vec<String>), rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    let mut side = |(preset, axes): &(String, Vec<String>)| {
+        let (param_names, mut workloads) = sweep_workloads(preset, axes, options);
+        if workloads.len() != 1 {
+            fail(format!("compare takes a single value per parameter, but `{}` was given {} combinations", preset, workloads.len()));
+        }
+        let (params, workload, communication) = workloads.remove(0);
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        let name = format!("{} {}", preset, point.join(" "));
+        let (phases, total) = phase_times(&workload, &mut *rng, options, results, &name, ("compare", preset, &params));
+        Side { name, phases, total, transcript: Some(communication.total()) }
+    };
+    let a = side(a);
+    let b = side(b);
+    // half a comparison compares nothing
+    if interrupt::requested() {
+        return;
+    }
+    let comparison = Comparison::new(a, b);
+    match &options.table {
+        Some(style) => print!("{}", comparison.to_table().render(style)),
+        None => print!("{}", comparison),
+    }
+}
+
+fn backends(preset: &str, axes: &[String], seed: u64, options: &cli::Options) {
+    let (param_names, workloads) = sweep_workloads(preset, axes, options);
+    #[cfg(feature = "gpu")]
+    if backend::gpu_devices() == 0 {
+        println!("(no GPU found: {} runs its multi-exps on the CPU)", backend::describe::<backend::Gpu>());
+    }
+    for (params, workload, communication) in workloads {
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        println!("{} {}", preset, point.join(" "));
+        let (core, rest) : (Vec<_>, Vec<_>) = workload.ops.into_iter().partition(|op| backend::is_core(op.kind));
+        if !rest.is_empty() {
+            let rest : Vec<String> = rest.iter().map(|op| op.to_string()).collect();
+            println!("(leaving out the ops not every backend runs: {})", rest.join(", "));
+        }
+        let workload = Workload { ops: core };
+        let mut reports = Vec::new();
+        let mut curves = Vec::new();
+        for (name, curve, report) in backend::simulate_on_all(&workload, seed) {
+            match report {
+                Ok(report) => reports.push((name, report)),
+                Err(e) => eprintln!("skipping {}: {}", name, e),
+            }
+            if !curves.contains(&curve) {
+                curves.push(curve);
+            }
+        }
+        let style = options.table.unwrap_or_default();
+        print!("{}", backend::comparison_table(&reports).render(&style));
+        print!("{}", backend::gap_table(&workload, &reports).render(&style));
+        for curve in curves {
+            println!("transcript on {}: {}", curve.name(), format_bytes(curve.bytes(&communication)));
+        }
+    }
+}
+
+// the best time of a phase of the preset with `budget` bytes of precomputation, per parameter
+// combination
+fn explore_budget(preset: &str, budget: usize, axes: &[String], rng: &mut impl RngCore, options: &cli::Options) {
+    let (param_names, workloads) = sweep_workloads(preset, axes, options);
+    for (params, workload, _) in workloads {
+        let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        println!("{} {}, phase {}", preset, point.join(" "), options.phase);
+        let ops : Vec<Op> = workload.ops.into_iter().filter(|op| op.phase.as_deref() == Some(options.phase.as_str())).collect();
+        if ops.is_empty() {
+            fail(format!("{} has no phase `{}` (see --phase)", preset, options.phase));
+        }
+        print!("{}", budget::explore(&Workload { ops }, budget, options.reps, &mut *rng));
+    }
+}
+
+// with --export-latex, `macros` in its file, after the command line that regenerates them
+fn export_latex(macros: &Macros, options: &cli::Options) {
+    let Some(path) = &options.export_latex else { return };
+    let command_line = std::iter::once("pvss-sim".to_string()).chain(std::env::args().skip(1)).collect::<Vec<_>>().join(" ");
+    let tex = macros.to_tex(&format!("written by `{}`, which rewrites this file", command_line));
+    std::fs::write(path, tex).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    println!("wrote {} LaTeX macros to {}", macros.definitions.len(), path.display());
+}
+
+// per-op costs of this machine, reporting every measurement on stderr unless --quiet, and with
+// --export-latex writing them as LaTeX macros
+fn calibrate_costs(seed: u64, options: &cli::Options) -> CostProfile {
+    let profile = Calibration::default().run(seed, |kind, size, ns| {
+        if options.quiet {
+            return;
+        }
+        if kind.is_sized() {
+            eprintln!("{} of size {}: {:.0} ns", kind.name(), size, ns);
+        } else {
+            eprintln!("{}: {:.0} ns", kind.name(), ns);
+        }
+    });
+    export_latex(&latex::cost_macros(&profile, &options.table.unwrap_or_default()), options);
+    profile
+}
+
+fn calibrate(seed: u64, options: &cli::Options) {
+    let path = options.output.clone().unwrap_or_else(|| "profile.toml".into());
+    let profile = calibrate_costs(seed, options);
+    profile.save(&path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    println!("saved profile to {}", path.display());
+}
+
+fn profile(seed: u64, options: &cli::Options) {
+    let path = options.output.clone().unwrap_or_else(|| "profile.json".into());
+    let machine = Machine::current().clone();
+    println!("machine: {}", machine);
+    let profile = MachineProfile::new(machine, calibrate_costs(seed, options));
+    profile.save(&path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    println!("saved profile {} to {}", profile.id(), path.display());
+}
+
+fn list_presets() {
+    for preset in presets::all() {
+        println!("{}: {}", preset.name(), preset.description());
+        let width = preset.params().iter().map(|param| param.name.len()).max().unwrap_or(0);
+        for param in preset.params() {
+            println!("    {:width$}  {}", param.name, param.description, width = width);
+        }
+    }
+}
+
+// every profile under its file's name
+fn merge_profiles(paths: &[std::path::PathBuf], options: &cli::Options) {
+    let profiles = paths.iter()
+        .map(|path| {
+            let profile = MachineProfile::load(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+            let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+            (name, profile)
+        })
+        .collect();
+    let comparison = MachineComparison::new(profiles, options.g1_exp_units);
+    match &options.table {
+        Some(style) => print!("{}", comparison.to_table().render(style)),
+        None => print!("{}", comparison),
+    }
+}
+
+// to --output, or stdout without it
+fn export_op_counts(preset: &str, axes: &[String], options: &cli::Options) {
+    let (_, workloads) = sweep_workloads(preset, axes, options);
+    let points : Vec<(Params, OpCounts)> = workloads.into_iter().map(|(params, workload, _)| (params, OpCounts::new(&workload))).collect();
+    let json = op_counts::to_json(preset, &points);
+    match &options.output {
+        Some(path) => {
+            std::fs::write(path, json).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+            eprintln!("saved op counts to {}", path.display());
+        }
+        None => print!("{}", json),
+    }
+}
+
+fn predict(profile: &CostProfile, preset: &str, axes: &[String], options: &cli::Options) {
+    let (param_names, workloads) = sweep_workloads(preset, axes, options);
+    let protocol = preset_protocol(preset, options);
+    let mut table = SweepTable { param_names, rows: Vec::new(), unit: protocol.throughput.as_ref().map(|(unit, _)| unit.clone()) };
+    let mut savings = Vec::new();
+    for (params, workload, communication) in &workloads {
+        let report = profile.estimate(workload).unwrap_or_else(|e| fail(e));
+        if workloads.len() == 1 {
+            match &options.table {
+                Some(style) => print!("{}", report.phase_table().render(style)),
+                None => print!("{}{}", report, communication),
+            }
+        }
+        let transcript = Some(communication.total());
+        let units = protocol.units(params).unwrap_or_else(|e| fail(e));
+        let point : Vec<String> = table.param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+        savings.extend(static_g2_savings(&point.join(" "), workload, &report.phase_totals()));
+        table.rows.push(SweepRow { params: params.clone(), phases: report.phase_totals(), total: report.total, transcript, units });
+    }
+    if workloads.len() > 1 {
+        match &options.table {
+            Some(style) => print!("{}", table.to_table().render(style)),
+            None => print!("{}", table),
+        }
+    }
+    for line in savings {
+        println!("{}", line);
+    }
+    print_crossover(&table, options);
+    print_fits(&table, options);
+    save_plot(&table, options);
+}
+
+// one run of a preset from the profile's costs and the network model, phase by phase
+fn project(profile: &CostProfile, preset: &str, axes: &[String], options: &cli::Options) {
+    let (param_names, mut workloads) = sweep_workloads(preset, axes, options);
+    if workloads.len() != 1 {
+        fail("project takes one value per parameter");
+    }
+    let (params, workload, communication) = workloads.remove(0);
+    let n = params.get("n").unwrap_or_else(|| fail("project needs the committee size as parameter n"));
+    let network = options.network.as_ref().unwrap();
+    let estimate = profile.estimate(&workload).unwrap_or_else(|e| fail(e));
+    let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+    println!("{}, {}", preset, point.join(", "));
+    match &options.table {
+        Some(style) => print!("{}", estimate.phase_table().render(style)),
+        None => print!("{}", estimate),
+    }
+    print!("{}", communication);
+    let round = network.phase_times(&estimate.phase_totals(), &communication, n as usize);
+    print!("{}", round);
+    println!("one run of {} with {} takes ~{:.2?}", preset, point.join(", "), round.total());
+}
+
+// prints the report, records it in `results` and returns the total time (the mean with --reps,
+// the time per run at the measured rates with --throughput)
+fn run<R: RngCore>(sim: &mut GroupOpsSimulation<R>, options: &cli::Options, results: &mut Baseline, prefix: &str) -> Duration {
+    sim.warm_up(options.warm_up).cold_start(options.cold_start).order(options.order).threads(options.threads);
+    if let Some(reporter) = progress(options) {
+        sim.progress(reporter);
+    }
+    if let Some(meter) = energy_meter(options) {
+        sim.measure_energy(meter);
+    }
+    if options.validate {
+        match sim.validate() {
+            Ok(checked) => eprintln!("validated {} items against naive computations", checked),
+            Err(item) => fail(format!("--validate: `{}` disagrees with the naive computation", item)),
+        }
+    }
+    if let Some(path) = &options.test_vectors {
+        let vectors = TestVectors { seed: options.seed, ..sim.test_vectors() };
+        vectors.save(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        eprintln!("saved the results of {} items to {}", vectors.items.len(), path.display());
+    }
+    if let Some(expected) = &options.check_vectors {
+        let mismatches = expected.mismatches(&sim.test_vectors());
+        for mismatch in &mismatches {
+            eprintln!("{}", mismatch);
+        }
+        if !mismatches.is_empty() {
+            fail(format!("--check-vectors: {} of {} items disagree with the test vectors (built with the same --fast-setup?)", mismatches.len(), expected.items.len()));
+        }
+        eprintln!("the results of all {} items match the test vectors", expected.items.len());
+    }
+    if let Some(schedule) = &options.replay {
+        sim.replay(schedule.clone()).unwrap_or_else(|e| fail(format!("--replay: {}", e)));
+    }
+    if let Some(path) = &options.record_schedule {
+        // the seed main derived every input from, not the simulation's own
+        let schedule = Schedule { seed: options.seed, ..sim.schedule(options.reps) };
+        schedule.save(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        eprintln!("saved schedule to {}", path.display());
+    }
+    #[cfg(feature = "instruction-counts")]
+    if let Some(dir) = instructions::child_dir() {
+        let items = sim.count_instructions();
+        instructions::write_items(&dir, &items).unwrap_or_else(|e| fail(format!("{}: {}", dir.display(), e)));
+        process::exit(0);
+    }
+    if let Some(budget) = options.throughput {
+        let report = sim.simulate_for(budget);
+        print!("{}", report);
+        return report.run_time();
+    }
+    if options.reps == 1 {
+        let report = sim.simulate();
+        match &options.table {
+            Some(style) => print!("{}", report.phase_table().render(style)),
+            None => print!("{}", report),
+        }
+        results.record(prefix, &report);
+        return report.total;
+    }
+
+    let runs = sim.simulate_n(options.reps);
+    match &options.table {
+        Some(style) => print!("{}", runs.phase_table().render(style)),
+        None => print!("{}", runs),
+    }
+    results.record_runs(prefix, &runs);
+    runs.total.mean
+}
+
+// with --instructions, the counts of every item of the same command, run with the same seed in
+// a child under Callgrind, instead of its times
+#[cfg(feature = "instruction-counts")]
+fn count_instructions(seed: u64) {
+    let args : Vec<String> = std::env::args().skip(1)
+        .filter(|arg| arg != "--instructions")
+        .chain(["--seed".to_string(), seed.to_string(), "--quiet".to_string()])
+        .collect();
+    let report = instructions::run_under_callgrind(&args).unwrap_or_else(|e| fail(format!("--instructions: {}", e)));
+    print!("{}", report);
+}
+
+#[cfg(not(feature = "instruction-counts"))]
+fn count_instructions(_seed: u64) {
+    fail("--instructions needs a build with the instruction-counts feature (cargo build --features instruction-counts)");
+}
 
+// with --estimate, the workload's predicted time, compared with the measured one if there is one
+fn print_estimate(workload: &Workload, measured: Option<Duration>, options: &cli::Options) {
+    let Some(profile) = &options.estimate else { return };
+    let estimate = match profile.estimate(workload) {
+        Ok(estimate) => estimate,
+        Err(e) => return eprintln!("can't estimate: {}", e),
+    };
+    println!("estimated:");
+    match &options.table {
+        Some(style) => print!("{}", estimate.phase_table().render(style)),
+        None => print!("{}", estimate),
+    }
+    if let Some(measured) = measured {
+        println!("measured / estimated: {:.2}", measured.as_secs_f64() / estimate.total.as_secs_f64());
+    }
+}
+
+// with --unit g1-exps, switches the tables to multiples of `g1_exp`, the time of one G1 exp
+fn use_g1_exp_units(options: &mut cli::Options, g1_exp: Duration) {
+    if let Some(style) = options.table.as_mut().filter(|_| options.g1_exp_units) {
+        eprintln!("1 G1 exp: {:?}", g1_exp);
+        style.unit = Unit::G1Exps(g1_exp);
+    }
+}
+
+// the time of one G1 exp from a profile's costs
+fn profile_g1_exp(profile: &CostProfile) -> Duration {
+    profile.op_time(&Op::new(OpKind::G1Exps, 1, 1)).unwrap_or_else(|e| fail(format!("--unit g1-exps: {}", e)))
+}
+
+// expands the workloads a command would simulate and prints their ops, without running anything
+fn dry_run(options: &cli::Options, workload: Option<Workload>) {
+    let print_sweep = |title: &str, preset: &str, axes: &[String]| {
+        let (param_names, workloads) = sweep_workloads(preset, axes, options);
+        for (params, workload, communication) in workloads {
+            let point : Vec<String> = param_names.iter().map(|name| format!("{}={}", name, params.get(name).unwrap())).collect();
+            println!("{} {}", title, point.join(" "));
+            print!("{}{}", workload, communication);
+            print_estimate(&workload, None, options);
+        }
+    };
+    match &options.command {
+        cli::Command::Groth => {
+            let params = GrothParams::default();
+            println!("Groth16, {}", params);
+            let workload = params.workload();
+            print!("{}", workload);
+            print_estimate(&workload, None, options);
+        }
+        cli::Command::Run(_) => {
+            let workload = workload.unwrap();
+            print!("{}", workload);
+            print_estimate(&workload, None, options);
+        }
+        cli::Command::Sweep { preset, axes } | cli::Command::Backends { preset, axes } | cli::Command::Predict { preset, axes, .. }
+            | cli::Command::Project { preset, axes, .. } | cli::Command::Ops { preset, axes } | cli::Command::Budget { preset, axes, .. } => print_sweep(preset, preset, axes),
+        cli::Command::Dkg { scheme, axes } => print_sweep(&format!("DKG over {},", scheme), &dkg_preset(scheme, axes), axes),
+        cli::Command::Async { preset, axes, .. } => print_sweep(preset, preset, axes),
+        cli::Command::Weighted { preset, weights, axes } => print_sweep(&format!("weighted {},", preset), preset, &weighted_axes(weights, axes)),
+        cli::Command::Compare { a, b } => {
+            for (preset, axes) in [a, b] {
+                print_sweep(preset, preset, axes);
+            }
+        }
+        cli::Command::Pvss { n, t } => {
+            println!("PVSS, n={}, t={}", n, t);
+            let params = Params::new().set("n", *n as i64).set("t", *t as i64);
+            let protocol = presets::pvss_protocol();
+            let workload = protocol.evaluate(&params).unwrap();
+            print!("{}{}", workload, protocol.communication(&params).unwrap());
+            print_estimate(&workload, None, options);
+        }
+        cli::Command::Reconstruct { .. } | cli::Command::MsmThreads { .. } | cli::Command::TuneMsm { .. } | cli::Command::Pipeline { .. } | cli::Command::Calibrate | cli::Command::Profile | cli::Command::Query(_) | cli::Command::List | cli::Command::Merge(_) =>
+            fail("--dry-run only applies to commands that simulate a workload"),
+    }
+}
+
+// the Groth preset, with its phases reported separately
+fn simulate_groth(params: &GrothParams, rng: &mut impl RngCore, options: &cli::Options, results: &mut Baseline) {
+    println!("Groth16, {}", params);
+    let workload = params.try_workload().unwrap_or_else(|e| fail(e));
+    let mut sim = GroupOpsSimulation::new(&mut *rng);
+    add_workload(sim.setup(options.setup), &workload);
+    let total = run(&mut sim, options, results, "");
+    print_estimate(&workload, Some(total), options);
+}
+
+// --pin, and whether the clock of the cores will hold still, before anything is measured
+fn prepare_cores(options: &cli::Options) {
+    if !options.pin.is_empty() {
+        parallel::pin(&options.pin).unwrap_or_else(|e| fail(format!("--pin: {}", e)));
+    }
+    let warnings = machine::frequency_warnings(&options.pin);
+    for warning in &warnings {
+        eprintln!("{}: {}", if options.strict { "error" } else { "warning" }, warning);
+    }
+    if options.strict && !warnings.is_empty() {
+        fail("the timings would be unstable (run without --strict to measure anyway)");
+    }
 }
 
 fn main() {
-    simulate_groth(1024, 16, 660, 16);
+    let mut options = cli::Options::from_env();
+    if let Some(style) = options.table.as_mut().filter(|_| options.cycle_units) {
+        let hz = machine::tsc_frequency().unwrap_or_else(|| fail("--unit cycles needs the time-stamp counter of x86-64 (give the frequency instead, e.g. cycles@3.5GHz)"));
+        eprintln!("time-stamp counter: {:.3} GHz", hz as f64 / 1e9);
+        style.unit = Unit::Cycles(hz);
+    }
+    // load the workload first so a broken file fails before anything is printed
+    let workload = match &options.command {
+        cli::Command::Run(path) => Some(config::load(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)))),
+        _ => None,
+    };
+    if options.dry_run {
+        if options.g1_exp_units {
+            let g1_exp = profile_g1_exp(options.estimate.as_ref().unwrap_or_else(|| fail("--unit g1-exps with --dry-run needs --estimate")));
+            use_g1_exp_units(&mut options, g1_exp);
+        }
+        return dry_run(&options, workload);
+    }
+    // neither listings, op counts nor predictions execute anything, so need no inputs
+    if let cli::Command::List = options.command {
+        return list_presets();
+    }
+    if let cli::Command::Merge(paths) = &options.command {
+        return merge_profiles(paths, &options);
+    }
+    if let cli::Command::Query(filter) = &options.command {
+        return query(filter, &options);
+    }
+    if let cli::Command::Ops { preset, axes } = &options.command {
+        return export_op_counts(preset, axes, &options);
+    }
+    if let cli::Command::Predict { profile: path, preset, axes } = &options.command {
+        let (preset, axes) = (preset.clone(), axes.clone());
+        let profile = CostProfile::load(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        use_g1_exp_units(&mut options, profile_g1_exp(&profile));
+        return predict(&profile, &preset, &axes, &options);
+    }
+    if let cli::Command::Project { profile: path, preset, axes } = &options.command {
+        let (preset, axes) = (preset.clone(), axes.clone());
+        let profile = CostProfile::load(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        use_g1_exp_units(&mut options, profile_g1_exp(&profile));
+        return project(&profile, &preset, &axes, &options);
+    }
+    prepare_cores(&options);
+    // print the seed even when it was picked at random, so any run can be reproduced
+    // a replay needs the inputs the schedule was recorded with
+    // and test vectors can only be checked on those they were computed from
+    let replayed_seed = options.replay.as_ref().and_then(|schedule| schedule.seed)
+        .or_else(|| options.check_vectors.as_ref().and_then(|vectors| vectors.seed));
+    if replayed_seed.is_some() && options.seed.is_some() && replayed_seed != options.seed {
+        eprintln!("--seed overridden by the seed of the replayed schedule or the test vectors");
+    }
+    let seed = replayed_seed.or(options.seed).unwrap_or_else(|| thread_rng().next_u64());
+    options.seed = Some(seed);
+    // tables go to stdout alone, ready to paste
+    if options.table.is_some() {
+        eprintln!("seed: {}", seed);
+    } else {
+        println!("seed: {}", seed);
+    }
+    if options.instructions {
+        return count_instructions(seed);
+    }
+    if options.g1_exp_units {
+        let calibration = Calibration { kinds: vec![(OpKind::G1Exps, vec![])], ..Calibration::default() };
+        use_g1_exp_units(&mut options, profile_g1_exp(&calibration.run(seed, |_, _, _| {})));
+    }
+    // the commands whose runs stop between items, and report what completed
+    if matches!(options.command, cli::Command::Groth | cli::Command::Run(_) | cli::Command::Sweep { .. } | cli::Command::Pvss { .. }
+        | cli::Command::Dkg { .. } | cli::Command::Async { .. } | cli::Command::Weighted { .. } | cli::Command::Compare { .. }) {
+        interrupt::install();
+    }
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut results = Baseline::new();
+    match &options.command {
+        cli::Command::Groth => {
+            simulate_groth(&GrothParams::default(), &mut rng, &options, &mut results);
+        }
+        cli::Command::Run(_) => {
+            let workload = workload.unwrap();
+            let mut sim = GroupOpsSimulation::new(&mut rng);
+            add_workload(sim.setup(options.setup), &workload);
+            let total = run(&mut sim, &options, &mut results, "");
+            print_estimate(&workload, Some(total), &options);
+        }
+        cli::Command::Sweep { preset, axes } => sweep(preset, axes, &mut rng, &options, &mut results),
+        cli::Command::Pvss { n, t } => pvss(*n, *t, &mut rng, &options, &mut results),
+        cli::Command::Dkg { scheme, axes } => simulate_dkg(scheme, axes, &mut rng, &options, &mut results),
+        cli::Command::Async { preset, delays, axes } => simulate_async(preset, delays, axes, &mut rng, &options, &mut results),
+        cli::Command::Weighted { preset, weights, axes } => simulate_weighted(preset, weights, axes, &mut rng, &options, &mut results),
+        cli::Command::Reconstruct { weights, t, samples } => print!("{}", weighted::sample_reconstructions(weights, *t, *samples, &mut rng)),
+        cli::Command::Compare { a, b } => compare(a, b, &mut rng, &options, &mut results),
+        cli::Command::Backends { preset, axes } => backends(preset, axes, seed, &options),
+        cli::Command::MsmThreads { size, max_threads } => print!("{}", scaling::msm_thread_scaling(*size, *max_threads, options.reps, &mut rng)),
+        cli::Command::TuneMsm { sizes } => {
+            for &size in sizes {
+                print!("{}", pippenger::tune_window(size, options.reps, &mut rng));
+            }
+        }
+        cli::Command::Pipeline { protocol, size, invalid_percent } =>
+            print!("{}", pipeline::pipelined_verification(*protocol, *size, *invalid_percent, options.reps, &mut rng)),
+        cli::Command::Budget { preset, budget, axes } => explore_budget(preset, *budget, axes, &mut rng, &options),
+        cli::Command::Calibrate => calibrate(seed, &options),
+        cli::Command::Profile => profile(seed, &options),
+        cli::Command::Predict { .. } | cli::Command::Project { .. } | cli::Command::Ops { .. } | cli::Command::Query(_) | cli::Command::List | cli::Command::Merge(_) => unreachable!(),
+    }
+    if let Some(name) = &options.save_baseline {
+        let path = Baseline::path(name);
+        results.save(&path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        println!("saved baseline to {}", path.display());
+    }
+    if let Some(dir) = &options.export_criterion {
+        let baseline = options.save_baseline.as_deref().unwrap_or("new");
+        let written = export::write_criterion(&results, dir, baseline).unwrap_or_else(|e| fail(format!("{}: {}", dir.display(), e)));
+        println!("wrote {} Criterion benchmarks to {} (baseline {})", written, dir.display(), baseline);
+    }
+    if let Some(path) = &options.export_bencher {
+        std::fs::write(path, export::bencher(&results)).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        println!("wrote {} benchmarks to {}", results.summaries.len(), path.display());
+    }
+    if !matches!(options.command, cli::Command::Calibrate | cli::Command::Profile) {
+        export_latex(&latex::phase_macros(&results, &options.table.unwrap_or_default()), &options);
+    }
+    if let Some(name) = &options.baseline {
+        let path = Baseline::path(name);
+        let baseline = Baseline::load(&path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+        let comparison = baseline.compare(&results, options.threshold);
+        print!("{}", comparison);
+        if comparison.regressions().next().is_some() {
+            process::exit(1);
+        }
+    }
+    if interrupt::requested() {
+        eprintln!("interrupted: the results above are partial");
+        process::exit(130);
+    }
 }
@@ -0,0 +1,110 @@
+//! The profiles of several machines (`pvss-sim profile` on each) merged into one table of
+//! per-op costs, a column per machine, for cross-platform comparisons. Every machine after the
+//! first also gets a column of its costs relative to the first's, or, normalized, all costs are
+//! in G1 exps of their own machine, which factors out the clock and leaves the differences in
+//! how the ops scale.
+
+use crate::cost_model::{Calibration, Cost};
+use crate::machine::MachineProfile;
+use crate::render::{Cell, Style, Table, Totals};
+use crate::workload::{Op, OpKind};
+
+use std::fmt;
+
+
+/// Named profiles, the first the reference of the relative columns.
+#[derive(Clone, Debug)]
+pub struct MachineComparison {
+    pub profiles: Vec<(String, MachineProfile)>,
+    /// costs in G1 exps of their machine rather than in time
+    pub normalized: bool,
+}
+
+impl MachineComparison {
+    pub fn new(profiles: Vec<(String, MachineProfile)>, normalized: bool) -> Self {
+        Self { profiles, normalized }
+    }
+
+    /// Every op and size any profile has a cost for: the calibrated sizes, and those of the
+    /// default calibration for multi-exp models, in the order of the first profile to have them.
+    pub fn ops(&self) -> Vec<(OpKind, usize)> {
+        let mut ops = Vec::new();
+        let calibration = Calibration::default();
+        for (_, profile) in &self.profiles {
+            for (kind, cost) in &profile.costs.costs {
+                let sizes = match cost {
+                    Cost::Fixed(_) => vec![1],
+                    Cost::BySize(points) => points.iter().map(|&(size, _)| size).collect(),
+                    Cost::Msm { .. } => calibration.kinds.iter()
+                        .find(|(k, _)| k == kind)
+                        .map_or_else(|| vec![1024], |(_, sizes)| sizes.clone()),
+                };
+                for size in sizes {
+                    if !ops.contains(&(*kind, size)) {
+                        ops.push((*kind, size));
+                    }
+                }
+            }
+        }
+        ops
+    }
+
+    // one op's cost on every machine, in nanoseconds or G1 exps
+    fn costs(&self, kind: OpKind, size: usize) -> Vec<Option<f64>> {
+        let op = Op::new(kind, 1, if kind.is_sized() { size } else { 1 });
+        self.profiles.iter()
+            .map(|(_, profile)| {
+                let ns = profile.costs.op_time(&op).ok()?.as_secs_f64() * 1e9;
+                if !self.normalized {
+                    return Some(ns);
+                }
+                let g1_exp = profile.costs.op_time(&Op::new(OpKind::G1Exps, 1, 1)).ok()?.as_secs_f64() * 1e9;
+                Some(ns / g1_exp)
+            })
+            .collect()
+    }
+
+    pub fn to_table(&self) -> Table {
+        let (first, _) = &self.profiles[0];
+        let mut header = vec!["op".to_string()];
+        header.extend(self.profiles.iter().map(|(name, _)| name.clone()));
+        if !self.normalized {
+            header.extend(self.profiles[1..].iter().map(|(name, _)| format!("{}/{}", name, first)));
+        }
+        let rows = self.ops().into_iter()
+            .map(|(kind, size)| {
+                let name = if kind.is_sized() { format!("{} ({})", kind.name(), size) } else { kind.name().to_string() };
+                let costs = self.costs(kind, size);
+                let mut row = vec![Cell::Text(name)];
+                row.extend(costs.iter().map(|cost| match cost {
+                    None => Cell::Empty,
+                    Some(g1_exps) if self.normalized => Cell::Text(format!("{:.3}", g1_exps)),
+                    Some(ns) => Cell::Time(std::time::Duration::from_secs_f64(ns / 1e9)),
+                }));
+                if !self.normalized {
+                    row.extend(costs[1..].iter().map(|cost| match (cost, costs[0]) {
+                        (Some(cost), Some(reference)) => Cell::Text(format!("{:.2}", cost / reference)),
+                        _ => Cell::Empty,
+                    }));
+                }
+                row
+            })
+            .collect();
+        Table { header, rows, totals: Totals::None }
+    }
+
+    /// Which machine every column is.
+    pub fn legend(&self) -> String {
+        self.profiles.iter().map(|(name, profile)| format!("{}: {} (profile {})\n", name, profile.machine, profile.id())).collect()
+    }
+}
+
+impl fmt::Display for MachineComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.legend())?;
+        if self.normalized {
+            writeln!(f, "costs in G1 exps of each machine:")?;
+        }
+        write!(f, "{}", self.to_table().render(&Style::default()))
+    }
+}
@@ -0,0 +1,294 @@
+//! Merkle trees over serialized group elements, as a transcript is committed to for light
+//! clients that sample a few of its shares: the leaves are the encodings of the share
+//! ciphertexts, every node is the hash of up to `arity` children, and a proof of one leaf is the
+//! siblings of the nodes on its path to the root.
+//!
+//! Leaves are hashed with a 0 byte in front and inner nodes with a 1 byte, so that no leaf can
+//! pass for a node. A node whose level doesn't fill its last group of `arity` has fewer
+//! children. A wider tree is shallower, with fewer but longer hashes on a path and longer
+//! proofs.
+
+use crate::communication::Element;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::hash::{blake2s, sha256};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use rand::RngCore;
+use std::fmt;
+use std::hint::black_box;
+
+
+pub type Digest = [u8; 32];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MerkleHash {
+    Sha256,
+    Blake2s,
+}
+
+impl MerkleHash {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sha256" => Some(MerkleHash::Sha256),
+            "blake2s" => Some(MerkleHash::Blake2s),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MerkleHash::Sha256 => "sha256",
+            MerkleHash::Blake2s => "blake2s",
+        }
+    }
+
+    pub fn hash(&self, msg: &[u8]) -> Digest {
+        match self {
+            MerkleHash::Sha256 => sha256(msg),
+            MerkleHash::Blake2s => blake2s(msg),
+        }
+    }
+
+    fn leaf(&self, leaf: &[u8]) -> Digest {
+        self.hash(&[&[0], leaf].concat())
+    }
+
+    fn node(&self, children: &[Digest]) -> Digest {
+        self.hash(&[&[1], children.concat().as_slice()].concat())
+    }
+}
+
+impl fmt::Display for MerkleHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MerkleHash::Sha256 => "SHA-256",
+            MerkleHash::Blake2s => "BLAKE2s",
+        })
+    }
+}
+
+/// All levels of a tree, from the leaves' digests to the root.
+pub struct MerkleTree {
+    arity: usize,
+    levels: Vec<Vec<Digest>>,
+}
+
+/// The siblings of the path from a leaf to the root, level by level, and the leaf's index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub index: usize,
+    pub siblings: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    pub fn new(hash: MerkleHash, arity: usize, leaves: &[&[u8]]) -> Self {
+        assert!(arity >= 2, "nodes need at least two children");
+        assert!(!leaves.is_empty(), "a tree needs at least one leaf");
+        let mut levels = vec![leaves.iter().map(|leaf| hash.leaf(leaf)).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels.last().unwrap().chunks(arity).map(|children| hash.node(children)).collect();
+            levels.push(level);
+        }
+        Self { arity, levels }
+    }
+
+    pub fn root(&self) -> Digest {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn proof(&self, index: usize) -> MerkleProof {
+        let mut position = index;
+        let siblings = self.levels[..self.levels.len() - 1].iter()
+            .map(|level| {
+                let group = position / self.arity * self.arity;
+                let siblings = level[group..(group + self.arity).min(level.len())].iter().enumerate()
+                    .filter(|(i, _)| group + i != position)
+                    .map(|(_, digest)| *digest)
+                    .collect();
+                position /= self.arity;
+                siblings
+            })
+            .collect();
+        MerkleProof { index, siblings }
+    }
+}
+
+/// Whether `proof` shows that `leaf` is at its index under `root`.
+pub fn verify(hash: MerkleHash, arity: usize, root: &Digest, leaf: &[u8], proof: &MerkleProof) -> bool {
+    let mut position = proof.index;
+    let mut digest = hash.leaf(leaf);
+    for siblings in &proof.siblings {
+        let offset = position % arity;
+        if offset > siblings.len() {
+            return false;
+        }
+        let mut children = siblings.clone();
+        children.insert(offset, digest);
+        digest = hash.node(&children);
+        position /= arity;
+    }
+    position == 0 && digest == *root
+}
+
+// the root computed top-down, to validate the level-by-level construction against: the
+// subtree of `leaves` has room for `span` of them, a power of the arity
+fn naive_root(hash: MerkleHash, arity: usize, leaves: &[&[u8]], span: usize) -> Digest {
+    match span {
+        1 => hash.leaf(leaves[0]),
+        _ => hash.node(&leaves.chunks(span / arity).map(|chunk| naive_root(hash, arity, chunk, span / arity)).collect::<Vec<_>>()),
+    }
+}
+
+// random stand-ins for the encodings of `size` elements, as hashing doesn't depend on them
+fn random_leaves(rng: &mut impl RngCore, size: usize, element: Element) -> Vec<Vec<u8>> {
+    (0..size)
+        .map(|_| {
+            let mut leaf = vec![0; element.size()];
+            rng.fill_bytes(&mut leaf);
+            leaf
+        })
+        .collect()
+}
+
+struct MerkleTreeBuilds {
+    hash: MerkleHash,
+    arity: usize,
+    element: Element,
+    trees: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTreeBuilds {
+    fn build(&self, i: usize) -> MerkleTree {
+        let leaves : Vec<&[u8]> = self.trees[i].iter().map(Vec::as_slice).collect();
+        MerkleTree::new(self.hash, self.arity, &leaves)
+    }
+}
+
+impl GroupOpsSimulationItem for MerkleTreeBuilds {
+    fn description(&self) -> String {
+        format!("{} Merkle trees of arity {} over encodings of {}: {} of {} leaves",
+            self.hash, self.arity, self.element.name(), self.trees.len(), self.trees.first().map_or(0, Vec::len))
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.trees.len(), self.trees.first().map_or(0, Vec::len))
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Digest>>((0..self.trees.len()).map(|i| self.build(i).root()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.trees.len(), |i| self.build(i).root()));
+    }
+
+    fn ops(&self) -> usize {
+        self.trees.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.build(i).root());
+    }
+
+    fn notes(&self) -> Option<String> {
+        let tree = self.build(0);
+        Some(format!("{} hashes of {} levels", tree.levels.iter().map(Vec::len).sum::<usize>(), tree.levels.len()))
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.trees.iter().enumerate().all(|(i, leaves)| {
+            let leaves : Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+            let span = std::iter::successors(Some(1), |span| Some(span * self.arity)).find(|&span| span >= leaves.len()).unwrap();
+            self.build(i).root() == naive_root(self.hash, self.arity, &leaves, span)
+        }))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some((0..self.trees.len()).flat_map(|i| self.build(i).root()).collect())
+    }
+}
+
+struct MerkleProofVerifications {
+    hash: MerkleHash,
+    arity: usize,
+    element: Element,
+    size: usize,
+    root: Digest,
+    // the sampled leaves with their proofs
+    samples: Vec<(Vec<u8>, MerkleProof)>,
+}
+
+impl MerkleProofVerifications {
+    fn verify(&self, i: usize) -> bool {
+        let (leaf, proof) = &self.samples[i];
+        verify(self.hash, self.arity, &self.root, leaf, proof)
+    }
+}
+
+impl GroupOpsSimulationItem for MerkleProofVerifications {
+    fn description(&self) -> String {
+        format!("{} Merkle proof verifications (arity {}, {} leaves): {} of encodings of {}",
+            self.hash, self.arity, self.size, self.samples.len(), self.element.name())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.samples.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.samples.len()).map(|i| self.verify(i)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.samples.len(), |i| self.verify(i)));
+    }
+
+    fn ops(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+
+    fn notes(&self) -> Option<String> {
+        let proof = &self.samples.first()?.1;
+        Some(format!("{} levels, {} sibling digests per proof", proof.siblings.len(), proof.siblings.iter().map(Vec::len).sum::<usize>()))
+    }
+
+    // every proof holds, and none for a changed leaf
+    fn validate(&self) -> Option<bool> {
+        Some(self.samples.iter().all(|(leaf, proof)| {
+            let mut forged = leaf.clone();
+            forged[0] ^= 1;
+            verify(self.hash, self.arity, &self.root, leaf, proof) && !verify(self.hash, self.arity, &self.root, &forged, proof)
+        }))
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` Merkle trees of the given arity over `size` encodings of `element`s each.
+    pub fn merkle_trees(&mut self, hash: MerkleHash, arity: usize, element: Element, num: usize, size: usize) -> &mut Self {
+        assert!(arity >= 2 && size > 0, "trees need an arity of at least 2 and at least one leaf");
+        let trees = (0..num).map(|_| random_leaves(self.rng(), size, element)).collect();
+        self.push_item(MerkleTreeBuilds { hash, arity, element, trees })
+    }
+
+    /// Verifications of the proofs of `num` random leaves of a tree over `size` encodings of
+    /// `element`s, as a light client sampling shares checks them.
+    pub fn merkle_proof_verifications(&mut self, hash: MerkleHash, arity: usize, element: Element, num: usize, size: usize) -> &mut Self {
+        assert!(arity >= 2 && size > 0, "trees need an arity of at least 2 and at least one leaf");
+        let leaves = random_leaves(self.rng(), size, element);
+        let tree = MerkleTree::new(hash, arity, &leaves.iter().map(Vec::as_slice).collect::<Vec<_>>());
+        let samples = (0..num)
+            .map(|_| {
+                let index = self.rng().next_u64() as usize % size;
+                (leaves[index].clone(), tree.proof(index))
+            })
+            .collect();
+        self.push_item(MerkleProofVerifications { hash, arity, element, size, root: tree.root(), samples })
+    }
+}
@@ -0,0 +1,149 @@
+//! A simple network model, to turn the compute of a round and the bytes it sends into an
+//! estimated wall-clock round time.
+
+use crate::communication::Communication;
+use crate::party::RoundReport;
+
+use std::fmt;
+use std::time::Duration;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    /// a broadcast channel: the sender uploads each message once
+    Broadcast,
+    /// the sender uploads a copy of each message to every other party
+    PointToPoint,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NetworkModel {
+    pub rtt: Duration,
+    /// the senders' upload bandwidth, in bits per second
+    pub bandwidth: f64,
+    pub delivery: Delivery,
+}
+
+impl NetworkModel {
+    /// Parses `BANDWIDTH,RTT[,broadcast|p2p]`, e.g. `100Mbps,50ms` or `1Gbps,200ms,p2p`, with
+    /// bandwidths in bps, kbps, Mbps or Gbps and round-trip times in us, ms or s. Broadcast is
+    /// the default.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',').map(str::trim);
+        let (bandwidth, rtt) = match (parts.next(), parts.next()) {
+            (Some(bandwidth), Some(rtt)) => (bandwidth, rtt),
+            _ => return Err(format!("expected BANDWIDTH,RTT[,broadcast|p2p], got `{}`", spec)),
+        };
+        let delivery = match parts.next() {
+            None | Some("broadcast") => Delivery::Broadcast,
+            Some("p2p") => Delivery::PointToPoint,
+            Some(other) => return Err(format!("unknown delivery `{}`, expected broadcast or p2p", other)),
+        };
+        if parts.next().is_some() {
+            return Err(format!("too many fields in `{}`", spec));
+        }
+        let number = |s: &str, units: &[(&str, f64)]| {
+            units.iter()
+                .find_map(|(unit, scale)| s.strip_suffix(unit).map(|value| (value, scale)))
+                .and_then(|(value, scale)| value.parse::<f64>().ok().filter(|v| *v >= 0.0).map(|v| v * scale))
+        };
+        let bandwidth = number(bandwidth, &[("Gbps", 1e9), ("Mbps", 1e6), ("kbps", 1e3), ("bps", 1.0)])
+            .filter(|&b| b > 0.0)
+            .ok_or_else(|| format!("invalid bandwidth `{}`", bandwidth))?;
+        let rtt = parse_duration(rtt).ok_or_else(|| format!("invalid round-trip time `{}`", rtt))?;
+        Ok(Self { rtt, bandwidth, delivery })
+    }
+
+    /// Time until `bytes` sent by one party have reached `recipients` others: half a round trip
+    /// plus the upload.
+    pub fn transfer_time(&self, bytes: usize, recipients: usize) -> Duration {
+        let copies = match self.delivery {
+            Delivery::Broadcast => 1,
+            Delivery::PointToPoint => recipients,
+        };
+        self.rtt / 2 + Duration::from_secs_f64((bytes * copies) as f64 * 8.0 / self.bandwidth)
+    }
+
+    /// The estimated time of every step of `round`: its slowest role's compute, then sending
+    /// the messages of its roles (matched to the roles by phase name) to the other `parties - 1`
+    /// parties. Steps that send nothing take no network time.
+    pub fn round_time(&self, round: &RoundReport, communication: &Communication, parties: usize) -> RoundTime {
+        let sent = communication.phase_totals();
+        let steps = round.steps.iter()
+            .map(|step| {
+                let name = step.iter().map(|role| role.name.as_str()).collect::<Vec<_>>().join(" + ");
+                let compute = step.iter().map(|role| role.latency()).max().unwrap_or_default();
+                let network = step.iter()
+                    .filter_map(|role| sent.iter().find(|(phase, _)| *phase == role.name))
+                    .map(|&(_, bytes)| self.transfer_time(bytes, parties.saturating_sub(1)))
+                    .max()
+                    .unwrap_or_default();
+                StepTime { name, compute, network }
+            })
+            .collect();
+        RoundTime { network: *self, parties, steps }
+    }
+
+    /// Same as `round_time` for a single party running `phases` one after the other, e.g. the
+    /// phase totals of an estimate: each phase's compute, then sending its messages.
+    pub fn phase_times(&self, phases: &[(String, Duration)], communication: &Communication, parties: usize) -> RoundTime {
+        let sent = communication.phase_totals();
+        let steps = phases.iter()
+            .map(|(name, compute)| {
+                let network = sent.iter()
+                    .find(|(phase, _)| phase == name)
+                    .map_or(Duration::ZERO, |&(_, bytes)| self.transfer_time(bytes, parties.saturating_sub(1)));
+                StepTime { name: name.clone(), compute: *compute, network }
+            })
+            .collect();
+        RoundTime { network: *self, parties, steps }
+    }
+}
+
+/// A non-negative time in us, ms or s, e.g. `50ms` or `0.2s`.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    [("us", 1e-6), ("ms", 1e-3), ("s", 1.0)].iter()
+        .find_map(|(unit, scale)| text.strip_suffix(unit).map(|value| (value, scale)))
+        .and_then(|(value, scale)| value.parse::<f64>().ok().filter(|v| *v >= 0.0 && v.is_finite()).map(|v| Duration::from_secs_f64(v * scale)))
+}
+
+impl fmt::Display for NetworkModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let delivery = match self.delivery {
+            Delivery::Broadcast => "broadcast",
+            Delivery::PointToPoint => "point-to-point",
+        };
+        write!(f, "{} Mbps, {:?} RTT, {}", self.bandwidth / 1e6, self.rtt, delivery)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StepTime {
+    pub name: String,
+    pub compute: Duration,
+    pub network: Duration,
+}
+
+/// Estimated wall-clock time of a round under a network model.
+#[derive(Clone, Debug)]
+pub struct RoundTime {
+    pub network: NetworkModel,
+    pub parties: usize,
+    pub steps: Vec<StepTime>,
+}
+
+impl RoundTime {
+    pub fn total(&self) -> Duration {
+        self.steps.iter().map(|step| step.compute + step.network).sum()
+    }
+}
+
+impl fmt::Display for RoundTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "estimated round time:")?;
+        for step in &self.steps {
+            writeln!(f, "  {}: {:.3?} ({:.3?} compute + {:.3?} network)", step.name, step.compute + step.network, step.compute, step.network)?;
+        }
+        writeln!(f, "  round: {:.3?} at {}, n={}", self.total(), self.network, self.parties)
+    }
+}
@@ -0,0 +1,163 @@
+//! A workload's op counts phase by phase, with the ops of each kind summed up and their sizes
+//! listed, exported as JSON so other tools can consume exact counts instead of re-deriving the
+//! formulas:
+//!
+//! ```json
+//! {
+//!   "preset": "groth",
+//!   "points": [
+//!     {
+//!       "params": { "k": 16, "l": 16, "n": 1024, "t": 660 },
+//!       "phases": [
+//!         ...,
+//!         {
+//!           "phase": "Verifier",
+//!           "ops": [
+//!             { "op": "g1_exps", "num": 1026 },
+//!             { "op": "g1_multi_exps", "num": 1032, "sizes": [[2, 1], [1025, 2], [17, 1025], [262161, 1], [1026, 1], [16, 2]] },
+//!             { "op": "g2_exps", "num": 1 },
+//!             { "op": "g2_multi_exps", "num": 2, "sizes": [[661, 1], [16, 1]] },
+//!             { "op": "pairing_equality_checks", "rhs_size": 2, "num": 1, "sizes": [[1, 1]] }
+//!           ]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Kinds with parameters carry them under the keys workload files use; `sizes` lists how many
+//! ops of the kind have each size, for sized kinds only. Ops outside any phase are in a phase
+//! `null`.
+
+use crate::json::Value;
+use crate::protocol::Params;
+use crate::workload::{OpKind, Workload};
+
+
+/// All ops of one kind in a phase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KindCount {
+    pub kind: OpKind,
+    pub num: usize,
+    /// (size, number of ops of that size), in the order the sizes first appear; empty for
+    /// unsized kinds
+    pub sizes: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseCounts {
+    pub phase: Option<String>,
+    /// in the order the kinds first appear in the phase
+    pub kinds: Vec<KindCount>,
+}
+
+/// The op counts of a workload, phases in the order they first appear.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub phases: Vec<PhaseCounts>,
+}
+
+impl OpCounts {
+    pub fn new(workload: &Workload) -> Self {
+        let mut counts = Self::default();
+        for op in &workload.ops {
+            let phase = match counts.phases.iter().position(|phase| phase.phase == op.phase) {
+                Some(i) => &mut counts.phases[i],
+                None => {
+                    counts.phases.push(PhaseCounts { phase: op.phase.clone(), kinds: Vec::new() });
+                    counts.phases.last_mut().unwrap()
+                }
+            };
+            let kind = match phase.kinds.iter().position(|count| count.kind == op.kind) {
+                Some(i) => &mut phase.kinds[i],
+                None => {
+                    phase.kinds.push(KindCount { kind: op.kind, num: 0, sizes: Vec::new() });
+                    phase.kinds.last_mut().unwrap()
+                }
+            };
+            kind.num += op.num;
+            if op.kind.is_sized() {
+                match kind.sizes.iter_mut().find(|(size, _)| *size == op.size) {
+                    Some((_, num)) => *num += op.num,
+                    None => kind.sizes.push((op.size, op.num)),
+                }
+            }
+        }
+        counts
+    }
+
+    pub fn phase(&self, name: &str) -> Option<&PhaseCounts> {
+        self.phases.iter().find(|phase| phase.phase.as_deref() == Some(name))
+    }
+
+    fn to_json(&self) -> Value {
+        Value::Array(self.phases.iter()
+            .map(|phase| Value::Object(vec![
+                ("phase".to_string(), phase.phase.clone().map_or(Value::Null, Value::String)),
+                ("ops".to_string(), Value::Array(phase.kinds.iter().map(KindCount::to_json).collect())),
+            ]))
+            .collect())
+    }
+}
+
+impl KindCount {
+    fn to_json(&self) -> Value {
+        let number = |n: usize| Value::Number(n as f64);
+        let mut pairs = vec![("op".to_string(), Value::String(self.kind.name().to_string()))];
+        pairs.extend(kind_params(&self.kind).into_iter().map(|(key, value)| (key.to_string(), value)));
+        pairs.push(("num".to_string(), number(self.num)));
+        if self.kind.is_sized() {
+            let sizes = self.sizes.iter().map(|&(size, num)| Value::Array(vec![number(size), number(num)])).collect();
+            pairs.push(("sizes".to_string(), Value::Array(sizes)));
+        }
+        Value::Object(pairs)
+    }
+}
+
+// the parameters of a kind beyond its count and size, as `config` reads them
+fn kind_params(kind: &OpKind) -> Vec<(&'static str, Value)> {
+    let number = |n: usize| Value::Number(n as f64);
+    match *kind {
+        OpKind::PairingEqualityChecks { rhs_size } => vec![("rhs_size", number(rhs_size))],
+        OpKind::PolyEvals { num_points } => vec![("num_points", number(num_points))],
+        OpKind::SerializeG1 { compressed } | OpKind::SerializeG2 { compressed }
+            | OpKind::DeserializeG1 { compressed } | OpKind::DeserializeG2 { compressed } => vec![("compressed", Value::Boolean(compressed))],
+        OpKind::FixedBaseExps { window_bits } | OpKind::G2FixedBaseExps { window_bits }
+            | OpKind::FeldmanCommitments { window_bits } | OpKind::G2FeldmanCommitments { window_bits } => vec![("window_bits", number(window_bits))],
+        OpKind::G1ShortExps { bits } | OpKind::G2ShortExps { bits }
+            | OpKind::G1ShortMultiExps { bits } | OpKind::G2ShortMultiExps { bits } => vec![("bits", number(bits))],
+        OpKind::G1SparseMultiExps { binary_percent } | OpKind::G2SparseMultiExps { binary_percent } => vec![("binary_percent", number(binary_percent))],
+        OpKind::ReedSolomonEncodings { data_shares } | OpKind::ReedSolomonDecodings { data_shares } => vec![("data_shares", number(data_shares))],
+        OpKind::MerkleTrees { hash, arity, leaf } | OpKind::MerkleProofVerifications { hash, arity, leaf } =>
+            vec![("hash", Value::String(hash.name().to_string())), ("arity", number(arity)), ("leaf", Value::String(leaf.key().to_string()))],
+        OpKind::G1StreamingMultiExps { chunk_size } | OpKind::G2StreamingMultiExps { chunk_size } => vec![("chunk_size", number(chunk_size))],
+        OpKind::G1PippengerMultiExps(config) | OpKind::G2PippengerMultiExps(config) =>
+            vec![("window_bits", number(config.window_bits)), ("buckets", Value::String(config.buckets.name().to_string()))],
+        OpKind::PairingsConverting { to_affine } | OpKind::MultiPairingsConverting { to_affine } => vec![("to_affine", Value::Boolean(to_affine))],
+        OpKind::ElGamalEncryptions { shared_randomness } => vec![("shared_randomness", Value::Boolean(shared_randomness))],
+        OpKind::ChunkedElGamal { chunk_bits, chunks, proof_repetitions } =>
+            vec![("chunk_bits", number(chunk_bits)), ("chunks", number(chunks)), ("proof_repetitions", number(proof_repetitions))],
+        OpKind::MultiPairingsStatic { static_inputs } => vec![("static_inputs", number(static_inputs))],
+        OpKind::KzgMultiOpenings { points } => vec![("points", number(points))],
+        OpKind::KzgBatchVerifications { degree } => vec![("degree", number(degree))],
+        OpKind::SrsVerifications { batched } => vec![("batched", Value::Boolean(batched))],
+        OpKind::SigmaVerifications { protocol, batched, invalid_percent } =>
+            vec![("protocol", Value::String(protocol.name().to_string())), ("batched", Value::Boolean(batched)), ("invalid_percent", number(invalid_percent))],
+        _ => Vec::new(),
+    }
+}
+
+/// The counts of a preset at every point of a sweep, as in the module documentation.
+pub fn to_json(preset: &str, points: &[(Params, OpCounts)]) -> String {
+    let points = points.iter()
+        .map(|(params, counts)| Value::Object(vec![
+            ("params".to_string(), Value::Object(params.iter().map(|(name, value)| (name.to_string(), Value::Number(value as f64))).collect())),
+            ("phases".to_string(), counts.to_json()),
+        ]))
+        .collect();
+    Value::Object(vec![
+        ("preset".to_string(), Value::String(preset.to_string())),
+        ("points".to_string(), Value::Array(points)),
+    ]).to_pretty_string()
+}
@@ -0,0 +1,116 @@
+//! Products of pairings with exponents, `∏ e(A_i, B_i)^{c_i}`, as verifiers of aggregated
+//! transcripts compute them, with the two ways of getting there: folding every `c_i` into the
+//! G1 side first, `n` G1 exps and one multi-pairing of size `n`, or pairing first, `n` pairings
+//! with a final exponentiation each and a Gt multi-exp of size `n`.
+
+use crate::curve_ops::BatchToAffine as _;
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, MultiExp};
+use crate::pairing_utils::multi_pairing;
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::{pairing, G1Projective, G2Projective, Gt, Scalar};
+use ff::Field as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// `∏ e(A_i^{c_i}, B_i)`
+    FoldIntoG1,
+    /// `∏ e(A_i, B_i)^{c_i}`
+    PairThenExp,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 2] = [Strategy::FoldIntoG1, Strategy::PairThenExp];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::FoldIntoG1 => "exponents folded into G1",
+            Strategy::PairThenExp => "pairings, then a Gt multi-exp",
+        }
+    }
+}
+
+/// `∏ e(A_i, B_i)^{c_i}` with `strategy`.
+pub fn pairing_product(args_g1: &[G1Projective], args_g2: &[G2Projective], exponents: &[Scalar], strategy: Strategy) -> Gt {
+    assert!(args_g1.len() == args_g2.len() && args_g1.len() == exponents.len(), "need as many G2 points and exponents as G1 points");
+    match strategy {
+        Strategy::FoldIntoG1 => {
+            let folded : Vec<G1Projective> = args_g1.iter().zip(exponents).map(|(a, c)| a * c).collect();
+            multi_pairing(&folded, args_g2)
+        }
+        Strategy::PairThenExp => {
+            let (args_g1, args_g2) = (G1Projective::batch_to_affine(args_g1), G2Projective::batch_to_affine(args_g2));
+            let pairings : Vec<Gt> = args_g1.iter().zip(&args_g2).map(|(a, b)| pairing(a, b)).collect();
+            Gt::multi_exp(&pairings, exponents)
+        }
+    }
+}
+
+struct PairingProducts {
+    strategy: Strategy,
+    num: usize,
+    args_g1: Vec<G1Projective>,
+    args_g2: Vec<G2Projective>,
+    exponents: Vec<Scalar>,
+}
+
+impl PairingProducts {
+    fn product(&self, strategy: Strategy) -> Gt {
+        pairing_product(&self.args_g1, &self.args_g2, &self.exponents, strategy)
+    }
+}
+
+impl GroupOpsSimulationItem for PairingProducts {
+    fn description(&self) -> String {
+        format!("pairing products with exponents ({}): {} of size {}", self.strategy.name(), self.num, self.args_g1.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.args_g1.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.product(self.strategy));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.product(self.strategy)));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.product(self.strategy));
+    }
+
+    // both strategies compute the same product
+    fn validate(&self) -> Option<bool> {
+        Some(self.product(Strategy::FoldIntoG1) == self.product(Strategy::PairThenExp))
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` products of `size` pairings with exponents, over the same inputs once with every
+    /// `Strategy`, as one item each.
+    pub fn pairing_products_with_exponents(&mut self, num: usize, size: usize) -> &mut Self {
+        let args_g1 : Vec<G1Projective> = self.rng().points(size);
+        let args_g2 : Vec<G2Projective> = self.rng().points(size);
+        let exponents : Vec<Scalar> = (0..size).map(|_| Scalar::random(&mut *self.rng())).collect();
+        self.group(|sim| {
+            for strategy in Strategy::ALL {
+                sim.push_item(PairingProducts { strategy, num, args_g1: args_g1.clone(), args_g2: args_g2.clone(), exponents: exponents.clone() });
+            }
+        })
+    }
+}
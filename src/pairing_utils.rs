@@ -0,0 +1,43 @@
+//! Multi-pairings `prod_i e(a_i, b_i)`, computed as one multi-Miller loop and a single final
+//! exponentiation, for inputs in the forms the simulation and the backends hold them. The Miller
+//! loops are also available on their own, for batch verifiers that multiply several loops'
+//! results before sharing one final exponentiation.
+
+use crate::curve_ops::BatchToAffine;
+
+use blstrs::{Bls12, G1Affine, G1Projective, G2Prepared, G2Projective, Gt, MillerLoopResult};
+use pairing::{MillerLoopResult as _, MultiMillerLoop as _};
+
+
+/// The multi-Miller loop of affine G1 points with G2 points prepared ahead of time, e.g. public
+/// keys that are reused across many multi-pairings. Only allocates the list of pairs that
+/// blstrs' `multi_miller_loop` takes.
+pub fn multi_miller_loop_affine(lhs: &[G1Affine], rhs: &[G2Prepared]) -> MillerLoopResult {
+    assert_eq!(lhs.len(), rhs.len(), "need as many G2 points as G1 points");
+    let terms : Vec<(&G1Affine, &G2Prepared)> = lhs.iter().zip(rhs).collect();
+    Bls12::multi_miller_loop(&terms)
+}
+
+/// Same as `multi_miller_loop_affine`, converting the G1 points with one batch normalization.
+pub fn multi_miller_loop_prepared(lhs: &[G1Projective], rhs: &[G2Prepared]) -> MillerLoopResult {
+    multi_miller_loop_affine(&G1Projective::batch_to_affine(lhs), rhs)
+}
+
+/// Same as `multi_miller_loop_prepared`, preparing the G2 points first: affine conversion uses
+/// batch normalization on both sides, as an optimized verifier would.
+pub fn multi_miller_loop(lhs: &[G1Projective], rhs: &[G2Projective]) -> MillerLoopResult {
+    let rhs : Vec<G2Prepared> = G2Projective::batch_to_affine(rhs).into_iter().map(G2Prepared::from).collect();
+    multi_miller_loop_prepared(lhs, &rhs)
+}
+
+pub fn multi_pairing_affine(lhs: &[G1Affine], rhs: &[G2Prepared]) -> Gt {
+    multi_miller_loop_affine(lhs, rhs).final_exponentiation()
+}
+
+pub fn multi_pairing_prepared(lhs: &[G1Projective], rhs: &[G2Prepared]) -> Gt {
+    multi_miller_loop_prepared(lhs, rhs).final_exponentiation()
+}
+
+pub fn multi_pairing(lhs: &[G1Projective], rhs: &[G2Projective]) -> Gt {
+    multi_miller_loop(lhs, rhs).final_exponentiation()
+}
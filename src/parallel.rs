@@ -0,0 +1,91 @@
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+// the cores of `pin`, whose worker threads `parallel_map` pins one to each in turn
+static PINNED : OnceLock<Vec<usize>> = OnceLock::new();
+
+/// Number of threads the parallel mode uses by default.
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Computes `f(0), ..., f(len - 1)` on up to `threads` threads, each handling one contiguous
+/// chunk of indices. Results are returned in index order.
+pub(crate) fn parallel_map<U, F>(threads: usize, len: usize, f: F) -> Vec<U>
+where U : Send,
+      F : Fn(usize) -> U + Sync
+{
+    if threads <= 1 || len <= 1 {
+        return (0..len).map(f).collect();
+    }
+
+    let chunk_size = len.div_ceil(threads);
+    thread::scope(|scope| {
+        let handles : Vec<_> = (0..len)
+            .step_by(chunk_size)
+            .enumerate()
+            .map(|(worker, start)| {
+                let f = &f;
+                scope.spawn(move || {
+                    if let Some(cores) = PINNED.get() {
+                        // best effort: the cores were all accepted when the process was pinned
+                        let _ = set_affinity(&[cores[worker % cores.len()]]);
+                    }
+                    (start..(start + chunk_size).min(len)).map(f).collect::<Vec<U>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// CPU time consumed so far by all threads of this process, including blst's internal thread
+/// pool. `None` where the platform offers no such clock.
+pub fn process_cpu_time() -> Option<Duration> {
+    #[cfg(unix)]
+    {
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        // SAFETY: clock_gettime only writes to the timespec we pass in
+        let ret = unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+        if ret == 0 {
+            return Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32));
+        }
+    }
+    None
+}
+
+/// Restricts this thread, and every thread it starts from then on (blst's thread pool among
+/// them), to `cores`. The worker threads of `--threads` are each pinned to one of them in turn,
+/// so that with as many cores as threads none of them migrates. Only supported on Linux.
+pub fn pin(cores: &[usize]) -> std::io::Result<()> {
+    set_affinity(cores)?;
+    PINNED.set(cores.to_vec()).map_err(|_| std::io::Error::other("already pinned"))
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(cores: &[usize]) -> std::io::Result<()> {
+    // SAFETY: an all-zero cpu_set_t is the empty set
+    let mut set : libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core >= libc::CPU_SETSIZE as usize {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("no core {}", core)));
+        }
+        // SAFETY: the core is within the set's bounds
+        unsafe { libc::CPU_SET(core, &mut set) };
+    }
+    // SAFETY: sched_setaffinity only reads the set we pass in; pid 0 is the calling thread
+    let ret = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+    match ret {
+        0 => Ok(()),
+        _ if std::io::Error::last_os_error().raw_os_error() == Some(libc::EINVAL) => {
+            Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "none of the cores is online or allowed for this process"))
+        }
+        _ => Err(std::io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(_cores: &[usize]) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "pinning threads to cores is only supported on Linux"))
+}
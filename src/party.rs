@@ -0,0 +1,183 @@
+//! Protocol rounds as roles played by many parties: every role's workload runs once (parties
+//! playing the same role work in parallel on their own machines), and the round's latency is
+//! the critical path through its steps.
+
+use crate::group_ops_simulation::{ExecutionOrder, GroupOpsSimulation, Setup, WarmUp};
+use crate::progress::Reporter;
+use crate::report::SimulationReport;
+use crate::workload::{Op, Workload};
+
+use rand::RngCore;
+use std::fmt;
+use std::time::Duration;
+
+
+/// A role (dealer, verifier, reconstructor, ...) and how many parties play it.
+#[derive(Clone, Debug)]
+pub struct Role {
+    pub name: String,
+    pub parties: usize,
+    pub workload: Workload,
+}
+
+/// Roles grouped into steps: the roles of a step run concurrently, and a step starts when the
+/// previous one has finished.
+#[derive(Clone, Debug)]
+pub struct PartySimulation {
+    pub steps: Vec<Vec<Role>>,
+    setup: Setup,
+    warm_up: WarmUp,
+    order: ExecutionOrder,
+    cold_start: bool,
+    threads: usize,
+    progress: Option<Reporter>,
+}
+
+impl Default for PartySimulation {
+    fn default() -> Self {
+        Self { steps: Vec::new(), setup: Setup::Random, warm_up: WarmUp::None, order: ExecutionOrder::Grouped, cold_start: false, threads: 1, progress: None }
+    }
+}
+
+impl PartySimulation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One step per phase of `workload`, in order, each with a single role named after the
+    /// phase and played by `parties(phase)` parties. Ops outside any phase are left out.
+    pub fn from_phases(workload: &Workload, mut parties: impl FnMut(&str) -> usize) -> Self {
+        let mut sim = Self::new();
+        for op in &workload.ops {
+            let Some(phase) = &op.phase else { continue };
+            // the role's name already says which phase its ops belong to
+            let op = Op { phase: None, ..op.clone() };
+            match sim.steps.last_mut().and_then(|step| step.last_mut()) {
+                Some(role) if role.name == *phase => role.workload.ops.push(op),
+                _ => sim.steps.push(vec![Role {
+                    name: phase.clone(),
+                    parties: parties(phase),
+                    workload: Workload { ops: vec![op] },
+                }]),
+            }
+        }
+        sim
+    }
+
+    /// Adds a role to the current step.
+    pub fn role(&mut self, name: &str, parties: usize, workload: Workload) -> &mut Self {
+        if self.steps.is_empty() {
+            self.steps.push(Vec::new());
+        }
+        self.steps.last_mut().unwrap().push(Role { name: name.to_string(), parties, workload });
+        self
+    }
+
+    /// Starts a new step, which begins once every role of the current one is done.
+    pub fn then(&mut self) -> &mut Self {
+        self.steps.push(Vec::new());
+        self
+    }
+
+    /// How every role's inputs are drawn, see `GroupOpsSimulation::setup`.
+    pub fn setup(&mut self, setup: Setup) -> &mut Self {
+        self.setup = setup;
+        self
+    }
+
+    pub fn warm_up(&mut self, warm_up: WarmUp) -> &mut Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// Order of every role's operations, see `GroupOpsSimulation::order`.
+    pub fn order(&mut self, order: ExecutionOrder) -> &mut Self {
+        self.order = order;
+        self
+    }
+
+    /// See `GroupOpsSimulation::cold_start`. All roles run in the same process, so a role's
+    /// first operation of a kind is only really cold if no earlier role ran that kind.
+    pub fn cold_start(&mut self, cold_start: bool) -> &mut Self {
+        self.cold_start = cold_start;
+        self
+    }
+
+    /// Threads of each party, see `GroupOpsSimulation::threads`.
+    pub fn threads(&mut self, threads: usize) -> &mut Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Reports the progress of every role's simulation, see `GroupOpsSimulation::progress`.
+    pub fn progress(&mut self, reporter: Reporter) -> &mut Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Runs every role's workload once, drawing all inputs from `rng`.
+    pub fn simulate(&self, mut rng: impl RngCore) -> RoundReport {
+        let steps = self.steps.iter()
+            .filter(|step| !step.is_empty())
+            .map(|step| step.iter()
+                .map(|role| {
+                    let mut sim = GroupOpsSimulation::new(&mut rng);
+                    sim.setup(self.setup).workload(&role.workload).warm_up(self.warm_up).cold_start(self.cold_start).order(self.order).threads(self.threads);
+                    if let Some(reporter) = &self.progress {
+                        sim.progress(reporter.clone());
+                    }
+                    RoleReport { name: role.name.clone(), parties: role.parties, report: sim.simulate() }
+                })
+                .collect())
+            .collect();
+        RoundReport { steps }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RoleReport {
+    pub name: String,
+    pub parties: usize,
+    /// what one party playing the role does
+    pub report: SimulationReport,
+}
+
+impl RoleReport {
+    /// The role's latency, that of each of its parties.
+    pub fn latency(&self) -> Duration {
+        self.report.total
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundReport {
+    pub steps: Vec<Vec<RoleReport>>,
+}
+
+impl RoundReport {
+    /// The round's latency: the slowest role of every step, added up.
+    pub fn critical_path(&self) -> Duration {
+        self.steps.iter()
+            .map(|step| step.iter().map(RoleReport::latency).max().unwrap_or_default())
+            .sum()
+    }
+
+    /// Compute summed over all parties of all roles.
+    pub fn total_compute(&self) -> Duration {
+        self.steps.iter().flatten().map(|role| role.latency() * role.parties as u32).sum()
+    }
+}
+
+impl fmt::Display for RoundReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            for role in step {
+                let plural = if role.parties == 1 { "party" } else { "parties" };
+                writeln!(f, "step {}, {} ({} {}): {:?}", i + 1, role.name, role.parties, plural, role.latency())?;
+                write!(f, "{}", role.report)?;
+            }
+        }
+        writeln!(f, "critical path: {:?}", self.critical_path())?;
+        writeln!(f, "compute over all parties: {:?}", self.total_compute())
+    }
+}
@@ -0,0 +1,120 @@
+//! Pedersen commitments in G1, `C = g_1^{v_1} ... g_m^{v_m} h^r`, to a single scalar (`m = 1`)
+//! or a vector of `m`: one multi-exp of size `m + 1` to commit, and the same again to check an
+//! opening `(v, r)`. Unlike Feldman commitments they are hiding, at the price of one more base.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// Independent bases for vectors of length `m`.
+#[derive(Clone, Debug)]
+pub struct PedersenKey {
+    /// `g_1, ..., g_m` followed by `h`
+    bases: Vec<G1Projective>,
+}
+
+impl PedersenKey {
+    pub fn new(mut bases: Vec<G1Projective>, h: G1Projective) -> Self {
+        bases.push(h);
+        Self { bases }
+    }
+
+    /// The length of the vectors the key commits to.
+    pub fn len(&self) -> usize {
+        self.bases.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn commit(&self, values: &[Scalar], r: &Scalar) -> G1Projective {
+        assert_eq!(values.len(), self.len(), "need one value per base");
+        let scalars : Vec<Scalar> = values.iter().chain([r]).copied().collect();
+        G1Projective::multi_exp(&self.bases, &scalars)
+    }
+
+    pub fn verify(&self, commitment: &G1Projective, values: &[Scalar], r: &Scalar) -> bool {
+        self.commit(values, r) == *commitment
+    }
+}
+
+// a vector and its randomness
+struct Opening {
+    values: Vec<Scalar>,
+    r: Scalar,
+}
+
+struct Commitments {
+    key: PedersenKey,
+    openings: Vec<Opening>,
+    // with `verify`, the openings are checked against these instead of committed
+    commitments: Option<Vec<G1Projective>>,
+}
+
+impl Commitments {
+    fn run(&self, i: usize) -> (G1Projective, bool) {
+        let Opening { values, r } = &self.openings[i];
+        match &self.commitments {
+            None => (self.key.commit(values, r), true),
+            Some(commitments) => (commitments[i], self.key.verify(&commitments[i], values, r)),
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for Commitments {
+    fn description(&self) -> String {
+        let what = if self.commitments.is_some() { "opening checks" } else { "commitments" };
+        format!("Pedersen {}: {} of length {}", what, self.openings.len(), self.key.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.openings.len(), self.key.len() + 1)
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<(G1Projective, bool)>>((0..self.openings.len()).map(|i| self.run(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.openings.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.run(i));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn pedersen_item(&mut self, num: usize, m: usize, verify: bool) -> &mut Self {
+        let mut bases = self.rng().points::<G1Projective>(m + 1);
+        let h = bases.pop().unwrap();
+        let key = PedersenKey::new(bases, h);
+        let openings : Vec<Opening> = (0..num)
+            .map(|_| Opening {
+                values: (0..m).map(|_| Scalar::random(&mut *self.rng())).collect(),
+                r: Scalar::random(&mut *self.rng()),
+            })
+            .collect();
+        let commitments = verify.then(|| openings.iter().map(|o| key.commit(&o.values, &o.r)).collect());
+        self.push_item(Commitments { key, openings, commitments })
+    }
+
+    /// `num` commitments to vectors of `m` scalars, `m = 1` committing to single scalars.
+    pub fn pedersen_commitments(&mut self, num: usize, m: usize) -> &mut Self {
+        self.pedersen_item(num, m, false)
+    }
+
+    /// `num` checks of openings of commitments to vectors of `m` scalars.
+    pub fn pedersen_openings(&mut self, num: usize, m: usize) -> &mut Self {
+        self.pedersen_item(num, m, true)
+    }
+}
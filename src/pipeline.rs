@@ -0,0 +1,123 @@
+//! A verifier that checks proofs as they arrive, flushing a batch every `b` of them, against one
+//! that waits for all of them and checks a single batch at the end: how soon each rejects the
+//! first invalid proof, and how long each takes in all.
+//!
+//! Proofs are taken to arrive faster than they are verified, so the verifier is never idle and
+//! both times are pure verification time from the first proof on. Every flush is checked as a
+//! batch through a random linear combination and bisected when it fails (module `sigma`), so the
+//! first invalid proof is rejected once the bisection of its flush isolates it. Smaller flushes
+//! reach it sooner but pay the fixed cost of a batch more often; `b = 1` checks proofs one by
+//! one and `b = n` is the single batch.
+
+use crate::clock::Instant;
+use crate::sigma::{Sigma, Verifications};
+use crate::stats::Summary;
+
+use blstrs::G1Projective;
+use group::Group as _;
+use rand::{RngCore, SeedableRng as _};
+use rand_chacha::ChaCha20Rng;
+use std::fmt;
+use std::ops::Range;
+use std::time::Duration;
+
+
+/// Median times of the verification of `size` proofs at every flush size.
+#[derive(Clone, Debug)]
+pub struct Pipelining {
+    pub protocol: Sigma,
+    pub size: usize,
+    /// positions of the invalid proofs, in increasing order
+    pub invalid: Vec<usize>,
+    pub flushes: Vec<Flush>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Flush {
+    pub flush_size: usize,
+    /// batches checked, those of the bisections included
+    pub batches: usize,
+    /// until the first invalid proof is rejected, `None` if all are valid
+    pub first_reject: Option<Duration>,
+    /// until every invalid proof is found
+    pub total: Duration,
+}
+
+/// 1, 2, 4, ... up to `size`, which is always included.
+pub fn flush_sizes(size: usize) -> Vec<usize> {
+    let mut sizes : Vec<usize> = std::iter::successors(Some(1), |&b| Some(b * 2)).take_while(|&b| b < size).collect();
+    sizes.push(size.max(1));
+    sizes
+}
+
+// the time and batches of one verification with flushes of `flush_size`, batch coefficients
+// drawn from `rng`
+fn run(verifications: &Verifications, flush_size: usize, rng: &mut impl RngCore) -> (Option<Duration>, Duration, usize) {
+    struct Run<'a, R> {
+        verifications: &'a Verifications,
+        rng: &'a mut R,
+        start_time: Instant,
+        first_reject: Option<Duration>,
+        batches: usize,
+    }
+
+    impl<R: RngCore> Run<'_, R> {
+        fn bisect(&mut self, range: Range<usize>) {
+            self.batches += 1;
+            if self.verifications.check(range.clone(), &mut *self.rng) {
+                return;
+            }
+            if range.len() == 1 {
+                let elapsed = self.start_time.elapsed();
+                self.first_reject.get_or_insert(elapsed);
+                return;
+            }
+            let middle = range.start + range.len() / 2;
+            self.bisect(range.start..middle);
+            self.bisect(middle..range.end);
+        }
+    }
+
+    let size = verifications.size();
+    let mut run = Run { verifications, rng, start_time: Instant::now(), first_reject: None, batches: 0 };
+    for start in (0..size).step_by(flush_size) {
+        run.bisect(start..(start + flush_size).min(size));
+    }
+    (run.first_reject, run.start_time.elapsed(), run.batches)
+}
+
+/// Times the verification of `size` proofs of `protocol`, `invalid_percent` percent of them
+/// invalid at random positions, with flushes of every size of `flush_sizes(size)`, taking the
+/// median of `repetitions` runs each.
+pub fn pipelined_verification(protocol: Sigma, size: usize, invalid_percent: usize, repetitions: usize, mut rng: impl RngCore) -> Pipelining {
+    assert!(size > 0 && repetitions > 0, "need at least one proof and one repetition");
+    let h = G1Projective::random(&mut rng);
+    let verifications = Verifications::new(protocol, 1, size, true, invalid_percent, h, &mut rng);
+    let flushes = flush_sizes(size).into_iter()
+        .map(|flush_size| {
+            let runs : Vec<_> = (0..repetitions)
+                .map(|i| run(&verifications, flush_size, &mut ChaCha20Rng::seed_from_u64(i as u64)))
+                .collect();
+            let first_reject = runs.iter().map(|&(first_reject, _, _)| first_reject).collect::<Option<Vec<_>>>()
+                .map(|samples| Summary::from_samples(&samples).median);
+            let totals : Vec<Duration> = runs.iter().map(|&(_, total, _)| total).collect();
+            Flush { flush_size, batches: runs[0].2, first_reject, total: Summary::from_samples(&totals).median }
+        })
+        .collect();
+    Pipelining { protocol, size, invalid: verifications.invalid.clone(), flushes }
+}
+
+impl fmt::Display for Pipelining {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.invalid.first() {
+            Some(first) => writeln!(f, "{} {} proofs, {} invalid, the first at position {}:", self.size, self.protocol, self.invalid.len(), first)?,
+            None => writeln!(f, "{} {} proofs, all valid:", self.size, self.protocol)?,
+        }
+        writeln!(f, "{:>7}  {:>7}  {:>12}  {:>12}", "flush", "batches", "first reject", "total")?;
+        for flush in &self.flushes {
+            let first_reject = flush.first_reject.map_or("-".to_string(), |time| format!("{:.3?}", time));
+            writeln!(f, "{:>7}  {:>7}  {:>12}  {:>12}", flush.flush_size, flush.batches, first_reject, format!("{:.3?}", flush.total))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+//! Pippenger's bucket method for multi-exponentiation with a chosen window size and bucket
+//! strategy, as an alternative to blst's built-in multi-exp (whose window size is fixed by a
+//! formula in blst), and a search for the fastest window size per multi-exp size on the host.
+//!
+//! Each scalar is cut into windows of `window_bits` bits. Per window, every base is added into
+//! the bucket of its digit, and the buckets are summed up weighted by their digit with a running
+//! sum; the window sums are then combined with `window_bits` doublings between consecutive
+//! windows. `PippengerConfig::counts` gives those operations for given scalars, and the items
+//! list them under their time: the bucket additions grow with the size, the summation with the
+//! number of buckets, which is why the best window size grows with the size.
+
+use crate::clock::Instant;
+use crate::curve_ops::BatchToAffine;
+use crate::group_ops_simulation::{naive_multi_exp, GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup, Sampler};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+use crate::stats::Summary;
+use crate::test_vectors::OutputBytes;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::{Field as _, PrimeField as _};
+use group::Group;
+use rand::RngCore;
+use std::fmt;
+use std::hint::black_box;
+use std::ops::{AddAssign, Mul, SubAssign};
+use std::time::Duration;
+
+
+pub const MAX_WINDOW_BITS : usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Buckets {
+    /// digits in `0..2^w`, one bucket per non-zero digit
+    Unsigned,
+    /// digits in `-2^(w-1)..=2^(w-1)`, halving the buckets: a negative digit subtracts the base
+    /// from the bucket of its absolute value, and negating a point is free
+    Signed,
+}
+
+impl Buckets {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "unsigned" => Some(Buckets::Unsigned),
+            "signed" => Some(Buckets::Signed),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Buckets::Unsigned => "unsigned",
+            Buckets::Signed => "signed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PippengerConfig {
+    pub window_bits: usize,
+    pub buckets: Buckets,
+}
+
+impl PippengerConfig {
+    pub fn new(window_bits: usize, buckets: Buckets) -> Self {
+        assert!((1..=MAX_WINDOW_BITS).contains(&window_bits), "window size must be between 1 and {} bits", MAX_WINDOW_BITS);
+        Self { window_bits, buckets }
+    }
+
+    /// A rule of thumb of about `ln(size)` bits, with signed buckets.
+    pub fn default_for(size: usize) -> Self {
+        let window_bits = if size < 32 { 3 } else { (size as f64).ln().round() as usize };
+        Self::new(window_bits.min(MAX_WINDOW_BITS), Buckets::Signed)
+    }
+
+    fn num_windows(&self) -> usize {
+        match self.buckets {
+            Buckets::Unsigned => (Scalar::NUM_BITS as usize).div_ceil(self.window_bits),
+            // room for the carry out of the top window
+            Buckets::Signed => (Scalar::NUM_BITS as usize + 1).div_ceil(self.window_bits),
+        }
+    }
+
+    fn num_buckets(&self) -> usize {
+        match self.buckets {
+            Buckets::Unsigned => (1 << self.window_bits) - 1,
+            Buckets::Signed => 1 << (self.window_bits - 1),
+        }
+    }
+
+    // digits of every scalar, given as little-endian bytes, window by window
+    fn digits(&self, scalars: &[[u8; 32]]) -> Vec<Vec<i32>> {
+        let num_windows = self.num_windows();
+        let mut digits = vec![vec![0i32; scalars.len()]; num_windows];
+        for (i, bytes) in scalars.iter().enumerate() {
+            let limbs : [u64; 4] = std::array::from_fn(|j| u64::from_le_bytes(bytes[8 * j..8 * j + 8].try_into().unwrap()));
+            let mut carry = 0;
+            for (w, window) in digits.iter_mut().enumerate() {
+                let digit = bits(&limbs, w * self.window_bits, self.window_bits) as i32 + carry;
+                window[i] = match self.buckets {
+                    Buckets::Signed if digit > 1 << (self.window_bits - 1) => { carry = 1; digit - (1 << self.window_bits) }
+                    _ => { carry = 0; digit }
+                };
+            }
+        }
+        digits
+    }
+
+    /// The group operations `multi_exp` performs with this configuration on `scalars`.
+    pub fn counts(&self, scalars: &[Scalar]) -> PippengerCounts {
+        let digits = self.digits(&to_bytes(scalars));
+        let windows = digits.len();
+        PippengerCounts {
+            windows,
+            buckets: self.num_buckets(),
+            bucket_additions: digits.iter().flatten().filter(|&&digit| digit != 0).count(),
+            // the running sum and the window sum, per bucket, and the window sum into the total
+            summation_additions: windows * (2 * self.num_buckets() + 1),
+            doublings: windows * self.window_bits,
+        }
+    }
+}
+
+/// What one multi-exp by Pippenger's method costs in group operations, which explains its time:
+/// the bucket additions grow with the size, the rest only with the window size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PippengerCounts {
+    pub windows: usize,
+    /// per window
+    pub buckets: usize,
+    /// bases added into (or subtracted from) their buckets, one per non-zero digit
+    pub bucket_additions: usize,
+    /// additions summing up the buckets of every window and the windows
+    pub summation_additions: usize,
+    pub doublings: usize,
+}
+
+impl PippengerCounts {
+    pub fn additions(&self) -> usize {
+        self.bucket_additions + self.summation_additions
+    }
+}
+
+impl fmt::Display for PippengerCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} windows of {} buckets: {} bucket additions, {} summation additions, {} doublings",
+            self.windows, self.buckets, self.bucket_additions, self.summation_additions, self.doublings)
+    }
+}
+
+fn to_bytes(scalars: &[Scalar]) -> Vec<[u8; 32]> {
+    scalars.iter().map(Scalar::to_bytes_le).collect()
+}
+
+impl fmt::Display for PippengerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-bit windows, {} buckets", self.window_bits, self.buckets.name())
+    }
+}
+
+// `len` bits of the 256-bit little-endian number starting at bit `start`
+fn bits(limbs: &[u64; 4], start: usize, len: usize) -> u64 {
+    let (limb, shift) = (start / 64, start % 64);
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut value = limbs[limb] >> shift;
+    if shift + len > 64 && limb + 1 < limbs.len() {
+        value |= limbs[limb + 1] << (64 - shift);
+    }
+    value & ((1 << len) - 1)
+}
+
+/// `sum_i scalars[i] * bases[i]` by Pippenger's method with the given window size and buckets.
+pub fn multi_exp<T>(bases: &[T::AffineRepr], scalars: &[Scalar], config: &PippengerConfig) -> T
+where T : BatchToAffine {
+    assert_eq!(bases.len(), scalars.len(), "need as many scalars as bases");
+    bucket_method(bases, &config.digits(&to_bytes(scalars)), config)
+}
+
+/// Same as `multi_exp`, for projective bases of any group and scalars as 32 little-endian bytes:
+/// the multi-exp of backends whose crate has none, so that they aren't compared by summing
+/// single exps.
+pub fn multi_exp_projective<T>(bases: &[T], scalars: &[[u8; 32]], config: &PippengerConfig) -> T
+where T : Group + for<'a> AddAssign<&'a T> + for<'a> SubAssign<&'a T> {
+    assert_eq!(bases.len(), scalars.len(), "need as many scalars as bases");
+    bucket_method(bases, &config.digits(scalars), config)
+}
+
+// adds every base into the bucket of its digit in projective buckets, window by window
+fn bucket_method<T, B>(bases: &[B], digits: &[Vec<i32>], config: &PippengerConfig) -> T
+where T : Group + for<'a> AddAssign<&'a B> + for<'a> SubAssign<&'a B> {
+    let mut buckets = vec![T::identity(); config.num_buckets()];
+    let mut acc = T::identity();
+    for window in digits.iter().rev() {
+        for _ in 0..config.window_bits {
+            acc = acc.double();
+        }
+        buckets.fill(T::identity());
+        for (base, &digit) in bases.iter().zip(window) {
+            match digit {
+                0 => {}
+                d if d > 0 => buckets[d as usize - 1] += base,
+                d => buckets[(-d) as usize - 1] -= base,
+            }
+        }
+        // sum_j (j + 1) * buckets[j], by adding up running sums from the top bucket down
+        let mut running = T::identity();
+        let mut window_sum = T::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            window_sum += running;
+        }
+        acc += window_sum;
+    }
+    acc
+}
+
+struct PippengerMultiExps<T>
+where T : BatchToAffine {
+    num: usize,
+    config: PippengerConfig,
+    bases: Vec<T::AffineRepr>,
+    scalars: Vec<Scalar>,
+}
+
+impl<T> PippengerMultiExps<T>
+where T : BatchToAffine {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize, size: usize, config: PippengerConfig) -> Self {
+        let bases : Vec<T> = rng.points(size);
+        Self {
+            num,
+            config,
+            bases: T::batch_to_affine(&bases),
+            scalars: (0..size).map(|_| Scalar::random(&mut *rng)).collect(),
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for PippengerMultiExps<T>
+where T : BatchToAffine + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync,
+      T::AffineRepr : Send + Sync {
+    fn description(&self) -> String {
+        format!("{} Pippenger multi-exps ({}): {} of size {}", T::NAME, self.config, self.num, self.bases.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>((0..self.num).map(|_| multi_exp(&self.bases, &self.scalars, &self.config)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| multi_exp::<T>(&self.bases, &self.scalars, &self.config)));
+    }
+
+    fn notes(&self) -> Option<String> {
+        Some(format!("each: {}", self.config.counts(&self.scalars)))
+    }
+
+    fn validate(&self) -> Option<bool> {
+        let bases : Vec<T> = self.bases.iter().map(|base| T::identity() + base).collect();
+        Some(multi_exp::<T>(&self.bases, &self.scalars, &self.config) == naive_multi_exp(&bases, &self.scalars))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(multi_exp::<T>(&self.bases, &self.scalars, &self.config).output_bytes())
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// Same as `g1_multi_exps`, with this module's single-threaded Pippenger in place of blst's.
+    pub fn g1_pippenger_multi_exps(&mut self, num: usize, size: usize, config: PippengerConfig) -> &mut Self {
+        let item = PippengerMultiExps::<G1Projective>::new(self.rng(), num, size, config);
+        self.push_item(item)
+    }
+    /// Same as `g1_pippenger_multi_exps`, in G2.
+    pub fn g2_pippenger_multi_exps(&mut self, num: usize, size: usize, config: PippengerConfig) -> &mut Self {
+        let item = PippengerMultiExps::<G2Projective>::new(self.rng(), num, size, config);
+        self.push_item(item)
+    }
+}
+
+/// Median times of a G1 multi-exp of one size at every window size tried, for both bucket
+/// strategies.
+#[derive(Clone, Debug)]
+pub struct WindowTuning {
+    pub size: usize,
+    /// (window bits, unsigned buckets, signed buckets), by increasing window size
+    pub times: Vec<(usize, Duration, Duration)>,
+    /// the group operations with signed buckets, at the same window sizes
+    pub counts: Vec<PippengerCounts>,
+    /// blstrs' `G1Projective::multi_exp`, for reference
+    pub builtin: Duration,
+}
+
+impl WindowTuning {
+    /// The fastest configuration and its time.
+    pub fn best(&self) -> (PippengerConfig, Duration) {
+        self.times.iter()
+            .flat_map(|&(window_bits, unsigned, signed)| [
+                (PippengerConfig::new(window_bits, Buckets::Unsigned), unsigned),
+                (PippengerConfig::new(window_bits, Buckets::Signed), signed),
+            ])
+            .min_by_key(|&(_, time)| time)
+            .unwrap()
+    }
+}
+
+/// Window sizes tried for a multi-exp of `size` bases: four either side of the rule of thumb.
+pub fn window_candidates(size: usize) -> std::ops::RangeInclusive<usize> {
+    let guess = PippengerConfig::default_for(size).window_bits;
+    guess.saturating_sub(4).max(1)..=(guess + 4).min(MAX_WINDOW_BITS)
+}
+
+/// Times a G1 multi-exp of `size` random bases at every window size of `window_candidates` with
+/// either bucket strategy, taking the median of `repetitions` runs each.
+pub fn tune_window(size: usize, repetitions: usize, mut rng: impl RngCore) -> WindowTuning {
+    assert!(size > 0 && repetitions > 0, "need at least one base and one repetition");
+    let projective : Vec<G1Projective> = (0..size).map(|_| G1Projective::random(&mut rng)).collect();
+    let bases = G1Projective::batch_to_affine(&projective);
+    let scalars : Vec<Scalar> = (0..size).map(|_| Scalar::random(&mut rng)).collect();
+    let median = |f: &dyn Fn()| {
+        let samples : Vec<Duration> = (0..repetitions)
+            .map(|_| {
+                let start_time = Instant::now();
+                f();
+                start_time.elapsed()
+            })
+            .collect();
+        Summary::from_samples(&samples).median
+    };
+    let time = |config: PippengerConfig| median(&|| { black_box(multi_exp::<G1Projective>(&bases, &scalars, &config)); });
+
+    let times = window_candidates(size)
+        .map(|window_bits| (window_bits, time(PippengerConfig::new(window_bits, Buckets::Unsigned)), time(PippengerConfig::new(window_bits, Buckets::Signed))))
+        .collect();
+    let counts = window_candidates(size).map(|window_bits| PippengerConfig::new(window_bits, Buckets::Signed).counts(&scalars)).collect();
+    let builtin = median(&|| { black_box(G1Projective::multi_exp(&projective, &scalars)); });
+    WindowTuning { size, times, counts, builtin }
+}
+
+impl fmt::Display for WindowTuning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "G1 Pippenger multi-exp of size {}:", self.size)?;
+        writeln!(f, "{:>6}  {:>12}  {:>12}  {:>9}  {:>9}", "window", "unsigned", "signed", "additions", "doublings")?;
+        for (&(window_bits, unsigned, signed), counts) in self.times.iter().zip(&self.counts) {
+            writeln!(f, "{:>6}  {:>12}  {:>12}  {:>9}  {:>9}", window_bits, format!("{:.3?}", unsigned), format!("{:.3?}", signed), counts.additions(), counts.doublings)?;
+        }
+        let (config, time) = self.best();
+        writeln!(f, "fastest: {} ({:.3?}); blstrs multi_exp: {:.3?}", config, time, self.builtin)
+    }
+}
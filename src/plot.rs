@@ -0,0 +1,203 @@
+//! SVG plots of sweeps: every phase's time and the total against the first parameter that
+//! varies, and the phases of every parameter combination as stacked bars.
+//!
+//! Written out by hand as plain SVG, which browsers display directly.
+
+use crate::render::Style;
+use crate::sweep::SweepTable;
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+
+const WIDTH : f64 = 760.0;
+const PANEL_HEIGHT : f64 = 380.0;
+// plot area margins within a panel
+const LEFT : f64 = 80.0;
+const RIGHT : f64 = 170.0;
+const TOP : f64 = 40.0;
+const BOTTOM : f64 = 60.0;
+const COLORS : [&str; 8] = ["#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f"];
+
+
+/// One axis' mapping from values to pixels, logarithmic when the values span more than a
+/// factor of 10.
+#[derive(Clone, Copy, Debug)]
+struct Scale {
+    min: f64,
+    max: f64,
+    log: bool,
+    // pixel range
+    from: f64,
+    to: f64,
+}
+
+impl Scale {
+    fn new(values: impl Iterator<Item = f64> + Clone, from: f64, to: f64, zero: bool) -> Self {
+        let max = values.clone().fold(f64::MIN, f64::max);
+        let min = values.fold(f64::MAX, f64::min);
+        let log = !zero && min > 0.0 && max / min > 10.0;
+        let (min, max) = match (log, zero) {
+            (true, _) => (10f64.powf(min.log10().floor()), 10f64.powf(max.log10().ceil())),
+            (false, true) => (0.0, max),
+            (false, false) => (min, max),
+        };
+        let max = if max > min { max } else { min + 1.0 };
+        Self { min, max, log, from, to }
+    }
+
+    fn position(&self, value: f64) -> f64 {
+        let t = if self.log {
+            (value.log10() - self.min.log10()) / (self.max.log10() - self.min.log10())
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        self.from + t * (self.to - self.from)
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        if self.log {
+            let (lo, hi) = (self.min.log10().round() as i32, self.max.log10().round() as i32);
+            return (lo..=hi).map(|e| 10f64.powi(e)).collect();
+        }
+        let rough = (self.max - self.min) / 5.0;
+        let magnitude = 10f64.powf(rough.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0].into_iter()
+            .map(|m| m * magnitude)
+            .find(|&step| step >= rough)
+            .unwrap();
+        let first = (self.min / step).ceil() as i64;
+        let last = (self.max / step).floor() as i64;
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+}
+
+fn time_label(seconds: f64) -> String {
+    Style::default().duration(Duration::from_secs_f64(seconds))
+}
+
+fn number_label(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{}", value as i64) } else { format!("{}", value) }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// axes, grid lines and tick labels of a panel whose top edge is at `y0`
+fn axes(out: &mut String, x: Option<&Scale>, y: &Scale, x_label: &str, y0: f64) {
+    let (left, right, bottom) = (LEFT, WIDTH - RIGHT, y0 + PANEL_HEIGHT - BOTTOM);
+    for tick in y.ticks() {
+        let py = y.position(tick);
+        writeln!(out, r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#ddd"/>"##, left, py, right, py).unwrap();
+        writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"#, left - 6.0, py, escape(&time_label(tick))).unwrap();
+    }
+    if let Some(x) = x {
+        for tick in x.ticks() {
+            let px = x.position(tick);
+            writeln!(out, r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#ddd"/>"##, px, y0 + TOP, px, bottom).unwrap();
+            writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#, px, bottom + 18.0, number_label(tick)).unwrap();
+        }
+    }
+    writeln!(out, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="black"/>"#, left, bottom, right, bottom).unwrap();
+    writeln!(out, r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="black"/>"#, left, y0 + TOP, left, bottom).unwrap();
+    if !x_label.is_empty() {
+        writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#, (left + right) / 2.0, bottom + 42.0, escape(x_label)).unwrap();
+    }
+}
+
+fn legend(out: &mut String, names: &[String], y0: f64) {
+    for (i, name) in names.iter().enumerate() {
+        let (x, y) = (WIDTH - RIGHT + 16.0, y0 + TOP + 10.0 + i as f64 * 20.0);
+        writeln!(out, r#"<rect x="{:.1}" y="{:.1}" width="12" height="12" fill="{}"/>"#, x, y - 6.0, COLORS[i % COLORS.len()]).unwrap();
+        writeln!(out, r#"<text x="{:.1}" y="{:.1}" dominant-baseline="middle">{}</text>"#, x + 18.0, y, escape(name)).unwrap();
+    }
+}
+
+/// Renders `table` as an SVG with a line chart of time against the first parameter taking more
+/// than one value (left out if there is none) above stacked bars of the phases of every row.
+pub fn sweep_svg(table: &SweepTable) -> String {
+    let mut phase_names : Vec<String> = Vec::new();
+    for row in &table.rows {
+        for (name, _) in &row.phases {
+            if !phase_names.contains(name) {
+                phase_names.push(name.clone());
+            }
+        }
+    }
+    let phase_time = |row: usize, name: &str| table.rows[row].phases.iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, d)| d.as_secs_f64());
+    let x_param = table.param_names.iter().find(|name| {
+        let mut values = table.rows.iter().filter_map(|row| row.params.get(name));
+        values.next().is_some_and(|first| values.any(|v| v != first))
+    });
+    let panels = if x_param.is_some() { 2.0 } else { 1.0 };
+
+    let mut out = String::new();
+    writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="12">"#, WIDTH, PANEL_HEIGHT * panels).unwrap();
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    let mut y0 = 0.0;
+
+    if let Some(x_param) = x_param {
+        let mut rows : Vec<usize> = (0..table.rows.len()).collect();
+        rows.sort_by_key(|&i| table.rows[i].params.get(x_param));
+        let x_value = |i: usize| table.rows[i].params.get(x_param).unwrap() as f64;
+        let mut series : Vec<(String, Vec<(f64, f64)>)> = phase_names.iter()
+            .map(|name| (name.clone(), rows.iter().filter_map(|&i| Some((x_value(i), phase_time(i, name)?))).collect()))
+            .collect();
+        if phase_names.len() != 1 {
+            series.push(("total".to_string(), rows.iter().map(|&i| (x_value(i), table.rows[i].total.as_secs_f64())).collect()));
+        }
+        let points = series.iter().flat_map(|(_, points)| points.iter()).filter(|&&(_, y)| y > 0.0);
+        let x = Scale::new(points.clone().map(|&(x, _)| x), LEFT, WIDTH - RIGHT, false);
+        let y = Scale::new(points.map(|&(_, y)| y), y0 + PANEL_HEIGHT - BOTTOM, y0 + TOP, false);
+        writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="14">time vs {}</text>"#, WIDTH / 2.0, y0 + 22.0, escape(x_param)).unwrap();
+        axes(&mut out, Some(&x), &y, x_param, y0);
+        for (i, (_, points)) in series.iter().enumerate() {
+            let color = COLORS[i % COLORS.len()];
+            let path : Vec<String> = points.iter()
+                .filter(|&&(_, t)| t > 0.0)
+                .map(|&(px, py)| format!("{:.1},{:.1}", x.position(px), y.position(py)))
+                .collect();
+            writeln!(out, r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#, path.join(" "), color).unwrap();
+            for point in &path {
+                let (px, py) = point.split_once(',').unwrap();
+                writeln!(out, r#"<circle cx="{}" cy="{}" r="3" fill="{}"/>"#, px, py, color).unwrap();
+            }
+        }
+        let names : Vec<String> = series.into_iter().map(|(name, _)| name).collect();
+        legend(&mut out, &names, y0);
+        y0 += PANEL_HEIGHT;
+    }
+
+    // stacked bars, one per row, in sweep order
+    let y = Scale::new(table.rows.iter().map(|row| row.phases.iter().map(|(_, d)| d.as_secs_f64()).sum::<f64>()), y0 + PANEL_HEIGHT - BOTTOM, y0 + TOP, true);
+    writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="14">time per phase</text>"#, WIDTH / 2.0, y0 + 22.0).unwrap();
+    axes(&mut out, None, &y, "", y0);
+    let slot = (WIDTH - RIGHT - LEFT) / table.rows.len().max(1) as f64;
+    for (i, row) in table.rows.iter().enumerate() {
+        let x = LEFT + i as f64 * slot + slot * 0.15;
+        let mut bottom = 0.0;
+        for (j, name) in phase_names.iter().enumerate() {
+            let Some(t) = phase_time(i, name) else { continue };
+            let (top, base) = (y.position(bottom + t), y.position(bottom));
+            writeln!(out, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect>"#,
+                x, top, slot * 0.7, base - top, COLORS[j % COLORS.len()], escape(name), escape(&time_label(t))).unwrap();
+            bottom += t;
+        }
+        let label : Vec<String> = table.param_names.iter()
+            .filter(|name| x_param.is_none_or(|x_param| x_param == *name))
+            .map(|name| format!("{}={}", name, row.params.get(name).unwrap()))
+            .collect();
+        writeln!(out, r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#, x + slot * 0.35, y0 + PANEL_HEIGHT - BOTTOM + 18.0, escape(&label.join(" "))).unwrap();
+    }
+    legend(&mut out, &phase_names, y0);
+    writeln!(out, "</svg>").unwrap();
+    out
+}
+
+pub fn save_sweep_svg(table: &SweepTable, path: impl AsRef<Path>) -> std::io::Result<()> {
+    std::fs::write(path, sweep_svg(table))
+}
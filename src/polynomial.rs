@@ -0,0 +1,258 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::Scalar;
+use ff::{BatchInvert as _, Field as _, PrimeField as _};
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// Evaluates the polynomial with coefficients `coeffs` (constant term first) at `x`.
+pub fn horner(coeffs: &[Scalar], x: &Scalar) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+/// Lagrange coefficients for interpolating at 0 from the values at distinct `points`:
+/// `λ_i = ∏_{j≠i} x_j / (x_j - x_i)`. Costs O(t²) multiplications and one batch inversion.
+pub fn lagrange_coefficients_at_zero(points: &[Scalar]) -> Vec<Scalar> {
+    let mut denominators : Vec<Scalar> = points.iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            points.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(*x_i, |acc, (_, x_j)| acc * (x_j - x_i))
+        })
+        .collect();
+    denominators.iter_mut().batch_invert();
+    // the full product of all points, divided by x_i via the extra x_i factor in the denominator
+    let numerator : Scalar = points.iter().product();
+    denominators.iter().map(|d| numerator * d).collect()
+}
+
+struct LagrangeCoefficients {
+    num: usize,
+    points: Vec<Scalar>,
+}
+
+impl LagrangeCoefficients {
+    fn new(num: usize, t: usize) -> Self {
+        Self {
+            num,
+            points: (1..=t as u64).map(Scalar::from).collect(),
+        }
+    }
+}
+
+impl GroupOpsSimulationItem for LagrangeCoefficients {
+    fn description(&self) -> String {
+        format!("Lagrange coefficients: {} sets of size {}", self.num, self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.points.len())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(lagrange_coefficients_at_zero(&self.points));
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| lagrange_coefficients_at_zero(&self.points)));
+    }
+}
+
+/// Multiplicative subgroup of size `2^log_size` of the scalar field, for radix-2 FFTs.
+pub struct Radix2Domain {
+    log_size: u32,
+    omega: Scalar,
+    omega_inv: Scalar,
+    size_inv: Scalar,
+}
+
+impl Radix2Domain {
+    /// Panics if `log_size` exceeds the 2-adicity of the scalar field (32).
+    pub fn new(log_size: u32) -> Self {
+        assert!(log_size <= Scalar::S, "the scalar field has no root of unity of order 2^{}", log_size);
+        let mut omega = Scalar::ROOT_OF_UNITY;
+        for _ in log_size..Scalar::S {
+            omega = omega.square();
+        }
+        Self {
+            log_size,
+            omega,
+            omega_inv: omega.invert().unwrap(),
+            size_inv: Scalar::from(1u64 << log_size).invert().unwrap(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Coefficients to evaluations at the powers of the generator, in place.
+    pub fn fft(&self, values: &mut [Scalar]) {
+        self.transform(values, self.omega);
+    }
+
+    /// Evaluations back to coefficients, in place.
+    pub fn ifft(&self, values: &mut [Scalar]) {
+        self.transform(values, self.omega_inv);
+        values.iter_mut().for_each(|v| *v *= self.size_inv);
+    }
+
+    // iterative Cooley-Tukey
+    fn transform(&self, values: &mut [Scalar], omega: Scalar) {
+        let n = self.size();
+        assert_eq!(values.len(), n, "input length must match the domain size");
+        // the transform of one value is itself (and the bit reversal below would shift by 64)
+        if n == 1 {
+            return;
+        }
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - self.log_size);
+            if i < j {
+                values.swap(i, j);
+            }
+        }
+
+        let mut half = 1;
+        while half < n {
+            // omega^(n / 2half) is a primitive 2half-th root of unity
+            let step = omega.pow_vartime([(n / (2 * half)) as u64]);
+            for start in (0..n).step_by(2 * half) {
+                let mut w = Scalar::ONE;
+                for i in start..start + half {
+                    let t = values[i + half] * w;
+                    values[i + half] = values[i] - t;
+                    values[i] += t;
+                    w *= step;
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+struct Ffts {
+    num: usize,
+    inverse: bool,
+    domain: Radix2Domain,
+    values: Vec<Scalar>,
+}
+
+impl Ffts {
+    fn new(mut rng: &mut impl RngCore, num: usize, log_size: u32, inverse: bool) -> Self {
+        let domain = Radix2Domain::new(log_size);
+        Self {
+            num,
+            inverse,
+            values: (0..domain.size()).map(|_| Scalar::random(&mut rng)).collect(),
+            domain,
+        }
+    }
+
+    fn transform(&self) -> Vec<Scalar> {
+        // the transform is in place, so work on a copy of the input
+        let mut values = self.values.clone();
+        if self.inverse {
+            self.domain.ifft(&mut values);
+        } else {
+            self.domain.fft(&mut values);
+        }
+        values
+    }
+}
+
+impl GroupOpsSimulationItem for Ffts {
+    fn description(&self) -> String {
+        let kind = if self.inverse { "inverse FFTs" } else { "FFTs" };
+        format!("{}: {} of size 2^{}", kind, self.num, self.domain.log_size)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.domain.size())
+    }
+
+    fn simulate(&self) {
+        for _ in 0..self.num {
+            black_box(self.transform());
+        }
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.transform()));
+    }
+}
+
+// Shamir dealing: evaluate each degree-t polynomial at the share indices 1..=num_points
+struct PolyEvals {
+    polys: Vec<Vec<Scalar>>,
+    points: Vec<Scalar>,
+}
+
+impl PolyEvals {
+    fn new(mut rng: &mut impl RngCore, num_polys: usize, degree: usize, num_points: usize) -> Self {
+        Self {
+            polys: (0..num_polys)
+                .map(|_| (0..=degree).map(|_| Scalar::random(&mut rng)).collect())
+                .collect(),
+            points: (1..=num_points as u64).map(Scalar::from).collect(),
+        }
+    }
+
+    fn evaluate(&self, poly: &[Scalar]) -> Vec<Scalar> {
+        self.points.iter().map(|x| horner(poly, x)).collect()
+    }
+}
+
+impl GroupOpsSimulationItem for PolyEvals {
+    fn description(&self) -> String {
+        format!("poly evals: {} of degree {} at {} points",
+            self.polys.len(), self.polys.first().map_or(0, |p| p.len() - 1), self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        // one operation per evaluation, sized by the number of coefficients
+        OpCount::multi(self.polys.len() * self.points.len(), self.polys.first().map_or(0, Vec::len))
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Vec<Scalar>>>(self.polys.iter().map(|poly| self.evaluate(poly)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.polys.len(), |i| self.evaluate(&self.polys[i])));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// Horner evaluation of `num_polys` random polynomials of the given degree at 1..=num_points.
+    pub fn poly_evals(&mut self, num_polys: usize, degree: usize, num_points: usize) -> &mut Self {
+        let item = PolyEvals::new(self.rng(), num_polys, degree, num_points);
+        self.push_item(item)
+    }
+
+    /// Reconstruction in the exponent from `t` shares, `num` times: the Lagrange coefficient
+    /// computation and the size-`t` G1 multi-exp are added as two items, so they are timed
+    /// separately.
+    pub fn lagrange_interpolation(&mut self, num: usize, t: usize) -> &mut Self {
+        self.group(|sim| { sim.push_item(LagrangeCoefficients::new(num, t)).g1_multi_exps(num, t); })
+    }
+    /// Same as `lagrange_interpolation`, with the shares in G2.
+    pub fn lagrange_interpolation_g2(&mut self, num: usize, t: usize) -> &mut Self {
+        self.group(|sim| { sim.push_item(LagrangeCoefficients::new(num, t)).g2_multi_exps(num, t); })
+    }
+    /// `num` forward and `num` inverse radix-2 FFTs of size `2^log_size`, as two items.
+    pub fn fft(&mut self, num: usize, log_size: u32) -> &mut Self {
+        let forward = Ffts::new(self.rng(), num, log_size, false);
+        let inverse = Ffts::new(self.rng(), num, log_size, true);
+        self.group(|sim| { sim.push_item(forward).push_item(inverse); })
+    }
+}
@@ -0,0 +1,63 @@
+use crate::communication::Element;
+use crate::presets;
+use crate::protocol::{BinOp, Expr, Protocol, SymbolicOp};
+use crate::workload::OpKind;
+
+
+/// What a validator does to admit the dealings of the PVSS `scheme` (one of "pvss", "groth",
+/// "groth21", "scrape", "schoenmakers") it receives, `r` per second. A run is one second's
+/// dealings, so the throughput is `r` over its time, and a machine keeps up while that is at
+/// least `r`. Each dealing is
+///
+/// - deserialized: every group element of the transcript and the sender's signature is
+///   decompressed and checked to be in its subgroup (scalars are only range checks, which are
+///   not counted),
+/// - checked to be signed by its sender: a BLS signature in G1 on the whole transcript, under a
+///   key in G2 the validator knows already, i.e. a hash to G1 of the transcript's bytes and a
+///   multi-pairing of size 2,
+/// - verified, as the scheme's "Verifier" phase.
+///
+/// The phases come in that order, and a dealing that fails one doesn't reach the next; the
+/// counts are those of a second in which every dealing is valid.
+pub fn admission_protocol(scheme: &str) -> Option<Protocol> {
+    let pvss = presets::by_name(scheme)?;
+    let rate = Expr::Param("r".to_string());
+    let per_second = |num: &Expr| Expr::Binary(Box::new(rate.clone()), BinOp::Mul, Box::new(num.clone()));
+    let transcript : Vec<_> = pvss.messages.iter()
+        .filter(|message| matches!(message.phase.as_deref(), Some("Dealer" | "Prover")))
+        .collect();
+    let elements = |element: Element| {
+        transcript.iter()
+            .filter(|message| message.element == element)
+            .map(|message| message.num.clone())
+            .reduce(|sum, num| Expr::Binary(Box::new(sum), BinOp::Add, Box::new(num)))
+    };
+    let transcript_bytes = transcript.iter()
+        .map(|message| Expr::Binary(Box::new(Expr::Const(message.element.size() as i64)), BinOp::Mul, Box::new(message.num.clone())))
+        .reduce(|sum, bytes| Expr::Binary(Box::new(sum), BinOp::Add, Box::new(bytes)))?;
+
+    let mut admission = Protocol::new(&format!("admission-{}", scheme))
+        .throughput("dealings", "r")
+        .phase("Deserialization")
+        .op(OpKind::DeserializeG1 { compressed: true }, "r", "1").label("signatures");
+    admission.checks = pvss.checks.clone();
+    for (element, kind) in [(Element::G1, OpKind::DeserializeG1 { compressed: true }), (Element::G2, OpKind::DeserializeG2 { compressed: true }), (Element::Gt, OpKind::DeserializeGt)] {
+        if let Some(num) = elements(element) {
+            admission.ops.push(SymbolicOp { kind, num: per_second(&num), size: Expr::Const(1), label: Some("transcripts".to_string()), phase: Some("Deserialization".to_string()), static_g2: None });
+        }
+    }
+    admission = admission.phase("Signature verification");
+    admission.ops.push(SymbolicOp {
+        kind: OpKind::G1HashToCurve,
+        num: rate.clone(),
+        size: transcript_bytes,
+        label: Some("signed transcripts".to_string()),
+        phase: Some("Signature verification".to_string()),
+        static_g2: None,
+    });
+    admission = admission.op(OpKind::MultiPairings, "r", "2").label("signature checks").static_g2("signer keys", 2, "n + 1");
+    for op in pvss.ops.iter().filter(|op| op.phase.as_deref() == Some("Verifier")) {
+        admission.ops.push(SymbolicOp { num: per_second(&op.num), phase: Some("Transcript verification".to_string()), ..op.clone() });
+    }
+    Some(admission)
+}
@@ -0,0 +1,44 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Aggregatable PVSS (Gurkan et al. '21) for `n` parties, threshold `t` and `m` dealers. Each
+/// dealer publishes `F_j = g1^{a_j}`, `u = g2^{a_0}`, share commitments `A_i = g1^{p(i)}`,
+/// encryptions `Y_i = ek_i^{p(i)}` in G2 and a signature of knowledge of `a_0`. Transcripts
+/// aggregate component-wise. The aggregate is verified with SCRAPE's dual-code check, one pairing
+/// check per encryption, and the `m` signatures of knowledge that attribute it to its dealers.
+pub fn aggregatable_protocol() -> Protocol {
+    Protocol::new("aggregatable")
+        .phase("Dealer")
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G1Exps, "t + 1", "1").label("coefficient commitments")
+        .op(OpKind::G2Exps, "1", "1").label("secret commitment")
+        .op(OpKind::G1Exps, "n", "1").label("share commitments")
+        .op(OpKind::G2Exps, "n", "1").label("share encryptions")
+        .op(OpKind::G1Exps, "2", "1").label("signature of knowledge")
+        .message("commitments", Element::G1, "t + 1 + n")
+        .message("commitments", Element::G2, "1")
+        .message("encrypted shares", Element::G2, "n")
+        .message("signature of knowledge", Element::G1, "1")
+        .message("signature of knowledge", Element::G2, "1")
+        .phase("Aggregator")
+        .op(OpKind::G1Additions, "(m - 1)*(t + 1 + n + 1)", "1").label("G1 components")
+        .op(OpKind::G2Additions, "(m - 1)*(n + 1)", "1").label("G2 components")
+        .message("aggregated commitments", Element::G1, "t + 1 + n")
+        .message("aggregated commitments", Element::G2, "1")
+        .message("aggregated encrypted shares", Element::G2, "n")
+        .message("signatures of knowledge", Element::G1, "m")
+        .message("signatures of knowledge", Element::G2, "m")
+        .message("dealer weights", Element::Scalar, "m")
+        .phase("Verifier")
+        .op(OpKind::MultiPairings, "m", "2").label("signatures of knowledge")
+        .op(OpKind::G1Additions, "m - 1", "1").label("dealer contributions")
+        .op(OpKind::MultiPairings, "1", "2").label("secret commitment")
+        .op(OpKind::ScalarMuls, "n*(n - t - 2) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - 2)", "1")
+        .op(OpKind::G1MultiExps, "1", "n").label("dual code check")
+        .op(OpKind::G1MultiExps, "1", "t + 1").label("coefficient commitment check")
+        .op(OpKind::MultiPairings, "n", "2").label("encryption checks").static_g2("encryption keys", 1, "n")
+}
@@ -0,0 +1,28 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// ALBATROSS's packed PVSS (Cascudo–David '20) for `n` parties, threshold `t` and packing
+/// factor `l`: one transcript shares `l` secrets, the values of a polynomial `p` of degree
+/// `t + l - 1` at `0, -1, ..., -(l - 1)`. Shares are committed, encrypted and checked as in
+/// SCRAPE (`scrape_protocol`), with the dual code of the larger degree. Divide by `l` for the
+/// per-secret cost.
+pub fn albatross_protocol() -> Protocol {
+    Protocol::new("albatross")
+        .phase("Dealer")
+        .op(OpKind::ScalarMuls, "n*(t + l - 1)", "1")
+        .op(OpKind::ScalarAdds, "n*(t + l - 1)", "1")
+        .op(OpKind::G1Exps, "n", "1").label("share commitments")
+        .op(OpKind::G2Exps, "n", "1").label("share encryptions")
+        .message("share commitments", Element::G1, "n")
+        .message("encrypted shares", Element::G2, "n")
+        .phase("Verifier")
+        .op(OpKind::ScalarMuls, "n*(n - t - l - 1) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - l - 1)", "1")
+        .op(OpKind::G1MultiExps, "1", "n").label("dual code check")
+        .op(OpKind::MultiPairings, "n", "2").label("encryption checks").static_g2("public keys", 1, "n")
+        .phase("Reconstructor")
+        // each of the l secrets is interpolated in the exponent from the same t + l shares
+        .op(OpKind::LagrangeInterpolation, "l", "t + l").label("secret reconstruction")
+}
@@ -0,0 +1,37 @@
+use crate::protocol::Protocol;
+use crate::sigma::Sigma;
+use crate::workload::OpKind;
+
+
+/// Verification of `m` pairing equations `prod_j e(a_ij, b_ij) = e(c_i, g2)` of `s` pairings each,
+/// the last with a G2 point shared by all equations: one by one, as `m` multi-pairings of size
+/// `s`, or batched through a random linear combination, raising the `s - 1` left-hand G1 points
+/// of equation `i` to a random `r_i`, combining the `c_i` into one multi-exp and checking a single
+/// multi-pairing of size `m*(s - 1) + 1`. The random exponents have 128 bits, the curve's
+/// security level, which halves the cost of the exps next to full-size ones. `sweep
+/// batch-pairing m=1..=64:*2 s=2 --crossover Individual,Batched` finds the `m` from which
+/// batching pays off.
+pub fn batch_pairing_protocol() -> Protocol {
+    Protocol::new("batch-pairing")
+        .phase("Individual")
+        .op(OpKind::MultiPairings, "m", "s").label("equation checks")
+        .phase("Batched")
+        .op(OpKind::G1ShortExps { bits: 128 }, "m*(s - 1)", "1").label("randomized left-hand sides")
+        .op(OpKind::G1ShortMultiExps { bits: 128 }, "1", "m").label("combined right-hand side")
+        .op(OpKind::MultiPairings, "1", "m*(s - 1) + 1").label("combined check")
+}
+
+/// Verification of `n` proofs of `protocol` one by one, or batched through a random linear
+/// combination (see module `sigma`). `sweep batch-dleq n=1..=64:*2 --crossover
+/// Individual,Batched` finds the `n` from which batching pays off.
+pub fn batch_sigma_protocol(protocol: Sigma) -> Protocol {
+    let name = match protocol {
+        Sigma::Schnorr => "batch-schnorr",
+        Sigma::Dleq => "batch-dleq",
+    };
+    Protocol::new(name)
+        .phase("Individual")
+        .op(OpKind::SigmaVerifications { protocol, batched: false, invalid_percent: 0 }, "1", "n").label("proof checks")
+        .phase("Batched")
+        .op(OpKind::SigmaVerifications { protocol, batched: true, invalid_percent: 0 }, "1", "n").label("combined check")
+}
@@ -0,0 +1,32 @@
+use crate::communication::Element;
+use crate::presets;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// A drand-style randomness beacon on threshold BLS (signatures in G1, keys in G2, as drand's
+/// unchained G1 scheme): the key comes from a DKG of the PVSS in module `pvss` among the `n`
+/// nodes, its phases as in `presets::dkg_protocol`, run once; every round then costs one node
+/// a partial signature on the hashed round number, the checks of the `t + 1` partial signatures
+/// it aggregates (public key shares cached from the DKG) and the check of the aggregate, after
+/// which a light client verifies the round's randomness with one multi-pairing against the
+/// group key. Setup and round costs appear side by side, so how many rounds amortize the DKG
+/// can be read off.
+pub fn beacon_protocol() -> Protocol {
+    let mut beacon = presets::dkg_protocol("pvss").expect("the pvss DKG exists");
+    beacon.name = "beacon".to_string();
+    beacon
+        .phase("Partial signature")
+        .op(OpKind::G1HashToCurve, "1", "32").label("round message")
+        .op(OpKind::G1Exps, "1", "1").label("partial signature")
+        .message("partial signature", Element::G1, "1")
+        .phase("Aggregation")
+        .op(OpKind::G1HashToCurve, "1", "32").label("round message")
+        .op(OpKind::MultiPairings, "t + 1", "2").label("partial signature checks").static_g2("committee keys", 2, "n + 2")
+        .op(OpKind::LagrangeInterpolation, "1", "t + 1").label("aggregation")
+        .op(OpKind::MultiPairings, "1", "2").label("signature check").static_g2("committee keys", 2, "n + 2")
+        .message("round signature", Element::G1, "1")
+        .phase("Light client")
+        .op(OpKind::G1HashToCurve, "1", "32").label("round message")
+        .op(OpKind::MultiPairings, "1", "2").label("signature check").static_g2("committee keys", 2, "n + 2")
+}
@@ -0,0 +1,100 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Threshold BLS with signatures in G1 and keys in G2, for `n` signers and threshold `t`: one
+/// signer's partial signature `H(m)^{sk_i}`, the combiner's checks of all `n` partial signatures
+/// and the aggregation of `t + 1` of them by Lagrange interpolation in the exponent, followed by
+/// a check of the result against the group key. The partial signatures are checked one
+/// multi-pairing `e(σ_i, g2) = e(H(m), pk_i)` at a time or, if `batched`, all at once through a
+/// random linear combination with 128-bit exponents.
+pub fn threshold_bls_protocol(batched: bool) -> Protocol {
+    let protocol = Protocol::new(if batched { "threshold-bls-batched" } else { "threshold-bls" })
+        .phase("Signer")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::G1Exps, "1", "1").label("partial signature")
+        .message("partial signature", Element::G1, "1")
+        .phase("Combiner")
+        .op(OpKind::G1HashToCurve, "1", "32");
+    let protocol = if batched {
+        protocol
+            .op(OpKind::G1ShortMultiExps { bits: 128 }, "1", "n").label("combined partial signatures")
+            .op(OpKind::G2ShortMultiExps { bits: 128 }, "1", "n").label("combined public keys")
+            .op(OpKind::MultiPairings, "1", "2").label("partial signature check").static_g2("committee keys", 1, "n + 2")
+    } else {
+        protocol.op(OpKind::MultiPairings, "n", "2").label("partial signature checks").static_g2("committee keys", 2, "n + 2")
+    };
+    protocol
+        .op(OpKind::LagrangeInterpolation, "1", "t + 1").label("aggregation")
+        .op(OpKind::MultiPairings, "1", "2").label("signature check").static_g2("committee keys", 2, "n + 2")
+        .message("signature", Element::G1, "1")
+}
+
+/// Verification of a certificate signed by `k` committee members, as its two phases: an
+/// aggregate signature over `k` distinct messages (`k` hashes and one multi-pairing of size
+/// `k + 1`), and a multisignature on a single message, whose public keys are added up first. The
+/// throughput is one certificate per phase total.
+pub fn bls_aggregate_protocol() -> Protocol {
+    Protocol::new("bls-aggregate")
+        .phase("Aggregate verification")
+        .op(OpKind::G1HashToCurve, "k", "32")
+        .op(OpKind::MultiPairings, "1", "k + 1")
+        .message("aggregate signature", Element::G1, "1")
+        .phase("Multisig verification")
+        .op(OpKind::G2Additions, "k - 1", "1").label("aggregate public key")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::MultiPairings, "1", "2")
+        .message("multisignature", Element::G1, "1")
+}
+
+/// The BLS VRF: evaluation is a signature `π = H(x)^{sk}` (the output is a hash of it, which is
+/// not counted), verification a multi-pairing `e(π, g2) = e(H(x), pk)`. `m` proofs under
+/// different keys are batch-verified as `e(Σ r_i π_i, g2) = ∏ e(H(x_i)^{r_i}, pk_i)`, with
+/// 128-bit `r_i`.
+pub fn bls_vrf_protocol() -> Protocol {
+    Protocol::new("bls-vrf")
+        .phase("Evaluate")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::G1Exps, "1", "1")
+        .message("proof", Element::G1, "1")
+        .phase("Verify")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::MultiPairings, "1", "2")
+        .phase("Batch verify")
+        .op(OpKind::G1HashToCurve, "m", "32")
+        .op(OpKind::G1ShortMultiExps { bits: 128 }, "1", "m").label("combined proofs")
+        .op(OpKind::G1ShortExps { bits: 128 }, "m", "1").label("randomized inputs")
+        .op(OpKind::MultiPairings, "1", "m + 1")
+}
+
+/// BLS signatures with proofs of possession (signatures in G1, keys in G2), as committees use
+/// them against rogue-key attacks: key generation, the PoP `H_pop(pk)^{sk}` over the 96-byte
+/// compressed key and its check, a signature and its check, and the batch verification of `m`
+/// signatures on distinct messages as in `bls_vrf_protocol`. When the committee changes, each
+/// member checks the PoPs of all `n` new members.
+pub fn bls_pop_protocol() -> Protocol {
+    Protocol::new("bls-pop")
+        .phase("Key generation")
+        .op(OpKind::G2Exps, "1", "1").label("public key")
+        .message("public key", Element::G2, "1")
+        .phase("PoP generation")
+        .op(OpKind::G1HashToCurve, "1", "96")
+        .op(OpKind::G1Exps, "1", "1")
+        .message("proof of possession", Element::G1, "1")
+        .phase("PoP verification")
+        .op(OpKind::G1HashToCurve, "n", "96")
+        .op(OpKind::MultiPairings, "n", "2").label("PoP checks")
+        .phase("Sign")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::G1Exps, "1", "1")
+        .message("signature", Element::G1, "1")
+        .phase("Verify")
+        .op(OpKind::G1HashToCurve, "1", "32")
+        .op(OpKind::MultiPairings, "1", "2")
+        .phase("Batch verify")
+        .op(OpKind::G1HashToCurve, "m", "32")
+        .op(OpKind::G1ShortMultiExps { bits: 128 }, "1", "m").label("combined signatures")
+        .op(OpKind::G1ShortExps { bits: 128 }, "m", "1").label("randomized messages")
+        .op(OpKind::MultiPairings, "1", "m + 1")
+}
@@ -0,0 +1,24 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Verification of an aggregated Bulletproofs range proof for `m` values of `b` bits each, in
+/// G1 and with the inner-product argument folded into a single multi-exp of size
+/// `2bm + 2 log(bm) + 7`. The field work is the inversion of the `log(bm)` round challenges,
+/// the `s` vector (one multiplication per entry, built up over the rounds) and the generator
+/// exponents.
+pub fn bulletproofs_protocol() -> Protocol {
+    Protocol::new("bulletproofs")
+        .phase("Prover")
+        .message("proof", Element::G1, "2*log2(b*m) + 4")
+        .message("proof", Element::Scalar, "5")
+        .phase("Verifier")
+        .op(OpKind::G1SubgroupChecks, "2*log2(b*m) + 4", "1")
+        .op(OpKind::ScalarBatchInversions, "1", "log2(b*m) + 1").label("challenge inversions")
+        .op(OpKind::ScalarMuls, "2*log2(b*m)", "1").label("challenge squares")
+        .op(OpKind::ScalarMuls, "b*m", "1").label("s vector")
+        .op(OpKind::ScalarMuls, "4*b*m", "1").label("generator exponents")
+        .op(OpKind::ScalarAdds, "2*b*m", "1")
+        .op(OpKind::G1MultiExps, "1", "2*b*m + 2*log2(b*m) + 7")
+}
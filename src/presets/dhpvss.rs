@@ -0,0 +1,49 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// DHPVSS (Cascudo–David–Shlomovits–Varlakov, "YOLO YOSO", '22) for `n` parties and threshold
+/// `t`, in G1 without pairings. The dealer, with key `pk_D = g^{sk_D}`, encrypts the share
+/// commitments `A_i = g^{p(i)}` as `C_i = A_i pk_i^{sk_D}` and proves all of them with one DLEQ
+/// proof: for a codeword `v` of the Reed–Solomon dual code drawn from a hash of the transcript,
+/// `∑ v_i A_i = 0`, so `V = ∑ v_i C_i` is `U^{sk_D}` for `U = ∑ v_i pk_i`. The verifier
+/// recomputes `U` and `V` and checks the proof. A reconstructor decrypts its share, `A_i = C_i -
+/// pk_D^{sk_i}`, with a DLEQ proof of correct decryption, checks the other `t` decryptions it
+/// collects and interpolates `g^{p(0)}` in the exponent.
+pub fn dhpvss_protocol() -> Protocol {
+    Protocol::new("dhpvss")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G1Exps, "n", "1").label("share commitments")
+        .op(OpKind::G1Exps, "n", "1").label("Diffie-Hellman keys")
+        .op(OpKind::G1Additions, "n", "1").label("share encryptions")
+        // v_i = w_i m(i) for a hashed m of degree n - t - 2, the w_i precomputed as in SCRAPE
+        .op(OpKind::ScalarMuls, "n*(n - t - 2) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - 2)", "1")
+        .op(OpKind::G1MultiExps, "2", "n").label("dual code sums")
+        .op(OpKind::G1Exps, "2", "1").label("DLEQ commitments")
+        .op(OpKind::ScalarMuls, "1", "1").label("DLEQ response")
+        .op(OpKind::ScalarAdds, "1", "1")
+        .message("encrypted shares", Element::G1, "n")
+        .message("DLEQ proof", Element::Scalar, "2")
+        .phase("Verifier")
+        .op(OpKind::ScalarMuls, "n*(n - t - 2) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - 2)", "1")
+        .op(OpKind::G1MultiExps, "2", "n").label("dual code sums")
+        // g^z pk_D^{-c} and U^z V^{-c}, then one hash
+        .op(OpKind::G1MultiExps, "2", "2").label("DLEQ check")
+        .phase("Reconstructor")
+        .op(OpKind::G1Exps, "1", "1").label("share decryption")
+        .op(OpKind::G1Additions, "1", "1").label("share decryption")
+        .op(OpKind::G1Exps, "2", "1").label("decryption proof")
+        .op(OpKind::ScalarMuls, "1", "1").label("decryption proof")
+        .op(OpKind::ScalarAdds, "1", "1")
+        .op(OpKind::G1Additions, "t", "1").label("decryption keys")
+        .op(OpKind::G1MultiExps, "2*t", "2").label("decryption proof checks")
+        .op(OpKind::LagrangeInterpolation, "1", "t + 1").label("interpolation")
+        .message("decrypted shares", Element::G1, "t + 1")
+        .message("decryption proofs", Element::Scalar, "2*(t + 1)")
+}
@@ -0,0 +1,116 @@
+use crate::communication::Element;
+use crate::presets;
+use crate::protocol::{BinOp, Expr, Params, Protocol, SymbolicMessage, SymbolicOp};
+use crate::workload::OpKind;
+
+
+/// A DKG run from the point of view of one of its `n` parties, with every party dealing one
+/// transcript of the PVSS `scheme` (one of "pvss", "groth", "groth21", "scrape",
+/// "schoenmakers"). Each party
+///
+/// - deals (the scheme's "Dealer" or "Prover" phase),
+/// - verifies the `n - 1` other transcripts (its "Verifier" phase, `n - 1` times), assuming
+///   nobody complains,
+/// - derives its keys: it adds up the coefficient commitments of all transcripts, which gives
+///   the public key and everyone's verification keys, and its encrypted shares, which it then
+///   decrypts once (the scheme's "Party" phase, if it has one).
+///
+/// Every party does the same work, so the network-wide compute is `n` times the total.
+pub fn dkg_protocol(scheme: &str) -> Option<Protocol> {
+    let (commitment_additions, share_additions) = groups(scheme)?;
+    let pvss = presets::by_name(scheme)?;
+    let in_phase = |names: &[&str]| -> Vec<SymbolicOp> {
+        pvss.ops.iter()
+            .filter(|op| op.phase.as_deref().is_some_and(|phase| names.contains(&phase)))
+            .cloned()
+            .collect()
+    };
+    let mut dkg = Protocol::new(&format!("dkg-{}", scheme));
+    dkg.checks = pvss.checks.clone();
+    for op in in_phase(&["Dealer", "Prover"]) {
+        dkg.ops.push(SymbolicOp { phase: Some("Dealing".to_string()), ..op });
+    }
+    dkg.messages.extend(pvss.messages.iter()
+        .filter(|message| matches!(message.phase.as_deref(), Some("Dealer" | "Prover")))
+        .map(|message| SymbolicMessage { phase: Some("Dealing".to_string()), ..message.clone() }));
+    let others = Expr::parse("n - 1").unwrap();
+    for op in in_phase(&["Verifier"]) {
+        let num = Expr::Binary(Box::new(others.clone()), BinOp::Mul, Box::new(op.num.clone()));
+        dkg.ops.push(SymbolicOp { num, phase: Some("Verification".to_string()), ..op });
+    }
+    dkg = dkg.phase("Key derivation")
+        .op(commitment_additions, "(n - 1)*(t + 1)", "1").label("combined commitments")
+        .op(share_additions, "n - 1", "1").label("combined encrypted shares");
+    for op in in_phase(&["Party"]) {
+        dkg.ops.push(SymbolicOp { phase: Some("Key derivation".to_string()), ..op });
+    }
+    Some(dkg)
+}
+
+// the additions of the groups of the scheme's commitments and of its encrypted shares
+fn groups(scheme: &str) -> Option<(OpKind, OpKind)> {
+    match scheme {
+        "pvss" => Some((OpKind::G2Additions, OpKind::G1Additions)),
+        "groth" | "groth21" => Some((OpKind::G2Additions, OpKind::G1Additions)),
+        "scrape" => Some((OpKind::G1Additions, OpKind::G2Additions)),
+        "schoenmakers" => Some((OpKind::G1Additions, OpKind::G1Additions)),
+        _ => None,
+    }
+}
+
+// the exps, multi-exps and equality checks of the group of `additions`, and its elements
+fn group_ops(additions: OpKind) -> (OpKind, OpKind, OpKind, Element) {
+    match additions {
+        OpKind::G1Additions => (OpKind::G1Exps, OpKind::G1MultiExps, OpKind::G1EqualityChecks, Element::G1),
+        _ => (OpKind::G2Exps, OpKind::G2MultiExps, OpKind::G2EqualityChecks, Element::G2),
+    }
+}
+
+/// `dkg_protocol` in a pessimistic run, with a "Complaints" phase before key derivation in
+/// which `f` of the dealers are faulty. Each faulty dealer is accused by `t` parties, as many as
+/// can accuse it without disqualifying it outright. This party, the worst off,
+///
+/// - is among the accusers of every faulty dealer, filing each complaint as its decrypted share
+///   with a DLEQ proof that it decrypts the encrypted share correctly (two exps in the group of
+///   the encrypted shares),
+/// - checks all `f*t` complaints, each proof taking four exps,
+/// - checks the share the dealer reveals for every complaint against the dealer's commitments:
+///   the share's commitment from the `t + 1` coefficient commitments, a multi-exp, and the
+///   share in the exponent, compared to it.
+///
+/// Faulty dealers that reveal valid shares stay qualified, so key derivation is unchanged.
+pub fn dkg_complaints_protocol(scheme: &str) -> Option<Protocol> {
+    let (commitment_additions, share_additions) = groups(scheme)?;
+    let (share_exps, _, _, share) = group_ops(share_additions);
+    let (commitment_exps, commitment_multi_exps, commitment_checks, _) = group_ops(commitment_additions);
+    let complaints = Protocol::new("complaints")
+        .phase("Complaints")
+        .op(share_exps, "2*f", "1").label("complaint proofs")
+        .message("complaints: decrypted shares", share, "f")
+        .message("complaints: decryption proofs", Element::Scalar, "2*f")
+        .op(share_exps, "4*f*t", "1").label("complaint checks")
+        .op(commitment_multi_exps, "f*t", "t + 1").label("revealed shares: commitments")
+        .op(commitment_exps, "f*t", "1").label("revealed shares in the exponent")
+        .op(commitment_checks, "f*t", "1").label("revealed share checks");
+    let mut dkg = dkg_protocol(scheme)?.check(check_complaints);
+    dkg.name = format!("dkg-{}-complaints", scheme);
+    let key_derivation = dkg.ops.iter().position(|op| op.phase.as_deref() == Some("Key derivation")).unwrap_or(dkg.ops.len());
+    dkg.ops.splice(key_derivation..key_derivation, complaints.ops);
+    dkg.messages.extend(complaints.messages);
+    Some(dkg)
+}
+
+// at most the `n - 1` other dealers are faulty, and `t` accusers leave a dealer qualified only if
+// there are more than `t` parties
+fn check_complaints(params: &Params) -> Result<(), String> {
+    let (Some(n), Some(t), Some(f)) = (params.get("n"), params.get("t"), params.get("f")) else {
+        return Ok(());
+    };
+    if t >= n {
+        return Err(format!("threshold t={} must be below n={}", t, n));
+    }
+    if f > n - 1 {
+        return Err(format!("at most n - 1 = {} dealers can be faulty, not f={}", n - 1, f));
+    }
+    Ok(())
+}
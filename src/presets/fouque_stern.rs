@@ -0,0 +1,51 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Fouque–Stern's one-round publicly verifiable sharing (PKC '01) for `n` parties and threshold
+/// `t`, adapted to run in G1 without Paillier, as a classical baseline without pairings. The
+/// dealer publishes Feldman commitments `C_j = g^{a_j}` and encrypts every share `s_i = p(i)`
+/// to its receiver in `m = ⌈255/b⌉` chunks of `b` bits with exponential ElGamal under fresh
+/// randomness, `R_ij = g^{r_ij}` and `E_ij = g^{s_ij} pk_i^{r_ij}`, where Fouque–Stern use one
+/// Paillier ciphertext. Its proof is theirs: per share, that the plaintexts recombine to the
+/// discrete log of `X_i = g^{p(i)}`, with responses over the integers whose size bounds the
+/// chunks, and a single Fiat–Shamir challenge. The verifier recomputes every `X_i` from the
+/// commitments and checks the proofs; a party decrypts its chunks by baby-step giant-step (as
+/// in the `reconstruction` preset).
+pub fn fouque_stern_protocol() -> Protocol {
+    let chunks = "n*((254 + b)/b)";
+    let giant_steps = "((254 + b)/b)*(2^(b/2)/2)";
+    Protocol::new("fouque-stern")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G1Exps, "t + 1", "1").label("coefficient commitments")
+        .op(OpKind::G1Exps, chunks, "1").label("chunk randomness")
+        .op(OpKind::G1MultiExps, chunks, "2").label("chunk encryptions")
+        // g^{w_rj} and g^{w_sj} pk_i^{w_rj} per chunk, g^{∑_j 2^{bj} w_sj} per share
+        .op(OpKind::G1Exps, chunks, "1").label("proof commitments")
+        .op(OpKind::G1MultiExps, chunks, "2").label("proof commitments")
+        .op(OpKind::G1Exps, "n", "1").label("proof commitments")
+        .op(OpKind::ScalarMuls, "2*n*((254 + b)/b)", "1").label("proof responses")
+        .op(OpKind::ScalarAdds, "2*n*((254 + b)/b)", "1")
+        .message("coefficient commitments", Element::G1, "t + 1")
+        .message("chunk ciphertexts", Element::G1, "2*n*((254 + b)/b)")
+        .message("proofs", Element::Scalar, "2*n*((254 + b)/b) + 1")
+        .phase("Verifier")
+        // powers i^j of every evaluation point
+        .op(OpKind::ScalarMuls, "n*t", "1").label("evaluation point powers")
+        .op(OpKind::G1MultiExps, "n", "t + 1").label("share commitments from coefficients")
+        // g^{z_rj} R_ij^{-c} and g^{z_sj} pk_i^{z_rj} E_ij^{-c} per chunk, g^{∑_j 2^{bj} z_sj} X_i^{-c}
+        // per share, then one hash
+        .op(OpKind::G1MultiExps, chunks, "2").label("proof checks")
+        .op(OpKind::G1MultiExps, chunks, "3").label("proof checks")
+        .op(OpKind::ScalarMuls, chunks, "1").label("proof checks")
+        .op(OpKind::G1MultiExps, "n", "2").label("proof checks")
+        .phase("Party")
+        .op(OpKind::G1Exps, "(254 + b)/b", "1").label("chunk unmasking")
+        .op(OpKind::G1Additions, giant_steps, "1").label("giant steps")
+        .op(OpKind::G1BatchNormalizations, "((254 + b)/b)*((2^(b/2)/2 + 63)/64)", "min(2^(b/2)/2, 64)").label("giant steps")
+        .op(OpKind::SerializeG1 { compressed: true }, giant_steps, "1").label("baby-step lookups")
+}
@@ -0,0 +1,172 @@
+use crate::communication::{format_bytes, Communication, Element};
+use crate::error::SimulationError;
+use crate::protocol::{Params, Protocol};
+use crate::workload::{Op, OpKind, Workload};
+
+use std::fmt;
+
+
+/// Groth's PVSS in terms of the number of parties `n`, chunks per share `k`, threshold `t` and
+/// chunk size `l` in bits, as a "Prover" and a "Verifier" phase. The parameters are checked as
+/// `GrothParams::new` checks them.
+pub fn groth_protocol() -> Protocol {
+    Protocol::new("groth")
+        .check(|params| GrothParams::from_params(params).map(|_| ()).map_err(|e| e.to_string()))
+        .phase("Prover")
+        .op(OpKind::G1Exps, "n + 2*k + l + 2", "1")
+        .op(OpKind::G1MultiExps, "2", "n")
+        .op(OpKind::G1MultiExps, "n*k + n + k + l + 1", "2")
+        .op(OpKind::G2Exps, "t + 1", "1").label("coefficient commitments")
+        // only the ciphertexts and commitments; the proofs' sizes aren't modeled
+        .message("coefficient commitments", Element::G2, "t + 1")
+        .message("chunk ciphertexts", Element::G1, "n*k + k")
+        .phase("Verifier")
+        .op(OpKind::G1Exps, "n + 2", "1")
+        .op(OpKind::G1MultiExps, "1", "2")
+        .op(OpKind::G1MultiExps, "2", "n + 1")
+        .op(OpKind::G1MultiExps, "n", "k + 1")
+        .op(OpKind::G1MultiExps, "1", "l + 1")
+        .op(OpKind::G1MultiExps, "1", "k*n*l + l + 1")
+        .op(OpKind::G1MultiExps, "1", "n + 2")
+        .op(OpKind::G1MultiExps, "2", "k")
+        .op(OpKind::G2Exps, "1", "1")
+        .op(OpKind::G2MultiExps, "1", "t + 1")
+        .op(OpKind::G2MultiExps, "1", "k")
+        .op(OpKind::PairingEqualityChecks { rhs_size: 2 }, "1", "1").label("pairing check")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrothParamsError {
+    NoParties,
+    /// `t` must be below `n`
+    ThresholdTooLarge { t: usize, n: usize },
+    NoChunks,
+    NoChunkBits,
+    /// `k*n*l`, about the bases of the verifier's largest multi-exp, above `GrothParams::MAX_BASES`
+    TooLarge { n: usize, k: usize, l: usize },
+    /// a parameter the protocol's counts refer to, missing or negative in `Params`
+    Missing(&'static str),
+}
+
+impl fmt::Display for GrothParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrothParamsError::NoParties => write!(f, "n must be at least 1"),
+            GrothParamsError::ThresholdTooLarge { t, n } => write!(f, "threshold t={} must be below n={}", t, n),
+            GrothParamsError::NoChunks => write!(f, "k must be at least 1"),
+            GrothParamsError::NoChunkBits => write!(f, "l must be at least 1"),
+            GrothParamsError::TooLarge { n, k, l } => write!(f, "k*n*l must be at most 2^60 (k={}, n={}, l={})", k, n, l),
+            GrothParamsError::Missing(name) => write!(f, "{} must be set to a non-negative integer", name),
+        }
+    }
+}
+
+impl std::error::Error for GrothParamsError {}
+
+/// Parameters of Groth's PVSS, checked on construction: `n` parties, `k` chunks per share,
+/// threshold `t < n` and `l`-bit chunks, with `k*n*l` at most `MAX_BASES`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrothParams {
+    n: usize,
+    k: usize,
+    t: usize,
+    l: usize,
+    // the same, for `groth_protocol`
+    params: Params,
+}
+
+impl Default for GrothParams {
+    /// The parameters the default command simulates.
+    fn default() -> Self {
+        Self::new(1024, 16, 660, 16).expect("the default parameters are valid")
+    }
+}
+
+impl GrothParams {
+    /// The largest `k*n*l`, about the bases of the verifier's largest multi-exp, for which
+    /// every count of `groth_protocol` fits an i64.
+    pub const MAX_BASES: u64 = 1 << 60;
+
+    pub fn new(n: usize, k: usize, t: usize, l: usize) -> Result<Self, GrothParamsError> {
+        if n == 0 {
+            return Err(GrothParamsError::NoParties);
+        }
+        if t >= n {
+            return Err(GrothParamsError::ThresholdTooLarge { t, n });
+        }
+        if k == 0 {
+            return Err(GrothParamsError::NoChunks);
+        }
+        if l == 0 {
+            return Err(GrothParamsError::NoChunkBits);
+        }
+        // every parameter is at most k*n*l (t is below n), so each fits an i64 as well
+        let too_large = || GrothParamsError::TooLarge { n, k, l };
+        if (k as u64).checked_mul(n as u64).and_then(|kn| kn.checked_mul(l as u64)).is_none_or(|bases| bases > Self::MAX_BASES) {
+            return Err(too_large());
+        }
+        let value = |x: usize| i64::try_from(x).map_err(|_| too_large());
+        let params = Params::new().set("n", value(n)?).set("k", value(k)?).set("t", value(t)?).set("l", value(l)?);
+        Ok(Self { n, k, t, l, params })
+    }
+
+    /// The parameters `n`, `k`, `t` and `l` of `params`, checked as `new` checks them.
+    pub fn from_params(params: &Params) -> Result<Self, GrothParamsError> {
+        let value = |name: &'static str| params.get(name).and_then(|x| usize::try_from(x).ok()).ok_or(GrothParamsError::Missing(name));
+        Self::new(value("n")?, value("k")?, value("t")?, value("l")?)
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn t(&self) -> usize {
+        self.t
+    }
+
+    pub fn l(&self) -> usize {
+        self.l
+    }
+
+    pub fn params(&self) -> Params {
+        self.params.clone()
+    }
+
+    pub fn workload(&self) -> Workload {
+        groth_protocol().evaluate(&self.params).expect("`new` bounds the parameters so that no count overflows")
+    }
+
+    /// `workload`, with every op checked (`Op::check`), e.g. against the size limits of
+    /// multi-exps.
+    pub fn try_workload(&self) -> Result<Workload, SimulationError> {
+        let workload = self.workload();
+        workload.ops.iter().try_for_each(Op::check)?;
+        Ok(workload)
+    }
+
+    pub fn communication(&self) -> Communication {
+        groth_protocol().communication(&self.params).expect("`new` bounds the parameters so that no count overflows")
+    }
+
+    /// Bytes of the transcript's ciphertexts and commitments.
+    pub fn transcript_bytes(&self) -> usize {
+        self.communication().total()
+    }
+
+    /// An upper bound on the bytes of the proofs: every G1 element the prover computes besides
+    /// the chunk ciphertexts, `2n + 2k + 2l + 5` of them. Scalars aren't counted.
+    pub fn proof_bytes(&self) -> usize {
+        (2 * self.n + 2 * self.k + 2 * self.l + 5) * Element::G1.size()
+    }
+}
+
+impl fmt::Display for GrothParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "n={}, k={}, t={}, l={} (transcript {}, proofs at most {})",
+            self.n, self.k, self.t, self.l, format_bytes(self.transcript_bytes()), format_bytes(self.proof_bytes()))
+    }
+}
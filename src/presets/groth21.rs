@@ -0,0 +1,47 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Groth's non-interactive DKG dealing ('21, as used in DFINITY's NIDKG) for `n` receivers,
+/// threshold `t`, chunks of `b` bits and `l` repetitions of the chunking proof. Each share is
+/// split into `m = ⌈255/b⌉` chunks, encrypted with chunked ElGamal in G1 under randomness shared
+/// by all receivers, so `C_ij = pk_i^{r_j} g1^{s_ij}` and `R_j = g1^{r_j}`. The forward-secure key
+/// tree is modeled by one G2 element per chunk and its pairing check. The dealer proves
+/// correct sharing against the Feldman commitments `A_k = g2^{a_k}`, and proves that every chunk
+/// is small with an approximate range proof.
+pub fn groth21_protocol() -> Protocol {
+    Protocol::new("groth21")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G2Exps, "t + 1", "1").label("coefficient commitments")
+        .op(OpKind::G1Exps, "(254 + b)/b", "1").label("chunk randomness")
+        .op(OpKind::G1MultiExps, "n*((254 + b)/b)", "2").label("chunk encryptions")
+        .op(OpKind::G2Exps, "(254 + b)/b", "1").label("forward-secure components")
+        .op(OpKind::G1Exps, "1", "1").label("sharing proof")
+        .op(OpKind::G1MultiExps, "1", "n + 1").label("sharing proof")
+        .op(OpKind::G2Exps, "1", "1").label("sharing proof")
+        .op(OpKind::G1Exps, "l", "1").label("chunking proof commitments")
+        .op(OpKind::G1MultiExps, "l", "n + 1").label("chunking proof commitments")
+        .op(OpKind::ScalarMuls, "l*n*((254 + b)/b)", "1").label("chunking proof responses")
+        .op(OpKind::ScalarAdds, "l*n*((254 + b)/b)", "1")
+        .message("coefficient commitments", Element::G2, "t + 1")
+        .message("chunk ciphertexts", Element::G1, "(n + 1)*((254 + b)/b)")
+        .message("forward-secure components", Element::G2, "(254 + b)/b")
+        .message("sharing proof", Element::G1, "2")
+        .message("sharing proof", Element::G2, "1")
+        .message("sharing proof", Element::Scalar, "2")
+        .message("chunking proof", Element::G1, "2*l")
+        .message("chunking proof", Element::Scalar, "l + 1")
+        .phase("Verifier")
+        // recombining the chunks of each ciphertext, C_i = ∏_j C_ij^{2^{bj}}
+        .op(OpKind::G1MultiExps, "n + 1", "(254 + b)/b").label("chunk recombination")
+        .op(OpKind::ScalarMuls, "n*(t + 1)", "1").label("sharing proof exponents")
+        .op(OpKind::G1MultiExps, "1", "n + 1").label("sharing proof")
+        .op(OpKind::G2MultiExps, "1", "t + 1").label("sharing proof")
+        .op(OpKind::MultiPairings, "1", "2").label("sharing proof")
+        .op(OpKind::G1MultiExps, "l", "n*((254 + b)/b) + 1").label("chunking proof")
+        .op(OpKind::MultiPairings, "(254 + b)/b", "2").label("forward-secure components")
+}
@@ -0,0 +1,35 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// KZG commitments to polynomials of degree `d`, as five phases: the one-time powers-of-tau
+/// setup and its check, committing, opening at one point, checking one opening, and checking
+/// `m` openings of different polynomials at different points at once.
+pub fn kzg_protocol() -> Protocol {
+    Protocol::new("kzg")
+        .phase("Setup")
+        .op(OpKind::SrsGenerations, "1", "d")
+        .op(OpKind::SrsVerifications { batched: true }, "1", "d")
+        .phase("Commit")
+        .op(OpKind::G1MultiExps, "1", "d + 1")
+        .message("commitment", Element::G1, "1")
+        .phase("Open")
+        // evaluation at z and synthetic division by (X - z)
+        .op(OpKind::ScalarMuls, "2*d", "1")
+        .op(OpKind::ScalarAdds, "2*d", "1")
+        .op(OpKind::G1MultiExps, "1", "d").label("quotient commitment")
+        .message("evaluation", Element::Scalar, "1")
+        .message("proof", Element::G1, "1")
+        .phase("Verify")
+        // e(C - g1^y, g2) = e(π, g2^τ - g2^z)
+        .op(OpKind::G1Exps, "1", "1")
+        .op(OpKind::G2Exps, "1", "1")
+        .op(OpKind::MultiPairings, "1", "2")
+        .phase("Batch verify")
+        // e(Σ r_i (C_i - g1^{y_i} + π_i^{z_i}), g2) = e(Σ r_i π_i, g2^τ)
+        .op(OpKind::ScalarMuls, "2*m", "1")
+        .op(OpKind::G1MultiExps, "1", "2*m + 1")
+        .op(OpKind::G1MultiExps, "1", "m")
+        .op(OpKind::MultiPairings, "1", "2")
+}
@@ -0,0 +1,121 @@
+//! Operation counts of published protocols, as `Workload`s.
+
+mod admission;
+mod aggregatable;
+mod albatross;
+mod batching;
+mod beacon;
+mod bls;
+mod bulletproofs;
+mod dhpvss;
+mod dkg;
+mod fouque_stern;
+mod groth;
+mod groth21;
+mod kzg;
+mod multi_secret;
+mod pvss;
+mod reconstruction;
+mod registry;
+mod relay;
+mod resharing;
+mod schoenmakers;
+mod scrape;
+mod simple_pvss;
+mod snark;
+
+use crate::protocol::{Params, Protocol};
+use crate::sweep::Sweep;
+
+pub use admission::admission_protocol;
+pub use aggregatable::aggregatable_protocol;
+pub use albatross::albatross_protocol;
+pub use batching::{batch_pairing_protocol, batch_sigma_protocol};
+pub use beacon::beacon_protocol;
+pub use bls::{bls_aggregate_protocol, bls_pop_protocol, bls_vrf_protocol, threshold_bls_protocol};
+pub use bulletproofs::bulletproofs_protocol;
+pub use dhpvss::dhpvss_protocol;
+pub use dkg::{dkg_complaints_protocol, dkg_protocol};
+pub use fouque_stern::fouque_stern_protocol;
+pub use groth::{groth_protocol, GrothParams, GrothParamsError};
+pub use groth21::groth21_protocol;
+pub use kzg::kzg_protocol;
+pub use multi_secret::multi_secret_protocol;
+pub use pvss::pvss_protocol;
+pub use reconstruction::reconstruction_protocol;
+pub use registry::{all, find, register, Param, Preset, RegisterError};
+pub use relay::relay_protocol;
+pub use resharing::resharing_protocol;
+pub use schoenmakers::schoenmakers_protocol;
+pub use scrape::{scrape, scrape_protocol};
+pub use simple_pvss::{PvssPreset, SimplePvss, SimplePvssScheme, SIMPLE_PVSS};
+pub use snark::{gm17_verifier_protocol, groth16_verifier_protocol, plonk_verifier_protocol, snark_verifiers_protocol};
+
+/// Looks up a preset by the name workload files and the command line use, among those in the
+/// registry (see `register`).
+pub fn by_name(name: &str) -> Option<Protocol> {
+    registry::find(name).map(|preset| preset.protocol())
+}
+
+/// Every built-in preset, with a small and a medium parameter set each, as sweep axes
+/// (constraints such as `t < n` hold). Benchmarks run all of them, so DKGs, which verify `n`
+/// transcripts, have fewer parties.
+pub const STANDARD: &[(&str, &[&str])] = &[
+    ("aggregatable", &["n=16 t=10 m=4", "n=64 t=42 m=16"]),
+    ("albatross", &["n=16 t=5 l=4", "n=64 t=21 l=16"]),
+    ("batch-dleq", &["n=4", "n=64"]),
+    ("batch-pairing", &["m=4 s=2", "m=64 s=3"]),
+    ("batch-schnorr", &["n=4", "n=64"]),
+    ("beacon", &["n=8 t=5", "n=16 t=10"]),
+    ("bls-aggregate", &["k=16", "k=128"]),
+    ("bls-pop", &["n=16 m=4", "n=64 m=64"]),
+    ("bls-vrf", &["m=4", "m=64"]),
+    ("bulletproofs", &["b=32 m=1", "b=64 m=8"]),
+    ("dhpvss", &["n=16 t=10", "n=64 t=42"]),
+    ("fouque-stern", &["n=16 t=10 b=16", "n=64 t=42 b=16"]),
+    ("gm17", &["p=1", "p=32"]),
+    ("groth", &["n=16 k=16 t=10 l=16", "n=64 k=16 t=42 l=16"]),
+    ("groth16", &["p=1", "p=32"]),
+    ("groth21", &["n=16 t=10 b=16 l=32", "n=64 t=42 b=16 l=32"]),
+    ("kzg", &["d=64 m=4", "d=1024 m=16"]),
+    ("multi-secret", &["n=16 t=10 b=4", "n=64 t=42 b=16"]),
+    ("plonk", &["p=1", "p=32"]),
+    ("pvss", &["n=16 t=10", "n=64 t=42"]),
+    ("reconstruction", &["t=10 b=16", "t=42 b=16"]),
+    ("relay", &["n=16 t=10 m=16", "n=64 t=42 m=16"]),
+    ("resharing", &["n=16 t=10 n_new=16 t_new=10", "n=64 t=42 n_new=48 t_new=31"]),
+    ("schoenmakers", &["n=16 t=10", "n=64 t=42"]),
+    ("scrape", &["n=16 t=10", "n=64 t=42"]),
+    ("snark-verifiers", &["p=1", "p=32"]),
+    ("threshold-bls", &["n=16 t=10", "n=64 t=42"]),
+    ("threshold-bls-batched", &["n=16 t=10", "n=64 t=42"]),
+    ("dkg-groth", &["n=8 k=16 t=5 l=16", "n=16 k=16 t=10 l=16"]),
+    ("dkg-groth21", &["n=8 t=5 b=16 l=32", "n=16 t=10 b=16 l=32"]),
+    ("dkg-pvss", &["n=8 t=5", "n=16 t=10"]),
+    ("dkg-schoenmakers", &["n=8 t=5", "n=16 t=10"]),
+    ("dkg-scrape", &["n=8 t=5", "n=16 t=10"]),
+    ("dkg-groth-complaints", &["n=8 k=16 t=5 l=16 f=1", "n=16 k=16 t=10 l=16 f=3"]),
+    ("dkg-groth21-complaints", &["n=8 t=5 b=16 l=32 f=1", "n=16 t=10 b=16 l=32 f=3"]),
+    ("dkg-pvss-complaints", &["n=8 t=5 f=1", "n=16 t=10 f=3"]),
+    ("dkg-schoenmakers-complaints", &["n=8 t=5 f=1", "n=16 t=10 f=3"]),
+    ("dkg-scrape-complaints", &["n=8 t=5 f=1", "n=16 t=10 f=3"]),
+    ("admission-groth", &["n=16 k=16 t=10 l=16 r=4", "n=64 k=16 t=42 l=16 r=16"]),
+    ("admission-groth21", &["n=16 t=10 b=16 l=32 r=4", "n=64 t=42 b=16 l=32 r=16"]),
+    ("admission-pvss", &["n=16 t=10 r=4", "n=64 t=42 r=16"]),
+    ("admission-schoenmakers", &["n=16 t=10 r=4", "n=64 t=42 r=16"]),
+    ("admission-scrape", &["n=16 t=10 r=4", "n=64 t=42 r=16"]),
+];
+
+/// The parameter sets of `STANDARD`, evaluated: one `Params` per set, in the order listed.
+pub fn standard_params(name: &str) -> Vec<Params> {
+    let (_, sets) = STANDARD.iter().find(|(preset, _)| *preset == name).unwrap_or_else(|| panic!("no standard parameters for `{}`", name));
+    sets.iter()
+        .map(|set| {
+            let mut sweep = Sweep::new();
+            for axis in set.split_whitespace() {
+                sweep.parse_axis(axis).expect("standard parameter sets are valid sweep axes");
+            }
+            sweep.points().expect("standard parameter sets evaluate").remove(0)
+        })
+        .collect()
+}
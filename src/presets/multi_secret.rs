@@ -0,0 +1,49 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// `pvss_protocol` with one dealer sharing `b` secrets in a single transcript, for `n` parties
+/// and threshold `t`: `b` polynomials, each committed and its shares encrypted as in `pvss`.
+/// The dealer's work and the transcript grow with `b`, but the verifier checks all `b` sharings
+/// at once, combined through random weights `r_j`: per party, the `b` share commitments and
+/// encrypted shares become one of each by a multi-exp of size `b`, as do the `b` commitments of
+/// every coefficient, and the combination then passes the single-secret checks, pairings and
+/// all. Parties likewise prove their `b` decryptions with one DLEQ proof on the combined
+/// decryption, and reconstructors check it on the combination. The sweep table reports the
+/// cost per secret; `b = 1` pays for the combinations on top of `pvss`.
+pub fn multi_secret_protocol() -> Protocol {
+    Protocol::new("multi-secret")
+        .phase("Dealer")
+        // Horner evaluation of the b degree-t polynomials at n points
+        .op(OpKind::ScalarMuls, "b*n*t", "1")
+        .op(OpKind::ScalarAdds, "b*n*t", "1")
+        .op(OpKind::G2Exps, "b*(t + 1)", "1").label("coefficient commitments")
+        .op(OpKind::G2Exps, "b*n", "1").label("share commitments")
+        .op(OpKind::G1Exps, "b*n", "1").label("share encryptions")
+        .message("coefficient commitments", Element::G2, "b*(t + 1)")
+        .message("share commitments", Element::G2, "b*n")
+        .message("encrypted shares", Element::G1, "b*n")
+        .phase("Verifier")
+        .op(OpKind::G2MultiExps, "t + 1", "b").label("secrets combined: coefficient commitments")
+        .op(OpKind::G2MultiExps, "n", "b").label("secrets combined: share commitments")
+        .op(OpKind::G1MultiExps, "n", "b").label("secrets combined: encrypted shares")
+        .op(OpKind::ScalarMuls, "n*(t + 1)", "1").label("combination exponents")
+        .op(OpKind::ScalarAdds, "n*(t + 1)", "1")
+        .op(OpKind::G2MultiExps, "1", "n").label("combined share commitments")
+        .op(OpKind::G2MultiExps, "1", "t + 1").label("combined coefficient commitments")
+        .op(OpKind::G2EqualityChecks, "1", "1").label("combined commitments check")
+        .op(OpKind::MultiPairings, "n", "2").label("encryption checks").static_g2("generator", 1, "1")
+        .phase("Party")
+        .op(OpKind::ScalarInversions, "1", "1")
+        .op(OpKind::G1Exps, "b", "1").label("share decryptions")
+        .op(OpKind::G1MultiExps, "2", "b").label("secrets combined: encrypted and decrypted shares")
+        .op(OpKind::G1Exps, "2", "1").label("decryption proof")
+        .message("decrypted shares", Element::G1, "b")
+        .message("decryption proof", Element::Scalar, "2")
+        .phase("Reconstructor")
+        .op(OpKind::G1MultiExps, "2*(t + 1)", "b").label("secrets combined: encrypted and decrypted shares")
+        .op(OpKind::G1Exps, "4*(t + 1)", "1").label("decryption proof checks")
+        .op(OpKind::LagrangeInterpolation, "b", "t + 1").label("interpolation in the exponent")
+        .throughput("secrets", "b")
+}
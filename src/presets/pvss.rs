@@ -0,0 +1,36 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Op counts of `pvss::deal`, `pvss::verify_dealing`, one party's `pvss::decrypt_share` and
+/// `pvss::reconstruct` for `n` parties and threshold `t`.
+pub fn pvss_protocol() -> Protocol {
+    Protocol::new("pvss")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G2Exps, "t + 1", "1").label("coefficient commitments")
+        .op(OpKind::G2Exps, "n", "1").label("share commitments")
+        .op(OpKind::G1Exps, "n", "1").label("share encryptions")
+        .message("coefficient commitments", Element::G2, "t + 1")
+        .message("share commitments", Element::G2, "n")
+        .message("encrypted shares", Element::G1, "n")
+        .phase("Verifier")
+        .op(OpKind::ScalarMuls, "n*(t + 1)", "1").label("combination exponents")
+        .op(OpKind::ScalarAdds, "n*(t + 1)", "1")
+        .op(OpKind::G2MultiExps, "1", "n").label("combined share commitments")
+        .op(OpKind::G2MultiExps, "1", "t + 1").label("combined coefficient commitments")
+        .op(OpKind::G2EqualityChecks, "1", "1").label("combined commitments check")
+        .op(OpKind::MultiPairings, "n", "2").label("encryption checks").static_g2("generator", 1, "1")
+        .phase("Party")
+        .op(OpKind::ScalarInversions, "1", "1")
+        .op(OpKind::G1Exps, "1", "1").label("share decryption")
+        .op(OpKind::G1Exps, "2", "1").label("decryption proof")
+        .message("decrypted share", Element::G1, "1")
+        .message("decryption proof", Element::Scalar, "2")
+        .phase("Reconstructor")
+        .op(OpKind::G1Exps, "4*(t + 1)", "1").label("decryption proof checks")
+        .op(OpKind::LagrangeInterpolation, "1", "t + 1").label("interpolation in the exponent")
+}
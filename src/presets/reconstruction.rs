@@ -0,0 +1,31 @@
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Reconstruction of a secret from `t` encrypted shares, two ways. In the exponent, the shares
+/// are ElGamal-encrypted group elements `g1^{s_i}`: one exp decrypts each, and `g1^s` is
+/// interpolated with Lagrange coefficients and a multi-exp of size `t`. In the field, the shares
+/// are scalars encrypted in chunks of `b` bits as in module `chunked_elgamal`: one exp unmasks
+/// every chunk and baby-step giant-step recovers it, half of the `2^{⌊b/2⌋}` giant steps on
+/// average, each an addition, a share of a batch normalization and a table lookup of the
+/// compressed point; `s` is then interpolated with scalar arithmetic alone. The exponent's
+/// multi-exp grows slower than linearly in `t`, the field's decryptions linearly with a large
+/// constant, and its coefficients quadratically with a small one.
+pub fn reconstruction_protocol() -> Protocol {
+    // chunks per share and average giant steps per chunk
+    let chunks = "t*((254 + b)/b)";
+    let giant_steps = "t*((254 + b)/b)*(2^(b/2)/2)";
+    Protocol::new("reconstruction")
+        .phase("In the exponent")
+        .op(OpKind::ElGamalDecryptions, "t", "1").label("share decryptions")
+        .op(OpKind::LagrangeInterpolation, "1", "t").label("interpolation")
+        .phase("In the field")
+        .op(OpKind::G1Exps, chunks, "1").label("chunk unmasking")
+        .op(OpKind::G1Additions, giant_steps, "1").label("giant steps")
+        .op(OpKind::G1BatchNormalizations, "t*((254 + b)/b)*((2^(b/2)/2 + 63)/64)", "min(2^(b/2)/2, 64)").label("giant steps")
+        .op(OpKind::SerializeG1 { compressed: true }, giant_steps, "1").label("baby-step lookups")
+        // the coefficients' numerators and denominators, and the weighted sum
+        .op(OpKind::ScalarMuls, "2*t*t + t", "1").label("interpolation")
+        .op(OpKind::ScalarBatchInversions, "1", "t").label("interpolation")
+        .op(OpKind::ScalarAdds, "t", "1").label("interpolation")
+}
@@ -0,0 +1,196 @@
+use crate::presets::{
+    admission_protocol, aggregatable_protocol, albatross_protocol, batch_pairing_protocol, batch_sigma_protocol, beacon_protocol, bls_aggregate_protocol, bls_pop_protocol,
+    bls_vrf_protocol, bulletproofs_protocol, dkg_complaints_protocol, dkg_protocol, fouque_stern_protocol, gm17_verifier_protocol, groth16_verifier_protocol,
+    groth21_protocol, groth_protocol, kzg_protocol, multi_secret_protocol, plonk_verifier_protocol, pvss_protocol, reconstruction_protocol, relay_protocol, resharing_protocol,
+    schoenmakers_protocol, scrape_protocol, snark_verifiers_protocol, threshold_bls_protocol,
+};
+use crate::presets::SIMPLE_PVSS;
+use crate::protocol::Protocol;
+use crate::sigma::Sigma;
+
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+
+
+/// A preset as `by_name` looks it up: sweeps, workload files and every other command that takes
+/// a preset name find it, and `list` prints it. The built-in presets implement it, and other
+/// crates can add their own with `register`.
+pub trait Preset: Send + Sync {
+    fn name(&self) -> &str;
+    /// a line or two on what the protocol is and which of its parts the phases are
+    fn description(&self) -> &str;
+    /// the parameters of the protocol, as sweeps and workload files set them
+    fn params(&self) -> &[Param];
+    fn protocol(&self) -> Protocol;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn param(name: &'static str, description: &'static str) -> Param {
+    Param { name, description }
+}
+
+pub(super) const N : Param = param("n", "parties");
+pub(super) const T : Param = param("t", "threshold");
+const P : Param = param("p", "public inputs");
+const R : Param = param("r", "dealings per second");
+const F : Param = param("f", "faulty dealers");
+
+#[derive(Clone, Copy)]
+struct Builtin {
+    name: &'static str,
+    description: &'static str,
+    params: &'static [Param],
+    protocol: fn() -> Protocol,
+}
+
+impl Preset for Builtin {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn params(&self) -> &[Param] {
+        self.params
+    }
+
+    fn protocol(&self) -> Protocol {
+        (self.protocol)()
+    }
+}
+
+const fn builtin(name: &'static str, description: &'static str, params: &'static [Param], protocol: fn() -> Protocol) -> Builtin {
+    Builtin { name, description, params, protocol }
+}
+
+const BUILTIN: &[Builtin] = &[
+    builtin("aggregatable", "aggregatable PVSS (Gurkan et al.): one dealer's transcript, the aggregation of m transcripts and the verification of the aggregate",
+        &[N, T, param("m", "aggregated transcripts")], aggregatable_protocol),
+    builtin("albatross", "ALBATROSS's packed PVSS, sharing l secrets per transcript",
+        &[N, T, param("l", "secrets per transcript")], albatross_protocol),
+    builtin("batch-dleq", "n DLEQ proofs over G1, checked one by one and batched through a random linear combination",
+        &[param("n", "proofs")], || batch_sigma_protocol(Sigma::Dleq)),
+    builtin("batch-pairing", "m pairing equations, checked one by one and batched through a random linear combination",
+        &[param("m", "equations"), param("s", "pairings per equation")], batch_pairing_protocol),
+    builtin("batch-schnorr", "n Schnorr proofs over G1, checked one by one and batched through a random linear combination",
+        &[param("n", "proofs")], || batch_sigma_protocol(Sigma::Schnorr)),
+    builtin("beacon", "a drand-style randomness beacon: the DKG of its threshold BLS key, then one round's signing, aggregation and light-client verification",
+        &[param("n", "nodes"), T], beacon_protocol),
+    builtin("bls-aggregate", "verification of a BLS aggregate signature on k distinct messages and of a k-signer multisignature on one message",
+        &[param("k", "signers")], bls_aggregate_protocol),
+    builtin("bls-pop", "BLS signatures with proofs of possession: key generation, PoPs of n new members, signing and batch verification of m signatures",
+        &[param("n", "new committee members"), param("m", "batch-verified signatures")], bls_pop_protocol),
+    builtin("bls-vrf", "BLS VRF evaluation, verification, and batch verification of m proofs",
+        &[param("m", "batch-verified proofs")], bls_vrf_protocol),
+    builtin("bulletproofs", "verification of an aggregated Bulletproofs range proof for m b-bit values",
+        &[param("b", "bits per value"), param("m", "values")], bulletproofs_protocol),
+    builtin("fouque-stern", "Fouque-Stern's '01 publicly verifiable sharing in G1 without Paillier, shares encrypted in chunks with exponential ElGamal",
+        &[N, T, param("b", "bits per chunk")], fouque_stern_protocol),
+    builtin("gm17", "GM17 verification", &[P], gm17_verifier_protocol),
+    builtin("groth", "Groth's PVSS, as a prover and a verifier",
+        &[N, param("k", "chunks per share"), T, param("l", "bits per chunk")], groth_protocol),
+    builtin("groth16", "Groth16 verification", &[P], groth16_verifier_protocol),
+    builtin("groth21", "Groth's '21 NIDKG dealing with chunked ElGamal encryption",
+        &[param("n", "receivers"), T, param("b", "bits per chunk"), param("l", "chunking proof repetitions")], groth21_protocol),
+    builtin("kzg", "KZG commitments: the setup and its check, commit, open, verify, and batch verification of m openings",
+        &[param("d", "polynomial degree"), param("m", "batch-verified openings")], kzg_protocol),
+    builtin("multi-secret", "the `pvss` dealer sharing b secrets in one transcript, whose sharings the verifier checks at once, with the cost per secret",
+        &[N, T, param("b", "secrets per transcript")], multi_secret_protocol),
+    builtin("plonk", "PLONK verification over KZG", &[P], plonk_verifier_protocol),
+    builtin("pvss", "the PVSS implemented in module `pvss`", &[N, T], pvss_protocol),
+    builtin("reconstruction", "reconstruction from t encrypted shares, in the exponent and in the field (chunked ElGamal by baby-step giant-step)",
+        &[param("t", "shares"), param("b", "bits per chunk")], reconstruction_protocol),
+    builtin("relay", "a relay node aggregating `aggregatable` transcripts: deserialization, subgroup checks, batched verification and aggregation of one second's m transcripts",
+        &[N, T, param("m", "transcripts per second")], relay_protocol),
+    builtin("resharing", "proactive resharing with Feldman VSS from an old committee to a new one",
+        &[param("n", "old parties"), param("t", "old threshold"), param("n_new", "new parties"), param("t_new", "new threshold")], resharing_protocol),
+    builtin("schoenmakers", "Schoenmakers' '99 PVSS with per-share DLEQ proofs, in G1 without pairings", &[N, T], schoenmakers_protocol),
+    builtin("scrape", "SCRAPE's pairing-based PVSS, including the Reed-Solomon dual-code check", &[N, T], scrape_protocol),
+    builtin("snark-verifiers", "the Groth16, GM17 and PLONK verifiers side by side", &[P], snark_verifiers_protocol),
+    builtin("threshold-bls", "threshold BLS signing, checks of n partial signatures one by one and aggregation of t + 1",
+        &[N, T], || threshold_bls_protocol(false)),
+    builtin("threshold-bls-batched", "threshold BLS signing, batched checks of n partial signatures and aggregation of t + 1",
+        &[N, T], || threshold_bls_protocol(true)),
+    builtin("dkg-groth", "one party's work in a DKG in which all n parties deal a `groth` transcript",
+        &[N, param("k", "chunks per share"), T, param("l", "bits per chunk")], || dkg_protocol("groth").unwrap()),
+    builtin("dkg-groth21", "one party's work in a DKG in which all n parties deal a `groth21` transcript",
+        &[N, T, param("b", "bits per chunk"), param("l", "chunking proof repetitions")], || dkg_protocol("groth21").unwrap()),
+    builtin("dkg-pvss", "one party's work in a DKG in which all n parties deal a `pvss` transcript",
+        &[N, T], || dkg_protocol("pvss").unwrap()),
+    builtin("dkg-schoenmakers", "one party's work in a DKG in which all n parties deal a `schoenmakers` transcript",
+        &[N, T], || dkg_protocol("schoenmakers").unwrap()),
+    builtin("dkg-scrape", "one party's work in a DKG in which all n parties deal a `scrape` transcript",
+        &[N, T], || dkg_protocol("scrape").unwrap()),
+    builtin("dkg-groth-complaints", "`dkg-groth` with f faulty dealers, each accused by t parties, whose revealed shares every party checks",
+        &[N, param("k", "chunks per share"), T, param("l", "bits per chunk"), F], || dkg_complaints_protocol("groth").unwrap()),
+    builtin("dkg-groth21-complaints", "`dkg-groth21` with f faulty dealers, each accused by t parties, whose revealed shares every party checks",
+        &[N, T, param("b", "bits per chunk"), param("l", "chunking proof repetitions"), F], || dkg_complaints_protocol("groth21").unwrap()),
+    builtin("dkg-pvss-complaints", "`dkg-pvss` with f faulty dealers, each accused by t parties, whose revealed shares every party checks",
+        &[N, T, F], || dkg_complaints_protocol("pvss").unwrap()),
+    builtin("dkg-schoenmakers-complaints", "`dkg-schoenmakers` with f faulty dealers, each accused by t parties, whose revealed shares every party checks",
+        &[N, T, F], || dkg_complaints_protocol("schoenmakers").unwrap()),
+    builtin("dkg-scrape-complaints", "`dkg-scrape` with f faulty dealers, each accused by t parties, whose revealed shares every party checks",
+        &[N, T, F], || dkg_complaints_protocol("scrape").unwrap()),
+    builtin("admission-groth", "a validator admitting r `groth` dealings per second: deserialization, the senders' BLS signatures and the transcripts",
+        &[N, param("k", "chunks per share"), T, param("l", "bits per chunk"), R], || admission_protocol("groth").unwrap()),
+    builtin("admission-groth21", "a validator admitting r `groth21` dealings per second: deserialization, the senders' BLS signatures and the transcripts",
+        &[N, T, param("b", "bits per chunk"), param("l", "chunking proof repetitions"), R], || admission_protocol("groth21").unwrap()),
+    builtin("admission-pvss", "a validator admitting r `pvss` dealings per second: deserialization, the senders' BLS signatures and the transcripts",
+        &[N, T, R], || admission_protocol("pvss").unwrap()),
+    builtin("admission-schoenmakers", "a validator admitting r `schoenmakers` dealings per second: deserialization, the senders' BLS signatures and the transcripts",
+        &[N, T, R], || admission_protocol("schoenmakers").unwrap()),
+    builtin("admission-scrape", "a validator admitting r `scrape` dealings per second: deserialization, the senders' BLS signatures and the transcripts",
+        &[N, T, R], || admission_protocol("scrape").unwrap()),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegisterError {
+    /// a preset of the name is registered already, built in or not
+    NameTaken(String),
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterError::NameTaken(name) => write!(f, "a preset named `{}` is registered already", name),
+        }
+    }
+}
+
+impl std::error::Error for RegisterError {}
+
+fn registry() -> &'static RwLock<Vec<Arc<dyn Preset>>> {
+    static REGISTRY : OnceLock<RwLock<Vec<Arc<dyn Preset>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtin = BUILTIN.iter().map(|preset| Arc::new(*preset) as Arc<dyn Preset>);
+        let simple_pvss = SIMPLE_PVSS.iter().map(|scheme| Arc::new(*scheme) as Arc<dyn Preset>);
+        RwLock::new(builtin.chain(simple_pvss).collect())
+    })
+}
+
+/// Adds `preset` for the rest of the process, after the built-in presets.
+pub fn register(preset: impl Preset + 'static) -> Result<(), RegisterError> {
+    let mut presets = registry().write().unwrap();
+    if presets.iter().any(|registered| registered.name() == preset.name()) {
+        return Err(RegisterError::NameTaken(preset.name().to_string()));
+    }
+    presets.push(Arc::new(preset));
+    Ok(())
+}
+
+/// The preset registered as `name`.
+pub fn find(name: &str) -> Option<Arc<dyn Preset>> {
+    registry().read().unwrap().iter().find(|preset| preset.name() == name).cloned()
+}
+
+/// Every registered preset, the built-in ones first, in the order they were registered.
+pub fn all() -> Vec<Arc<dyn Preset>> {
+    registry().read().unwrap().clone()
+}
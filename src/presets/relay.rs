@@ -0,0 +1,48 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// A relay node that aggregates the transcripts of the `aggregatable` preset (`n` parties,
+/// threshold `t`) as they arrive, `m` per second, and forwards the aggregate. A run is one
+/// second's batch, so its throughput is `m` over its time, and the relay keeps up while that is
+/// at least `m`. Every transcript is decompressed, its points checked to be in their subgroups,
+/// and the batch is verified at once: the aggregatable verifier's equations, each combined
+/// across the `m` transcripts with random weights, i.e. one dual-code check of size `mn`, one
+/// multi-pairing for the `n` encryption checks over the weighted sums of every party's share
+/// commitments and encryptions, one for the secret commitments and one for the signatures of
+/// knowledge. The batch is then added into the aggregate, which the relay forwards.
+pub fn relay_protocol() -> Protocol {
+    Protocol::new("relay")
+        .throughput("transcripts", "m")
+        // coefficient and share commitments and the signature of knowledge in G1, the secret
+        // commitment, the encrypted shares and the signature of knowledge in G2
+        .phase("Deserialization")
+        .op(OpKind::G1Decompressions, "m*(t + n + 2)", "1")
+        .op(OpKind::G2Decompressions, "m*(n + 2)", "1")
+        .phase("Subgroup checks")
+        .op(OpKind::G1SubgroupChecks, "m*(t + n + 2)", "1")
+        .op(OpKind::G2SubgroupChecks, "m*(n + 2)", "1")
+        .phase("Batched verification")
+        .op(OpKind::ScalarMuls, "n*(n - t - 2) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - 2)", "1")
+        .op(OpKind::ScalarMuls, "m*n", "1").label("weighted dual codewords")
+        .op(OpKind::G1MultiExps, "1", "m*n").label("dual code check")
+        .op(OpKind::G1MultiExps, "1", "m*(t + 1)").label("coefficient commitment checks")
+        .op(OpKind::G1MultiExps, "n", "m").label("encryption checks")
+        .op(OpKind::G2MultiExps, "n", "m").label("encryption checks")
+        .op(OpKind::MultiPairings, "1", "n + 1").label("encryption checks")
+        .op(OpKind::G1MultiExps, "1", "m").label("secret commitments")
+        .op(OpKind::G2MultiExps, "1", "m").label("secret commitments")
+        .op(OpKind::MultiPairings, "1", "2").label("secret commitments")
+        .op(OpKind::G1MultiExps, "1", "m").label("signatures of knowledge")
+        .op(OpKind::MultiPairings, "1", "m + 1").label("signatures of knowledge")
+        .phase("Aggregation")
+        .op(OpKind::G1Additions, "m*(t + 1 + n)", "1").label("G1 components")
+        .op(OpKind::G2Additions, "m*(n + 1)", "1").label("G2 components")
+        .message("aggregated commitments", Element::G1, "t + 1 + n")
+        .message("aggregated commitments", Element::G2, "1")
+        .message("aggregated encrypted shares", Element::G2, "n")
+        .message("signatures of knowledge", Element::G1, "m")
+        .message("signatures of knowledge", Element::G2, "m")
+}
@@ -0,0 +1,34 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Proactive resharing with Feldman VSS from an old committee of `n` parties with threshold `t`
+/// to a new one of `n_new` parties with threshold `t_new`, as in Desmedt–Jajodia redistribution.
+/// Each of `t + 1` old parties deals a sub-sharing of its share `s_i` with a degree-`t_new`
+/// polynomial, whose constant term commitment must match its old verification key `g^{s_i}`.
+/// Each new party checks the `t + 1` dealings addressed to it, recombines its new share as the
+/// Lagrange combination of its sub-shares, and derives the new coefficient commitments the same
+/// way in the exponent. Sub-shares go over private channels, whose encryption isn't counted.
+pub fn resharing_protocol() -> Protocol {
+    Protocol::new("resharing")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t_new polynomial at n_new points
+        .op(OpKind::ScalarMuls, "n_new*t_new", "1")
+        .op(OpKind::ScalarAdds, "n_new*t_new", "1")
+        .op(OpKind::FeldmanCommitments { window_bits: 8 }, "1", "t_new").label("sub-sharing commitments")
+        .message("sub-sharing commitments", Element::G1, "t_new + 1")
+        .message("sub-shares", Element::Scalar, "n_new")
+        .phase("Verifier")
+        // the old verification keys g^{s_i} are checked against the old public commitments
+        .op(OpKind::FeldmanShareChecks, "t + 1", "t").label("old verification keys")
+        .op(OpKind::FixedBaseExps { window_bits: 8 }, "t + 1", "1").label("sub-shares in the exponent")
+        .op(OpKind::FeldmanShareChecks, "t + 1", "t_new").label("sub-share checks")
+        .phase("Recombination")
+        // λ_i = ∏_{j≠i} j / (j - i) for the t + 1 dealers, then the combination of sub-shares
+        .op(OpKind::ScalarInversions, "t + 1", "1").label("Lagrange coefficients")
+        .op(OpKind::ScalarMuls, "2*t*(t + 1)", "1")
+        .op(OpKind::ScalarMuls, "t + 1", "1").label("new share")
+        .op(OpKind::ScalarAdds, "t", "1")
+        .op(OpKind::G1MultiExps, "t_new + 1", "t + 1").label("new coefficient commitments")
+}
@@ -0,0 +1,32 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Schoenmakers' PVSS ('99) for `n` parties and threshold `t`, run in G1 without pairings. The
+/// dealer publishes Feldman commitments `C_j = g^{a_j}`, encrypted shares `Y_i = pk_i^{p(i)}` and
+/// one DLEQ proof per share that `log_g X_i = log_{pk_i} Y_i` for `X_i = g^{p(i)}`, with a single
+/// Fiat–Shamir challenge. The verifier recomputes every `X_i` from the commitments and checks the
+/// proofs.
+pub fn schoenmakers_protocol() -> Protocol {
+    Protocol::new("schoenmakers")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G1Exps, "t + 1", "1").label("coefficient commitments")
+        .op(OpKind::G1Exps, "n", "1").label("share commitments")
+        .op(OpKind::G1Exps, "n", "1").label("share encryptions")
+        .op(OpKind::G1Exps, "2*n", "1").label("DLEQ commitments")
+        .op(OpKind::ScalarMuls, "n", "1").label("DLEQ responses")
+        .op(OpKind::ScalarAdds, "n", "1")
+        .message("coefficient commitments", Element::G1, "t + 1")
+        .message("encrypted shares", Element::G1, "n")
+        .message("DLEQ proofs", Element::Scalar, "n + 1")
+        .phase("Verifier")
+        // powers i^j of every evaluation point
+        .op(OpKind::ScalarMuls, "n*t", "1").label("evaluation point powers")
+        .op(OpKind::G1MultiExps, "n", "t + 1").label("share commitments from coefficients")
+        // a_1 = g^r X_i^c and a_2 = pk_i^r Y_i^c per share, then one hash
+        .op(OpKind::G1MultiExps, "2*n", "2").label("DLEQ checks")
+}
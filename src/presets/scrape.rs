@@ -0,0 +1,36 @@
+use crate::communication::Element;
+use crate::protocol::{Params, Protocol};
+use crate::workload::{OpKind, Workload};
+
+
+/// SCRAPE's pairing-based PVSS (Cascudo–David) for `n` parties and threshold `t`. Shares are
+/// committed as `v_i = g1^{p(i)}` and encrypted under G2 keys as `pk_i^{p(i)}`. The verifier
+/// checks the commitments with the Reed–Solomon dual code: for a random codeword `c` of the
+/// dual code, `∏ v_i^{c_i} = 1`. Then one pairing equation per party checks the encryptions.
+pub fn scrape_protocol() -> Protocol {
+    Protocol::new("scrape")
+        .phase("Dealer")
+        // Horner evaluation of the degree-t polynomial at n points
+        .op(OpKind::ScalarMuls, "n*t", "1")
+        .op(OpKind::ScalarAdds, "n*t", "1")
+        .op(OpKind::G1Exps, "n", "1").label("share commitments")
+        .op(OpKind::G2Exps, "n", "1").label("share encryptions")
+        .message("share commitments", Element::G1, "n")
+        .message("encrypted shares", Element::G2, "n")
+        .phase("Verifier")
+        // the dual codeword is c_i = w_i m(i) for a random m of degree n - t - 2, where the
+        // w_i = ∏_{j≠i} 1/(i - j) only depend on n and are precomputed
+        .op(OpKind::ScalarMuls, "n*(n - t - 2) + n", "1").label("dual codeword")
+        .op(OpKind::ScalarAdds, "n*(n - t - 2)", "1")
+        .op(OpKind::G1MultiExps, "1", "n").label("dual code check")
+        .op(OpKind::MultiPairings, "n", "2").label("encryption checks").static_g2("public keys", 1, "n")
+}
+
+/// `scrape_protocol` evaluated for concrete parameters.
+pub fn scrape(n: usize, t: usize) -> Workload {
+    assert!(t + 2 <= n, "SCRAPE needs t <= n - 2");
+    let params = Params::new()
+        .set("n", n as i64)
+        .set("t", t as i64);
+    scrape_protocol().evaluate(&params).expect("scrape op counts only use n and t")
+}
@@ -0,0 +1,119 @@
+use crate::presets::dhpvss_protocol;
+use crate::presets::registry::{Param, Preset, N, T};
+use crate::protocol::{ExprError, Params, Protocol};
+use crate::workload::{Op, Workload};
+
+
+/// The three roles of a PVSS for `n` parties and threshold `t`, the parameters all simple PVSS
+/// schemes share, so that they compare with each other directly.
+pub trait PvssPreset {
+    /// deals one transcript
+    fn dealer(&self) -> Workload;
+    /// checks one transcript
+    fn verifier(&self) -> Workload;
+    /// decrypts its share and recovers the secret from `t + 1` shares
+    fn reconstructor(&self) -> Workload;
+
+    /// All three roles, in the phases "Dealer", "Verifier" and "Reconstructor".
+    fn workload(&self) -> Workload {
+        self.dealer().in_phase("Dealer")
+            .extend(self.verifier().in_phase("Verifier"))
+            .extend(self.reconstructor().in_phase("Reconstructor"))
+    }
+}
+
+/// A simple PVSS scheme: a protocol over `n` and `t` alone with the phases "Dealer", "Verifier"
+/// and "Reconstructor". The registry lists every scheme in `SIMPLE_PVSS` as a preset, whose
+/// protocol checks that `t <= n - 2`, so adding one here adds it to sweeps and `compare`.
+#[derive(Clone, Copy)]
+pub struct SimplePvssScheme {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub protocol: fn() -> Protocol,
+}
+
+impl Preset for SimplePvssScheme {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn params(&self) -> &[Param] {
+        &[N, T]
+    }
+
+    fn protocol(&self) -> Protocol {
+        (self.protocol)().check(check_threshold)
+    }
+}
+
+/// The built-in simple PVSS schemes.
+pub const SIMPLE_PVSS: &[SimplePvssScheme] = &[
+    SimplePvssScheme {
+        name: "dhpvss",
+        description: "DHPVSS (\"YOLO YOSO\"): Diffie-Hellman encrypted shares and one dual-code DLEQ proof per transcript, in G1 without pairings",
+        protocol: dhpvss_protocol,
+    },
+];
+
+// the dual code of the shares' Reed-Solomon code has codewords of degree n - t - 2
+fn check_threshold(params: &Params) -> Result<(), String> {
+    let (Some(n), Some(t)) = (params.get("n"), params.get("t")) else {
+        return Ok(());
+    };
+    if t > n - 2 {
+        return Err(format!("simple PVSS schemes need t <= n - 2, not t={} for n={}", t, n));
+    }
+    Ok(())
+}
+
+/// A simple PVSS scheme's `Protocol` evaluated for concrete parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimplePvss {
+    workload: Workload,
+}
+
+impl SimplePvss {
+    /// `protocol` for `n` parties and threshold `t`, an error if `t > n - 2` or a count doesn't
+    /// evaluate.
+    pub fn new(protocol: Protocol, n: usize, t: usize) -> Result<Self, ExprError> {
+        let value = |name: &str, x: usize| i64::try_from(x).map_err(|_| ExprError::InvalidParams(format!("{}={} is too large", name, x)));
+        let params = Params::new()
+            .set("n", value("n", n)?)
+            .set("t", value("t", t)?);
+        let workload = protocol.check(check_threshold).evaluate(&params)?;
+        Ok(Self { workload })
+    }
+
+    /// The scheme called `name` in `SIMPLE_PVSS`, `None` if there is none.
+    pub fn by_name(name: &str, n: usize, t: usize) -> Option<Result<Self, ExprError>> {
+        let scheme = SIMPLE_PVSS.iter().find(|scheme| scheme.name == name)?;
+        Some(Self::new((scheme.protocol)(), n, t))
+    }
+
+    // the ops of one phase, out of it
+    fn role(&self, phase: &str) -> Workload {
+        let ops = self.workload.ops.iter()
+            .filter(|op| op.phase.as_deref() == Some(phase))
+            .map(|op| Op { phase: None, ..op.clone() })
+            .collect();
+        Workload { ops }
+    }
+}
+
+impl PvssPreset for SimplePvss {
+    fn dealer(&self) -> Workload {
+        self.role("Dealer")
+    }
+
+    fn verifier(&self) -> Workload {
+        self.role("Verifier")
+    }
+
+    fn reconstructor(&self) -> Workload {
+        self.role("Reconstructor")
+    }
+}
@@ -0,0 +1,73 @@
+use crate::communication::Element;
+use crate::protocol::Protocol;
+use crate::workload::OpKind;
+
+
+/// Groth16 verification with `p` public inputs: the proof points' subgroup checks, the
+/// public-input multi-exp and `e(A, B) = e(α, β) e(IC, γ) e(C, δ)` with `e(α, β)` precomputed.
+pub fn groth16_verifier_protocol() -> Protocol {
+    Protocol::new("groth16")
+        .phase("Prover")
+        .message("proof", Element::G1, "2")
+        .message("proof", Element::G2, "1")
+        .phase("Verifier")
+        .op(OpKind::G1SubgroupChecks, "2", "1")
+        .op(OpKind::G2SubgroupChecks, "1", "1")
+        .op(OpKind::G1MultiExps, "1", "p + 1").label("public inputs")
+        .op(OpKind::MultiPairings, "1", "3")
+}
+
+/// GM17 verification with `p` public inputs: as Groth16 plus the second pairing equation
+/// `e(A, g2^γ) = e(g1^γ, B)`.
+pub fn gm17_verifier_protocol() -> Protocol {
+    Protocol::new("gm17")
+        .phase("Prover")
+        .message("proof", Element::G1, "2")
+        .message("proof", Element::G2, "1")
+        .phase("Verifier")
+        .op(OpKind::G1SubgroupChecks, "2", "1")
+        .op(OpKind::G2SubgroupChecks, "1", "1")
+        .op(OpKind::G1MultiExps, "1", "p + 1").label("public inputs")
+        .op(OpKind::G1Additions, "1", "1")
+        .op(OpKind::G2Additions, "1", "1")
+        .op(OpKind::MultiPairings, "1", "3")
+        .op(OpKind::MultiPairings, "1", "2")
+}
+
+/// PLONK verification over KZG with `p` public inputs, following the paper's verifier: the nine
+/// proof points' subgroup checks, the public-input polynomial at the challenge (`p` Lagrange
+/// basis evaluations with one batch inversion), the linearization and batched opening as one
+/// G1 multi-exp of 18 terms, and a two-term multi-pairing. Transcript hashing is not counted.
+pub fn plonk_verifier_protocol() -> Protocol {
+    Protocol::new("plonk")
+        .phase("Prover")
+        .message("proof", Element::G1, "9")
+        .message("proof", Element::Scalar, "6")
+        .phase("Verifier")
+        .op(OpKind::G1SubgroupChecks, "9", "1")
+        .op(OpKind::ScalarBatchInversions, "1", "p + 1").label("Lagrange basis denominators")
+        .op(OpKind::ScalarMuls, "3*p + 40", "1").label("public inputs and field checks")
+        .op(OpKind::ScalarAdds, "p + 30", "1")
+        .op(OpKind::G1MultiExps, "1", "18").label("linearization and batched opening")
+        .op(OpKind::MultiPairings, "1", "2")
+}
+
+/// The three verifiers side by side for the same number `p` of public inputs, one phase each.
+pub fn snark_verifiers_protocol() -> Protocol {
+    let mut protocol = Protocol::new("snark-verifiers");
+    for (name, verifier) in [
+        ("Groth16", groth16_verifier_protocol()),
+        ("GM17", gm17_verifier_protocol()),
+        ("PLONK", plonk_verifier_protocol()),
+    ] {
+        protocol.ops.extend(verifier.ops.into_iter().map(|mut op| {
+            op.phase = Some(name.to_string());
+            op
+        }));
+        protocol.messages.extend(verifier.messages.into_iter().map(|mut message| {
+            message.phase = Some(name.to_string());
+            message
+        }));
+    }
+    protocol
+}
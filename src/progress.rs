@@ -0,0 +1,219 @@
+//! Progress of long simulations: a `Reporter` is told which item is about to run, so runs of
+//! many minutes show where they are and roughly how long is left.
+
+use crate::clock;
+
+use std::cell::RefCell;
+use std::fmt::{self, Write as _};
+use std::io::{IsTerminal as _, Write as _};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
+
+
+/// The state of a simulation as one of its items starts, or as it finishes (`item == items`).
+#[derive(Clone, Debug)]
+pub struct Progress<'a> {
+    /// 0-based index of the item about to run
+    pub item: usize,
+    pub items: usize,
+    /// 0-based repetition, for `simulate_n`
+    pub repetition: usize,
+    pub repetitions: usize,
+    pub description: &'a str,
+    pub label: Option<&'a str>,
+    pub phase: Option<&'a str>,
+    /// since measurement started, excluding warm-up
+    pub elapsed: Duration,
+}
+
+impl Progress<'_> {
+    pub fn is_done(&self) -> bool {
+        self.item == self.items
+    }
+
+    /// Remaining time, extrapolated from the items done so far. `None` until one has run.
+    pub fn eta(&self) -> Option<Duration> {
+        let done = self.repetition * self.items + self.item;
+        let total = self.repetitions * self.items;
+        (done > 0).then(|| self.elapsed.mul_f64((total - done) as f64 / done as f64))
+    }
+}
+
+impl fmt::Display for Progress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.repetitions > 1 {
+            write!(f, "repetition {}/{}, ", self.repetition + 1, self.repetitions)?;
+        }
+        write!(f, "item {}/{}: ", self.item + 1, self.items)?;
+        if let Some(phase) = self.phase {
+            write!(f, "{} / ", phase)?;
+        }
+        write!(f, "{}", self.description)?;
+        if let Some(label) = self.label {
+            write!(f, " ({})", label)?;
+        }
+        write!(f, " [elapsed {:.1?}", self.elapsed)?;
+        if let Some(eta) = self.eta() {
+            write!(f, ", ETA {:.1?}", eta)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Receives a simulation's progress; cheap to clone, so one reporter can follow several
+/// simulations.
+#[derive(Clone)]
+pub struct Reporter(Rc<dyn Fn(&Progress)>);
+
+impl Reporter {
+    pub fn new(f: impl Fn(&Progress) + 'static) -> Self {
+        Self(Rc::new(f))
+    }
+
+    /// Reports on stderr: on a terminal as one line, redrawn for every item and cleared at the
+    /// end; otherwise one line per item.
+    pub fn stderr() -> Self {
+        let terminal = std::io::stderr().is_terminal();
+        Self::new(move |progress| {
+            let mut stderr = std::io::stderr().lock();
+            // progress is best-effort, a closed stderr shouldn't abort the simulation
+            let _ = match (terminal, progress.is_done()) {
+                (true, true) => write!(stderr, "\r\x1b[2K"),
+                (true, false) => write!(stderr, "\r\x1b[2K{}", progress),
+                (false, true) => Ok(()),
+                (false, false) => writeln!(stderr, "{}", progress),
+            };
+            let _ = stderr.flush();
+        })
+    }
+
+    /// Keeps the Prometheus text file at `path` up to date, rewritten (atomically, through
+    /// `PATH.tmp`) as every item starts: the running item, the items and simulations completed,
+    /// the time measured so far and the time of every item run so far, of every simulation the
+    /// reporter followed. A node exporter's textfile collector can serve it for remote
+    /// monitoring, and the item times survive an interrupted run. Fails if the file can't be
+    /// written at the start; later write errors are ignored.
+    pub fn metrics(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let metrics = RefCell::new(Metrics { path: path.into(), ..Metrics::default() });
+        metrics.borrow().write()?;
+        Ok(Self::new(move |progress| {
+            let mut metrics = metrics.borrow_mut();
+            metrics.update(progress);
+            let _ = metrics.write();
+        }))
+    }
+
+    /// Reports to both `self` and `other`.
+    pub fn and(self, other: Reporter) -> Self {
+        Self::new(move |progress| {
+            self.report(progress);
+            other.report(progress);
+        })
+    }
+
+    pub(crate) fn report(&self, progress: &Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for Reporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Reporter")
+    }
+}
+
+// the state behind `Reporter::metrics`
+#[derive(Default)]
+struct Metrics {
+    path: PathBuf,
+    /// of the simulations done
+    simulations: usize,
+    items_completed: usize,
+    measured: Duration,
+    /// the running simulation's items completed and time measured
+    current: Option<(usize, Duration)>,
+    /// the index in `items` of the running item and its start within the simulation
+    running: Option<(usize, Duration)>,
+    /// every item of every simulation so far
+    items: Vec<ItemTime>,
+}
+
+struct ItemTime {
+    /// both 1-based
+    simulation: usize,
+    item: usize,
+    /// known once the item first runs
+    name: String,
+    time: Duration,
+}
+
+impl ItemTime {
+    fn labels(&self) -> String {
+        format!("simulation=\"{}\",item=\"{}\",name=\"{}\"", self.simulation, self.item, escape(&self.name))
+    }
+}
+
+impl Metrics {
+    fn update(&mut self, progress: &Progress) {
+        if let Some((i, start)) = self.running.take() {
+            self.items[i].time += progress.elapsed.saturating_sub(start);
+        }
+        if progress.is_done() {
+            self.simulations += 1;
+            self.items_completed += progress.repetitions * progress.items;
+            self.measured += progress.elapsed;
+            self.current = None;
+            return;
+        }
+        if self.current.is_none() {
+            let simulation = self.simulations + 1;
+            self.items.extend((1..=progress.items).map(|item| ItemTime { simulation, item, name: String::new(), time: Duration::ZERO }));
+        }
+        self.current = Some((progress.repetition * progress.items + progress.item, progress.elapsed));
+        let i = self.items.len() - progress.items + progress.item;
+        if self.items[i].name.is_empty() {
+            let mut name = String::new();
+            if let Some(phase) = progress.phase {
+                write!(name, "{} / ", phase).unwrap();
+            }
+            name.push_str(progress.description);
+            if let Some(label) = progress.label {
+                write!(name, " ({})", label).unwrap();
+            }
+            self.items[i].name = name;
+        }
+        self.running = Some((i, progress.elapsed));
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        let (completed, elapsed) = self.current.unwrap_or_default();
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, values: Vec<(String, f64)>| {
+            writeln!(out, "# HELP pvss_sim_{} {}\n# TYPE pvss_sim_{} {}", name, help, name, kind).unwrap();
+            for (labels, value) in values {
+                let labels = if labels.is_empty() { labels } else { format!("{{{}}}", labels) };
+                writeln!(out, "pvss_sim_{}{} {}", name, labels, value).unwrap();
+            }
+        };
+        let single = |value: f64| vec![(String::new(), value)];
+        metric("simulations_completed_total", "counter", "Simulations run to the end.", single(self.simulations as f64));
+        metric("items_completed_total", "counter", "Item runs completed, over all repetitions and simulations.", single((self.items_completed + completed) as f64));
+        metric("measured_seconds_total", "counter", "Time measured, excluding warm-ups.", single((self.measured + elapsed).as_secs_f64()));
+        metric("running_item", "gauge", "The item running now.", self.running.iter().map(|&(i, _)| (self.items[i].labels(), 1.0)).collect());
+        metric("item_seconds_total", "counter", "Time spent in every item so far, over all its repetitions.",
+            self.items.iter().filter(|item| !item.name.is_empty()).map(|item| (item.labels(), item.time.as_secs_f64())).collect());
+        let now = clock::system_time().duration_since(UNIX_EPOCH).unwrap_or_default();
+        metric("last_update_timestamp_seconds", "gauge", "When this file was written.", single(now.as_secs_f64()));
+
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+// as a Prometheus label value
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,554 @@
+//! Protocols described by symbolic op counts, e.g. "n*k + n + k + l + 1 G1 multi-exps of size 2",
+//! that are evaluated for concrete parameters into a `Workload`.
+//!
+//! ```
+//! use blstrs_pvss_simulation::protocol::{Params, Protocol};
+//! use blstrs_pvss_simulation::workload::OpKind;
+//!
+//! let protocol = Protocol::new("example")
+//!     .op(OpKind::G1Exps, "n + 2", "1")
+//!     .op(OpKind::G1MultiExps, "2", "n*k + 1");
+//! let workload = protocol.evaluate(&Params::new().set("n", 64).set("k", 16)).unwrap();
+//! assert_eq!((workload.ops[1].num, workload.ops[1].size), (2, 1025));
+//! ```
+
+use crate::communication::{Communication, Element, Message};
+use crate::op_counts::OpCounts;
+use crate::workload::{Op, OpKind, Workload};
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+
+/// An integer expression over named parameters: `+ - * / %` (division rounds down), `^` for
+/// powers, parentheses, and the functions `log2` (rounded up), `min` and `max`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Const(i64),
+    Param(String),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Func {
+    Log2,
+    Min,
+    Max,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    Syntax { expr: String, message: String },
+    UnknownParam(String),
+    /// overflow, division by zero, or a negative count
+    Arithmetic(String),
+    /// parameters that a check of the protocol (`Protocol::check`) rejects, e.g. a threshold
+    /// not below the number of parties
+    InvalidParams(String),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Syntax { expr, message } => write!(f, "invalid expression `{}`: {}", expr, message),
+            ExprError::UnknownParam(name) => write!(f, "unknown parameter `{}`", name),
+            ExprError::Arithmetic(message) => write!(f, "{}", message),
+            ExprError::InvalidParams(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// Concrete parameter values to evaluate expressions with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, i64>);
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: &str, value: i64) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    pub fn insert(&mut self, name: &str, value: i64) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.0.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.0.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+impl Expr {
+    pub fn parse(text: &str) -> Result<Expr, ExprError> {
+        let tokens = tokenize(text).map_err(|message| ExprError::Syntax { expr: text.to_string(), message })?;
+        let mut parser = ExprParser { tokens: &tokens, pos: 0 };
+        let expr = parser.sum();
+        match expr {
+            Ok(expr) if parser.pos == tokens.len() => Ok(expr),
+            Ok(_) => Err(ExprError::Syntax { expr: text.to_string(), message: format!("unexpected `{}`", tokens[parser.pos]) }),
+            Err(message) => Err(ExprError::Syntax { expr: text.to_string(), message }),
+        }
+    }
+
+    pub fn eval(&self, params: &Params) -> Result<i64, ExprError> {
+        let overflow = || ExprError::Arithmetic(format!("overflow evaluating `{}`", self));
+        match self {
+            Expr::Const(c) => Ok(*c),
+            Expr::Param(name) => params.get(name).ok_or_else(|| ExprError::UnknownParam(name.clone())),
+            Expr::Binary(lhs, op, rhs) => {
+                let (a, b) = (lhs.eval(params)?, rhs.eval(params)?);
+                match op {
+                    BinOp::Add => a.checked_add(b).ok_or_else(overflow),
+                    BinOp::Sub => a.checked_sub(b).ok_or_else(overflow),
+                    BinOp::Mul => a.checked_mul(b).ok_or_else(overflow),
+                    BinOp::Div | BinOp::Rem if b == 0 => Err(ExprError::Arithmetic(format!("division by zero in `{}`", self))),
+                    BinOp::Div => Ok(a.div_euclid(b)),
+                    BinOp::Rem => Ok(a.rem_euclid(b)),
+                    BinOp::Pow => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)).ok_or_else(overflow),
+                }
+            }
+            Expr::Call(func, args) => {
+                let args = args.iter().map(|arg| arg.eval(params)).collect::<Result<Vec<i64>, _>>()?;
+                match func {
+                    Func::Log2 if args[0] <= 0 => Err(ExprError::Arithmetic(format!("log2 of non-positive value in `{}`", self))),
+                    Func::Log2 => Ok(64 - (args[0] - 1).leading_zeros() as i64),
+                    Func::Min => Ok(args.into_iter().min().unwrap()),
+                    Func::Max => Ok(args.into_iter().max().unwrap()),
+                }
+            }
+        }
+    }
+
+    /// Evaluates to a count, which must not be negative.
+    pub fn eval_count(&self, params: &Params) -> Result<usize, ExprError> {
+        let value = self.eval(params)?;
+        usize::try_from(value)
+            .map_err(|_| ExprError::Arithmetic(format!("`{}` is negative ({})", self, value)))
+    }
+
+    /// Names of all parameters the expression refers to.
+    pub fn params(&self) -> Vec<&str> {
+        match self {
+            Expr::Const(_) => Vec::new(),
+            Expr::Param(name) => vec![name.as_str()],
+            Expr::Binary(lhs, _, rhs) => [lhs.params(), rhs.params()].concat(),
+            Expr::Call(_, args) => args.iter().flat_map(Expr::params).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(c) => write!(f, "{}", c),
+            Expr::Param(name) => write!(f, "{}", name),
+            Expr::Binary(lhs, op, rhs) => {
+                let op = match op {
+                    BinOp::Add => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    BinOp::Div => "/",
+                    BinOp::Rem => "%",
+                    BinOp::Pow => "^",
+                };
+                write!(f, "({} {} {})", lhs, op, rhs)
+            }
+            Expr::Call(func, args) => {
+                let name = match func {
+                    Func::Log2 => "log2",
+                    Func::Min => "min",
+                    Func::Max => "max",
+                };
+                let args : Vec<String> = args.iter().map(Expr::to_string).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+        }
+    }
+}
+
+impl From<i64> for Expr {
+    fn from(c: i64) -> Self {
+        Expr::Const(c)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Symbol(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars : Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                i += 1;
+            }
+            let digits : String = chars[start..i].iter().filter(|&&c| c != '_').collect();
+            tokens.push(Token::Number(digits.parse().map_err(|_| format!("number `{}` is too large", digits))?));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if "+-*/%^(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("unexpected character `{}`", c));
+        }
+    }
+    Ok(tokens)
+}
+
+// recursive descent: sum := product (('+' | '-') product)*, product := power (('*' | '/' | '%')
+// power)*, power := atom ('^' power)?, atom := number | name | name '(' args ')' | '(' sum ')' | '-' atom
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.tokens.get(self.pos) == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn binary(&mut self, ops: &[(char, BinOp)], mut operand: impl FnMut(&mut Self) -> Result<Expr, String>) -> Result<Expr, String> {
+        let mut lhs = operand(self)?;
+        'outer: loop {
+            for &(symbol, op) in ops {
+                if self.eat(symbol) {
+                    lhs = Expr::Binary(Box::new(lhs), op, Box::new(operand(self)?));
+                    continue 'outer;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        self.binary(&[('+', BinOp::Add), ('-', BinOp::Sub)], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        self.binary(&[('*', BinOp::Mul), ('/', BinOp::Div), ('%', BinOp::Rem)], Self::power)
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            Ok(Expr::Binary(Box::new(base), BinOp::Pow, Box::new(self.power()?)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("unexpected end of expression")?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Const(n)),
+            Token::Ident(name) if self.eat('(') => {
+                let func = match name.as_str() {
+                    "log2" => Func::Log2,
+                    "min" => Func::Min,
+                    "max" => Func::Max,
+                    _ => return Err(format!("unknown function `{}`", name)),
+                };
+                let mut args = vec![self.sum()?];
+                while self.eat(',') {
+                    args.push(self.sum()?);
+                }
+                if !self.eat(')') {
+                    return Err("expected `)`".to_string());
+                }
+                if func == Func::Log2 && args.len() != 1 {
+                    return Err("log2 takes one argument".to_string());
+                }
+                Ok(Expr::Call(func, args))
+            }
+            Token::Ident(name) => Ok(Expr::Param(name)),
+            Token::Symbol('(') => {
+                let expr = self.sum()?;
+                if !self.eat(')') {
+                    return Err("expected `)`".to_string());
+                }
+                Ok(expr)
+            }
+            Token::Symbol('-') => Ok(Expr::Binary(Box::new(Expr::Const(0)), BinOp::Sub, Box::new(self.atom()?))),
+            Token::Symbol(c) => Err(format!("unexpected `{}`", c)),
+        }
+    }
+}
+
+
+/// One kind of operation with a symbolic count and size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolicOp {
+    pub kind: OpKind,
+    pub num: Expr,
+    pub size: Expr,
+    pub label: Option<String>,
+    pub phase: Option<String>,
+    /// for multi-pairings whose G2 inputs are partly static, set by `static_g2`
+    pub static_g2: Option<StaticG2>,
+}
+
+/// The G2 inputs of a multi-pairing op that stay the same across runs, e.g. committee public
+/// keys and generators: the first `per_op` of every multi-pairing, taken from `num` points
+/// named `label`. Ops with the same label share their points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StaticG2 {
+    pub label: String,
+    pub per_op: usize,
+    pub num: Expr,
+}
+
+/// The phase in which `Protocol::with_static_g2` prepares the static G2 points.
+pub const STATIC_G2_PHASE : &str = "Static G2";
+
+/// In a workload of a protocol `with_static_g2`, the static G2 points prepared once and the
+/// preparations its multi-pairings skip by reusing them, which is what preparing them in every
+/// multi-pairing would add.
+pub fn static_g2_preparations(workload: &Workload) -> (usize, usize) {
+    workload.ops.iter().fold((0, 0), |(prepared, skipped), op| match op.kind {
+        OpKind::G2Preparations if op.phase.as_deref() == Some(STATIC_G2_PHASE) => (prepared + op.num, skipped),
+        OpKind::MultiPairingsStatic { static_inputs } => (prepared, skipped + op.num * static_inputs),
+        _ => (prepared, skipped),
+    })
+}
+
+/// `num` elements that a phase sends, with a symbolic count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolicMessage {
+    pub element: Element,
+    pub num: Expr,
+    pub label: Option<String>,
+    pub phase: Option<String>,
+}
+
+/// A constraint on a protocol's parameters, with why they fail it.
+pub type ParamsCheck = fn(&Params) -> Result<(), String>;
+
+/// A protocol's operations and messages in terms of its parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Protocol {
+    pub name: String,
+    pub ops: Vec<SymbolicOp>,
+    pub messages: Vec<SymbolicMessage>,
+    /// the units, e.g. transcripts, that one run handles and how many, set by `throughput`
+    pub throughput: Option<(String, Expr)>,
+    /// constraints on the parameters beyond what the counts need, added by `check`
+    pub checks: Vec<ParamsCheck>,
+    // phase that `op` adds to, set by `phase`
+    current_phase: Option<String>,
+}
+
+impl Protocol {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ops: Vec::new(), messages: Vec::new(), throughput: None, checks: Vec::new(), current_phase: None }
+    }
+
+    /// Adds `num` operations of the given size. Both are expressions over the protocol's
+    /// parameters; this panics if they don't parse, use `try_op` for expressions that aren't
+    /// fixed in the source.
+    pub fn op(self, kind: OpKind, num: &str, size: &str) -> Self {
+        self.try_op(kind, num, size).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_op(mut self, kind: OpKind, num: &str, size: &str) -> Result<Self, ExprError> {
+        self.ops.push(SymbolicOp {
+            kind,
+            num: Expr::parse(num)?,
+            size: Expr::parse(size)?,
+            label: None,
+            phase: self.current_phase.clone(),
+            static_g2: None,
+        });
+        Ok(self)
+    }
+
+    /// Adds a message of `num` elements sent in the current phase; panics if `num` doesn't parse.
+    pub fn message(mut self, label: &str, element: Element, num: &str) -> Self {
+        self.messages.push(SymbolicMessage {
+            element,
+            num: Expr::parse(num).unwrap_or_else(|e| panic!("{}", e)),
+            label: Some(label.to_string()),
+            phase: self.current_phase.clone(),
+        });
+        self
+    }
+
+    /// Labels the most recently added op.
+    pub fn label(mut self, label: &str) -> Self {
+        if let Some(op) = self.ops.last_mut() {
+            op.label = Some(label.to_string());
+        }
+        self
+    }
+
+    /// Marks the first `per_op` G2 inputs of every pairing of the most recently added op, a
+    /// multi-pairing, as static: taken from `num` points named `label` that are the same in
+    /// every run; panics if `num` doesn't parse. Only `with_static_g2` makes use of it.
+    pub fn static_g2(mut self, label: &str, per_op: usize, num: &str) -> Self {
+        if let Some(op) = self.ops.last_mut() {
+            assert!(op.kind == OpKind::MultiPairings, "only multi-pairings have static G2 inputs");
+            op.static_g2 = Some(StaticG2 { label: label.to_string(), per_op, num: Expr::parse(num).unwrap_or_else(|e| panic!("{}", e)) });
+        }
+        self
+    }
+
+    /// The protocol with its static G2 points prepared once, in a first phase `STATIC_G2_PHASE`
+    /// (one `G2Preparations` op per label), and every multi-pairing marked by `static_g2`
+    /// reusing them instead of preparing them again (`MultiPairingsStatic`).
+    pub fn with_static_g2(&self) -> Protocol {
+        let mut preparations : Vec<SymbolicOp> = Vec::new();
+        let mut ops = Vec::new();
+        for op in &self.ops {
+            let Some(static_g2) = &op.static_g2 else {
+                ops.push(op.clone());
+                continue;
+            };
+            if !preparations.iter().any(|preparation| preparation.label.as_ref() == Some(&static_g2.label)) {
+                preparations.push(SymbolicOp {
+                    kind: OpKind::G2Preparations,
+                    num: static_g2.num.clone(),
+                    size: Expr::Const(1),
+                    label: Some(static_g2.label.clone()),
+                    phase: Some(STATIC_G2_PHASE.to_string()),
+                    static_g2: None,
+                });
+            }
+            ops.push(SymbolicOp { kind: OpKind::MultiPairingsStatic { static_inputs: static_g2.per_op }, ..op.clone() });
+        }
+        preparations.extend(ops);
+        Protocol { ops: preparations, ..self.clone() }
+    }
+
+    /// Adds a constraint on the parameters, e.g. that a threshold is below the number of
+    /// parties, which `evaluate` and `communication` check before the counts, so that it explains
+    /// parameters that would also make a count negative or overflow.
+    pub fn check(mut self, check: ParamsCheck) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    fn check_params(&self, params: &Params) -> Result<(), ExprError> {
+        self.checks.iter().try_for_each(|check| check(params).map_err(ExprError::InvalidParams))
+    }
+
+    /// Ops added from here on belong to the phase `name`.
+    pub fn phase(mut self, name: &str) -> Self {
+        self.current_phase = Some(name.to_string());
+        self
+    }
+
+    /// One run handles `num` units named `unit` (plural), e.g. a relay's batch of transcripts, so
+    /// that sweeps report the units per second; panics if `num` doesn't parse.
+    pub fn throughput(mut self, unit: &str, num: &str) -> Self {
+        self.throughput = Some((unit.to_string(), Expr::parse(num).unwrap_or_else(|e| panic!("{}", e))));
+        self
+    }
+
+    /// How many units of `throughput` one run handles, if the protocol has any.
+    pub fn units(&self, params: &Params) -> Result<Option<usize>, ExprError> {
+        self.throughput.as_ref().map(|(_, num)| num.eval_count(params)).transpose()
+    }
+
+    /// Parameter names that the op counts refer to, in order of first use.
+    pub fn params(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        let exprs = self.ops.iter().flat_map(|op| [&op.num, &op.size]).chain(self.messages.iter().map(|m| &m.num))
+            .chain(self.throughput.iter().map(|(_, num)| num));
+        for expr in exprs {
+            for name in expr.params() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    pub fn evaluate(&self, params: &Params) -> Result<Workload, ExprError> {
+        self.check_params(params)?;
+        let ops = self.ops.iter()
+            .map(|op| {
+                let size = if op.kind.is_sized() { op.size.eval_count(params)? } else { 1 };
+                Ok(Op {
+                    kind: op.kind,
+                    num: op.num.eval_count(params)?,
+                    size,
+                    sizes: None,
+                    label: op.label.clone(),
+                    phase: op.phase.clone(),
+                })
+            })
+            .collect::<Result<_, ExprError>>()?;
+        Ok(Workload { ops })
+    }
+
+    /// The op counts of `evaluate`, phase by phase and kind by kind, as the `ops` command
+    /// exports them and the golden files under `tests/golden` record them.
+    pub fn expand(&self, params: &Params) -> Result<OpCounts, ExprError> {
+        Ok(OpCounts::new(&self.evaluate(params)?))
+    }
+
+    /// The protocol's messages for concrete parameters. The parameter `n`, if set, is taken as
+    /// the number of parties receiving them.
+    pub fn communication(&self, params: &Params) -> Result<Communication, ExprError> {
+        self.check_params(params)?;
+        let messages = self.messages.iter()
+            .map(|message| Ok(Message {
+                element: message.element,
+                num: message.num.eval_count(params)?,
+                label: message.label.clone(),
+                phase: message.phase.clone(),
+            }))
+            .collect::<Result<_, ExprError>>()?;
+        Ok(Communication { messages, parties: params.get("n").map(|n| n as usize) })
+    }
+}
@@ -0,0 +1,53 @@
+use crate::polynomial::horner;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand::RngCore;
+
+
+pub struct Keypair {
+    pub secret: Scalar,
+    pub public: G1Projective,
+}
+
+impl Keypair {
+    pub fn generate(rng: &mut impl RngCore) -> Self {
+        let secret = Scalar::random(rng);
+        Self { secret, public: G1Projective::generator() * secret }
+    }
+}
+
+/// The dealer's transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dealing {
+    /// `A_j = g2^{a_j}` for the `t + 1` coefficients
+    pub coefficient_commitments: Vec<G2Projective>,
+    /// `v_i = g2^{p(i)}`, one per party
+    pub share_commitments: Vec<G2Projective>,
+    /// `Y_i = pk_i^{p(i)}`, one per party
+    pub encrypted_shares: Vec<G1Projective>,
+}
+
+impl Dealing {
+    pub fn threshold(&self) -> usize {
+        self.coefficient_commitments.len() - 1
+    }
+}
+
+/// Shares `secret` among the owners of `public_keys` with a degree-`t` polynomial, so any `t + 1`
+/// of them can reconstruct `g1^secret`.
+pub fn deal(secret: &Scalar, t: usize, public_keys: &[G1Projective], rng: &mut impl RngCore) -> Dealing {
+    assert!(t < public_keys.len(), "threshold must be below the number of parties");
+    let coefficients : Vec<Scalar> = std::iter::once(*secret)
+        .chain((0..t).map(|_| Scalar::random(&mut *rng)))
+        .collect();
+    let shares : Vec<Scalar> = (1..=public_keys.len() as u64)
+        .map(|i| horner(&coefficients, &Scalar::from(i)))
+        .collect();
+    Dealing {
+        coefficient_commitments: coefficients.iter().map(|a| G2Projective::generator() * a).collect(),
+        share_commitments: shares.iter().map(|s| G2Projective::generator() * s).collect(),
+        encrypted_shares: public_keys.iter().zip(&shares).map(|(pk, s)| pk * s).collect(),
+    }
+}
@@ -0,0 +1,56 @@
+use crate::dleq::DleqProof;
+use crate::polynomial::lagrange_coefficients_at_zero;
+use crate::pvss::{Dealing, Keypair, VerificationError};
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand::RngCore;
+
+
+/// A party's decrypted share `S_i = Y_i^{1/sk_i} = g1^{p(i)}`, with a proof that it was
+/// decrypted with the secret key of `pk_i`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecryptedShare {
+    /// the party's index (0-based), so the share is the evaluation at `index + 1`
+    pub index: usize,
+    pub share: G1Projective,
+    /// proves `log_g1 pk_i = log_{S_i} Y_i`
+    pub proof: DleqProof,
+}
+
+/// Decrypts the share of party `index`. Costs an inversion and three G1 exponentiations.
+pub fn decrypt_share(dealing: &Dealing, index: usize, key: &Keypair, rng: &mut impl RngCore) -> DecryptedShare {
+    let encrypted = &dealing.encrypted_shares[index];
+    let share = encrypted * Option::<Scalar>::from(key.secret.invert()).expect("secret key must not be zero");
+    let proof = DleqProof::prove(&G1Projective::generator(), &share, &key.public, encrypted, &key.secret, rng);
+    DecryptedShare { index, share, proof }
+}
+
+/// Checks the decryption proof of `share` against the party's public key. Costs four G1
+/// exponentiations.
+pub fn verify_decryption(dealing: &Dealing, public_key: &G1Projective, share: &DecryptedShare) -> bool {
+    match dealing.encrypted_shares.get(share.index) {
+        Some(encrypted) => share.proof.verify(&G1Projective::generator(), &share.share, public_key, encrypted),
+        None => false,
+    }
+}
+
+/// Verifies the first `t + 1` of `shares` and recovers `g1^secret` from them by Lagrange
+/// interpolation in the exponent, with one G1 multi-exp of size `t + 1`. The `t + 1` must be of
+/// distinct parties: two points at the same index would make the interpolation divide by zero.
+pub fn reconstruct(dealing: &Dealing, public_keys: &[G1Projective], shares: &[DecryptedShare]) -> Result<G1Projective, VerificationError> {
+    let shares = shares.get(..dealing.threshold() + 1).ok_or(VerificationError::NotEnoughShares)?;
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|earlier| earlier.index == share.index) {
+            return Err(VerificationError::DuplicateShare(share.index));
+        }
+        let valid = public_keys.get(share.index).is_some_and(|pk| verify_decryption(dealing, pk, share));
+        if !valid {
+            return Err(VerificationError::InvalidDecryption(share.index));
+        }
+    }
+    let points : Vec<Scalar> = shares.iter().map(|share| Scalar::from(share.index as u64 + 1)).collect();
+    let bases : Vec<G1Projective> = shares.iter().map(|share| share.share).collect();
+    Ok(G1Projective::multi_exp(&bases, &lagrange_coefficients_at_zero(&points)))
+}
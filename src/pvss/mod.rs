@@ -0,0 +1,20 @@
+//! An actual PVSS over BLS12-381, to check the simulated op counts against a real
+//! implementation's measured time.
+//!
+//! Parties hold keys `pk_i = g1^{sk_i}`. The dealer shares `s` with a random degree-`t`
+//! polynomial `p` (`p(0) = s`, party `i` gets `p(i)` for `i = 1..=n`) and publishes
+//!
+//! - Feldman commitments `A_j = g2^{a_j}` to the coefficients of `p`,
+//! - share commitments `v_i = g2^{p(i)}`,
+//! - encrypted shares `Y_i = pk_i^{p(i)}`.
+//!
+//! Party `i` decrypts `S_i = Y_i^{1/sk_i} = g1^{p(i)}` and proves it did so correctly; any
+//! `t + 1` decrypted shares give `g1^s` by Lagrange interpolation in the exponent.
+
+mod dealing;
+mod decryption;
+mod verification;
+
+pub use dealing::{deal, Dealing, Keypair};
+pub use decryption::{decrypt_share, reconstruct, verify_decryption, DecryptedShare};
+pub use verification::{check_encryptions, check_share_commitments, verify_dealing, VerificationError};
@@ -0,0 +1,91 @@
+use crate::curve_ops::BatchToAffine;
+use crate::pvss::Dealing;
+
+use blstrs::{Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar};
+use ff::Field as _;
+use group::{prime::PrimeCurveAffine as _, Group as _};
+use pairing::{MillerLoopResult as _, MultiMillerLoop as _};
+use rand::RngCore;
+use std::fmt;
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    /// the transcript doesn't have one share commitment and encrypted share per public key
+    WrongLength,
+    /// the share commitments don't lie on the committed polynomial
+    InconsistentCommitments,
+    /// the encrypted share of this party (0-based) doesn't match its share commitment
+    InvalidEncryption(usize),
+    /// fewer than `t + 1` decrypted shares were given
+    NotEnoughShares,
+    /// the decryption proof of this party's share doesn't verify
+    InvalidDecryption(usize),
+    /// this party's share was given more than once among the `t + 1` used
+    DuplicateShare(usize),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::WrongLength => write!(f, "transcript length doesn't match the number of parties"),
+            VerificationError::InconsistentCommitments => write!(f, "share commitments are inconsistent with the coefficient commitments"),
+            VerificationError::InvalidEncryption(i) => write!(f, "encrypted share {} doesn't match its commitment", i),
+            VerificationError::NotEnoughShares => write!(f, "not enough decrypted shares to reconstruct"),
+            VerificationError::InvalidDecryption(i) => write!(f, "decrypted share {} has an invalid proof", i),
+            VerificationError::DuplicateShare(i) => write!(f, "decrypted share {} was given more than once", i),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// Checks everything a third party can about `dealing`, without any secret key:
+/// `check_share_commitments` and then `check_encryptions`.
+pub fn verify_dealing(dealing: &Dealing, public_keys: &[G1Projective], rng: &mut impl RngCore) -> Result<(), VerificationError> {
+    if dealing.share_commitments.len() != public_keys.len() || dealing.encrypted_shares.len() != public_keys.len() {
+        return Err(VerificationError::WrongLength);
+    }
+    check_share_commitments(dealing, rng)?;
+    check_encryptions(dealing, public_keys)
+}
+
+/// Checks `v_i = ∏_j A_j^{i^j}` for all parties at once, via a random linear combination:
+/// `∏_i v_i^{r_i} = ∏_j A_j^{Σ_i r_i i^j}`. Costs two G2 multi-exps, of size n and t + 1.
+pub fn check_share_commitments(dealing: &Dealing, rng: &mut impl RngCore) -> Result<(), VerificationError> {
+    let n = dealing.share_commitments.len();
+    let r : Vec<Scalar> = (0..n).map(|_| Scalar::random(&mut *rng)).collect();
+    let mut exponents = vec![Scalar::ZERO; dealing.coefficient_commitments.len()];
+    for (i, r_i) in (1..=n as u64).zip(&r) {
+        let x = Scalar::from(i);
+        let mut power = *r_i;
+        for e in &mut exponents {
+            *e += power;
+            power *= x;
+        }
+    }
+    let lhs = G2Projective::multi_exp(&dealing.share_commitments, &r);
+    let rhs = G2Projective::multi_exp(&dealing.coefficient_commitments, &exponents);
+    if lhs == rhs {
+        Ok(())
+    } else {
+        Err(VerificationError::InconsistentCommitments)
+    }
+}
+
+/// Checks `e(pk_i, v_i) = e(Y_i, g2)` for every party, with one multi-pairing of size 2 each.
+pub fn check_encryptions(dealing: &Dealing, public_keys: &[G1Projective]) -> Result<(), VerificationError> {
+    let public_keys = G1Projective::batch_to_affine(public_keys);
+    let negated_shares : Vec<G1Projective> = dealing.encrypted_shares.iter().map(|y| -y).collect();
+    let negated_shares = G1Projective::batch_to_affine(&negated_shares);
+    let commitments = G2Projective::batch_to_affine(&dealing.share_commitments);
+    let g2 = G2Prepared::from(G2Affine::generator());
+    for (i, ((pk, y), v)) in public_keys.iter().zip(&negated_shares).zip(commitments).enumerate() {
+        let v = G2Prepared::from(v);
+        let terms : [(&G1Affine, &G2Prepared); 2] = [(pk, &v), (y, &g2)];
+        if !bool::from(Bls12::multi_miller_loop(&terms).final_exponentiation().is_identity()) {
+            return Err(VerificationError::InvalidEncryption(i));
+        }
+    }
+    Ok(())
+}
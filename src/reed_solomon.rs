@@ -0,0 +1,226 @@
+//! Systematic Reed–Solomon codes over the scalar field, as dissemination layers use to
+//! erasure-code transcripts: a payload of `k` scalars is the evaluations at `1..=k` of the
+//! polynomial of degree below `k` through them, extended with its evaluations at `k+1..=n`.
+//! Any `k` of the `n` symbols give the payload back.
+//!
+//! Encoding multiplies the payload by the code's generator matrix, which depends only on `n`
+//! and `k` and is precomputed: `k` multiplications per parity symbol. Decoding depends on which
+//! symbols arrived, so it first derives the interpolation weights of those (`O(k^2)`
+//! multiplications and one batch inversion), then recovers every missing payload symbol as
+//! their combination. A payload longer than `k` scalars is split into codewords that arrive on
+//! the same shares and share the weights.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::Scalar;
+use ff::{BatchInvert as _, Field as _};
+use rand::RngCore;
+use rand::seq::index::sample;
+use std::hint::black_box;
+
+
+/// For every point of `at`, the coefficients of the values at `from` that interpolate the
+/// polynomial of degree below `from.len()` at it: `L_j(x) = w_j ∏_l (x - x_l) / (x - x_j)` in
+/// barycentric form, with `w_j = 1 / ∏_{l≠j} (x_j - x_l)`. No point of `at` may be in `from`.
+pub fn interpolation_rows(from: &[Scalar], at: &[Scalar]) -> Vec<Vec<Scalar>> {
+    let mut weights : Vec<Scalar> = from.iter()
+        .enumerate()
+        .map(|(j, x_j)| from.iter().enumerate().filter(|(l, _)| *l != j).map(|(_, x_l)| x_j - x_l).product())
+        .collect();
+    let mut differences : Vec<Scalar> = at.iter().flat_map(|x| from.iter().map(move |x_j| x - x_j)).collect();
+    // one inversion for both
+    weights.iter_mut().chain(differences.iter_mut()).batch_invert();
+    at.iter().zip(differences.chunks_exact(from.len()))
+        .map(|(x, inverses)| {
+            let vanishing : Scalar = from.iter().map(|x_j| x - x_j).product();
+            weights.iter().zip(inverses).map(|(w, inverse)| vanishing * w * inverse).collect()
+        })
+        .collect()
+}
+
+fn points(indices: impl IntoIterator<Item = usize>) -> Vec<Scalar> {
+    indices.into_iter().map(|i| Scalar::from(i as u64 + 1)).collect()
+}
+
+/// The code of `n` symbols for payloads of `k`, with its generator matrix.
+pub struct ReedSolomonCode {
+    n: usize,
+    k: usize,
+    // the row of every parity symbol
+    parity_rows: Vec<Vec<Scalar>>,
+}
+
+impl ReedSolomonCode {
+    pub fn new(n: usize, k: usize) -> Self {
+        assert!(1 <= k && k <= n, "a code needs 1 <= k <= n");
+        Self { n, k, parity_rows: interpolation_rows(&points(0..k), &points(k..n)) }
+    }
+
+    /// The `n` symbols of `payload`, which come first.
+    pub fn encode(&self, payload: &[Scalar]) -> Vec<Scalar> {
+        assert_eq!(payload.len(), self.k, "payloads have k symbols");
+        let parity = self.parity_rows.iter().map(|row| row.iter().zip(payload).map(|(c, m)| c * m).sum());
+        payload.iter().copied().chain(parity).collect()
+    }
+
+    /// The decoder for codewords of which the symbols at `shares` (`k` distinct indices below
+    /// `n`, in any order) arrived.
+    pub fn decoder(&self, shares: &[usize]) -> Decoder {
+        assert_eq!(shares.len(), self.k, "decoding takes k shares");
+        let missing : Vec<usize> = (0..self.k).filter(|i| !shares.contains(i)).collect();
+        let rows = interpolation_rows(&points(shares.iter().copied()), &points(missing.iter().copied()));
+        Decoder { shares: shares.to_vec(), missing, rows }
+    }
+}
+
+/// Recovers payloads from the same `k` shares of their codewords.
+pub struct Decoder {
+    shares: Vec<usize>,
+    // the payload indices that didn't arrive, and the coefficients of the received symbols
+    // that give each
+    missing: Vec<usize>,
+    rows: Vec<Vec<Scalar>>,
+}
+
+impl Decoder {
+    /// The payload of a codeword whose symbols at the decoder's shares are `received`, in the
+    /// order of the shares.
+    pub fn decode(&self, received: &[Scalar]) -> Vec<Scalar> {
+        let mut payload = vec![Scalar::ZERO; received.len()];
+        for (&i, symbol) in self.shares.iter().zip(received) {
+            if let Some(slot) = payload.get_mut(i) {
+                *slot = *symbol;
+            }
+        }
+        for (&i, row) in self.missing.iter().zip(&self.rows) {
+            payload[i] = row.iter().zip(received).map(|(c, y)| c * y).sum();
+        }
+        payload
+    }
+}
+
+// the polynomial through `(x_i, y_i)` at `x`, with one inversion per term
+fn naive_interpolation(xs: &[Scalar], ys: &[Scalar], x: &Scalar) -> Scalar {
+    xs.iter().zip(ys).enumerate()
+        .map(|(i, (x_i, y_i))| {
+            let (numerator, denominator) = xs.iter().enumerate().filter(|(j, _)| *j != i)
+                .fold((Scalar::ONE, Scalar::ONE), |(num, den), (_, x_j)| (num * (x - x_j), den * (x_i - x_j)));
+            *y_i * numerator * denominator.invert().unwrap()
+        })
+        .sum()
+}
+
+struct ReedSolomonEncodings {
+    code: ReedSolomonCode,
+    payloads: Vec<Vec<Scalar>>,
+}
+
+impl GroupOpsSimulationItem for ReedSolomonEncodings {
+    fn description(&self) -> String {
+        format!("Reed–Solomon encodings: {} of {} symbols into {}", self.payloads.len(), self.code.k, self.code.n)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.payloads.len(), self.code.n)
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Vec<Scalar>>>(self.payloads.iter().map(|payload| self.code.encode(payload)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.payloads.len(), |i| self.code.encode(&self.payloads[i])));
+    }
+
+    fn ops(&self) -> usize {
+        self.payloads.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.code.encode(&self.payloads[i]));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        let xs = points(0..self.code.k);
+        Some(self.payloads.iter().all(|payload| {
+            let codeword = self.code.encode(payload);
+            points(0..self.code.n).iter().zip(&codeword).all(|(x, symbol)| naive_interpolation(&xs, payload, x) == *symbol)
+        }))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let symbols : Vec<Scalar> = self.payloads.iter().flat_map(|payload| self.code.encode(payload)).collect();
+        Some(symbols.iter().flat_map(Scalar::to_bytes_le).collect())
+    }
+}
+
+struct ReedSolomonDecodings {
+    code: ReedSolomonCode,
+    shares: Vec<usize>,
+    payloads: Vec<Vec<Scalar>>,
+    // the symbols of every codeword at `shares`
+    received: Vec<Vec<Scalar>>,
+}
+
+impl ReedSolomonDecodings {
+    fn decode(&self) -> Vec<Vec<Scalar>> {
+        let decoder = self.code.decoder(&self.shares);
+        self.received.iter().map(|received| decoder.decode(received)).collect()
+    }
+}
+
+impl GroupOpsSimulationItem for ReedSolomonDecodings {
+    fn description(&self) -> String {
+        format!("Reed–Solomon decodings: {} of {} symbols from {} of {}", self.received.len(), self.code.k, self.code.k, self.code.n)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.received.len(), self.code.k)
+    }
+
+    fn simulate(&self) {
+        black_box(self.decode());
+    }
+
+    fn notes(&self) -> Option<String> {
+        let missing = (0..self.code.k).filter(|i| !self.shares.contains(i)).count();
+        Some(format!("{} of the {} payload symbols recovered per codeword, with one set of weights", missing, self.code.k))
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.decode() == self.payloads)
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(self.decode().concat().iter().flat_map(Scalar::to_bytes_le).collect())
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// Systematic encodings of `num` random payloads of `k` scalars into `n` symbols each, with
+    /// the generator matrix precomputed.
+    pub fn reed_solomon_encodings(&mut self, num: usize, n: usize, k: usize) -> &mut Self {
+        let code = ReedSolomonCode::new(n, k);
+        let payloads = (0..num).map(|_| (0..k).map(|_| Scalar::random(&mut *self.rng())).collect()).collect();
+        self.push_item(ReedSolomonEncodings { code, payloads })
+    }
+
+    /// Decodings of `num` codewords of `n` symbols from the same random `k` of them, deriving the
+    /// interpolation weights once.
+    pub fn reed_solomon_decodings(&mut self, num: usize, n: usize, k: usize) -> &mut Self {
+        let code = ReedSolomonCode::new(n, k);
+        let shares = sample(self.rng(), n, k).into_vec();
+        let payloads : Vec<Vec<Scalar>> = (0..num).map(|_| (0..k).map(|_| Scalar::random(&mut *self.rng())).collect()).collect();
+        let received = payloads.iter()
+            .map(|payload| {
+                let codeword = code.encode(payload);
+                shares.iter().map(|&i| codeword[i]).collect()
+            })
+            .collect();
+        self.push_item(ReedSolomonDecodings { code, shares, payloads, received })
+    }
+}
@@ -0,0 +1,294 @@
+//! Result tables for pasting into papers: plain text, Markdown or a LaTeX `tabular`, with times
+//! in a chosen unit and to a chosen number of significant digits, with or without the rows or
+//! columns of totals, and optionally each time's share of its total.
+
+use crate::communication::format_bytes;
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Markdown,
+    Latex,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Format::Text),
+            "markdown" | "md" => Some(Format::Markdown),
+            "latex" | "tex" => Some(Format::Latex),
+            _ => None,
+        }
+    }
+}
+
+/// Unit of the times in a table. `Auto` picks one per cell and writes it next to the number;
+/// a fixed unit goes into the column headers instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    #[default]
+    Auto,
+    Ns,
+    Us,
+    Ms,
+    S,
+    /// multiples of the time of one G1 exponentiation, the cost measure of PVSS papers
+    G1Exps(Duration),
+    /// clock cycles at the given frequency in Hz
+    Cycles(u64),
+}
+
+impl Unit {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Unit::Auto),
+            "ns" => Some(Unit::Ns),
+            "us" | "µs" => Some(Unit::Us),
+            "ms" => Some(Unit::Ms),
+            "s" => Some(Unit::S),
+            _ => Some(Unit::Cycles(parse_frequency(name.strip_prefix("cycles@")?)?)),
+        }
+    }
+
+    fn for_duration(duration: Duration) -> Self {
+        match duration.as_nanos() {
+            0..=999 => Unit::Ns,
+            1_000..=999_999 => Unit::Us,
+            1_000_000..=999_999_999 => Unit::Ms,
+            _ => Unit::S,
+        }
+    }
+
+    fn nanos(self) -> f64 {
+        match self {
+            Unit::Auto | Unit::Ns => 1.0,
+            Unit::Us => 1e3,
+            Unit::Ms => 1e6,
+            Unit::S => 1e9,
+            Unit::G1Exps(exp) => exp.as_nanos().max(1) as f64,
+            Unit::Cycles(hz) => 1e9 / hz.max(1) as f64,
+        }
+    }
+
+    fn symbol(self, format: Format) -> &'static str {
+        match (self, format) {
+            (Unit::Auto, _) => "",
+            (Unit::Ns, _) => "ns",
+            (Unit::Us, Format::Latex) => "$\\mu$s",
+            (Unit::Us, _) => "µs",
+            (Unit::Ms, _) => "ms",
+            (Unit::S, _) => "s",
+            (Unit::G1Exps(_), _) => "G1 exps",
+            (Unit::Cycles(_), _) => "cycles",
+        }
+    }
+}
+
+/// A frequency such as `3.5GHz`, `2400MHz` or `3e9` (in Hz), in Hz.
+pub fn parse_frequency(text: &str) -> Option<u64> {
+    let (number, scale) = [("GHz", 1e9), ("MHz", 1e6), ("kHz", 1e3), ("Hz", 1.0)].into_iter()
+        .find_map(|(suffix, scale)| Some((text.strip_suffix(suffix)?, scale)))
+        .unwrap_or((text, 1.0));
+    let hz = number.trim().parse::<f64>().ok()? * scale;
+    (hz >= 1.0 && hz.is_finite()).then_some(hz.round() as u64)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    pub format: Format,
+    pub unit: Unit,
+    /// significant digits of times
+    pub digits: usize,
+    /// whether to keep the table's row or column of totals
+    pub totals: bool,
+    /// whether to follow every time column with each time's percentage of its total
+    pub percent: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self { format: Format::Text, unit: Unit::Auto, digits: 3, totals: true, percent: false }
+    }
+}
+
+impl Style {
+    /// `duration` in the style's unit, to `digits` significant digits (never dropping digits
+    /// before the decimal point).
+    pub fn duration(&self, duration: Duration) -> String {
+        let unit = if self.unit == Unit::Auto { Unit::for_duration(duration) } else { self.unit };
+        let value = duration.as_nanos() as f64 / unit.nanos();
+        let magnitude = if value == 0.0 { 0 } else { value.abs().log10().floor() as i64 };
+        let decimals = (self.digits as i64 - 1 - magnitude).max(0) as usize;
+        let number = format!("{:.*}", decimals, value);
+        match self.unit {
+            Unit::Auto => format!("{} {}", number, unit.symbol(self.format)),
+            _ => number,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Time(Duration),
+    Bytes(usize),
+    Percent(f64),
+    Empty,
+}
+
+/// The row or column of a table that sums up the others, e.g. the total of a run's phases, or of
+/// every sweep point's phases.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Totals {
+    #[default]
+    None,
+    Row(usize),
+    Column(usize),
+}
+
+/// A header and rows of cells; columns holding any non-numeric text are left-aligned, all others
+/// right-aligned. Headers of time columns get the unit appended when it is fixed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+    pub totals: Totals,
+}
+
+impl Table {
+    /// The table as `style` has it: with a percentage column after every time column if
+    /// `percent`, each time's share of the total of its column (`Totals::Row`) or its row
+    /// (`Totals::Column`), and without the totals unless `totals`.
+    fn arranged(&self, style: &Style) -> (Vec<String>, Vec<Vec<Cell>>) {
+        let time = |row: usize, column: usize| match self.rows[row].get(column) {
+            Some(Cell::Time(d)) => Some(*d),
+            _ => None,
+        };
+        let is_time = |column: usize| (0..self.rows.len()).any(|row| time(row, column).is_some());
+        let share = |row: usize, column: usize| {
+            let total = match self.totals {
+                Totals::Row(total) if total != row => time(total, column)?,
+                Totals::Column(total) if total != column => time(row, total)?,
+                _ => return None,
+            };
+            Some(Cell::Percent(100.0 * time(row, column)?.as_secs_f64() / total.as_secs_f64()))
+        };
+        let with_shares = |column: usize| style.percent && is_time(column) && self.totals != Totals::None && self.totals != Totals::Column(column);
+        let kept_row = |row: usize| style.totals || self.totals != Totals::Row(row);
+        let kept_column = |column: usize| style.totals || self.totals != Totals::Column(column);
+
+        let mut header = Vec::new();
+        for (i, name) in self.header.iter().enumerate().filter(|&(i, _)| kept_column(i)) {
+            header.push(name.clone());
+            if with_shares(i) {
+                header.push("%".to_string());
+            }
+        }
+        let rows = (0..self.rows.len())
+            .filter(|&row| kept_row(row))
+            .map(|row| {
+                let mut cells = Vec::new();
+                for i in (0..self.header.len()).filter(|&i| kept_column(i)) {
+                    cells.push(self.rows[row].get(i).cloned().unwrap_or(Cell::Empty));
+                    if with_shares(i) {
+                        cells.push(share(row, i).unwrap_or(Cell::Empty));
+                    }
+                }
+                cells
+            })
+            .collect();
+        (header, rows)
+    }
+
+    pub fn render(&self, style: &Style) -> String {
+        let (header, table_rows) = self.arranged(style);
+        let columns = header.len();
+        let column = |i: usize| table_rows.iter().filter_map(move |row| row.get(i));
+        let is_time = |i: usize| column(i).any(|cell| matches!(cell, Cell::Time(_)));
+        let left_aligned : Vec<bool> = (0..columns)
+            .map(|i| column(i).any(|cell| matches!(cell, Cell::Text(text) if text.parse::<f64>().is_err())))
+            .collect();
+        let text = |s: &str| match style.format {
+            Format::Latex => escape_latex(s),
+            _ => s.to_string(),
+        };
+        let header : Vec<String> = header.iter().enumerate()
+            .map(|(i, name)| match style.unit {
+                Unit::Auto => text(name),
+                unit if is_time(i) => format!("{} ({})", text(name), unit.symbol(style.format)),
+                _ => text(name),
+            })
+            .collect();
+        let rows : Vec<Vec<String>> = table_rows.iter()
+            .map(|row| (0..columns)
+                .map(|i| match row.get(i).unwrap_or(&Cell::Empty) {
+                    Cell::Text(s) => text(s),
+                    Cell::Time(d) => style.duration(*d),
+                    Cell::Bytes(bytes) => text(&format_bytes(*bytes)),
+                    Cell::Percent(percent) => text(&format!("{:.1}%", percent)),
+                    Cell::Empty => String::new(),
+                })
+                .collect())
+            .collect();
+        let widths : Vec<usize> = (0..columns)
+            .map(|i| rows.iter().map(|row| row[i].chars().count()).chain([header[i].chars().count()]).max().unwrap())
+            .collect();
+        let line = |cells: &[String], separator: &str| -> String {
+            let cells : Vec<String> = cells.iter().zip(&widths).zip(&left_aligned)
+                .map(|((cell, &w), &left)| if left { format!("{:<w$}", cell, w = w) } else { format!("{:>w$}", cell, w = w) })
+                .collect();
+            cells.join(separator)
+        };
+
+        let mut out = String::new();
+        match style.format {
+            Format::Text => {
+                for row in [header].iter().chain(&rows) {
+                    writeln!(out, "{}", line(row, "  ").trim_end()).unwrap();
+                }
+            }
+            Format::Markdown => {
+                writeln!(out, "| {} |", line(&header, " | ")).unwrap();
+                let rule : Vec<String> = widths.iter().zip(&left_aligned)
+                    .map(|(&w, &left)| if left { format!(":{}", "-".repeat(w.max(2) - 1)) } else { format!("{}:", "-".repeat(w.max(2) - 1)) })
+                    .collect();
+                writeln!(out, "|{}|", rule.iter().map(|r| format!(" {} ", r)).collect::<Vec<_>>().join("|")).unwrap();
+                for row in &rows {
+                    writeln!(out, "| {} |", line(row, " | ")).unwrap();
+                }
+            }
+            Format::Latex => {
+                let spec : String = left_aligned.iter().map(|&left| if left { 'l' } else { 'r' }).collect();
+                writeln!(out, "\\begin{{tabular}}{{{}}}", spec).unwrap();
+                writeln!(out, "\\hline").unwrap();
+                writeln!(out, "{} \\\\", line(&header, " & ")).unwrap();
+                writeln!(out, "\\hline").unwrap();
+                for row in &rows {
+                    writeln!(out, "{} \\\\", line(row, " & ")).unwrap();
+                }
+                writeln!(out, "\\hline").unwrap();
+                writeln!(out, "\\end{{tabular}}").unwrap();
+            }
+        }
+        out
+    }
+}
+
+fn escape_latex(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => { out.push('\\'); out.push(c); }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '\\' => out.push_str("\\textbackslash{}"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,558 @@
+use crate::clock;
+use crate::communication::format_bytes;
+use crate::energy;
+use crate::machine::Machine;
+use crate::render::{Cell, Table, Totals};
+use crate::stats::Summary;
+
+use std::fmt;
+use std::ops::AddAssign;
+use std::time::{Duration, SystemTime};
+
+/// How many operations an item performs: `num` operations of `size` each, where `size` is e.g.
+/// the number of bases of a multi-exp, or 1 for single operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpCount {
+    pub num: usize,
+    pub size: usize,
+}
+
+impl OpCount {
+    pub fn single(num: usize) -> Self {
+        Self { num, size: 1 }
+    }
+
+    pub fn multi(num: usize, size: usize) -> Self {
+        Self { num, size }
+    }
+
+    /// `total` divided over the `num` operations, e.g. the time of one exponentiation.
+    pub fn per_op(&self, total: Duration) -> Option<Duration> {
+        (self.num > 0).then(|| total.div_f64(self.num as f64))
+    }
+
+    /// `total` divided over all `num * size` terms, e.g. the time per base of a multi-exp.
+    pub fn per_term(&self, total: Duration) -> Option<Duration> {
+        (self.num > 0 && self.size > 0).then(|| total.div_f64((self.num * self.size) as f64))
+    }
+}
+
+// "X/op, " or, for ops of several terms, "X/op, Y/term, ", then the cold start if measured
+fn amortized(op_count: OpCount, total: Duration, cold: Option<Duration>) -> String {
+    let mut out = match (op_count.per_op(total), op_count.per_term(total)) {
+        (Some(per_op), Some(per_term)) if op_count.size > 1 => format!("{:.3?}/op, {:.3?}/term, ", per_op, per_term),
+        (Some(per_op), _) => format!("{:.3?}/op, ", per_op),
+        _ => String::new(),
+    };
+    if let Some(cold) = cold {
+        out += &format!("first op cold {:.3?}, ", cold);
+    }
+    out
+}
+
+// how many allocations each op of an item makes, which hides in its time otherwise
+fn allocations_per_op(op_count: OpCount, memory: &MemoryUsage) -> String {
+    match op_count.num {
+        0 => String::new(),
+        num => format!(", {:.1} allocations/op", memory.allocations as f64 / num as f64),
+    }
+}
+
+/// Settings a report was produced with.
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    pub started_at: SystemTime,
+    pub threads: usize,
+    /// set when the inputs were derived from a known seed
+    pub seed: Option<u64>,
+    /// the ID of the machine the times were measured on, or of the profile they were predicted
+    /// from (see `machine`)
+    pub profile_id: Option<String>,
+    /// set when the run was interrupted (see `interrupt`), so that only the items reported
+    /// completed
+    pub partial: bool,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Self {
+            started_at: clock::system_time(),
+            threads: 1,
+            seed: None,
+            profile_id: Some(Machine::current().id()),
+            partial: false,
+        }
+    }
+}
+
+// "label (description)", or just the description for unlabeled items
+pub(crate) fn item_name(description: &str, label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("{} ({})", label, description),
+        None => description.to_string(),
+    }
+}
+
+// writes `lines` indented under a heading for every run of consecutive items in the same phase,
+// and returns the indices where each phase ends
+pub(crate) fn write_by_phase<T>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[T],
+    phase: impl Fn(&T) -> Option<&str>,
+    mut line: impl FnMut(&mut fmt::Formatter<'_>, &str, &T) -> fmt::Result,
+    mut phase_end: impl FnMut(&mut fmt::Formatter<'_>, &[T]) -> fmt::Result,
+) -> fmt::Result {
+    let mut start = 0;
+    while start < items.len() {
+        let current = phase(&items[start]);
+        let len = items[start..].iter().take_while(|item| phase(item) == current).count();
+        let group = &items[start..start + len];
+        match current {
+            Some(name) => {
+                writeln!(f, "  {}:", name)?;
+                group.iter().try_for_each(|item| line(f, "    ", item))?;
+                phase_end(f, group)?;
+            }
+            None => group.iter().try_for_each(|item| line(f, "  ", item))?,
+        }
+        start += len;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemReport {
+    pub description: String,
+    pub label: Option<String>,
+    /// the phase the item was added in, nested phases joined by " / "
+    pub phase: Option<String>,
+    /// the same phases apart, outermost first, empty outside any phase
+    pub phase_path: Vec<String>,
+    pub op_count: OpCount,
+    /// time spent drawing the item's inputs before the run, not included in `duration`
+    pub setup: Duration,
+    /// with cold-start measurement, the time of the item's first operation if it was the first
+    /// of its class to run (see `GroupOpsSimulation::cold_start`)
+    pub cold: Option<Duration>,
+    pub duration: Duration,
+    /// set when allocations are tracked (the `alloc-tracking` feature)
+    pub memory: Option<MemoryUsage>,
+    /// joules, set when energy is measured (`GroupOpsSimulation::measure_energy`)
+    pub energy: Option<f64>,
+    /// `GroupOpsSimulationItem::notes`
+    pub notes: Option<String>,
+}
+
+/// An item's heap usage, in bytes, and its number of allocations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// held by the item's inputs (bases, scalars, ...) from construction on
+    pub inputs: usize,
+    /// allocated while running the item
+    pub allocated: usize,
+    /// calls to the allocator (reallocations included) while running the item
+    pub allocations: usize,
+    /// most held at once while running the item, beyond what was held before
+    pub peak: usize,
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.allocations == 1 { "" } else { "s" };
+        write!(f, "inputs {}, allocated {} in {} allocation{}, peak {}", format_bytes(self.inputs), format_bytes(self.allocated), self.allocations, plural, format_bytes(self.peak))
+    }
+}
+
+impl ItemReport {
+    pub fn name(&self) -> String {
+        item_name(&self.description, self.label.as_deref())
+    }
+}
+
+/// Result of one simulation run.
+#[derive(Clone, Debug)]
+pub struct SimulationReport {
+    pub metadata: Metadata,
+    pub items: Vec<ItemReport>,
+    /// wall-clock time of the whole run
+    pub total: Duration,
+    /// CPU time of the whole run across all threads, where the platform can measure it
+    pub cpu: Option<Duration>,
+}
+
+// a row per phase and one for the total, with the execution and the setup time of each; `setup`
+// lists the same phases as `phases`
+fn phase_table(phases: Vec<(String, Duration)>, setup: Vec<(String, Duration)>, total: Duration, total_setup: Duration) -> Table {
+    let rows = phases.into_iter().zip(setup)
+        .map(|((name, duration), (_, setup))| vec![Cell::Text(name), Cell::Time(duration), Cell::Time(setup)])
+        .chain([vec![Cell::Text("total".to_string()), Cell::Time(total), Cell::Time(total_setup)]])
+        .collect::<Vec<_>>();
+    let totals = Totals::Row(rows.len() - 1);
+    Table { header: vec!["phase".to_string(), "time".to_string(), "setup".to_string()], rows, totals }
+}
+
+// sums `duration` (or another value, such as energy) over each phase, in order of first
+// appearance; unphased items are skipped
+fn phase_totals<'a, T: 'a, V: Copy + AddAssign>(items: &'a [T], phase: impl Fn(&T) -> Option<&str>, duration: impl Fn(&T) -> V) -> Vec<(String, V)> {
+    let mut totals : Vec<(String, V)> = Vec::new();
+    for item in items {
+        if let Some(name) = phase(item) {
+            match totals.iter_mut().find(|(n, _)| n == name) {
+                Some((_, total)) => *total += duration(item),
+                None => totals.push((name.to_string(), duration(item))),
+            }
+        }
+    }
+    totals
+}
+
+/// A phase of nested phases (`sim.phase` inside `sim.phase`), with the time of its own items
+/// (`exclusive`) and that including its subphases (`inclusive`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseNode {
+    /// the innermost of the phase's names
+    pub name: String,
+    pub inclusive: Duration,
+    pub exclusive: Duration,
+    pub children: Vec<PhaseNode>,
+}
+
+impl PhaseNode {
+    fn finish(&mut self) -> Duration {
+        self.inclusive = self.exclusive + self.children.iter_mut().map(PhaseNode::finish).sum::<Duration>();
+        self.inclusive
+    }
+}
+
+// the phases of `items` as a tree of their paths, in order of first appearance; unphased items
+// are skipped
+fn phase_tree<T>(items: &[T], path: impl Fn(&T) -> &[String], duration: impl Fn(&T) -> Duration) -> Vec<PhaseNode> {
+    let mut roots : Vec<PhaseNode> = Vec::new();
+    for item in items {
+        let mut level = &mut roots;
+        let mut parts = path(item).iter().peekable();
+        while let Some(part) = parts.next() {
+            let i = match level.iter().position(|node| node.name == *part) {
+                Some(i) => i,
+                None => {
+                    level.push(PhaseNode { name: part.to_string(), inclusive: Duration::ZERO, exclusive: Duration::ZERO, children: Vec::new() });
+                    level.len() - 1
+                }
+            };
+            if parts.peek().is_none() {
+                level[i].exclusive += duration(item);
+            }
+            level = &mut level[i].children;
+        }
+    }
+    roots.iter_mut().for_each(|node| { node.finish(); });
+    roots
+}
+
+// the tree, if any phase is nested, as "name: inclusive (own items exclusive)" lines
+fn write_phase_tree(f: &mut fmt::Formatter<'_>, tree: &[PhaseNode]) -> fmt::Result {
+    fn write_nodes(f: &mut fmt::Formatter<'_>, nodes: &[PhaseNode], depth: usize) -> fmt::Result {
+        for node in nodes {
+            writeln!(f, "{:indent$}{}: {:?} (own items {:?})", "", node.name, node.inclusive, node.exclusive, indent = 4 + 2 * depth)?;
+            write_nodes(f, &node.children, depth + 1)?;
+        }
+        Ok(())
+    }
+    if tree.iter().all(|node| node.children.is_empty()) {
+        return Ok(());
+    }
+    writeln!(f, "  phases, including nested ones:")?;
+    write_nodes(f, tree, 0)
+}
+
+// ", 1.234 J" for an item whose energy was measured
+fn energy_note(energy: Option<f64>) -> String {
+    energy.map_or(String::new(), |joules| format!(", {:.3} J", joules))
+}
+
+// if any energy was measured, its total and that of each phase
+fn write_energy(f: &mut fmt::Formatter<'_>, total: Option<f64>, phases: &[(String, f64)]) -> fmt::Result {
+    match total {
+        Some(total) => writeln!(f, "  energy: {}", energy::summary(total, phases)),
+        None => Ok(()),
+    }
+}
+
+impl SimulationReport {
+    /// Joules per phase, if energy was measured (`GroupOpsSimulation::measure_energy`).
+    pub fn phase_energy(&self) -> Vec<(String, f64)> {
+        phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.energy.unwrap_or(0.0))
+    }
+
+    /// Joules of all items, if energy was measured.
+    pub fn energy(&self) -> Option<f64> {
+        self.items.iter().any(|item| item.energy.is_some()).then(|| self.items.iter().filter_map(|item| item.energy).sum())
+    }
+
+    /// Total time per phase.
+    pub fn phase_totals(&self) -> Vec<(String, Duration)> {
+        phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.duration)
+    }
+
+    /// The nested phases with their inclusive and exclusive times.
+    pub fn phase_tree(&self) -> Vec<PhaseNode> {
+        phase_tree(&self.items, |item| &item.phase_path, |item| item.duration)
+    }
+
+    /// Time spent drawing inputs, which `total` leaves out.
+    pub fn setup(&self) -> Duration {
+        self.items.iter().map(|item| item.setup).sum()
+    }
+
+    /// Phase totals and the total as a table, for rendering as Markdown or LaTeX.
+    pub fn phase_table(&self) -> Table {
+        let setup = phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.setup);
+        phase_table(self.phase_totals(), setup, self.total, self.setup())
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_by_phase(f, &self.items, |item| item.phase.as_deref(),
+            |f, indent, item| {
+                let energy = energy_note(item.energy);
+                match &item.memory {
+                    Some(memory) => writeln!(f, "{}{}: {:?} ({}setup {:?}{}) [{}{}]", indent, item.name(), item.duration, amortized(item.op_count, item.duration, item.cold), item.setup, energy, memory, allocations_per_op(item.op_count, memory))?,
+                    None => writeln!(f, "{}{}: {:?} ({}setup {:?}{})", indent, item.name(), item.duration, amortized(item.op_count, item.duration, item.cold), item.setup, energy)?,
+                }
+                match &item.notes {
+                    Some(notes) => writeln!(f, "{}  {}", indent, notes),
+                    None => Ok(()),
+                }
+            },
+            |f, group| writeln!(f, "    phase total: {:?}", group.iter().map(|item| item.duration).sum::<Duration>()))?;
+        write_phase_tree(f, &self.phase_tree())?;
+        match self.cpu {
+            Some(cpu) if self.metadata.threads > 1 =>
+                writeln!(f, "  total: {:?} wall-clock, {:?} CPU ({} threads)", self.total, cpu, self.metadata.threads)?,
+            _ => writeln!(f, "  total: {:?}", self.total)?,
+        }
+        writeln!(f, "  setup: {:?}", self.setup())?;
+        write_energy(f, self.energy(), &self.phase_energy())?;
+        if self.items.iter().any(|item| item.memory.is_some()) {
+            let inputs : usize = self.items.iter().filter_map(|item| item.memory).map(|memory| memory.inputs).sum();
+            let peak = self.items.iter().filter_map(|item| item.memory).map(|memory| memory.peak).max().unwrap_or(0);
+            let allocations : usize = self.items.iter().filter_map(|item| item.memory).map(|memory| memory.allocations).sum();
+            writeln!(f, "  memory: {} of inputs, at most {} more while running, {} allocations", format_bytes(inputs), format_bytes(peak), allocations)?;
+        }
+        if let Some(id) = &self.metadata.profile_id {
+            writeln!(f, "  machine profile: {}", id)?;
+        }
+        if self.metadata.partial {
+            writeln!(f, "  partial: interrupted, only the items above completed")?;
+        }
+        Ok(())
+    }
+}
+
+/// How many operations of an item `GroupOpsSimulation::simulate_for` completed in its budget.
+#[derive(Clone, Debug)]
+pub struct ItemThroughput {
+    pub description: String,
+    pub label: Option<String>,
+    pub phase: Option<String>,
+    /// the operations of one run of the item
+    pub op_count: OpCount,
+    /// completed within `elapsed`
+    pub ops: usize,
+    pub elapsed: Duration,
+}
+
+impl ItemThroughput {
+    pub fn name(&self) -> String {
+        item_name(&self.description, self.label.as_deref())
+    }
+
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// The time of one run of the item at the measured rate.
+    pub fn run_time(&self) -> Duration {
+        self.elapsed.mul_f64(self.op_count.num as f64 / self.ops.max(1) as f64)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ThroughputReport {
+    pub metadata: Metadata,
+    /// the time every item ran for (at least one run each)
+    pub budget: Duration,
+    pub items: Vec<ItemThroughput>,
+}
+
+impl ThroughputReport {
+    /// The time of one run of all items at the measured rates.
+    pub fn run_time(&self) -> Duration {
+        self.items.iter().map(ItemThroughput::run_time).sum()
+    }
+}
+
+impl fmt::Display for ThroughputReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_by_phase(f, &self.items, |item| item.phase.as_deref(),
+            |f, indent, item| writeln!(f, "{}{}: {:.1} ops/s ({} ops in {:.3?}, {:.3?}/op)", indent, item.name(), item.ops_per_sec(),
+                item.ops, item.elapsed, item.elapsed.div_f64(item.ops.max(1) as f64)),
+            |f, group| writeln!(f, "    phase total: {:?} per run", group.iter().map(ItemThroughput::run_time).sum::<Duration>()))?;
+        writeln!(f, "  total: {:?} per run, from {:?} of every item", self.run_time(), self.budget)?;
+        if let Some(id) = &self.metadata.profile_id {
+            writeln!(f, "  machine profile: {}", id)?;
+        }
+        if self.metadata.partial {
+            writeln!(f, "  partial: interrupted, only the items above completed")?;
+        }
+        Ok(())
+    }
+}
+
+/// One phase of `GroupOpsSimulation::simulate_concurrent`.
+#[derive(Clone, Debug)]
+pub struct PhaseRun {
+    /// `None` for the items outside any phase
+    pub phase: Option<String>,
+    pub worker: usize,
+    /// since the first phase started
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConcurrentReport {
+    pub metadata: Metadata,
+    pub workers: usize,
+    /// in the order the phases were added
+    pub phases: Vec<PhaseRun>,
+    /// until the last phase finished
+    pub makespan: Duration,
+}
+
+impl ConcurrentReport {
+    /// The phases' times added up, as if they had run one after the other.
+    pub fn sequential(&self) -> Duration {
+        self.phases.iter().map(|run| run.duration).sum()
+    }
+}
+
+impl fmt::Display for ConcurrentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "phases on {} workers:", self.workers)?;
+        for run in &self.phases {
+            writeln!(f, "  {}: {:?} (worker {}, from {:?} to {:?})", run.phase.as_deref().unwrap_or("(no phase)"),
+                run.duration, run.worker, run.start, run.start + run.duration)?;
+        }
+        writeln!(f, "  makespan: {:?}", self.makespan)?;
+        writeln!(f, "  sequential: {:?} ({:.2}x the makespan)", self.sequential(), self.sequential().as_secs_f64() / self.makespan.as_secs_f64())?;
+        if let Some(id) = &self.metadata.profile_id {
+            writeln!(f, "  machine profile: {}", id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemSummary {
+    pub description: String,
+    pub label: Option<String>,
+    pub phase: Option<String>,
+    /// see `ItemReport::phase_path`
+    pub phase_path: Vec<String>,
+    pub op_count: OpCount,
+    /// drawing the item's inputs, done once for all repetitions
+    pub setup: Duration,
+    /// see `ItemReport::cold`
+    pub cold: Option<Duration>,
+    pub summary: Summary,
+    /// the mean joules of a run, see `ItemReport::energy`
+    pub energy: Option<f64>,
+    /// see `ItemReport::notes`
+    pub notes: Option<String>,
+}
+
+impl ItemSummary {
+    pub fn name(&self) -> String {
+        item_name(&self.description, self.label.as_deref())
+    }
+}
+
+/// Per-item and whole-list timing statistics from `GroupOpsSimulation::simulate_n`.
+#[derive(Clone, Debug)]
+pub struct RepeatedRuns {
+    pub repetitions: usize,
+    pub threads: usize,
+    pub items: Vec<ItemSummary>,
+    pub total: Summary,
+    pub total_cpu: Option<Summary>,
+    /// see `Metadata::profile_id`
+    pub profile_id: Option<String>,
+    /// see `Metadata::partial`: `repetitions` counts the completed ones, or is 1 for an
+    /// interrupted first repetition, whose completed items alone are reported
+    pub partial: bool,
+}
+
+impl RepeatedRuns {
+    /// Mean joules of a run per phase, if energy was measured.
+    pub fn phase_energy(&self) -> Vec<(String, f64)> {
+        phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.energy.unwrap_or(0.0))
+    }
+
+    /// Mean joules of a run, if energy was measured.
+    pub fn energy(&self) -> Option<f64> {
+        self.items.iter().any(|item| item.energy.is_some()).then(|| self.items.iter().filter_map(|item| item.energy).sum())
+    }
+
+    /// Sum of the mean item times per phase.
+    pub fn phase_totals(&self) -> Vec<(String, Duration)> {
+        phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.summary.mean)
+    }
+
+    /// Like `SimulationReport::phase_tree`, with the mean item times.
+    pub fn phase_tree(&self) -> Vec<PhaseNode> {
+        phase_tree(&self.items, |item| &item.phase_path, |item| item.summary.mean)
+    }
+
+    /// Time spent drawing inputs, once before all repetitions.
+    pub fn setup(&self) -> Duration {
+        self.items.iter().map(|item| item.setup).sum()
+    }
+
+    /// Mean phase totals and the mean total as a table.
+    pub fn phase_table(&self) -> Table {
+        let setup = phase_totals(&self.items, |item| item.phase.as_deref(), |item| item.setup);
+        phase_table(self.phase_totals(), setup, self.total.mean, self.setup())
+    }
+}
+
+impl fmt::Display for RepeatedRuns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} repetitions:", self.repetitions)?;
+        write_by_phase(f, &self.items, |item| item.phase.as_deref(),
+            |f, indent, item| {
+                writeln!(f, "{}{}: {}; {}setup {:?}{}", indent, item.name(), item.summary, amortized(item.op_count, item.summary.mean, item.cold), item.setup, energy_note(item.energy))?;
+                match &item.notes {
+                    Some(notes) => writeln!(f, "{}  {}", indent, notes),
+                    None => Ok(()),
+                }
+            },
+            |_, _| Ok(()))?;
+        write_phase_tree(f, &self.phase_tree())?;
+        writeln!(f, "  total: {}", self.total)?;
+        writeln!(f, "  setup: {:?}", self.setup())?;
+        write_energy(f, self.energy(), &self.phase_energy())?;
+        match &self.total_cpu {
+            Some(cpu) if self.threads > 1 => writeln!(f, "  total CPU ({} threads): {}", self.threads, cpu)?,
+            _ => {}
+        }
+        if let Some(id) = &self.profile_id {
+            writeln!(f, "  machine profile: {}", id)?;
+        }
+        if self.partial {
+            writeln!(f, "  partial: interrupted after {} repetitions", self.repetitions)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! How multi-exponentiation scales with threads, to decide how many cores to provision.
+//!
+//! blst spreads its multi-exp over a process-wide thread pool sized to the machine, which can't
+//! be resized from outside. So each thread count here splits the bases into that many contiguous
+//! chunks, runs blst's single-threaded Pippenger on each chunk in its own thread and adds up the
+//! results; blstrs' `multi_exp` with blst's own pool is measured alongside for reference.
+
+use crate::backend::{Backend, Blst};
+use crate::clock::Instant;
+use crate::parallel::parallel_map;
+use crate::stats::Summary;
+
+use blst::{blst_p1, blst_p1_add_or_double};
+use blstrs::{G1Projective, Scalar};
+use group::{ff::Field as _, Group as _};
+use rand::RngCore;
+use std::fmt;
+use std::hint::black_box;
+use std::time::Duration;
+
+
+/// Median times of one G1 multi-exp of `size` bases at every thread count.
+#[derive(Clone, Debug)]
+pub struct ThreadScaling {
+    pub size: usize,
+    /// (threads, median time), by increasing thread count
+    pub times: Vec<(usize, Duration)>,
+    /// blstrs' `G1Projective::multi_exp`, threaded by blst
+    pub builtin: Duration,
+}
+
+/// 1, 2, 4, ... up to `max_threads`, which is always included.
+pub fn thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts : Vec<usize> = std::iter::successors(Some(1), |&t| Some(t * 2)).take_while(|&t| t < max_threads).collect();
+    counts.push(max_threads.max(1));
+    counts
+}
+
+/// Times a G1 multi-exp of `size` random bases at every count of `thread_counts(max_threads)`,
+/// taking the median of `repetitions` runs each.
+pub fn msm_thread_scaling(size: usize, max_threads: usize, repetitions: usize, mut rng: impl RngCore) -> ThreadScaling {
+    assert!(size > 0 && repetitions > 0, "need at least one base and one repetition");
+    let rng : &mut dyn RngCore = &mut rng;
+    let bases : Vec<blst_p1> = (0..size).map(|_| Blst::random_g1(rng)).collect();
+    let scalars : Vec<_> = (0..size).map(|_| Blst::random_scalar(rng)).collect();
+    let median = |f: &dyn Fn()| {
+        let samples : Vec<Duration> = (0..repetitions)
+            .map(|_| {
+                let start_time = Instant::now();
+                f();
+                start_time.elapsed()
+            })
+            .collect();
+        Summary::from_samples(&samples).median
+    };
+
+    let times = thread_counts(max_threads).into_iter()
+        .map(|threads| {
+            let chunk_size = size.div_ceil(threads);
+            let time = median(&|| {
+                let partials = parallel_map(threads, size.div_ceil(chunk_size), |i| {
+                    let range = i * chunk_size..((i + 1) * chunk_size).min(size);
+                    Blst::g1_multi_exp(&bases[range.clone()], &scalars[range])
+                });
+                let mut sum = blst_p1::default();
+                for partial in &partials {
+                    // SAFETY: blst allows the output to alias an input
+                    unsafe { blst_p1_add_or_double(&mut sum, &sum, partial) };
+                }
+                black_box(sum);
+            });
+            (threads, time)
+        })
+        .collect();
+
+    let bases : Vec<G1Projective> = (0..size).map(|_| G1Projective::random(&mut *rng)).collect();
+    let scalars : Vec<Scalar> = (0..size).map(|_| Scalar::random(&mut *rng)).collect();
+    let builtin = median(&|| { black_box(G1Projective::multi_exp(&bases, &scalars)); });
+    ThreadScaling { size, times, builtin }
+}
+
+impl fmt::Display for ThreadScaling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "G1 multi-exp of size {}:", self.size)?;
+        writeln!(f, "{:>7}  {:>12}  {:>7}  {:>10}", "threads", "time", "speedup", "efficiency")?;
+        let single = self.times.first().map_or(Duration::ZERO, |&(_, time)| time).as_secs_f64();
+        for &(threads, time) in &self.times {
+            let speedup = single / time.as_secs_f64();
+            writeln!(f, "{:>7}  {:>12}  {:>6.2}x  {:>9.0}%", threads, format!("{:.3?}", time), speedup, 100.0 * speedup / threads as f64)?;
+        }
+        writeln!(f, "blstrs multi_exp (blst's thread pool): {:.3?}", self.builtin)
+    }
+}
@@ -0,0 +1,157 @@
+//! The exact schedule of a run, saved to replay it later: which items ran, in which order, one
+//! list of steps per repetition, and the seed the inputs were drawn from. Replaying runs the
+//! same operations on the same inputs in the same order, so a timing anomaly seen once (e.g.
+//! with `--order shuffled`) can be reproduced and investigated:
+//!
+//! ```json
+//! {
+//!   "seed": "10935326333961687641",
+//!   "items": ["G1 exps: 2", "pairings: 1"],
+//!   "repetitions": [
+//!     [[1, 0], [0, 1], [0, 0]]
+//!   ]
+//! }
+//! ```
+//!
+//! A step `[item]` runs a whole item, `[item, op]` one operation of it. The seed is a string,
+//! since JSON numbers are read as f64.
+
+use crate::json::{self, Value};
+
+use std::fmt;
+use std::path::Path;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Item(usize),
+    /// (item, operation)
+    Op(usize, usize),
+}
+
+impl Step {
+    pub fn item(&self) -> usize {
+        match *self {
+            Step::Item(item) | Step::Op(item, _) => item,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub seed: Option<u64>,
+    /// the descriptions of the items, so a replay can check it runs the same ones
+    pub items: Vec<String>,
+    pub repetitions: Vec<Vec<Step>>,
+}
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    Io(std::io::Error),
+    Invalid(String),
+    /// the simulation's items differ from the schedule's
+    Mismatch(String),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Io(e) => write!(f, "{}", e),
+            ScheduleError::Invalid(message) => write!(f, "invalid schedule: {}", message),
+            ScheduleError::Mismatch(message) => write!(f, "the schedule doesn't match the simulation: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl From<std::io::Error> for ScheduleError {
+    fn from(e: std::io::Error) -> Self {
+        ScheduleError::Io(e)
+    }
+}
+
+impl Schedule {
+    /// Checks that the schedule was recorded for items with these descriptions and that every
+    /// step is within them, given how many operations each item splits into.
+    pub fn check(&self, items: &[String], ops: &[usize]) -> Result<(), ScheduleError> {
+        let mismatch = |message: String| Err(ScheduleError::Mismatch(message));
+        if self.items.len() != items.len() {
+            return mismatch(format!("it has {} items, the simulation {}", self.items.len(), items.len()));
+        }
+        if let Some(i) = (0..items.len()).find(|&i| self.items[i] != items[i]) {
+            return mismatch(format!("item {} is `{}` in it, `{}` in the simulation", i + 1, self.items[i], items[i]));
+        }
+        for step in self.repetitions.iter().flatten() {
+            match *step {
+                Step::Item(item) if item < items.len() => {}
+                Step::Op(item, op) if item < items.len() && op < ops[item] => {}
+                _ => return mismatch(format!("step {:?} is out of range", step)),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        let number = |n: usize| Value::Number(n as f64);
+        let repetitions = self.repetitions.iter()
+            .map(|steps| Value::Array(steps.iter()
+                .map(|step| match *step {
+                    Step::Item(item) => Value::Array(vec![number(item)]),
+                    Step::Op(item, op) => Value::Array(vec![number(item), number(op)]),
+                })
+                .collect()))
+            .collect();
+        Value::Object(vec![
+            ("seed".to_string(), self.seed.map_or(Value::Null, |seed| Value::String(seed.to_string()))),
+            ("items".to_string(), Value::Array(self.items.iter().cloned().map(Value::String).collect())),
+            ("repetitions".to_string(), Value::Array(repetitions)),
+        ]).to_pretty_string()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScheduleError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ScheduleError> {
+        let invalid = |message: &str| ScheduleError::Invalid(message.to_string());
+        let root = json::parse(text).map_err(|e| ScheduleError::Invalid(e.to_string()))?;
+        let seed = match root.get("seed") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(value.as_str().and_then(|seed| seed.parse().ok()).ok_or_else(|| invalid("`seed` must be an unsigned integer, as a string"))?),
+        };
+        let Some(Value::Array(items)) = root.get("items") else {
+            return Err(invalid("missing `items` list"));
+        };
+        let items = items.iter()
+            .map(|item| item.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("`items` must be strings"))?;
+        let Some(Value::Array(repetitions)) = root.get("repetitions") else {
+            return Err(invalid("missing `repetitions` list"));
+        };
+        let index = |value: &Value| value.as_f64().filter(|&x| x >= 0.0 && x.fract() == 0.0).map(|x| x as usize);
+        let repetitions = repetitions.iter()
+            .map(|steps| match steps {
+                Value::Array(steps) => steps.iter()
+                    .map(|step| match step {
+                        Value::Array(pair) => match pair.as_slice() {
+                            [item] => index(item).map(Step::Item),
+                            [item, op] => index(item).zip(index(op)).map(|(item, op)| Step::Op(item, op)),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|repetitions| !repetitions.is_empty())
+            .ok_or_else(|| invalid("`repetitions` must be lists of [item] or [item, op] steps"))?;
+        Ok(Self { seed, items, repetitions })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
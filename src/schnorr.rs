@@ -0,0 +1,274 @@
+//! Schnorr proofs of knowledge of a discrete logarithm, made non-interactive with Fiat–Shamir:
+//! given `g, a = g^x`, prove knowledge of `x`. As for DLEQ proofs (module `dleq`), the batchable
+//! form carries the commitment instead of the challenge.
+//!
+//! The items model key registration, where every committee member proves knowledge of its
+//! secret key `x` for `a = g^x` in G1 or G2 and the others verify one proof per member, one by
+//! one or all at once. Their times include the Fiat–Shamir hashing.
+
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem, MultiExp, NamedGroup};
+use crate::hash::hash_to_scalar;
+use crate::report::OpCount;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field as _;
+use group::{Group, GroupEncoding};
+use rand::{RngCore, SeedableRng as _};
+use rand_chacha::ChaCha20Rng;
+use std::hint::black_box;
+
+
+const DOMAIN: &[u8] = b"blstrs-pvss-simulation Schnorr v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+fn challenge<T>(g: &T, a: &T, commitment: &T) -> Scalar
+where T : GroupEncoding {
+    let encodings = [g, a, commitment].map(|p| p.to_bytes());
+    let inputs : Vec<&[u8]> = encodings.iter().map(|e| e.as_ref()).collect();
+    hash_to_scalar(DOMAIN, &inputs)
+}
+
+impl SchnorrProof {
+    /// Proves knowledge of `x` with `a = g^x`. Costs one exponentiation.
+    pub fn prove<T>(g: &T, a: &T, x: &Scalar, rng: &mut impl RngCore) -> Self
+    where T : Group<Scalar = Scalar> + GroupEncoding {
+        let w = Scalar::random(rng);
+        let challenge = challenge(g, a, &(*g * w));
+        Self { challenge, response: w + challenge * x }
+    }
+
+    /// Checks a proof of knowledge of `log_g a`, with `g^z a^-c` as a multi-exp of size 2.
+    pub fn verify<T>(&self, g: &T, a: &T) -> bool
+    where T : Group<Scalar = Scalar> + GroupEncoding + MultiExp {
+        let commitment = T::multi_exp(&[*g, *a], &[self.response, -self.challenge]);
+        challenge(g, a, &commitment) == self.challenge
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchableSchnorrProof<T> {
+    pub commitment: T,
+    pub response: Scalar,
+}
+
+impl<T> BatchableSchnorrProof<T>
+where T : Group<Scalar = Scalar> + GroupEncoding + MultiExp
+{
+    pub fn prove(g: &T, a: &T, x: &Scalar, rng: &mut impl RngCore) -> Self {
+        let w = Scalar::random(rng);
+        let commitment = *g * w;
+        let challenge = challenge(g, a, &commitment);
+        Self { commitment, response: w + challenge * x }
+    }
+
+    /// Checks proofs of knowledge of `log_g a_i` for all `a_i`, as
+    /// `g^(sum r_i z_i) = prod (A_i a_i^c_i)^r_i` with random `r_i`: one multi-exp of size
+    /// `2 * n + 1` for `n` proofs.
+    pub fn verify_batch(g: &T, statements: &[T], proofs: &[Self], rng: &mut impl RngCore) -> bool {
+        assert_eq!(statements.len(), proofs.len(), "need one proof per statement");
+        let mut bases = Vec::with_capacity(2 * proofs.len() + 1);
+        let mut exponents = Vec::with_capacity(2 * proofs.len() + 1);
+        let mut combined_response = Scalar::ZERO;
+        for (a, proof) in statements.iter().zip(proofs) {
+            let challenge = challenge(g, a, &proof.commitment);
+            let r = Scalar::random(&mut *rng);
+            combined_response += r * proof.response;
+            bases.extend([proof.commitment, *a]);
+            exponents.extend([-r, -(r * challenge)]);
+        }
+        bases.push(*g);
+        exponents.push(combined_response);
+        bool::from(T::multi_exp(&bases, &exponents).is_identity())
+    }
+}
+
+// the bounds of the groups keys are registered in
+trait KeyGroup : Group<Scalar = Scalar> + GroupEncoding + MultiExp + NamedGroup + Send + Sync {}
+
+impl<T> KeyGroup for T
+where T : Group<Scalar = Scalar> + GroupEncoding + MultiExp + NamedGroup + Send + Sync {}
+
+// the generator and random public keys with their secret keys
+struct Keys<T> {
+    g: T,
+    public_keys: Vec<T>,
+    secret_keys: Vec<Scalar>,
+}
+
+impl<T> Keys<T>
+where T : KeyGroup {
+    fn new(rng: &mut impl RngCore, num: usize) -> Self {
+        let g = T::generator();
+        let secret_keys : Vec<Scalar> = (0..num).map(|_| Scalar::random(&mut *rng)).collect();
+        let public_keys = secret_keys.iter().map(|x| g * x).collect();
+        Self { g, public_keys, secret_keys }
+    }
+}
+
+struct Proofs<T> {
+    keys: Keys<T>,
+}
+
+impl<T> Proofs<T>
+where T : KeyGroup {
+    fn prove(&self, i: usize) -> SchnorrProof {
+        // a fixed nonce seed per proof keeps `simulate` free of shared state
+        SchnorrProof::prove(&self.keys.g, &self.keys.public_keys[i], &self.keys.secret_keys[i], &mut ChaCha20Rng::seed_from_u64(i as u64))
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Proofs<T>
+where T : KeyGroup {
+    fn description(&self) -> String {
+        format!("{} Schnorr proofs of knowledge: {}", T::NAME, self.keys.public_keys.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.keys.public_keys.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<SchnorrProof>>((0..self.keys.public_keys.len()).map(|i| self.prove(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.keys.public_keys.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.prove(i));
+    }
+}
+
+struct Verifications<T> {
+    keys: Keys<T>,
+    proofs: Vec<SchnorrProof>,
+}
+
+impl<T> Verifications<T>
+where T : KeyGroup {
+    fn verify(&self, i: usize) -> bool {
+        self.proofs[i].verify(&self.keys.g, &self.keys.public_keys[i])
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Verifications<T>
+where T : KeyGroup {
+    fn description(&self) -> String {
+        format!("{} Schnorr verifications: {}", T::NAME, self.proofs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.proofs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.proofs.len()).map(|i| self.verify(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.proofs.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+}
+
+struct BatchVerifications<T> {
+    num: usize,
+    keys: Keys<T>,
+    proofs: Vec<BatchableSchnorrProof<T>>,
+}
+
+impl<T> BatchVerifications<T>
+where T : KeyGroup {
+    fn verify(&self, i: usize) -> bool {
+        BatchableSchnorrProof::verify_batch(&self.keys.g, &self.keys.public_keys, &self.proofs, &mut ChaCha20Rng::seed_from_u64(i as u64))
+    }
+}
+
+impl<T> GroupOpsSimulationItem for BatchVerifications<T>
+where T : KeyGroup {
+    fn description(&self) -> String {
+        format!("{} Schnorr batch verifications: {} of {} proofs", T::NAME, self.num, self.proofs.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.proofs.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<bool>>((0..self.num).map(|i| self.verify(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn add_schnorr_proofs<T>(&mut self, num: usize) -> &mut Self
+    where T : KeyGroup + 'static {
+        let keys = Keys::<T>::new(self.rng(), num);
+        self.push_item(Proofs { keys })
+    }
+
+    fn add_schnorr_verifications<T>(&mut self, num: usize) -> &mut Self
+    where T : KeyGroup + 'static {
+        let keys = Keys::<T>::new(self.rng(), num);
+        let proofs = keys.public_keys.iter().zip(&keys.secret_keys)
+            .map(|(a, x)| SchnorrProof::prove(&keys.g, a, x, &mut *self.rng()))
+            .collect();
+        self.push_item(Verifications { keys, proofs })
+    }
+
+    fn add_schnorr_batch_verifications<T>(&mut self, num: usize, size: usize) -> &mut Self
+    where T : KeyGroup + 'static {
+        let keys = Keys::<T>::new(self.rng(), size);
+        let proofs = keys.public_keys.iter().zip(&keys.secret_keys)
+            .map(|(a, x)| BatchableSchnorrProof::prove(&keys.g, a, x, &mut *self.rng()))
+            .collect();
+        self.push_item(BatchVerifications { num, keys, proofs })
+    }
+
+    /// `num` proofs of knowledge of secret keys of G1 public keys, one exp and one hash each.
+    pub fn g1_schnorr_prove(&mut self, num: usize) -> &mut Self {
+        self.add_schnorr_proofs::<G1Projective>(num)
+    }
+    /// Same as `g1_schnorr_prove`, in G2.
+    pub fn g2_schnorr_prove(&mut self, num: usize) -> &mut Self {
+        self.add_schnorr_proofs::<G2Projective>(num)
+    }
+
+    /// `num` verifications of such proofs one by one, a multi-exp of size 2 and a hash each.
+    pub fn g1_schnorr_verify(&mut self, num: usize) -> &mut Self {
+        self.add_schnorr_verifications::<G1Projective>(num)
+    }
+    /// Same as `g1_schnorr_verify`, in G2.
+    pub fn g2_schnorr_verify(&mut self, num: usize) -> &mut Self {
+        self.add_schnorr_verifications::<G2Projective>(num)
+    }
+
+    /// `num` verifications of the registrations of `size` keys at once: `size` hashes and one
+    /// multi-exp of size `2 * size + 1`.
+    pub fn g1_schnorr_batch_verify(&mut self, num: usize, size: usize) -> &mut Self {
+        self.add_schnorr_batch_verifications::<G1Projective>(num, size)
+    }
+    /// Same as `g1_schnorr_batch_verify`, in G2.
+    pub fn g2_schnorr_batch_verify(&mut self, num: usize, size: usize) -> &mut Self {
+        self.add_schnorr_batch_verifications::<G2Projective>(num, size)
+    }
+}
@@ -0,0 +1,301 @@
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+
+use blstrs::{Compress, G1Affine, G1Projective, G2Affine, G2Projective, Gt};
+use group::{Curve as _, Group as _};
+use rand::RngCore;
+use std::hint::black_box;
+
+
+/// Wire encodings of group elements. Decoding performs the full validity checks a verifier needs
+/// for untrusted input (on-curve and subgroup membership).
+pub trait PointEncoding : Sized {
+    const NAME: &'static str;
+    const COMPRESSED_SIZE: usize;
+    /// `None` if the group has no uncompressed encoding (Gt).
+    const UNCOMPRESSED_SIZE: Option<usize>;
+
+    fn random_element(rng: &mut impl RngCore) -> Self;
+    fn encode(&self, compressed: bool, out: &mut [u8]);
+    fn decode(bytes: &[u8], compressed: bool) -> Option<Self>;
+
+    fn encoded_size(compressed: bool) -> usize {
+        if compressed {
+            Self::COMPRESSED_SIZE
+        } else {
+            Self::UNCOMPRESSED_SIZE
+                .unwrap_or_else(|| panic!("{} has no uncompressed encoding", Self::NAME))
+        }
+    }
+}
+
+impl PointEncoding for G1Affine {
+    const NAME: &'static str = "G1";
+    const COMPRESSED_SIZE: usize = 48;
+    const UNCOMPRESSED_SIZE: Option<usize> = Some(96);
+
+    fn random_element(rng: &mut impl RngCore) -> Self {
+        G1Projective::random(rng).to_affine()
+    }
+
+    fn encode(&self, compressed: bool, out: &mut [u8]) {
+        if compressed {
+            out.copy_from_slice(&self.to_compressed());
+        } else {
+            out.copy_from_slice(&self.to_uncompressed());
+        }
+    }
+
+    fn decode(bytes: &[u8], compressed: bool) -> Option<Self> {
+        if compressed {
+            G1Affine::from_compressed(bytes.try_into().ok()?).into()
+        } else {
+            G1Affine::from_uncompressed(bytes.try_into().ok()?).into()
+        }
+    }
+}
+
+impl PointEncoding for G2Affine {
+    const NAME: &'static str = "G2";
+    const COMPRESSED_SIZE: usize = 96;
+    const UNCOMPRESSED_SIZE: Option<usize> = Some(192);
+
+    fn random_element(rng: &mut impl RngCore) -> Self {
+        G2Projective::random(rng).to_affine()
+    }
+
+    fn encode(&self, compressed: bool, out: &mut [u8]) {
+        if compressed {
+            out.copy_from_slice(&self.to_compressed());
+        } else {
+            out.copy_from_slice(&self.to_uncompressed());
+        }
+    }
+
+    fn decode(bytes: &[u8], compressed: bool) -> Option<Self> {
+        if compressed {
+            G2Affine::from_compressed(bytes.try_into().ok()?).into()
+        } else {
+            G2Affine::from_uncompressed(bytes.try_into().ok()?).into()
+        }
+    }
+}
+
+// blstrs only offers torus-based compression for Gt; decompression includes the subgroup check
+impl PointEncoding for Gt {
+    const NAME: &'static str = "Gt";
+    const COMPRESSED_SIZE: usize = 288;
+    const UNCOMPRESSED_SIZE: Option<usize> = None;
+
+    fn random_element(rng: &mut impl RngCore) -> Self {
+        Gt::random(rng)
+    }
+
+    fn encode(&self, _compressed: bool, out: &mut [u8]) {
+        self.write_compressed(out).unwrap();
+    }
+
+    fn decode(bytes: &[u8], _compressed: bool) -> Option<Self> {
+        Gt::read_compressed(bytes).ok()
+    }
+}
+
+/// Compressed decoding without the subgroup check (still on the curve), for verifiers that check
+/// subgroup membership separately (module `checks`).
+pub trait UncheckedDecoding : PointEncoding {
+    fn decompress_unchecked(bytes: &[u8]) -> Option<Self>;
+}
+
+impl UncheckedDecoding for G1Affine {
+    fn decompress_unchecked(bytes: &[u8]) -> Option<Self> {
+        G1Affine::from_compressed_unchecked(bytes.try_into().ok()?).into()
+    }
+}
+
+impl UncheckedDecoding for G2Affine {
+    fn decompress_unchecked(bytes: &[u8]) -> Option<Self> {
+        G2Affine::from_compressed_unchecked(bytes.try_into().ok()?).into()
+    }
+}
+
+fn format_name<T: PointEncoding>(compressed: bool) -> String {
+    if T::UNCOMPRESSED_SIZE.is_none() {
+        T::NAME.to_string()
+    } else if compressed {
+        format!("{} compressed", T::NAME)
+    } else {
+        format!("{} uncompressed", T::NAME)
+    }
+}
+
+struct Serializations<T> {
+    compressed: bool,
+    points: Vec<T>,
+}
+
+impl<T> Serializations<T>
+where T : PointEncoding {
+    fn new(rng: &mut impl RngCore, num: usize, compressed: bool) -> Self {
+        // fail at setup rather than mid-simulation if the encoding doesn't exist
+        T::encoded_size(compressed);
+        Self {
+            compressed,
+            points: (0..num).map(|_| T::random_element(rng)).collect(),
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Serializations<T>
+where T : PointEncoding + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} serializations: {}", format_name::<T>(self.compressed), self.points.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.points.len())
+    }
+
+    fn simulate(&self) {
+        let size = T::encoded_size(self.compressed);
+        let mut out = vec![0u8; size * self.points.len()];
+        for (point, chunk) in self.points.iter().zip(out.chunks_exact_mut(size)) {
+            point.encode(self.compressed, chunk);
+        }
+        black_box(out);
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        let size = T::encoded_size(self.compressed);
+        black_box(parallel_map(threads, self.points.len(), |i| {
+            let mut out = vec![0u8; size];
+            self.points[i].encode(self.compressed, &mut out);
+            out
+        }));
+    }
+}
+
+struct Deserializations<T> {
+    compressed: bool,
+    // all encodings back to back, `T::encoded_size(compressed)` bytes each
+    encodings: Vec<u8>,
+    _group: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> Deserializations<T>
+where T : PointEncoding {
+    fn new(rng: &mut impl RngCore, num: usize, compressed: bool) -> Self {
+        let size = T::encoded_size(compressed);
+        let mut encodings = vec![0u8; size * num];
+        for chunk in encodings.chunks_exact_mut(size) {
+            T::random_element(rng).encode(compressed, chunk);
+        }
+        Self {
+            compressed,
+            encodings,
+            _group: std::marker::PhantomData,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> T {
+        T::decode(bytes, self.compressed).expect("encodings of valid points must decode")
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Deserializations<T>
+where T : PointEncoding + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} deserializations (with subgroup checks): {}",
+            format_name::<T>(self.compressed), self.encodings.len() / T::encoded_size(self.compressed))
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.encodings.len() / T::encoded_size(self.compressed))
+    }
+
+    fn simulate(&self) {
+        let size = T::encoded_size(self.compressed);
+        black_box::<Vec<T>>(self.encodings.chunks_exact(size).map(|bytes| self.decode(bytes)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        let size = T::encoded_size(self.compressed);
+        black_box(parallel_map(threads, self.encodings.len() / size, |i| {
+            self.decode(&self.encodings[i * size..(i + 1) * size])
+        }));
+    }
+}
+
+struct Decompressions<T> {
+    // as in `Deserializations`, compressed
+    encodings: Deserializations<T>,
+}
+
+impl<T> Decompressions<T>
+where T : UncheckedDecoding {
+    fn decode(&self, bytes: &[u8]) -> T {
+        T::decompress_unchecked(bytes).expect("encodings of valid points must decode")
+    }
+}
+
+impl<T> GroupOpsSimulationItem for Decompressions<T>
+where T : UncheckedDecoding + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} decompressions (without subgroup checks): {}", T::NAME, self.encodings.encodings.len() / T::COMPRESSED_SIZE)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.encodings.encodings.len() / T::COMPRESSED_SIZE)
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.encodings.encodings.chunks_exact(T::COMPRESSED_SIZE).map(|bytes| self.decode(bytes)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        let size = T::COMPRESSED_SIZE;
+        black_box(parallel_map(threads, self.encodings.encodings.len() / size, |i| {
+            self.decode(&self.encodings.encodings[i * size..(i + 1) * size])
+        }));
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    pub fn serialize_g1(&mut self, num: usize, compressed: bool) -> &mut Self {
+        let item = Serializations::<G1Affine>::new(self.rng(), num, compressed);
+        self.push_item(item)
+    }
+    pub fn serialize_g2(&mut self, num: usize, compressed: bool) -> &mut Self {
+        let item = Serializations::<G2Affine>::new(self.rng(), num, compressed);
+        self.push_item(item)
+    }
+    /// Gt elements only have a compressed encoding.
+    pub fn serialize_gt(&mut self, num: usize) -> &mut Self {
+        let item = Serializations::<Gt>::new(self.rng(), num, true);
+        self.push_item(item)
+    }
+    pub fn deserialize_g1(&mut self, num: usize, compressed: bool) -> &mut Self {
+        let item = Deserializations::<G1Affine>::new(self.rng(), num, compressed);
+        self.push_item(item)
+    }
+    pub fn deserialize_g2(&mut self, num: usize, compressed: bool) -> &mut Self {
+        let item = Deserializations::<G2Affine>::new(self.rng(), num, compressed);
+        self.push_item(item)
+    }
+    /// Compressed G1 decodings that leave the subgroup check to `g1_subgroup_checks`.
+    pub fn g1_decompressions(&mut self, num: usize) -> &mut Self {
+        let item = Decompressions { encodings: Deserializations::<G1Affine>::new(self.rng(), num, true) };
+        self.push_item(item)
+    }
+    /// Compressed G2 decodings that leave the subgroup check to `g2_subgroup_checks`.
+    pub fn g2_decompressions(&mut self, num: usize) -> &mut Self {
+        let item = Decompressions { encodings: Deserializations::<G2Affine>::new(self.rng(), num, true) };
+        self.push_item(item)
+    }
+    pub fn deserialize_gt(&mut self, num: usize) -> &mut Self {
+        let item = Deserializations::<Gt>::new(self.rng(), num, true);
+        self.push_item(item)
+    }
+}
@@ -0,0 +1,198 @@
+//! Exponentiations and multi-exps by short exponents, such as the 128-bit (or shorter) random
+//! coefficients of batch verification: a random linear combination only needs exponents long
+//! enough that a cheating prover can't guess them, and blst runs proportionally fewer
+//! doublings (and Pippenger windows) for them. blstrs always passes all 255 bits, so these call
+//! blst with the exponent's length.
+
+use crate::group_ops_simulation::{naive_multi_exp, GroupOpsSimulation, GroupOpsSimulationItem, NamedGroup, Sampler};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+use crate::test_vectors::{concat_outputs, OutputBytes};
+
+use blst::{blst_p1, blst_p1_mult, blst_p2, blst_p2_mult, p1_affines, p2_affines};
+use blstrs::{G1Projective, G2Projective, Scalar};
+use group::Group;
+use rand::RngCore;
+use std::hint::black_box;
+use std::ops::Mul;
+
+
+/// Exponents are little-endian bytes, below `2^bits`.
+pub trait ShortExp : Sized {
+    fn short_exp(&self, exponent: &[u8; 32], bits: usize) -> Self;
+    fn short_multi_exp(bases: &[Self], exponents: &[[u8; 32]], bits: usize) -> Self;
+}
+
+macro_rules! impl_short_exp {
+    ($projective:ty, $blst_point:ty, $blst_mult:ident, $blst_affines:ty) => {
+        impl ShortExp for $projective {
+            fn short_exp(&self, exponent: &[u8; 32], bits: usize) -> Self {
+                let mut out = <$blst_point>::default();
+                // SAFETY: blstrs' projective types are repr(transparent) wrappers of the blst
+                // types, and blst reads `bits` bits of the 32-byte exponent
+                unsafe {
+                    $blst_mult(&mut out, self as *const Self as *const $blst_point, exponent.as_ptr(), bits);
+                    std::mem::transmute::<$blst_point, Self>(out)
+                }
+            }
+
+            fn short_multi_exp(bases: &[Self], exponents: &[[u8; 32]], bits: usize) -> Self {
+                // SAFETY: as above
+                let bases = unsafe { std::slice::from_raw_parts(bases.as_ptr() as *const $blst_point, bases.len()) };
+                // blst packs the exponents in as many bytes as `bits` takes
+                let exponents : Vec<u8> = exponents.iter().flat_map(|exponent| &exponent[..bits.div_ceil(8)]).copied().collect();
+                let out = <$blst_affines>::from(bases).mult(&exponents, bits);
+                // SAFETY: as above
+                unsafe { std::mem::transmute::<$blst_point, Self>(out) }
+            }
+        }
+    };
+}
+
+impl_short_exp!(G1Projective, blst_p1, blst_p1_mult, p1_affines);
+impl_short_exp!(G2Projective, blst_p2, blst_p2_mult, p2_affines);
+
+/// A uniformly random exponent of `bits` bits, below the group order for up to 254 bits.
+pub fn random_exponent(rng: &mut impl RngCore, bits: usize) -> [u8; 32] {
+    assert!((1..=254).contains(&bits), "exponents have 1 to 254 bits, not {}", bits);
+    let mut exponent = [0u8; 32];
+    rng.fill_bytes(&mut exponent);
+    for (i, byte) in exponent.iter_mut().enumerate() {
+        *byte &= match (bits.saturating_sub(8 * i)).min(8) {
+            8 => 0xff,
+            left => (1u8 << left) - 1,
+        };
+    }
+    exponent
+}
+
+fn to_scalar(exponent: &[u8; 32]) -> Scalar {
+    Scalar::from_bytes_le(exponent).expect("short exponents are below the group order")
+}
+
+struct ShortExps<T> {
+    bits: usize,
+    bases: Vec<T>,
+    exponents: Vec<[u8; 32]>,
+}
+
+impl<T> ShortExps<T>
+where T : Group {
+    fn new(rng: &mut Sampler<impl RngCore>, num: usize, bits: usize) -> Self {
+        Self { bits, bases: rng.points(num), exponents: (0..num).map(|_| random_exponent(&mut *rng, bits)).collect() }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for ShortExps<T>
+where T : ShortExp + Group + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} exps: {} ({}-bit exponents)", T::NAME, self.bases.len(), self.bits)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::single(self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>(self.bases.iter().zip(&self.exponents).map(|(base, exponent)| base.short_exp(exponent, self.bits)).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.bases.len(), |i| self.bases[i].short_exp(&self.exponents[i], self.bits)));
+    }
+
+    fn ops(&self) -> usize {
+        self.bases.len()
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.bases[i].short_exp(&self.exponents[i], self.bits));
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.bases.iter().zip(&self.exponents).all(|(base, exponent)| base.short_exp(exponent, self.bits) == *base * to_scalar(exponent)))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        let results : Vec<T> = self.bases.iter().zip(&self.exponents).map(|(base, exponent)| base.short_exp(exponent, self.bits)).collect();
+        Some(concat_outputs(&results))
+    }
+}
+
+struct ShortMultiExps<T> {
+    num: usize,
+    bits: usize,
+    bases: Vec<T>,
+    exponents: Vec<[u8; 32]>,
+}
+
+impl<T> ShortMultiExps<T>
+where T : ShortExp {
+    fn multi_exp(&self) -> T {
+        T::short_multi_exp(&self.bases, &self.exponents, self.bits)
+    }
+}
+
+impl<T> GroupOpsSimulationItem for ShortMultiExps<T>
+where T : ShortExp + Group + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} multi-exps: {} of size {} ({}-bit exponents)", T::NAME, self.num, self.bases.len(), self.bits)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>((0..self.num).map(|_| self.multi_exp()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.multi_exp()));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.multi_exp());
+    }
+
+    fn validate(&self) -> Option<bool> {
+        let scalars : Vec<Scalar> = self.exponents.iter().map(to_scalar).collect();
+        Some(self.multi_exp() == naive_multi_exp(&self.bases, &scalars))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(self.multi_exp().output_bytes())
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` G1 exps by random `bits`-bit exponents.
+    pub fn g1_short_exps(&mut self, num: usize, bits: usize) -> &mut Self {
+        let item = ShortExps::<G1Projective>::new(self.rng(), num, bits);
+        self.push_item(item)
+    }
+
+    pub fn g2_short_exps(&mut self, num: usize, bits: usize) -> &mut Self {
+        let item = ShortExps::<G2Projective>::new(self.rng(), num, bits);
+        self.push_item(item)
+    }
+
+    /// `num` G1 multi-exps of `size` bases by random `bits`-bit exponents, each over the same
+    /// inputs.
+    pub fn g1_short_multi_exps(&mut self, num: usize, size: usize, bits: usize) -> &mut Self {
+        let ShortExps { bits, bases, exponents } = ShortExps::<G1Projective>::new(self.rng(), size, bits);
+        self.push_item(ShortMultiExps { num, bits, bases, exponents })
+    }
+
+    pub fn g2_short_multi_exps(&mut self, num: usize, size: usize, bits: usize) -> &mut Self {
+        let ShortExps { bits, bases, exponents } = ShortExps::<G2Projective>::new(self.rng(), size, bits);
+        self.push_item(ShortMultiExps { num, bits, bases, exponents })
+    }
+}
@@ -0,0 +1,235 @@
+//! Verification of `n` sigma-protocol proofs over G1, Schnorr or DLEQ, either one by one or all
+//! at once through a random linear combination. Checked one by one, every proof costs one
+//! multi-exp of size 2 per base; batched, the `n` proofs cost one multi-exp of size `2 * n + 1`
+//! per base, as each proof contributes its commitment and its statement. Which is faster for a
+//! given `n` is what the `batch-schnorr` and `batch-dleq` presets measure.
+//!
+//! Both methods are cheapest when every proof is valid. A fraction of the proofs can be made
+//! invalid instead, as an adversary would send them: one by one, the verifier stops at the first
+//! invalid proof, rejecting the rest unchecked; batched, a failed batch only tells that some
+//! proof is invalid, so the verifier bisects it, checking both halves of every failed batch
+//! down to the single proofs, to find every invalid one.
+
+use crate::dleq::{BatchableDleqProof, DleqProof};
+use crate::group_ops_simulation::{GroupOpsSimulation, GroupOpsSimulationItem};
+use crate::report::OpCount;
+use crate::schnorr::{BatchableSchnorrProof, SchnorrProof};
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand::{RngCore, SeedableRng as _};
+use rand_chacha::ChaCha20Rng;
+use std::fmt;
+use std::hint::black_box;
+use std::ops::Range;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sigma {
+    Schnorr,
+    Dleq,
+}
+
+impl Sigma {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "schnorr" => Some(Sigma::Schnorr),
+            "dleq" => Some(Sigma::Dleq),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sigma::Schnorr => "schnorr",
+            Sigma::Dleq => "dleq",
+        }
+    }
+}
+
+impl fmt::Display for Sigma {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Sigma::Schnorr => "Schnorr",
+            Sigma::Dleq => "DLEQ",
+        })
+    }
+}
+
+/// How many of `size` proofs are invalid if `invalid_percent` percent are, rounded to the nearest.
+pub fn invalid_proofs(size: usize, invalid_percent: usize) -> usize {
+    (size * invalid_percent + 50) / 100
+}
+
+/// The batches a bisection checks to find `invalid` invalid proofs among `size`, as (batch size,
+/// number of batches of that size), for invalid proofs spread evenly, which is close to the
+/// worst case.
+pub fn bisection_batches(size: usize, invalid: usize) -> Vec<(usize, usize)> {
+    fn visit(range: Range<usize>, invalid: &[usize], batches: &mut Vec<(usize, usize)>) {
+        match batches.iter_mut().find(|(size, _)| *size == range.len()) {
+            Some((_, num)) => *num += 1,
+            None => batches.push((range.len(), 1)),
+        }
+        if range.len() == 1 || !invalid.iter().any(|i| range.contains(i)) {
+            return;
+        }
+        let middle = range.start + range.len() / 2;
+        visit(range.start..middle, invalid, batches);
+        visit(middle..range.end, invalid, batches);
+    }
+    let invalid : Vec<usize> = (0..invalid.min(size)).map(|k| (2 * k + 1) * size / (2 * invalid)).collect();
+    let mut batches = Vec::new();
+    if size > 0 {
+        visit(0..size, &invalid, &mut batches);
+    }
+    batches
+}
+
+enum Proofs {
+    Schnorr(Vec<SchnorrProof>),
+    BatchableSchnorr(Vec<BatchableSchnorrProof<G1Projective>>),
+    Dleq(Vec<DleqProof>),
+    BatchableDleq(Vec<BatchableDleqProof<G1Projective>>),
+}
+
+pub(crate) struct Verifications {
+    protocol: Sigma,
+    num: usize,
+    g: G1Projective,
+    h: G1Projective,
+    // `(g^x, h^x)`; Schnorr proofs only use `g^x`
+    statements: Vec<(G1Projective, G1Projective)>,
+    proofs: Proofs,
+    // positions of the invalid proofs, in increasing order
+    pub(crate) invalid: Vec<usize>,
+}
+
+impl Verifications {
+    // `size` proofs over the bases `g` and `h`, `invalid_percent` percent of them invalid at
+    // random positions
+    pub(crate) fn new(protocol: Sigma, num: usize, size: usize, batched: bool, invalid_percent: usize, h: G1Projective, rng: &mut impl RngCore) -> Self {
+        assert!(invalid_percent <= 100, "at most 100% of the proofs can be invalid");
+        let g = G1Projective::generator();
+        let witnesses : Vec<Scalar> = (0..size).map(|_| Scalar::random(&mut *rng)).collect();
+        let statements : Vec<(G1Projective, G1Projective)> = witnesses.iter().map(|x| (g * x, h * x)).collect();
+        let mut invalid = rand::seq::index::sample(&mut *rng, size, invalid_proofs(size, invalid_percent)).into_vec();
+        invalid.sort();
+        // an invalid proof proves the statement with a wrong witness
+        let witnesses : Vec<Scalar> = witnesses.iter().enumerate()
+            .map(|(i, x)| if invalid.binary_search(&i).is_ok() { x + Scalar::ONE } else { *x })
+            .collect();
+        let pairs = statements.iter().zip(&witnesses);
+        let proofs = match (protocol, batched) {
+            (Sigma::Schnorr, false) => Proofs::Schnorr(pairs.map(|((a, _), x)| SchnorrProof::prove(&g, a, x, &mut *rng)).collect()),
+            (Sigma::Schnorr, true) => Proofs::BatchableSchnorr(pairs.map(|((a, _), x)| BatchableSchnorrProof::prove(&g, a, x, &mut *rng)).collect()),
+            (Sigma::Dleq, false) => Proofs::Dleq(pairs.map(|((a, b), x)| DleqProof::prove(&g, &h, a, b, x, &mut *rng)).collect()),
+            (Sigma::Dleq, true) => Proofs::BatchableDleq(pairs.map(|((a, b), x)| BatchableDleqProof::prove(&g, &h, a, b, x, &mut *rng)).collect()),
+        };
+        Self { protocol, num, g, h, statements, proofs, invalid }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.statements.len()
+    }
+
+    fn batched(&self) -> bool {
+        matches!(self.proofs, Proofs::BatchableSchnorr(_) | Proofs::BatchableDleq(_))
+    }
+
+    // the proofs in `range`, one by one (stopping at the first invalid one) or as a batch
+    pub(crate) fn check(&self, range: Range<usize>, rng: &mut impl RngCore) -> bool {
+        let (g, h) = (&self.g, &self.h);
+        let statements = &self.statements[range.clone()];
+        match &self.proofs {
+            Proofs::Schnorr(proofs) => proofs[range].iter().zip(statements).all(|(proof, (a, _))| proof.verify(g, a)),
+            Proofs::Dleq(proofs) => proofs[range].iter().zip(statements).all(|(proof, (a, b))| proof.verify_multi_exp(g, h, a, b)),
+            Proofs::BatchableSchnorr(proofs) => {
+                let statements : Vec<G1Projective> = statements.iter().map(|(a, _)| *a).collect();
+                BatchableSchnorrProof::verify_batch(g, &statements, &proofs[range], rng)
+            }
+            Proofs::BatchableDleq(proofs) => BatchableDleqProof::verify_batch(g, h, statements, &proofs[range], rng),
+        }
+    }
+
+    // the invalid proofs in `range`, appended to `found` by checking the range as a batch and
+    // bisecting it if that fails
+    fn bisect(&self, range: Range<usize>, rng: &mut impl RngCore, found: &mut Vec<usize>) {
+        if self.check(range.clone(), rng) {
+            return;
+        }
+        if range.len() == 1 {
+            found.push(range.start);
+            return;
+        }
+        let middle = range.start + range.len() / 2;
+        self.bisect(range.start..middle, rng, found);
+        self.bisect(middle..range.end, rng, found);
+    }
+
+    // the positions of the invalid proofs the verification finds: the first one checked one by
+    // one, all of them batched; batches seed their random coefficients with `i`
+    fn verify(&self, i: usize) -> Vec<usize> {
+        let mut rng = ChaCha20Rng::seed_from_u64(i as u64);
+        if self.batched() {
+            let mut found = Vec::new();
+            self.bisect(0..self.size(), &mut rng, &mut found);
+            return found;
+        }
+        (0..self.size()).find(|&j| !self.check(j..j + 1, &mut rng)).into_iter().collect()
+    }
+}
+
+impl GroupOpsSimulationItem for Verifications {
+    fn description(&self) -> String {
+        let method = match self.proofs {
+            Proofs::Schnorr(_) | Proofs::Dleq(_) => "one by one",
+            Proofs::BatchableSchnorr(_) | Proofs::BatchableDleq(_) => "batched",
+        };
+        let invalid = if self.invalid.is_empty() { String::new() } else { format!(", {} invalid", self.invalid.len()) };
+        format!("{} verifications ({}): {} of {} proofs{}", self.protocol, method, self.num, self.size(), invalid)
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.size())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<Vec<usize>>>((0..self.num).map(|i| self.verify(i)).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, i: usize) {
+        black_box(self.verify(i));
+    }
+
+    // the verification must find the invalid proofs, and only those
+    fn validate(&self) -> Option<bool> {
+        let expected = if self.batched() { self.invalid.clone() } else { self.invalid.iter().copied().take(1).collect() };
+        Some(self.verify(0) == expected)
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// `num` verifications of `size` proofs of `protocol` each, with `batched` through a random
+    /// linear combination and otherwise one by one.
+    pub fn sigma_verifications(&mut self, protocol: Sigma, num: usize, size: usize, batched: bool) -> &mut Self {
+        self.sigma_verifications_with_invalid(protocol, num, size, batched, 0)
+    }
+
+    /// Same as `sigma_verifications`, with `invalid_percent` percent of the proofs (rounded to
+    /// the nearest count) invalid, at random positions drawn once for all `num` verifications:
+    /// one by one, the verification stops at the first; batched, it bisects failed batches until
+    /// it has found all of them.
+    pub fn sigma_verifications_with_invalid(&mut self, protocol: Sigma, num: usize, size: usize, batched: bool, invalid_percent: usize) -> &mut Self {
+        let h = self.rng().points::<G1Projective>(1)[0];
+        let verifications = Verifications::new(protocol, num, size, batched, invalid_percent, h, self.rng());
+        self.push_item(verifications)
+    }
+}
@@ -0,0 +1,234 @@
+//! Distributions of op sizes, for multi-exps and multi-pairings whose sizes vary from one op to
+//! the next, as a verifier's do when every message it checks carries a different number of
+//! terms. An item draws the size of each of its ops from the distribution once, when its inputs
+//! are drawn, so every run times the same ops.
+
+use rand::{Rng as _, RngCore};
+use std::fmt;
+
+
+/// The largest size a distribution may draw. `SizeDistribution::probabilities` lists every size
+/// up to it, and the cost model predicts each of them.
+pub const MAX_SIZE : usize = 1 << 20;
+
+/// Where the sizes of ops come from, with sizes from 1 to `MAX_SIZE`, checked by the
+/// constructors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeDistribution(Kind);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Uniform { min: usize, max: usize },
+    List(Vec<usize>),
+    Zipf { max: usize, exponent: f64 },
+}
+
+// exponents are finite, see `SizeDistribution::zipf`
+impl Eq for SizeDistribution {}
+
+fn check_size(size: usize) -> Result<usize, String> {
+    match size {
+        1..=MAX_SIZE => Ok(size),
+        _ => Err(format!("invalid size {} (sizes are from 1 to {})", size, MAX_SIZE)),
+    }
+}
+
+impl SizeDistribution {
+    /// Every size from `min` to `max` equally likely.
+    pub fn uniform(min: usize, max: usize) -> Result<Self, String> {
+        let (min, max) = (check_size(min)?, check_size(max)?);
+        if min > max {
+            return Err(format!("empty range of sizes {}..={}", min, max));
+        }
+        Ok(Self(Kind::Uniform { min, max }))
+    }
+
+    /// A size of the list, each entry equally likely (so repeating a size weights it).
+    pub fn list(sizes: Vec<usize>) -> Result<Self, String> {
+        if sizes.is_empty() {
+            return Err("the list of sizes is empty".to_string());
+        }
+        sizes.iter().try_for_each(|&size| check_size(size).map(|_| ()))?;
+        Ok(Self(Kind::List(sizes)))
+    }
+
+    /// Size `k` from 1 to `max` with probability in proportion to `1/k^exponent`, for a finite
+    /// exponent of at least 0.
+    pub fn zipf(max: usize, exponent: f64) -> Result<Self, String> {
+        if !(exponent.is_finite() && exponent >= 0.0) {
+            return Err(format!("invalid exponent {} (exponents are finite and at least 0)", exponent));
+        }
+        Ok(Self(Kind::Zipf { max: check_size(max)?, exponent }))
+    }
+
+    /// `uniform:MIN:MAX`, `zipf:MAX:S` for exponent `S`, or a comma-separated list of sizes, each
+    /// from 1 to `MAX_SIZE`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let number = |s: &str| s.parse::<usize>().map_err(|_| format!("invalid size `{}`", s));
+        let distribution = || match text.split(':').collect::<Vec<_>>()[..] {
+            ["uniform", min, max] => Self::uniform(number(min)?, number(max)?),
+            ["zipf", max, exponent] => match exponent.parse::<f64>() {
+                Ok(exponent) => Self::zipf(number(max)?, exponent),
+                Err(_) => Err(format!("invalid exponent `{}`", exponent)),
+            },
+            [list] => Self::list(list.split(',').map(number).collect::<Result<_, _>>()?),
+            _ => Err("unknown syntax (uniform:MIN:MAX, zipf:MAX:S, S1,S2,...)".to_string()),
+        };
+        distribution().map_err(|e| format!("{} in sizes `{}`", e, text))
+    }
+
+    /// The largest size the distribution can draw.
+    pub fn max(&self) -> usize {
+        match &self.0 {
+            Kind::Uniform { max, .. } | Kind::Zipf { max, .. } => *max,
+            Kind::List(sizes) => sizes.iter().copied().max().unwrap_or(0),
+        }
+    }
+
+    /// The expected size.
+    pub fn mean(&self) -> f64 {
+        match &self.0 {
+            Kind::Uniform { min, max } => (min + max) as f64 / 2.0,
+            Kind::List(sizes) => sizes.iter().sum::<usize>() as f64 / sizes.len() as f64,
+            Kind::Zipf { max, exponent } => {
+                let total : f64 = (1..=*max).map(|k| zipf_weight(k, *exponent)).sum();
+                (1..=*max).map(|k| k as f64 * zipf_weight(k, *exponent)).sum::<f64>() / total
+            }
+        }
+    }
+
+    /// Every size the distribution can draw, with its probability: for uniform and Zipf
+    /// distributions, one entry per size up to the largest.
+    pub fn probabilities(&self) -> Vec<(usize, f64)> {
+        match &self.0 {
+            Kind::Uniform { min, max } => (*min..=*max).map(|size| (size, 1.0 / (max - min + 1) as f64)).collect(),
+            Kind::List(sizes) => {
+                let mut distinct = sizes.clone();
+                distinct.sort_unstable();
+                distinct.dedup();
+                distinct.into_iter()
+                    .map(|size| (size, sizes.iter().filter(|&&s| s == size).count() as f64 / sizes.len() as f64))
+                    .collect()
+            }
+            Kind::Zipf { max, exponent } => {
+                let total : f64 = (1..=*max).map(|k| zipf_weight(k, *exponent)).sum();
+                (1..=*max).map(|k| (k, zipf_weight(k, *exponent) / total)).collect()
+            }
+        }
+    }
+
+    /// The sizes of `num` ops.
+    pub fn sample(&self, num: usize, rng: &mut (impl RngCore + ?Sized)) -> Vec<usize> {
+        match &self.0 {
+            Kind::Uniform { min, max } => (0..num).map(|_| rng.gen_range(*min..=*max)).collect(),
+            Kind::List(sizes) => (0..num).map(|_| sizes[rng.gen_range(0..sizes.len())]).collect(),
+            Kind::Zipf { max, exponent } => {
+                let zipf = Zipf::new(*max, *exponent);
+                (0..num).map(|_| zipf.sample(rng)).collect()
+            }
+        }
+    }
+}
+
+// the unnormalized probability of size `k`
+fn zipf_weight(k: usize, exponent: f64) -> f64 {
+    (k as f64).powf(-exponent)
+}
+
+// Sizes from 1 to `max` with probability in proportion to `1/k^exponent`, by rejection-inversion
+// (Hörmann and Derflinger, "Rejection-inversion to generate variates from monotone discrete
+// distributions", 1996): it inverts the integral `H` of the continuous `h(x) = 1/x^exponent`,
+// which bounds the weights from above, and rejects the few draws that land outside a weight. It
+// takes a few draws at most, in constant memory, whatever `max`.
+struct Zipf {
+    max: usize,
+    exponent: f64,
+    // `H(1.5) - 1` and `H(max + 0.5)`, the ends of the range `H` is inverted over
+    h_integral_1: f64,
+    h_integral_max: f64,
+    // draws at most this far below their rounding are accepted without comparing
+    squeeze: f64,
+}
+
+impl Zipf {
+    fn new(max: usize, exponent: f64) -> Self {
+        let mut zipf = Self { max, exponent, h_integral_1: 0.0, h_integral_max: 0.0, squeeze: 0.0 };
+        zipf.h_integral_1 = zipf.h_integral(1.5) - 1.0;
+        zipf.h_integral_max = zipf.h_integral(max as f64 + 0.5);
+        zipf.squeeze = 2.0 - zipf.h_integral_inverse(zipf.h_integral(2.5) - zipf.h(2.0));
+        zipf
+    }
+
+    fn h(&self, x: f64) -> f64 {
+        (-self.exponent * x.ln()).exp()
+    }
+
+    // `H(x) = (x^(1 - exponent) - 1) / (1 - exponent)`, or `ln x` for exponent 1
+    fn h_integral(&self, x: f64) -> f64 {
+        let log_x = x.ln();
+        expm1_over_x((1.0 - self.exponent) * log_x) * log_x
+    }
+
+    fn h_integral_inverse(&self, x: f64) -> f64 {
+        // rounding can take `t` below -1, outside the domain of `ln_1p`
+        let t = (x * (1.0 - self.exponent)).max(-1.0);
+        (ln_1p_over_x(t) * x).exp()
+    }
+
+    fn sample(&self, rng: &mut (impl RngCore + ?Sized)) -> usize {
+        loop {
+            let u = self.h_integral_max + rng.gen::<f64>() * (self.h_integral_1 - self.h_integral_max);
+            let x = self.h_integral_inverse(u);
+            let k = ((x + 0.5) as usize).clamp(1, self.max);
+            if k as f64 - x <= self.squeeze || u >= self.h_integral(k as f64 + 0.5) - self.h(k as f64) {
+                return k;
+            }
+        }
+    }
+}
+
+// `(e^x - 1) / x` and `ln(1 + x) / x`, by their Taylor series near 0, where the division loses
+// precision
+fn expm1_over_x(x: f64) -> f64 {
+    match x.abs() > 1e-8 {
+        true => x.exp_m1() / x,
+        false => 1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + 0.25 * x)),
+    }
+}
+
+fn ln_1p_over_x(x: f64) -> f64 {
+    match x.abs() > 1e-8 {
+        true => x.ln_1p() / x,
+        false => 1.0 - x * (0.5 - x * (1.0 / 3.0 - 0.25 * x)),
+    }
+}
+
+/// The syntax `parse` reads.
+impl fmt::Display for SizeDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Kind::Uniform { min, max } => write!(f, "uniform:{}:{}", min, max),
+            Kind::List(sizes) => write!(f, "{}", sizes.iter().map(usize::to_string).collect::<Vec<_>>().join(",")),
+            Kind::Zipf { max, exponent } => write!(f, "zipf:{}:{}", max, exponent),
+        }
+    }
+}
+
+/// `sizes` as item descriptions show them: `of size 64` if all are equal, otherwise their range
+/// and mean.
+pub fn describe(sizes: &[usize]) -> String {
+    let (min, max) = (sizes.iter().min().copied().unwrap_or(0), sizes.iter().max().copied().unwrap_or(0));
+    if min == max {
+        format!("of size {}", max)
+    } else {
+        format!("of sizes {} to {} (mean {:.1})", min, max, sizes.iter().sum::<usize>() as f64 / sizes.len() as f64)
+    }
+}
+
+/// The mean of `sizes`, rounded, as the size of their `OpCount`.
+pub fn mean(sizes: &[usize]) -> usize {
+    match sizes.len() {
+        0 => 0,
+        num => (sizes.iter().sum::<usize>() + num / 2) / num,
+    }
+}
@@ -0,0 +1,151 @@
+//! Multi-exps in which a share of the scalars is 0 or 1, as in the subset sums of verifiers
+//! (aggregating the keys of a signer set, summing commitments selected by a bitmap): a generic
+//! multi-exp spends a full scalar's windows on them, whereas a subset sum skips the zeros, adds
+//! the bases of the ones and multi-exps only the rest.
+
+use crate::group_ops_simulation::{naive_multi_exp, GroupOpsSimulation, GroupOpsSimulationItem, MultiExp, NamedGroup};
+use crate::parallel::parallel_map;
+use crate::report::OpCount;
+use crate::test_vectors::OutputBytes;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field as _;
+use group::Group;
+use rand::RngCore;
+use std::hint::black_box;
+use std::ops::Mul;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SparseMethod {
+    /// blstrs' multi-exp over all bases, zeros and ones included
+    Generic,
+    /// the bases of the ones added up, and a multi-exp of the other non-zero scalars
+    SubsetSum,
+}
+
+impl SparseMethod {
+    pub const ALL: [SparseMethod; 2] = [SparseMethod::Generic, SparseMethod::SubsetSum];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SparseMethod::Generic => "generic",
+            SparseMethod::SubsetSum => "subset sum",
+        }
+    }
+}
+
+/// `size` scalars of which about `binary_percent` percent are 0 or 1, as many of each, and the
+/// rest random.
+pub fn sparse_scalars(rng: &mut impl RngCore, size: usize, binary_percent: usize) -> Vec<Scalar> {
+    assert!(binary_percent <= 100, "a percentage is at most 100, not {}", binary_percent);
+    (0..size)
+        .map(|_| match rng.next_u32() % 200 {
+            x if (x as usize) < binary_percent => Scalar::ZERO,
+            x if (x as usize) < 2 * binary_percent => Scalar::ONE,
+            _ => Scalar::random(&mut *rng),
+        })
+        .collect()
+}
+
+struct SparseMultiExps<T> {
+    method: SparseMethod,
+    num: usize,
+    binary_percent: usize,
+    bases: Vec<T>,
+    scalars: Vec<Scalar>,
+    // for `SubsetSum`, split up when the item is built as a verifier has its bitmap up front
+    ones: Vec<T>,
+    rest_bases: Vec<T>,
+    rest_scalars: Vec<Scalar>,
+}
+
+impl<T> SparseMultiExps<T>
+where T : Group + MultiExp {
+    fn new(method: SparseMethod, num: usize, binary_percent: usize, bases: Vec<T>, scalars: Vec<Scalar>) -> Self {
+        let mut item = Self { method, num, binary_percent, bases, scalars, ones: Vec::new(), rest_bases: Vec::new(), rest_scalars: Vec::new() };
+        for (base, scalar) in item.bases.iter().zip(&item.scalars) {
+            if *scalar == Scalar::ONE {
+                item.ones.push(*base);
+            } else if *scalar != Scalar::ZERO {
+                item.rest_bases.push(*base);
+                item.rest_scalars.push(*scalar);
+            }
+        }
+        item
+    }
+
+    fn multi_exp(&self) -> T {
+        match self.method {
+            SparseMethod::Generic => T::multi_exp(&self.bases, &self.scalars),
+            SparseMethod::SubsetSum => {
+                let sum : T = self.ones.iter().sum();
+                if self.rest_bases.is_empty() {
+                    sum
+                } else {
+                    sum + T::multi_exp(&self.rest_bases, &self.rest_scalars)
+                }
+            }
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for SparseMultiExps<T>
+where T : Group + MultiExp + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync {
+    fn description(&self) -> String {
+        format!("{} multi-exps ({}, {}% binary scalars): {} of size {}", T::NAME, self.method.name(), self.binary_percent, self.num, self.bases.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.bases.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>((0..self.num).map(|_| self.multi_exp()).collect());
+    }
+
+    fn simulate_parallel(&self, threads: usize) {
+        black_box(parallel_map(threads, self.num, |_| self.multi_exp()));
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.multi_exp());
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.multi_exp() == naive_multi_exp(&self.bases, &self.scalars))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(self.multi_exp().output_bytes())
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn add_sparse_multi_exps<T>(&mut self, num: usize, size: usize, binary_percent: usize) -> &mut Self
+    where T : Group + MultiExp + Mul<Scalar, Output = T> + NamedGroup + OutputBytes + Send + Sync + 'static {
+        let bases : Vec<T> = self.rng().points(size);
+        let scalars = sparse_scalars(self.rng(), size, binary_percent);
+        self.group(|sim| {
+            for method in SparseMethod::ALL {
+                sim.push_item(SparseMultiExps::new(method, num, binary_percent, bases.clone(), scalars.clone()));
+            }
+        })
+    }
+
+    /// `num` G1 multi-exps of `size` bases, about `binary_percent` percent of whose scalars are
+    /// 0 or 1, once with every `SparseMethod`, as one item each.
+    pub fn g1_sparse_multi_exps(&mut self, num: usize, size: usize, binary_percent: usize) -> &mut Self {
+        self.add_sparse_multi_exps::<G1Projective>(num, size, binary_percent)
+    }
+    /// Same as `g1_sparse_multi_exps`, in G2.
+    pub fn g2_sparse_multi_exps(&mut self, num: usize, size: usize, binary_percent: usize) -> &mut Self {
+        self.add_sparse_multi_exps::<G2Projective>(num, size, binary_percent)
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Summary statistics over repeated timings of the same work.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    /// the number of samples
+    pub count: usize,
+    pub mean: Duration,
+    pub stddev: Duration,
+    pub median: Duration,
+    /// the median of the samples' distances from the median
+    pub median_abs_dev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    /// Panics if `samples` is empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "cannot summarize zero samples");
+        let secs : Vec<f64> = samples.iter().map(Duration::as_secs_f64).collect();
+        let n = secs.len() as f64;
+        let mean = secs.iter().sum::<f64>() / n;
+        // sample (n - 1) standard deviation; a single run has no spread
+        let stddev = if secs.len() > 1 {
+            (secs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let median = sorted_median(&sorted);
+        let mut deviations : Vec<Duration> = sorted.iter().map(|&x| x.abs_diff(median)).collect();
+        deviations.sort();
+        let median_abs_dev = sorted_median(&deviations);
+
+        Self {
+            count: samples.len(),
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(stddev),
+            median,
+            median_abs_dev,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+// of sorted, non-empty samples
+fn sorted_median(sorted: &[Duration]) -> Duration {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mean {:?} ± {:?} (median {:?}, min {:?}, max {:?})",
+            self.mean, self.stddev, self.median, self.min, self.max)
+    }
+}
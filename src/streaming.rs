@@ -0,0 +1,142 @@
+//! Multi-exps for verifiers that can't hold all their bases in memory, such as light clients: the
+//! bases are kept in their uncompressed encodings, as they are stored or arrive, and the
+//! streamed multi-exp decodes `chunk_size` of them at a time, multi-exponentiates them and adds
+//! the result to a running sum, so that it never holds more than one chunk of points (and the
+//! multi-exp's scratch space for one chunk). The same multi-exps are timed all at once too,
+//! decoding every base first, so the two differ only in the chunking: each chunk is a multi-exp
+//! of its own, and Pippenger's saving shrinks with the size, so small chunks cost more.
+//!
+//! The decoding skips the on-curve and subgroup checks (the bases are trusted, e.g. a setup the
+//! client checked once), as both items do it for every base alike. With alloc tracking, the
+//! peak memory of the two items shows what the chunking saves.
+
+use crate::group_ops_simulation::{naive_multi_exp, GroupOpsSimulation, GroupOpsSimulationItem, MultiExp, NamedGroup};
+use crate::report::OpCount;
+use crate::test_vectors::OutputBytes;
+
+use blstrs::{G1Projective, G2Projective, Scalar};
+use ff::Field as _;
+use group::{Curve, UncompressedEncoding};
+use rand::RngCore;
+use std::hint::black_box;
+use std::ops::{Mul, Range};
+
+
+// the bounds of the groups whose multi-exps can be streamed
+trait StreamedGroup : Curve + MultiExp + NamedGroup + Mul<Scalar, Output = Self> + From<Self::AffineRepr> + OutputBytes + Send + Sync
+where Self::AffineRepr : UncompressedEncoding {}
+
+impl<T> StreamedGroup for T
+where T : Curve + MultiExp + NamedGroup + Mul<Scalar, Output = T> + From<T::AffineRepr> + OutputBytes + Send + Sync,
+      T::AffineRepr : UncompressedEncoding {}
+
+struct StreamingMultiExps<T> {
+    num: usize,
+    // the uncompressed encodings of the bases, back to back
+    encoded: Vec<u8>,
+    scalars: Vec<Scalar>,
+    /// `None` for all at once
+    chunk_size: Option<usize>,
+    _group: std::marker::PhantomData<T>,
+}
+
+impl<T> StreamingMultiExps<T>
+where T : StreamedGroup,
+      T::AffineRepr : UncompressedEncoding {
+    fn encoded_size() -> usize {
+        <T::AffineRepr as UncompressedEncoding>::Uncompressed::default().as_ref().len()
+    }
+
+    fn decode(&self, range: Range<usize>) -> Vec<T> {
+        let size = Self::encoded_size();
+        self.encoded[range.start * size..range.end * size].chunks_exact(size)
+            .map(|bytes| {
+                let mut encoding = <T::AffineRepr as UncompressedEncoding>::Uncompressed::default();
+                encoding.as_mut().copy_from_slice(bytes);
+                T::from(T::AffineRepr::from_uncompressed_unchecked(&encoding).unwrap())
+            })
+            .collect()
+    }
+
+    fn multi_exp(&self) -> T {
+        let size = self.scalars.len();
+        match self.chunk_size {
+            None => T::multi_exp(&self.decode(0..size), &self.scalars),
+            Some(chunk_size) => (0..size).step_by(chunk_size)
+                .map(|start| start..(start + chunk_size).min(size))
+                .fold(T::identity(), |acc, chunk| acc + T::multi_exp(&self.decode(chunk.clone()), &self.scalars[chunk])),
+        }
+    }
+}
+
+impl<T> GroupOpsSimulationItem for StreamingMultiExps<T>
+where T : StreamedGroup,
+      T::AffineRepr : UncompressedEncoding {
+    fn description(&self) -> String {
+        let method = match self.chunk_size {
+            None => "all at once".to_string(),
+            Some(chunk_size) => format!("streamed in chunks of {}", chunk_size),
+        };
+        format!("{} multi-exps ({}): {} of size {}", T::NAME, method, self.num, self.scalars.len())
+    }
+
+    fn op_count(&self) -> OpCount {
+        OpCount::multi(self.num, self.scalars.len())
+    }
+
+    fn simulate(&self) {
+        black_box::<Vec<T>>((0..self.num).map(|_| self.multi_exp()).collect());
+    }
+
+    fn ops(&self) -> usize {
+        self.num
+    }
+
+    fn simulate_op(&self, _i: usize) {
+        black_box(self.multi_exp());
+    }
+
+    fn notes(&self) -> Option<String> {
+        let chunk_size = self.chunk_size?;
+        Some(format!("{} multi-exps of at most {} bases each, summed up", self.scalars.len().div_ceil(chunk_size), chunk_size))
+    }
+
+    fn validate(&self) -> Option<bool> {
+        Some(self.multi_exp() == naive_multi_exp(&self.decode(0..self.scalars.len()), &self.scalars))
+    }
+
+    fn output(&self) -> Option<Vec<u8>> {
+        Some(self.multi_exp().output_bytes())
+    }
+}
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    fn streaming_multi_exps<T>(&mut self, num: usize, size: usize, chunk_size: usize) -> &mut Self
+    where T : StreamedGroup + 'static,
+          T::AffineRepr : UncompressedEncoding {
+        assert!(chunk_size > 0, "chunks need at least one base");
+        let encoded : Vec<u8> = self.rng().points::<T>(size).iter()
+            .flat_map(|p| p.to_affine().to_uncompressed().as_ref().to_vec())
+            .collect();
+        let scalars : Vec<Scalar> = (0..size).map(|_| Scalar::random(&mut *self.rng())).collect();
+        self.group(|sim| {
+            for chunk_size in [None, Some(chunk_size)] {
+                let (encoded, scalars) = (encoded.clone(), scalars.clone());
+                sim.push_item(StreamingMultiExps::<T> { num, encoded, scalars, chunk_size, _group: std::marker::PhantomData });
+            }
+        })
+    }
+
+    /// The same `num` G1 multi-exps of `size` bases, all at once and streamed in chunks of
+    /// `chunk_size` bases, as one item each, both decoding their bases from their uncompressed
+    /// encodings.
+    pub fn g1_streaming_multi_exps(&mut self, num: usize, size: usize, chunk_size: usize) -> &mut Self {
+        self.streaming_multi_exps::<G1Projective>(num, size, chunk_size)
+    }
+
+    pub fn g2_streaming_multi_exps(&mut self, num: usize, size: usize, chunk_size: usize) -> &mut Self {
+        self.streaming_multi_exps::<G2Projective>(num, size, chunk_size)
+    }
+}
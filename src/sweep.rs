@@ -0,0 +1,227 @@
+//! Parameter sweeps: run a protocol for every combination of parameter values and collect the
+//! timings in one table.
+
+use crate::communication::format_bytes;
+use crate::fit::{fit_all, Fit};
+use crate::protocol::{Expr, ExprError, Params};
+use crate::render::{Cell, Table, Totals};
+
+use std::fmt;
+use std::time::Duration;
+
+
+/// The parameter combinations of a sweep. Each axis is a list of expressions that may refer to
+/// the axes before it, so derived parameters such as `t = 2*n/3` are single-value axes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sweep {
+    pub axes: Vec<(String, Vec<Expr>)>,
+}
+
+impl Sweep {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn axis(mut self, name: &str, values: impl IntoIterator<Item = i64>) -> Self {
+        self.axes.push((name.to_string(), values.into_iter().map(Expr::Const).collect()));
+        self
+    }
+
+    /// A parameter computed from the ones before it; panics if `expr` doesn't parse.
+    pub fn derived(mut self, name: &str, expr: &str) -> Self {
+        self.axes.push((name.to_string(), vec![Expr::parse(expr).unwrap_or_else(|e| panic!("{}", e))]));
+        self
+    }
+
+    /// Parses an axis given as `NAME=VALUES`, where VALUES is a comma-separated list whose
+    /// entries are expressions or ranges: `LO..=HI` (step 1), `LO..=HI:+STEP` or `LO..=HI:*FACTOR`.
+    /// E.g. `n=128..=8192:*2`, `k=8,16`, `t=2*n/3`.
+    pub fn parse_axis(&mut self, spec: &str) -> Result<(), String> {
+        let (name, values) = spec.split_once('=').ok_or_else(|| format!("expected NAME=VALUES, got `{}`", spec))?;
+        let mut exprs = Vec::new();
+        for entry in values.split(',') {
+            match entry.split_once("..=") {
+                Some((lo, rest)) => {
+                    let (hi, step) = rest.split_once(':').unwrap_or((rest, "+1"));
+                    let constant = |s: &str| s.trim().parse::<i64>().map_err(|_| format!("invalid range bound `{}`", s));
+                    let (lo, hi) = (constant(lo)?, constant(hi)?);
+                    let (geometric, step) = match step.split_at(1.min(step.len())) {
+                        ("+", step) => (false, constant(step)?),
+                        ("*", step) => (true, constant(step)?),
+                        _ => return Err(format!("invalid range step `{}`, expected +N or *N", step)),
+                    };
+                    if step <= i64::from(geometric) {
+                        return Err(format!("range step in `{}` must make progress", entry));
+                    }
+                    let mut value = lo;
+                    while value <= hi {
+                        exprs.push(Expr::Const(value));
+                        value = if geometric { value * step } else { value + step };
+                    }
+                }
+                None => exprs.push(Expr::parse(entry).map_err(|e| e.to_string())?),
+            }
+        }
+        self.axes.push((name.trim().to_string(), exprs));
+        Ok(())
+    }
+
+    /// All parameter combinations, with the first axis varying slowest.
+    pub fn points(&self) -> Result<Vec<Params>, ExprError> {
+        let mut points = vec![Params::new()];
+        for (name, values) in &self.axes {
+            let mut next = Vec::with_capacity(points.len() * values.len());
+            for point in &points {
+                for value in values {
+                    next.push(point.clone().set(name, value.eval(point)?));
+                }
+            }
+            points = next;
+        }
+        Ok(points)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.axes.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+/// Per-phase and total times for one parameter combination.
+#[derive(Clone, Debug)]
+pub struct SweepRow {
+    pub params: Params,
+    pub phases: Vec<(String, Duration)>,
+    pub total: Duration,
+    /// bytes of all messages, if known
+    pub transcript: Option<usize>,
+    /// the units of the table's `unit` one run handles
+    pub units: Option<usize>,
+}
+
+/// The outcome of a sweep: one row per parameter combination.
+#[derive(Clone, Debug, Default)]
+pub struct SweepTable {
+    pub param_names: Vec<String>,
+    pub rows: Vec<SweepRow>,
+    /// the protocol's throughput unit, for columns of the total per unit (the amortized cost)
+    /// and units per second
+    pub unit: Option<String>,
+}
+
+impl SweepTable {
+    // in the order they first appear
+    fn phase_names(&self) -> Vec<&str> {
+        let mut phase_names : Vec<&str> = Vec::new();
+        for row in &self.rows {
+            for (name, _) in &row.phases {
+                if !phase_names.contains(&name.as_str()) {
+                    phase_names.push(name);
+                }
+            }
+        }
+        phase_names
+    }
+
+    /// The first row in which phase `b` takes less time than phase `a`, e.g. the smallest batch
+    /// for which a batched verifier beats checking one by one.
+    pub fn crossover(&self, a: &str, b: &str) -> Option<&SweepRow> {
+        let time = |row: &SweepRow, phase: &str| row.phases.iter().find(|(name, _)| name == phase).map(|(_, d)| *d);
+        self.rows.iter().find(|row| matches!((time(row, a), time(row, b)), (Some(a), Some(b)) if b < a))
+    }
+
+    /// The fits of every phase's and the total time against parameter `param`, each with the
+    /// best model first (see module `fit`). The other parameters should be fixed or derived
+    /// from `param`, e.g. `t=2*n/3`.
+    pub fn fits(&self, param: &str) -> Vec<(String, Vec<Fit>)> {
+        let points = |time: &dyn Fn(&SweepRow) -> Option<Duration>| -> Vec<(f64, f64)> {
+            self.rows.iter()
+                .filter_map(|row| Some((row.params.get(param)? as f64, time(row)?.as_secs_f64())))
+                .collect()
+        };
+        self.phase_names().into_iter()
+            .map(|phase| (phase.to_string(), fit_all(&points(&|row| row.phases.iter().find(|(name, _)| name == phase).map(|(_, d)| *d)))))
+            .chain([("total".to_string(), fit_all(&points(&|row| Some(row.total))))])
+            .collect()
+    }
+
+    // the total per unit, if the row has a count of them
+    fn amortized(&self, row: &SweepRow) -> Option<Option<Duration>> {
+        self.unit.as_ref()?;
+        Some(row.units.filter(|&units| units > 0).map(|units| row.total / units as u32))
+    }
+
+    // one unit of `unit`, e.g. `per secret` for secrets
+    fn amortized_header(&self) -> Option<String> {
+        self.unit.as_ref().map(|unit| format!("per {}", unit.strip_suffix('s').unwrap_or(unit)))
+    }
+
+    // units per second, if the row has a count of them
+    fn rate(&self, row: &SweepRow) -> Option<String> {
+        self.unit.as_ref()?;
+        Some(row.units.map_or_else(String::new, |units| format!("{:.1}", units as f64 / row.total.as_secs_f64())))
+    }
+
+    fn rate_header(&self) -> Option<String> {
+        self.unit.as_ref().map(|unit| format!("{}/s", unit))
+    }
+
+    /// The same columns as the plain-text table, for rendering as Markdown or LaTeX.
+    pub fn to_table(&self) -> Table {
+        let phase_names = self.phase_names();
+        let with_transcript = self.rows.iter().any(|row| row.transcript.is_some());
+        let header = self.param_names.iter().cloned()
+            .chain(phase_names.iter().map(|name| name.to_string()))
+            .chain(["total".to_string()])
+            .chain(with_transcript.then(|| "transcript".to_string()))
+            .chain(self.amortized_header())
+            .chain(self.rate_header())
+            .collect();
+        let rows = self.rows.iter()
+            .map(|row| {
+                let params = self.param_names.iter()
+                    .map(|name| row.params.get(name).map_or(Cell::Empty, |v| Cell::Text(v.to_string())));
+                let phases = phase_names.iter()
+                    .map(|name| row.phases.iter().find(|(n, _)| n == name).map_or(Cell::Empty, |(_, d)| Cell::Time(*d)));
+                let transcript = with_transcript.then(|| row.transcript.map_or(Cell::Empty, Cell::Bytes));
+                let amortized = self.amortized(row).map(|time| time.map_or(Cell::Empty, Cell::Time));
+                let rate = self.rate(row).map(|rate| if rate.is_empty() { Cell::Empty } else { Cell::Text(rate) });
+                params.chain(phases).chain([Cell::Time(row.total)]).chain(transcript).chain(amortized).chain(rate).collect()
+            })
+            .collect();
+        Table { header, rows, totals: Totals::Column(self.param_names.len() + phase_names.len()) }
+    }
+}
+
+impl fmt::Display for SweepTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase_names = self.phase_names();
+        let with_transcript = self.rows.iter().any(|row| row.transcript.is_some());
+        let header : Vec<String> = self.param_names.iter().cloned()
+            .chain(phase_names.iter().map(|name| name.to_string()))
+            .chain(["total".to_string()])
+            .chain(with_transcript.then(|| "transcript".to_string()))
+            .chain(self.amortized_header())
+            .chain(self.rate_header())
+            .collect();
+        let cells : Vec<Vec<String>> = self.rows.iter()
+            .map(|row| {
+                let params = self.param_names.iter()
+                    .map(|name| row.params.get(name).map_or_else(String::new, |v| v.to_string()));
+                let phases = phase_names.iter().map(|name| {
+                    row.phases.iter().find(|(n, _)| n == name).map_or_else(String::new, |(_, d)| format!("{:.3?}", d))
+                });
+                let transcript = with_transcript.then(|| row.transcript.map_or_else(String::new, format_bytes));
+                let amortized = self.amortized(row).map(|time| time.map_or_else(String::new, |time| format!("{:.3?}", time)));
+                params.chain(phases).chain([format!("{:.3?}", row.total)]).chain(transcript).chain(amortized).chain(self.rate(row)).collect()
+            })
+            .collect();
+        let widths : Vec<usize> = (0..header.len())
+            .map(|i| cells.iter().map(|row| row[i].len()).chain([header[i].len()]).max().unwrap())
+            .collect();
+        for row in [header].iter().chain(&cells) {
+            let line : Vec<String> = row.iter().zip(&widths).map(|(cell, &w)| format!("{:>w$}", cell, w = w)).collect();
+            writeln!(f, "{}", line.join("  "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! The results a run computes, saved as test vectors: for every item that computes something
+//! (exps, multi-exps, pairings, ...), the concatenated encodings of its results, and the seed
+//! its inputs were drawn from. The same workload built from the same seed on another backend
+//! or blstrs version must reproduce them bit for bit:
+//!
+//! ```json
+//! {
+//!   "seed": "10935326333961687641",
+//!   "items": [
+//!     { "item": "G1 exps: 2", "output": "a1f3...", "bytes": 96 }
+//!   ]
+//! }
+//! ```
+//!
+//! Points are in their compressed encodings and Gt elements in blstrs' compressed form. Items
+//! whose results aren't canonical are saved in a canonical form: Miller loops with the final
+//! exponentiation applied, pairing equality checks as the multi-pairing they compare with the
+//! identity.
+
+use crate::json::{self, Value};
+use crate::serialization::PointEncoding;
+
+use blstrs::{G1Projective, G2Projective, Gt};
+use group::Curve as _;
+use std::fmt;
+use std::path::Path;
+
+
+/// Canonical bytes of an item's result, for test vectors.
+pub trait OutputBytes {
+    fn output_bytes(&self) -> Vec<u8>;
+}
+
+impl OutputBytes for G1Projective {
+    fn output_bytes(&self) -> Vec<u8> {
+        self.to_affine().to_compressed().to_vec()
+    }
+}
+
+impl OutputBytes for G2Projective {
+    fn output_bytes(&self) -> Vec<u8> {
+        self.to_affine().to_compressed().to_vec()
+    }
+}
+
+impl OutputBytes for Gt {
+    fn output_bytes(&self) -> Vec<u8> {
+        let mut out = vec![0; Gt::COMPRESSED_SIZE];
+        self.encode(true, &mut out);
+        out
+    }
+}
+
+/// The results of a sequence of operations, one after the other.
+pub fn concat_outputs<'a, T>(outputs: impl IntoIterator<Item = &'a T>) -> Vec<u8>
+where T : OutputBytes + 'a {
+    outputs.into_iter().flat_map(OutputBytes::output_bytes).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVectors {
+    pub seed: Option<u64>,
+    /// the name of every item with results (as reports print it) and its results
+    pub items: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Debug)]
+pub enum TestVectorError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for TestVectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestVectorError::Io(e) => write!(f, "{}", e),
+            TestVectorError::Invalid(message) => write!(f, "invalid test vectors: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TestVectorError {}
+
+impl From<std::io::Error> for TestVectorError {
+    fn from(e: std::io::Error) -> Self {
+        TestVectorError::Io(e)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+impl TestVectors {
+    /// How `computed` differs from these vectors, item by item, as messages; empty if every
+    /// item computed the same bytes.
+    pub fn mismatches(&self, computed: &TestVectors) -> Vec<String> {
+        if self.items.len() != computed.items.len() {
+            return vec![format!("{} items with results expected, {} computed", self.items.len(), computed.items.len())];
+        }
+        self.items.iter().zip(&computed.items)
+            .filter_map(|((name, expected), (computed_name, output))| {
+                if name != computed_name {
+                    Some(format!("`{}` expected, `{}` computed", name, computed_name))
+                } else if expected != output {
+                    let first = expected.iter().zip(output).position(|(a, b)| a != b).unwrap_or(expected.len().min(output.len()));
+                    Some(format!("`{}`: results differ from byte {} on", name, first))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let items = self.items.iter()
+            .map(|(name, output)| Value::Object(vec![
+                ("item".to_string(), Value::String(name.clone())),
+                ("output".to_string(), Value::String(to_hex(output))),
+                ("bytes".to_string(), Value::Number(output.len() as f64)),
+            ]))
+            .collect();
+        Value::Object(vec![
+            ("seed".to_string(), self.seed.map_or(Value::Null, |seed| Value::String(seed.to_string()))),
+            ("items".to_string(), Value::Array(items)),
+        ]).to_pretty_string()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TestVectorError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TestVectorError> {
+        let invalid = |message: &str| TestVectorError::Invalid(message.to_string());
+        let root = json::parse(text).map_err(|e| TestVectorError::Invalid(e.to_string()))?;
+        let seed = match root.get("seed") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(value.as_str().and_then(|seed| seed.parse().ok()).ok_or_else(|| invalid("`seed` must be an unsigned integer, as a string"))?),
+        };
+        let Some(Value::Array(items)) = root.get("items") else {
+            return Err(invalid("missing `items` list"));
+        };
+        let items = items.iter()
+            .map(|item| {
+                let name = item.get("item").and_then(Value::as_str)?;
+                let output = from_hex(item.get("output").and_then(Value::as_str)?)?;
+                Some((name.to_string(), output))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("`items` must be objects with an `item` name and a hex `output`"))?;
+        Ok(Self { seed, items })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+}
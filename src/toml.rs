@@ -0,0 +1,360 @@
+// A parser for the subset of TOML that workload files use: comments, bare and quoted keys,
+// strings, integers, floats, booleans, arrays, inline tables, `[table]` and `[[array.of.tables]]`
+// headers (dotted paths allowed). Dates and multi-line strings are not supported.
+
+use crate::json;
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Table),
+}
+
+impl Value {
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+        }
+    }
+}
+
+/// Key/value pairs in file order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Table(pub(crate) Vec<(String, Value)>);
+
+impl Table {
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.0.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(k, _)| k.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ParseError {
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub(crate) fn parse(text: &str) -> Result<Table, ParseError> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0, line: 1 };
+    let mut root = Table::default();
+    // path of the table that key/value lines currently go into
+    let mut current : Vec<String> = Vec::new();
+    loop {
+        parser.skip_whitespace_and_comments();
+        match parser.peek() {
+            None => return Ok(root),
+            Some('[') => {
+                parser.pos += 1;
+                let is_array = parser.eat('[');
+                let path = parser.key_path()?;
+                parser.expect(']')?;
+                if is_array {
+                    parser.expect(']')?;
+                }
+                parser.end_of_line()?;
+                let line = parser.line;
+                open_table(&mut root, &path, is_array).map_err(|message| ParseError { line, message })?;
+                current = path;
+            }
+            Some(_) => {
+                let path = parser.key_path()?;
+                parser.skip_spaces();
+                parser.expect('=')?;
+                let value = parser.value()?;
+                parser.end_of_line()?;
+                let line = parser.line;
+                let table = current_table(&mut root, &current);
+                insert(table, &path, value).map_err(|message| ParseError { line, message })?;
+            }
+        }
+    }
+}
+
+/// The value of a JSON document as TOML would read it, so the same code reads both: numbers
+/// without a fraction become integers. JSON's null has no counterpart.
+pub(crate) fn from_json(value: json::Value) -> Result<Value, String> {
+    Ok(match value {
+        json::Value::Null => return Err("null is not a valid value".to_string()),
+        json::Value::Boolean(b) => Value::Boolean(b),
+        json::Value::Number(x) if x.fract() == 0.0 && x.abs() < 2f64.powi(53) => Value::Integer(x as i64),
+        json::Value::Number(x) => Value::Float(x),
+        json::Value::String(s) => Value::String(s),
+        json::Value::Array(values) => Value::Array(values.into_iter().map(from_json).collect::<Result<_, _>>()?),
+        json::Value::Object(pairs) => Value::Table(Table(pairs.into_iter()
+            .map(|(key, value)| Ok((key, from_json(value)?)))
+            .collect::<Result<_, String>>()?)),
+    })
+}
+
+// creates the table at `path` (or appends a new element to the array of tables)
+fn open_table(root: &mut Table, path: &[String], is_array: bool) -> Result<(), String> {
+    let (last, parents) = path.split_last().unwrap();
+    let parent = current_table(root, parents);
+    match (parent.get_mut(last), is_array) {
+        (None, false) => parent.0.push((last.clone(), Value::Table(Table::default()))),
+        (None, true) => parent.0.push((last.clone(), Value::Array(vec![Value::Table(Table::default())]))),
+        (Some(Value::Array(tables)), true) => tables.push(Value::Table(Table::default())),
+        (Some(_), _) => return Err(format!("`{}` is defined twice", path.join("."))),
+    }
+    Ok(())
+}
+
+// follows `path` from the root, entering the last element of arrays of tables
+fn current_table<'a>(root: &'a mut Table, path: &[String]) -> &'a mut Table {
+    let mut table = root;
+    for key in path {
+        if table.get(key).is_none() {
+            table.0.push((key.clone(), Value::Table(Table::default())));
+        }
+        table = match table.get_mut(key).unwrap() {
+            Value::Table(t) => t,
+            Value::Array(values) => match values.last_mut() {
+                Some(Value::Table(t)) => t,
+                _ => unreachable!("headers only open tables"),
+            },
+            _ => unreachable!("headers only open tables"),
+        };
+    }
+    table
+}
+
+fn insert(table: &mut Table, path: &[String], value: Value) -> Result<(), String> {
+    let (last, parents) = path.split_last().unwrap();
+    let mut table = table;
+    for key in parents {
+        if table.get(key).is_none() {
+            table.0.push((key.clone(), Value::Table(Table::default())));
+        }
+        table = match table.get_mut(key).unwrap() {
+            Value::Table(t) => t,
+            _ => return Err(format!("`{}` is not a table", key)),
+        };
+    }
+    if table.get(last).is_some() {
+        return Err(format!("`{}` is defined twice", path.join(".")));
+    }
+    table.0.push((last.clone(), value));
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError { line: self.line, message: message.into() })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_spaces();
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", c))
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some('\n') => self.line += 1,
+                Some('\r') => {}
+                _ => return,
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), ParseError> {
+        self.skip_spaces();
+        self.skip_comment();
+        self.eat('\r');
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.pos += 1;
+                self.line += 1;
+                Ok(())
+            }
+            Some(c) => self.error(format!("unexpected `{}` after value", c)),
+        }
+    }
+
+    fn key_path(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut path = vec![self.key()?];
+        loop {
+            self.skip_spaces();
+            if !self.eat('.') {
+                return Ok(path);
+            }
+            path.push(self.key()?);
+        }
+    }
+
+    fn key(&mut self) -> Result<String, ParseError> {
+        self.skip_spaces();
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return self.error("expected a key");
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return self.error("unterminated string"),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    s.push(match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        _ => return self.error("unsupported escape sequence"),
+                    });
+                    self.pos += 1;
+                }
+                Some(c) => {
+                    s.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('"') => self.string().map(Value::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                loop {
+                    self.skip_whitespace_and_comments();
+                    if self.eat(']') {
+                        return Ok(Value::Array(values));
+                    }
+                    values.push(self.value()?);
+                    self.skip_whitespace_and_comments();
+                    if !self.eat(',') {
+                        self.skip_whitespace_and_comments();
+                        self.expect(']')?;
+                        return Ok(Value::Array(values));
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut table = Table::default();
+                self.skip_spaces();
+                if self.eat('}') {
+                    return Ok(Value::Table(table));
+                }
+                loop {
+                    let path = self.key_path()?;
+                    self.expect('=')?;
+                    let value = self.value()?;
+                    let line = self.line;
+                    insert(&mut table, &path, value).map_err(|message| ParseError { line, message })?;
+                    self.skip_spaces();
+                    if !self.eat(',') {
+                        self.expect('}')?;
+                        return Ok(Value::Table(table));
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || "+-._".contains(c)) {
+                    self.pos += 1;
+                }
+                let word : String = self.chars[start..self.pos].iter().collect();
+                match word.as_str() {
+                    "" => self.error("expected a value"),
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    _ => {
+                        let digits = word.replace('_', "");
+                        if let Ok(i) = digits.parse() {
+                            Ok(Value::Integer(i))
+                        } else if let Ok(x) = digits.parse() {
+                            Ok(Value::Float(x))
+                        } else {
+                            self.error(format!("invalid value `{}`", word))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Bindings for running workloads in a browser, on `wasm32-unknown-unknown`. The module imports
+//! from `pvss_sim`, besides the clock's `now` (see `clock`):
+//!
+//! - `random(ptr, len)`, to fill `len` bytes at `ptr` with random bytes, e.g. by
+//!   `crypto.getRandomValues` on a view of the memory;
+//! - `result(ptr, len)`, which receives the outcome of `pvss_sim_run` as `len` bytes of JSON at
+//!   `ptr`, to post wherever the page collects results.
+//!
+//! To run a workload file, the embedder copies its text into a buffer from `pvss_sim_alloc`,
+//! calls `pvss_sim_run` on it and frees it with `pvss_sim_free`; `wasm/pvss-sim.mjs` does this.
+//! The ops run once each on `backend::Zkcrypto` with feature `zkcrypto`, the pure-Rust curve,
+//! and on `backend::Blstrs` without it, with inputs derived from the `seed` of the file's
+//! `[options]` (0 if it sets none). Build with `cargo build-wasm` (see `.cargo/config.toml`).
+
+use crate::backend::{self, Backend};
+use crate::config;
+use crate::json::Value;
+use crate::report::SimulationReport;
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(feature = "zkcrypto")]
+type WasmBackend = backend::Zkcrypto;
+#[cfg(not(feature = "zkcrypto"))]
+type WasmBackend = backend::Blstrs;
+
+#[link(wasm_import_module = "pvss_sim")]
+extern "C" {
+    fn random(ptr: *mut u8, len: usize);
+    fn result(ptr: *const u8, len: usize);
+}
+
+fn random_bytes(dest: &mut [u8]) -> Result<(), getrandom::Error> {
+    // SAFETY: the import writes exactly `dest.len()` bytes at `dest`
+    unsafe { random(dest.as_mut_ptr(), dest.len()) };
+    Ok(())
+}
+
+getrandom::register_custom_getrandom!(random_bytes);
+
+/// A buffer of `len` bytes, for the embedder to write a workload file into.
+#[no_mangle]
+pub extern "C" fn pvss_sim_alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// Frees a buffer of `pvss_sim_alloc`.
+///
+/// # Safety
+///
+/// `ptr` must come from `pvss_sim_alloc(len)` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn pvss_sim_free(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// Runs the workload file of `len` bytes at `ptr` and hands its report to `result`, or
+/// `{"error": ...}` if the file doesn't parse or has ops the backend lacks. Returns 0 on success
+/// and 1 on an error.
+///
+/// # Safety
+///
+/// `ptr` must point to `len` initialized bytes, e.g. in a buffer of `pvss_sim_alloc`.
+#[no_mangle]
+pub unsafe extern "C" fn pvss_sim_run(ptr: *const u8, len: usize) -> u32 {
+    let text = String::from_utf8_lossy(std::slice::from_raw_parts(ptr, len));
+    let (status, json) = match run(&text) {
+        Ok(report) => (0, report),
+        Err(e) => (1, Value::Object(vec![("error".to_string(), Value::String(e))])),
+    };
+    let json = json.to_compact_string();
+    result(json.as_ptr(), json.len());
+    status
+}
+
+fn run(text: &str) -> Result<Value, String> {
+    let file = config::parse_file(text).map_err(|e| e.to_string())?;
+    let seed = file.options.seed.unwrap_or(0);
+    let report = backend::simulate_on::<WasmBackend>(&file.workload, ChaCha20Rng::seed_from_u64(seed)).map_err(|e| e.to_string())?;
+    Ok(to_json(&report, seed))
+}
+
+// times in nanoseconds, and the start in milliseconds since the Unix epoch, as JS dates count
+fn to_json(report: &SimulationReport, seed: u64) -> Value {
+    let nanos = |duration: Duration| Value::Number(duration.as_nanos() as f64);
+    let optional = |text: &Option<String>| text.clone().map_or(Value::Null, Value::String);
+    let started_at = report.metadata.started_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let items = report.items.iter()
+        .map(|item| Value::Object(vec![
+            ("description".to_string(), Value::String(item.description.clone())),
+            ("label".to_string(), optional(&item.label)),
+            ("phase".to_string(), optional(&item.phase)),
+            ("setup".to_string(), nanos(item.setup)),
+            ("duration".to_string(), nanos(item.duration)),
+        ]))
+        .collect();
+    Value::Object(vec![
+        ("backend".to_string(), Value::String(backend::describe::<WasmBackend>())),
+        ("curve".to_string(), Value::String(WasmBackend::CURVE.name().to_string())),
+        // as a string, since JSON numbers lose the low bits of large seeds
+        ("seed".to_string(), Value::String(seed.to_string())),
+        ("started_at".to_string(), Value::Number(started_at.as_millis() as f64)),
+        ("items".to_string(), Value::Array(items)),
+        ("total".to_string(), nanos(report.total)),
+    ])
+}
@@ -0,0 +1,234 @@
+//! Stake-weighted thresholds: party `i` holds `w_i` of the `W = w_1 + ... + w_n` shares, and the
+//! threshold counts shares rather than parties. A preset for `n` parties then runs with `n = W`,
+//! one share per virtual party, and its phases scale per real party:
+//!
+//! - "Verifier" runs once per party, whatever its weight, as every party checks the whole
+//!   transcript of `W` shares,
+//! - "Party" (decrypting one share) runs once per share a party holds,
+//! - every other phase (dealing, reconstruction from `t + 1` shares) runs once.
+//!
+//! Which parties answer a reconstruction is up to chance, so `sample_reconstructions` runs the
+//! actual PVSS reconstruction for random qualifying subsets and reports how its time varies.
+
+use crate::clock::Instant;
+use crate::polynomial::lagrange_coefficients_at_zero;
+use crate::pvss::{self, DecryptedShare, Keypair};
+use crate::stats::Summary;
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use rand::seq::SliceRandom as _;
+use rand::RngCore;
+use std::fmt;
+use std::time::Duration;
+
+
+/// The weight of every party, at least 1 each.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Weights(Vec<usize>);
+
+impl Weights {
+    pub fn new(weights: Vec<usize>) -> Result<Self, String> {
+        if weights.is_empty() {
+            return Err("need at least one party".to_string());
+        }
+        if weights.contains(&0) {
+            return Err("every party needs a weight of at least 1".to_string());
+        }
+        Ok(Self(weights))
+    }
+
+    /// A comma-separated list of weights, `equal:N:W` for `N` parties of weight `W`, or
+    /// `zipf:N:W` for `N` parties sharing about `W` shares in proportion to `1/i`, rounded and at
+    /// least 1 each.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let number = |s: &str| s.parse::<usize>().map_err(|_| format!("invalid number `{}` in weights `{}`", s, text));
+        match text.split(':').collect::<Vec<_>>()[..] {
+            ["equal", n, w] => Self::new(vec![number(w)?; number(n)?]),
+            ["zipf", n, w] => {
+                let (n, w) = (number(n)?, number(w)?);
+                let harmonic : f64 = (1..=n).map(|i| 1.0 / i as f64).sum();
+                Self::new((1..=n).map(|i| ((w as f64 / (i as f64 * harmonic)).round() as usize).max(1)).collect())
+            }
+            [list] => Self::new(list.split(',').map(number).collect::<Result<_, _>>()?),
+            _ => Err(format!("unknown weights `{}` (W1,W2,..., equal:N:W, zipf:N:W)", text)),
+        }
+    }
+
+    pub fn parties(&self) -> usize {
+        self.0.len()
+    }
+
+    /// `W`, the number of shares.
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    pub fn max(&self) -> usize {
+        *self.0.iter().max().unwrap()
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+/// How often a phase runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scaling {
+    Once,
+    PerParty,
+    PerShare,
+}
+
+impl Scaling {
+    pub fn of_phase(name: &str) -> Self {
+        match name {
+            "Verifier" => Scaling::PerParty,
+            "Party" => Scaling::PerShare,
+            _ => Scaling::Once,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WeightedPhase {
+    pub name: String,
+    pub scaling: Scaling,
+    /// one run of the phase
+    pub time: Duration,
+}
+
+/// A preset's phase times, measured with `n = W`, scaled by the weights.
+#[derive(Clone, Debug)]
+pub struct WeightedReport {
+    pub weights: Weights,
+    pub phases: Vec<WeightedPhase>,
+}
+
+impl WeightedReport {
+    pub fn new(weights: Weights, phase_totals: &[(String, Duration)]) -> Self {
+        let phases = phase_totals.iter()
+            .map(|(name, time)| WeightedPhase { name: name.clone(), scaling: Scaling::of_phase(name), time: *time })
+            .collect();
+        Self { weights, phases }
+    }
+
+    fn sum(&self, weight: impl Fn(Scaling) -> usize) -> Duration {
+        self.phases.iter().map(|phase| phase.time * weight(phase.scaling) as u32).sum()
+    }
+
+    /// The work of the heaviest party: verification, and decryption of its shares.
+    pub fn max_party(&self) -> Duration {
+        let max = self.weights.max();
+        self.sum(|scaling| match scaling {
+            Scaling::Once => 0,
+            Scaling::PerParty => 1,
+            Scaling::PerShare => max,
+        })
+    }
+
+    /// The work of everyone, the one-off phases included.
+    pub fn total(&self) -> Duration {
+        let (parties, shares) = (self.weights.parties(), self.weights.total());
+        self.sum(|scaling| match scaling {
+            Scaling::Once => 1,
+            Scaling::PerParty => parties,
+            Scaling::PerShare => shares,
+        })
+    }
+}
+
+impl fmt::Display for WeightedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (parties, shares, max) = (self.weights.parties(), self.weights.total(), self.weights.max());
+        writeln!(f, "weights: {} parties, {} shares, at most {} per party", parties, shares, max)?;
+        for phase in &self.phases {
+            match phase.scaling {
+                Scaling::Once => writeln!(f, "  {} (once): {:?}", phase.name, phase.time)?,
+                Scaling::PerParty => writeln!(f, "  {} (per party): {:?}, all parties {:?}", phase.name, phase.time, phase.time * parties as u32)?,
+                Scaling::PerShare => writeln!(f, "  {} (per share): {:?}, heaviest party {:?}, all parties {:?}",
+                    phase.name, phase.time, phase.time * max as u32, phase.time * shares as u32)?,
+            }
+        }
+        writeln!(f, "  max party: {:?}", self.max_party())?;
+        writeln!(f, "  total: {:?}", self.total())
+    }
+}
+
+/// Random parties, in the order they answer, until they hold at least `shares` shares together.
+pub fn sample_subset(weights: &Weights, shares: usize, rng: &mut impl RngCore) -> Vec<usize> {
+    let mut order : Vec<usize> = (0..weights.parties()).collect();
+    order.shuffle(rng);
+    let mut held = 0;
+    order.into_iter()
+        .take_while(|&party| {
+            let needed = held < shares;
+            held += weights.0[party];
+            needed
+        })
+        .collect()
+}
+
+/// One reconstruction from a random subset.
+#[derive(Clone, Copy, Debug)]
+pub struct ReconstructionSample {
+    pub parties: usize,
+    /// every share of the answering parties, at least `t + 1`
+    pub shares: usize,
+    pub time: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReconstructionSampling {
+    pub weights: Weights,
+    pub threshold: usize,
+    pub samples: Vec<ReconstructionSample>,
+}
+
+impl ReconstructionSampling {
+    pub fn summary(&self) -> Summary {
+        Summary::from_samples(&self.samples.iter().map(|sample| sample.time).collect::<Vec<_>>())
+    }
+}
+
+impl fmt::Display for ReconstructionSampling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = |values: Vec<usize>| (*values.iter().min().unwrap(), *values.iter().max().unwrap());
+        let parties = range(self.samples.iter().map(|sample| sample.parties).collect());
+        let shares = range(self.samples.iter().map(|sample| sample.shares).collect());
+        writeln!(f, "reconstruction from {} random subsets of {} parties ({} shares, threshold {}):",
+            self.samples.len(), self.weights.parties(), self.weights.total(), self.threshold)?;
+        writeln!(f, "  answering parties: {} to {}, shares checked: {} to {}", parties.0, parties.1, shares.0, shares.1)?;
+        writeln!(f, "  time: {}", self.summary())
+    }
+}
+
+/// Deals one PVSS transcript to the `W` shares of `weights` with threshold `t` (in shares), has
+/// every share decrypted, and then times `samples` reconstructions, each from a random set of
+/// parties that together hold `t + 1` shares: the reconstructor checks the decryption proofs of
+/// all shares the answering parties send, then interpolates from the first `t + 1`.
+pub fn sample_reconstructions(weights: &Weights, t: usize, samples: usize, rng: &mut impl RngCore) -> ReconstructionSampling {
+    assert!(t < weights.total(), "threshold must be below the number of shares");
+    assert!(samples >= 1, "need at least one sample");
+    let keys : Vec<Keypair> = (0..weights.total()).map(|_| Keypair::generate(&mut *rng)).collect();
+    let public_keys : Vec<G1Projective> = keys.iter().map(|key| key.public).collect();
+    let dealing = pvss::deal(&Scalar::random(&mut *rng), t, &public_keys, &mut *rng);
+    let shares : Vec<DecryptedShare> = keys.iter().enumerate().map(|(i, key)| pvss::decrypt_share(&dealing, i, key, &mut *rng)).collect();
+    // party i holds the shares from offsets[i] on
+    let offsets : Vec<usize> = weights.0.iter().scan(0, |offset, w| { *offset += w; Some(*offset - w) }).collect();
+    let samples = (0..samples)
+        .map(|_| {
+            let subset = sample_subset(weights, t + 1, rng);
+            let received : Vec<&DecryptedShare> = subset.iter().flat_map(|&party| &shares[offsets[party]..offsets[party] + weights.0[party]]).collect();
+            let start = Instant::now();
+            let valid = received.iter().all(|share| pvss::verify_decryption(&dealing, &public_keys[share.index], share));
+            let used = &received[..t + 1];
+            let points : Vec<Scalar> = used.iter().map(|share| Scalar::from(share.index as u64 + 1)).collect();
+            let bases : Vec<G1Projective> = used.iter().map(|share| share.share).collect();
+            std::hint::black_box((valid, G1Projective::multi_exp(&bases, &lagrange_coefficients_at_zero(&points))));
+            ReconstructionSample { parties: subset.len(), shares: received.len(), time: start.elapsed() }
+        })
+        .collect();
+    ReconstructionSampling { weights: weights.clone(), threshold: t, samples }
+}
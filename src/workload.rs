@@ -0,0 +1,848 @@
+use crate::chunked_elgamal::MAX_CHUNK_BITS;
+use crate::communication::{format_bytes, Element};
+use crate::error::SimulationError;
+use crate::group_ops_simulation::GroupOpsSimulation;
+use crate::merkle::MerkleHash;
+use crate::pippenger::{PippengerConfig, MAX_WINDOW_BITS};
+use crate::sigma::Sigma;
+use crate::sizes::SizeDistribution;
+use crate::report::{item_name, write_by_phase};
+
+use blstrs::Scalar;
+use ff::PrimeField as _;
+use rand::RngCore;
+use rand_chacha::ChaCha20Rng;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul};
+
+
+/// The kinds of operations a workload can contain, one per `GroupOpsSimulation` builder method.
+/// Kinds that take more than a count and a size carry the extra parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpKind {
+    G1Exps,
+    G2Exps,
+    GtExps,
+    G1MultiExps,
+    G2MultiExps,
+    GtMultiExps,
+    Pairings,
+    /// pairings of projective points, with `to_affine` converting them to affine as an item of
+    /// its own
+    PairingsConverting { to_affine: bool },
+    MultiPairings,
+    /// multi-pairings of projective points, or of points that are affine already unless
+    /// `to_affine`
+    MultiPairingsConverting { to_affine: bool },
+    MultiPairingsPrepared,
+    /// multi-pairings whose first `static_inputs` G2 points were prepared in advance, once for
+    /// the run (see `G2Preparations`)
+    MultiPairingsStatic { static_inputs: usize },
+    /// G2Prepared precomputations on their own
+    G2Preparations,
+    MillerLoops,
+    FinalExponentiations,
+    /// `size` pairings on the left-hand side
+    PairingEqualityChecks { rhs_size: usize },
+    /// the same products of `size` pairings with every `pairing_products::Strategy`
+    PairingProductsWithExponents,
+    ScalarMuls,
+    ScalarAdds,
+    ScalarInversions,
+    ScalarBatchInversions,
+    /// Lagrange coefficients, powers and inner products of `size` scalars, one by one and batched
+    ScalarBatchOps,
+    /// `num` polynomials of degree `size`, each evaluated at `num_points` points
+    PolyEvals { num_points: usize },
+    LagrangeInterpolation,
+    LagrangeInterpolationG2,
+    /// `size` is the domain size, a power of two
+    Fft,
+    G1SubgroupChecks,
+    G2SubgroupChecks,
+    /// `size` is the length of the messages in bytes
+    G1HashToCurve,
+    G2HashToCurve,
+    /// `num` of each base-field operation and map to the curve that hashing is made of
+    G1HashToCurveSteps,
+    G2HashToCurveSteps,
+    SerializeG1 { compressed: bool },
+    SerializeG2 { compressed: bool },
+    SerializeGt,
+    DeserializeG1 { compressed: bool },
+    DeserializeG2 { compressed: bool },
+    DeserializeGt,
+    /// compressed decodings without the subgroup check
+    G1Decompressions,
+    G2Decompressions,
+    G1Additions,
+    G2Additions,
+    G1MixedAdditions,
+    G2MixedAdditions,
+    G1Doublings,
+    G2Doublings,
+    G1AffineConversions,
+    G2AffineConversions,
+    G1BatchNormalizations,
+    G2BatchNormalizations,
+    /// the same `num` sums of `size` points in every `accumulation::Coordinates`
+    G1Accumulations,
+    G2Accumulations,
+    /// equality and `is_identity` checks of projective points, directly or, in batches of
+    /// `size`, in affine coordinates after a batch normalization
+    G1EqualityChecks,
+    G2EqualityChecks,
+    G1AffineEqualityChecks,
+    G2AffineEqualityChecks,
+    G1IdentityChecks,
+    G2IdentityChecks,
+    G1AffineIdentityChecks,
+    G2AffineIdentityChecks,
+    GtMuls,
+    /// the same inversions with every `gt_ops::GtInversion`
+    GtInversions,
+    GtEqualityChecks,
+    GtMembershipChecks,
+    FixedBaseExps { window_bits: usize },
+    G2FixedBaseExps { window_bits: usize },
+    /// the same exps with every `exp_methods::ExpMethod`
+    G1ExpMethods,
+    G2ExpMethods,
+    /// the same exps with and without the GLV endomorphism
+    G1GlvExps,
+    /// exps and multi-exps by random exponents of `bits` bits, as batch verification draws them
+    G1ShortExps { bits: usize },
+    G2ShortExps { bits: usize },
+    G1ShortMultiExps { bits: usize },
+    G2ShortMultiExps { bits: usize },
+    /// the same multi-exps, about `binary_percent` percent of whose scalars are 0 or 1, with every
+    /// `sparse_multi_exps::SparseMethod`
+    G1SparseMultiExps { binary_percent: usize },
+    G2SparseMultiExps { binary_percent: usize },
+    /// the same multi-exps with all bases decoded at once and streamed `chunk_size` at a time
+    G1StreamingMultiExps { chunk_size: usize },
+    G2StreamingMultiExps { chunk_size: usize },
+    G1PippengerMultiExps(PippengerConfig),
+    G2PippengerMultiExps(PippengerConfig),
+    /// `num` encryptions to `size` receivers each
+    ElGamalEncryptions { shared_randomness: bool },
+    ElGamalDecryptions,
+    /// `num` systematic Reed–Solomon encodings of `data_shares` scalars into `size` symbols, or
+    /// decodings of them from `data_shares` symbols
+    ReedSolomonEncodings { data_shares: usize },
+    ReedSolomonDecodings { data_shares: usize },
+    /// `num` Merkle trees over the encodings of `size` `leaf` elements, or verifications of the
+    /// proofs of `num` leaves of one
+    MerkleTrees { hash: MerkleHash, arity: usize, leaf: Element },
+    MerkleProofVerifications { hash: MerkleHash, arity: usize, leaf: Element },
+    /// `num` dealings to `size` receivers, with proof verification and one receiver's decryption
+    ChunkedElGamal { chunk_bits: usize, chunks: usize, proof_repetitions: usize },
+    DleqProofs,
+    /// the same verifications with exps and with multi-exps
+    DleqVerifications,
+    /// `num` batches of `size` proofs
+    DleqBatchVerifications,
+    /// proofs of knowledge of secret keys, verified one by one or `size` at a time
+    G1SchnorrProofs,
+    G2SchnorrProofs,
+    G1SchnorrVerifications,
+    G2SchnorrVerifications,
+    G1SchnorrBatchVerifications,
+    G2SchnorrBatchVerifications,
+    /// `num` verifications of `size` proofs, batched or one by one, `invalid_percent` percent of
+    /// which are invalid
+    SigmaVerifications { protocol: Sigma, batched: bool, invalid_percent: usize },
+    /// `num` commitments to polynomials of degree `size`
+    FeldmanCommitments { window_bits: usize },
+    G2FeldmanCommitments { window_bits: usize },
+    /// `num` shares checked against a polynomial of degree `size`
+    FeldmanShareChecks,
+    G2FeldmanShareChecks,
+    /// `num` commitments to, or opening checks of, vectors of `size` scalars
+    PedersenCommitments,
+    PedersenOpenings,
+    /// `num` opening proofs of polynomials of degree `size`, at one point or at `points`
+    KzgOpenings,
+    KzgMultiOpenings { points: usize },
+    /// `num` batch verifications of `size` openings of polynomials of degree `degree`
+    KzgBatchVerifications { degree: usize },
+    /// `num` powers-of-tau setups of degree `size`, generated or checked
+    SrsGenerations,
+    SrsVerifications { batched: bool },
+}
+
+impl OpKind {
+    /// All kinds that take no parameters beyond a count and a size.
+    pub const UNPARAMETERIZED: &'static [OpKind] = &[
+        OpKind::G1Exps, OpKind::G2Exps, OpKind::GtExps,
+        OpKind::G1MultiExps, OpKind::G2MultiExps, OpKind::GtMultiExps,
+        OpKind::Pairings, OpKind::MultiPairings, OpKind::MultiPairingsPrepared, OpKind::G2Preparations,
+        OpKind::MillerLoops, OpKind::FinalExponentiations, OpKind::PairingProductsWithExponents,
+        OpKind::ScalarMuls, OpKind::ScalarAdds, OpKind::ScalarInversions, OpKind::ScalarBatchInversions, OpKind::ScalarBatchOps,
+        OpKind::LagrangeInterpolation, OpKind::LagrangeInterpolationG2, OpKind::Fft,
+        OpKind::G1SubgroupChecks, OpKind::G2SubgroupChecks, OpKind::G1HashToCurve, OpKind::G2HashToCurve,
+        OpKind::G1HashToCurveSteps, OpKind::G2HashToCurveSteps, OpKind::SerializeGt, OpKind::DeserializeGt, OpKind::G1Decompressions, OpKind::G2Decompressions,
+        OpKind::G1Additions, OpKind::G2Additions, OpKind::G1MixedAdditions, OpKind::G2MixedAdditions,
+        OpKind::G1Doublings, OpKind::G2Doublings, OpKind::G1AffineConversions, OpKind::G2AffineConversions,
+        OpKind::G1BatchNormalizations, OpKind::G2BatchNormalizations, OpKind::G1Accumulations, OpKind::G2Accumulations,
+        OpKind::G1EqualityChecks, OpKind::G2EqualityChecks, OpKind::G1AffineEqualityChecks, OpKind::G2AffineEqualityChecks,
+        OpKind::G1IdentityChecks, OpKind::G2IdentityChecks, OpKind::G1AffineIdentityChecks, OpKind::G2AffineIdentityChecks,
+        OpKind::GtMuls, OpKind::GtInversions, OpKind::GtEqualityChecks, OpKind::GtMembershipChecks,
+        OpKind::G1ExpMethods, OpKind::G2ExpMethods, OpKind::G1GlvExps, OpKind::ElGamalDecryptions,
+        OpKind::DleqProofs, OpKind::DleqVerifications, OpKind::DleqBatchVerifications,
+        OpKind::G1SchnorrProofs, OpKind::G2SchnorrProofs, OpKind::G1SchnorrVerifications, OpKind::G2SchnorrVerifications,
+        OpKind::G1SchnorrBatchVerifications, OpKind::G2SchnorrBatchVerifications,
+        OpKind::FeldmanShareChecks, OpKind::G2FeldmanShareChecks, OpKind::PedersenCommitments, OpKind::PedersenOpenings,
+        OpKind::KzgOpenings, OpKind::SrsGenerations,
+    ];
+
+    /// Looks up one of the `UNPARAMETERIZED` kinds by name.
+    pub fn from_name(name: &str) -> Option<OpKind> {
+        Self::UNPARAMETERIZED.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// The name of the corresponding builder method, which is also how workload files refer to
+    /// the kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OpKind::G1Exps => "g1_exps",
+            OpKind::G2Exps => "g2_exps",
+            OpKind::GtExps => "gt_exps",
+            OpKind::G1MultiExps => "g1_multi_exps",
+            OpKind::G2MultiExps => "g2_multi_exps",
+            OpKind::GtMultiExps => "gt_multi_exps",
+            OpKind::Pairings => "pairings",
+            OpKind::PairingsConverting { .. } => "pairings_converting",
+            OpKind::MultiPairings => "multi_pairings",
+            OpKind::MultiPairingsConverting { .. } => "multi_pairings_converting",
+            OpKind::MultiPairingsPrepared => "multi_pairings_prepared",
+            OpKind::MultiPairingsStatic { .. } => "multi_pairings_static",
+            OpKind::G2Preparations => "g2_preparations",
+            OpKind::MillerLoops => "miller_loops",
+            OpKind::FinalExponentiations => "final_exponentiations",
+            OpKind::PairingEqualityChecks { .. } => "pairing_equality_checks",
+            OpKind::PairingProductsWithExponents => "pairing_products_with_exponents",
+            OpKind::ScalarMuls => "scalar_muls",
+            OpKind::ScalarAdds => "scalar_adds",
+            OpKind::ScalarInversions => "scalar_inversions",
+            OpKind::ScalarBatchInversions => "scalar_batch_inversions",
+            OpKind::ScalarBatchOps => "scalar_batch_ops",
+            OpKind::PolyEvals { .. } => "poly_evals",
+            OpKind::LagrangeInterpolation => "lagrange_interpolation",
+            OpKind::LagrangeInterpolationG2 => "lagrange_interpolation_g2",
+            OpKind::Fft => "fft",
+            OpKind::G1SubgroupChecks => "g1_subgroup_checks",
+            OpKind::G2SubgroupChecks => "g2_subgroup_checks",
+            OpKind::G1HashToCurve => "g1_hash_to_curve",
+            OpKind::G2HashToCurve => "g2_hash_to_curve",
+            OpKind::G1HashToCurveSteps => "g1_hash_to_curve_steps",
+            OpKind::G2HashToCurveSteps => "g2_hash_to_curve_steps",
+            OpKind::SerializeG1 { .. } => "serialize_g1",
+            OpKind::SerializeG2 { .. } => "serialize_g2",
+            OpKind::SerializeGt => "serialize_gt",
+            OpKind::DeserializeG1 { .. } => "deserialize_g1",
+            OpKind::DeserializeG2 { .. } => "deserialize_g2",
+            OpKind::DeserializeGt => "deserialize_gt",
+            OpKind::G1Decompressions => "g1_decompressions",
+            OpKind::G2Decompressions => "g2_decompressions",
+            OpKind::G1Additions => "g1_additions",
+            OpKind::G2Additions => "g2_additions",
+            OpKind::G1MixedAdditions => "g1_mixed_additions",
+            OpKind::G2MixedAdditions => "g2_mixed_additions",
+            OpKind::G1Doublings => "g1_doublings",
+            OpKind::G2Doublings => "g2_doublings",
+            OpKind::G1AffineConversions => "g1_affine_conversions",
+            OpKind::G2AffineConversions => "g2_affine_conversions",
+            OpKind::G1BatchNormalizations => "g1_batch_normalizations",
+            OpKind::G2BatchNormalizations => "g2_batch_normalizations",
+            OpKind::G1Accumulations => "g1_accumulations",
+            OpKind::G2Accumulations => "g2_accumulations",
+            OpKind::G1EqualityChecks => "g1_equality_checks",
+            OpKind::G2EqualityChecks => "g2_equality_checks",
+            OpKind::G1AffineEqualityChecks => "g1_affine_equality_checks",
+            OpKind::G2AffineEqualityChecks => "g2_affine_equality_checks",
+            OpKind::G1IdentityChecks => "g1_identity_checks",
+            OpKind::G2IdentityChecks => "g2_identity_checks",
+            OpKind::G1AffineIdentityChecks => "g1_affine_identity_checks",
+            OpKind::G2AffineIdentityChecks => "g2_affine_identity_checks",
+            OpKind::GtMuls => "gt_muls",
+            OpKind::GtInversions => "gt_inversions",
+            OpKind::GtEqualityChecks => "gt_equality_checks",
+            OpKind::GtMembershipChecks => "gt_membership_checks",
+            OpKind::FixedBaseExps { .. } => "fixed_base_exps",
+            OpKind::G2FixedBaseExps { .. } => "g2_fixed_base_exps",
+            OpKind::G1ExpMethods => "g1_exp_methods",
+            OpKind::G2ExpMethods => "g2_exp_methods",
+            OpKind::G1GlvExps => "g1_glv_exps",
+            OpKind::G1ShortExps { .. } => "g1_short_exps",
+            OpKind::G2ShortExps { .. } => "g2_short_exps",
+            OpKind::G1ShortMultiExps { .. } => "g1_short_multi_exps",
+            OpKind::G2ShortMultiExps { .. } => "g2_short_multi_exps",
+            OpKind::G1SparseMultiExps { .. } => "g1_sparse_multi_exps",
+            OpKind::G2SparseMultiExps { .. } => "g2_sparse_multi_exps",
+            OpKind::G1StreamingMultiExps { .. } => "g1_streaming_multi_exps",
+            OpKind::G2StreamingMultiExps { .. } => "g2_streaming_multi_exps",
+            OpKind::G1PippengerMultiExps(_) => "g1_pippenger_multi_exps",
+            OpKind::G2PippengerMultiExps(_) => "g2_pippenger_multi_exps",
+            OpKind::ElGamalEncryptions { .. } => "elgamal_encryptions",
+            OpKind::ElGamalDecryptions => "elgamal_decryptions",
+            OpKind::ChunkedElGamal { .. } => "chunked_elgamal",
+            OpKind::ReedSolomonEncodings { .. } => "reed_solomon_encodings",
+            OpKind::ReedSolomonDecodings { .. } => "reed_solomon_decodings",
+            OpKind::MerkleTrees { .. } => "merkle_trees",
+            OpKind::MerkleProofVerifications { .. } => "merkle_proof_verifications",
+            OpKind::DleqProofs => "dleq_prove",
+            OpKind::DleqVerifications => "dleq_verify",
+            OpKind::DleqBatchVerifications => "dleq_batch_verify",
+            OpKind::G1SchnorrProofs => "g1_schnorr_prove",
+            OpKind::G2SchnorrProofs => "g2_schnorr_prove",
+            OpKind::G1SchnorrVerifications => "g1_schnorr_verify",
+            OpKind::G2SchnorrVerifications => "g2_schnorr_verify",
+            OpKind::G1SchnorrBatchVerifications => "g1_schnorr_batch_verify",
+            OpKind::G2SchnorrBatchVerifications => "g2_schnorr_batch_verify",
+            OpKind::SigmaVerifications { .. } => "sigma_verifications",
+            OpKind::FeldmanCommitments { .. } => "feldman_commitments",
+            OpKind::G2FeldmanCommitments { .. } => "g2_feldman_commitments",
+            OpKind::FeldmanShareChecks => "feldman_share_checks",
+            OpKind::G2FeldmanShareChecks => "g2_feldman_share_checks",
+            OpKind::PedersenCommitments => "pedersen_commitments",
+            OpKind::PedersenOpenings => "pedersen_openings",
+            OpKind::KzgOpenings => "kzg_openings",
+            OpKind::KzgMultiOpenings { .. } => "kzg_multi_openings",
+            OpKind::KzgBatchVerifications { .. } => "kzg_batch_verifications",
+            OpKind::SrsGenerations => "srs_generations",
+            OpKind::SrsVerifications { .. } => "srs_verifications",
+        }
+    }
+
+    /// Whether ops of the kind can have sizes drawn from a `SizeDistribution` (see `Op::varying`).
+    pub fn takes_size_distribution(&self) -> bool {
+        matches!(self, OpKind::G1MultiExps | OpKind::G2MultiExps | OpKind::GtMultiExps | OpKind::MultiPairings | OpKind::MultiPairingsConverting { .. })
+    }
+
+    /// Whether the kind is made of operations of varying size (multi-exps, multi-pairings, ...).
+    /// For the other kinds, only `Op::num` matters.
+    pub fn is_sized(&self) -> bool {
+        matches!(self,
+            OpKind::G1MultiExps | OpKind::G2MultiExps | OpKind::GtMultiExps
+            | OpKind::MultiPairings | OpKind::MultiPairingsConverting { .. } | OpKind::MultiPairingsPrepared | OpKind::MultiPairingsStatic { .. } | OpKind::MillerLoops
+            | OpKind::PairingEqualityChecks { .. } | OpKind::PairingProductsWithExponents
+            | OpKind::ScalarBatchInversions | OpKind::ScalarBatchOps | OpKind::PolyEvals { .. }
+            | OpKind::LagrangeInterpolation | OpKind::LagrangeInterpolationG2 | OpKind::Fft
+            | OpKind::G1BatchNormalizations | OpKind::G2BatchNormalizations | OpKind::G1HashToCurve | OpKind::G2HashToCurve
+            | OpKind::G1Accumulations | OpKind::G2Accumulations
+            | OpKind::G1AffineEqualityChecks | OpKind::G2AffineEqualityChecks | OpKind::G1AffineIdentityChecks | OpKind::G2AffineIdentityChecks
+            | OpKind::G1ShortMultiExps { .. } | OpKind::G2ShortMultiExps { .. } | OpKind::G1SparseMultiExps { .. } | OpKind::G2SparseMultiExps { .. }
+            | OpKind::G1StreamingMultiExps { .. } | OpKind::G2StreamingMultiExps { .. }
+            | OpKind::G1PippengerMultiExps(_) | OpKind::G2PippengerMultiExps(_) | OpKind::ElGamalEncryptions { .. } | OpKind::ChunkedElGamal { .. } | OpKind::DleqBatchVerifications
+            | OpKind::ReedSolomonEncodings { .. } | OpKind::ReedSolomonDecodings { .. } | OpKind::MerkleTrees { .. } | OpKind::MerkleProofVerifications { .. }
+            | OpKind::G1SchnorrBatchVerifications | OpKind::G2SchnorrBatchVerifications
+            | OpKind::SigmaVerifications { .. } | OpKind::FeldmanCommitments { .. } | OpKind::G2FeldmanCommitments { .. }
+            | OpKind::FeldmanShareChecks | OpKind::G2FeldmanShareChecks | OpKind::PedersenCommitments | OpKind::PedersenOpenings
+            | OpKind::KzgOpenings | OpKind::KzgMultiOpenings { .. } | OpKind::KzgBatchVerifications { .. }
+            | OpKind::SrsGenerations | OpKind::SrsVerifications { .. })
+    }
+}
+
+/// `num` operations of one kind, each of the given size (1 for unsized kinds), or of sizes drawn
+/// from `sizes` for multi-exps and multi-pairings (`OpKind::takes_size_distribution`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Op {
+    pub kind: OpKind,
+    pub num: usize,
+    /// the largest size if `sizes` is set, which estimates from the size then overstate
+    pub size: usize,
+    pub sizes: Option<SizeDistribution>,
+    pub label: Option<String>,
+    pub phase: Option<String>,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let OpKind::PairingEqualityChecks { rhs_size } = self.kind {
+            write!(f, "{}: {} of {} = {} pairings", self.kind.name(), self.num, self.size, rhs_size)
+        } else if self.kind == (OpKind::ElGamalEncryptions { shared_randomness: true }) {
+            write!(f, "{}: {} of size {}, shared randomness", self.kind.name(), self.num, self.size)
+        } else if let OpKind::ChunkedElGamal { chunk_bits, chunks, proof_repetitions } = self.kind {
+            write!(f, "{}: {} to {} receivers, {} chunks of {} bits, {} proof repetitions", self.kind.name(), self.num, self.size, chunks, chunk_bits, proof_repetitions)
+        } else if let OpKind::ReedSolomonEncodings { data_shares } | OpKind::ReedSolomonDecodings { data_shares } = self.kind {
+            write!(f, "{}: {} of {} symbols, {} of them data", self.kind.name(), self.num, self.size, data_shares)
+        } else if let OpKind::MerkleTrees { hash, arity, leaf } | OpKind::MerkleProofVerifications { hash, arity, leaf } = self.kind {
+            write!(f, "{}: {} of {} {} leaves, {} of arity {}", self.kind.name(), self.num, self.size, leaf.name(), hash, arity)
+        } else if let OpKind::SigmaVerifications { protocol, batched, invalid_percent } = self.kind {
+            let method = if batched { "batched" } else { "one by one" };
+            write!(f, "{}: {} of {} {} proofs, {}", self.kind.name(), self.num, self.size, protocol, method)?;
+            match invalid_percent {
+                0 => Ok(()),
+                percent => write!(f, ", {}% invalid", percent),
+            }
+        } else if let OpKind::PairingsConverting { to_affine } | OpKind::MultiPairingsConverting { to_affine } = self.kind {
+            let conversion = if to_affine { "converted to affine" } else { "affine already" };
+            match self.kind.is_sized() {
+                true => write!(f, "{}: {} of {}, inputs {}", self.kind.name(), self.num, self.size_text(), conversion),
+                false => write!(f, "{}: {}, inputs {}", self.kind.name(), self.num, conversion),
+            }
+        } else if self.kind.is_sized() {
+            write!(f, "{}: {} of {}", self.kind.name(), self.num, self.size_text())
+        } else {
+            write!(f, "{}: {}", self.kind.name(), self.num)
+        }
+    }
+}
+
+impl Op {
+    pub fn new(kind: OpKind, num: usize, size: usize) -> Self {
+        Self { kind, num, size, sizes: None, label: None, phase: None }
+    }
+
+    /// `num` ops of `kind` with sizes drawn from `sizes`, for kinds that take a size distribution.
+    pub fn varying(kind: OpKind, num: usize, sizes: SizeDistribution) -> Self {
+        Self { size: sizes.max(), sizes: Some(sizes), ..Self::new(kind, num, 1) }
+    }
+
+    // "size 64" or "sizes zipf:1024:1.1"
+    fn size_text(&self) -> String {
+        match &self.sizes {
+            Some(sizes) => format!("sizes {}", sizes),
+            None => format!("size {}", self.size),
+        }
+    }
+
+    /// The size of each of the `num` ops: `size`, or drawn from `sizes`.
+    pub fn draw_sizes(&self, rng: &mut (impl RngCore + ?Sized)) -> Vec<usize> {
+        match &self.sizes {
+            Some(sizes) => sizes.sample(self.num, rng),
+            None => vec![self.size; self.num],
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Checks what the builder methods assume of the op, which they assert or don't check at
+    /// all: a size of at least 1 for sized kinds and of exactly 1 for the others, the kind's
+    /// parameters in their ranges, and inputs whose size fits a `usize`.
+    pub fn check(&self) -> Result<(), SimulationError> {
+        let invalid = |message: String| Err(SimulationError::InvalidSize { op: self.to_string(), message });
+        let in_range = |name: &str, value: usize, max: usize| match value {
+            1.. if value <= max => Ok(()),
+            _ => invalid(format!("`{}` must be between 1 and {}, not {}", name, max, value)),
+        };
+        match &self.sizes {
+            Some(_) if !self.kind.takes_size_distribution() => return invalid(format!("{} ops take no size distribution", self.kind.name())),
+            Some(sizes) if sizes.max() != self.size => return invalid(format!("the size must be the largest of sizes {}", sizes)),
+            _ => {}
+        }
+        match (self.kind.is_sized(), self.size) {
+            (true, 0) => return invalid("the size must be at least 1".to_string()),
+            (false, size) if size != 1 => return invalid(format!("{} ops take no size, but have size {}", self.kind.name(), size)),
+            _ => {}
+        }
+        match self.kind {
+            OpKind::Fft if !self.size.is_power_of_two() || self.size.trailing_zeros() > Scalar::S =>
+                return invalid(format!("FFT sizes must be powers of two up to 2^{}", Scalar::S)),
+            OpKind::KzgMultiOpenings { points } => in_range("points", points, self.size)?,
+            OpKind::ChunkedElGamal { chunk_bits, .. } => in_range("chunk_bits", chunk_bits, MAX_CHUNK_BITS)?,
+            OpKind::ReedSolomonEncodings { data_shares } | OpKind::ReedSolomonDecodings { data_shares } => in_range("data_shares", data_shares, self.size)?,
+            OpKind::MerkleTrees { arity, .. } | OpKind::MerkleProofVerifications { arity, .. } if arity < 2 =>
+                return invalid(format!("`arity` must be at least 2, not {}", arity)),
+            OpKind::FixedBaseExps { window_bits } | OpKind::G2FixedBaseExps { window_bits }
+            | OpKind::FeldmanCommitments { window_bits } | OpKind::G2FeldmanCommitments { window_bits } => in_range("window_bits", window_bits, 16)?,
+            OpKind::G1PippengerMultiExps(config) | OpKind::G2PippengerMultiExps(config) => in_range("window_bits", config.window_bits, MAX_WINDOW_BITS)?,
+            OpKind::G1ShortExps { bits } | OpKind::G2ShortExps { bits } | OpKind::G1ShortMultiExps { bits } | OpKind::G2ShortMultiExps { bits } =>
+                in_range("bits", bits, 254)?,
+            OpKind::G1SparseMultiExps { binary_percent } | OpKind::G2SparseMultiExps { binary_percent } if binary_percent > 100 =>
+                return invalid(format!("`binary_percent` must be at most 100, not {}", binary_percent)),
+            OpKind::G1StreamingMultiExps { chunk_size } | OpKind::G2StreamingMultiExps { chunk_size } => in_range("chunk_size", chunk_size, self.size)?,
+            OpKind::MultiPairingsStatic { static_inputs } if static_inputs > self.size =>
+                return invalid(format!("`static_inputs` must be at most the size, not {}", static_inputs)),
+            OpKind::SigmaVerifications { invalid_percent, .. } if invalid_percent > 100 =>
+                return invalid(format!("`invalid_percent` must be at most 100, not {}", invalid_percent)),
+            _ => {}
+        }
+        match self.checked_input_bytes() {
+            Some(_) => Ok(()),
+            None => Err(SimulationError::Overflow(format!("the inputs of {} take more than {} bytes", self, usize::MAX))),
+        }
+    }
+
+    /// Estimated size of the inputs of all `num` operations, in the encodings of `Element`.
+    /// Precomputed tables (prepared G2 points, fixed-base windows) are not counted.
+    /// Saturates at `usize::MAX`; see `checked_input_bytes`.
+    pub fn input_bytes(&self) -> usize {
+        self.checked_input_bytes().unwrap_or(usize::MAX)
+    }
+
+    /// `input_bytes`, or `None` if they don't fit a `usize`.
+    pub fn checked_input_bytes(&self) -> Option<usize> {
+        // in u128, where sizes and counts of up to 2^64 can't overflow
+        let (g1, g2, gt, scalar) = (Element::G1.size() as u128, Element::G2.size() as u128, Element::Gt.size() as u128, Element::Scalar.size() as u128);
+        let size = self.size as u128;
+        let per_op = match self.kind {
+            OpKind::G1Exps | OpKind::FixedBaseExps { .. } | OpKind::G1ExpMethods | OpKind::G1GlvExps | OpKind::G1ShortExps { .. } => g1 + scalar,
+            OpKind::G2Exps | OpKind::G2FixedBaseExps { .. } | OpKind::G2ExpMethods | OpKind::G2ShortExps { .. } => g2 + scalar,
+            OpKind::GtExps => gt + scalar,
+            OpKind::G1MultiExps | OpKind::G1ShortMultiExps { .. } | OpKind::G1SparseMultiExps { .. } | OpKind::G1PippengerMultiExps(_) | OpKind::LagrangeInterpolation => size * (g1 + scalar),
+            // uncompressed bases
+            OpKind::G1StreamingMultiExps { .. } => size * (2 * g1 + scalar),
+            OpKind::G2StreamingMultiExps { .. } => size * (2 * g2 + scalar),
+            OpKind::G2MultiExps | OpKind::G2ShortMultiExps { .. } | OpKind::G2SparseMultiExps { .. } | OpKind::G2PippengerMultiExps(_) | OpKind::LagrangeInterpolationG2 => size * (g2 + scalar),
+            OpKind::GtMultiExps => size * (gt + scalar),
+            OpKind::Pairings | OpKind::PairingsConverting { .. } => g1 + g2,
+            OpKind::MultiPairings | OpKind::MultiPairingsConverting { .. } | OpKind::MultiPairingsPrepared | OpKind::MultiPairingsStatic { .. } | OpKind::MillerLoops => size * (g1 + g2),
+            OpKind::FinalExponentiations => gt,
+            OpKind::PairingEqualityChecks { rhs_size } => (size + rhs_size as u128) * (g1 + g2),
+            OpKind::PairingProductsWithExponents => size * (g1 + g2 + scalar),
+            OpKind::ScalarMuls | OpKind::ScalarAdds => 2 * scalar,
+            OpKind::ScalarInversions => scalar,
+            OpKind::ScalarBatchInversions | OpKind::Fft => size * scalar,
+            // the two vectors of the inner product
+            OpKind::ScalarBatchOps => 2 * size * scalar,
+            // the coefficients and the points
+            OpKind::PolyEvals { num_points } => (size + 1 + num_points as u128) * scalar,
+            OpKind::G1SubgroupChecks | OpKind::G1AffineConversions | OpKind::G1Doublings | OpKind::SerializeG1 { .. } | OpKind::G1Decompressions => g1,
+            OpKind::G2SubgroupChecks | OpKind::G2AffineConversions | OpKind::G2Doublings | OpKind::SerializeG2 { .. } | OpKind::G2Decompressions
+            | OpKind::G2Preparations => g2,
+            OpKind::SerializeGt | OpKind::DeserializeGt => gt,
+            // 32-byte messages
+            OpKind::G1HashToCurve | OpKind::G2HashToCurve => size,
+            // the two base-field elements of every step
+            OpKind::G1HashToCurveSteps => 2 * 48,
+            OpKind::G2HashToCurveSteps => 2 * 96,
+            OpKind::DeserializeG1 { compressed } => if compressed { g1 } else { 2 * g1 },
+            OpKind::DeserializeG2 { compressed } => if compressed { g2 } else { 2 * g2 },
+            OpKind::G1Additions | OpKind::G1MixedAdditions => 2 * g1,
+            OpKind::G2Additions | OpKind::G2MixedAdditions => 2 * g2,
+            OpKind::G1BatchNormalizations | OpKind::G1AffineIdentityChecks | OpKind::G1Accumulations => size * g1,
+            OpKind::G2BatchNormalizations | OpKind::G2AffineIdentityChecks | OpKind::G2Accumulations => size * g2,
+            OpKind::G1EqualityChecks => 2 * g1,
+            OpKind::G2EqualityChecks => 2 * g2,
+            OpKind::G1AffineEqualityChecks => 2 * size * g1,
+            OpKind::G2AffineEqualityChecks => 2 * size * g2,
+            OpKind::G1IdentityChecks => g1,
+            OpKind::G2IdentityChecks => g2,
+            OpKind::GtMuls | OpKind::GtEqualityChecks => 2 * gt,
+            OpKind::GtInversions | OpKind::GtMembershipChecks => gt,
+            // keys, messages and randomness
+            OpKind::ElGamalEncryptions { .. } => size * (2 * g1 + scalar),
+            OpKind::ElGamalDecryptions => 2 * g1 + scalar,
+            // keys and each receiver's chunks
+            OpKind::ChunkedElGamal { chunks, .. } => size * (g1 + chunks as u128 * scalar),
+            // the payload, or the symbols that arrived
+            OpKind::ReedSolomonEncodings { data_shares } | OpKind::ReedSolomonDecodings { data_shares } => data_shares as u128 * scalar,
+            OpKind::MerkleTrees { leaf, .. } => size * leaf.size() as u128,
+            // the leaf and the siblings on its path
+            OpKind::MerkleProofVerifications { arity, leaf, .. } => {
+                let depth = std::iter::successors(Some(1u128), |span| Some(span * arity as u128)).take_while(|&span| span < size).count() as u128;
+                leaf.size() as u128 + depth * (arity as u128 - 1) * 32
+            }
+            // the statement and the witness or the proof
+            OpKind::DleqProofs | OpKind::DleqVerifications => 2 * g1 + 2 * scalar,
+            OpKind::DleqBatchVerifications => size * (4 * g1 + scalar),
+            // the key and the secret key or the proof
+            OpKind::G1SchnorrProofs => g1 + scalar,
+            OpKind::G2SchnorrProofs => g2 + scalar,
+            OpKind::G1SchnorrVerifications => g1 + 2 * scalar,
+            OpKind::G2SchnorrVerifications => g2 + 2 * scalar,
+            OpKind::G1SchnorrBatchVerifications => size * (2 * g1 + scalar),
+            OpKind::G2SchnorrBatchVerifications => size * (2 * g2 + scalar),
+            // statements and proofs; batchable proofs carry commitments instead of challenges
+            OpKind::SigmaVerifications { protocol: Sigma::Schnorr, batched, .. } => size * (2 * g1 + if batched { scalar } else { 2 * scalar }),
+            OpKind::SigmaVerifications { protocol: Sigma::Dleq, batched, .. } => size * if batched { 4 * g1 + scalar } else { 2 * g1 + 2 * scalar },
+            // the coefficients, or the commitments and the share in the exponent
+            OpKind::FeldmanCommitments { .. } | OpKind::G2FeldmanCommitments { .. } => (size + 1) * scalar,
+            OpKind::FeldmanShareChecks => (size + 2) * g1,
+            OpKind::G2FeldmanShareChecks => (size + 2) * g2,
+            OpKind::PedersenCommitments => (size + 1) * scalar,
+            OpKind::PedersenOpenings => g1 + (size + 1) * scalar,
+            // the coefficients and the points
+            OpKind::KzgOpenings => (size + 2) * scalar,
+            OpKind::KzgMultiOpenings { points } => (size + 1 + points as u128) * scalar,
+            // commitments, proofs, points and values
+            OpKind::KzgBatchVerifications { .. } => size * (2 * g1 + 2 * scalar),
+            // τ, or the powers, g2^τ and the weights
+            OpKind::SrsGenerations => scalar,
+            OpKind::SrsVerifications { .. } => (size + 1) * g1 + g2 + size * scalar,
+        };
+        usize::try_from((self.num as u128).checked_mul(per_op)?).ok()
+    }
+}
+
+/// A protocol's operations as plain data, independent of any concrete inputs. Can be built in
+/// code, loaded from a workload file, or produced by a preset, and is turned into items with
+/// `GroupOpsSimulation::workload`, or into a whole simulation with `Workload::simulation`.
+///
+/// Workloads compose like the op counts they are: `a + b` has the ops of both, an op of `b`
+/// equal to one of `a` but for its count (same kind, size, label and phase) adding to that one,
+/// and `k * a` has every count of `a` times `k`. A DKG party's work, say, is
+/// `dealing + (n - 1) * verification + key_derivation`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Workload {
+    pub ops: Vec<Op>,
+}
+
+impl Workload {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn op(mut self, kind: OpKind, num: usize, size: usize) -> Self {
+        self.ops.push(Op::new(kind, num, size));
+        self
+    }
+
+    /// Appends all ops of `other`.
+    pub fn extend(mut self, other: Workload) -> Self {
+        self.ops.extend(other.ops);
+        self
+    }
+
+    /// A simulation of the ops, on inputs drawn from `seed` (`GroupOpsSimulation::with_seed`),
+    /// checked as `GroupOpsSimulation::try_workload` checks them.
+    pub fn simulation(&self, seed: u64) -> Result<GroupOpsSimulation<ChaCha20Rng>, SimulationError> {
+        let mut sim = GroupOpsSimulation::with_seed(seed);
+        sim.try_workload(self)?;
+        Ok(sim)
+    }
+
+    /// Puts every op into the phase `name`, nested inside any phase it already has.
+    pub fn in_phase(mut self, name: &str) -> Self {
+        for op in &mut self.ops {
+            op.phase = Some(match &op.phase {
+                Some(inner) => format!("{} / {}", name, inner),
+                None => name.to_string(),
+            });
+        }
+        self
+    }
+}
+
+impl Add for Workload {
+    type Output = Workload;
+
+    fn add(mut self, other: Workload) -> Workload {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Workload {
+    fn add_assign(&mut self, other: Workload) {
+        for op in other.ops {
+            let same = |mine: &&mut Op| mine.kind == op.kind && mine.size == op.size && mine.sizes == op.sizes && mine.label == op.label && mine.phase == op.phase;
+            match self.ops.iter_mut().find(same) {
+                Some(mine) => mine.num = mine.num.checked_add(op.num).expect("op count overflows"),
+                None => self.ops.push(op),
+            }
+        }
+    }
+}
+
+impl Sum for Workload {
+    fn sum<I: Iterator<Item = Workload>>(workloads: I) -> Workload {
+        workloads.fold(Workload::new(), Add::add)
+    }
+}
+
+impl Mul<Workload> for usize {
+    type Output = Workload;
+
+    fn mul(self, mut workload: Workload) -> Workload {
+        for op in &mut workload.ops {
+            op.num = op.num.checked_mul(self).expect("op count overflows");
+        }
+        workload
+    }
+}
+
+impl Mul<usize> for Workload {
+    type Output = Workload;
+
+    fn mul(self, k: usize) -> Workload {
+        k * self
+    }
+}
+
+/// The ops phase by phase, with the estimated size of their inputs, as a dry run prints them.
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "operations:")?;
+        write_by_phase(f, &self.ops,
+            |op| op.phase.as_deref(),
+            |f, indent, op| writeln!(f, "{}{}, {} of inputs", indent, item_name(&op.to_string(), op.label.as_deref()), format_bytes(op.input_bytes())),
+            |f, group| writeln!(f, "    phase total: {} ops, {} of inputs",
+                group.iter().map(|op| op.num).sum::<usize>(), format_bytes(group.iter().map(Op::input_bytes).sum()))
+        )?;
+        writeln!(f, "  total: {} ops, {} of inputs", self.ops.iter().map(|op| op.num).sum::<usize>(), format_bytes(self.ops.iter().map(Op::input_bytes).sum()))
+    }
+}
+
+
+impl<R> GroupOpsSimulation<R>
+where R : RngCore
+{
+    /// Adds one item (or item group) per op of `workload`.
+    pub fn workload(&mut self, workload: &Workload) -> &mut Self {
+        for op in &workload.ops {
+            match &op.phase {
+                Some(phase) => self.phase(phase, |sim| { sim.op(op); }),
+                None => self.op(op),
+            };
+        }
+        self
+    }
+
+    /// Like `workload`, but checks every op first (`Op::check`) and that the inputs of all of
+    /// them together stay within the `memory_limit`, adding nothing if any check fails.
+    pub fn try_workload(&mut self, workload: &Workload) -> Result<&mut Self, SimulationError> {
+        let mut estimate = 0usize;
+        for op in &workload.ops {
+            op.check()?;
+            estimate = estimate.checked_add(op.input_bytes())
+                .ok_or_else(|| SimulationError::Overflow(format!("the inputs of the workload take more than {} bytes", usize::MAX)))?;
+        }
+        self.check_memory(estimate)?;
+        Ok(self.workload(workload))
+    }
+
+    /// Adds the item (or item group) of one op, as `workload` does, if it passes `Op::check`
+    /// and its inputs stay within the `memory_limit`.
+    pub fn try_op(&mut self, op: &Op) -> Result<&mut Self, SimulationError> {
+        op.check()?;
+        self.check_memory(op.input_bytes())?;
+        Ok(self.workload(&Workload { ops: vec![op.clone()] }))
+    }
+
+    fn op(&mut self, op: &Op) -> &mut Self {
+        let (num, size) = (op.num, op.size);
+        if let Some(sizes) = &op.sizes {
+            return match op.kind {
+                OpKind::G1MultiExps => self.g1_multi_exps_varying(num, sizes),
+                OpKind::G2MultiExps => self.g2_multi_exps_varying(num, sizes),
+                OpKind::GtMultiExps => self.gt_multi_exps_varying(num, sizes),
+                OpKind::MultiPairings => self.multi_pairings_varying(num, sizes, true),
+                OpKind::MultiPairingsConverting { to_affine } => self.multi_pairings_varying(num, sizes, to_affine),
+                kind => panic!("{} ops take no size distribution", kind.name()),
+            };
+        }
+        match op.kind {
+            OpKind::G1Exps => self.g1_exps(num),
+            OpKind::G2Exps => self.g2_exps(num),
+            OpKind::GtExps => self.gt_exps(num),
+            OpKind::G1MultiExps => self.g1_multi_exps(num, size),
+            OpKind::G2MultiExps => self.g2_multi_exps(num, size),
+            OpKind::GtMultiExps => self.gt_multi_exps(num, size),
+            OpKind::Pairings => self.pairings(num),
+            OpKind::PairingsConverting { to_affine } => self.pairings_converting(num, to_affine),
+            OpKind::MultiPairings => self.multi_pairings(num, size),
+            OpKind::MultiPairingsConverting { to_affine } => self.multi_pairings_converting(num, size, to_affine),
+            OpKind::MultiPairingsPrepared => self.multi_pairings_prepared(num, size),
+            OpKind::MultiPairingsStatic { static_inputs } => self.multi_pairings_static(num, size, static_inputs),
+            OpKind::G2Preparations => self.g2_preparations(num),
+            OpKind::MillerLoops => self.miller_loops(num, size),
+            OpKind::FinalExponentiations => self.final_exponentiations(num),
+            OpKind::PairingEqualityChecks { rhs_size } => self.pairing_equality_checks(num, size, rhs_size),
+            OpKind::PairingProductsWithExponents => self.pairing_products_with_exponents(num, size),
+            OpKind::ScalarMuls => self.scalar_muls(num),
+            OpKind::ScalarAdds => self.scalar_adds(num),
+            OpKind::ScalarInversions => self.scalar_inversions(num),
+            OpKind::ScalarBatchInversions => self.scalar_batch_inversions(num, size),
+            OpKind::ScalarBatchOps => self.scalar_batch_ops(num, size),
+            OpKind::PolyEvals { num_points } => self.poly_evals(num, size, num_points),
+            OpKind::LagrangeInterpolation => self.lagrange_interpolation(num, size),
+            OpKind::LagrangeInterpolationG2 => self.lagrange_interpolation_g2(num, size),
+            OpKind::Fft => {
+                assert!(size.is_power_of_two(), "FFT size must be a power of two");
+                self.fft(num, size.trailing_zeros())
+            }
+            OpKind::G1SubgroupChecks => self.g1_subgroup_checks(num),
+            OpKind::G2SubgroupChecks => self.g2_subgroup_checks(num),
+            OpKind::G1HashToCurve => self.g1_hash_to_curve(num, size),
+            OpKind::G2HashToCurve => self.g2_hash_to_curve(num, size),
+            OpKind::G1HashToCurveSteps => self.g1_hash_to_curve_steps(num),
+            OpKind::G2HashToCurveSteps => self.g2_hash_to_curve_steps(num),
+            OpKind::SerializeG1 { compressed } => self.serialize_g1(num, compressed),
+            OpKind::SerializeG2 { compressed } => self.serialize_g2(num, compressed),
+            OpKind::SerializeGt => self.serialize_gt(num),
+            OpKind::DeserializeG1 { compressed } => self.deserialize_g1(num, compressed),
+            OpKind::DeserializeG2 { compressed } => self.deserialize_g2(num, compressed),
+            OpKind::DeserializeGt => self.deserialize_gt(num),
+            OpKind::G1Decompressions => self.g1_decompressions(num),
+            OpKind::G2Decompressions => self.g2_decompressions(num),
+            OpKind::G1Additions => self.g1_additions(num),
+            OpKind::G2Additions => self.g2_additions(num),
+            OpKind::G1MixedAdditions => self.g1_mixed_additions(num),
+            OpKind::G2MixedAdditions => self.g2_mixed_additions(num),
+            OpKind::G1Doublings => self.g1_doublings(num),
+            OpKind::G2Doublings => self.g2_doublings(num),
+            OpKind::G1AffineConversions => self.g1_affine_conversions(num),
+            OpKind::G2AffineConversions => self.g2_affine_conversions(num),
+            OpKind::G1BatchNormalizations => self.g1_batch_normalizations(num, size),
+            OpKind::G2BatchNormalizations => self.g2_batch_normalizations(num, size),
+            OpKind::G1Accumulations => self.g1_accumulations(num, size),
+            OpKind::G2Accumulations => self.g2_accumulations(num, size),
+            OpKind::G1EqualityChecks => self.g1_equality_checks(num),
+            OpKind::G2EqualityChecks => self.g2_equality_checks(num),
+            OpKind::G1AffineEqualityChecks => self.g1_affine_equality_checks(num, size),
+            OpKind::G2AffineEqualityChecks => self.g2_affine_equality_checks(num, size),
+            OpKind::G1IdentityChecks => self.g1_identity_checks(num),
+            OpKind::G2IdentityChecks => self.g2_identity_checks(num),
+            OpKind::G1AffineIdentityChecks => self.g1_affine_identity_checks(num, size),
+            OpKind::G2AffineIdentityChecks => self.g2_affine_identity_checks(num, size),
+            OpKind::GtMuls => self.gt_muls(num),
+            OpKind::GtInversions => self.gt_inversions(num),
+            OpKind::GtEqualityChecks => self.gt_equality_checks(num),
+            OpKind::GtMembershipChecks => self.gt_membership_checks(num),
+            OpKind::FixedBaseExps { window_bits } => self.fixed_base_exps(num, window_bits),
+            OpKind::G2FixedBaseExps { window_bits } => self.g2_fixed_base_exps(num, window_bits),
+            OpKind::G1ExpMethods => self.g1_exp_methods(num),
+            OpKind::G2ExpMethods => self.g2_exp_methods(num),
+            OpKind::G1GlvExps => self.g1_glv_exps(num),
+            OpKind::G1ShortExps { bits } => self.g1_short_exps(num, bits),
+            OpKind::G2ShortExps { bits } => self.g2_short_exps(num, bits),
+            OpKind::G1ShortMultiExps { bits } => self.g1_short_multi_exps(num, size, bits),
+            OpKind::G2ShortMultiExps { bits } => self.g2_short_multi_exps(num, size, bits),
+            OpKind::G1SparseMultiExps { binary_percent } => self.g1_sparse_multi_exps(num, size, binary_percent),
+            OpKind::G2SparseMultiExps { binary_percent } => self.g2_sparse_multi_exps(num, size, binary_percent),
+            OpKind::G1StreamingMultiExps { chunk_size } => self.g1_streaming_multi_exps(num, size, chunk_size),
+            OpKind::G2StreamingMultiExps { chunk_size } => self.g2_streaming_multi_exps(num, size, chunk_size),
+            OpKind::G1PippengerMultiExps(config) => self.g1_pippenger_multi_exps(num, size, config),
+            OpKind::G2PippengerMultiExps(config) => self.g2_pippenger_multi_exps(num, size, config),
+            OpKind::ElGamalEncryptions { shared_randomness } => self.elgamal_encryptions(num, size, shared_randomness),
+            OpKind::ElGamalDecryptions => self.elgamal_decryptions(num),
+            OpKind::ReedSolomonEncodings { data_shares } => self.reed_solomon_encodings(num, size, data_shares),
+            OpKind::ReedSolomonDecodings { data_shares } => self.reed_solomon_decodings(num, size, data_shares),
+            OpKind::MerkleTrees { hash, arity, leaf } => self.merkle_trees(hash, arity, leaf, num, size),
+            OpKind::MerkleProofVerifications { hash, arity, leaf } => self.merkle_proof_verifications(hash, arity, leaf, num, size),
+            OpKind::ChunkedElGamal { chunk_bits, chunks, proof_repetitions } => self.chunked_elgamal(num, size, chunk_bits, chunks, proof_repetitions),
+            OpKind::DleqProofs => self.dleq_prove(num),
+            OpKind::DleqVerifications => self.dleq_verify(num),
+            OpKind::DleqBatchVerifications => self.dleq_batch_verify(num, size),
+            OpKind::G1SchnorrProofs => self.g1_schnorr_prove(num),
+            OpKind::G2SchnorrProofs => self.g2_schnorr_prove(num),
+            OpKind::G1SchnorrVerifications => self.g1_schnorr_verify(num),
+            OpKind::G2SchnorrVerifications => self.g2_schnorr_verify(num),
+            OpKind::G1SchnorrBatchVerifications => self.g1_schnorr_batch_verify(num, size),
+            OpKind::G2SchnorrBatchVerifications => self.g2_schnorr_batch_verify(num, size),
+            OpKind::SigmaVerifications { protocol, batched, invalid_percent } => self.sigma_verifications_with_invalid(protocol, num, size, batched, invalid_percent),
+            OpKind::FeldmanCommitments { window_bits } => self.feldman_commitments(num, size, window_bits),
+            OpKind::G2FeldmanCommitments { window_bits } => self.g2_feldman_commitments(num, size, window_bits),
+            OpKind::FeldmanShareChecks => self.feldman_share_checks(num, size),
+            OpKind::G2FeldmanShareChecks => self.g2_feldman_share_checks(num, size),
+            OpKind::PedersenCommitments => self.pedersen_commitments(num, size),
+            OpKind::PedersenOpenings => self.pedersen_openings(num, size),
+            OpKind::KzgOpenings => self.kzg_openings(num, size),
+            OpKind::KzgMultiOpenings { points } => self.kzg_multi_openings(num, size, points),
+            OpKind::KzgBatchVerifications { degree } => self.kzg_batch_verifications(num, degree, size),
+            OpKind::SrsGenerations => self.srs_generations(num, size),
+            OpKind::SrsVerifications { batched } => self.srs_verifications(num, size, batched),
+        };
+        if let Some(label) = &op.label {
+            self.label(label);
+        }
+        self
+    }
+}
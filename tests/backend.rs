@@ -0,0 +1,307 @@
+//! Backends against blstrs: the same ops on the same inputs give the same points, pairings are
+//! bilinear on every backend, and a workload counts the same ops whichever backend runs it.
+
+use blstrs_pvss_simulation::backend::{describe, gap_table, simulate_on, Backend, Blst, Blstrs};
+#[cfg(any(feature = "arkworks", feature = "bn254"))]
+use blstrs_pvss_simulation::backend::{Ark, ArkCurve};
+#[cfg(feature = "arkworks")]
+use blstrs_pvss_simulation::backend::Arkworks;
+#[cfg(feature = "zkcrypto")]
+use blstrs_pvss_simulation::backend::Zkcrypto;
+#[cfg(feature = "bn254")]
+use blstrs_pvss_simulation::backend::{simulate_on_all, ArkBn254, Curve};
+#[cfg(feature = "gpu")]
+use blstrs_pvss_simulation::backend::Gpu;
+use blstrs_pvss_simulation::render::Cell;
+use blstrs_pvss_simulation::report::SimulationReport;
+use blstrs_pvss_simulation::workload::{OpKind, Workload};
+
+use blst::{
+    blst_fp12, blst_fp12_is_equal, blst_p1, blst_p1_generator, blst_p1_serialize, blst_p2, blst_p2_generator,
+    blst_p2_serialize, blst_scalar, blst_scalar_from_uint64,
+};
+use blstrs::{G1Affine, G1Projective, G2Affine, G2Projective, Gt, Scalar};
+use group::Group as _;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use std::time::Duration;
+
+
+/// What the tests need of a backend beyond `Backend`.
+trait Scalars: Backend {
+    fn scalar(x: u64) -> Self::Scalar;
+    fn gt_eq(a: &Self::Gt, b: &Self::Gt) -> bool;
+}
+
+/// For backends of BLS12-381, which can be compared with blstrs point by point.
+trait Encode: Scalars {
+    fn g1_generator() -> Self::G1;
+    fn g2_generator() -> Self::G2;
+    /// the uncompressed encoding, as blstrs' `to_uncompressed`
+    fn g1_bytes(p: &Self::G1) -> Vec<u8>;
+    fn g2_bytes(p: &Self::G2) -> Vec<u8>;
+}
+
+impl Scalars for Blstrs {
+    fn scalar(x: u64) -> Scalar {
+        Scalar::from(x)
+    }
+    fn gt_eq(a: &Gt, b: &Gt) -> bool {
+        a == b
+    }
+}
+
+impl Encode for Blstrs {
+    fn g1_generator() -> G1Projective {
+        G1Projective::generator()
+    }
+    fn g2_generator() -> G2Projective {
+        G2Projective::generator()
+    }
+    fn g1_bytes(p: &G1Projective) -> Vec<u8> {
+        G1Affine::from(p).to_uncompressed().to_vec()
+    }
+    fn g2_bytes(p: &G2Projective) -> Vec<u8> {
+        G2Affine::from(p).to_uncompressed().to_vec()
+    }
+}
+
+impl Scalars for Blst {
+    fn scalar(x: u64) -> blst_scalar {
+        let mut out = blst_scalar::default();
+        // SAFETY: blst reads four limbs
+        unsafe { blst_scalar_from_uint64(&mut out, [x, 0, 0, 0].as_ptr()) };
+        out
+    }
+    fn gt_eq(a: &blst_fp12, b: &blst_fp12) -> bool {
+        // SAFETY: both are valid elements
+        unsafe { blst_fp12_is_equal(a, b) }
+    }
+}
+
+impl Encode for Blst {
+    fn g1_generator() -> blst_p1 {
+        // SAFETY: blst's generators are statics
+        unsafe { *blst_p1_generator() }
+    }
+    fn g2_generator() -> blst_p2 {
+        // SAFETY: as for `g1_generator`
+        unsafe { *blst_p2_generator() }
+    }
+    fn g1_bytes(p: &blst_p1) -> Vec<u8> {
+        let mut out = [0u8; 96];
+        // SAFETY: blst writes 96 bytes
+        unsafe { blst_p1_serialize(out.as_mut_ptr(), p) };
+        out.to_vec()
+    }
+    fn g2_bytes(p: &blst_p2) -> Vec<u8> {
+        let mut out = [0u8; 192];
+        // SAFETY: blst writes 192 bytes
+        unsafe { blst_p2_serialize(out.as_mut_ptr(), p) };
+        out.to_vec()
+    }
+}
+
+#[cfg(any(feature = "arkworks", feature = "bn254"))]
+impl<E: ArkCurve> Scalars for Ark<E> {
+    fn scalar(x: u64) -> E::ScalarField {
+        x.into()
+    }
+    fn gt_eq(a: &Self::Gt, b: &Self::Gt) -> bool {
+        a == b
+    }
+}
+
+#[cfg(feature = "arkworks")]
+impl Encode for Arkworks {
+    fn g1_generator() -> ark_bls12_381::G1Projective {
+        ark_ec::Group::generator()
+    }
+    fn g2_generator() -> ark_bls12_381::G2Projective {
+        ark_ec::Group::generator()
+    }
+    // big-endian coordinates, the imaginary part of each first in G2
+    fn g1_bytes(p: &ark_bls12_381::G1Projective) -> Vec<u8> {
+        use ark_ff::{BigInteger as _, PrimeField as _};
+        let p = ark_ec::CurveGroup::into_affine(*p);
+        [p.x, p.y].iter().flat_map(|c| c.into_bigint().to_bytes_be()).collect()
+    }
+    fn g2_bytes(p: &ark_bls12_381::G2Projective) -> Vec<u8> {
+        use ark_ff::{BigInteger as _, PrimeField as _};
+        let p = ark_ec::CurveGroup::into_affine(*p);
+        [p.x.c1, p.x.c0, p.y.c1, p.y.c0].iter().flat_map(|c| c.into_bigint().to_bytes_be()).collect()
+    }
+}
+
+#[cfg(feature = "zkcrypto")]
+impl Scalars for Zkcrypto {
+    fn scalar(x: u64) -> bls12_381::Scalar {
+        bls12_381::Scalar::from(x)
+    }
+    fn gt_eq(a: &bls12_381::Gt, b: &bls12_381::Gt) -> bool {
+        a == b
+    }
+}
+
+#[cfg(feature = "zkcrypto")]
+impl Encode for Zkcrypto {
+    fn g1_generator() -> bls12_381::G1Projective {
+        bls12_381::G1Projective::generator()
+    }
+    fn g2_generator() -> bls12_381::G2Projective {
+        bls12_381::G2Projective::generator()
+    }
+    fn g1_bytes(p: &bls12_381::G1Projective) -> Vec<u8> {
+        bls12_381::G1Affine::from(p).to_uncompressed().to_vec()
+    }
+    fn g2_bytes(p: &bls12_381::G2Projective) -> Vec<u8> {
+        bls12_381::G2Affine::from(p).to_uncompressed().to_vec()
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl Scalars for Gpu {
+    fn scalar(x: u64) -> Scalar {
+        Blstrs::scalar(x)
+    }
+    fn gt_eq(a: &Gt, b: &Gt) -> bool {
+        a == b
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl Encode for Gpu {
+    fn g1_generator() -> G1Projective {
+        Blstrs::g1_generator()
+    }
+    fn g2_generator() -> G2Projective {
+        Blstrs::g2_generator()
+    }
+    fn g1_bytes(p: &G1Projective) -> Vec<u8> {
+        Blstrs::g1_bytes(p)
+    }
+    fn g2_bytes(p: &G2Projective) -> Vec<u8> {
+        Blstrs::g2_bytes(p)
+    }
+}
+
+// an exp and a multi-exp in each group on fixed inputs, encoded
+fn results<B: Encode>() -> Vec<Vec<u8>> {
+    let (g1, g2) = (B::g1_generator(), B::g2_generator());
+    let exps = [3, 5, 7].map(B::scalar);
+    let g1s = exps.each_ref().map(|x| B::g1_exp(&g1, x));
+    let g2s = exps.each_ref().map(|x| B::g2_exp(&g2, x));
+    let scalars = [11, 1 << 40, 0xdead_beef].map(B::scalar);
+    vec![
+        B::g1_bytes(&g1s[2]),
+        B::g2_bytes(&g2s[2]),
+        B::g1_bytes(&B::g1_multi_exp(&g1s, &scalars)),
+        B::g2_bytes(&B::g2_multi_exp(&g2s, &scalars)),
+    ]
+}
+
+// e(a·p, b·q) = e(p, q)^ab, and the same for a multi-pairing of two terms
+fn assert_bilinear<B: Scalars>() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (p, q) = (B::random_g1(&mut rng), B::random_g2(&mut rng));
+    let e = B::pairing(&p, &q);
+    let (a, b, c, d) = (6_700_417, 641, 1 << 30, 0xffff);
+    let pairing = B::pairing(&B::g1_exp(&p, &B::scalar(a)), &B::g2_exp(&q, &B::scalar(b)));
+    assert!(B::gt_eq(&pairing, &B::gt_exp(&e, &B::scalar(a * b))), "{}: pairing", B::NAME);
+    let lhs = [a, c].map(|x| B::g1_exp(&p, &B::scalar(x)));
+    let rhs = [b, d].map(|x| B::g2_exp(&q, &B::scalar(x)));
+    assert!(B::gt_eq(&B::multi_pairing(&lhs, &rhs), &B::gt_exp(&e, &B::scalar(a * b + c * d))), "{}: multi-pairing", B::NAME);
+}
+
+// every op `Backend` has, small
+fn core_workload() -> Workload {
+    Workload::new()
+        .op(OpKind::G1Exps, 2, 1)
+        .op(OpKind::G2Exps, 1, 1)
+        .op(OpKind::GtExps, 1, 1)
+        .op(OpKind::G1MultiExps, 2, 8)
+        .op(OpKind::G2MultiExps, 1, 4)
+        .op(OpKind::Pairings, 2, 1)
+        .op(OpKind::MultiPairings, 1, 3)
+}
+
+fn assert_same_op_counts<B: Backend>() {
+    let counts = |report: SimulationReport| -> Vec<_> {
+        report.items.into_iter().map(|item| (item.description, item.op_count)).collect()
+    };
+    let workload = core_workload();
+    let report = simulate_on::<B>(&workload, ChaCha20Rng::seed_from_u64(0)).unwrap();
+    let expected = simulate_on::<Blstrs>(&workload, ChaCha20Rng::seed_from_u64(0)).unwrap();
+    assert_eq!(counts(report), counts(expected), "{}", B::NAME);
+}
+
+#[test]
+fn blstrs_is_bilinear() {
+    assert_bilinear::<Blstrs>();
+}
+
+#[test]
+fn blst_agrees_with_blstrs() {
+    assert_eq!(results::<Blst>(), results::<Blstrs>());
+    assert_bilinear::<Blst>();
+    assert_same_op_counts::<Blst>();
+}
+
+#[test]
+fn gap_table_sums_each_kind_of_op() {
+    // G1 exps and pairings twice, apart
+    let workload = core_workload().op(OpKind::G1Exps, 3, 1).op(OpKind::Pairings, 1, 1);
+    let reports = vec![
+        (describe::<Blstrs>(), simulate_on::<Blstrs>(&workload, ChaCha20Rng::seed_from_u64(0)).unwrap()),
+        (describe::<Blst>(), simulate_on::<Blst>(&workload, ChaCha20Rng::seed_from_u64(0)).unwrap()),
+    ];
+    let table = gap_table(&workload, &reports);
+    assert_eq!(table.header, ["op class", "blstrs (BLS12-381)", "blst (BLS12-381)", "blst / blstrs"]);
+    let kinds = core_workload().ops.iter().map(|op| op.kind).collect::<Vec<_>>();
+    assert_eq!(table.rows.len(), kinds.len());
+    for (row, kind) in table.rows.iter().zip(kinds) {
+        let time = |report: &SimulationReport| -> Duration {
+            workload.ops.iter().zip(&report.items).filter(|(op, _)| op.kind == kind).map(|(_, item)| item.duration).sum()
+        };
+        let (blstrs, blst) = (time(&reports[0].1), time(&reports[1].1));
+        let ratio = format!("{:.2}", blst.as_secs_f64() / blstrs.as_secs_f64());
+        assert_eq!(row, &[Cell::Text(kind.name().to_string()), Cell::Time(blstrs), Cell::Time(blst), Cell::Text(ratio)]);
+    }
+}
+
+#[cfg(feature = "arkworks")]
+#[test]
+fn arkworks_agrees_with_blstrs() {
+    assert_eq!(results::<Arkworks>(), results::<Blstrs>());
+    assert_bilinear::<Arkworks>();
+    assert_same_op_counts::<Arkworks>();
+}
+
+#[cfg(feature = "zkcrypto")]
+#[test]
+fn zkcrypto_agrees_with_blstrs() {
+    assert_eq!(results::<Zkcrypto>(), results::<Blstrs>());
+    assert_bilinear::<Zkcrypto>();
+    assert_same_op_counts::<Zkcrypto>();
+}
+
+// on the GPU where there is one, on the CPU otherwise
+#[cfg(feature = "gpu")]
+#[test]
+fn gpu_agrees_with_blstrs() {
+    assert_eq!(results::<Gpu>(), results::<Blstrs>());
+    assert_bilinear::<Gpu>();
+    assert_same_op_counts::<Gpu>();
+}
+
+#[cfg(feature = "bn254")]
+#[test]
+fn bn254_runs_on_its_backend() {
+    assert_eq!(ArkBn254::CURVE, Curve::Bn254);
+    assert_bilinear::<ArkBn254>();
+    assert_same_op_counts::<ArkBn254>();
+    let all = simulate_on_all(&core_workload(), 0);
+    let (_, curve, report) = all.iter().find(|(name, _, _)| *name == describe::<ArkBn254>()).unwrap();
+    assert_eq!((describe::<ArkBn254>().as_str(), *curve), ("arkworks (BN254)", Curve::Bn254));
+    assert_eq!(report.as_ref().unwrap().items.len(), core_workload().ops.len());
+}
@@ -0,0 +1,75 @@
+//! Cost tables: the tables `CostProfile::parse` rejects, and estimates between, below and beyond
+//! the measured sizes.
+
+use blstrs_pvss_simulation::cost_model::{Cost, CostProfile, ProfileError};
+use blstrs_pvss_simulation::machine::{Machine, MachineProfile};
+use blstrs_pvss_simulation::workload::{Op, OpKind};
+
+
+fn ns(profile: &CostProfile, kind: OpKind, size: usize) -> f64 {
+    profile.op_time(&Op::new(kind, 1, size)).unwrap().as_secs_f64() * 1e9
+}
+
+// estimates are durations, in whole nanoseconds
+fn close(x: f64, y: f64) -> bool {
+    (x - y).abs() <= 1.0
+}
+
+#[test]
+fn parse_rejects_tables_it_cannot_interpolate() {
+    for table in [
+        "[[4, 100.0], [4, 120.0]]",
+        "[[16, 100.0], [4, 50.0]]",
+        "[]",
+        "[[0, 1.0], [4, 2.0]]",
+        "[[2, 0.0], [4, 2.0]]",
+        "[[2, -1.0]]",
+    ] {
+        let text = format!("[costs]\ng1_multi_exps = {}\n", table);
+        assert!(matches!(CostProfile::parse(&text), Err(ProfileError::Invalid(_))), "{}", table);
+    }
+    assert!(matches!(Cost::by_size(OpKind::MultiPairings, vec![(2, 1.0), (2, 1.0)]), Err(ProfileError::Invalid(_))));
+}
+
+// a profile of `machine` with the given `costs` object
+fn json_profile(costs: &str) -> String {
+    let machine = Machine { cpu_model: None, cores: 1, governor: None, blstrs_version: "0.7.1".to_string(), blst_version: "0.3.13".to_string() };
+    format!(
+        r#"{{ "id": "{}", "created": 0, "machine": {{ "cores": 1, "blstrs": "0.7.1", "blst": "0.3.13" }}, "costs": {} }}"#,
+        machine.id(), costs,
+    )
+}
+
+#[test]
+fn toml_tables_and_json_profiles_read_costs_alike() {
+    let toml = CostProfile::parse("[costs]\ng1_exps = 120000\ng1_multi_exps = [[2, 150000.0], [4, 190000.5]]\ng2_multi_exps = { a = 9.5, b = 4 }\n").unwrap();
+    let json = MachineProfile::parse(&json_profile(r#"{ "g1_exps": 120000, "g1_multi_exps": [[2, 150000.0], [4, 190000.5]], "g2_multi_exps": { "a": 9.5, "b": 4 } }"#)).unwrap();
+    assert_eq!(json.costs.costs, toml.costs);
+    for costs in [r#"{ "g1_multi_exps": [[4, 1.0], [2, 1.0]] }"#, r#"{ "g1_multi_exps": [[2, 1.0], [2, 1.0]] }"#, r#"{ "g1_exps": "fast" }"#, r#"{ "g1_exps": null }"#] {
+        assert!(matches!(MachineProfile::parse(&json_profile(costs)), Err(ProfileError::Invalid(_))), "{}", costs);
+    }
+}
+
+#[test]
+fn estimates_interpolate_in_log_log_space() {
+    let profile = CostProfile::parse("[costs]\nmulti_pairings = [[2, 1000.0], [8, 4000.0], [32, 8000.0]]\n").unwrap();
+    let kind = OpKind::MultiPairings;
+    assert!(close(ns(&profile, kind, 8), 4000.0));
+    // halfway between 2 and 8 in log space, so halfway between the costs' logs
+    assert!(close(ns(&profile, kind, 4), 2000.0));
+    assert!(close(ns(&profile, kind, 16), (4000.0f64 * 8000.0).sqrt()));
+    // linear below the smallest size and beyond the largest
+    assert!(close(ns(&profile, kind, 1), 500.0));
+    assert!(close(ns(&profile, kind, 64), 16000.0));
+}
+
+#[test]
+fn multi_exps_extrapolate_from_the_largest_size() {
+    let profile = CostProfile::parse("[costs]\ng1_multi_exps = [[2, 100.0], [16, 400.0]]\ng2_multi_exps = [[1, 300.0]]\n").unwrap();
+    // as size / log2(size) beyond 16
+    assert!(close(ns(&profile, OpKind::G1MultiExps, 256), 400.0 * (256.0 / 8.0) / (16.0 / 4.0)));
+    // with no size above 1 to scale from, linearly
+    for size in [1, 2, 1024] {
+        assert!(close(ns(&profile, OpKind::G2MultiExps, size), 300.0 * size as f64), "size {}", size);
+    }
+}
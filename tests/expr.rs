@@ -0,0 +1,116 @@
+//! The expressions of protocols and workload files: what parses, how tightly operators bind,
+//! and the overflows and unknown parameters evaluation rejects.
+
+use blstrs_pvss_simulation::config::{self, ConfigError};
+use blstrs_pvss_simulation::protocol::{BinOp, Expr, ExprError, Func, Params};
+
+
+fn params() -> Params {
+    Params::new().set("n", 64).set("k", 16).set("t", 42)
+}
+
+fn eval(text: &str) -> Result<i64, ExprError> {
+    Expr::parse(text)?.eval(&params())
+}
+
+fn is_syntax_error(text: &str) -> bool {
+    matches!(Expr::parse(text), Err(ExprError::Syntax { .. }))
+}
+
+fn is_arithmetic_error(text: &str) -> bool {
+    matches!(eval(text), Err(ExprError::Arithmetic(_)))
+}
+
+#[test]
+fn expressions_parse_into_their_trees() {
+    let param = |name: &str| Box::new(Expr::Param(name.to_string()));
+    assert_eq!(Expr::parse(" n*k+1 ").unwrap(), Expr::Binary(
+        Box::new(Expr::Binary(param("n"), BinOp::Mul, param("k"))),
+        BinOp::Add,
+        Box::new(Expr::Const(1)),
+    ));
+    assert_eq!(Expr::parse("max(t_new, log2(n))").unwrap(), Expr::Call(Func::Max, vec![
+        Expr::Param("t_new".to_string()),
+        Expr::Call(Func::Log2, vec![Expr::Param("n".to_string())]),
+    ]));
+    assert_eq!(Expr::parse("1_048_576").unwrap(), Expr::Const(1 << 20));
+    // what `Display` prints parses back to the same tree
+    for text in ["n*(k + 1) - t/2 % 3", "2^k^2", "min(n, t + 1, 7) - -t", "-(n - 1)"] {
+        let expr = Expr::parse(text).unwrap();
+        assert_eq!(Expr::parse(&expr.to_string()).unwrap(), expr, "{}", text);
+    }
+}
+
+#[test]
+fn malformed_expressions_are_syntax_errors() {
+    for text in ["", "n +", "(n + 1", "n + 1)", "n k", "n $ 2", "2 ** 3", "foo(n)", "log2(n, k)", "min()", "max(n,)", "99999999999999999999", ","] {
+        assert!(is_syntax_error(text), "{:?}", text);
+    }
+}
+
+#[test]
+fn operators_bind_by_precedence() {
+    for (text, value) in [
+        ("1 + 2*3", 7),
+        ("(1 + 2)*3", 9),
+        ("2*3^2", 18),
+        // left associative, but for powers
+        ("10 - 4 - 3", 3),
+        ("64/4/2", 8),
+        ("2^3^2", 512),
+        ("n - t + 1", 23),
+        ("n*k/t", 24),
+        ("n % 10 * 2", 8),
+        // unary minus binds tighter than everything
+        ("-2^2", 4),
+        ("-n + t", -22),
+        // division rounds down, remainders aren't negative
+        ("-7/2", -4),
+        ("-7 % 3", 2),
+        ("log2(n) + log2(65) + log2(1)", 6 + 7),
+        ("min(n, k, t) + max(n, k, t)", 80),
+    ] {
+        assert_eq!(eval(text), Ok(value), "{}", text);
+    }
+}
+
+#[test]
+fn overflow_is_an_error_not_a_wrap() {
+    for text in [
+        "2^63",
+        "9223372036854775807 + 1",
+        "-9223372036854775807 - 2",
+        "n^n",
+        "3037000500*3037000500",
+        "2^-1",
+        "n/(k - 16)",
+        "n % (t - t)",
+        "log2(0)",
+        "log2(t - n)",
+    ] {
+        assert!(is_arithmetic_error(text), "{:?}", text);
+    }
+    assert_eq!(eval("2^62 + (2^62 - 1)"), Ok(i64::MAX));
+    // counts must not be negative
+    let count = Expr::parse("t - n").unwrap().eval_count(&params());
+    assert!(matches!(count, Err(ExprError::Arithmetic(_))));
+}
+
+#[test]
+fn unknown_parameters_are_named() {
+    assert_eq!(eval("n*k + l"), Err(ExprError::UnknownParam("l".to_string())));
+    assert_eq!(eval("max(n, log2(t_new))"), Err(ExprError::UnknownParam("t_new".to_string())));
+    // names are case sensitive
+    assert_eq!(eval("N"), Err(ExprError::UnknownParam("N".to_string())));
+    assert_eq!(Expr::parse("n*k + max(l, n)").unwrap().params(), ["n", "k", "l", "n"]);
+}
+
+#[test]
+fn workload_files_evaluate_counts_over_their_params() {
+    let file = |num: &str| format!("[params]\nn = 64\nk = 16\n\n[[item]]\nop = \"g1_multi_exps\"\nnum = \"{}\"\nsize = \"n*k + 1\"\n", num);
+    let workload = config::parse_file(&file("2*(n - 1)")).unwrap().workload;
+    assert_eq!((workload.ops[0].num, workload.ops[0].size), (126, 1025));
+    for num in ["n*l", "n -", "2^n", "k - n"] {
+        assert!(matches!(config::parse_file(&file(num)), Err(ConfigError::Invalid(_))), "{}", num);
+    }
+}
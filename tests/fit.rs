@@ -0,0 +1,53 @@
+//! Complexity fits: synthetic timings of each model recover its coefficients, and the model they
+//! were drawn from fits best.
+
+use blstrs_pvss_simulation::fit::{fit_all, Fit, Model};
+
+
+const NS : [f64; 7] = [16.0, 32.0, 64.0, 128.0, 256.0, 512.0, 1024.0];
+
+fn timings(model: Model, a: f64, b: f64) -> Vec<(f64, f64)> {
+    NS.iter().map(|&n| (n, a * model.term(n) + b)).collect()
+}
+
+fn close(x: f64, y: f64) -> bool {
+    (x - y).abs() <= 1e-9 * y.abs().max(1e-12)
+}
+
+#[test]
+fn exact_timings_recover_the_coefficients() {
+    for model in Model::ALL {
+        let (a, b) = (3e-7, 2e-3);
+        let fits = fit_all(&timings(model, a, b));
+        assert_eq!(fits.len(), Model::ALL.len());
+        let best = fits[0];
+        assert_eq!(best.model, model);
+        assert!(close(best.a, a) && close(best.b, b), "{:?}", best);
+        assert!(close(best.r_squared, 1.0), "{:?}", best);
+        assert!(fits[1..].iter().all(|fit| fit.r_squared < best.r_squared), "{:?}", fits);
+        assert!(close(best.predict(2048.0), a * model.term(2048.0) + b));
+    }
+}
+
+#[test]
+fn noisy_timings_pick_the_model_they_were_drawn_from() {
+    // ±1% around a·n log n + b, alternating
+    let points : Vec<(f64, f64)> = timings(Model::NLogN, 5e-8, 1e-4).into_iter()
+        .enumerate()
+        .map(|(i, (n, y))| (n, y * if i % 2 == 0 { 1.01 } else { 0.99 }))
+        .collect();
+    let best = fit_all(&points)[0];
+    assert_eq!(best.model, Model::NLogN);
+    assert!((best.a - 5e-8).abs() < 5e-8 * 0.02, "{:?}", best);
+    assert!(best.r_squared > 0.999 && best.r_squared < 1.0, "{:?}", best);
+}
+
+#[test]
+fn a_fit_needs_two_distinct_sizes() {
+    assert_eq!(Fit::new(Model::Linear, &[]), None);
+    assert_eq!(Fit::new(Model::Linear, &[(64.0, 1.0)]), None);
+    assert_eq!(Fit::new(Model::Quadratic, &[(64.0, 1.0), (64.0, 2.0)]), None);
+    // constant times are fit exactly by a flat line
+    let flat = Fit::new(Model::Linear, &[(1.0, 0.5), (2.0, 0.5), (3.0, 0.5)]).unwrap();
+    assert_eq!((flat.a, flat.b, flat.r_squared), (0.0, 0.5, 1.0));
+}
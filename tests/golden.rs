@@ -0,0 +1,74 @@
+//! Golden files of the op counts of every preset in `presets::STANDARD` at its standard
+//! parameters, in the JSON of the `ops` command, one file per preset under `tests/golden`. A
+//! change to a preset's formulas changes its file, so it shows up in review as a diff of the
+//! counts. After a deliberate change, rewrite the files with
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+//!
+//! and commit them with the change.
+
+use blstrs_pvss_simulation::op_counts;
+use blstrs_pvss_simulation::presets;
+
+use std::path::PathBuf;
+
+
+fn golden_path(preset: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.json", preset))
+}
+
+// the preset's counts at every standard parameter set
+fn expand(preset: &str) -> String {
+    let protocol = presets::by_name(preset).unwrap();
+    let points : Vec<_> = presets::standard_params(preset).into_iter()
+        .map(|params| {
+            let counts = protocol.expand(&params).unwrap_or_else(|e| panic!("{}: {}", preset, e));
+            (params, counts)
+        })
+        .collect();
+    op_counts::to_json(preset, &points) + "\n"
+}
+
+// the first line that differs, numbered from 1
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    for line in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(a), Some(b)) if a == b => {}
+            (a, b) => return format!("line {}: expected {:?}, got {:?}", line, a.unwrap_or("<end>"), b.unwrap_or("<end>")),
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn preset_op_counts_match_golden_files() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut mismatches = Vec::new();
+    for (preset, _) in presets::STANDARD {
+        let path = golden_path(preset);
+        let actual = expand(preset);
+        if update {
+            std::fs::write(&path, &actual).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            continue;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => mismatches.push(format!("{}: {}", preset, first_difference(&expected, &actual))),
+            Err(e) => mismatches.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    assert!(mismatches.is_empty(), "op counts differ from the golden files (rerun with UPDATE_GOLDEN=1 if the change is intended):\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn golden_files_have_a_standard_preset() {
+    let presets : Vec<String> = presets::STANDARD.iter().map(|(preset, _)| format!("{}.json", preset)).collect();
+    for entry in std::fs::read_dir(golden_path("x").parent().unwrap()).unwrap() {
+        let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+        assert!(presets.contains(&name), "tests/golden/{} belongs to no preset of presets::STANDARD", name);
+    }
+}
@@ -0,0 +1,114 @@
+//! The TOML and JSON readers behind workload files and machine profiles: the same workload in
+//! either syntax, strings that survive a round trip, and malformed input they reject.
+
+use blstrs_pvss_simulation::config::{self, ConfigError};
+use blstrs_pvss_simulation::cost_model::{Cost, CostProfile};
+use blstrs_pvss_simulation::machine::{Machine, MachineProfile};
+use blstrs_pvss_simulation::workload::{Op, OpKind};
+
+use std::time::{Duration, UNIX_EPOCH};
+
+
+const TOML : &str = r#"
+version = 1 # trailing comment
+
+[options]
+reps = 3
+
+[[item]]
+op = "g1_multi_exps"
+num = 2
+size = 1024
+label = "share \"commitments\"\té"
+phase = "Dealer"
+
+[[item]]
+op = "pairings"
+"num" = 5
+"#;
+
+const JSON : &str = r#"{
+  "version": 1,
+  "options": { "reps": 3 },
+  "item": [
+    { "op": "g1_multi_exps", "num": 2, "size": 1024, "label": "share \"commitments\"\té", "phase": "Dealer" },
+    { "op": "pairings", "num": 5 }
+  ]
+}"#;
+
+fn is_parse_error(result: Result<config::WorkloadFile, ConfigError>) -> bool {
+    matches!(result, Err(ConfigError::Parse { .. }))
+}
+
+#[test]
+fn toml_and_json_read_the_same_workload() {
+    let toml = config::parse_file(TOML).unwrap();
+    let json = config::parse_json(JSON).unwrap();
+    let ops = &toml.workload.ops;
+    assert_eq!((ops.len(), ops[0].kind, ops[0].num, ops[0].size), (2, OpKind::G1MultiExps, 2, 1024));
+    assert_eq!(ops[0].label.as_deref(), Some("share \"commitments\"\té"));
+    assert_eq!(ops[0].phase.as_deref(), Some("Dealer"));
+    assert_eq!(ops[1], Op::new(OpKind::Pairings, 5, 1));
+    assert_eq!(toml.workload, json.workload);
+    assert_eq!((toml.version, toml.options.reps), (1, Some(3)));
+    assert_eq!((json.version, json.options.reps), (1, Some(3)));
+}
+
+#[test]
+fn json_decodes_surrogate_pairs() {
+    let json = r#"{ "version": 1, "item": [{ "op": "pairings", "label": "\uD83D\uDE00 and \ud83d\ude00!" }] }"#;
+    let file = config::parse_json(json).unwrap();
+    assert_eq!(file.workload.ops[0].label.as_deref(), Some("😀 and 😀!"));
+}
+
+#[test]
+fn json_rejects_malformed_input() {
+    let item = |label: &str| format!(r#"{{ "version": 1, "item": [{{ "op": "pairings", "label": "{}" }}] }}"#, label);
+    for label in [r"\uD83D", r"\uD83Dx", r"\uD83DA", r"\uDE00", r"\u12", r"\u+123", r"\q", "line\nbreak"] {
+        assert!(is_parse_error(config::parse_json(&item(label))), "{:?}", label);
+    }
+    for text in ["", "{", r#"{ "version": 1 } 2"#, r#"{ "version": 1, }"#, r#"{ "version" 1 }"#, "[1, 2", r#"{ "version": tru }"#] {
+        assert!(is_parse_error(config::parse_json(text)), "{:?}", text);
+    }
+    // well formed, but not a workload
+    assert!(matches!(config::parse_json("[]"), Err(ConfigError::Invalid(_))));
+}
+
+#[test]
+fn toml_rejects_malformed_input() {
+    for text in [
+        "version = ",
+        "version = 1 2",
+        "label = \"unterminated",
+        "label = \"bad \\q escape\"",
+        "[item",
+        "[[item]\nop = \"pairings\"",
+        "num = [1, 2",
+        "num = { a = 1",
+        "num = 1\nnum = 2",
+        "= 1",
+        "date = 2024-01-01",
+    ] {
+        assert!(is_parse_error(config::parse_file(text)), "{:?}", text);
+    }
+}
+
+#[test]
+fn machine_profiles_round_trip_through_json() {
+    let machine = Machine {
+        cpu_model: Some("\"Quoted\" \\ model\twith\u{1}control 😀 é".to_string()),
+        cores: 8,
+        governor: None,
+        blstrs_version: "0.7.1".to_string(),
+        blst_version: "0.3.13".to_string(),
+    };
+    let mut costs = CostProfile::default();
+    costs.costs.push((OpKind::Pairings, Cost::Fixed(412_345.6)));
+    costs.costs.push((OpKind::G1MultiExps, Cost::BySize(vec![(2, 80_000.0), (1024, 3_000_000.5)])));
+    let mut profile = MachineProfile::new(machine, costs);
+    profile.created = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    let parsed = MachineProfile::parse(&profile.to_json()).unwrap();
+    assert_eq!(parsed, profile);
+    assert_eq!(parsed.to_json(), profile.to_json());
+}
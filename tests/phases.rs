@@ -0,0 +1,87 @@
+//! Nested phases: the path each item records and the tree of inclusive and exclusive times the
+//! reports build from it, with names taken as they are.
+
+use blstrs_pvss_simulation::group_ops_simulation::GroupOpsSimulation;
+use blstrs_pvss_simulation::report::PhaseNode;
+use blstrs_pvss_simulation::workload::{Op, OpKind, Workload};
+
+use rand_chacha::ChaCha20Rng;
+use std::time::Duration;
+
+
+fn names(nodes: &[PhaseNode]) -> Vec<&str> {
+    nodes.iter().map(|node| node.name.as_str()).collect()
+}
+
+// the inclusive times add up along the tree
+fn assert_consistent(nodes: &[PhaseNode]) {
+    for node in nodes {
+        assert_eq!(node.inclusive, node.exclusive + node.children.iter().map(|child| child.inclusive).sum::<Duration>(), "{}", node.name);
+        assert_consistent(&node.children);
+    }
+}
+
+fn simulation() -> GroupOpsSimulation<ChaCha20Rng> {
+    let mut sim = GroupOpsSimulation::with_seed(1);
+    sim.phase("verify-transcript", |sim| {
+        sim.scalar_muls(4);
+        sim.phase("encryption checks", |sim| {
+            sim.g1_additions(4);
+            sim.phase("one / by / one", |sim| { sim.scalar_muls(2); });
+        });
+        sim.g1_additions(2);
+    });
+    sim.scalar_muls(1);
+    sim.phase("Dealer / Prover", |sim| { sim.g1_additions(1); });
+    sim
+}
+
+#[test]
+fn items_record_their_phases_apart() {
+    let report = simulation().simulate();
+    let paths : Vec<Vec<&str>> = report.items.iter().map(|item| item.phase_path.iter().map(String::as_str).collect()).collect();
+    assert_eq!(paths, [
+        vec!["verify-transcript"],
+        vec!["verify-transcript", "encryption checks"],
+        vec!["verify-transcript", "encryption checks", "one / by / one"],
+        vec!["verify-transcript"],
+        vec![],
+        vec!["Dealer / Prover"],
+    ]);
+    assert_eq!(report.items[2].phase.as_deref(), Some("verify-transcript / encryption checks / one / by / one"));
+    assert_eq!(report.items[4].phase, None);
+}
+
+#[test]
+fn the_tree_nests_by_path_not_by_name() {
+    let report = simulation().simulate();
+    let tree = report.phase_tree();
+    assert_eq!(names(&tree), ["verify-transcript", "Dealer / Prover"]);
+    assert!(tree[1].children.is_empty());
+    let checks = &tree[0].children;
+    assert_eq!(names(checks), ["encryption checks"]);
+    assert_eq!(names(&checks[0].children), ["one / by / one"]);
+    assert!(checks[0].children[0].children.is_empty());
+    let time = |i: usize| report.items[i].duration;
+    assert_eq!(tree[0].exclusive, time(0) + time(3));
+    assert_eq!(checks[0].exclusive, time(1));
+    assert_eq!(tree[0].inclusive, time(0) + time(1) + time(2) + time(3));
+    assert_consistent(&tree);
+
+    let runs = simulation().simulate_n(2);
+    let tree = runs.phase_tree();
+    assert_eq!(names(&tree), ["verify-transcript", "Dealer / Prover"]);
+    assert_eq!(names(&tree[0].children[0].children), ["one / by / one"]);
+    assert_consistent(&tree);
+}
+
+#[test]
+fn workload_phases_are_one_level_each() {
+    let mut op = Op::new(OpKind::ScalarMuls, 2, 1);
+    op.phase = Some("Dealer / Prover".to_string());
+    let mut sim = GroupOpsSimulation::with_seed(1);
+    sim.workload(&Workload { ops: vec![op] });
+    let report = sim.simulate();
+    assert_eq!(report.items[0].phase_path, ["Dealer / Prover"]);
+    assert_eq!(names(&report.phase_tree()), ["Dealer / Prover"]);
+}
@@ -0,0 +1,46 @@
+//! FFTs against direct evaluation, down to the domain of one element.
+
+use blstrs_pvss_simulation::polynomial::{horner, Radix2Domain};
+use blstrs_pvss_simulation::workload::{OpKind, Workload};
+
+use blstrs::Scalar;
+use ff::Field as _;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+
+#[test]
+fn fft_evaluates_at_the_powers_of_the_generator() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    for log_size in 0..=4 {
+        let domain = Radix2Domain::new(log_size);
+        let coeffs : Vec<Scalar> = (0..domain.size()).map(|_| Scalar::random(&mut rng)).collect();
+        let mut values = coeffs.clone();
+        domain.fft(&mut values);
+
+        // the generator is the value of `x` at index 1
+        let mut x = Scalar::ONE;
+        let generator = match domain.size() {
+            1 => Scalar::ONE,
+            _ => {
+                let mut unit = vec![Scalar::ZERO; domain.size()];
+                unit[1] = Scalar::ONE;
+                domain.fft(&mut unit);
+                unit[1]
+            }
+        };
+        for value in &values {
+            assert_eq!(*value, horner(&coeffs, &x), "size {}", domain.size());
+            x *= generator;
+        }
+
+        domain.ifft(&mut values);
+        assert_eq!(values, coeffs, "size {}", domain.size());
+    }
+}
+
+#[test]
+fn fft_of_size_one_runs_as_a_workload_op() {
+    let report = Workload::new().op(OpKind::Fft, 2, 1).simulation(0).unwrap().simulate();
+    assert!(!report.items.is_empty());
+}
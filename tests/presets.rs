@@ -0,0 +1,94 @@
+//! Presets: the parameters their checks reject, on every path that evaluates them, and the
+//! simple PVSS schemes in the registry and on the command line.
+
+use blstrs_pvss_simulation::presets::{self, GrothParams, GrothParamsError, PvssPreset, SimplePvss, SIMPLE_PVSS};
+use blstrs_pvss_simulation::protocol::{ExprError, Params};
+
+use std::process::Command;
+
+
+fn params(values: &[(&str, i64)]) -> Params {
+    values.iter().fold(Params::new(), |params, &(name, value)| params.set(name, value))
+}
+
+fn is_invalid<T>(result: Result<T, ExprError>) -> bool {
+    matches!(result, Err(ExprError::InvalidParams(_)))
+}
+
+#[test]
+fn groth_params_are_checked_on_construction() {
+    assert_eq!(GrothParams::new(4, 1, 99, 1), Err(GrothParamsError::ThresholdTooLarge { t: 99, n: 4 }));
+    assert_eq!(GrothParams::new(1 << 21, 1 << 20, 1, 1 << 20), Err(GrothParamsError::TooLarge { n: 1 << 21, k: 1 << 20, l: 1 << 20 }));
+    assert_eq!(GrothParams::new(usize::MAX, 2, 1, 1), Err(GrothParamsError::TooLarge { n: usize::MAX, k: 2, l: 1 }));
+    // at the bound, every count still fits
+    let largest = GrothParams::new(1 << 20, 1 << 20, 1, 1 << 20).unwrap();
+    assert_eq!(largest.workload().ops.len(), GrothParams::default().workload().ops.len());
+    largest.communication();
+    assert_eq!(GrothParams::from_params(&params(&[("n", 4), ("k", 1), ("t", -1), ("l", 1)])), Err(GrothParamsError::Missing("t")));
+}
+
+#[test]
+fn the_groth_presets_check_their_parameters() {
+    let valid = params(&[("n", 16), ("k", 16), ("t", 10), ("l", 16), ("r", 1)]);
+    let threshold = params(&[("n", 4), ("k", 1), ("t", 99), ("l", 1), ("r", 1)]);
+    let overflow = params(&[("n", 1 << 21), ("k", 1 << 20), ("t", 1), ("l", 1 << 20), ("r", 1)]);
+    for name in ["groth", "dkg-groth", "dkg-groth-complaints", "admission-groth"] {
+        let protocol = presets::by_name(name).unwrap();
+        let valid = valid.clone().set("f", 1);
+        assert!(protocol.evaluate(&valid).is_ok() && protocol.communication(&valid).is_ok(), "{}", name);
+        for invalid in [&threshold, &overflow] {
+            let invalid = invalid.clone().set("f", 1);
+            assert!(is_invalid(protocol.evaluate(&invalid)), "{} {:?}", name, invalid);
+            assert!(is_invalid(protocol.communication(&invalid)), "{} {:?}", name, invalid);
+        }
+    }
+    let groth = GrothParams::new(16, 16, 10, 16).unwrap();
+    assert_eq!(presets::by_name("groth").unwrap().evaluate(&groth.params()).unwrap(), groth.workload());
+}
+
+#[test]
+fn dkg_complaints_need_a_threshold_below_n_and_fewer_faulty_dealers_than_parties() {
+    for scheme in ["pvss", "groth", "groth21", "scrape", "schoenmakers"] {
+        let protocol = presets::dkg_complaints_protocol(scheme).unwrap();
+        let run = |n, t, f| params(&[("n", n), ("t", t), ("f", f), ("k", 4), ("l", 8), ("b", 16)]);
+        assert!(protocol.evaluate(&run(16, 10, 15)).is_ok(), "{}", scheme);
+        for (n, t, f) in [(16, 10, 16), (16, 16, 1), (16, 20, 1)] {
+            assert!(is_invalid(protocol.evaluate(&run(n, t, f))), "{} n={} t={} f={}", scheme, n, t, f);
+            assert!(is_invalid(protocol.communication(&run(n, t, f))), "{} n={} t={} f={}", scheme, n, t, f);
+        }
+    }
+}
+
+#[test]
+fn simple_pvss_schemes_are_presets() {
+    for scheme in SIMPLE_PVSS {
+        let preset = presets::find(scheme.name).unwrap();
+        assert_eq!(preset.params().iter().map(|param| param.name).collect::<Vec<_>>(), ["n", "t"]);
+        let protocol = preset.protocol();
+        let pvss = SimplePvss::by_name(scheme.name, 16, 10).unwrap().unwrap();
+        assert_eq!(pvss.workload(), protocol.evaluate(&params(&[("n", 16), ("t", 10)])).unwrap(), "{}", scheme.name);
+        assert!(!pvss.dealer().ops.is_empty() && !pvss.verifier().ops.is_empty() && !pvss.reconstructor().ops.is_empty());
+        assert!(is_invalid(protocol.evaluate(&params(&[("n", 16), ("t", 15)]))), "{}", scheme.name);
+        assert!(is_invalid(SimplePvss::by_name(scheme.name, 16, 15).unwrap()), "{}", scheme.name);
+        assert!(is_invalid(SimplePvss::new((scheme.protocol)(), usize::MAX, 1)), "{}", scheme.name);
+    }
+    assert!(SimplePvss::by_name("scrape", 16, 10).is_none());
+}
+
+fn pvss_sim(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_pvss-sim")).args(args).output().unwrap();
+    assert!(output.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn dhpvss_shows_up_in_sweep_and_compare() {
+    let sweep = pvss_sim(&["sweep", "dhpvss", "n=4,5", "t=1", "--seed", "1", "--no-history"]);
+    let header = sweep.lines().find(|line| line.starts_with("n ")).unwrap();
+    assert!(["Dealer", "Verifier", "Reconstructor", "transcript"].iter().all(|column| header.contains(column)), "{}", sweep);
+    assert!(sweep.lines().any(|line| line.starts_with("4  1 ")) && sweep.lines().any(|line| line.starts_with("5  1 ")), "{}", sweep);
+
+    let compare = pvss_sim(&["compare", "dhpvss", "n=4", "t=1", "vs", "scrape", "n=4", "t=1", "--seed", "1", "--no-history"]);
+    assert!(compare.contains("A: dhpvss n=4 t=1") && compare.contains("B: scrape n=4 t=1"), "{}", compare);
+    assert!(compare.lines().any(|line| line.starts_with("Reconstructor ")), "{}", compare);
+}
@@ -0,0 +1,78 @@
+//! The PVSS end to end: dealing, verifying the transcript, decrypting shares and reconstructing
+//! `g1^secret`, and the shares reconstruction rejects.
+
+use blstrs_pvss_simulation::pvss::{self, DecryptedShare, Dealing, Keypair, VerificationError};
+
+use blstrs::{G1Projective, Scalar};
+use ff::Field as _;
+use group::Group as _;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+
+const N : usize = 7;
+const T : usize = 3;
+
+struct Setup {
+    rng: ChaCha20Rng,
+    secret: Scalar,
+    keys: Vec<Keypair>,
+    public_keys: Vec<G1Projective>,
+    dealing: Dealing,
+}
+
+fn setup() -> Setup {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let keys : Vec<Keypair> = (0..N).map(|_| Keypair::generate(&mut rng)).collect();
+    let public_keys : Vec<G1Projective> = keys.iter().map(|key| key.public).collect();
+    let secret = Scalar::random(&mut rng);
+    let dealing = pvss::deal(&secret, T, &public_keys, &mut rng);
+    Setup { rng, secret, keys, public_keys, dealing }
+}
+
+// the decrypted shares of `parties`, in that order
+fn decrypt(setup: &mut Setup, parties: &[usize]) -> Vec<DecryptedShare> {
+    parties.iter().map(|&i| pvss::decrypt_share(&setup.dealing, i, &setup.keys[i], &mut setup.rng)).collect()
+}
+
+#[test]
+fn any_t_plus_one_shares_reconstruct_the_secret() {
+    let mut setup = setup();
+    pvss::verify_dealing(&setup.dealing, &setup.public_keys, &mut setup.rng).unwrap();
+    let expected = G1Projective::generator() * setup.secret;
+    for parties in [[0, 1, 2, 3], [6, 4, 2, 0], [3, 5, 1, 6]] {
+        let shares = decrypt(&mut setup, &parties);
+        assert_eq!(pvss::reconstruct(&setup.dealing, &setup.public_keys, &shares), Ok(expected), "parties {:?}", parties);
+    }
+}
+
+#[test]
+fn reconstruction_rejects_a_repeated_share() {
+    let mut setup = setup();
+    // t + 1 shares, but only t parties, followed by a share that would have made up for it
+    let shares = decrypt(&mut setup, &[0, 1, 2, 1, 3]);
+    assert_eq!(pvss::reconstruct(&setup.dealing, &setup.public_keys, &shares), Err(VerificationError::DuplicateShare(1)));
+}
+
+#[test]
+fn reconstruction_rejects_a_tampered_share() {
+    let mut setup = setup();
+    let mut shares = decrypt(&mut setup, &[0, 1, 2, 3]);
+    shares[2].share += G1Projective::generator();
+    assert_eq!(pvss::reconstruct(&setup.dealing, &setup.public_keys, &shares), Err(VerificationError::InvalidDecryption(2)));
+}
+
+#[test]
+fn reconstruction_needs_t_plus_one_shares() {
+    let mut setup = setup();
+    let shares = decrypt(&mut setup, &[0, 1, 2]);
+    assert_eq!(pvss::reconstruct(&setup.dealing, &setup.public_keys, &shares), Err(VerificationError::NotEnoughShares));
+}
+
+#[test]
+fn verification_rejects_a_tampered_dealing() {
+    let mut setup = setup();
+    let mut dealing = setup.dealing.clone();
+    dealing.encrypted_shares[4] += G1Projective::generator();
+    assert_eq!(pvss::verify_dealing(&dealing, &setup.public_keys, &mut setup.rng), Err(VerificationError::InvalidEncryption(4)));
+}
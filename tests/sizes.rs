@@ -0,0 +1,67 @@
+//! Size distributions: what `parse` and the constructors accept, and Zipf samples against the
+//! probabilities.
+
+use blstrs_pvss_simulation::sizes::{SizeDistribution, MAX_SIZE};
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+
+#[test]
+fn parse_caps_the_sizes() {
+    assert!(SizeDistribution::parse(&format!("zipf:{}:1.1", MAX_SIZE)).is_ok());
+    for text in ["zipf:4000000000:1.0", "uniform:1:2000000", "16,4000000", "uniform:0:4"] {
+        assert!(SizeDistribution::parse(text).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn constructors_check_what_parse_checks() {
+    for exponent in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.5] {
+        assert!(SizeDistribution::zipf(16, exponent).is_err(), "{}", exponent);
+    }
+    for text in ["zipf:16:NaN", "zipf:16:inf", "zipf:16:-1", "uniform:8:4", "16,,4", ""] {
+        assert!(SizeDistribution::parse(text).is_err(), "{}", text);
+    }
+    assert!(SizeDistribution::zipf(MAX_SIZE + 1, 1.0).is_err());
+    assert!(SizeDistribution::zipf(0, 1.0).is_err());
+    assert!(SizeDistribution::uniform(1, MAX_SIZE + 1).is_err());
+    assert!(SizeDistribution::uniform(8, 4).is_err());
+    assert!(SizeDistribution::list(Vec::new()).is_err());
+    assert!(SizeDistribution::list(vec![16, 0]).is_err());
+    // what `Display` prints parses back to the same distribution
+    for sizes in [SizeDistribution::zipf(MAX_SIZE, 1.1), SizeDistribution::uniform(4, 16), SizeDistribution::list(vec![16, 16, 64])] {
+        let sizes = sizes.unwrap();
+        assert_eq!(SizeDistribution::parse(&sizes.to_string()), Ok(sizes));
+    }
+}
+
+#[test]
+fn zipf_samples_follow_the_probabilities() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let num = 200_000;
+    for exponent in [0.0, 0.5, 1.0, 1.1, 2.5] {
+        let zipf = SizeDistribution::zipf(20, exponent).unwrap();
+        let mut counts = [0usize; 21];
+        for size in zipf.sample(num, &mut rng) {
+            counts[size] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        for (size, p) in zipf.probabilities() {
+            let frequency = counts[size] as f64 / num as f64;
+            // about five standard deviations
+            let tolerance = 5.0 * (p * (1.0 - p) / num as f64).sqrt() + 1e-4;
+            assert!((frequency - p).abs() < tolerance, "exponent {}, size {}: {} drawn, {} expected", exponent, size, frequency, p);
+        }
+        let mean = zipf.probabilities().iter().map(|&(size, p)| size as f64 * p).sum::<f64>();
+        assert!((zipf.mean() - mean).abs() < 1e-9, "exponent {}", exponent);
+    }
+}
+
+#[test]
+fn zipf_samples_large_sizes_in_constant_memory() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let sizes = SizeDistribution::zipf(MAX_SIZE, 1.0).unwrap().sample(1000, &mut rng);
+    assert!(sizes.iter().all(|&size| (1..=MAX_SIZE).contains(&size)));
+    assert!(sizes.iter().any(|&size| size > 1000));
+}
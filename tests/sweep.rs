@@ -0,0 +1,129 @@
+//! Sweeps: the axes `parse_axis` reads and the points they span, the fits and crossovers of a
+//! sweep's table, and `sweep --fit` on the command line.
+
+use blstrs_pvss_simulation::fit::Model;
+use blstrs_pvss_simulation::protocol::{ExprError, Params};
+use blstrs_pvss_simulation::sweep::{Sweep, SweepRow, SweepTable};
+
+use std::process::{Command, Output};
+use std::time::Duration;
+
+
+fn points(axes: &[&str]) -> Vec<Vec<(String, i64)>> {
+    let mut sweep = Sweep::new();
+    for axis in axes {
+        sweep.parse_axis(axis).unwrap();
+    }
+    sweep.points().unwrap().iter()
+        .map(|params| params.iter().map(|(name, value)| (name.to_string(), value)).collect())
+        .collect()
+}
+
+fn values(axis: &str) -> Vec<i64> {
+    points(&[axis]).into_iter().map(|point| point[0].1).collect()
+}
+
+#[test]
+fn axes_are_lists_ranges_and_expressions() {
+    assert_eq!(values("n=128..=1024:*2"), [128, 256, 512, 1024]);
+    assert_eq!(values("n=1..=10:+4"), [1, 5, 9]);
+    assert_eq!(values("n=3..=5"), [3, 4, 5]);
+    assert_eq!(values("n=8,16,2^5,1..=2"), [8, 16, 32, 1, 2]);
+    assert_eq!(values("n=5..=4"), Vec::<i64>::new());
+    for spec in ["n", "n=1..=8:*1", "n=1..=8:+0", "n=1..=8:2", "n=1..=x", "n=a..=8", "n=(1", "n=1,,2"] {
+        assert!(Sweep::new().parse_axis(spec).is_err(), "{}", spec);
+    }
+}
+
+#[test]
+fn points_vary_the_first_axis_slowest_and_derive_later_ones() {
+    let point = |n, k, t| vec![("k".to_string(), k), ("n".to_string(), n), ("t".to_string(), t)];
+    assert_eq!(points(&["n=64,128", "k=8,16", "t=2*n/3"]), [
+        point(64, 8, 42), point(64, 16, 42), point(128, 8, 85), point(128, 16, 85),
+    ]);
+    let sweep = Sweep::new().axis("n", [4, 8]).derived("t", "n - 1");
+    assert_eq!(sweep.names(), ["n", "t"]);
+    // an axis can only refer to the axes before it
+    let mut sweep = Sweep::new();
+    sweep.parse_axis("t=n/2").unwrap();
+    sweep.parse_axis("n=8").unwrap();
+    assert_eq!(sweep.points(), Err(ExprError::UnknownParam("n".to_string())));
+}
+
+// a row at `n` with the given phase times, in seconds
+fn row(n: i64, phases: &[(&str, f64)]) -> SweepRow {
+    let phases : Vec<(String, Duration)> = phases.iter().map(|&(name, secs)| (name.to_string(), Duration::from_secs_f64(secs))).collect();
+    SweepRow {
+        params: Params::new().set("n", n).set("t", 2 * n / 3),
+        total: phases.iter().map(|(_, time)| *time).sum(),
+        phases,
+        transcript: None,
+        units: None,
+    }
+}
+
+fn table(rows: Vec<SweepRow>) -> SweepTable {
+    SweepTable { param_names: vec!["n".to_string(), "t".to_string()], rows, unit: None }
+}
+
+#[test]
+fn fits_pick_each_phases_model() {
+    let rows = [64, 128, 256, 512, 1024].into_iter()
+        .map(|n| {
+            let n_f = n as f64;
+            row(n, &[("Dealer", 2e-5 * n_f + 1e-3), ("Verifier", 3e-8 * n_f * n_f + 2e-3)])
+        })
+        .collect();
+    let fits = table(rows).fits("n");
+    let names : Vec<&str> = fits.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["Dealer", "Verifier", "total"]);
+    let best = |phase: &str| fits.iter().find(|(name, _)| name == phase).unwrap().1[0];
+    assert_eq!(best("Dealer").model, Model::Linear);
+    assert!((best("Dealer").a - 2e-5).abs() < 1e-9 && (best("Dealer").b - 1e-3).abs() < 1e-8, "{:?}", best("Dealer"));
+    assert_eq!(best("Verifier").model, Model::Quadratic);
+    assert!(best("Verifier").r_squared > 0.999_999, "{:?}", best("Verifier"));
+    // the sum is dominated by the quadratic phase
+    assert_eq!(best("total").model, Model::Quadratic);
+}
+
+#[test]
+fn fits_need_two_values_of_the_parameter() {
+    let fits = table(vec![row(64, &[("Dealer", 1e-3)]), row(64, &[("Dealer", 2e-3)])]).fits("n");
+    assert!(fits.iter().all(|(_, fits)| fits.is_empty()), "{:?}", fits);
+    // a parameter the rows don't set has no points to fit
+    let fits = table(vec![row(64, &[("Dealer", 1e-3)]), row(128, &[("Dealer", 2e-3)])]).fits("k");
+    assert!(fits.iter().all(|(_, fits)| fits.is_empty()), "{:?}", fits);
+}
+
+#[test]
+fn crossover_is_the_first_row_where_the_second_phase_is_faster() {
+    let table = table(vec![
+        row(4, &[("one by one", 1.0), ("batched", 3.0)]),
+        row(8, &[("one by one", 2.0), ("batched", 2.0)]),
+        row(16, &[("one by one", 4.0), ("batched", 3.0)]),
+        row(32, &[("one by one", 8.0), ("batched", 4.0)]),
+    ]);
+    assert_eq!(table.crossover("one by one", "batched").map(|row| row.params.get("n")), Some(Some(16)));
+    assert_eq!(table.crossover("batched", "one by one").map(|row| row.params.get("n")), Some(Some(4)));
+    assert!(table.crossover("one by one", "unknown").is_none());
+}
+
+fn pvss_sim(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pvss-sim")).args(args).args(["--seed", "1", "--no-history"]).output().unwrap()
+}
+
+#[test]
+fn sweep_fit_prints_a_fit_per_phase() {
+    let output = pvss_sim(&["sweep", "dhpvss", "n=4,6,8", "t=1", "--fit", "n"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fits = stdout.split_once("fits against n:\n").map(|(_, fits)| fits).unwrap_or_else(|| panic!("{}", stdout));
+    for phase in ["Dealer", "Verifier", "Reconstructor", "total"] {
+        let line = fits.lines().find(|line| line.starts_with(&format!("  {}: ", phase))).unwrap_or_else(|| panic!("{}", stdout));
+        assert!(line.contains("R² = ") && line.matches("a·").count() == 3, "{}", line);
+    }
+
+    let output = pvss_sim(&["sweep", "dhpvss", "n=4,6", "t=1", "--fit", "k"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fit: no parameter `k` in the sweep"));
+}
@@ -0,0 +1,38 @@
+// Loads the module of `cargo build-wasm` and runs workload files on it, in a browser or in Node:
+//
+//     const sim = await load(await (await fetch("blstrs_pvss_simulation.wasm")).arrayBuffer());
+//     const report = sim.run(workloadToml);  // { backend, curve, seed, started_at, items, total }
+//
+// `run` throws the error of a file that doesn't parse or has ops the backend lacks.
+
+export async function load(bytes) {
+    let exports, posted;
+    const memory = () => new Uint8Array(exports.memory.buffer);
+    const { instance } = await WebAssembly.instantiate(bytes, {
+        pvss_sim: {
+            now: () => performance.timeOrigin + performance.now(),
+            random: (ptr, len) => {
+                // getRandomValues fills at most 65536 bytes at a time
+                for (let i = 0; i < len; i += 65536) {
+                    crypto.getRandomValues(memory().subarray(ptr + i, ptr + Math.min(len, i + 65536)));
+                }
+            },
+            result: (ptr, len) => { posted = new TextDecoder().decode(memory().slice(ptr, ptr + len)); },
+        },
+    });
+    exports = instance.exports;
+    return {
+        run(text) {
+            const input = new TextEncoder().encode(text);
+            const ptr = exports.pvss_sim_alloc(input.length);
+            memory().set(input, ptr);
+            const status = exports.pvss_sim_run(ptr, input.length);
+            exports.pvss_sim_free(ptr, input.length);
+            const result = JSON.parse(posted);
+            if (status !== 0) {
+                throw new Error(result.error);
+            }
+            return result;
+        },
+    };
+}